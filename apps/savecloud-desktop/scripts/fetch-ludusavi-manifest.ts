@@ -0,0 +1,28 @@
+/**
+ * Descarga la copia offline del manifiesto de Ludusavi que se empaqueta con la app.
+ * Ejecutar antes de `tauri build`.
+ *
+ * Uso:
+ * bun run scripts/fetch-ludusavi-manifest.ts
+ */
+
+import { mkdirSync } from "fs";
+import { resolve, join } from "path";
+
+const MANIFEST_URL =
+  "https://raw.githubusercontent.com/mtkennerly/ludusavi-manifest/master/data/manifest.yaml";
+
+const baseDir = resolve(import.meta.dir, "..");
+const outDir = join(baseDir, "src-tauri/resources/ludusavi");
+const outFile = join(outDir, "manifest.yaml");
+
+const res = await fetch(MANIFEST_URL);
+if (!res.ok) {
+  console.error(`No se pudo descargar el manifiesto: HTTP ${res.status}`);
+  process.exit(1);
+}
+
+mkdirSync(outDir, { recursive: true });
+const bytes = await Bun.write(outFile, await res.arrayBuffer());
+
+console.log(`✓ Manifiesto guardado en ${outFile} (${(bytes / 1024 / 1024).toFixed(1)} MB)`);
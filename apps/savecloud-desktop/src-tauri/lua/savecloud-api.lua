@@ -11,8 +11,59 @@
 ---@class SaveCloudUI
 ---@field emit fun(evento: string, payload: string) Envia un evento al frontend de la app.
 
+---@class SaveCloudKV
+---@field get fun(clave: string): any Valor guardado, o nil si no existe.
+---@field set fun(clave: string, valor: any) Guarda nil, booleanos, numeros, strings o tablas de ellos. Guardar nil borra la clave.
+---@field delete fun(clave: string): boolean Borra la clave; true si existia.
+---@field list fun(prefijo?: string): string[] Claves del plugin (que empiezan por el prefijo), en orden alfabetico.
+
+---@class SaveCloudSQL
+---@field prefix string Prefijo obligatorio de las tablas e indices del plugin.
+---@field query fun(sql: string, params?: any[]): table<string, any>[] Filas como tablas columna = valor. Los parametros se enlazan como ?1, ?2...
+---@field execute fun(sql: string, params?: any[]): integer Ejecuta una sentencia y devuelve las filas cambiadas.
+
+---@class SaveCloudDBGame
+---@field id string
+---@field paths string[]
+---@field steamAppId string|nil
+---@field installDir string|nil
+
+---@class SaveCloudOperation
+---@field timestamp string
+---@field kind string Tipo de operacion (subida, descarga...).
+---@field gameId string
+---@field fileCount integer
+---@field errCount integer
+---@field deviceName string|nil
+
+---@class SaveCloudPlaytimeBucket
+---@field period string "2026-10-19", "2026-W42" o "2026-10".
+---@field seconds integer
+---@field idleSeconds integer
+---@field sessions integer
+
+---@class SaveCloudPlaySession
+---@field id integer
+---@field gameId string
+---@field deviceId string
+---@field startedAt integer Segundos Unix.
+---@field endedAt integer Segundos Unix.
+---@field durationSeconds integer
+---@field idleSeconds integer
+---@field launchedVia "detected"|"app"|"managed"
+
+---@class SaveCloudSyncStatus
+---@field jobs table[] Trabajos de la cola de sincronizacion (pendientes, en curso o fallidos).
+---@field transfers table[] Transferencias en cola o en curso.
+
 ---@class SaveCloudDB
----@field log_operation fun(plugin: string, accion: string, detalles: string) Guarda un registro de operacion.
+---@field kv SaveCloudKV Clave-valor propio del plugin.
+---@field sql SaveCloudSQL Tablas propias del plugin.
+---@field games fun(): SaveCloudDBGame[] Juegos configurados.
+---@field history fun(limite?: integer): SaveCloudOperation[] Ultimas operaciones de sincronizacion (20 por defecto).
+---@field playtime fun(periodo: "day"|"week"|"month", juego?: string): SaveCloudPlaytimeBucket[] Tiempo jugado por periodo.
+---@field sessions fun(juego?: string, limite?: integer): SaveCloudPlaySession[] Partidas mas recientes (20 por defecto).
+---@field sync_status fun(): SaveCloudSyncStatus Estado de la sincronizacion.
 
 ---@class SaveCloudHttpResponse
 ---@field ok boolean True si el status HTTP esta entre 200 y 299.
@@ -20,19 +71,106 @@
 ---@field body string Cuerpo de la respuesta como string.
 ---@field error string|nil Mensaje de error de red. Solo presente si ok es false y status es 0.
 
+--- Las peticiones pausan el plugin mientras esperan la respuesta, sin bloquear la app.
+--- No las llames desde corrutinas propias (coroutine.create/wrap).
 ---@class SaveCloudHttp
 ---@field get fun(url: string, headers?: table<string, string>): SaveCloudHttpResponse Realiza una peticion GET.
 ---@field post fun(url: string, body: string, headers?: table<string, string>): SaveCloudHttpResponse Realiza una peticion POST.
 ---@field put fun(url: string, body: string, headers?: table<string, string>): SaveCloudHttpResponse Realiza una peticion PUT.
 ---@field delete fun(url: string, headers?: table<string, string>): SaveCloudHttpResponse Realiza una peticion DELETE.
 
+--- Las funciones de timer corren en segundo plano y se cancelan al descargar el plugin.
+---@class SaveCloudTimer
+---@field after fun(ms: integer, fn: fun()): integer Llama a fn una vez dentro de ms milisegundos. Devuelve el id del timer.
+---@field every fun(ms: integer, fn: fun()): integer Llama a fn cada ms milisegundos (minimo 1000). Se detiene si fn lanza un error.
+---@field cancel fun(id: integer): boolean Cancela un timer; false si ya termino o no existe.
+---@field sleep fun(ms: integer) Pausa la llamada actual sin bloquear la app.
+
+---@class SaveCloudFs
+---@field read fun(ruta: string): string|nil, string|nil Lee un archivo. Devuelve nil y el error si falla.
+---@field write fun(ruta: string, contenido: string): boolean|nil, string|nil Escribe (o reemplaza) un archivo.
+---@field list fun(ruta: string): string[]|nil, string|nil Nombres de las entradas de una carpeta.
+---@field exists fun(ruta: string): boolean|nil, string|nil Indica si la ruta existe.
+
+--- Solo 'log' y 'timer' existen siempre. 'ui', 'db', 'http' y 'fs' aparecen si plugin.toml
+--- los pide en [capabilities] y el usuario los aprobo; si no, son nil.
 ---@class SaveCloudCore
 ---@field log SaveCloudLog
----@field ui SaveCloudUI
----@field db SaveCloudDB
----@field http SaveCloudHttp
+---@field timer SaveCloudTimer
+---@field ui SaveCloudUI|nil Requiere capabilities.ui_events.
+---@field db SaveCloudDB|nil Requiere capabilities.db = true.
+---@field http SaveCloudHttp|nil Requiere capabilities.http (solo esos hosts).
+---@field fs SaveCloudFs|nil Requiere capabilities.fs (rutas absolutas dentro de esas carpetas).
 
 --- Objeto global inyectado por el core de Rust en tiempo de ejecucion.
 --- No modifiques este valor — es solo una declaracion para el autocompletado.
 ---@diagnostic disable-next-line: missing-fields
-savecloud = {} ---@type SaveCloudCore
\ No newline at end of file
+savecloud = {} ---@type SaveCloudCore
+
+-- Hooks de ciclo de vida. Define en tu 'init.lua' las funciones globales que necesites;
+-- las que no existan simplemente no se llaman.
+-- 'on_before_upload' y 'on_before_download' se esperan (10 s como mucho); el resto de
+-- hooks corren en segundo plano y la operacion no los espera.
+
+--- Al cargar el plugin: al arrancar, al activarlo o tras una recarga.
+---@type fun()
+on_init = nil
+
+--- Antes de descargar el plugin: al desactivarlo, recargarlo, borrar su carpeta o cerrar la app.
+---@type fun()
+on_unload = nil
+
+---@class SaveCloudGame
+---@field id string Identificador del juego.
+---@field paths string[] Carpetas de guardado configuradas.
+
+---@class SaveCloudFile
+---@field path string Ruta relativa a la carpeta del juego.
+---@field size integer|nil Tamano en bytes.
+---@field last_modified string|nil Fecha de la copia en la nube (solo en descargas).
+
+---@class SaveCloudSyncResult
+---@field ok_count integer Archivos transferidos.
+---@field err_count integer Archivos con error.
+---@field errors string[] Mensajes de error.
+
+---@class SaveCloudConflict
+---@field path string Ruta relativa a la carpeta del juego.
+---@field local_modified string Fecha de la copia local.
+---@field cloud_modified string Fecha de la copia en la nube.
+
+---@class SaveCloudBackup
+---@field kind "local"|"cloud" Copia previa a una descarga o backup empaquetado subido.
+---@field location string Ruta en disco o nombre del backup en la nube.
+
+---@class SaveCloudHookReply
+---@field cancel boolean|nil True para cancelar la operacion.
+---@field reason string|nil Motivo de la cancelacion, visible para el usuario.
+---@field skip string[]|nil Rutas relativas que se dejan fuera.
+
+--- Antes de subir. Devuelve false o { cancel = true } para cancelar, o { skip = {...} } para omitir archivos.
+---@type fun(game: SaveCloudGame, files: SaveCloudFile[]): boolean|SaveCloudHookReply|nil
+on_before_upload = nil
+
+---@type fun(game: SaveCloudGame, result: SaveCloudSyncResult)
+on_after_upload = nil
+
+--- Antes de descargar. Acepta las mismas respuestas que on_before_upload.
+---@type fun(game: SaveCloudGame, files: SaveCloudFile[]): boolean|SaveCloudHookReply|nil
+on_before_download = nil
+
+---@type fun(game: SaveCloudGame, result: SaveCloudSyncResult)
+on_after_download = nil
+
+---@type fun(game: SaveCloudGame, info: { launched: boolean })
+on_game_started = nil
+
+---@type fun(game: SaveCloudGame, info: { played_seconds: integer })
+on_game_exited = nil
+
+--- Archivos locales mas recientes que los de la nube.
+---@type fun(game: SaveCloudGame, conflicts: SaveCloudConflict[])
+on_conflict = nil
+
+---@type fun(game: SaveCloudGame, backup: SaveCloudBackup)
+on_backup_created = nil
@@ -0,0 +1,6 @@
+//! `savecloud-launch`: envoltorio para la opción de lanzamiento de Steam
+//! (`savecloud-launch %command%`). Equivale a `savecloud launch <argumentos>`.
+
+fn main() {
+    std::process::exit(savecloud_desktop_lib::cli::run_launch())
+}
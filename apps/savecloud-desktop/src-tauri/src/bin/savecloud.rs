@@ -0,0 +1,5 @@
+//! `savecloud`: CLI sin interfaz gráfica. Ver `savecloud --help`.
+
+fn main() {
+    std::process::exit(savecloud_desktop_lib::cli::run())
+}
@@ -0,0 +1,656 @@
+//! CLI sin interfaz (`savecloud`) que reutiliza el mismo motor de sincronización que la app.
+//!
+//! Pensada para servidores o el modo juego de Steam Deck: cada subcomando escribe su
+//! resultado como JSON en stdout, el progreso va a stderr y el código de salida
+//! distingue éxito, error, uso incorrecto, fallos parciales y cancelación (Ctrl+C).
+
+mod progress;
+
+use crate::commands::sync::cancellation::CancellationToken;
+use crate::commands::sync::models::{DownloadConflictDto, GameSyncResultDto, SyncResultDto};
+use crate::commands::sync::observer::SyncObserver;
+use crate::commands::sync::{api, download, full_backup, upload};
+use crate::config::ConfiguredGame;
+use crate::launch::{self, OnConflict, PullOutcome, PullReport};
+use crate::storage::{Storage, StorageBackend};
+use crate::transfers::{self, TransferKind, TransferPriority};
+use clap::{Args, Parser, Subcommand};
+use serde::Serialize;
+use std::ffi::OsString;
+use std::io::IsTerminal;
+
+/// Todo terminó bien.
+const EXIT_OK: i32 = 0;
+/// La operación no pudo ejecutarse (configuración incompleta, red, juego inexistente...).
+const EXIT_ERROR: i32 = 1;
+/// La operación terminó, pero algún juego o archivo falló (ver `errors` en el JSON).
+const EXIT_PARTIAL: i32 = 3;
+/// El usuario canceló con Ctrl+C (128 + SIGINT, como las shells).
+const EXIT_CANCELLED: i32 = 130;
+
+#[derive(Parser)]
+#[command(
+    name = "savecloud",
+    version,
+    about = "Sincroniza los guardados de tus juegos desde la terminal"
+)]
+struct Cli {
+    /// No escribir el progreso en stderr.
+    #[arg(short, long, global = true)]
+    quiet: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Lista los juegos configurados (o los guardados en la nube con `--remote`).
+    List {
+        #[arg(long)]
+        remote: bool,
+    },
+    /// Backend activo y estado de cada juego (en ejecución, cambios pendientes de subir).
+    Status,
+    /// Sube los guardados de los juegos indicados.
+    Upload(GameSelection),
+    /// Descarga los guardados de los juegos indicados (con copia local previa).
+    Download(GameSelection),
+    /// Sincroniza en los dos sentidos: descarga lo que la nube tenga más reciente y
+    /// después sube los juegos con cambios locales.
+    Sync(SyncArgs),
+    /// Backups empaquetados (.tar) en la nube.
+    #[command(subcommand)]
+    Backup(BackupCommand),
+    /// Busca carpetas de guardado candidatas en este equipo.
+    Scan,
+    /// Importa o exporta la configuración.
+    #[command(subcommand)]
+    Config(ConfigCommand),
+    /// Dispositivos que suben guardados a este perfil.
+    #[command(subcommand)]
+    Devices(DevicesCommand),
+    /// Lanza un juego sincronizando: descarga antes de jugar y sube al salir.
+    ///
+    /// Como opción de lanzamiento de Steam: `savecloud-launch %command%`.
+    Launch(LaunchArgs),
+}
+
+#[derive(Args)]
+struct GameSelection {
+    /// Identificadores de los juegos.
+    #[arg(required_unless_present = "all", conflicts_with = "all")]
+    games: Vec<String>,
+
+    /// Todos los juegos configurados.
+    #[arg(long)]
+    all: bool,
+}
+
+#[derive(Args)]
+struct SyncArgs {
+    /// Limitar a estos juegos (por defecto, todos).
+    games: Vec<String>,
+
+    /// Si la descarga sobrescribiría cambios locales: `ask` pregunta en la terminal
+    /// (sin terminal conserva lo local), `cloud` descarga y `local` sube lo local.
+    #[arg(long, default_value = "ask", value_parser = ["ask", "cloud", "local"])]
+    on_conflict: String,
+}
+
+#[derive(Args)]
+struct LaunchArgs {
+    /// Juego configurado; por defecto se deduce del `SteamAppId` o del ejecutable.
+    #[arg(long)]
+    game: Option<String>,
+
+    /// Si la descarga sobrescribiría cambios locales: `ask` pregunta en la terminal
+    /// (sin terminal conserva lo local), `cloud` descarga y `local` no descarga.
+    #[arg(long, default_value = "ask", value_parser = ["ask", "cloud", "local"])]
+    on_conflict: String,
+
+    /// Línea de comandos del juego (el `%command%` de Steam); por defecto, el ejecutable configurado.
+    #[arg(
+        trailing_var_arg = true,
+        allow_hyphen_values = true,
+        required_unless_present = "game"
+    )]
+    command: Vec<String>,
+}
+
+#[derive(Subcommand)]
+enum BackupCommand {
+    /// Empaqueta la carpeta del juego y la sube a la nube.
+    Create { game: String },
+    /// Lista los backups empaquetados de un juego.
+    List { game: String },
+    /// Descarga y extrae un backup (por defecto, el más reciente).
+    Restore {
+        game: String,
+        /// Clave del backup, tal como aparece en `backup list`.
+        key: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Exporta la configuración completa a un archivo JSON.
+    Export { path: String },
+    /// Importa la configuración desde un archivo JSON.
+    Import {
+        path: String,
+        /// `merge` añade juegos y rutas; `replace` sustituye la configuración.
+        #[arg(long, default_value = "merge", value_parser = ["merge", "replace"])]
+        mode: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum DevicesCommand {
+    /// Lista los dispositivos del perfil (marca el actual).
+    List,
+    /// Revoca un dispositivo: sus subidas se rechazarán.
+    Revoke {
+        /// Id del dispositivo, tal como aparece en `devices list`.
+        id: String,
+    },
+}
+
+/// Resultado de `sync` para un juego: lo descargado, los conflictos y lo subido.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncGameReport {
+    game_id: String,
+    #[serde(flatten)]
+    pull: PullReport,
+    pushed: Option<SyncResultDto>,
+    errors: Vec<String>,
+}
+
+impl SyncGameReport {
+    fn has_errors(&self) -> bool {
+        !self.errors.is_empty() || self.pushed.as_ref().is_some_and(|p| p.err_count > 0)
+    }
+}
+
+/// Resultado de un subcomando: el JSON para stdout y si algo falló parcialmente.
+struct Output {
+    json: serde_json::Value,
+    partial: bool,
+}
+
+impl Output {
+    fn new(value: impl Serialize) -> Result<Self, String> {
+        Ok(Self {
+            json: serde_json::to_value(value).map_err(|e| e.to_string())?,
+            partial: false,
+        })
+    }
+
+    fn game_results(results: Vec<GameSyncResultDto>) -> Result<Self, String> {
+        let partial = results.iter().any(|r| r.result.err_count > 0);
+        Ok(Self {
+            partial,
+            ..Self::new(results)?
+        })
+    }
+}
+
+/// Punto de entrada del binario `savecloud`; devuelve el código de salida del proceso.
+pub fn run() -> i32 {
+    run_with(std::env::args_os())
+}
+
+/// Punto de entrada de `savecloud-launch`: equivale a `savecloud launch <argumentos>`.
+pub fn run_launch() -> i32 {
+    let args = std::env::args_os().skip(1);
+    run_with(
+        [OsString::from("savecloud"), OsString::from("launch")]
+            .into_iter()
+            .chain(args),
+    )
+}
+
+fn run_with(args: impl IntoIterator<Item = OsString>) -> i32 {
+    crate::load_dotenv();
+
+    let cli = match Cli::try_parse_from(args) {
+        Ok(cli) => cli,
+        Err(e) => {
+            let _ = e.print();
+            return e.exit_code();
+        }
+    };
+
+    let runtime = match tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(rt) => rt,
+        Err(e) => {
+            eprintln!("No se pudo iniciar el runtime: {}", e);
+            return EXIT_ERROR;
+        }
+    };
+
+    let observer: Box<dyn SyncObserver> = if cli.quiet {
+        Box::new(progress::Quiet)
+    } else {
+        Box::<progress::StderrProgress>::default()
+    };
+
+    let cancel = CancellationToken::new();
+    runtime.spawn(cancel_on_ctrl_c(cancel.clone()));
+
+    let result = runtime.block_on(execute(cli.command, observer.as_ref(), &cancel));
+    let (json, code) = match result {
+        Ok(out) if cancel.is_cancelled() => (out.json, EXIT_CANCELLED),
+        Err(e) if cancel.is_cancelled() => (serde_json::json!({ "error": e }), EXIT_CANCELLED),
+        Ok(out) if out.partial => (out.json, EXIT_PARTIAL),
+        Ok(out) => (out.json, EXIT_OK),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            (serde_json::json!({ "error": e }), EXIT_ERROR)
+        }
+    };
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&json).unwrap_or_default()
+    );
+    code
+}
+
+/// El primer Ctrl+C cancela las transferencias en curso (las subidas multipart se abortan en el backend);
+/// el segundo sale sin esperar.
+async fn cancel_on_ctrl_c(cancel: CancellationToken) {
+    if tokio::signal::ctrl_c().await.is_err() {
+        return;
+    }
+    eprintln!("Cancelando… (Ctrl+C otra vez para salir ya)");
+    cancel.cancel();
+    transfers::global().cancel_all();
+    if tokio::signal::ctrl_c().await.is_ok() {
+        std::process::exit(EXIT_CANCELLED);
+    }
+}
+
+async fn execute(
+    command: Command,
+    observer: &dyn SyncObserver,
+    cancel: &CancellationToken,
+) -> Result<Output, String> {
+    match command {
+        Command::List { remote: true } => Output::new(api::sync_list_remote_saves().await?),
+        Command::List { remote: false } => Output::new(crate::config::load_config().games),
+        Command::Status => status().await,
+        Command::Upload(selection) => {
+            Storage::active()?;
+            let mut results = Vec::new();
+            for game_id in selected_games(selection) {
+                if cancel.should_stop() {
+                    break;
+                }
+                let transfer =
+                    transfers::begin(TransferKind::Upload, &game_id, TransferPriority::Normal)
+                        .await;
+                let r = upload::sync_upload_game_impl(
+                    game_id.clone(),
+                    &transfer.observer(observer),
+                    transfer.token(),
+                )
+                .await;
+                results.push(game_result(game_id, r));
+            }
+            Output::game_results(results)
+        }
+        Command::Download(selection) => {
+            Storage::active()?;
+            let mut results = Vec::new();
+            for game_id in selected_games(selection) {
+                if cancel.should_stop() {
+                    break;
+                }
+                let transfer =
+                    transfers::begin(TransferKind::Download, &game_id, TransferPriority::Normal)
+                        .await;
+                let r = download::sync_download_game_impl(
+                    game_id.clone(),
+                    &transfer.observer(observer),
+                    transfer.token(),
+                    None,
+                )
+                .await;
+                results.push(game_result(game_id, r));
+            }
+            Output::game_results(results)
+        }
+        Command::Sync(args) => sync_games(args, observer, cancel).await,
+        Command::Backup(BackupCommand::Create { game }) => {
+            let transfer =
+                transfers::begin(TransferKind::FullBackup, &game, TransferPriority::Normal).await;
+            let filename = full_backup::create_and_upload_full_backup_impl(
+                game.clone(),
+                &transfer.observer(observer),
+                transfer.token(),
+            )
+            .await?;
+            Output::new(serde_json::json!({ "gameId": game, "filename": filename }))
+        }
+        Command::Backup(BackupCommand::List { game }) => {
+            Output::new(full_backup::list_full_backups(game).await?)
+        }
+        Command::Backup(BackupCommand::Restore { game, key }) => {
+            let key = match key {
+                Some(k) => k,
+                None => {
+                    let mut backups = full_backup::list_full_backups(game.clone()).await?;
+                    backups.sort_by(|a, b| b.last_modified.cmp(&a.last_modified));
+                    backups
+                        .into_iter()
+                        .next()
+                        .map(|b| b.key)
+                        .ok_or_else(|| format!("No hay backups de {} en la nube", game))?
+                }
+            };
+            let transfer =
+                transfers::begin(TransferKind::Download, &game, TransferPriority::Normal).await;
+            full_backup::download_and_restore_full_backup_impl(
+                game.clone(),
+                key.clone(),
+                &transfer.observer(observer),
+                transfer.token(),
+            )
+            .await?;
+            Output::new(serde_json::json!({ "gameId": game, "key": key }))
+        }
+        Command::Scan => {
+            let manifest_index = crate::manifest::load_manifest_index_async().await.ok();
+            let candidates = tokio::task::spawn_blocking(move || {
+                crate::commands::scan::scan_path_candidates_sync(manifest_index)
+            })
+            .await
+            .map_err(|e| format!("Error en el hilo de escaneo: {}", e))?;
+            Output::new(candidates)
+        }
+        Command::Config(ConfigCommand::Export { path }) => Output::new(serde_json::json!({
+            "path": crate::config::config_cmds::export_config_to_file(path)?
+        })),
+        Command::Config(ConfigCommand::Import { path, mode }) => {
+            crate::config::config_cmds::import_config_from_file(path.clone(), mode.clone())?;
+            Output::new(serde_json::json!({ "path": path, "mode": mode }))
+        }
+        Command::Devices(DevicesCommand::List) => {
+            Output::new(crate::devices::list_devices().await?)
+        }
+        Command::Devices(DevicesCommand::Revoke { id }) => {
+            crate::devices::revoke_device(id.clone()).await?;
+            Output::new(serde_json::json!({ "revoked": id }))
+        }
+        Command::Launch(args) => launch_with_sync(args, observer).await,
+    }
+}
+
+/// Descarga primero lo que la nube tenga más reciente en cada juego y después sube los
+/// juegos con cambios locales. Un juego cuya descarga falla no se sube, para no pisar
+/// la nube con un guardado a medias.
+async fn sync_games(
+    args: SyncArgs,
+    observer: &dyn SyncObserver,
+    cancel: &CancellationToken,
+) -> Result<Output, String> {
+    Storage::active()?;
+    let on_conflict = parse_on_conflict(&args.on_conflict);
+    let games: Vec<ConfiguredGame> = if args.games.is_empty() {
+        crate::config::load_config().games
+    } else {
+        args.games
+            .iter()
+            .map(|id| launch::find_game(id))
+            .collect::<Result<_, _>>()?
+    };
+
+    let mut reports = Vec::new();
+    for game in &games {
+        if cancel.should_stop() {
+            break;
+        }
+        let mut report = SyncGameReport {
+            game_id: game.id.clone(),
+            pull: PullReport::default(),
+            pushed: None,
+            errors: Vec::new(),
+        };
+        match pull_resolving_conflicts(game, on_conflict, observer).await {
+            Ok(pull) => report.pull = pull,
+            Err(e) => report.errors.push(e),
+        }
+        reports.push(report);
+    }
+
+    // Tras descargar, solo quedan pendientes los juegos con cambios locales de verdad.
+    let pending = download::sync_check_unsynced_games().await?;
+    for report in &mut reports {
+        if cancel.should_stop() {
+            break;
+        }
+        let has_changes = pending
+            .iter()
+            .any(|p| p.game_id.eq_ignore_ascii_case(&report.game_id));
+        if !report.errors.is_empty() || !has_changes {
+            continue;
+        }
+        let transfer = transfers::begin(
+            TransferKind::Upload,
+            &report.game_id,
+            TransferPriority::Normal,
+        )
+        .await;
+        match upload::sync_upload_game_impl(
+            report.game_id.clone(),
+            &transfer.observer(observer),
+            transfer.token(),
+        )
+        .await
+        {
+            Ok(pushed) => report.pushed = Some(pushed),
+            Err(e) => report.errors.push(e),
+        }
+    }
+
+    Ok(Output {
+        partial: reports.iter().any(SyncGameReport::has_errors),
+        ..Output::new(reports)?
+    })
+}
+
+/// Descarga si hace falta, lanza el juego, espera a que se cierre y sube.
+async fn launch_with_sync(args: LaunchArgs, observer: &dyn SyncObserver) -> Result<Output, String> {
+    let game = launch::resolve_game(args.game.as_deref(), &args.command)?;
+    let on_conflict = parse_on_conflict(&args.on_conflict);
+    let report = pull_resolving_conflicts(&game, on_conflict, observer).await?;
+
+    let running = launch::launch(&game, &args.command)?;
+    let result = launch::finish(running, report, observer).await;
+    Ok(Output {
+        partial: result.has_errors(),
+        ..Output::new(result)?
+    })
+}
+
+/// Valor de `--on-conflict`; clap ya limita los valores posibles.
+fn parse_on_conflict(value: &str) -> OnConflict {
+    match value {
+        "cloud" => OnConflict::UseCloud,
+        "local" => OnConflict::KeepLocal,
+        _ => OnConflict::Ask,
+    }
+}
+
+/// Descarga lo que la nube tenga más reciente; si hay conflictos y `on_conflict` es
+/// `Ask`, pregunta en la terminal. Los avisos se escriben en stderr.
+async fn pull_resolving_conflicts(
+    game: &ConfiguredGame,
+    on_conflict: OnConflict,
+    observer: &dyn SyncObserver,
+) -> Result<PullReport, String> {
+    let report = match launch::pull_before_play(game, on_conflict, observer).await? {
+        PullOutcome::Ready(report) => report,
+        PullOutcome::Conflicts(conflicts) => {
+            let choice = ask_conflict(&game.id, &conflicts);
+            match launch::pull_before_play(game, choice, observer).await? {
+                PullOutcome::Ready(report) => report,
+                PullOutcome::Conflicts(_) => return Err("Conflictos sin resolver".to_string()),
+            }
+        }
+    };
+    for warning in &report.warnings {
+        eprintln!("[{}] {}", game.id, warning);
+    }
+    if let Some(lock) = &report.session_lock {
+        eprintln!("[{}] aviso: {}", game.id, lock.warning());
+    }
+    Ok(report)
+}
+
+/// Pregunta en la terminal qué hacer con los conflictos; sin terminal (p. ej. lanzado
+/// desde Steam) conserva lo local, que se sube después.
+fn ask_conflict(game_id: &str, conflicts: &[DownloadConflictDto]) -> OnConflict {
+    eprintln!(
+        "[{}] {} archivo(s) locales son más recientes que la nube:",
+        game_id,
+        conflicts.len()
+    );
+    for conflict in conflicts {
+        eprintln!("  {}", conflict.filename);
+    }
+    if !std::io::stdin().is_terminal() {
+        eprintln!(
+            "Sin terminal: se conservan los locales (usa --on-conflict cloud para descargar)."
+        );
+        return OnConflict::KeepLocal;
+    }
+    eprint!("¿Descargar la nube y sobrescribirlos? Queda copia local previa [s/N] ");
+    let mut answer = String::new();
+    let _ = std::io::stdin().read_line(&mut answer);
+    match answer.trim().to_lowercase().as_str() {
+        "s" | "si" | "sí" | "y" | "yes" => OnConflict::UseCloud,
+        _ => OnConflict::KeepLocal,
+    }
+}
+
+/// Estado de cada juego configurado: si está en ejecución y si tiene cambios sin subir.
+async fn status() -> Result<Output, String> {
+    let storage = Storage::active()?;
+    let cfg = crate::config::load_config();
+    let game_ids: Vec<String> = cfg.games.iter().map(|g| g.id.clone()).collect();
+    let running = crate::system::process_check::are_games_running(&game_ids);
+    let pending = download::sync_check_unsynced_games().await?;
+
+    let games: Vec<serde_json::Value> = game_ids
+        .iter()
+        .map(|id| {
+            serde_json::json!({
+                "gameId": id,
+                "running": running.get(id).copied().unwrap_or(false),
+                "pendingUpload": pending.iter().any(|p| p.game_id.eq_ignore_ascii_case(id)),
+            })
+        })
+        .collect();
+
+    Output::new(serde_json::json!({
+        "storage": storage.name(),
+        "userId": storage.user_id(),
+        "games": games,
+    }))
+}
+
+fn selected_games(selection: GameSelection) -> Vec<String> {
+    if selection.all {
+        crate::config::load_config()
+            .games
+            .into_iter()
+            .map(|g| g.id)
+            .collect()
+    } else {
+        selection.games
+    }
+}
+
+fn game_result(game_id: String, r: Result<SyncResultDto, String>) -> GameSyncResultDto {
+    let result = r.unwrap_or_else(|e| SyncResultDto {
+        ok_count: 0,
+        err_count: 1,
+        errors: vec![e],
+    });
+    GameSyncResultDto { game_id, result }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn parses_subcommands() {
+        Cli::command().debug_assert();
+
+        let cli = Cli::try_parse_from(["savecloud", "upload", "--all", "-q"]).unwrap();
+        assert!(cli.quiet);
+        assert!(matches!(
+            cli.command,
+            Command::Upload(GameSelection { all: true, .. })
+        ));
+
+        // Hay que indicar juegos o `--all`, pero no ambos.
+        assert!(Cli::try_parse_from(["savecloud", "upload"]).is_err());
+        assert!(Cli::try_parse_from(["savecloud", "download", "hades", "--all"]).is_err());
+        assert!(
+            Cli::try_parse_from(["savecloud", "config", "import", "x.json", "--mode", "x"])
+                .is_err()
+        );
+
+        let cli = Cli::try_parse_from(["savecloud", "backup", "restore", "hades"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Backup(BackupCommand::Restore { key: None, .. })
+        ));
+
+        let cli = Cli::try_parse_from(["savecloud", "sync"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Sync(SyncArgs { ref games, ref on_conflict })
+                if games.is_empty() && on_conflict == "ask"
+        ));
+        let cli =
+            Cli::try_parse_from(["savecloud", "sync", "hades", "--on-conflict", "local"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Sync(SyncArgs { ref games, ref on_conflict })
+                if games.len() == 1 && on_conflict == "local"
+        ));
+        assert!(Cli::try_parse_from(["savecloud", "sync", "--on-conflict", "x"]).is_err());
+
+        assert!(Cli::try_parse_from(["savecloud", "devices", "revoke"]).is_err());
+        let cli = Cli::try_parse_from(["savecloud", "devices", "revoke", "abc"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Devices(DevicesCommand::Revoke { id }) if id == "abc"
+        ));
+
+        // Lo que sigue al primer argumento del juego se pasa tal cual, guiones incluidos.
+        assert!(Cli::try_parse_from(["savecloud", "launch"]).is_err());
+        let cli = Cli::try_parse_from([
+            "savecloud",
+            "launch",
+            "/games/x.sh",
+            "-windowed",
+            "--game",
+            "y",
+        ])
+        .unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Launch(LaunchArgs { game: None, ref command, .. }) if command.len() == 4
+        ));
+    }
+}
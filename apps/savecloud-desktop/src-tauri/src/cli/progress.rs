@@ -0,0 +1,71 @@
+//! Progreso de la CLI: el motor de sincronización avisa a un [`SyncObserver`] y aquí
+//! se traduce a líneas legibles en stderr, dejando stdout solo para el JSON.
+
+use crate::commands::sync::models::SyncProgressPayload;
+use crate::commands::sync::observer::SyncObserver;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Cada cuántos puntos porcentuales se escribe una línea por archivo.
+const PROGRESS_STEP_PCT: u8 = 5;
+
+/// Escribe el progreso en stderr, como mucho una línea cada [`PROGRESS_STEP_PCT`] % por archivo.
+#[derive(Default)]
+pub(super) struct StderrProgress {
+    last_pct: Mutex<HashMap<(String, String), u8>>,
+}
+
+impl StderrProgress {
+    fn report(&self, verb: &str, progress: SyncProgressPayload) {
+        let pct = progress
+            .loaded
+            .checked_mul(100)
+            .and_then(|scaled| scaled.checked_div(progress.total))
+            .map_or(100, |pct| pct.min(100) as u8);
+        let key = (progress.game_id, progress.filename);
+        let Ok(mut last) = self.last_pct.lock() else {
+            return;
+        };
+        let should_print = match last.get(&key) {
+            Some(&prev) => {
+                pct >= prev.saturating_add(PROGRESS_STEP_PCT) || (pct == 100 && prev < 100)
+            }
+            None => true,
+        };
+        if should_print {
+            eprintln!("[{}] {} {}: {}%", key.0, verb, key.1, pct);
+            last.insert(key, pct);
+        }
+    }
+}
+
+impl SyncObserver for StderrProgress {
+    fn upload_progress(&self, progress: SyncProgressPayload) {
+        self.report("subiendo", progress);
+    }
+
+    fn download_progress(&self, progress: SyncProgressPayload) {
+        self.report("descargando", progress);
+    }
+
+    fn upload_paused(&self, game_id: &str, filename: &str) {
+        eprintln!("[{}] subida de {} pausada", game_id, filename);
+    }
+
+    fn download_paused(&self, game_id: &str, filename: &str) {
+        eprintln!("[{}] descarga de {} pausada", game_id, filename);
+    }
+}
+
+/// Observador para `--quiet`: descarta el progreso.
+pub(super) struct Quiet;
+
+impl SyncObserver for Quiet {
+    fn upload_progress(&self, _progress: SyncProgressPayload) {}
+
+    fn download_progress(&self, _progress: SyncProgressPayload) {}
+
+    fn upload_paused(&self, _game_id: &str, _filename: &str) {}
+
+    fn download_paused(&self, _game_id: &str, _filename: &str) {}
+}
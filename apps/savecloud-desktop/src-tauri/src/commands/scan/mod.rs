@@ -7,6 +7,8 @@
 //! - Escaneo de rutas candidatas para Unix.
 //! - Escaneo de rutas candidatas para Steam.
 //! - Escaneo de rutas candidatas para Ludusavi.
+//! - Escaneo de juegos de otros lanzadores (Heroic, Lutris, Epic, GOG, itch).
+//! - Escaneo de guardados de emuladores, divididos por juego.
 //! - Escaneo de rutas candidatas para otros juegos.
 
 mod extensions;
@@ -14,6 +16,9 @@ mod filters;
 mod paths;
 
 use crate::config;
+use crate::emulators;
+use crate::launchers::{InstalledGame, InstalledGamesIndex};
+use crate::utils::path_translation::HostContext;
 #[cfg(target_os = "windows")]
 use crate::{manifest, steam};
 use filters::{
@@ -263,13 +268,51 @@ fn scan_base_paths_into_vec(base_path: &str, base_label: &str) -> Vec<PathCandid
     candidates
 }
 
+/// Carpetas de instalación de juegos de otros lanzadores (Heroic, Lutris, Epic, GOG, itch)
+/// que contienen archivos con pinta de guardado.
+fn find_launcher_candidates(games: &[InstalledGame]) -> Vec<PathCandidateDto> {
+    games
+        .par_iter()
+        .filter_map(|game| {
+            let folder = game.install_dir.file_name()?.to_string_lossy().to_string();
+            if is_excluded_folder(&folder) || !folder_contains_save_like_files(&game.install_dir) {
+                return None;
+            }
+            Some(PathCandidateDto {
+                path: game.install_dir.to_string_lossy().to_string(),
+                folder_name: game.title.clone(),
+                base_path: game.launcher.label().to_string(),
+                steam_app_id: game.steam_app_id.clone(),
+                paths: None,
+            })
+        })
+        .collect()
+}
+
+/// Guardados de emuladores (RetroArch, Dolphin, PCSX2, Yuzu, DuckStation), uno por juego.
+///
+/// La primera ruta es la principal; el resto (carpetas de estados, otras tarjetas) van
+/// en `paths` para que el juego se configure con todas.
+fn find_emulator_candidates() -> Vec<PathCandidateDto> {
+    emulators::list_emulator_saves()
+        .into_iter()
+        .filter_map(|entry| {
+            let path = entry.paths.first()?.clone();
+            Some(PathCandidateDto {
+                path,
+                folder_name: entry.title,
+                base_path: format!("Emulador {}", entry.emulator.label()),
+                steam_app_id: None,
+                paths: (entry.paths.len() > 1).then_some(entry.paths),
+            })
+        })
+        .collect()
+}
+
 #[cfg(target_os = "windows")]
 mod windows_scanners {
     use super::*;
 
-    static VDF_PATH_REGEX: LazyLock<Regex> =
-        LazyLock::new(|| Regex::new(r#""path"\s+"([^"]+)""#).unwrap());
-
     const MAX_SCAN_DEPTH: usize = 5;
 
     /// Devuelve todas las unidades lógicas disponibles (A:\ … Z:\).
@@ -381,10 +424,9 @@ mod windows_scanners {
             .ok()
             .and_then(|p| p.to_str().map(|s| s.to_lowercase()));
 
-        VDF_PATH_REGEX
-            .captures_iter(&content)
-            .filter_map(|cap| cap.get(1))
-            .map(|m| m.as_str().replace("\\\\", "\\"))
+        steam::vdf::parse_libraryfolders(&content)
+            .into_iter()
+            .map(|p| p.to_string_lossy().to_string())
             .filter(|p| {
                 if let (Some(steam), Ok(lib_canon)) = (&steam_norm, Path::new(p).canonicalize()) {
                     if let Some(lib_s) = lib_canon.to_str() {
@@ -655,7 +697,6 @@ fn read_registry_install_dir(full_path: &str) -> Option<String> {
 ///
 /// Este índice permite que el filtro de redundancia opere en O(1) por
 /// candidato en lugar de iterar toda la lista en cada comprobación (O(n²)).
-#[cfg(target_os = "windows")]
 fn build_official_path_index(candidates: &[PathCandidateDto]) -> HashSet<String> {
     let mut index = HashSet::new();
     for c in candidates {
@@ -672,13 +713,13 @@ fn build_official_path_index(candidates: &[PathCandidateDto]) -> HashSet<String>
 }
 
 pub fn scan_path_candidates_sync(
-    #[cfg(target_os = "windows")] manifest_index: Option<crate::manifest::ManifestIndex>,
+    manifest_index: Option<crate::manifest::ManifestIndex>,
 ) -> Vec<PathCandidateDto> {
     let cfg = config::load_config();
     let env = EnvContext::resolve();
     let mut list = CandidateList::new();
+    let launcher_games = InstalledGamesIndex::build();
 
-    #[cfg(target_os = "windows")]
     if let Some(manifest) = &manifest_index {
         let mut unique_entries = Vec::new();
         let mut seen_names = HashSet::new();
@@ -688,32 +729,79 @@ pub fn scan_path_candidates_sync(
             }
         }
 
+        // En Linux la carpeta de instalación sale de las bibliotecas de Steam y las
+        // rutas de Windows se buscan dentro del prefijo Proton de cada juego.
+        #[cfg(not(target_os = "windows"))]
+        let proton_prefixes = crate::steam::proton::list_proton_prefixes();
+        #[cfg(not(target_os = "windows"))]
+        let common_install_dirs = crate::steam::proton::list_common_install_dirs();
+
         let active_candidates: Vec<PathCandidateDto> = unique_entries
             .par_iter()
             .filter_map(|entry| {
                 let mut valid_game_paths = Vec::new();
 
-                let mut install_dir_cache: Option<String> = None;
-                if let Some(reg_path) = &entry.registry_path {
-                    install_dir_cache = read_registry_install_dir(reg_path);
-                }
+                // Juegos de otros lanzadores: aportan carpeta de instalación y prefijo Wine
+                // cuando Steam/registro no los conocen.
+                let launcher_game = launcher_games.find(&entry.install_dirs, &entry.name);
+                let launcher_install_dir =
+                    || launcher_game.map(|g| g.install_dir.to_string_lossy().to_string());
+
+                #[cfg(target_os = "windows")]
+                let (install_dir_cache, ctx) = (
+                    entry
+                        .registry_path
+                        .as_ref()
+                        .and_then(|reg_path| read_registry_install_dir(reg_path))
+                        .or_else(launcher_install_dir),
+                    HostContext::default(),
+                );
+                #[cfg(not(target_os = "windows"))]
+                let (install_dir_cache, ctx) = (
+                    entry
+                        .install_dirs
+                        .iter()
+                        .find_map(|name| {
+                            common_install_dirs
+                                .get(&name.to_lowercase())
+                                .map(|p| p.to_string_lossy().to_string())
+                        })
+                        .or_else(launcher_install_dir),
+                    HostContext {
+                        proton_prefix: entry
+                            .steam_id
+                            .as_ref()
+                            .and_then(|id| proton_prefixes.get(id).cloned())
+                            .or_else(|| launcher_game.and_then(|g| g.wine_prefix.clone())),
+                    },
+                );
 
                 for template in &entry.save_paths {
-                    let expanded_path_opt = match template {
+                    let expanded_path_opt = match &template.template {
                         crate::manifest::PathTemplate::Absolute(_) => {
-                            Some(crate::manifest::resolve_path_template(template, None))
+                            Some(crate::manifest::resolve_path_template(template, None, &ctx))
                         }
                         crate::manifest::PathTemplate::RelativeToInstall(_) => {
                             install_dir_cache.as_ref().map(|install_dir| {
-                                crate::manifest::resolve_path_template(template, Some(install_dir))
+                                crate::manifest::resolve_path_template(
+                                    template,
+                                    Some(install_dir),
+                                    &ctx,
+                                )
                             })
                         }
                     };
 
-                    if let Some(expanded) = expanded_path_opt {
+                    let expanded_path_opt = expanded_path_opt.filter(|p| !p.is_empty());
+
+                    // Una ruta por carpeta de usuario de la tienda (`<storeUserId>`).
+                    for expanded in expanded_path_opt
+                        .iter()
+                        .flat_map(|p| crate::utils::path_utils::expand_dir_wildcards(p))
+                    {
                         let clean_path_str = if let Some(idx) = expanded.find('*') {
                             let before = &expanded[..idx];
-                            if let Some(sep) = before.rfind(|c| c == '\\' || c == '/') {
+                            if let Some(sep) = before.rfind(['\\', '/']) {
                                 before[..sep].to_string()
                             } else {
                                 before.to_string()
@@ -735,10 +823,9 @@ pub fn scan_path_candidates_sync(
                                 && fs::read_dir(&folder_path)
                                     .map(|mut i| i.next().is_some())
                                     .unwrap_or(false)
+                                && !valid_game_paths.contains(&folder_path)
                             {
-                                if !valid_game_paths.contains(&folder_path) {
-                                    valid_game_paths.push(folder_path);
-                                }
+                                valid_game_paths.push(folder_path);
                             }
                         }
                     }
@@ -774,6 +861,8 @@ pub fn scan_path_candidates_sync(
         .collect();
 
     list.extend(parallel_candidates);
+    list.extend(find_launcher_candidates(launcher_games.games()));
+    list.extend(find_emulator_candidates());
 
     #[cfg(target_os = "windows")]
     {
@@ -784,10 +873,7 @@ pub fn scan_path_candidates_sync(
 
     let all_candidates = list.into_vec();
 
-    #[cfg(target_os = "windows")]
     let official_index = build_official_path_index(&all_candidates);
-    #[cfg(not(target_os = "windows"))]
-    let official_index: HashSet<String> = HashSet::new();
 
     all_candidates
         .into_iter()
@@ -809,19 +895,9 @@ pub fn scan_path_candidates_sync(
 
 #[tauri::command]
 pub async fn scan_path_candidates() -> Result<Vec<PathCandidateDto>, String> {
-    #[cfg(target_os = "windows")]
     let manifest_index = crate::manifest::load_manifest_index_async().await.ok();
 
-    tauri::async_runtime::spawn_blocking(move || {
-        #[cfg(target_os = "windows")]
-        {
-            scan_path_candidates_sync(manifest_index)
-        }
-        #[cfg(not(target_os = "windows"))]
-        {
-            scan_path_candidates_sync()
-        }
-    })
-    .await
-    .map_err(|e| format!("Error en el hilo de escaneo: {}", e))
+    tauri::async_runtime::spawn_blocking(move || scan_path_candidates_sync(manifest_index))
+        .await
+        .map_err(|e| format!("Error en el hilo de escaneo: {}", e))
 }
@@ -3,18 +3,14 @@
 //! Contiene las estructuras de datos y funciones para:
 //!
 //! - Obtener las estadísticas por juego.
-//! - Expandir variables de entorno en rutas.
-//! - Recorrer directorios para sumar tamaños y encontrar la fecha más reciente.
-//! - Calcular estadísticas locales para una lista de rutas de un juego.
+//! - Calcular estadísticas locales (tamaño y fecha más reciente) de los archivos de un juego.
 
 use crate::commands::sync;
 use crate::config;
+use crate::utils::path_translation;
 use futures_util::future::join_all;
-use regex::Regex;
 use serde::Serialize;
 use std::collections::HashMap;
-use std::fs;
-use std::path::{Path, PathBuf};
 use std::time::UNIX_EPOCH;
 
 #[derive(Serialize)]
@@ -27,100 +23,15 @@ pub struct GameStatsDto {
     pub playtime_seconds: u64,
 }
 
-/// Expande variables de entorno como %APPDATA% o ~ en rutas.
-fn expand_path(raw: &str) -> Option<PathBuf> {
-    let mut result = raw.to_string();
-    let re = Regex::new(r"%([^%]+)%").ok()?;
-    for cap in re.captures_iter(raw) {
-        let var = cap.get(1)?.as_str();
-        let val = std::env::var(var).unwrap_or_default();
-        result = result.replace(&format!("%{}%", var), &val);
-    }
-    if result.starts_with('~') {
-        let home = std::env::var("USERPROFILE")
-            .or_else(|_| std::env::var("HOME"))
-            .unwrap_or_default();
-        if !home.is_empty() {
-            let rest = result.trim_start_matches('~').trim_start_matches('/');
-            result = if rest.is_empty() {
-                home
-            } else {
-                format!("{}/{}", home.trim_end_matches(&['/', '\\']), rest)
-            };
-        }
-    }
-    if result.is_empty() {
-        None
-    } else {
-        Some(PathBuf::from(result))
-    }
-}
-
-/// Recorre directorios para sumar tamaños y encontrar la fecha más reciente.
-fn collect_files_with_meta(dir: &Path, base: &Path, out: &mut Vec<(u64, std::time::SystemTime)>) {
-    let Ok(entries) = fs::read_dir(dir) else {
-        return;
-    };
-    for e in entries.flatten() {
-        let full = e.path();
-        let meta = match e.metadata() {
-            Ok(m) => m,
-            Err(_) => continue,
-        };
-        if meta.is_dir() {
-            if !e.file_name().to_string_lossy().starts_with('.') {
-                collect_files_with_meta(&full, base, out);
-            }
-        } else if meta.is_file() {
-            if full.strip_prefix(base).is_ok() {
-                let size = meta.len();
-                let mtime = meta.modified().unwrap_or(UNIX_EPOCH);
-                out.push((size, mtime));
-            }
-        }
-    }
-}
-
-/// Calcula estadísticas locales para una lista de rutas de un juego.
-fn local_stats_for_paths(paths: &[String]) -> (u64, Option<std::time::SystemTime>) {
-    let mut total_size = 0u64;
-    let mut max_mtime: Option<std::time::SystemTime> = None;
-
-    for raw in paths {
-        let expanded = match expand_path(raw.trim()) {
-            Some(p) => p,
-            None => continue,
-        };
-        if !expanded.exists() {
-            continue;
-        }
-        let meta = match fs::metadata(&expanded) {
-            Ok(m) => m,
-            Err(_) => continue,
-        };
-        if meta.is_file() {
-            total_size += meta.len();
-            if let Ok(mtime) = meta.modified() {
-                max_mtime = Some(match max_mtime {
-                    Some(prev) if mtime > prev => mtime,
-                    Some(prev) => prev,
-                    None => mtime,
-                });
-            }
-        } else if meta.is_dir() {
-            let mut files = Vec::new();
-            collect_files_with_meta(&expanded, &expanded, &mut files);
-            for (size, mtime) in files {
-                total_size += size;
-                max_mtime = Some(match max_mtime {
-                    Some(prev) if mtime > prev => mtime,
-                    Some(prev) => prev,
-                    None => mtime,
-                });
-            }
-        }
-    }
-
+/// Suma el tamaño de los archivos locales de un juego y devuelve la fecha más reciente.
+///
+/// Parte de [`path_translation::list_game_files_with_mtime`], así que cuenta solo lo que
+/// se sincronizaría: las rutas con comodines (`saves/Juego.*`) suman los archivos que
+/// coinciden, no la carpeta compartida entera.
+fn local_stats_for_game(game: &config::ConfiguredGame) -> (u64, Option<std::time::SystemTime>) {
+    let files = path_translation::list_game_files_with_mtime(game);
+    let total_size = files.iter().map(|(_, _, _, size)| size).sum();
+    let max_mtime = files.iter().map(|(_, _, mtime, _)| *mtime).max();
     (total_size, max_mtime)
 }
 
@@ -164,9 +75,9 @@ pub async fn get_game_stats() -> Result<Vec<GameStatsDto>, String> {
     let mut handles = Vec::with_capacity(cfg.games.len());
     for game in &cfg.games {
         let id = game.id.clone();
-        let paths = game.paths.clone();
+        let game = game.clone();
         handles.push(tokio::task::spawn_blocking(move || {
-            let (local_size, local_mtime) = local_stats_for_paths(&paths);
+            let (local_size, local_mtime) = local_stats_for_game(&game);
             (id, local_size, local_mtime)
         }));
     }
@@ -1,16 +1,13 @@
-//! Cliente HTTP para la API de gestión de guardados.
+//! Comandos de gestión de guardados remotos.
 //!
-//! Encapsula la comunicación con el backend, proporcionando métodos
-//! para interactuar con los endpoints relacionados con la sincronización
-//! de archivos.
-//!
-//! Incluye configuración de timeouts, manejo de errores y soporte para
-//! reintentos en solicitudes críticas.
+//! Listado, borrado y renombrado de juegos y copia de guardados de amigos sobre
+//! el backend de almacenamiento del perfil ([`crate::storage`]).
 
+use super::models::RemoteSaveInfoDto;
 use super::models::SyncResultDto;
-use super::models::{RemoteSaveDto, RemoteSaveInfoDto};
-use crate::network::API_CLIENT;
-use serde::{Deserialize, Serialize};
+use crate::devices::registry::DEVICES_FOLDER;
+use crate::storage::{Storage, StorageBackend, BACKUPS_PREFIX, META_PREFIX};
+use serde::Deserialize;
 use std::sync::LazyLock;
 
 const S3_ACCELERATE_HOST: &str = "s3-accelerate.amazonaws.com";
@@ -18,53 +15,6 @@ const S3_ACCELERATE_HOST: &str = "s3-accelerate.amazonaws.com";
 static S3_ENDPOINT_CACHE: LazyLock<std::sync::RwLock<Option<String>>> =
     LazyLock::new(|| std::sync::RwLock::new(None));
 
-#[derive(Serialize)]
-#[serde(rename_all = "camelCase")]
-struct UploadUrlItemRequest {
-    game_id: String,
-    filename: String,
-}
-
-#[derive(Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct UploadUrlResultItem {
-    upload_url: String,
-    #[serde(rename = "key")]
-    _key: String,
-    #[serde(rename = "gameId")]
-    _game_id: String,
-    filename: String,
-}
-
-#[derive(Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct UploadUrlsResponse {
-    urls: Vec<UploadUrlResultItem>,
-}
-
-#[derive(Serialize)]
-#[serde(rename_all = "camelCase")]
-struct DownloadUrlItemRequest {
-    game_id: String,
-    key: String,
-}
-
-#[derive(Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct DownloadUrlResultItem {
-    download_url: String,
-    #[serde(rename = "gameId")]
-    _game_id: String,
-    #[serde(rename = "key")]
-    key: String,
-}
-
-#[derive(Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct DownloadUrlsResponse {
-    urls: Vec<DownloadUrlResultItem>,
-}
-
 #[derive(Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct CopyFriendFilePlanDto {
@@ -73,234 +23,58 @@ pub struct CopyFriendFilePlanDto {
     pub target_filename: String,
 }
 
-struct ApiContext {
-    base_url: String,
-    user_id: String,
-    api_key: String,
-}
-
-fn get_api_context() -> Result<ApiContext, String> {
-    let cfg = crate::config::load_config();
-    let base_url = cfg
-        .api_base_url
-        .as_deref()
-        .filter(|s| !s.trim().is_empty())
-        .ok_or("Configura apiBaseUrl en Configuración")?
-        .to_string();
-    let user_id = cfg
-        .user_id
-        .as_deref()
-        .filter(|s| !s.trim().is_empty())
-        .ok_or("Configura userId en Configuración")?
-        .to_string();
-    let api_key = cfg.api_key.unwrap_or_default();
-
-    Ok(ApiContext {
-        base_url,
-        user_id,
-        api_key,
-    })
-}
-
-pub(crate) async fn api_request(
-    base_url: &str,
-    user_id: &str,
-    api_key: &str,
-    method: &str,
-    path: &str,
-    body: Option<&[u8]>,
-) -> Result<reqwest::Response, String> {
-    let url = format!("{}/saves{}", base_url.trim_end_matches('/'), path);
-
-    let mut req = API_CLIENT
-        .request(method.parse().unwrap(), &url)
-        .header("x-user-id", user_id)
-        .header("x-api-key", api_key);
-
-    if let Some(b) = body {
-        req = req
-            .header("Content-Type", "application/json")
-            .body(b.to_vec());
-    }
-
-    req.send().await.map_err(|e| e.to_string())
-}
-
-pub(crate) async fn get_upload_urls(
-    base_url: &str,
-    user_id: &str,
-    api_key: &str,
-    game_id: &str,
-    filenames: &[String],
-) -> Result<Vec<(String, String)>, String> {
-    if filenames.is_empty() {
-        return Ok(Vec::new());
-    }
-    let items: Vec<UploadUrlItemRequest> = filenames
-        .iter()
-        .map(|f| UploadUrlItemRequest {
-            game_id: game_id.to_string(),
-            filename: f.clone(),
-        })
-        .collect();
-
-    let body = serde_json::json!({ "items": items }).to_string();
-
-    let res = api_request(
-        base_url,
-        user_id,
-        api_key,
-        "POST",
-        "/upload-urls",
-        Some(body.as_bytes()),
-    )
-    .await
-    .map_err(|e| format!("upload-urls: {}", e))?;
-
-    if !res.status().is_success() {
-        return Err(format!(
-            "API upload-urls: {} {}",
-            res.status(),
-            res.text().await.unwrap_or_default()
-        ));
-    }
-
-    let parsed: UploadUrlsResponse = res.json().await.map_err(|e| e.to_string())?;
-    Ok(parsed
-        .urls
-        .into_iter()
-        .map(|u| (u.upload_url, u.filename))
-        .collect())
-}
-
-pub(crate) async fn get_download_urls(
-    base_url: &str,
-    user_id: &str,
-    api_key: &str,
-    items: &[(String, String)],
-) -> Result<Vec<(String, String)>, String> {
-    if items.is_empty() {
-        return Ok(Vec::new());
-    }
-    let req_items: Vec<DownloadUrlItemRequest> = items
-        .iter()
-        .map(|(game_id, key)| DownloadUrlItemRequest {
-            game_id: game_id.clone(),
-            key: key.clone(),
-        })
-        .collect();
-
-    let body = serde_json::json!({ "items": req_items }).to_string();
-
-    let res = api_request(
-        base_url,
-        user_id,
-        api_key,
-        "POST",
-        "/download-urls",
-        Some(body.as_bytes()),
-    )
-    .await
-    .map_err(|e| format!("download-urls: {}", e))?;
-
-    if !res.status().is_success() {
-        return Err(format!(
-            "API download-urls: {} {}",
-            res.status(),
-            res.text().await.unwrap_or_default()
-        ));
-    }
-
-    let parsed: DownloadUrlsResponse = res.json().await.map_err(|e| e.to_string())?;
-    Ok(parsed
-        .urls
-        .into_iter()
-        .map(|u| (u.download_url, u.key))
-        .collect())
-}
-
+/// Lista los guardados de `owner` en el backend, sin los backups empaquetados.
 async fn list_remote_saves_for_user(
-    api_base: &str,
-    api_key: &str,
-    user_id: &str,
+    storage: &Storage,
+    owner: &str,
 ) -> Result<Vec<RemoteSaveInfoDto>, String> {
-    let res = api_request(api_base, user_id, api_key, "GET", "", None)
-        .await
-        .map_err(|e| format!("GET /saves: {}", e))?;
-
-    if !res.status().is_success() {
-        return Err(format!(
-            "API: {} {}",
-            res.status(),
-            res.text().await.unwrap_or_default()
-        ));
-    }
-
-    let raw: Vec<RemoteSaveDto> = res.json().await.map_err(|e| e.to_string())?;
-    let out: Vec<RemoteSaveInfoDto> = raw
+    let objects = storage.list(owner).await?;
+    Ok(objects
         .into_iter()
-        .map(|s| {
-            let parts: Vec<&str> = s.key.split('/').collect();
-            let filename = if parts.len() >= 3 {
-                parts[2..].join("/")
-            } else {
-                s.key.clone()
-            };
+        .map(|o| {
+            let filename = o.filename();
             RemoteSaveInfoDto {
-                game_id: s.game_id,
-                key: s.key,
+                game_id: o.game_id,
+                key: o.key,
                 filename,
-                last_modified: s.last_modified,
-                size: s.size,
+                last_modified: o.last_modified,
+                size: o.size,
             }
         })
-        .collect();
-    Ok(out)
+        .filter(|s| {
+            !s.filename.starts_with(BACKUPS_PREFIX)
+                && !s.filename.starts_with(META_PREFIX)
+                && s.game_id != DEVICES_FOLDER
+        })
+        .collect())
 }
 
 #[tauri::command]
 pub async fn sync_list_remote_saves() -> Result<Vec<RemoteSaveInfoDto>, String> {
-    let ctx = get_api_context()?;
-    list_remote_saves_for_user(&ctx.base_url, &ctx.api_key, &ctx.user_id).await
+    let storage = Storage::active()?;
+    list_remote_saves_for_user(&storage, storage.user_id()).await
 }
 
 #[tauri::command]
 pub async fn sync_list_remote_saves_for_user(
     user_id: String,
 ) -> Result<Vec<RemoteSaveInfoDto>, String> {
-    let ctx = get_api_context()?;
+    let storage = Storage::active()?;
     let trimmed_user_id = user_id.trim();
     if trimmed_user_id.is_empty() {
         return Err("userId vacío".into());
     }
 
-    list_remote_saves_for_user(&ctx.base_url, &ctx.api_key, trimmed_user_id).await
+    list_remote_saves_for_user(&storage, trimmed_user_id).await
 }
 
 #[tauri::command]
 pub async fn sync_delete_game_from_cloud(game_id: String) -> Result<(), String> {
-    let ctx = get_api_context()?;
-    let body = serde_json::json!({ "gameId": game_id.trim() }).to_string();
-
-    let res = api_request(
-        &ctx.base_url,
-        &ctx.user_id,
-        &ctx.api_key,
-        "POST",
-        "/delete-game",
-        Some(body.as_bytes()),
-    )
-    .await
-    .map_err(|e| format!("delete-game: {}", e))?;
-
-    if !res.status().is_success() && res.status().as_u16() != 204 {
-        return Err(format!(
-            "API delete-game: {} {}",
-            res.status(),
-            res.text().await.unwrap_or_default()
-        ));
-    }
-    Ok(())
+    let storage = Storage::active()?;
+    storage
+        .delete_game(game_id.trim())
+        .await
+        .map_err(|e| format!("delete-game: {}", e))
 }
 
 #[tauri::command]
@@ -308,32 +82,11 @@ pub async fn sync_rename_game_in_cloud(
     old_game_id: String,
     new_game_id: String,
 ) -> Result<(), String> {
-    let ctx = get_api_context()?;
-    let body = serde_json::json!({
-        "oldGameId": old_game_id.trim(),
-        "newGameId": new_game_id.trim()
-    })
-    .to_string();
-
-    let res = api_request(
-        &ctx.base_url,
-        &ctx.user_id,
-        &ctx.api_key,
-        "POST",
-        "/rename-game",
-        Some(body.as_bytes()),
-    )
-    .await
-    .map_err(|e| format!("rename-game: {}", e))?;
-
-    if !res.status().is_success() && res.status().as_u16() != 204 {
-        return Err(format!(
-            "API rename-game: {} {}",
-            res.status(),
-            res.text().await.unwrap_or_default()
-        ));
-    }
-    Ok(())
+    let storage = Storage::active()?;
+    storage
+        .rename_game(old_game_id.trim(), new_game_id.trim())
+        .await
+        .map_err(|e| format!("rename-game: {}", e))
 }
 
 async fn copy_friend_saves_with_plan_impl(
@@ -341,7 +94,7 @@ async fn copy_friend_saves_with_plan_impl(
     game_id: &str,
     plan: Vec<CopyFriendFilePlanDto>,
 ) -> Result<SyncResultDto, String> {
-    let ctx = get_api_context()?;
+    let storage = Storage::active()?;
 
     if plan.is_empty() {
         return Ok(SyncResultDto {
@@ -356,101 +109,30 @@ async fn copy_friend_saves_with_plan_impl(
         .map(|p| (game_id.to_string(), p.key.clone()))
         .collect();
 
-    let download_urls = match get_download_urls(
-        &ctx.base_url,
-        friend_user_id,
-        &ctx.api_key,
-        &download_requests,
-    )
-    .await
-    {
-        Ok(urls) => urls,
-        Err(e) => {
-            return Err(format!(
-                "Fallo al obtener URLs de descarga en bloque: {}",
-                e
-            ))
-        }
-    };
+    let download_targets = storage
+        .get_targets(friend_user_id, &download_requests)
+        .await
+        .map_err(|e| format!("Fallo al obtener URLs de descarga en bloque: {}", e))?;
 
     let upload_requests: Vec<String> = plan.iter().map(|p| p.target_filename.clone()).collect();
-    let upload_urls = match get_upload_urls(
-        &ctx.base_url,
-        &ctx.user_id,
-        &ctx.api_key,
-        game_id,
-        &upload_requests,
-    )
-    .await
-    {
-        Ok(urls) => urls,
-        Err(e) => return Err(format!("Fallo al obtener URLs de subida en bloque: {}", e)),
-    };
-
-    let download_map: std::collections::HashMap<_, _> = download_urls
-        .into_iter()
-        .map(|(url, key)| (key, url))
-        .collect();
-    let upload_map: std::collections::HashMap<_, _> = upload_urls
-        .into_iter()
-        .map(|(url, filename)| (filename, url))
-        .collect();
+    let upload_targets = storage
+        .put_targets(game_id, &upload_requests)
+        .await
+        .map_err(|e| format!("Fallo al obtener URLs de subida en bloque: {}", e))?;
 
     let mut set = tokio::task::JoinSet::new();
 
-    for item in plan {
-        let download_url = download_map.get(&item.key).cloned();
-        let upload_url = upload_map.get(&item.target_filename).cloned();
-
+    for ((item, source), dest) in plan.into_iter().zip(download_targets).zip(upload_targets) {
         set.spawn(async move {
-            let mut item_err = None;
-            let mut success = false;
-
-            let d_url = match download_url {
-                Some(u) => u,
-                None => return (item, false, Some("Sin URL de descarga".to_string())),
-            };
-            let u_url = match upload_url {
-                Some(u) => u,
-                None => return (item, false, Some("Sin URL de subida".to_string())),
+            let bytes = match source.read_all().await {
+                Ok(b) => b,
+                Err(e) => return (item, false, Some(format!("error en descarga: {}", e))),
             };
 
-            let bytes = match API_CLIENT.get(&d_url).send().await {
-                Ok(resp) if resp.status().is_success() => match resp.bytes().await {
-                    Ok(b) => b,
-                    Err(e) => return (item, false, Some(format!("error leyendo descarga: {}", e))),
-                },
-                Ok(resp) => {
-                    return (
-                        item,
-                        false,
-                        Some(format!("error HTTP descarga: {}", resp.status())),
-                    )
-                }
-                Err(e) => {
-                    return (
-                        item,
-                        false,
-                        Some(format!("falla de red en descarga: {}", e)),
-                    )
-                }
-            };
-
-            let content_length = bytes.len();
-            match API_CLIENT
-                .put(&u_url)
-                .body(bytes)
-                .header("Content-Type", "application/octet-stream")
-                .header("Content-Length", content_length.to_string())
-                .send()
-                .await
-            {
-                Ok(put_res) if put_res.status().is_success() => success = true,
-                Ok(put_res) => item_err = Some(format!("error S3 PUT: {}", put_res.status())),
-                Err(e) => item_err = Some(format!("falla de red en subida: {}", e)),
-            };
-
-            (item, success, item_err)
+            match dest.put(bytes, "application/octet-stream").await {
+                Ok(_) => (item, true, None),
+                Err(e) => (item, false, Some(format!("error en subida: {}", e))),
+            }
         });
     }
 
@@ -508,8 +190,8 @@ pub async fn copy_friend_saves(
         return Err("gameId vacío".into());
     }
 
-    let ctx = get_api_context()?;
-    let all_saves = list_remote_saves_for_user(&ctx.base_url, &ctx.api_key, friend_id).await?;
+    let storage = Storage::active()?;
+    let all_saves = list_remote_saves_for_user(&storage, friend_id).await?;
 
     let plan: Vec<CopyFriendFilePlanDto> = all_saves
         .into_iter()
@@ -548,6 +230,13 @@ pub async fn copy_friend_saves_with_plan(
     copy_friend_saves_with_plan_impl(friend_id, game_id_trimmed, plan).await
 }
 
+/// Olvida el tipo de endpoint detectado (al cambiar de backend).
+pub(crate) fn clear_s3_endpoint_cache() {
+    if let Ok(mut lock) = S3_ENDPOINT_CACHE.write() {
+        *lock = None;
+    }
+}
+
 #[tauri::command]
 pub async fn get_s3_transfer_endpoint_type() -> Result<String, String> {
     if let Ok(lock) = S3_ENDPOINT_CACHE.read() {
@@ -556,36 +245,18 @@ pub async fn get_s3_transfer_endpoint_type() -> Result<String, String> {
         }
     }
 
-    let Ok(ctx) = get_api_context() else {
+    let Ok(storage) = Storage::active() else {
         return Ok("unknown".to_string());
     };
 
-    let body = serde_json::json!({
-        "gameId": "__check__",
-        "filename": "__check__.tmp"
-    })
-    .to_string();
-
-    let res = match api_request(
-        &ctx.base_url,
-        &ctx.user_id,
-        &ctx.api_key,
-        "POST",
-        "/upload-url",
-        Some(body.as_bytes()),
-    )
-    .await
-    {
-        Ok(r) => r,
-        Err(_) => return Ok("unknown".to_string()),
+    // Solo la API entrega URLs de un S3 que puede estar acelerado.
+    let Storage::Api(api) = &storage else {
+        return Ok("standard".to_string());
     };
 
-    if !res.status().is_success() {
+    let Ok(upload_url) = api.probe_upload_url("__check__", "__check__.tmp").await else {
         return Ok("unknown".to_string());
-    }
-
-    let json: serde_json::Value = res.json().await.unwrap_or_default();
-    let upload_url = json.get("uploadUrl").and_then(|v| v.as_str()).unwrap_or("");
+    };
 
     let endpoint_type = if upload_url.contains(S3_ACCELERATE_HOST) {
         "accelerated".to_string()
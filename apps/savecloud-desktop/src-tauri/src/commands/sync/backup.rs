@@ -15,7 +15,7 @@ use std::path::Path;
 use std::time::UNIX_EPOCH;
 
 use super::models::{BackupInfoDto, CleanupBackupsResultDto, SyncResultDto};
-use crate::utils::path_utils;
+use crate::utils::path_translation::SaveDestinations;
 
 fn count_files_recursive(dir: &Path) -> u32 {
     let Ok(entries) = fs::read_dir(dir) else {
@@ -36,7 +36,7 @@ fn count_files_recursive(dir: &Path) -> u32 {
 fn copy_recursive_to(
     src_root: &Path,
     src: &Path,
-    dest_base: &Path,
+    dest: &SaveDestinations,
     ok_count: &mut u32,
     errors: &mut Vec<String>,
 ) {
@@ -49,10 +49,11 @@ fn copy_recursive_to(
         let Some(rel) = rel else {
             continue;
         };
-        let dest_path = dest_base.join(rel);
+        let rel_str = rel.to_string_lossy();
+        let dest_path = dest.dir_for(&rel_str).join(rel);
         if path.is_dir() {
             let _ = fs::create_dir_all(&dest_path);
-            copy_recursive_to(src_root, &path, dest_base, ok_count, errors);
+            copy_recursive_to(src_root, &path, dest, ok_count, errors);
         } else if path.is_file() {
             match fs::copy(&path, &dest_path) {
                 Ok(_) => *ok_count += 1,
@@ -142,10 +143,7 @@ pub fn restore_backup(game_id: String, backup_id: String) -> Result<SyncResultDt
         ));
     }
 
-    let dest_base = match path_utils::expand_path(game.paths[0].trim()) {
-        Some(p) => std::path::PathBuf::from(p),
-        None => return Err("No se pudo expandir la ruta del juego".into()),
-    };
+    let dest = SaveDestinations::for_game(game).ok_or("No se pudo expandir la ruta del juego")?;
 
     let backup_dir = crate::config::config_dir()
         .ok_or("No se pudo obtener directorio de configuración")?
@@ -160,13 +158,7 @@ pub fn restore_backup(game_id: String, backup_id: String) -> Result<SyncResultDt
     let mut ok_count = 0u32;
     let mut errors = Vec::new();
 
-    copy_recursive_to(
-        &backup_dir,
-        &backup_dir,
-        &dest_base,
-        &mut ok_count,
-        &mut errors,
-    );
+    copy_recursive_to(&backup_dir, &backup_dir, &dest, &mut ok_count, &mut errors);
 
     Ok(SyncResultDto {
         ok_count,
@@ -0,0 +1,52 @@
+//! Pausa y cancelación de transferencias sin depender del estado de la bandeja.
+//!
+//! El motor de sincronización comprueba un [`CancellationToken`] entre partes y
+//! entre archivos. Cada transferencia del gestor (`crate::transfers`) tiene el suyo,
+//! que la UI pausa o cancela por separado y la CLI cancela con Ctrl+C.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+#[derive(Default)]
+struct TokenState {
+    cancel: AtomicBool,
+    pause: AtomicBool,
+}
+
+/// Token clonable; todas las copias comparten los mismos flags.
+#[derive(Clone, Default)]
+pub struct CancellationToken(Arc<TokenState>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pide cancelar: las subidas multipart se abortan en el backend.
+    pub fn cancel(&self) {
+        self.0.cancel.store(true, Ordering::Release);
+    }
+
+    /// Pide pausar: la subida multipart guarda su estado en disco para reanudarla.
+    pub fn pause(&self) {
+        self.0.pause.store(true, Ordering::Release);
+    }
+
+    /// Quita la pausa pedida (la cancelación no se puede deshacer).
+    pub fn resume(&self) {
+        self.0.pause.store(false, Ordering::Release);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancel.load(Ordering::Acquire)
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.0.pause.load(Ordering::Acquire)
+    }
+
+    /// True si se pidió pausar o cancelar.
+    pub fn should_stop(&self) -> bool {
+        self.is_cancelled() || self.is_paused()
+    }
+}
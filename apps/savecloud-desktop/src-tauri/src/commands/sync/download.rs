@@ -9,7 +9,6 @@
 use std::collections::HashMap;
 use std::fs;
 use std::io::ErrorKind;
-use std::path::PathBuf;
 use std::time::{Duration, UNIX_EPOCH};
 
 use chrono::{DateTime, Utc};
@@ -18,14 +17,19 @@ use tokio::io::{AsyncWriteExt, BufWriter};
 
 use super::api;
 use super::backup;
+use super::cancellation::CancellationToken;
 use super::models::{
     DownloadConflictDto, DownloadConflictsResultDto, GameConflictsResultDto, GameSyncResultDto,
     RemoteSaveInfoDto, SyncProgressPayload, SyncResultDto, UnsyncedGameDto,
 };
-use crate::utils::path_utils;
+use super::observer::SyncObserver;
+use super::paused::{self, PausedTransfer, ResumePoint};
 use crate::commands::logs::sync_logger;
-use crate::network::DATA_CLIENT;
+use crate::plugins::hooks::{self, BackupInfo};
+use crate::storage::{Storage, StorageBackend, TransferTarget};
+use crate::transfers::{self, TransferKind, TransferPriority};
 use crate::tray::tray_state::TrayState;
+use crate::utils::path_translation::SaveDestinations;
 use tauri::{AppHandle, Emitter, State};
 
 /// Número máximo de reintentos al intentar crear un archivo bloqueado.
@@ -42,18 +46,9 @@ const DOWNLOAD_PROGRESS_EMIT_BYTES: u64 = 256 * 1024;
 /// Número máximo de descargas de archivos individuales en paralelo por juego.
 const DOWNLOAD_FILE_CONCURRENCY: usize = 16;
 
-/// Número máximo de juegos descargándose en paralelo en una operación batch.
-const DOWNLOAD_BATCH_CONCURRENCY: usize = 4;
-
-/// Número máximo de restauraciones de backups empaquetados en paralelo.
-const RESTORE_PACKAGED_CONCURRENCY: usize = 2;
-
 /// Tamaño del buffer de escritura a disco, en bytes.
 const WRITE_BUF_SIZE: usize = 512 * 1024;
 
-/// Tamaño máximo de un lote al solicitar URLs de descarga a la API.
-const DOWNLOAD_URLS_BATCH_SIZE: usize = 500;
-
 /// Tolerancia en segundos al comparar timestamps local vs. nube en la detección
 /// de archivos sin sincronizar.
 ///
@@ -128,16 +123,16 @@ async fn create_file_with_retry(path: &std::path::Path) -> Result<tokio::fs::Fil
 ///
 /// # Arguments
 ///
-/// * `dest_base` - Directorio raíz donde residen los archivos de guardado locales.
+/// * `dest` - Carpetas donde residen los archivos de guardado locales.
 /// * `saves` - Lista de metadatos de archivos disponibles en la nube para este juego.
-fn check_conflicts_for_game(
-    dest_base: &std::path::Path,
+pub(crate) fn check_conflicts_for_game(
+    dest: &SaveDestinations,
     saves: &[RemoteSaveInfoDto],
 ) -> Vec<DownloadConflictDto> {
     let mut conflicts = Vec::new();
 
     for save in saves {
-        let dest_path = dest_base.join(&save.filename);
+        let dest_path = dest.dir_for(&save.filename).join(&save.filename);
 
         let Ok(meta) = fs::metadata(&dest_path) else {
             continue;
@@ -167,6 +162,7 @@ fn check_conflicts_for_game(
                 filename: save.filename.clone(),
                 local_modified: local_dt.to_rfc3339(),
                 cloud_modified: save.last_modified.clone(),
+                remote_origin: None,
             });
         }
     }
@@ -174,6 +170,50 @@ fn check_conflicts_for_game(
     conflicts
 }
 
+/// Completa cada conflicto con el dispositivo que subió la versión de la nube.
+///
+/// Solo lee la procedencia del juego si hay conflictos; si no se puede leer,
+/// los conflictos se devuelven sin origen.
+pub(crate) async fn attach_remote_origins(
+    saves: &[RemoteSaveInfoDto],
+    conflicts: &mut [DownloadConflictDto],
+) {
+    // La carpeta remota puede diferir en mayúsculas del id configurado.
+    let Some(remote_game_id) = saves.first().map(|s| s.game_id.as_str()) else {
+        return;
+    };
+    if conflicts.is_empty() {
+        return;
+    }
+    let Ok(storage) = Storage::active() else {
+        return;
+    };
+    let Ok(provenance) = crate::devices::provenance::load(&storage, remote_game_id).await else {
+        return;
+    };
+    for conflict in conflicts {
+        conflict.remote_origin = provenance.origin_of(&conflict.filename).cloned();
+    }
+}
+
+/// Indica si la nube tiene algún archivo del juego que falta en local o es más
+/// reciente que su copia local (con la tolerancia de [`UNSYNCED_LOCAL_NEWER_TOLERANCE_SECS`]).
+pub(crate) fn cloud_has_newer_files(dest: &SaveDestinations, saves: &[RemoteSaveInfoDto]) -> bool {
+    let tolerance = chrono::Duration::seconds(UNSYNCED_LOCAL_NEWER_TOLERANCE_SECS);
+    saves.iter().any(|save| {
+        let Ok(cloud_dt) = DateTime::parse_from_rfc3339(&save.last_modified)
+            .or_else(|_| DateTime::parse_from_rfc2822(&save.last_modified))
+        else {
+            return false;
+        };
+        let dest_path = dest.dir_for(&save.filename).join(&save.filename);
+        match fs::metadata(&dest_path).and_then(|m| m.modified()) {
+            Ok(local_mtime) => DateTime::<Utc>::from(local_mtime) + tolerance < cloud_dt,
+            Err(_) => true,
+        }
+    })
+}
+
 /// Comprueba si existen conflictos de descarga para un juego concreto.
 ///
 /// Obtiene la lista completa de guardados remotos, la filtra por `game_id` y
@@ -195,10 +235,7 @@ pub async fn sync_check_download_conflicts(
         .find(|g| g.id.eq_ignore_ascii_case(&game_id))
         .ok_or_else(|| format!("Juego no encontrado: {}", game_id))?;
 
-    let dest_base = match path_utils::expand_path(game.paths[0].trim()) {
-        Some(p) => PathBuf::from(p),
-        None => return Err("No se pudo expandir la ruta de destino".into()),
-    };
+    let dest = SaveDestinations::for_game(game).ok_or("No se pudo expandir la ruta de destino")?;
 
     let all = api::sync_list_remote_saves().await?;
     let saves: Vec<RemoteSaveInfoDto> = all
@@ -206,7 +243,9 @@ pub async fn sync_check_download_conflicts(
         .filter(|s| s.game_id.eq_ignore_ascii_case(&game_id))
         .collect();
 
-    let conflicts = check_conflicts_for_game(&dest_base, &saves);
+    let mut conflicts = check_conflicts_for_game(&dest, &saves);
+    attach_remote_origins(&saves, &mut conflicts).await;
+    hooks::conflict(game, &conflicts).await;
     Ok(DownloadConflictsResultDto { conflicts })
 }
 
@@ -252,8 +291,8 @@ pub async fn sync_check_download_conflicts_batch(
             }
         };
 
-        let dest_base = match path_utils::expand_path(game.paths[0].trim()) {
-            Some(p) => PathBuf::from(p),
+        let dest = match SaveDestinations::for_game(game) {
+            Some(d) => d,
             None => {
                 results.push(GameConflictsResultDto {
                     game_id,
@@ -269,7 +308,9 @@ pub async fn sync_check_download_conflicts_batch(
             .cloned()
             .collect();
 
-        let conflicts = check_conflicts_for_game(&dest_base, &saves);
+        let mut conflicts = check_conflicts_for_game(&dest, &saves);
+        attach_remote_origins(&saves, &mut conflicts).await;
+        hooks::conflict(game, &conflicts).await;
         results.push(GameConflictsResultDto { game_id, conflicts });
     }
 
@@ -335,11 +376,12 @@ pub async fn sync_check_unsynced_games() -> Result<Vec<UnsyncedGameDto>, String>
                 .max()
         });
 
-        let paths = game.paths.clone();
-        let local_files =
-            tokio::task::spawn_blocking(move || crate::utils::path_utils::list_all_files_with_mtime(&paths))
-                .await
-                .map_err(|e| format!("Error en scan local: {}", e))?;
+        let scanned = game.clone();
+        let local_files = tokio::task::spawn_blocking(move || {
+            crate::utils::path_translation::list_game_files_with_mtime(&scanned)
+        })
+        .await
+        .map_err(|e| format!("Error en scan local: {}", e))?;
 
         let mut has_unsynced = false;
 
@@ -413,12 +455,12 @@ pub async fn sync_check_unsynced_games() -> Result<Vec<UnsyncedGameDto>, String>
 ///
 /// # Arguments
 ///
-/// * `dest_base` - Directorio raíz de guardados del juego.
+/// * `dest` - Carpetas de guardados del juego.
 /// * `backup_dir` - Directorio donde almacenar la copia previa, si se desea.
 /// * `save` - Metadatos del archivo remoto a descargar.
-/// * `download_url` - URL presignada para la descarga del contenido.
+/// * `target` - Origen del contenido en el backend de almacenamiento activo.
 /// * `game_id` - Identificador del juego, usado en los eventos de progreso.
-/// * `app` - Handle de la aplicación Tauri para emitir eventos al frontend.
+/// * `observer` - Destino de los eventos de progreso.
 ///
 /// # Errors
 ///
@@ -426,14 +468,15 @@ pub async fn sync_check_unsynced_games() -> Result<Vec<UnsyncedGameDto>, String>
 /// puede crear el archivo destino después de los reintentos, o si ocurre un
 /// error de escritura durante la transferencia.
 async fn download_one_file(
-    dest_base: &std::path::Path,
+    dest: &SaveDestinations,
     backup_dir: Option<&std::path::Path>,
     save: &RemoteSaveInfoDto,
-    download_url: &str,
+    target: &TransferTarget,
     game_id: &str,
-    app: &AppHandle,
+    observer: &dyn SyncObserver,
 ) -> Result<(), String> {
-    let dest_path = dest_base.join(&save.filename);
+    let dest_dir = dest.dir_for(&save.filename);
+    let dest_path = dest_dir.join(&save.filename);
 
     if let Some(parent) = dest_path.parent() {
         let _ = fs::create_dir_all(parent);
@@ -443,7 +486,7 @@ async fn download_one_file(
     // Se usa fs::copy directamente en lugar de exists() + copy() para evitar
     // una condición de carrera entre la comprobación y la copia.
     if let Some(backup_base) = backup_dir {
-        if let Ok(rel) = dest_path.strip_prefix(dest_base) {
+        if let Ok(rel) = dest_path.strip_prefix(dest_dir) {
             let backup_path = backup_base.join(rel);
             if let Some(bp) = backup_path.parent() {
                 let _ = fs::create_dir_all(bp);
@@ -462,13 +505,12 @@ async fn download_one_file(
         }
     }
 
-    let res = DATA_CLIENT
-        .get(download_url)
-        .send()
+    let download = target
+        .open()
         .await
         .map_err(|e| format!("{}: {}", save.filename, e))?;
 
-    let total = res.content_length().or(save.size).unwrap_or(0);
+    let total = download.size.or(save.size).unwrap_or(0);
     let mut loaded: u64 = 0;
     let mut last_emit: u64 = 0;
 
@@ -477,7 +519,7 @@ async fn download_one_file(
         .map_err(|e| file_write_error_message(&save.filename, &e))?;
 
     let mut writer = BufWriter::with_capacity(WRITE_BUF_SIZE, file);
-    let mut stream = res.bytes_stream();
+    let mut stream = download.stream;
     let mut write_err: Option<String> = None;
 
     while let Some(chunk_result) = stream.next().await {
@@ -490,15 +532,12 @@ async fn download_one_file(
 
                 if should_emit {
                     last_emit = loaded;
-                    let _ = app.emit(
-                        "sync-download-progress",
-                        SyncProgressPayload {
-                            game_id: game_id.to_string(),
-                            filename: save.filename.clone(),
-                            loaded,
-                            total,
-                        },
-                    );
+                    observer.download_progress(SyncProgressPayload {
+                        game_id: game_id.to_string(),
+                        filename: save.filename.clone(),
+                        loaded,
+                        total,
+                    });
                 }
 
                 if let Err(e) = writer.write_all(&chunk).await {
@@ -519,15 +558,12 @@ async fn download_one_file(
     // Emite el 100 % si la transferencia terminó correctamente pero el último
     // chunk no lo alcanzó exactamente por el umbral de emisión.
     if write_err.is_none() && total > 0 && loaded < total {
-        let _ = app.emit(
-            "sync-download-progress",
-            SyncProgressPayload {
-                game_id: game_id.to_string(),
-                filename: save.filename.clone(),
-                loaded: total,
-                total,
-            },
-        );
+        observer.download_progress(SyncProgressPayload {
+            game_id: game_id.to_string(),
+            filename: save.filename.clone(),
+            loaded: total,
+            total,
+        });
     }
 
     if write_err.is_none() {
@@ -591,7 +627,16 @@ pub async fn sync_download_game(
     tray_state.0.syncing_inc();
     tray_state.0.update_tooltip();
 
-    let result = sync_download_game_impl(game_id.clone(), app.clone(), None).await;
+    let transfer =
+        transfers::begin(TransferKind::Download, &game_id, TransferPriority::Normal).await;
+    let result = sync_download_game_impl(
+        game_id.clone(),
+        &transfer.observer(&app),
+        transfer.token(),
+        None,
+    )
+    .await;
+    drop(transfer);
 
     tray_state.0.syncing_dec();
     tray_state.0.clone().refresh_unsynced_async();
@@ -612,7 +657,10 @@ pub async fn sync_download_game(
 /// # Arguments
 ///
 /// * `game_id` - Identificador del juego a descargar.
-/// * `app` - Handle de la aplicación para emitir eventos de progreso.
+/// * `observer` - Destino de los eventos de progreso.
+/// * `cancel` - Pausa o cancelación. Se comprueba antes de empezar cada archivo
+///   (uno a medias no se corta, para no dejar un guardado truncado); al pausar,
+///   los que no empezaron quedan registrados para reanudar la descarga.
 /// * `prefetched_saves` - Lista de guardados remotos ya obtenida, o `None`.
 ///
 /// # Errors
@@ -621,7 +669,8 @@ pub async fn sync_download_game(
 /// incompleta, si la API falla o si no se pueden obtener las URLs de descarga.
 pub(crate) async fn sync_download_game_impl(
     game_id: String,
-    app: AppHandle,
+    observer: &dyn SyncObserver,
+    cancel: &CancellationToken,
     prefetched_saves: Option<Vec<RemoteSaveInfoDto>>,
 ) -> Result<SyncResultDto, String> {
     let cfg = crate::config::load_config();
@@ -638,22 +687,9 @@ pub(crate) async fn sync_download_game_impl(
         ));
     }
 
-    let api_base = cfg
-        .api_base_url
-        .as_deref()
-        .filter(|s| !s.trim().is_empty())
-        .ok_or("Configura apiBaseUrl en Configuración")?;
-    let user_id = cfg
-        .user_id
-        .as_deref()
-        .filter(|s| !s.trim().is_empty())
-        .ok_or("Configura userId en Configuración")?;
-    let api_key = cfg.api_key.as_deref().unwrap_or("");
-
-    let dest_base = match path_utils::expand_path(game.paths[0].trim()) {
-        Some(p) => PathBuf::from(p),
-        None => return Err("No se pudo expandir la ruta de destino".into()),
-    };
+    let storage = Storage::active()?;
+
+    let dest = SaveDestinations::for_game(game).ok_or("No se pudo expandir la ruta de destino")?;
 
     // Usa la lista provista por el llamador o la descarga si es una llamada individual.
     let saves: Vec<_> = match prefetched_saves {
@@ -681,60 +717,81 @@ pub(crate) async fn sync_download_game_impl(
         return Ok(result);
     }
 
+    // Los plugins pueden cancelar la descarga o dejar archivos fuera.
+    let skip = hooks::before_download(game, &saves).await?;
+    let saves: Vec<_> = saves
+        .into_iter()
+        .filter(|s| !skip.contains(&s.filename))
+        .collect();
+    if saves.is_empty() {
+        return Ok(SyncResultDto {
+            ok_count: 0,
+            err_count: 0,
+            errors: vec!["Los plugins dejaron fuera todos los archivos del juego".into()],
+        });
+    }
+
     let items: Vec<(String, String)> = saves
         .iter()
         .map(|s| (game_id.clone(), s.key.clone()))
         .collect();
 
-    let mut download_urls = Vec::with_capacity(saves.len());
-    for chunk in items.chunks(DOWNLOAD_URLS_BATCH_SIZE) {
-        let batch = api::get_download_urls(api_base, user_id, api_key, chunk)
-            .await
-            .map_err(|e| format!("download-urls: {}", e))?;
-        download_urls.extend(batch);
-    }
-
-    if download_urls.len() != saves.len() {
-        return Err(format!(
-            "API devolvió {} URLs para {} archivos",
-            download_urls.len(),
-            saves.len()
-        ));
-    }
+    let targets = storage
+        .get_targets(storage.user_id(), &items)
+        .await
+        .map_err(|e| format!("download-urls: {}", e))?;
 
     let backup_dir = crate::config::config_dir().map(|root| {
         let ts = chrono::Utc::now().format("%Y-%m-%d_%H-%M-%S");
         root.join("backups").join(&game_id).join(ts.to_string())
     });
 
-    let results: Vec<Result<(), String>> = stream::iter(
-        saves
-            .into_iter()
-            .zip(download_urls)
-            .map(|(save, (download_url, _))| (save, download_url)),
-    )
-    .map(|(save, download_url)| {
-        let dest_base = dest_base.clone();
-        let backup_dir = backup_dir.clone();
-        let game_id = game_id.clone();
-        let app = app.clone();
-
-        async move {
-            download_one_file(
-                &dest_base,
-                backup_dir.as_deref(),
-                &save,
-                &download_url,
-                &game_id,
-                &app,
-            )
-            .await
-        }
-    })
-    .buffer_unordered(DOWNLOAD_FILE_CONCURRENCY)
-    .collect()
-    .await;
+    // `None`: el archivo no llegó a empezar porque se pausó o canceló la descarga.
+    let results: Vec<(RemoteSaveInfoDto, Option<Result<(), String>>)> =
+        stream::iter(saves.into_iter().zip(targets))
+            .map(|(save, target)| {
+                let dest = dest.clone();
+                let backup_dir = backup_dir.clone();
+                let game_id = game_id.clone();
+
+                async move {
+                    if cancel.should_stop() {
+                        return (save, None);
+                    }
+                    let r = download_one_file(
+                        &dest,
+                        backup_dir.as_deref(),
+                        &save,
+                        &target,
+                        &game_id,
+                        observer,
+                    )
+                    .await;
+                    (save, Some(r))
+                }
+            })
+            .buffer_unordered(DOWNLOAD_FILE_CONCURRENCY)
+            .collect()
+            .await;
+
+    let (done, skipped): (Vec<_>, Vec<_>) = results.into_iter().partition(|(_, r)| r.is_some());
+    if !skipped.is_empty() && cancel.is_paused() && !cancel.is_cancelled() {
+        let total: u64 = skipped.iter().filter_map(|(s, _)| s.size).sum();
+        let label = format!("{} archivos pendientes", skipped.len());
+        let record = PausedTransfer::new(
+            TransferKind::Download,
+            &game_id,
+            &label,
+            (0, total),
+            ResumePoint::Download {
+                remaining: skipped.into_iter().map(|(s, _)| s.key).collect(),
+            },
+        );
+        paused::save(&record)?;
+        observer.download_paused(&game_id, &label);
+    }
 
+    let results: Vec<Result<(), String>> = done.into_iter().filter_map(|(_, r)| r).collect();
     let ok_count = results.iter().filter(|r| r.is_ok()).count() as u32;
     let errors: Vec<String> = results.into_iter().filter_map(|r| r.err()).collect();
     let err_count = errors.len() as u32;
@@ -752,6 +809,12 @@ pub(crate) async fn sync_download_game_impl(
         result.err_count,
     );
 
+    // La carpeta solo existe si algún archivo local se copió antes de sobrescribirlo.
+    if let Some(dir) = backup_dir.as_deref().filter(|dir| dir.is_dir()) {
+        hooks::backup_created(&game.id, BackupInfo::local(dir)).await;
+    }
+    hooks::after_download(game, &result).await;
+
     if backup_dir.is_some() && result.err_count == 0 {
         let keep = cfg
             .keep_backups_per_game
@@ -770,9 +833,8 @@ pub(crate) async fn sync_download_game_impl(
 /// 2. Para los juegos restantes se comprueba si existe un backup empaquetado
 ///    reciente en la nube; si lo hay, se restaura (más eficiente para juegos
 ///    con muchos archivos). Si no, se descargan los archivos individuales.
-/// 3. Las restauraciones de backups y las descargas individuales se realizan
-///    en paralelo con concurrencias configuradas por [`RESTORE_PACKAGED_CONCURRENCY`]
-///    y [`DOWNLOAD_BATCH_CONCURRENCY`] respectivamente.
+/// 3. Cada juego es una transferencia del gestor global ([`crate::transfers`]),
+///    que limita cuántas descargas y restauraciones corren a la vez.
 /// 4. La lista de guardados individuales se obtiene una sola vez y se
 ///    distribuye entre los juegos que la necesitan, evitando N peticiones.
 ///
@@ -790,18 +852,7 @@ pub async fn sync_download_all_games(
     tray_state: State<'_, TrayState>,
 ) -> Result<Vec<GameSyncResultDto>, String> {
     let cfg = crate::config::load_config();
-
-    let api_base = cfg
-        .api_base_url
-        .as_deref()
-        .filter(|s| !s.trim().is_empty())
-        .ok_or("Configura apiBaseUrl en Configuración")?;
-    let user_id = cfg
-        .user_id
-        .as_deref()
-        .filter(|s| !s.trim().is_empty())
-        .ok_or("Configura userId en Configuración")?;
-    let api_key = cfg.api_key.as_deref().unwrap_or("");
+    let storage = Storage::active()?;
 
     tray_state.0.syncing_inc();
     tray_state.0.update_tooltip();
@@ -834,20 +885,15 @@ pub async fn sync_download_all_games(
         .map(|g| g.id.clone())
         .collect();
 
-    let api_base_owned = api_base.to_string();
-    let user_id_owned = user_id.to_string();
-
     // Consulta en paralelo si existe un backup empaquetado para cada juego.
     let backups_fetched: Vec<(String, Option<String>)> = stream::iter(to_process.clone())
         .map(|game_id| {
-            let api_base = api_base_owned.clone();
-            let user_id = user_id_owned.clone();
+            let storage = storage.clone();
             async move {
-                let list =
-                    super::full_backup::list_cloud_backups(&api_base, &user_id, api_key, &game_id)
-                        .await
-                        .ok()
-                        .filter(|l| !l.is_empty());
+                let list = super::full_backup::list_cloud_backups(&storage, &game_id)
+                    .await
+                    .ok()
+                    .filter(|l| !l.is_empty());
 
                 let backup_key = list.and_then(|mut list| {
                     list.sort_by(|a, b| b.last_modified.cmp(&a.last_modified));
@@ -871,29 +917,33 @@ pub async fn sync_download_all_games(
         }
     }
 
-    let tray_inner = tray_state.0.clone();
-
-    // Restaura backups empaquetados en paralelo.
+    // Restaura backups empaquetados en paralelo; el gestor de transferencias pone el límite.
+    let restore_count = to_restore.len();
     let restore_results: Vec<(String, Result<SyncResultDto, String>)> = stream::iter(to_restore)
         .map(|(game_id, backup_key)| {
-            let app = app.clone();
-            let tray = tray_inner.clone();
+            let app = &app;
+            let tray = &tray_state.0;
             async move {
+                let transfer =
+                    transfers::begin(TransferKind::Download, &game_id, TransferPriority::Normal)
+                        .await;
                 let r = super::full_backup::download_and_restore_full_backup_impl(
                     game_id.clone(),
                     backup_key,
-                    app,
-                    tray,
-                    false,
+                    &transfer.observer(app),
+                    transfer.token(),
                 )
                 .await;
 
                 let result = match r {
-                    Ok(()) => SyncResultDto {
-                        ok_count: 1,
-                        err_count: 0,
-                        errors: vec![],
-                    },
+                    Ok(()) => {
+                        tray.set_just_restored(&game_id);
+                        SyncResultDto {
+                            ok_count: 1,
+                            err_count: 0,
+                            errors: vec![],
+                        }
+                    }
                     Err(e) => SyncResultDto {
                         ok_count: 0,
                         err_count: 1,
@@ -903,7 +953,7 @@ pub async fn sync_download_all_games(
                 (game_id, Ok(result))
             }
         })
-        .buffer_unordered(RESTORE_PACKAGED_CONCURRENCY)
+        .buffer_unordered(restore_count.max(1))
         .collect()
         .await;
 
@@ -951,9 +1001,10 @@ pub async fn sync_download_all_games(
 
     // Descarga archivos individuales en paralelo, distribuyendo la lista remota
     // ya obtenida para evitar una petición extra por juego.
+    let download_count = to_download_normal.len();
     let completed: Vec<(String, Result<SyncResultDto, String>)> = stream::iter(to_download_normal)
         .map(|game_id| {
-            let app = app.clone();
+            let app = &app;
             let game_saves: Vec<_> = all_saves
                 .iter()
                 .filter(|s| s.game_id.eq_ignore_ascii_case(&game_id))
@@ -961,11 +1012,20 @@ pub async fn sync_download_all_games(
                 .collect();
 
             async move {
-                let r = sync_download_game_impl(game_id.clone(), app, Some(game_saves)).await;
+                let transfer =
+                    transfers::begin(TransferKind::Download, &game_id, TransferPriority::Normal)
+                        .await;
+                let r = sync_download_game_impl(
+                    game_id.clone(),
+                    &transfer.observer(app),
+                    transfer.token(),
+                    Some(game_saves),
+                )
+                .await;
                 (game_id, r)
             }
         })
-        .buffer_unordered(DOWNLOAD_BATCH_CONCURRENCY)
+        .buffer_unordered(download_count.max(1))
         .collect()
         .await;
 
@@ -996,3 +1056,56 @@ pub async fn sync_download_all_games(
 
     Ok(results)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::sync::observer::tests::RecordingObserver;
+    use crate::commands::sync::upload;
+    use crate::testing::{self, mock_api::MockApi};
+
+    #[tokio::test]
+    async fn upload_then_download_round_trips_a_game() {
+        let _config = testing::CONFIG_LOCK.lock().await;
+        let api = MockApi::start().await;
+        let saves = tempfile::tempdir().unwrap();
+        std::fs::create_dir(saves.path().join("perfil")).unwrap();
+        std::fs::write(saves.path().join("slot1.sav"), b"nivel=7").unwrap();
+        std::fs::write(saves.path().join("perfil/opciones.cfg"), b"volumen=3").unwrap();
+        testing::use_mock_api(
+            &api,
+            "user1",
+            serde_json::json!([{ "id": "savecloud-roundtrip", "paths": [saves.path()] }]),
+        );
+
+        let uploaded = upload::sync_upload_game_impl(
+            "savecloud-roundtrip".into(),
+            &RecordingObserver::default(),
+            &CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+        assert_eq!((uploaded.ok_count, uploaded.err_count), (2, 0));
+
+        std::fs::remove_file(saves.path().join("slot1.sav")).unwrap();
+        std::fs::write(saves.path().join("perfil/opciones.cfg"), b"roto").unwrap();
+
+        let downloaded = sync_download_game_impl(
+            "savecloud-roundtrip".into(),
+            &RecordingObserver::default(),
+            &CancellationToken::new(),
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!((downloaded.ok_count, downloaded.err_count), (2, 0));
+        assert_eq!(
+            std::fs::read(saves.path().join("slot1.sav")).unwrap(),
+            b"nivel=7"
+        );
+        assert_eq!(
+            std::fs::read(saves.path().join("perfil/opciones.cfg")).unwrap(),
+            b"volumen=3"
+        );
+    }
+}
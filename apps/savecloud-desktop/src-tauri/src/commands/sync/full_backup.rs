@@ -1,4 +1,4 @@
-//! Módulo de backup completo de juegos mediante archivo `.tar` en S3.
+//! Módulo de backup completo de juegos mediante archivo `.tar` en el almacenamiento remoto.
 //!
 //! Agrupa todos los archivos de un juego en un único archivo `.tar`
 //! para optimizar la transferencia hacia y desde almacenamiento remoto.
@@ -6,8 +6,9 @@
 //! Flujo de operación:
 //!
 //! 1. Empaquetado del directorio del juego en un archivo `.tar`.
-//! 2. Subida del archivo mediante multipart upload.
-//! 3. Identificación y gestión del backup mediante su clave en S3.
+//! 2. Subida del archivo mediante multipart upload (o en un único `PUT` si el
+//!    backend no admite subidas por partes).
+//! 3. Identificación y gestión del backup mediante su clave en el backend.
 //! 4. Descarga y extracción del contenido en el sistema local.
 //!
 //! Este enfoque reduce la sobrecarga asociada a la transferencia de
@@ -21,79 +22,76 @@ use std::path::{Path, PathBuf};
 use tokio::io::AsyncWriteExt;
 use tokio_util::io::SyncIoBridge;
 
-use super::api;
+use super::cancellation::CancellationToken;
 use super::models::SyncProgressPayload;
-use super::multipart_upload;
+use super::multipart_upload::{self, PAUSED_ERR_MSG};
+use super::observer::SyncObserver;
+use super::paused::{self, PausedTransfer, ResumePoint};
 use super::streaming;
+use super::streaming::multipart::StreamCheckpoint;
+use crate::commands::logs::sync_logger;
 use crate::config;
-use crate::network::DATA_CLIENT;
+use crate::devices::provenance::{self, UploadOrigin};
+use crate::plugins::hooks::{self, BackupInfo};
+use crate::storage::{sibling_key, Storage, StorageBackend, BACKUPS_PREFIX};
+use crate::transfers::{self, TransferKind, TransferPriority};
 use crate::tray::tray_state::TrayState;
+use crate::utils::path_translation::{self, SaveDestinations};
+use crate::utils::path_utils;
 use tauri::{AppHandle, Emitter, State};
 
-/// Prefijo S3 para backups (key = userId/gameId/backups/<filename>.tar).
-const BACKUPS_PREFIX: &str = "backups/";
-
-struct ApiContext {
-    base_url: String,
-    user_id: String,
-    api_key: String,
-}
-
-fn get_api_context() -> Result<ApiContext, String> {
-    let cfg = config::load_config();
-    let base_url = cfg
-        .api_base_url
-        .as_deref()
-        .filter(|s| !s.trim().is_empty())
-        .ok_or("Configura apiBaseUrl en Configuración")?
-        .to_string();
-    let user_id = cfg
-        .user_id
-        .as_deref()
-        .filter(|s| !s.trim().is_empty())
-        .ok_or("Configura userId en Configuración")?
-        .to_string();
-    let api_key = cfg.api_key.unwrap_or_default();
-
-    Ok(ApiContext {
-        base_url,
-        user_id,
-        api_key,
-    })
-}
-
-/// Crea un archivo .tar con el contenido de `source_dir` y lo escribe en `dest_path`.
+/// Crea un archivo .tar con el contenido de `sources` y lo escribe en `dest_path`.
 /// No comprime (solo agrupa); muchos juegos ya están comprimidos.
-fn create_tar_archive(source_dir: &Path, dest_path: &Path) -> Result<u64, String> {
+fn create_tar_archive(sources: &[PathBuf], dest_path: &Path) -> Result<u64, String> {
     let file = fs::File::create(dest_path).map_err(|e| e.to_string())?;
     let writer = BufWriter::new(file);
     let mut builder = tar::Builder::new(writer);
-    builder
-        .append_dir_all(".", source_dir)
-        .map_err(|e| e.to_string())?;
+    for entry in streaming::tar_stream::source_entries(sources)? {
+        builder
+            .append_path_with_name(&entry.path, &entry.relative)
+            .map_err(|e| e.to_string())?;
+    }
     builder.finish().map_err(|e| e.to_string())?;
     fs::metadata(dest_path)
         .map(|m| m.len())
         .map_err(|e| e.to_string())
 }
 
+/// Carpeta principal de un juego y lo que empaqueta su backup completo.
+///
+/// Los juegos con comodines (`saves/Juego.*`, típicos de emuladores) comparten carpeta
+/// con otros: se empaquetan solo sus archivos, y al restaurar cada uno vuelve a su
+/// carpeta con [`SaveDestinations::dir_for`]. El resto empaqueta su carpeta principal.
+fn backup_sources(game: &config::ConfiguredGame) -> Option<(PathBuf, Vec<PathBuf>)> {
+    let source_dir = path_translation::resolve_game_save_dir(game)?;
+    let has_globs = game
+        .paths
+        .iter()
+        .any(|raw| path_utils::split_glob(raw).is_some());
+    let sources = if has_globs {
+        path_translation::resolve_game_paths(game)
+    } else {
+        vec![source_dir.clone()]
+    };
+    Some((source_dir, sources))
+}
+
 /// Guard que elimina un archivo temporal al salir del scope (éxito o error).
-struct TempFileGuard(PathBuf);
+struct TempFileGuard(Option<PathBuf>);
+impl TempFileGuard {
+    /// Conserva el archivo: la subida pausada lo necesita para reanudarse.
+    fn keep(mut self) {
+        self.0 = None;
+    }
+}
 impl Drop for TempFileGuard {
     fn drop(&mut self) {
-        let _ = fs::remove_file(&self.0);
+        if let Some(path) = self.0.take() {
+            let _ = fs::remove_file(path);
+        }
     }
 }
 
-#[derive(serde::Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct BackupDto {
-    key: String,
-    last_modified: String,
-    size: Option<u64>,
-    filename: String,
-}
-
 #[derive(Clone, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CloudBackupInfo {
@@ -101,43 +99,29 @@ pub struct CloudBackupInfo {
     pub last_modified: String,
     pub size: Option<u64>,
     pub filename: String,
+    /// Dispositivo que subió el backup, si quedó registrado.
+    pub uploaded_from: Option<UploadOrigin>,
 }
 
-/// Lista los backups en la nube para un juego.
+/// Lista los backups en la nube para un juego, del más reciente al más antiguo.
 pub async fn list_cloud_backups(
-    api_base: &str,
-    user_id: &str,
-    api_key: &str,
+    storage: &Storage,
     game_id: &str,
 ) -> Result<Vec<CloudBackupInfo>, String> {
-    let path = format!("/backups?gameId={}", urlencoding::encode(game_id));
-
-    let res = api::api_request(api_base, user_id, api_key, "GET", &path, None)
+    let backups = storage
+        .list_backups(game_id)
         .await
         .map_err(|e| format!("GET /backups: {}", e))?;
-
-    if !res.status().is_success() {
-        return Err(format!(
-            "API backups: {} {}",
-            res.status(),
-            res.text().await.unwrap_or_default()
-        ));
-    }
-
-    #[derive(serde::Deserialize)]
-    struct Response {
-        backups: Vec<BackupDto>,
-    }
-
-    let body: Response = res.json().await.map_err(|e| e.to_string())?;
-    Ok(body
-        .backups
+    // Sin procedencia (backups antiguos o error de lectura) el listado sigue igual.
+    let provenance = provenance::load(storage, game_id).await.unwrap_or_default();
+    Ok(backups
         .into_iter()
         .map(|b| CloudBackupInfo {
+            uploaded_from: provenance.origin_of(&b.filename()).cloned(),
+            filename: b.key.rsplit('/').next().unwrap_or_default().to_string(),
             key: b.key,
             last_modified: b.last_modified,
             size: b.size,
-            filename: b.filename,
         })
         .collect())
 }
@@ -159,9 +143,9 @@ const FULL_BACKUP_DOWNLOAD_EMIT_BYTES: u64 = 256 * 1024;
 ///
 /// * `game_id` - El identificador único del juego.
 /// * `backup_key` - La clave o ruta del objeto en el almacenamiento remoto.
-/// * `app` - El manejador de la aplicación Tauri, utilizado para emitir eventos de progreso.
-/// * `tray_state` - Referencia atómica al estado de la bandeja del sistema.
-/// * `emit_done` - Bandera que indica si se debe emitir el evento `sync-download-done` al finalizar.
+/// * `observer` - Destino de los eventos de progreso.
+/// * `cancel` - Pausa o cancelación entre fragmentos. Al pausar se registra la
+///   restauración para repetirla entera y se devuelve [`PAUSED_ERR_MSG`].
 ///
 /// # Errors
 ///
@@ -170,11 +154,10 @@ const FULL_BACKUP_DOWNLOAD_EMIT_BYTES: u64 = 256 * 1024;
 pub async fn download_and_restore_full_backup_impl(
     game_id: String,
     backup_key: String,
-    app: AppHandle,
-    tray_state: std::sync::Arc<crate::tray::tray_state::TrayStateInner>,
-    emit_done: bool,
+    observer: &dyn SyncObserver,
+    cancel: &CancellationToken,
 ) -> Result<(), String> {
-    let ctx = get_api_context()?;
+    let storage = Storage::active()?;
     let cfg = config::load_config();
 
     let game = cfg
@@ -183,60 +166,35 @@ pub async fn download_and_restore_full_backup_impl(
         .find(|g| g.id.eq_ignore_ascii_case(&game_id))
         .ok_or_else(|| format!("Juego no encontrado: {}", game_id))?;
 
-    let dest_dir =
-        crate::utils::path_utils::expand_path(game.paths.first().map(|s| s.as_str()).unwrap_or(""))
-            .ok_or("No se pudo expandir la ruta del juego")?;
-    let dest_dir = PathBuf::from(dest_dir);
-
-    // Resolución de URL pre-firmada
-    let body = serde_json::json!({ "gameId": game_id, "key": backup_key });
-    let res = api::api_request(
-        &ctx.base_url,
-        &ctx.user_id,
-        &ctx.api_key,
-        "POST",
-        "/download-url",
-        Some(body.to_string().as_bytes()),
-    )
-    .await
-    .map_err(|e| format!("download-url: {}", e))?;
-
-    if !res.status().is_success() {
-        return Err(format!(
-            "API download-url: {} {}",
-            res.status(),
-            res.text().await.unwrap_or_default()
-        ));
-    }
+    let dest = SaveDestinations::for_game(game).ok_or("No se pudo expandir la ruta del juego")?;
 
-    let json: serde_json::Value = res.json().await.map_err(|e| e.to_string())?;
-    let download_url = json
-        .get("downloadUrl")
-        .and_then(|v| v.as_str())
-        .ok_or("API no devolvió downloadUrl")?;
-
-    let tar_name = backup_key.rsplit('/').next().unwrap_or("backup.tar");
-
-    let res = DATA_CLIENT
-        .get(download_url)
-        .send()
+    // Resolución del origen del backup en el backend activo
+    let target = storage
+        .get_targets(storage.user_id(), &[(game_id.clone(), backup_key.clone())])
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| format!("download-url: {}", e))?
+        .pop()
+        .ok_or("El backend no devolvió el origen del backup")?;
+
+    let tar_name = backup_key
+        .rsplit('/')
+        .next()
+        .unwrap_or("backup.tar")
+        .to_string();
 
-    if !res.status().is_success() {
-        return Err(format!("Descarga del backup: HTTP {}", res.status()));
-    }
+    let download = target
+        .open()
+        .await
+        .map_err(|e| format!("Descarga del backup: {}", e))?;
 
-    let total = res.content_length().unwrap_or(0);
-    let mut stream = res.bytes_stream();
+    let total = download.size.unwrap_or(0);
+    let mut stream = download.stream;
 
     // Arquitectura de Streaming: Tubería (Pipe) en memoria RAM con capacidad de 5MB.
     // Proporciona retroalimentación de presión (backpressure): si el disco extrae
     // más lento de lo que la red descarga, la red se pausará temporalmente.
     let (mut tx, rx) = tokio::io::duplex(5 * 1024 * 1024);
 
-    let dest_dir_clone = dest_dir.clone();
-
     // Hilo dedicado a la descompresión. Se ejecuta en paralelo a la descarga.
     let extract_task = tokio::task::spawn_blocking(move || {
         // SyncIoBridge convierte el canal asíncrono 'rx' en un lector implementando std::io::Read
@@ -244,9 +202,24 @@ pub async fn download_and_restore_full_backup_impl(
         let sync_reader = SyncIoBridge::new(rx);
         let mut archive = tar::Archive::new(sync_reader);
 
-        archive
-            .unpack(&dest_dir_clone)
-            .map_err(|e| format!("Fallo en extracción: {}", e))
+        let entries = archive
+            .entries()
+            .map_err(|e| format!("Fallo en extracción: {}", e))?;
+        for entry in entries {
+            let mut entry = entry.map_err(|e| format!("Fallo en extracción: {}", e))?;
+            let path = entry
+                .path()
+                .map_err(|e| format!("Fallo en extracción: {}", e))?
+                .to_string_lossy()
+                .into_owned();
+            // Los archivos sueltos de un juego con comodines vuelven a la carpeta de su
+            // patrón; el resto, a la carpeta principal.
+            let dest_dir = dest.dir_for(path.trim_start_matches("./"));
+            entry
+                .unpack_in(dest_dir)
+                .map_err(|e| format!("Fallo en extracción: {}", e))?;
+        }
+        Ok::<(), String>(())
     });
 
     let mut loaded: u64 = 0;
@@ -254,6 +227,25 @@ pub async fn download_and_restore_full_backup_impl(
 
     // Consumo del flujo de red
     while let Some(item) = stream.next().await {
+        if cancel.should_stop() {
+            // Cerrar la tubería corta la extracción; el archivo a medias se reescribe al repetirla.
+            drop(tx);
+            let _ = extract_task.await;
+            if cancel.is_cancelled() {
+                return Err("Restauración cancelada".to_string());
+            }
+            let record = PausedTransfer::new(
+                TransferKind::Download,
+                &game_id,
+                &tar_name,
+                (loaded, total),
+                ResumePoint::Restore { backup_key },
+            );
+            paused::save(&record)?;
+            observer.download_paused(&game_id, &tar_name);
+            return Err(PAUSED_ERR_MSG.to_string());
+        }
+
         let chunk = item.map_err(|e| e.to_string())?;
         let n = chunk.len() as u64;
         loaded += n;
@@ -265,28 +257,22 @@ pub async fn download_and_restore_full_backup_impl(
 
         if loaded - last_emit >= FULL_BACKUP_DOWNLOAD_EMIT_BYTES || (total > 0 && loaded >= total) {
             last_emit = loaded;
-            let _ = app.emit(
-                "sync-download-progress",
-                SyncProgressPayload {
-                    game_id: game_id.clone(),
-                    filename: tar_name.to_string(),
-                    loaded,
-                    total,
-                },
-            );
+            observer.download_progress(SyncProgressPayload {
+                game_id: game_id.clone(),
+                filename: tar_name.clone(),
+                loaded,
+                total,
+            });
         }
     }
 
     if total > 0 && loaded < total {
-        let _ = app.emit(
-            "sync-download-progress",
-            SyncProgressPayload {
-                game_id: game_id.clone(),
-                filename: tar_name.to_string(),
-                loaded: total,
-                total,
-            },
-        );
+        observer.download_progress(SyncProgressPayload {
+            game_id: game_id.clone(),
+            filename: tar_name.clone(),
+            loaded: total,
+            total,
+        });
     }
 
     // Clausura del transmisor. Esto inyecta una señal EOF (End Of File) en el receptor,
@@ -298,20 +284,50 @@ pub async fn download_and_restore_full_backup_impl(
         .await
         .map_err(|e| format!("Pánico en hilo de descompresión: {}", e))??;
 
-    tray_state.set_just_restored(&game_id);
-    if emit_done {
-        let _ = app.emit("sync-download-done", ());
-    }
-
     Ok(())
 }
+
 #[tauri::command]
 pub async fn create_and_upload_full_backup(
     game_id: String,
     app: AppHandle,
     tray_state: State<'_, TrayState>,
 ) -> Result<String, String> {
-    let ctx = get_api_context()?;
+    tray_state.0.syncing_inc();
+    tray_state.0.update_tooltip();
+
+    let transfer =
+        transfers::begin(TransferKind::FullBackup, &game_id, TransferPriority::Normal).await;
+    let result =
+        create_and_upload_full_backup_impl(game_id, &transfer.observer(&app), transfer.token())
+            .await;
+    drop(transfer);
+
+    tray_state.0.syncing_dec();
+    tray_state.0.update_tooltip();
+
+    let _ = app.emit("full-backup-done", ());
+
+    if result.is_ok() {
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        let _ = app.emit("sync-upload-done", ());
+    }
+
+    result
+}
+
+/// Empaqueta la carpeta del juego en un `.tar` y lo sube a `backups/`; devuelve el nombre relativo.
+///
+/// Según la configuración el tar se sube en streaming mientras se genera o se crea
+/// antes en un archivo temporal. `cancel` permite pausar o cancelar entre partes; al
+/// pausar queda registrada una transferencia pausada y se devuelve [`PAUSED_ERR_MSG`].
+pub(crate) async fn create_and_upload_full_backup_impl(
+    game_id: String,
+    observer: &dyn SyncObserver,
+    cancel: &CancellationToken,
+) -> Result<String, String> {
+    let storage = Storage::active()?;
+    crate::devices::registry::check_in(&storage).await?;
     let cfg = config::load_config();
 
     let game = cfg
@@ -320,151 +336,223 @@ pub async fn create_and_upload_full_backup(
         .find(|g| g.id.eq_ignore_ascii_case(&game_id))
         .ok_or_else(|| format!("Juego no encontrado: {}", game_id))?;
 
-    let raw_path = game.paths.first().map(|s| s.as_str()).unwrap_or("");
-    let source_dir =
-        crate::utils::path_utils::expand_path(raw_path).ok_or("No se pudo expandir la ruta")?;
-    let source_dir = PathBuf::from(&source_dir);
+    let (source_dir, sources) = backup_sources(game).ok_or("No se pudo expandir la ruta")?;
 
     if !source_dir.exists() || !source_dir.is_dir() {
         return Err("La carpeta del juego no existe".to_string());
     }
 
-    let source_dir_for_size = source_dir.clone();
-    let estimated_total = tokio::task::spawn_blocking(move || -> u64 {
-        fn dir_size(path: &Path) -> u64 {
-            let mut total = 0u64;
-            let Ok(meta) = std::fs::metadata(path) else {
-                return 0;
-            };
-            if meta.is_file() {
-                return meta.len();
-            }
-
-            let Ok(read_dir) = std::fs::read_dir(path) else {
-                return 0;
-            };
-            for entry in read_dir.flatten() {
-                total += dir_size(&entry.path());
-            }
-            total
-        }
-        dir_size(&source_dir_for_size)
-    })
-    .await
-    .unwrap_or(0);
+    let sources_for_size = sources.clone();
+    let estimated_total =
+        tokio::task::spawn_blocking(move || streaming::tar_stream::source_size(&sources_for_size))
+            .await
+            .unwrap_or(0);
 
     let temp_dir = std::env::temp_dir();
     let filename = format!("{}.tar", chrono::Utc::now().format("%Y-%m-%d_%H-%M-%S"));
     let tar_path = temp_dir.join(&filename);
     let relative_filename = format!("{}{}", BACKUPS_PREFIX, filename);
 
-    tray_state.0.reset_upload_cancel();
-    tray_state.0.reset_upload_pause();
-
-    let _ = app.emit(
-        "sync-upload-progress",
-        SyncProgressPayload {
-            game_id: game_id.clone(),
-            filename: "Empaquetando…".to_string(),
-            loaded: 0,
-            total: 1,
-        },
-    );
+    observer.upload_progress(SyncProgressPayload {
+        game_id: game_id.clone(),
+        filename: "Empaquetando…".to_string(),
+        loaded: 0,
+        total: 1,
+    });
 
-    let use_streaming = cfg.full_backup_streaming.unwrap_or(false);
+    // El streaming sube el tar por partes a medida que se genera, así que solo es
+    // posible con backends que admiten multipart.
+    let use_streaming = cfg.full_backup_streaming.unwrap_or(false) && storage.supports_multipart();
     let dry_run = cfg.full_backup_streaming_dry_run.unwrap_or(false);
 
-    tray_state.0.syncing_inc();
-    tray_state.0.update_tooltip();
-
     let result = if use_streaming && dry_run {
         let strategy = streaming::upload_strategy::UploadStrategy::for_file(estimated_total);
 
         let (rx, tar_handle) =
-            streaming::tar_stream::spawn_tar_stream(source_dir, strategy.tar_channel_capacity);
+            streaming::tar_stream::spawn_tar_stream(sources, strategy.tar_channel_capacity, 0);
         let upload_res = streaming::multipart::upload_tar_stream_multipart_dry_run(
             rx,
             &game_id,
             &relative_filename,
             estimated_total,
-            app.clone(),
-            Some(tray_state.0.clone()),
+            observer,
+            cancel,
         )
         .await;
         let _ = tar_handle.await;
         upload_res
     } else if use_streaming {
-        let strategy = streaming::upload_strategy::UploadStrategy::for_file(estimated_total);
-
-        let (rx, tar_handle) =
-            streaming::tar_stream::spawn_tar_stream(source_dir, strategy.tar_channel_capacity);
-        let upload_res = streaming::multipart::upload_tar_stream_multipart(
-            rx,
-            &game_id,
-            &relative_filename,
-            estimated_total,
-            &ctx.base_url,
-            &ctx.user_id,
-            &ctx.api_key,
-            app.clone(),
-            Some(tray_state.0.clone()),
-        )
-        .await;
-        let _ = tar_handle.await;
-        upload_res
-    } else {
-        let source_dir_clone = source_dir.clone();
-        let tar_path_clone = tar_path.clone();
-
-        let size = tokio::task::spawn_blocking(move || {
-            create_tar_archive(&source_dir_clone, &tar_path_clone)
+        let fingerprint_sources = sources.clone();
+        let fingerprint = tokio::task::spawn_blocking(move || {
+            streaming::tar_stream::source_fingerprint(&fingerprint_sources)
         })
         .await
         .map_err(|e| e.to_string())??;
+        let source = source_dir.to_string_lossy().to_string();
+
+        // Si un backup anterior de este mismo contenido quedó a medias (pausa, error de
+        // red o cierre de la app), se completa esa subida en vez de empezar otra.
+        let (id, checkpoint) = match paused::take_streaming(&game_id, &source, &fingerprint).await {
+            Some((record, checkpoint)) => (record.id, checkpoint),
+            None => {
+                let checkpoint = streaming::multipart::init_stream_checkpoint(
+                    &storage,
+                    &game_id,
+                    &relative_filename,
+                    &source,
+                    &sources,
+                    fingerprint,
+                    estimated_total,
+                )
+                .await?;
+                (paused::new_id(), checkpoint)
+            }
+        };
+        return run_streaming_backup(&id, &game_id, checkpoint, &storage, observer, cancel).await;
+    } else {
+        let tar_path_clone = tar_path.clone();
 
-        let _temp_guard = TempFileGuard(tar_path.clone());
+        let size =
+            tokio::task::spawn_blocking(move || create_tar_archive(&sources, &tar_path_clone))
+                .await
+                .map_err(|e| e.to_string())??;
 
-        let _ = app.emit(
-            "sync-upload-progress",
-            SyncProgressPayload {
-                game_id: game_id.clone(),
-                filename: relative_filename.clone(),
-                loaded: 0,
-                total: size,
-            },
-        );
+        let temp_guard = TempFileGuard(Some(tar_path.clone()));
 
-        multipart_upload::upload_one_file_multipart(
+        observer.upload_progress(SyncProgressPayload {
+            game_id: game_id.clone(),
+            filename: relative_filename.clone(),
+            loaded: 0,
+            total: size,
+        });
+
+        let upload_res = multipart_upload::upload_one_file_multipart(
             &tar_path,
             &relative_filename,
             size,
             &game_id,
-            &ctx.base_url,
-            &ctx.user_id,
-            &ctx.api_key,
-            app.clone(),
-            Some(tray_state.0.clone()),
+            TransferKind::FullBackup,
+            &storage,
+            observer,
+            cancel,
         )
-        .await
+        .await;
+        if matches!(&upload_res, Err(e) if e == PAUSED_ERR_MSG) {
+            temp_guard.keep();
+        }
+        upload_res
     };
 
-    tray_state.0.syncing_dec();
-    tray_state.0.update_tooltip();
+    result?;
+    // La prueba en seco no deja nada en la nube: ni procedencia ni evento de backup.
+    if !(use_streaming && dry_run) {
+        provenance::record_upload(&storage, &game_id, std::slice::from_ref(&relative_filename))
+            .await;
+        hooks::backup_created(&game_id, BackupInfo::cloud(&relative_filename)).await;
+    }
+    Ok(relative_filename)
+}
 
-    let _ = app.emit("full-backup-done", ());
+/// Sube (o continúa subiendo) un backup en streaming desde su checkpoint.
+///
+/// El checkpoint se guarda como registro `id` al empezar y tras cada parte, así que
+/// sobrevive a un cierre inesperado. Se borra al completar o cancelar; al pausar o
+/// ante un error se conserva para reanudarlo. Devuelve el nombre relativo del backup.
+async fn run_streaming_backup(
+    id: &str,
+    game_id: &str,
+    mut checkpoint: StreamCheckpoint,
+    storage: &Storage,
+    observer: &dyn SyncObserver,
+    cancel: &CancellationToken,
+) -> Result<String, String> {
+    let _active = paused::mark_active(id);
+    let persist = |checkpoint: &StreamCheckpoint| {
+        if let Err(e) = paused::save(&PausedTransfer::streaming(id, game_id, checkpoint)) {
+            sync_logger::log_error("streaming_checkpoint_save", game_id, &e);
+        }
+    };
+    persist(&checkpoint);
 
-    if result.is_ok() {
-        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
-        let _ = app.emit("sync-upload-done", ());
+    let strategy = streaming::upload_strategy::UploadStrategy::for_file(checkpoint.estimated_total);
+    let (rx, tar_handle) = streaming::tar_stream::spawn_tar_stream(
+        checkpoint.sources(),
+        strategy.tar_channel_capacity,
+        checkpoint.resume_offset(),
+    );
+    let result = streaming::multipart::upload_tar_stream_multipart(
+        rx,
+        game_id,
+        &mut checkpoint,
+        storage,
+        observer,
+        cancel,
+        &persist,
+    )
+    .await;
+    let _ = tar_handle.await;
+
+    match &result {
+        Err(e) if e == PAUSED_ERR_MSG => {
+            observer.upload_paused(game_id, &checkpoint.relative_filename)
+        }
+        Err(_) if !cancel.is_cancelled() => {}
+        _ => paused::remove(id),
     }
+    result?;
+    provenance::record_upload(
+        storage,
+        game_id,
+        std::slice::from_ref(&checkpoint.relative_filename),
+    )
+    .await;
+    hooks::backup_created(game_id, BackupInfo::cloud(&checkpoint.relative_filename)).await;
+    Ok(checkpoint.relative_filename)
+}
 
-    result.map(|_| relative_filename)
+/// Reanuda un backup en streaming pausado o interrumpido.
+///
+/// Antes de regenerar el TAR comprueba que la carpeta del juego no ha cambiado; si
+/// cambió, las partes subidas ya no sirven: se descarta la subida y se hace un backup nuevo.
+pub(crate) async fn resume_streaming_backup(
+    record: PausedTransfer,
+    observer: &dyn SyncObserver,
+    cancel: &CancellationToken,
+) -> Result<String, String> {
+    let ResumePoint::StreamingBackup(checkpoint) = record.resume.clone() else {
+        return Err("La transferencia no es un backup en streaming".to_string());
+    };
+    let storage = Storage::active()?;
+
+    let sources = checkpoint.sources();
+    let fingerprint =
+        tokio::task::spawn_blocking(move || streaming::tar_stream::source_fingerprint(&sources))
+            .await
+            .map_err(|e| e.to_string())?;
+    if fingerprint.as_deref() != Ok(checkpoint.source_fingerprint.as_str()) {
+        sync_logger::log_operation(
+            "streaming_checkpoint_stale",
+            &format!("gameId={} key={}", record.game_id, checkpoint.key),
+        );
+        paused::discard(&record).await;
+        return create_and_upload_full_backup_impl(record.game_id, observer, cancel).await;
+    }
+
+    run_streaming_backup(
+        &record.id,
+        &record.game_id,
+        checkpoint,
+        &storage,
+        observer,
+        cancel,
+    )
+    .await
 }
 
 #[tauri::command]
 pub async fn list_full_backups(game_id: String) -> Result<Vec<CloudBackupInfo>, String> {
-    let ctx = get_api_context()?;
-    list_cloud_backups(&ctx.base_url, &ctx.user_id, &ctx.api_key, &game_id).await
+    let storage = Storage::active()?;
+    list_cloud_backups(&storage, &game_id).await
 }
 
 #[tauri::command]
@@ -481,20 +569,18 @@ pub async fn list_full_backups_batch(
         return Ok(std::collections::HashMap::new());
     }
 
-    let ctx = get_api_context()?;
+    let storage = Storage::active()?;
     let empty: Vec<CloudBackupInfo> = Vec::new();
 
     let futures: Vec<_> = game_ids
         .iter()
         .map(|game_id| {
             let game_id = game_id.clone();
-            let api_base = ctx.base_url.clone();
-            let user_id = ctx.user_id.clone();
-            let api_key = ctx.api_key.clone();
+            let storage = storage.clone();
             let fallback = empty.clone();
 
             async move {
-                let result = list_cloud_backups(&api_base, &user_id, &api_key, &game_id).await;
+                let result = list_cloud_backups(&storage, &game_id).await;
                 (game_id, result.unwrap_or(fallback))
             }
             .boxed()
@@ -512,34 +598,28 @@ pub async fn download_and_restore_full_backup(
     app: AppHandle,
     tray_state: State<'_, TrayState>,
 ) -> Result<(), String> {
-    download_and_restore_full_backup_impl(game_id, backup_key, app, tray_state.0.clone(), true)
-        .await
+    let transfer =
+        transfers::begin(TransferKind::Download, &game_id, TransferPriority::Normal).await;
+    download_and_restore_full_backup_impl(
+        game_id.clone(),
+        backup_key,
+        &transfer.observer(&app),
+        transfer.token(),
+    )
+    .await?;
+    drop(transfer);
+    tray_state.0.set_just_restored(&game_id);
+    let _ = app.emit("sync-download-done", ());
+    Ok(())
 }
 
 #[tauri::command]
 pub async fn delete_cloud_backup(game_id: String, backup_key: String) -> Result<(), String> {
-    let ctx = get_api_context()?;
-    let body = serde_json::json!({ "gameId": game_id, "key": backup_key });
-
-    let res = api::api_request(
-        &ctx.base_url,
-        &ctx.user_id,
-        &ctx.api_key,
-        "DELETE",
-        "/backup",
-        Some(body.to_string().as_bytes()),
-    )
-    .await
-    .map_err(|e| format!("delete backup: {}", e))?;
-
-    if !res.status().is_success() && res.status() != reqwest::StatusCode::NO_CONTENT {
-        return Err(format!(
-            "API delete backup: {} {}",
-            res.status(),
-            res.text().await.unwrap_or_default()
-        ));
-    }
-    Ok(())
+    let storage = Storage::active()?;
+    storage
+        .delete(&game_id, &backup_key)
+        .await
+        .map_err(|e| format!("delete backup: {}", e))
 }
 
 #[tauri::command]
@@ -548,7 +628,7 @@ pub async fn rename_cloud_backup(
     backup_key: String,
     new_filename: String,
 ) -> Result<(), String> {
-    let ctx = get_api_context()?;
+    let storage = Storage::active()?;
     let new_filename = new_filename.trim();
 
     if new_filename.is_empty() || !new_filename.ends_with(".tar") {
@@ -558,29 +638,86 @@ pub async fn rename_cloud_backup(
         return Err("El nombre no puede contener rutas.".to_string());
     }
 
-    let body = serde_json::json!({
-        "gameId": game_id,
-        "key": backup_key,
-        "newFilename": new_filename
-    });
+    storage
+        .rename(
+            &game_id,
+            &backup_key,
+            &sibling_key(&backup_key, new_filename),
+        )
+        .await
+        .map_err(|e| format!("rename backup: {}", e))
+}
 
-    let res = api::api_request(
-        &ctx.base_url,
-        &ctx.user_id,
-        &ctx.api_key,
-        "PATCH",
-        "/backup",
-        Some(body.to_string().as_bytes()),
-    )
-    .await
-    .map_err(|e| format!("rename backup: {}", e))?;
-
-    if !res.status().is_success() && res.status() != reqwest::StatusCode::NO_CONTENT {
-        return Err(format!(
-            "API rename backup: {} {}",
-            res.status(),
-            res.text().await.unwrap_or_default()
-        ));
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::sync::observer::tests::RecordingObserver;
+    use crate::testing::{self, mock_api::MockApi};
+
+    /// Un juego de emulador con comodines: el backup solo lleva sus archivos y al
+    /// restaurarlo cada uno vuelve a su carpeta.
+    #[tokio::test]
+    async fn full_backup_of_a_glob_game_round_trips_only_its_files() {
+        let _config = testing::CONFIG_LOCK.lock().await;
+        let api = MockApi::start().await;
+        let emu = tempfile::tempdir().unwrap();
+        let (saves, states) = (emu.path().join("saves"), emu.path().join("states"));
+        std::fs::create_dir_all(&saves).unwrap();
+        std::fs::create_dir_all(&states).unwrap();
+        std::fs::write(saves.join("Juego.srm"), b"partida").unwrap();
+        std::fs::write(states.join("Juego.state1"), b"estado").unwrap();
+        std::fs::write(saves.join("Otro.srm"), b"de otro juego").unwrap();
+        testing::use_mock_api(
+            &api,
+            "user1",
+            serde_json::json!([{
+                "id": "savecloud-backup",
+                "paths": [saves.join("Juego.*"), states.join("Juego.state*")],
+            }]),
+        );
+
+        let relative = create_and_upload_full_backup_impl(
+            "savecloud-backup".into(),
+            &RecordingObserver::default(),
+            &CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+        assert!(relative.starts_with(BACKUPS_PREFIX));
+
+        let storage = Storage::active().unwrap();
+        let backups = list_cloud_backups(&storage, "savecloud-backup")
+            .await
+            .unwrap();
+        assert_eq!(backups.len(), 1);
+        let tar = api.object(&backups[0].key).unwrap();
+        let mut names: Vec<String> = tar::Archive::new(tar.as_slice())
+            .entries()
+            .unwrap()
+            .map(|e| e.unwrap().path().unwrap().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["Juego.srm", "Juego.state1"]);
+
+        std::fs::remove_file(saves.join("Juego.srm")).unwrap();
+        std::fs::remove_file(states.join("Juego.state1")).unwrap();
+        download_and_restore_full_backup_impl(
+            "savecloud-backup".into(),
+            backups[0].key.clone(),
+            &RecordingObserver::default(),
+            &CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(std::fs::read(saves.join("Juego.srm")).unwrap(), b"partida");
+        assert_eq!(
+            std::fs::read(states.join("Juego.state1")).unwrap(),
+            b"estado"
+        );
+        assert_eq!(
+            std::fs::read(saves.join("Otro.srm")).unwrap(),
+            b"de otro juego"
+        );
+        assert!(!saves.join("Juego.state1").exists());
     }
-    Ok(())
 }
@@ -8,14 +8,22 @@
 //! - `preview`: Previsualización de sync
 //! - `upload`: Subida de guardados
 //! - `download`: Descarga y conflictos
+//! - `observer`: Avisos de progreso del motor (eventos Tauri o salida de la CLI)
+//! - `cancellation`: Pausa y cancelación de transferencias en curso
+//! - `queue`: Cola persistente de operaciones pendientes con reintentos
+//! - `paused`: Transferencias pausadas, guardadas para reanudarlas por separado
 
 pub mod api;
 pub mod backup;
+pub mod cancellation;
 pub mod download;
 pub mod full_backup;
-mod models;
+pub mod models;
 pub mod multipart_upload;
+pub mod observer;
+pub mod paused;
 pub mod preview;
+pub mod queue;
 pub mod streaming;
 pub mod upload;
 
@@ -33,7 +41,7 @@ pub async fn list_save_files(game_id: String) -> Result<Vec<SaveFileDto>, String
         .find(|g| g.id.eq_ignore_ascii_case(&game_id))
         .ok_or_else(|| format!("Juego no encontrado: {}", game_id))?;
 
-    let files = crate::utils::path_utils::list_all_files_from_paths(&game.paths);
+    let files = crate::utils::path_translation::list_game_files(game);
     Ok(files
         .into_iter()
         .map(|(absolute, relative)| SaveFileDto { absolute, relative })
@@ -7,7 +7,7 @@
 //! Estas estructuras garantizan consistencia en el intercambio de datos
 //! entre cliente y backend.
 
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 
 #[derive(Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -16,16 +16,6 @@ pub struct SaveFileDto {
     pub relative: String,
 }
 
-#[derive(Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub(crate) struct RemoteSaveDto {
-    pub game_id: String,
-    pub key: String,
-    pub last_modified: String,
-    #[serde(default)]
-    pub size: Option<u64>,
-}
-
 #[derive(Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct SyncResultDto {
@@ -109,12 +99,14 @@ pub struct UnsyncedGameDto {
     pub game_id: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct DownloadConflictDto {
     pub filename: String,
     pub local_modified: String,
     pub cloud_modified: String,
+    /// Dispositivo que subió la versión de la nube, si quedó registrado.
+    pub remote_origin: Option<crate::devices::provenance::UploadOrigin>,
 }
 
 #[derive(Serialize)]
@@ -7,8 +7,8 @@
 //! 3. Transferencia de cada parte mediante solicitudes PUT.
 //! 4. Finalización de la subida o abort en caso de cancelación.
 //!
-//! Permite pausar la operación persistiendo el estado en disco,
-//! facilitando su reanudación posterior.
+//! Permite pausar la operación guardando las partes confirmadas como una
+//! transferencia pausada (`super::paused`), que se reanuda con el mismo `upload_id`.
 //!
 //! # Fiabilidad
 //!
@@ -22,20 +22,23 @@ use std::collections::HashSet;
 use std::io::SeekFrom;
 use std::path::{Path, PathBuf};
 
-use super::api;
+use super::cancellation::CancellationToken;
 use super::models::SyncProgressPayload;
+use super::observer::SyncObserver;
+use super::paused::{self, PausedTransfer};
 use crate::commands::logs::sync_logger;
-use crate::network::DATA_CLIENT;
+use crate::storage::{Storage, StorageBackend, TransferTarget};
+use crate::transfers::TransferKind;
 use futures_util::stream::StreamExt;
 use serde::{Deserialize, Serialize};
-use tauri::Emitter;
 use tokio::io::{AsyncReadExt, AsyncSeekExt};
 use tokio::sync::mpsc;
 use tokio_stream::wrappers::ReceiverStream;
 
+/// Error con el que el motor indica que la transferencia se pausó (y quedó registrada).
 pub const PAUSED_ERR_MSG: &str = "PAUSED";
-const PAUSED_STATE_FILE: &str = "paused_upload.json";
 
+/// Subida multipart a medias: lo necesario para completarla con el mismo `upload_id`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PausedUploadState {
@@ -55,31 +58,6 @@ pub struct CompletedPartState {
     pub etag: String,
 }
 
-fn paused_state_path() -> Option<std::path::PathBuf> {
-    crate::config::config_dir().map(|d| d.join(PAUSED_STATE_FILE))
-}
-
-fn save_paused_state(state: &PausedUploadState) -> Result<(), String> {
-    let path = paused_state_path().ok_or("No se pudo obtener directorio de config")?;
-    let json = serde_json::to_string_pretty(state).map_err(|e| e.to_string())?;
-    std::fs::write(&path, json).map_err(|e| format!("Guardar estado pausado: {}", e))?;
-    Ok(())
-}
-
-/// Carga el estado de una subida pausada, si existe.
-pub fn load_paused_state() -> Option<PausedUploadState> {
-    let path = paused_state_path()?;
-    let json = std::fs::read_to_string(&path).ok()?;
-    serde_json::from_str(&json).ok()
-}
-
-/// Elimina el archivo de estado pausado (tras reanudar con éxito o al cancelar).
-pub fn remove_paused_state_file() {
-    if let Some(p) = paused_state_path() {
-        let _ = std::fs::remove_file(p);
-    }
-}
-
 /// Tamaño de cada parte (S3 mínimo 5 MiB excepto la última). 10 MiB es un buen equilibrio.
 pub(crate) const PART_SIZE: u64 = 10 * 1024 * 1024;
 
@@ -97,6 +75,25 @@ const RETRY_DELAY_SECS: u64 = 1;
 /// Cuántas partes se suben en paralelo (acelera mucho archivos grandes).
 const MULTIPART_PUT_CONCURRENCY: usize = 8;
 
+fn part_count(total_size: u64) -> u32 {
+    total_size.div_ceil(PART_SIZE) as u32
+}
+
+/// Tamaño de la parte `part_number` (la última puede ser menor).
+fn part_len(part_number: u32, total_size: u64) -> u64 {
+    let start = (part_number - 1) as u64 * PART_SIZE;
+    std::cmp::min(PART_SIZE, total_size.saturating_sub(start))
+}
+
+/// Bytes ya confirmados de una subida a medias.
+pub(crate) fn uploaded_bytes(state: &PausedUploadState) -> u64 {
+    state
+        .completed_parts
+        .iter()
+        .map(|p| part_len(p.part_number, state.total_size))
+        .sum()
+}
+
 /// Ejecuta una operación async con reintentos y backoff. Devuelve el último error si todos fallan.
 async fn with_retry<F, Fut, T>(mut op: F) -> Result<T, String>
 where
@@ -119,247 +116,40 @@ where
     Err(last_err)
 }
 
-#[derive(serde::Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct MultipartInitResponse {
-    upload_id: String,
-    key: String,
-}
-
-#[derive(serde::Serialize)]
-#[serde(rename_all = "camelCase")]
-struct PartUrlsRequest {
-    key: String,
-    upload_id: String,
-    part_numbers: Vec<u32>,
-}
-
-#[derive(serde::Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct PartUrlItem {
-    part_number: u32,
-    url: String,
-}
-
-#[derive(serde::Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct PartUrlsResponse {
-    part_urls: Vec<PartUrlItem>,
-}
-
-#[derive(serde::Serialize)]
-#[serde(rename_all = "camelCase")]
-struct CompleteRequest {
-    key: String,
-    upload_id: String,
-    parts: Vec<CompletedPartReq>,
-}
-
-#[derive(serde::Serialize)]
-#[serde(rename_all = "camelCase")]
-struct CompletedPartReq {
-    part_number: u32,
-    etag: String,
-}
-
-async fn multipart_init(
-    api_base: &str,
-    user_id: &str,
-    api_key: &str,
-    game_id: &str,
-    filename: &str,
-) -> Result<(String, String), String> {
-    let body = serde_json::json!({
-        "gameId": game_id,
-        "filename": filename
-    });
-    let res = api::api_request(
-        api_base,
-        user_id,
-        api_key,
-        "POST",
-        "/multipart/init",
-        Some(body.to_string().as_bytes()),
-    )
-    .await
-    .map_err(|e| format!("multipart/init: {}", e))?;
-
-    if !res.status().is_success() {
-        let status = res.status();
-        let text = res.text().await.unwrap_or_default();
-        sync_logger::log_api("multipart/init", "/multipart/init", status.as_u16(), &text);
-        return Err(format!("API multipart/init: {} {}", status, text));
-    }
-
-    let parsed: MultipartInitResponse =
-        res.json().await.map_err(|e| format!("parse init: {}", e))?;
-    Ok((parsed.upload_id, parsed.key))
-}
-
-async fn multipart_part_urls(
-    api_base: &str,
-    user_id: &str,
-    api_key: &str,
-    key: &str,
-    upload_id: &str,
-    part_numbers: &[u32],
-) -> Result<Vec<(u32, String)>, String> {
-    if part_numbers.is_empty() {
-        return Ok(Vec::new());
-    }
-    let body = PartUrlsRequest {
-        key: key.to_string(),
-        upload_id: upload_id.to_string(),
-        part_numbers: part_numbers.to_vec(),
-    };
-    let body_bytes =
-        serde_json::to_vec(&body).map_err(|e| format!("serialize part-urls: {}", e))?;
-    let res = api::api_request(
-        api_base,
-        user_id,
-        api_key,
-        "POST",
-        "/multipart/part-urls",
-        Some(&body_bytes),
-    )
-    .await
-    .map_err(|e| format!("multipart/part-urls: {}", e))?;
-
-    if !res.status().is_success() {
-        let status = res.status();
-        let text = res.text().await.unwrap_or_default();
-        sync_logger::log_api(
-            "multipart/part-urls",
-            "/multipart/part-urls",
-            status.as_u16(),
-            &text,
-        );
-        return Err(format!("API multipart/part-urls: {} {}", status, text));
-    }
-
-    let parsed: PartUrlsResponse = res
-        .json()
-        .await
-        .map_err(|e| format!("parse part-urls: {}", e))?;
-    Ok(parsed
-        .part_urls
-        .into_iter()
-        .map(|p| (p.part_number, p.url))
-        .collect())
-}
-
-async fn multipart_complete(
-    api_base: &str,
-    user_id: &str,
-    api_key: &str,
-    key: &str,
-    upload_id: &str,
-    parts: &[(u32, String)],
-) -> Result<(), String> {
-    let body = CompleteRequest {
-        key: key.to_string(),
-        upload_id: upload_id.to_string(),
-        parts: parts
-            .iter()
-            .map(|(n, etag)| CompletedPartReq {
-                part_number: *n,
-                etag: etag.clone(),
-            })
-            .collect(),
-    };
-    let body_bytes = serde_json::to_vec(&body).map_err(|e| format!("serialize complete: {}", e))?;
-    let res = api::api_request(
-        api_base,
-        user_id,
-        api_key,
-        "POST",
-        "/multipart/complete",
-        Some(&body_bytes),
-    )
-    .await
-    .map_err(|e| format!("multipart/complete: {}", e))?;
-
-    if !res.status().is_success() && res.status().as_u16() != 204 {
-        let status = res.status();
-        let text = res.text().await.unwrap_or_default();
-        sync_logger::log_api(
-            "multipart/complete",
-            "/multipart/complete",
-            status.as_u16(),
-            &text,
-        );
-        return Err(format!("API multipart/complete: {} {}", status, text));
-    }
-    Ok(())
-}
-
-async fn multipart_abort(
-    api_base: &str,
-    user_id: &str,
-    api_key: &str,
-    key: &str,
-    upload_id: &str,
-) -> Result<(), String> {
-    let body = serde_json::json!({ "key": key, "uploadId": upload_id });
-    let res = api::api_request(
-        api_base,
-        user_id,
-        api_key,
-        "POST",
-        "/multipart/abort",
-        Some(body.to_string().as_bytes()),
-    )
-    .await
-    .map_err(|e| format!("multipart/abort: {}", e))?;
-
-    if !res.status().is_success() && res.status().as_u16() != 204 {
-        let status = res.status();
-        let text = res.text().await.unwrap_or_default();
-        sync_logger::log_api(
-            "multipart/abort",
-            "/multipart/abort",
-            status.as_u16(),
-            &text,
-        );
-        return Err(format!("API multipart/abort: {} {}", status, text));
-    }
-    Ok(())
-}
-
 /// Función de ayuda que encapsula el hilo "Productor".
 /// Genera URLs en lotes y las inyecta en el canal (tx) para que los workers las consuman.
 fn spawn_url_prefetcher(
-    api_base: String,
-    user_id: String,
-    api_key: String,
+    storage: Storage,
     key: String,
     upload_id: String,
     total_size: u64,
     parts_to_fetch: Vec<u32>,
-    tx: mpsc::Sender<(u32, u64, u64, String)>,
-    cancel: Option<std::sync::Arc<crate::tray::tray_state::TrayStateInner>>,
+    tx: mpsc::Sender<(u32, u64, u64, TransferTarget)>,
+    cancel: CancellationToken,
 ) {
     tokio::spawn(async move {
         for chunk in parts_to_fetch.chunks(PARTS_PER_URL_BATCH as usize) {
-            if let Some(ref t) = cancel {
-                if t.upload_cancel_requested() || t.upload_pause_requested() {
-                    break;
-                }
+            if cancel.should_stop() {
+                break;
             }
 
-            let urls_result = with_retry(|| {
-                multipart_part_urls(&api_base, &user_id, &api_key, &key, &upload_id, chunk)
+            let (storage, key, upload_id) = (&storage, key.as_str(), upload_id.as_str());
+            let urls_result = with_retry(|| async move {
+                storage
+                    .multipart_part_targets(key, upload_id, chunk)
+                    .await
+                    .map_err(|e| format!("multipart/part-urls: {}", e))
             })
             .await;
 
             match urls_result {
-                Ok(urls) => {
-                    for (num, url) in urls {
+                Ok(targets) => {
+                    for (num, target) in targets {
                         let start = (num - 1) as u64 * PART_SIZE;
-                        let part_len = std::cmp::min(PART_SIZE, total_size.saturating_sub(start));
+                        let len = part_len(num, total_size);
 
                         // Si el canal está lleno, `.send` esperará a que un worker termine
-                        if tx.send((num, start, part_len, url)).await.is_err() {
+                        if tx.send((num, start, len, target)).await.is_err() {
                             return; // El consumidor (rx) se ha cerrado, abortamos la tarea
                         }
                     }
@@ -373,337 +163,372 @@ fn spawn_url_prefetcher(
     });
 }
 
-/// Sube un archivo mediante multipart. Emite progreso y respeta cancelación entre partes.
-pub(crate) async fn upload_one_file_multipart(
-    absolute_path: &Path,
-    relative_filename: &str,
-    total_size: u64,
-    game_id: &str,
-    api_base: &str,
-    user_id: &str,
-    api_key: &str,
-    app: tauri::AppHandle,
-    cancel: Option<std::sync::Arc<crate::tray::tray_state::TrayStateInner>>,
-) -> Result<(), String> {
-    let ctx =
-        sync_logger::upload_context(game_id, relative_filename, &absolute_path.to_string_lossy());
-    sync_logger::log_operation("upload_multipart_start", &ctx);
-
-    let num_parts = if total_size == 0 {
-        0u32
-    } else {
-        ((total_size + PART_SIZE - 1) / PART_SIZE) as u32
-    };
+/// Lee la parte `part_number` del archivo y la sube a su destino; devuelve `(parte, etag, bytes)`.
+async fn upload_part(
+    path: PathBuf,
+    part_number: u32,
+    start: u64,
+    part_len: u64,
+    target: TransferTarget,
+) -> Result<(u32, String, u64), String> {
+    let mut f = tokio::fs::File::open(&path)
+        .await
+        .map_err(|e| format!("abrir parte {}: {}", part_number, e))?;
+    f.seek(SeekFrom::Start(start))
+        .await
+        .map_err(|e| format!("seek parte {}: {}", part_number, e))?;
+    let mut buf = vec![0u8; part_len as usize];
+    AsyncReadExt::read_exact(&mut f, &mut buf)
+        .await
+        .map_err(|e| format!("leer parte {}: {}", part_number, e))?;
 
-    let (upload_id, key) = if num_parts == 0 {
-        let (uid, k) =
-            with_retry(|| multipart_init(api_base, user_id, api_key, game_id, relative_filename))
-                .await?;
-        multipart_complete(api_base, user_id, api_key, &k, &uid, &[]).await?;
-        let _ = app.emit(
-            "sync-upload-progress",
-            SyncProgressPayload {
-                game_id: game_id.to_string(),
-                filename: relative_filename.to_string(),
-                loaded: total_size,
-                total: total_size,
-            },
-        );
-        return Ok(());
-    } else {
-        with_retry(|| multipart_init(api_base, user_id, api_key, game_id, relative_filename))
-            .await?
-    };
+    let etag = target
+        .put(buf.into(), "application/octet-stream")
+        .await
+        .map_err(|e| format!("PUT parte {}: {}", part_number, e))?;
+    Ok((part_number, etag, part_len))
+}
 
-    // Canal con capacidad limitada para aplicar contrapresión (Backpressure)
-    let (tx, rx) = mpsc::channel::<(u32, u64, u64, String)>(MULTIPART_PUT_CONCURRENCY * 2);
+/// Completa la subida con reintentos.
+async fn complete_with_retry(
+    storage: &Storage,
+    key: &str,
+    upload_id: &str,
+    parts: &[(u32, String)],
+) -> Result<(), String> {
+    with_retry(|| async move {
+        storage
+            .multipart_complete(key, upload_id, parts)
+            .await
+            .map_err(|e| format!("multipart/complete: {}", e))
+    })
+    .await
+}
 
-    let parts_to_fetch: Vec<u32> = (1..=num_parts).collect();
+/// Sube las partes que faltan de `state` y completa la subida.
+///
+/// Entre partes respeta el token: al pausar devuelve [`PAUSED_ERR_MSG`] con
+/// `state.completed_parts` al día para guardarlo. No aborta la subida en el
+/// backend ante ningún error: eso lo decide el llamador.
+async fn upload_parts(
+    storage: &Storage,
+    state: &mut PausedUploadState,
+    observer: &dyn SyncObserver,
+    cancel: &CancellationToken,
+) -> Result<(), String> {
+    let num_parts = part_count(state.total_size);
+    let completed_set: HashSet<u32> = state
+        .completed_parts
+        .iter()
+        .map(|p| p.part_number)
+        .collect();
+    let remaining: Vec<u32> = (1..=num_parts)
+        .filter(|n| !completed_set.contains(n))
+        .collect();
 
-    spawn_url_prefetcher(
-        api_base.to_string(),
-        user_id.to_string(),
-        api_key.to_string(),
-        key.clone(),
-        upload_id.clone(),
-        total_size,
-        parts_to_fetch,
-        tx,
-        cancel.clone(),
-    );
-
-    let path_buf = absolute_path.to_path_buf();
-    let game_id_owned = game_id.to_string();
-    let filename_owned = relative_filename.to_string();
-
-    let mut completed_parts: Vec<(u32, String)> = Vec::with_capacity(num_parts as usize);
-    let mut loaded: u64 = 0;
-
-    // Tarea Consumidora: Workers procesando el flujo de URLs
-    let mut stream = ReceiverStream::new(rx)
-        .map(|(part_number, start, part_len, url)| {
-            let path = path_buf.clone();
-            async move {
-                let mut f = tokio::fs::File::open(&path)
-                    .await
-                    .map_err(|e| format!("abrir parte {}: {}", part_number, e))?;
-                f.seek(SeekFrom::Start(start))
-                    .await
-                    .map_err(|e| format!("seek parte {}: {}", part_number, e))?;
-                let mut buf = vec![0u8; part_len as usize];
-                AsyncReadExt::read_exact(&mut f, &mut buf)
-                    .await
-                    .map_err(|e| format!("leer parte {}: {}", part_number, e))?;
+    if remaining.is_empty() {
+        observer.upload_progress(SyncProgressPayload {
+            game_id: state.game_id.clone(),
+            filename: state.filename.clone(),
+            loaded: state.total_size,
+            total: state.total_size,
+        });
+    } else {
+        // Canal con capacidad limitada para aplicar contrapresión (Backpressure)
+        let (tx, rx) =
+            mpsc::channel::<(u32, u64, u64, TransferTarget)>(MULTIPART_PUT_CONCURRENCY * 2);
+
+        spawn_url_prefetcher(
+            storage.clone(),
+            state.key.clone(),
+            state.upload_id.clone(),
+            state.total_size,
+            remaining,
+            tx,
+            cancel.clone(),
+        );
 
-                let res = DATA_CLIENT
-                    .put(&url)
-                    .body(buf)
-                    .header("Content-Type", "application/octet-stream")
-                    .send()
-                    .await
-                    .map_err(|e| format!("PUT parte {}: {}", part_number, e))?;
+        let path_buf = PathBuf::from(&state.absolute_path);
+        let mut loaded = uploaded_bytes(state);
 
-                if !res.status().is_success() {
-                    return Err(format!("parte {}: S3 PUT {}", part_number, res.status()));
+        // Tarea Consumidora: Workers procesando el flujo de URLs
+        let mut stream = ReceiverStream::new(rx)
+            .map(|(part_number, start, part_len, target)| {
+                upload_part(path_buf.clone(), part_number, start, part_len, target)
+            })
+            .buffer_unordered(MULTIPART_PUT_CONCURRENCY);
+
+        while let Some(result) = stream.next().await {
+            match result {
+                Ok((part_number, etag, part_len)) => {
+                    state
+                        .completed_parts
+                        .push(CompletedPartState { part_number, etag });
+                    loaded = std::cmp::min(loaded + part_len, state.total_size);
+                    observer.upload_progress(SyncProgressPayload {
+                        game_id: state.game_id.clone(),
+                        filename: state.filename.clone(),
+                        loaded,
+                        total: state.total_size,
+                    });
                 }
-                let etag = res
-                    .headers()
-                    .get("etag")
-                    .and_then(|v| v.to_str().ok())
-                    .unwrap_or("")
-                    .to_string();
-                Ok((part_number, etag, part_len))
+                // Una parte que falla justo al pausar se repetirá al reanudar.
+                Err(_) if cancel.is_paused() && !cancel.is_cancelled() => {}
+                Err(e) => return Err(e),
             }
-        })
-        .buffer_unordered(MULTIPART_PUT_CONCURRENCY);
-
-    while let Some(result) = stream.next().await {
-        if let Some(ref t) = cancel {
-            if t.upload_cancel_requested() {
-                let _ = multipart_abort(api_base, user_id, api_key, &key, &upload_id).await;
+            if cancel.is_cancelled() {
                 return Err("Subida cancelada".to_string());
             }
-            if t.upload_pause_requested() {
-                let state = PausedUploadState {
-                    upload_id: upload_id.clone(),
-                    key: key.clone(),
-                    game_id: game_id_owned.clone(),
-                    filename: filename_owned.clone(),
-                    absolute_path: absolute_path.to_string_lossy().to_string(),
-                    total_size,
-                    completed_parts: completed_parts
-                        .iter()
-                        .map(|(n, e)| CompletedPartState {
-                            part_number: *n,
-                            etag: e.clone(),
-                        })
-                        .collect(),
-                };
-                save_paused_state(&state).map_err(|e| format!("guardar pausa: {}", e))?;
+            if cancel.is_paused() {
                 return Err(PAUSED_ERR_MSG.to_string());
             }
         }
-
-        let (part_number, etag, part_len) = match result {
-            Ok(x) => x,
-            Err(e) => {
-                let _ = multipart_abort(api_base, user_id, api_key, &key, &upload_id).await;
-                return Err(e);
-            }
-        };
-        completed_parts.push((part_number, etag));
-        loaded = std::cmp::min(loaded + part_len, total_size);
-        let _ = app.emit(
-            "sync-upload-progress",
-            SyncProgressPayload {
-                game_id: game_id_owned.clone(),
-                filename: filename_owned.clone(),
-                loaded,
-                total: total_size,
-            },
-        );
     }
 
-    if completed_parts.len() < num_parts as usize {
-        let _ = multipart_abort(api_base, user_id, api_key, &key, &upload_id).await;
+    if state.completed_parts.len() < num_parts as usize {
         return Err("Error: La subida finalizó de forma incompleta (posible fallo de red)".into());
     }
 
-    completed_parts.sort_by_key(|p| p.0);
+    let mut parts: Vec<(u32, String)> = state
+        .completed_parts
+        .iter()
+        .map(|p| (p.part_number, p.etag.clone()))
+        .collect();
+    parts.sort_by_key(|p| p.0);
+    complete_with_retry(storage, &state.key, &state.upload_id, &parts).await
+}
 
-    with_retry(|| {
-        multipart_complete(
-            api_base,
-            user_id,
-            api_key,
-            &key,
-            &upload_id,
-            &completed_parts,
-        )
+/// Sube un archivo mediante multipart. Informa del progreso y respeta pausa/cancelación entre partes.
+///
+/// Al pausar guarda la subida como transferencia pausada de tipo `kind` y devuelve
+/// [`PAUSED_ERR_MSG`]; cualquier otro error aborta la subida en el backend.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn upload_one_file_multipart(
+    absolute_path: &Path,
+    relative_filename: &str,
+    total_size: u64,
+    game_id: &str,
+    kind: TransferKind,
+    storage: &Storage,
+    observer: &dyn SyncObserver,
+    cancel: &CancellationToken,
+) -> Result<(), String> {
+    let ctx =
+        sync_logger::upload_context(game_id, relative_filename, &absolute_path.to_string_lossy());
+    sync_logger::log_operation("upload_multipart_start", &ctx);
+
+    // Backends sin multipart (WebDAV): el archivo se envía entero en streaming.
+    if !storage.supports_multipart() {
+        let targets = storage
+            .put_targets(game_id, &[relative_filename.to_string()])
+            .await?;
+        let target = targets.first().ok_or("Sin destino de subida")?;
+        target
+            .put_file(absolute_path, "application/octet-stream")
+            .await?;
+        observer.upload_progress(SyncProgressPayload {
+            game_id: game_id.to_string(),
+            filename: relative_filename.to_string(),
+            loaded: total_size,
+            total: total_size,
+        });
+        return Ok(());
+    }
+
+    let (upload_id, key) = with_retry(|| async move {
+        storage
+            .multipart_init(game_id, relative_filename)
+            .await
+            .map_err(|e| format!("multipart/init: {}", e))
     })
     .await?;
 
-    Ok(())
-}
-
-/// Reanuda una subida multipart desde el estado guardado en disco con concurrencia.
-pub(crate) async fn resume_paused_upload(app: tauri::AppHandle) -> Result<(), String> {
-    let state = load_paused_state().ok_or("No hay ninguna subida pausada")?;
-
-    let cfg = crate::config::load_config();
-    let api_base = cfg
-        .api_base_url
-        .as_deref()
-        .filter(|s| !s.trim().is_empty())
-        .ok_or("Configura apiBaseUrl en Configuración")?;
-    let user_id = cfg
-        .user_id
-        .as_deref()
-        .filter(|s| !s.trim().is_empty())
-        .ok_or("Configura userId en Configuración")?;
-    let api_key = cfg.api_key.as_deref().unwrap_or("");
-
-    let num_parts = if state.total_size == 0 {
-        0u32
-    } else {
-        ((state.total_size + PART_SIZE - 1) / PART_SIZE) as u32
+    let mut state = PausedUploadState {
+        upload_id,
+        key,
+        game_id: game_id.to_string(),
+        filename: relative_filename.to_string(),
+        absolute_path: absolute_path.to_string_lossy().to_string(),
+        total_size,
+        completed_parts: Vec::with_capacity(part_count(total_size) as usize),
     };
 
-    let completed_set: HashSet<u32> = state
-        .completed_parts
-        .iter()
-        .map(|p| p.part_number)
-        .collect();
+    match upload_parts(storage, &mut state, observer, cancel).await {
+        Err(e) if e == PAUSED_ERR_MSG => {
+            paused::save(&PausedTransfer::multipart(kind, state))
+                .map_err(|e| format!("guardar pausa: {}", e))?;
+            observer.upload_paused(game_id, relative_filename);
+            Err(e)
+        }
+        Err(e) => {
+            let _ = storage.multipart_abort(&state.key, &state.upload_id).await;
+            Err(e)
+        }
+        Ok(()) => Ok(()),
+    }
+}
 
-    let remaining: Vec<u32> = (1..=num_parts)
-        .filter(|n| !completed_set.contains(n))
-        .collect();
+/// Reanuda una subida multipart pausada con el mismo `upload_id`.
+///
+/// Si se vuelve a pausar, el registro se actualiza con el mismo id; al terminar o
+/// cancelar se borra. Ante un error de red se conserva para reintentarlo.
+pub(crate) async fn resume_paused_upload(
+    record: PausedTransfer,
+    observer: &dyn SyncObserver,
+    cancel: &CancellationToken,
+) -> Result<(), String> {
+    let paused::ResumePoint::Multipart(mut state) = record.resume.clone() else {
+        return Err("La transferencia pausada no es una subida multipart".to_string());
+    };
+    let storage = Storage::active()?;
+
+    let result = upload_parts(&storage, &mut state, observer, cancel).await;
+    match &result {
+        Err(e) if e == PAUSED_ERR_MSG => {
+            let (game_id, filename) = (state.game_id.clone(), state.filename.clone());
+            let updated = PausedTransfer {
+                id: record.id,
+                ..PausedTransfer::multipart(record.kind, state)
+            };
+            paused::save(&updated).map_err(|e| format!("guardar pausa: {}", e))?;
+            observer.upload_paused(&game_id, &filename);
+        }
+        Err(_) if cancel.is_cancelled() => {
+            let _ = storage.multipart_abort(&state.key, &state.upload_id).await;
+            paused::remove(&record.id);
+        }
+        Err(_) => {}
+        Ok(()) => {
+            paused::remove(&record.id);
+            crate::devices::provenance::record_upload(
+                &storage,
+                &state.game_id,
+                std::slice::from_ref(&state.filename),
+            )
+            .await;
+        }
+    }
+    result
+}
 
-    // Si ya no faltan partes, completamos
-    if remaining.is_empty() {
-        multipart_complete(
-            api_base,
-            user_id,
-            api_key,
-            &state.key,
-            &state.upload_id,
-            &state
-                .completed_parts
-                .iter()
-                .map(|p| (p.part_number, p.etag.clone()))
-                .collect::<Vec<_>>(),
-        )
-        .await?;
-        remove_paused_state_file();
-        let _ = app.emit(
-            "sync-upload-progress",
-            SyncProgressPayload {
-                game_id: state.game_id.clone(),
-                filename: state.filename.clone(),
-                loaded: state.total_size,
-                total: state.total_size,
-            },
-        );
-        return Ok(());
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::sync::observer::tests::RecordingObserver;
+    use crate::testing::mock_api::{Fault, MockApi};
+
+    /// Archivo de dos partes: la primera completa y 1 KB en la segunda.
+    fn two_part_file(dir: &Path) -> (PathBuf, Vec<u8>) {
+        let path = dir.join("big.bin");
+        let data: Vec<u8> = (0..=255u8)
+            .cycle()
+            .take(PART_SIZE as usize + 1024)
+            .collect();
+        std::fs::write(&path, &data).unwrap();
+        (path, data)
     }
 
-    // Productor-Consumidor para la reanudación
-    let (tx, rx) = mpsc::channel::<(u32, u64, u64, String)>(MULTIPART_PUT_CONCURRENCY * 2);
-
-    spawn_url_prefetcher(
-        api_base.to_string(),
-        user_id.to_string(),
-        api_key.to_string(),
-        state.key.clone(),
-        state.upload_id.clone(),
-        state.total_size,
-        remaining,
-        tx,
-        None,
-    );
-
-    let mut all_parts: Vec<(u32, String)> = state
-        .completed_parts
-        .iter()
-        .map(|p| (p.part_number, p.etag.clone()))
-        .collect();
+    /// Pide cancelar en cuanto llega el primer aviso de progreso.
+    struct CancelOnProgress(CancellationToken);
 
-    let path_buf = PathBuf::from(&state.absolute_path);
-    let mut loaded = (all_parts.len() as u64) * PART_SIZE;
+    impl SyncObserver for CancelOnProgress {
+        fn upload_progress(&self, _progress: SyncProgressPayload) {
+            self.0.cancel();
+        }
 
-    // Concurrencia para la reanudación
-    let mut stream = ReceiverStream::new(rx)
-        .map(|(part_number, start, part_len, url)| {
-            let path = path_buf.clone();
-            async move {
-                let mut f = tokio::fs::File::open(&path)
-                    .await
-                    .map_err(|e| format!("abrir archivo para reanudar {}: {}", part_number, e))?;
-                f.seek(SeekFrom::Start(start))
-                    .await
-                    .map_err(|e| format!("seek parte {}: {}", part_number, e))?;
-                let mut buf = vec![0u8; part_len as usize];
-                AsyncReadExt::read_exact(&mut f, &mut buf)
-                    .await
-                    .map_err(|e| format!("leer parte {}: {}", part_number, e))?;
+        fn download_progress(&self, _progress: SyncProgressPayload) {}
 
-                let res = DATA_CLIENT
-                    .put(&url)
-                    .body(buf)
-                    .header("Content-Type", "application/octet-stream")
-                    .send()
-                    .await
-                    .map_err(|e| format!("PUT parte {}: {}", part_number, e))?;
+        fn upload_paused(&self, _game_id: &str, _filename: &str) {}
 
-                if !res.status().is_success() {
-                    return Err(format!("parte {}: S3 PUT {}", part_number, res.status()));
-                }
-                let etag = res
-                    .headers()
-                    .get("etag")
-                    .and_then(|v| v.to_str().ok())
-                    .unwrap_or("")
-                    .to_string();
-                Ok((part_number, etag, part_len))
-            }
-        })
-        .buffer_unordered(MULTIPART_PUT_CONCURRENCY);
-
-    let required_parts_len = num_parts as usize;
-
-    while let Some(result) = stream.next().await {
-        let (part_number, etag, part_len) = result?;
-        all_parts.push((part_number, etag));
-        loaded = std::cmp::min(loaded + part_len, state.total_size);
-
-        let _ = app.emit(
-            "sync-upload-progress",
-            SyncProgressPayload {
-                game_id: state.game_id.clone(),
-                filename: state.filename.clone(),
-                loaded,
-                total: state.total_size,
-            },
-        );
+        fn download_paused(&self, _game_id: &str, _filename: &str) {}
     }
 
-    if all_parts.len() < required_parts_len {
-        return Err("Error: La reanudación finalizó de forma incompleta".into());
+    #[tokio::test]
+    async fn reports_progress_to_observer() {
+        let api = MockApi::start().await;
+        let storage = Storage::Api(api.backend("user1"));
+        let dir = tempfile::tempdir().unwrap();
+        let (path, data) = two_part_file(dir.path());
+        let observer = RecordingObserver::default();
+
+        upload_one_file_multipart(
+            &path,
+            "big.bin",
+            data.len() as u64,
+            "game",
+            TransferKind::Upload,
+            &storage,
+            &observer,
+            &CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+
+        let uploads = observer.uploads.lock().unwrap();
+        assert_eq!(uploads.len(), 2);
+        assert_eq!(uploads[1].loaded, data.len() as u64);
+        assert_eq!(api.object("user1/game/big.bin").unwrap(), data);
     }
 
-    all_parts.sort_by_key(|p| p.0);
-    with_retry(|| {
-        multipart_complete(
-            api_base,
-            user_id,
-            api_key,
-            &state.key,
-            &state.upload_id,
-            &all_parts,
+    #[tokio::test]
+    async fn cancellation_aborts_between_parts() {
+        let api = MockApi::start().await;
+        let storage = Storage::Api(api.backend("user1"));
+        let dir = tempfile::tempdir().unwrap();
+        let (path, data) = two_part_file(dir.path());
+        let cancel = CancellationToken::new();
+
+        let result = upload_one_file_multipart(
+            &path,
+            "big.bin",
+            data.len() as u64,
+            "game",
+            TransferKind::Upload,
+            &storage,
+            &CancelOnProgress(cancel.clone()),
+            &cancel,
         )
-    })
-    .await?;
+        .await;
+
+        assert_eq!(result.unwrap_err(), "Subida cancelada");
+        assert_eq!(api.open_uploads(), 0);
+        assert!(api.object("user1/game/big.bin").is_none());
+    }
 
-    remove_paused_state_file();
-    Ok(())
+    #[tokio::test]
+    async fn parts_and_complete_survive_transient_failures() {
+        let api = MockApi::start().await;
+        let storage = Storage::Api(api.backend("user1"));
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("big.bin");
+        let data: Vec<u8> = (0..=255u8).cycle().take(3000).collect();
+        std::fs::write(&path, &data).unwrap();
+
+        let (upload_id, key) = storage.multipart_init("game", "big.bin").await.unwrap();
+        let targets = storage
+            .multipart_part_targets(&key, &upload_id, &[1, 2])
+            .await
+            .unwrap();
+
+        // Los dos primeros PUT fallan: la parte 1 se sube al tercer intento.
+        api.inject("/parts/", Fault::Status(500), 2);
+        let mut parts = Vec::new();
+        for (n, target) in targets {
+            let start = (n - 1) as u64 * 2000;
+            let len = (data.len() as u64 - start).min(2000);
+            let (n, etag, _) =
+                with_retry(|| upload_part(path.clone(), n, start, len, target.clone()))
+                    .await
+                    .unwrap();
+            parts.push((n, etag));
+        }
+        api.inject("/saves/multipart/complete", Fault::Status(503), 1);
+        complete_with_retry(&storage, &key, &upload_id, &parts)
+            .await
+            .unwrap();
+
+        assert_eq!(api.object(&key).unwrap(), data);
+        assert_eq!(api.hits("/parts/"), 4);
+        assert_eq!(api.hits("/saves/multipart/complete"), 2);
+    }
 }
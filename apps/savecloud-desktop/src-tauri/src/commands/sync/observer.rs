@@ -0,0 +1,127 @@
+//! Observador de progreso del motor de sincronización.
+//!
+//! Las funciones de subida, descarga y backups empaquetados no emiten eventos de
+//! Tauri directamente: informan a un [`SyncObserver`]. La app de escritorio pasa su
+//! `AppHandle` (el adaptador está en `ipc::sync_observer` y reenvía a `emit`); la CLI
+//! usa un observador que escribe en stderr. Un `Vec` de observadores reparte cada
+//! aviso entre todos, para alimentar varias interfaces a la vez.
+
+use super::models::SyncProgressPayload;
+use std::sync::Arc;
+
+/// Recibe el progreso de las transferencias en curso.
+pub trait SyncObserver: Send + Sync {
+    /// Progreso de subida de un archivo (`sync-upload-progress`).
+    fn upload_progress(&self, progress: SyncProgressPayload);
+
+    /// Progreso de descarga de un archivo (`sync-download-progress`).
+    fn download_progress(&self, progress: SyncProgressPayload);
+
+    /// La subida multipart se pausó y su estado quedó en disco (`sync-upload-paused`).
+    fn upload_paused(&self, game_id: &str, filename: &str);
+
+    /// La descarga o restauración se pausó y quedó registrada (`sync-download-paused`).
+    fn download_paused(&self, game_id: &str, filename: &str);
+}
+
+impl<T: SyncObserver + ?Sized> SyncObserver for Arc<T> {
+    fn upload_progress(&self, progress: SyncProgressPayload) {
+        (**self).upload_progress(progress);
+    }
+
+    fn download_progress(&self, progress: SyncProgressPayload) {
+        (**self).download_progress(progress);
+    }
+
+    fn upload_paused(&self, game_id: &str, filename: &str) {
+        (**self).upload_paused(game_id, filename);
+    }
+
+    fn download_paused(&self, game_id: &str, filename: &str) {
+        (**self).download_paused(game_id, filename);
+    }
+}
+
+impl<T: SyncObserver> SyncObserver for Vec<T> {
+    fn upload_progress(&self, progress: SyncProgressPayload) {
+        for observer in self {
+            observer.upload_progress(progress.clone());
+        }
+    }
+
+    fn download_progress(&self, progress: SyncProgressPayload) {
+        for observer in self {
+            observer.download_progress(progress.clone());
+        }
+    }
+
+    fn upload_paused(&self, game_id: &str, filename: &str) {
+        for observer in self {
+            observer.upload_paused(game_id, filename);
+        }
+    }
+
+    fn download_paused(&self, game_id: &str, filename: &str) {
+        for observer in self {
+            observer.download_paused(game_id, filename);
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Observador que guarda cada aviso, para los tests del motor.
+    #[derive(Default)]
+    pub(crate) struct RecordingObserver {
+        pub uploads: Mutex<Vec<SyncProgressPayload>>,
+        pub downloads: Mutex<Vec<SyncProgressPayload>>,
+        pub paused: Mutex<Vec<(String, String)>>,
+    }
+
+    impl SyncObserver for RecordingObserver {
+        fn upload_progress(&self, progress: SyncProgressPayload) {
+            self.uploads.lock().unwrap().push(progress);
+        }
+
+        fn download_progress(&self, progress: SyncProgressPayload) {
+            self.downloads.lock().unwrap().push(progress);
+        }
+
+        fn upload_paused(&self, game_id: &str, filename: &str) {
+            self.paused
+                .lock()
+                .unwrap()
+                .push((game_id.to_string(), filename.to_string()));
+        }
+
+        fn download_paused(&self, game_id: &str, filename: &str) {
+            self.upload_paused(game_id, filename);
+        }
+    }
+
+    #[test]
+    fn vec_of_observers_forwards_to_each() {
+        let a = Arc::new(RecordingObserver::default());
+        let b = Arc::new(RecordingObserver::default());
+        let observers: Vec<Arc<dyn SyncObserver>> = vec![a.clone(), b.clone()];
+
+        observers.download_progress(SyncProgressPayload {
+            game_id: "hades".into(),
+            filename: "slot1.sav".into(),
+            loaded: 5,
+            total: 10,
+        });
+        observers.upload_paused("hades", "big.bin");
+
+        for o in [&a, &b] {
+            assert_eq!(o.downloads.lock().unwrap()[0].loaded, 5);
+            assert_eq!(
+                o.paused.lock().unwrap()[0],
+                ("hades".to_string(), "big.bin".to_string())
+            );
+        }
+    }
+}
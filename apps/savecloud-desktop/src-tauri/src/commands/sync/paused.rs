@@ -0,0 +1,533 @@
+//! Transferencias pausadas, guardadas en disco para reanudarlas cada una por separado.
+//!
+//! Cada pausa se guarda en su propio JSON dentro de `paused_transfers/` (en el
+//! directorio de configuración). Así puede haber varias a la vez, de distintos
+//! juegos o tipos, y sobreviven a reinicios. Al reanudar, una subida multipart
+//! sigue desde la última parte confirmada. Las descargas solo bajan los archivos
+//! que faltaban. Las restauraciones se relanzan enteras.
+//!
+//! Los backups en streaming guardan su registro mientras suben, tras cada parte,
+//! para poder continuarlos también tras un cierre inesperado. Mientras la subida
+//! sigue viva el registro se marca como activo y no aparece en la lista.
+
+use super::api;
+use super::cancellation::CancellationToken;
+use super::download;
+use super::full_backup;
+use super::models::{GameSyncResultDto, SyncResultDto};
+use super::multipart_upload::{self, PausedUploadState, PAUSED_ERR_MSG};
+use super::observer::SyncObserver;
+use super::streaming::multipart::{abort_stream_upload, StreamCheckpoint};
+use crate::commands::logs::sync_logger;
+use crate::storage::{Storage, StorageBackend, BACKUPS_PREFIX};
+use crate::transfers::{self, TransferKind, TransferPriority};
+use crate::tray::tray_state::TrayState;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, State};
+
+const PAUSED_DIR: &str = "paused_transfers";
+/// Estado único de versiones anteriores; se importa como una pausa más.
+const LEGACY_STATE_FILE: &str = "paused_upload.json";
+
+/// Una transferencia pausada y lo necesario para reanudarla.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PausedTransfer {
+    pub id: String,
+    pub kind: TransferKind,
+    pub game_id: String,
+    /// Archivo, backup o resumen de lo pendiente, para la UI.
+    pub label: String,
+    pub loaded: u64,
+    pub total: u64,
+    /// Segundos Unix en que se pausó.
+    pub paused_at: u64,
+    pub resume: ResumePoint,
+}
+
+/// Punto desde el que continúa cada tipo de transferencia.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ResumePoint {
+    /// Subida multipart con sus partes ya confirmadas.
+    Multipart(PausedUploadState),
+    /// Descarga de archivos sueltos: claves que quedaban por bajar.
+    Download { remaining: Vec<String> },
+    /// Restauración de un backup empaquetado; se repite desde el principio.
+    #[serde(rename_all = "camelCase")]
+    Restore { backup_key: String },
+    /// Backup empaquetado en streaming: partes confirmadas y huella del origen.
+    StreamingBackup(StreamCheckpoint),
+}
+
+impl PausedTransfer {
+    pub(crate) fn new(
+        kind: TransferKind,
+        game_id: &str,
+        label: &str,
+        (loaded, total): (u64, u64),
+        resume: ResumePoint,
+    ) -> Self {
+        Self {
+            id: new_id(),
+            kind,
+            game_id: game_id.to_string(),
+            label: label.to_string(),
+            loaded,
+            total,
+            paused_at: now_secs(),
+            resume,
+        }
+    }
+
+    /// Subida multipart pausada; el progreso sale de las partes confirmadas.
+    pub(crate) fn multipart(kind: TransferKind, state: PausedUploadState) -> Self {
+        let progress = (multipart_upload::uploaded_bytes(&state), state.total_size);
+        let (game_id, filename) = (state.game_id.clone(), state.filename.clone());
+        Self::new(
+            kind,
+            &game_id,
+            &filename,
+            progress,
+            ResumePoint::Multipart(state),
+        )
+    }
+
+    /// Estado actual de un backup en streaming; conserva `id` entre guardados.
+    pub(crate) fn streaming(id: &str, game_id: &str, checkpoint: &StreamCheckpoint) -> Self {
+        Self {
+            id: id.to_string(),
+            kind: TransferKind::FullBackup,
+            game_id: game_id.to_string(),
+            label: checkpoint.relative_filename.clone(),
+            loaded: checkpoint.uploaded_bytes(),
+            total: checkpoint.estimated_total,
+            paused_at: now_secs(),
+            resume: ResumePoint::StreamingBackup(checkpoint.clone()),
+        }
+    }
+}
+
+/// Registros de transferencias que siguen en curso (se guardan mientras suben).
+static ACTIVE: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
+/// Mantiene un registro fuera de la lista de pausas mientras su transferencia sigue viva.
+pub(crate) struct ActiveRecord(String);
+
+impl Drop for ActiveRecord {
+    fn drop(&mut self) {
+        if let Ok(mut active) = ACTIVE.lock() {
+            active.remove(&self.0);
+        }
+    }
+}
+
+pub(crate) fn mark_active(id: &str) -> ActiveRecord {
+    if let Ok(mut active) = ACTIVE.lock() {
+        active.insert(id.to_string());
+    }
+    ActiveRecord(id.to_string())
+}
+
+fn is_active(id: &str) -> bool {
+    ACTIVE.lock().map(|a| a.contains(id)).unwrap_or(false)
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Id único aunque se pausen varias en el mismo milisegundo.
+pub(crate) fn new_id() -> String {
+    static SEQ: AtomicU32 = AtomicU32::new(0);
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    format!("{:x}-{:x}", millis, SEQ.fetch_add(1, Ordering::Relaxed))
+}
+
+fn paused_dir() -> Option<PathBuf> {
+    crate::config::config_dir().map(|d| d.join(PAUSED_DIR))
+}
+
+/// Ruta del registro `id`. Los ids vienen de la UI: solo se aceptan los generados aquí.
+fn record_path(dir: &Path, id: &str) -> Option<PathBuf> {
+    let valid = !id.is_empty() && id.chars().all(|c| c.is_ascii_hexdigit() || c == '-');
+    valid.then(|| dir.join(format!("{}.json", id)))
+}
+
+fn save_in(dir: &Path, record: &PausedTransfer) -> Result<(), String> {
+    let path = record_path(dir, &record.id).ok_or("Id de pausa no válido")?;
+    std::fs::create_dir_all(dir).map_err(|e| format!("Crear {}: {}", dir.display(), e))?;
+    let json = serde_json::to_string_pretty(record).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| format!("Guardar estado pausado: {}", e))
+}
+
+fn load_in(dir: &Path, id: &str) -> Option<PausedTransfer> {
+    let json = std::fs::read_to_string(record_path(dir, id)?).ok()?;
+    serde_json::from_str(&json).ok()
+}
+
+/// Pausas guardadas, de la más antigua a la más reciente. Los archivos ilegibles se ignoran.
+fn list_in(dir: &Path) -> Vec<PausedTransfer> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut records: Vec<PausedTransfer> = entries
+        .flatten()
+        .filter(|e| e.path().extension().is_some_and(|x| x == "json"))
+        .filter_map(|e| std::fs::read_to_string(e.path()).ok())
+        .filter_map(|json| serde_json::from_str(&json).ok())
+        .collect();
+    records.sort_by(|a, b| a.paused_at.cmp(&b.paused_at).then(a.id.cmp(&b.id)));
+    records
+}
+
+fn remove_in(dir: &Path, id: &str) {
+    if let Some(path) = record_path(dir, id) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+/// Importa el `paused_upload.json` de versiones anteriores como una pausa más.
+fn migrate_legacy(dir: &Path, legacy: &Path) {
+    let Some(state) = std::fs::read_to_string(legacy)
+        .ok()
+        .and_then(|json| serde_json::from_str::<PausedUploadState>(&json).ok())
+    else {
+        return;
+    };
+    let kind = if state.filename.starts_with(BACKUPS_PREFIX) {
+        TransferKind::FullBackup
+    } else {
+        TransferKind::Upload
+    };
+    if save_in(dir, &PausedTransfer::multipart(kind, state)).is_ok() {
+        let _ = std::fs::remove_file(legacy);
+    }
+}
+
+/// Guarda una transferencia pausada.
+pub(crate) fn save(record: &PausedTransfer) -> Result<(), String> {
+    let dir = paused_dir().ok_or("No se pudo obtener directorio de config")?;
+    save_in(&dir, record)
+}
+
+/// Todas las transferencias pausadas, de la más antigua a la más reciente.
+pub(crate) fn list() -> Vec<PausedTransfer> {
+    let Some(config) = crate::config::config_dir() else {
+        return Vec::new();
+    };
+    let dir = config.join(PAUSED_DIR);
+    migrate_legacy(&dir, &config.join(LEGACY_STATE_FILE));
+    let mut records = list_in(&dir);
+    records.retain(|r| !is_active(&r.id));
+    records
+}
+
+pub(crate) fn load(id: &str) -> Option<PausedTransfer> {
+    if is_active(id) {
+        return None;
+    }
+    load_in(&paused_dir()?, id)
+}
+
+pub(crate) fn remove(id: &str) {
+    if let Some(dir) = paused_dir() {
+        remove_in(&dir, id);
+    }
+}
+
+/// Olvida una pausa: aborta la subida multipart en el backend y borra el tar temporal.
+pub(crate) async fn discard(record: &PausedTransfer) {
+    match &record.resume {
+        ResumePoint::Multipart(state) => {
+            if let Ok(storage) = Storage::active() {
+                let _ = storage.multipart_abort(&state.key, &state.upload_id).await;
+            }
+            if record.kind == TransferKind::FullBackup {
+                let _ = std::fs::remove_file(&state.absolute_path);
+            }
+        }
+        ResumePoint::StreamingBackup(checkpoint) => {
+            if let Ok(storage) = Storage::active() {
+                let _ = abort_stream_upload(&storage, checkpoint).await;
+            }
+        }
+        ResumePoint::Download { .. } | ResumePoint::Restore { .. } => {}
+    }
+    remove(&record.id);
+}
+
+/// Backup en streaming interrumpido de `source_dir` cuyo contenido no ha cambiado.
+///
+/// Los de la misma carpeta con otra huella ya no se pueden completar (el TAR
+/// regenerado no cuadraría con las partes subidas) y se descartan.
+pub(crate) async fn take_streaming(
+    game_id: &str,
+    source_dir: &str,
+    fingerprint: &str,
+) -> Option<(PausedTransfer, StreamCheckpoint)> {
+    let mut found = None;
+    for record in list() {
+        let ResumePoint::StreamingBackup(checkpoint) = &record.resume else {
+            continue;
+        };
+        if !record.game_id.eq_ignore_ascii_case(game_id) || checkpoint.source_dir != source_dir {
+            continue;
+        }
+        if found.is_none() && checkpoint.source_fingerprint == fingerprint {
+            let checkpoint = checkpoint.clone();
+            found = Some((record, checkpoint));
+        } else if checkpoint.source_fingerprint != fingerprint {
+            sync_logger::log_operation(
+                "streaming_checkpoint_stale",
+                &format!("gameId={} key={}", game_id, checkpoint.key),
+            );
+            discard(&record).await;
+        }
+    }
+    found
+}
+
+/// Reanuda una pausa con el token de su nueva transferencia.
+///
+/// El registro se borra al terminar, al cancelar o si se vuelve a pausar (la nueva
+/// pausa deja el suyo); ante un error se conserva para reintentarlo.
+pub(crate) async fn resume_impl(
+    record: PausedTransfer,
+    observer: &dyn SyncObserver,
+    cancel: &CancellationToken,
+) -> Result<(), String> {
+    let game_id = record.game_id.clone();
+    let result = match &record.resume {
+        ResumePoint::Multipart(state)
+            if record.kind == TransferKind::FullBackup
+                && !Path::new(&state.absolute_path).exists() =>
+        {
+            // El tar temporal ya no existe (p. ej. se limpió la carpeta temporal): se
+            // descarta la subida a medias y se empaqueta de nuevo.
+            discard(&record).await;
+            full_backup::create_and_upload_full_backup_impl(game_id, observer, cancel)
+                .await
+                .map(|_| ())
+        }
+        ResumePoint::Multipart(state) => {
+            // Guarda y borra el registro por su cuenta: la pausa conserva el mismo id.
+            let tar = PathBuf::from(&state.absolute_path);
+            let result =
+                multipart_upload::resume_paused_upload(record.clone(), observer, cancel).await;
+            if record.kind == TransferKind::FullBackup && (result.is_ok() || cancel.is_cancelled())
+            {
+                let _ = std::fs::remove_file(tar);
+            }
+            return result;
+        }
+        ResumePoint::Download { remaining } => {
+            let saves = api::sync_list_remote_saves()
+                .await?
+                .into_iter()
+                .filter(|s| s.game_id.eq_ignore_ascii_case(&game_id) && remaining.contains(&s.key))
+                .collect();
+            let result =
+                download::sync_download_game_impl(game_id, observer, cancel, Some(saves)).await?;
+            if result.err_count > 0 {
+                Err(result.errors.join("; "))
+            } else {
+                Ok(())
+            }
+        }
+        ResumePoint::Restore { backup_key } => {
+            full_backup::download_and_restore_full_backup_impl(
+                game_id,
+                backup_key.clone(),
+                observer,
+                cancel,
+            )
+            .await
+        }
+        ResumePoint::StreamingBackup(_) => {
+            // Guarda y borra el registro por su cuenta mientras sube.
+            return full_backup::resume_streaming_backup(record, observer, cancel)
+                .await
+                .map(|_| ());
+        }
+    };
+    if result.is_ok() || cancel.should_stop() {
+        remove(&record.id);
+    }
+    result
+}
+
+/// Transferencias pausadas que se pueden reanudar.
+#[tauri::command]
+pub fn list_paused_transfers() -> Vec<PausedTransfer> {
+    list()
+}
+
+/// Reanuda una transferencia pausada como una transferencia nueva del gestor.
+async fn resume_one(
+    app: &AppHandle,
+    tray_state: &TrayState,
+    record: PausedTransfer,
+) -> Result<(), String> {
+    tray_state.0.syncing_inc();
+    tray_state.0.update_tooltip();
+
+    let kind = record.kind;
+    let transfer = transfers::begin(kind, &record.game_id, TransferPriority::Normal).await;
+    let result = resume_impl(record, &transfer.observer(app), transfer.token()).await;
+    drop(transfer);
+
+    tray_state.0.syncing_dec();
+    tray_state.0.clone().refresh_unsynced_async();
+    let event = match kind {
+        TransferKind::Download => "sync-download-done",
+        _ => "sync-upload-done",
+    };
+    let _ = app.emit(event, ());
+    result
+}
+
+/// Reanuda una transferencia pausada.
+#[tauri::command]
+pub async fn resume_paused_transfer(
+    id: String,
+    app: AppHandle,
+    tray_state: State<'_, TrayState>,
+) -> Result<(), String> {
+    let record =
+        load(&id).ok_or_else(|| format!("No hay ninguna transferencia pausada con id {}", id))?;
+    resume_one(&app, &tray_state, record).await
+}
+
+/// Reanuda todas las transferencias pausadas de un juego; el gestor decide cuántas corren a la vez.
+#[tauri::command]
+pub async fn resume_game_transfers(
+    game_id: String,
+    app: AppHandle,
+    tray_state: State<'_, TrayState>,
+) -> Result<GameSyncResultDto, String> {
+    let records: Vec<PausedTransfer> = list()
+        .into_iter()
+        .filter(|r| r.game_id.eq_ignore_ascii_case(&game_id))
+        .collect();
+    if records.is_empty() {
+        return Err(format!("No hay transferencias pausadas de {}", game_id));
+    }
+
+    let results = futures_util::future::join_all(
+        records
+            .into_iter()
+            .map(|record| resume_one(&app, &tray_state, record)),
+    )
+    .await;
+    let ok_count = results.iter().filter(|r| r.is_ok()).count() as u32;
+    let errors: Vec<String> = results
+        .into_iter()
+        .filter_map(|r| r.err())
+        .filter(|e| e != PAUSED_ERR_MSG)
+        .collect();
+    for e in &errors {
+        sync_logger::log_error("resume_game_transfers", &game_id, e);
+    }
+    Ok(GameSyncResultDto {
+        game_id,
+        result: SyncResultDto {
+            ok_count,
+            err_count: errors.len() as u32,
+            errors,
+        },
+    })
+}
+
+/// Descarta una transferencia pausada (aborta lo que quedara a medias en el backend).
+#[tauri::command]
+pub async fn cancel_paused_transfer(id: String) -> Result<(), String> {
+    let record =
+        load(&id).ok_or_else(|| format!("No hay ninguna transferencia pausada con id {}", id))?;
+    discard(&record).await;
+    Ok(())
+}
+
+/// Descarta las transferencias pausadas de un juego; devuelve cuántas había.
+pub(crate) async fn discard_game(game_id: &str) -> usize {
+    let records: Vec<PausedTransfer> = list()
+        .into_iter()
+        .filter(|r| r.game_id.eq_ignore_ascii_case(game_id))
+        .collect();
+    for record in &records {
+        discard(record).await;
+    }
+    records.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn upload_state(game_id: &str, filename: &str) -> PausedUploadState {
+        PausedUploadState {
+            upload_id: "u1".into(),
+            key: format!("user1/{}/{}", game_id, filename),
+            game_id: game_id.into(),
+            filename: filename.into(),
+            absolute_path: "/tmp/big.bin".into(),
+            total_size: 3 * multipart_upload::PART_SIZE,
+            completed_parts: vec![multipart_upload::CompletedPartState {
+                part_number: 1,
+                etag: "e1".into(),
+            }],
+        }
+    }
+
+    #[test]
+    fn keeps_several_pauses_and_migrates_the_legacy_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = dir.path().join(PAUSED_DIR);
+        let legacy = dir.path().join(LEGACY_STATE_FILE);
+        let old = upload_state("hades", &format!("{}old.tar", BACKUPS_PREFIX));
+        std::fs::write(&legacy, serde_json::to_string(&old).unwrap()).unwrap();
+
+        let upload =
+            PausedTransfer::multipart(TransferKind::Upload, upload_state("hades", "a.sav"));
+        let restore = PausedTransfer::new(
+            TransferKind::Download,
+            "celeste",
+            "b.tar",
+            (5, 10),
+            ResumePoint::Restore {
+                backup_key: "backups/b.tar".into(),
+            },
+        );
+        save_in(&store, &upload).unwrap();
+        save_in(&store, &restore).unwrap();
+        migrate_legacy(&store, &legacy);
+
+        assert!(!legacy.exists());
+        let records = list_in(&store);
+        assert_eq!(records.len(), 3);
+        assert_eq!(upload.loaded, multipart_upload::PART_SIZE);
+        assert!(records
+            .iter()
+            .any(|r| r.kind == TransferKind::FullBackup && r.label.ends_with("old.tar")));
+
+        // Cada una se reanuda o descarta por su id sin tocar las demás.
+        remove_in(&store, &upload.id);
+        assert!(load_in(&store, &upload.id).is_none());
+        match load_in(&store, &restore.id).unwrap().resume {
+            ResumePoint::Restore { backup_key } => assert_eq!(backup_key, "backups/b.tar"),
+            other => panic!("punto de reanudación inesperado: {:?}", other),
+        }
+        assert!(record_path(&store, "../config").is_none());
+    }
+}
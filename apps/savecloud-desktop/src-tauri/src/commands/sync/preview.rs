@@ -10,8 +10,7 @@
 use super::api;
 use super::download;
 use super::models::{PreviewDownloadDto, PreviewFileDto, PreviewUploadDto};
-use crate::utils::path_utils;
-use std::path::PathBuf;
+use crate::utils::path_translation;
 
 /// Previsualiza qué archivos se subirían.
 #[tauri::command]
@@ -23,7 +22,7 @@ pub fn preview_upload(game_id: String) -> Result<PreviewUploadDto, String> {
         .find(|g| g.id.eq_ignore_ascii_case(&game_id))
         .ok_or_else(|| format!("Juego no encontrado: {}", game_id))?;
 
-    let files = path_utils::list_all_files_with_mtime(&game.paths);
+    let files = path_translation::list_game_files_with_mtime(game);
     let total_size: u64 = files.iter().map(|(_, _, _, s)| s).sum();
 
     let preview_files: Vec<PreviewFileDto> = files
@@ -52,10 +51,8 @@ pub async fn preview_download(game_id: String) -> Result<PreviewDownloadDto, Str
         .find(|g| g.id.eq_ignore_ascii_case(&game_id))
         .ok_or_else(|| format!("Juego no encontrado: {}", game_id))?;
 
-    let dest_base = match path_utils::expand_path(game.paths[0].trim()) {
-        Some(p) => PathBuf::from(p),
-        None => return Err("No se pudo expandir la ruta".into()),
-    };
+    let dest =
+        path_translation::SaveDestinations::for_game(game).ok_or("No se pudo expandir la ruta")?;
 
     let all = api::sync_list_remote_saves().await?;
     let saves: Vec<_> = all
@@ -79,7 +76,7 @@ pub async fn preview_download(game_id: String) -> Result<PreviewDownloadDto, Str
         files.push(PreviewFileDto {
             filename: save.filename.clone(),
             size: save.size.unwrap_or(0),
-            local_newer: if dest_base.join(&save.filename).exists() {
+            local_newer: if dest.dir_for(&save.filename).join(&save.filename).exists() {
                 Some(local_newer)
             } else {
                 None
@@ -0,0 +1,142 @@
+//! Cola persistente de operaciones de sincronización (subidas, descargas y backups
+//! empaquetados) que no pudieron completarse, normalmente por falta de red.
+//!
+//! Los trabajos viven en la tabla `sync_jobs` de [`AppDb`], así que sobreviven a
+//! reinicios. El demonio `system::sync_queue` los ejecuta con backoff exponencial y
+//! adelanta los pendientes cuando vuelve la conexión. Cada cambio emite
+//! `sync-queue-changed` para que la UI refresque la lista.
+
+pub mod store;
+
+use crate::commands::logs::sync_logger;
+use crate::sqlite::AppDb;
+use rusqlite::Connection;
+use std::sync::LazyLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+use store::{SyncJobDto, SyncJobKind};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::Notify;
+
+/// Despierta al demonio de la cola en cuanto hay trabajo nuevo, sin esperar al siguiente tick.
+pub(crate) static QUEUE_WAKE: LazyLock<Notify> = LazyLock::new(Notify::new);
+
+/// Segundos Unix actuales.
+pub(crate) fn now_secs() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Ejecuta `f` sobre la conexión en un hilo bloqueante.
+pub(crate) async fn with_db<T: Send + 'static>(
+    db: &AppDb,
+    f: impl FnOnce(&Connection) -> Result<T, rusqlite::Error> + Send + 'static,
+) -> Result<T, String> {
+    let db = db.clone();
+    tokio::task::spawn_blocking(move || db.with_conn(f))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Avisa a la UI y al demonio de que la cola cambió.
+pub(crate) fn notify_changed(app: &AppHandle) {
+    QUEUE_WAKE.notify_one();
+    let _ = app.emit("sync-queue-changed", ());
+}
+
+/// Encola una operación automática que acaba de fallar (p. ej. la subida al cerrar un juego).
+///
+/// El primer reintento espera el backoff inicial; si la app no tiene base de datos, no hace nada.
+pub(crate) async fn enqueue_after_failure(
+    app: &AppHandle,
+    kind: SyncJobKind,
+    game_id: &str,
+    error: &str,
+) {
+    let Some(db) = app.try_state::<AppDb>() else {
+        return;
+    };
+    let game_id = game_id.to_string();
+    let error = error.to_string();
+    let delay = store::backoff_secs(1);
+    let res = with_db(&db, move |c| {
+        store::enqueue(c, kind, &game_id, 0, Some(&error), delay, now_secs())
+    })
+    .await;
+    match res {
+        Ok(_) => notify_changed(app),
+        Err(e) => sync_logger::log_error("sync_queue", "No se pudo encolar el trabajo", &e),
+    }
+}
+
+/// Trabajos de la cola: en curso, pendientes (por prioridad) y fallidos.
+#[tauri::command]
+pub async fn list_sync_jobs(db: State<'_, AppDb>) -> Result<Vec<SyncJobDto>, String> {
+    with_db(&db, store::list).await
+}
+
+/// Encola una operación para ejecutarla en cuanto haya conexión. Devuelve el id del trabajo.
+#[tauri::command]
+pub async fn enqueue_sync_job(
+    app: AppHandle,
+    db: State<'_, AppDb>,
+    kind: SyncJobKind,
+    game_id: String,
+    priority: Option<i64>,
+) -> Result<i64, String> {
+    let id = with_db(&db, move |c| {
+        store::enqueue(
+            c,
+            kind,
+            &game_id,
+            priority.unwrap_or(0),
+            None,
+            0,
+            now_secs(),
+        )
+    })
+    .await?;
+    notify_changed(&app);
+    Ok(id)
+}
+
+/// Cambia la prioridad de un trabajo (mayor = se ejecuta antes).
+#[tauri::command]
+pub async fn set_sync_job_priority(
+    app: AppHandle,
+    db: State<'_, AppDb>,
+    id: i64,
+    priority: i64,
+) -> Result<(), String> {
+    if !with_db(&db, move |c| {
+        store::set_priority(c, id, priority, now_secs())
+    })
+    .await?
+    {
+        return Err(format!("No existe el trabajo {}", id));
+    }
+    notify_changed(&app);
+    Ok(())
+}
+
+/// Quita un trabajo de la cola. Los que están en curso no se pueden cancelar.
+#[tauri::command]
+pub async fn cancel_sync_job(app: AppHandle, db: State<'_, AppDb>, id: i64) -> Result<(), String> {
+    if !with_db(&db, move |c| store::cancel(c, id)).await? {
+        return Err(format!("El trabajo {} no existe o está en curso", id));
+    }
+    notify_changed(&app);
+    Ok(())
+}
+
+/// Vuelve a poner en cola un trabajo que agotó sus intentos.
+#[tauri::command]
+pub async fn retry_sync_job(app: AppHandle, db: State<'_, AppDb>, id: i64) -> Result<(), String> {
+    if !with_db(&db, move |c| store::retry(c, id, now_secs())).await? {
+        return Err(format!("El trabajo {} no está marcado como fallido", id));
+    }
+    notify_changed(&app);
+    Ok(())
+}
@@ -0,0 +1,370 @@
+//! Acceso SQL a la tabla `sync_jobs`.
+//!
+//! Todas las funciones reciben `now` (segundos Unix) para que el backoff sea
+//! determinista en los tests.
+
+use rusqlite::{Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+/// Espera tras el primer fallo; se dobla en cada intento.
+const BACKOFF_BASE_SECS: i64 = 30;
+/// Techo del backoff: una hora.
+const BACKOFF_MAX_SECS: i64 = 60 * 60;
+/// Intentos tras los que el trabajo queda en `failed` hasta que se reintente a mano.
+pub const MAX_ATTEMPTS: i64 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncJobKind {
+    Upload,
+    Download,
+    FullBackup,
+}
+
+impl SyncJobKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Upload => "upload",
+            Self::Download => "download",
+            Self::FullBackup => "full_backup",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "upload" => Some(Self::Upload),
+            "download" => Some(Self::Download),
+            "full_backup" => Some(Self::FullBackup),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncJobDto {
+    pub id: i64,
+    pub kind: SyncJobKind,
+    pub game_id: String,
+    /// `pending`, `running` o `failed`.
+    pub state: String,
+    pub priority: i64,
+    pub attempts: i64,
+    pub next_attempt_at: i64,
+    pub last_error: Option<String>,
+    pub created_at: i64,
+}
+
+const JOB_COLUMNS: &str = "id, kind, game_id, state, priority, attempts, next_attempt_at, \
+                           last_error, created_at";
+
+fn job_from_row(row: &Row<'_>) -> Result<SyncJobDto, rusqlite::Error> {
+    let kind: String = row.get(1)?;
+    Ok(SyncJobDto {
+        id: row.get(0)?,
+        kind: SyncJobKind::parse(&kind).ok_or_else(|| {
+            rusqlite::Error::FromSqlConversionFailure(
+                1,
+                rusqlite::types::Type::Text,
+                format!("tipo de trabajo desconocido: {}", kind).into(),
+            )
+        })?,
+        game_id: row.get(2)?,
+        state: row.get(3)?,
+        priority: row.get(4)?,
+        attempts: row.get(5)?,
+        next_attempt_at: row.get(6)?,
+        last_error: row.get(7)?,
+        created_at: row.get(8)?,
+    })
+}
+
+/// Segundos de espera tras `attempts` fallos consecutivos (30 s, 60 s, 120 s… hasta 1 h).
+pub fn backoff_secs(attempts: i64) -> i64 {
+    let exp = attempts.saturating_sub(1).clamp(0, 30) as u32;
+    BACKOFF_BASE_SECS
+        .saturating_mul(1i64 << exp)
+        .min(BACKOFF_MAX_SECS)
+}
+
+/// Encola un trabajo, o actualiza el pendiente del mismo tipo y juego.
+///
+/// Si ya había uno pendiente se conserva su backoff y se sube la prioridad si la
+/// nueva es mayor. Un trabajo `failed` del mismo tipo y juego queda sustituido.
+pub fn enqueue(
+    conn: &Connection,
+    kind: SyncJobKind,
+    game_id: &str,
+    priority: i64,
+    last_error: Option<&str>,
+    delay_secs: i64,
+    now: i64,
+) -> Result<i64, rusqlite::Error> {
+    conn.execute(
+        "DELETE FROM sync_jobs WHERE kind = ?1 AND game_id = ?2 AND state = 'failed'",
+        (kind.as_str(), game_id),
+    )?;
+    conn.query_row(
+        "INSERT INTO sync_jobs (kind, game_id, priority, last_error, next_attempt_at, created_at, updated_at) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6) \
+         ON CONFLICT (kind, game_id) WHERE state = 'pending' DO UPDATE SET \
+         priority = max(priority, excluded.priority), \
+         last_error = coalesce(excluded.last_error, last_error), \
+         updated_at = excluded.updated_at \
+         RETURNING id",
+        (
+            kind.as_str(),
+            game_id,
+            priority,
+            last_error,
+            now + delay_secs.max(0),
+            now,
+        ),
+        |row| row.get(0),
+    )
+}
+
+/// Toda la cola: primero lo que está en curso, luego por prioridad y turno.
+pub fn list(conn: &Connection) -> Result<Vec<SyncJobDto>, rusqlite::Error> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {JOB_COLUMNS} FROM sync_jobs \
+         ORDER BY CASE state WHEN 'running' THEN 0 WHEN 'pending' THEN 1 ELSE 2 END, \
+         priority DESC, next_attempt_at, id"
+    ))?;
+    let rows = stmt.query_map([], job_from_row)?;
+    rows.collect()
+}
+
+/// Marca como `running` el trabajo pendiente más prioritario cuyo turno ya llegó.
+pub fn claim_next_due(conn: &Connection, now: i64) -> Result<Option<SyncJobDto>, rusqlite::Error> {
+    let job = conn
+        .query_row(
+            &format!(
+                "SELECT {JOB_COLUMNS} FROM sync_jobs \
+                 WHERE state = 'pending' AND next_attempt_at <= ?1 \
+                 ORDER BY priority DESC, next_attempt_at, id LIMIT 1"
+            ),
+            [now],
+            job_from_row,
+        )
+        .optional()?;
+    let Some(mut job) = job else {
+        return Ok(None);
+    };
+    conn.execute(
+        "UPDATE sync_jobs SET state = 'running', updated_at = ?2 WHERE id = ?1",
+        (job.id, now),
+    )?;
+    job.state = "running".to_string();
+    Ok(Some(job))
+}
+
+/// El trabajo terminó bien: sale de la cola.
+pub fn complete(conn: &Connection, id: i64) -> Result<(), rusqlite::Error> {
+    conn.execute("DELETE FROM sync_jobs WHERE id = ?1", [id])?;
+    Ok(())
+}
+
+/// Registra un fallo: vuelve a `pending` con backoff o pasa a `failed` tras [`MAX_ATTEMPTS`].
+///
+/// Si mientras corría se encoló otro trabajo igual, este se descarta en su favor.
+pub fn fail(conn: &Connection, id: i64, error: &str, now: i64) -> Result<(), rusqlite::Error> {
+    let superseded = conn.execute(
+        "UPDATE sync_jobs SET last_error = ?2, updated_at = ?3 \
+         WHERE state = 'pending' AND (kind, game_id) = \
+         (SELECT kind, game_id FROM sync_jobs WHERE id = ?1)",
+        (id, error, now),
+    )?;
+    if superseded > 0 {
+        return complete(conn, id);
+    }
+
+    let attempts: i64 = conn.query_row(
+        "SELECT attempts + 1 FROM sync_jobs WHERE id = ?1",
+        [id],
+        |row| row.get(0),
+    )?;
+    let state = if attempts >= MAX_ATTEMPTS {
+        "failed"
+    } else {
+        "pending"
+    };
+    conn.execute(
+        "UPDATE sync_jobs SET state = ?2, attempts = ?3, last_error = ?4, \
+         next_attempt_at = ?5, updated_at = ?6 WHERE id = ?1",
+        (
+            id,
+            state,
+            attempts,
+            error,
+            now + backoff_secs(attempts),
+            now,
+        ),
+    )?;
+    Ok(())
+}
+
+/// Cambia la prioridad (mayor = antes). Devuelve `false` si el trabajo no existe.
+pub fn set_priority(
+    conn: &Connection,
+    id: i64,
+    priority: i64,
+    now: i64,
+) -> Result<bool, rusqlite::Error> {
+    let n = conn.execute(
+        "UPDATE sync_jobs SET priority = ?2, updated_at = ?3 WHERE id = ?1",
+        (id, priority, now),
+    )?;
+    Ok(n > 0)
+}
+
+/// Quita un trabajo que no esté en curso. Devuelve `false` si no había ninguno así.
+pub fn cancel(conn: &Connection, id: i64) -> Result<bool, rusqlite::Error> {
+    let n = conn.execute(
+        "DELETE FROM sync_jobs WHERE id = ?1 AND state <> 'running'",
+        [id],
+    )?;
+    Ok(n > 0)
+}
+
+/// Devuelve un trabajo `failed` a la cola con los intentos a cero.
+pub fn retry(conn: &Connection, id: i64, now: i64) -> Result<bool, rusqlite::Error> {
+    let n = conn.execute(
+        "UPDATE sync_jobs SET state = 'pending', attempts = 0, next_attempt_at = ?2, \
+         updated_at = ?2 WHERE id = ?1 AND state = 'failed'",
+        (id, now),
+    )?;
+    Ok(n > 0)
+}
+
+/// Al arrancar: los trabajos que quedaron `running` (la app se cerró a medias) vuelven a `pending`.
+pub fn recover_interrupted(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "DELETE FROM sync_jobs WHERE state = 'running' AND EXISTS ( \
+         SELECT 1 FROM sync_jobs p WHERE p.state = 'pending' \
+         AND p.kind = sync_jobs.kind AND p.game_id = sync_jobs.game_id)",
+        [],
+    )?;
+    conn.execute(
+        "UPDATE sync_jobs SET state = 'pending' WHERE state = 'running'",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Al volver la conexión: los pendientes en backoff se adelantan para vaciar la cola ya.
+pub fn release_backoff(conn: &Connection, now: i64) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "UPDATE sync_jobs SET next_attempt_at = ?1 WHERE state = 'pending' AND next_attempt_at > ?1",
+        [now],
+    )?;
+    Ok(())
+}
+
+/// `(pendientes, pendientes cuyo turno ya llegó)`.
+pub fn pending_counts(conn: &Connection, now: i64) -> Result<(i64, i64), rusqlite::Error> {
+    conn.query_row(
+        "SELECT count(*), coalesce(sum(next_attempt_at <= ?1), 0) \
+         FROM sync_jobs WHERE state = 'pending'",
+        [now],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sqlite::run_migrations;
+
+    fn db() -> Connection {
+        let conn = Connection::open_in_memory().expect("in memory");
+        run_migrations(&conn).expect("migrate");
+        conn
+    }
+
+    #[test]
+    fn enqueue_deduplicates_per_game_and_kind() {
+        let conn = db();
+        let a = enqueue(&conn, SyncJobKind::Upload, "hades", 0, None, 0, 100).unwrap();
+        let b = enqueue(
+            &conn,
+            SyncJobKind::Upload,
+            "HADES",
+            5,
+            Some("offline"),
+            0,
+            110,
+        )
+        .unwrap();
+        let c = enqueue(&conn, SyncJobKind::Download, "hades", 0, None, 0, 120).unwrap();
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        let jobs = list(&conn).unwrap();
+        assert_eq!(jobs.len(), 2);
+        assert_eq!(jobs[0].id, a);
+        assert_eq!(jobs[0].priority, 5);
+        assert_eq!(jobs[0].last_error.as_deref(), Some("offline"));
+    }
+
+    #[test]
+    fn failures_back_off_and_stop_after_max_attempts() {
+        let conn = db();
+        let id = enqueue(&conn, SyncJobKind::Upload, "hades", 0, None, 0, 0).unwrap();
+
+        let mut now = 0;
+        for attempt in 1..=MAX_ATTEMPTS {
+            let job = claim_next_due(&conn, now).unwrap().expect("due");
+            assert_eq!(job.id, id);
+            assert!(claim_next_due(&conn, now).unwrap().is_none());
+
+            fail(&conn, id, "timeout", now).unwrap();
+            let job = &list(&conn).unwrap()[0];
+            assert_eq!(job.attempts, attempt);
+            assert_eq!(job.next_attempt_at, now + backoff_secs(attempt));
+            now = job.next_attempt_at;
+        }
+
+        let job = &list(&conn).unwrap()[0];
+        assert_eq!(job.state, "failed");
+        assert!(claim_next_due(&conn, now).unwrap().is_none());
+
+        assert!(retry(&conn, id, now).unwrap());
+        assert_eq!(claim_next_due(&conn, now).unwrap().unwrap().attempts, 0);
+        assert_eq!(backoff_secs(1), 30);
+        assert_eq!(backoff_secs(MAX_ATTEMPTS), BACKOFF_MAX_SECS);
+    }
+
+    #[test]
+    fn running_job_is_superseded_by_newer_pending_one() {
+        let conn = db();
+        let first = enqueue(&conn, SyncJobKind::Upload, "hades", 0, None, 0, 0).unwrap();
+        claim_next_due(&conn, 0).unwrap();
+        assert!(!cancel(&conn, first).unwrap());
+
+        let second = enqueue(&conn, SyncJobKind::Upload, "hades", 0, None, 0, 1).unwrap();
+        assert_ne!(first, second);
+        fail(&conn, first, "HTTP 503", 2).unwrap();
+
+        let jobs = list(&conn).unwrap();
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].id, second);
+        assert_eq!(jobs[0].last_error.as_deref(), Some("HTTP 503"));
+    }
+
+    #[test]
+    fn priority_orders_claims_and_backoff_can_be_released() {
+        let conn = db();
+        let low = enqueue(&conn, SyncJobKind::Upload, "a", 0, None, 0, 0).unwrap();
+        let high = enqueue(&conn, SyncJobKind::FullBackup, "b", 0, None, 600, 0).unwrap();
+        assert!(set_priority(&conn, high, 10, 0).unwrap());
+        assert_eq!(pending_counts(&conn, 0).unwrap(), (2, 1));
+
+        release_backoff(&conn, 0).unwrap();
+        assert_eq!(claim_next_due(&conn, 0).unwrap().unwrap().id, high);
+
+        recover_interrupted(&conn).unwrap();
+        assert_eq!(pending_counts(&conn, 0).unwrap(), (2, 2));
+        assert!(cancel(&conn, low).unwrap());
+    }
+}
@@ -31,21 +31,30 @@
 //! Los eventos `sync-upload-progress` solo se emiten cuando se completa una parte
 //! completa o cuando cambia el porcentaje entero, evitando inundar el frontend con
 //! eventos mientras el encoder TAR llena el buffer interno.
+//!
+//! ## Reanudación
+//!
+//! Cada parte confirmada se anota en un [`StreamCheckpoint`] que el llamador
+//! persiste. Tras una pausa, un error de red o un cierre inesperado, el TAR se
+//! regenera desde el primer hueco y se completa el mismo `upload_id`; solo la
+//! cancelación aborta la subida en el backend.
 
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::time::Instant;
 
 use bytes::{BufMut, BytesMut};
+use serde::{Deserialize, Serialize};
 use tokio::sync::Semaphore;
 
-use crate::network::DATA_CLIENT;
-use tauri::Emitter;
-
-use super::super::api;
+use super::super::cancellation::CancellationToken;
 use super::super::models::SyncProgressPayload;
+use super::super::multipart_upload::PAUSED_ERR_MSG;
+use super::super::observer::SyncObserver;
 use super::tar_stream::TarStreamMsg;
 use super::upload_strategy::{ConcurrencyController, UploadStrategy};
 use crate::commands::logs::sync_logger;
+use crate::storage::{Storage, StorageBackend, TransferTarget};
 
 /// Número de URLs solicitadas en cada batch al API.
 /// Debe coincidir con `PART_URL_BATCH` en `upload_strategy.rs`.
@@ -54,62 +63,15 @@ const PART_URL_BATCH: u32 = 32;
 const MAX_RETRIES: u32 = 3;
 const RETRY_DELAY_SECS: u64 = 1;
 
-/// Contexto compartido para las operaciones del API.
+/// Contexto compartido para las operaciones multipart del backend.
 /// Evita repetir los mismos parámetros en cada función auxiliar.
 struct UploadCtx<'a> {
-    api_base: &'a str,
-    user_id: &'a str,
-    api_key: &'a str,
+    storage: &'a Storage,
     key: &'a str,
     upload_id: &'a str,
 }
 
-// Tipos de request/response del API
-
-#[derive(serde::Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct MultipartInitResponse {
-    upload_id: String,
-    key: String,
-}
-
-#[derive(serde::Serialize)]
-#[serde(rename_all = "camelCase")]
-struct PartUrlsRequest {
-    key: String,
-    upload_id: String,
-    part_numbers: Vec<u32>,
-}
-
-#[derive(serde::Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct PartUrlItem {
-    part_number: u32,
-    url: String,
-}
-
-#[derive(serde::Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct PartUrlsResponse {
-    part_urls: Vec<PartUrlItem>,
-}
-
-#[derive(serde::Serialize)]
-#[serde(rename_all = "camelCase")]
-struct CompleteRequest {
-    key: String,
-    upload_id: String,
-    parts: Vec<CompletedPartReq>,
-}
-
-#[derive(serde::Serialize)]
-#[serde(rename_all = "camelCase")]
-struct CompletedPartReq {
-    part_number: u32,
-    etag: String,
-}
-
-// Helpers de retry y peticiones al API
+// Helpers de retry y peticiones al backend
 
 async fn with_retry<F, Fut, T>(mut op: F) -> Result<T, String>
 where
@@ -132,119 +94,28 @@ where
     Err(last_err)
 }
 
-async fn multipart_init(
-    api_base: &str,
-    user_id: &str,
-    api_key: &str,
-    game_id: &str,
-    filename: &str,
-) -> Result<(String, String), String> {
-    let body = serde_json::json!({ "gameId": game_id, "filename": filename });
-    let res = api::api_request(
-        api_base,
-        user_id,
-        api_key,
-        "POST",
-        "/multipart/init",
-        Some(body.to_string().as_bytes()),
-    )
-    .await
-    .map_err(|e| format!("multipart/init: {}", e))?;
-
-    if !res.status().is_success() {
-        let status = res.status();
-        let text = res.text().await.unwrap_or_default();
-        return Err(format!("API multipart/init: {} {}", status, text));
-    }
-    let parsed: MultipartInitResponse = res.json().await.map_err(|e| e.to_string())?;
-    Ok((parsed.upload_id, parsed.key))
-}
-
 async fn multipart_part_urls(
     ctx: &UploadCtx<'_>,
     part_numbers: &[u32],
-) -> Result<Vec<(u32, String)>, String> {
-    let body = PartUrlsRequest {
-        key: ctx.key.to_string(),
-        upload_id: ctx.upload_id.to_string(),
-        part_numbers: part_numbers.to_vec(),
-    };
-    let body_bytes = serde_json::to_vec(&body).map_err(|e| e.to_string())?;
-    let res = api::api_request(
-        ctx.api_base,
-        ctx.user_id,
-        ctx.api_key,
-        "POST",
-        "/multipart/part-urls",
-        Some(&body_bytes),
-    )
-    .await
-    .map_err(|e| format!("multipart/part-urls: {}", e))?;
-
-    if !res.status().is_success() {
-        let status = res.status();
-        let text = res.text().await.unwrap_or_default();
-        return Err(format!("API multipart/part-urls: {} {}", status, text));
-    }
-    let parsed: PartUrlsResponse = res.json().await.map_err(|e| e.to_string())?;
-    Ok(parsed
-        .part_urls
-        .into_iter()
-        .map(|p| (p.part_number, p.url))
-        .collect())
+) -> Result<Vec<(u32, TransferTarget)>, String> {
+    ctx.storage
+        .multipart_part_targets(ctx.key, ctx.upload_id, part_numbers)
+        .await
+        .map_err(|e| format!("multipart/part-urls: {}", e))
 }
 
 async fn multipart_complete(ctx: &UploadCtx<'_>, parts: &[(u32, String)]) -> Result<(), String> {
-    let body = CompleteRequest {
-        key: ctx.key.to_string(),
-        upload_id: ctx.upload_id.to_string(),
-        parts: parts
-            .iter()
-            .map(|(n, etag)| CompletedPartReq {
-                part_number: *n,
-                etag: etag.clone(),
-            })
-            .collect(),
-    };
-    let body_bytes = serde_json::to_vec(&body).map_err(|e| e.to_string())?;
-    let res = api::api_request(
-        ctx.api_base,
-        ctx.user_id,
-        ctx.api_key,
-        "POST",
-        "/multipart/complete",
-        Some(&body_bytes),
-    )
-    .await
-    .map_err(|e| format!("multipart/complete: {}", e))?;
-
-    if !res.status().is_success() && res.status().as_u16() != 204 {
-        let status = res.status();
-        let text = res.text().await.unwrap_or_default();
-        return Err(format!("API multipart/complete: {} {}", status, text));
-    }
-    Ok(())
+    ctx.storage
+        .multipart_complete(ctx.key, ctx.upload_id, parts)
+        .await
+        .map_err(|e| format!("multipart/complete: {}", e))
 }
 
 async fn multipart_abort(ctx: &UploadCtx<'_>) -> Result<(), String> {
-    let body = serde_json::json!({ "key": ctx.key, "uploadId": ctx.upload_id });
-    let res = api::api_request(
-        ctx.api_base,
-        ctx.user_id,
-        ctx.api_key,
-        "POST",
-        "/multipart/abort",
-        Some(body.to_string().as_bytes()),
-    )
-    .await
-    .map_err(|e| format!("multipart/abort: {}", e))?;
-
-    if !res.status().is_success() && res.status().as_u16() != 204 {
-        let status = res.status();
-        let text = res.text().await.unwrap_or_default();
-        return Err(format!("API multipart/abort: {} {}", status, text));
-    }
-    Ok(())
+    ctx.storage
+        .multipart_abort(ctx.key, ctx.upload_id)
+        .await
+        .map_err(|e| format!("multipart/abort: {}", e))
 }
 
 /// Estado del prefetch de URLs en background.
@@ -255,7 +126,7 @@ async fn multipart_abort(ctx: &UploadCtx<'_>) -> Result<(), String> {
 struct PrefetchState {
     /// Tarea en vuelo que resuelve el siguiente batch de URLs, o `None` si no hay
     /// ningún prefetch en curso.
-    task: Option<tokio::task::JoinHandle<Result<Vec<(u32, String)>, String>>>,
+    task: Option<tokio::task::JoinHandle<Result<Vec<(u32, TransferTarget)>, String>>>,
 }
 
 impl PrefetchState {
@@ -269,11 +140,9 @@ impl PrefetchState {
     /// El llamador debe comprobar que la tarea anterior terminó antes de llamar de nuevo.
     fn launch(
         &mut self,
+        storage: Storage,
         ctx_key: String,
         ctx_upload_id: String,
-        ctx_api_base: String,
-        ctx_user_id: String,
-        ctx_api_key: String,
         part_numbers: Vec<u32>,
     ) {
         if self.task.is_some() {
@@ -281,9 +150,7 @@ impl PrefetchState {
         }
         self.task = Some(tokio::spawn(async move {
             let ctx = UploadCtx {
-                api_base: &ctx_api_base,
-                user_id: &ctx_user_id,
-                api_key: &ctx_api_key,
+                storage: &storage,
                 key: &ctx_key,
                 upload_id: &ctx_upload_id,
             };
@@ -295,7 +162,7 @@ impl PrefetchState {
     ///
     /// Si la tarea falló, el error se registra pero no se propaga: el llamador
     /// volverá a pedir las URLs de forma bloqueante en la siguiente iteración.
-    async fn drain_into_cache(&mut self, cache: &mut HashMap<u32, String>) {
+    async fn drain_into_cache(&mut self, cache: &mut HashMap<u32, TransferTarget>) {
         if let Some(task) = self.task.take() {
             match task.await {
                 Ok(Ok(urls)) => {
@@ -324,7 +191,7 @@ impl PrefetchState {
 /// especulativo del siguiente batch como tarea en background cuando el caché
 /// cae por debajo de `prefetch_threshold`.
 async fn ensure_part_url_cached(
-    cache: &mut HashMap<u32, String>,
+    cache: &mut HashMap<u32, TransferTarget>,
     prefetch: &mut PrefetchState,
     ctx: &UploadCtx<'_>,
     part_number: u32,
@@ -356,11 +223,9 @@ async fn ensure_part_url_cached(
             .collect();
         if !missing.is_empty() {
             prefetch.launch(
+                ctx.storage.clone(),
                 ctx.key.to_string(),
                 ctx.upload_id.to_string(),
-                ctx.api_base.to_string(),
-                ctx.user_id.to_string(),
-                ctx.api_key.to_string(),
                 missing,
             );
         }
@@ -369,6 +234,9 @@ async fn ensure_part_url_cached(
     Ok(())
 }
 
+/// Resultado de cada tarea de subida de parte dentro del `JoinSet`.
+type PartTaskResult = Result<(u32, String, u64, u128, tokio::sync::OwnedSemaphorePermit), String>;
+
 /// Resultado normalizado de una tarea de subida de parte.
 enum TaskResult {
     Part {
@@ -384,12 +252,7 @@ enum TaskResult {
 }
 
 /// Normaliza el resultado de `JoinSet::join_next` absorbiendo panics del runtime.
-fn normalize_join_result(
-    res: Result<
-        Result<(u32, String, u64, u128, tokio::sync::OwnedSemaphorePermit), String>,
-        tokio::task::JoinError,
-    >,
-) -> TaskResult {
+fn normalize_join_result(res: Result<PartTaskResult, tokio::task::JoinError>) -> TaskResult {
     match res {
         Ok(Ok((pn, etag, bytes_sent, elapsed_ms, permit))) => TaskResult::Part {
             part_number: pn,
@@ -404,14 +267,15 @@ fn normalize_join_result(
 }
 
 /// Drena todas las tareas pendientes del JoinSet esperando su finalización.
-/// En caso de error cancela el resto y llama a `multipart_abort`.
+/// Cada parte confirmada queda en el checkpoint antes de mirar la siguiente.
+/// En caso de error cancela el resto; el llamador decide si abortar la subida.
 async fn drain_upload_tasks(
-    tasks: &mut tokio::task::JoinSet<
-        Result<(u32, String, u64, u128, tokio::sync::OwnedSemaphorePermit), String>,
-    >,
-    ctx: &UploadCtx<'_>,
-) -> Result<Vec<(u32, String, u64, u128)>, String> {
-    let mut collected = Vec::new();
+    tasks: &mut tokio::task::JoinSet<PartTaskResult>,
+    checkpoint: &mut StreamCheckpoint,
+    persist: &(dyn Fn(&StreamCheckpoint) + Sync),
+    loaded: &mut u64,
+    concurrency: &mut ConcurrencyController,
+) -> Result<(), String> {
     while let Some(res) = tasks.join_next().await {
         match normalize_join_result(res) {
             TaskResult::Part {
@@ -421,27 +285,27 @@ async fn drain_upload_tasks(
                 elapsed_ms,
                 ..
             } => {
-                collected.push((part_number, etag, bytes_sent, elapsed_ms));
+                checkpoint.record_part(part_number, etag, bytes_sent);
+                persist(checkpoint);
+                *loaded += bytes_sent;
+                concurrency.record_part(bytes_sent, elapsed_ms);
             }
             TaskResult::Err(e) => {
                 tasks.abort_all();
-                let _ = multipart_abort(ctx).await;
                 return Err(e);
             }
         }
     }
-    Ok(collected)
+    Ok(())
 }
 
 /// Recoge sin bloquear las tareas que ya finalizaron.
-/// Actualiza `completed_parts`, `loaded` y alimenta el `ConcurrencyController`.
+/// Actualiza el checkpoint, `loaded` y alimenta el `ConcurrencyController`.
 /// Devuelve los bytes recién completados para que el llamador decida si emitir progreso.
 async fn collect_finished_tasks(
-    tasks: &mut tokio::task::JoinSet<
-        Result<(u32, String, u64, u128, tokio::sync::OwnedSemaphorePermit), String>,
-    >,
-    ctx: &UploadCtx<'_>,
-    completed_parts: &mut Vec<(u32, String)>,
+    tasks: &mut tokio::task::JoinSet<PartTaskResult>,
+    checkpoint: &mut StreamCheckpoint,
+    persist: &(dyn Fn(&StreamCheckpoint) + Sync),
     loaded: &mut u64,
     concurrency: &mut ConcurrencyController,
 ) -> Result<u64, String> {
@@ -455,28 +319,28 @@ async fn collect_finished_tasks(
                 elapsed_ms,
                 ..
             } => {
-                completed_parts.push((part_number, etag));
+                checkpoint.record_part(part_number, etag, bytes_sent);
                 *loaded += bytes_sent;
                 newly_loaded += bytes_sent;
                 concurrency.record_part(bytes_sent, elapsed_ms);
             }
             TaskResult::Err(e) => {
                 tasks.abort_all();
-                let _ = multipart_abort(ctx).await;
                 return Err(e);
             }
         }
     }
+    if newly_loaded > 0 {
+        persist(checkpoint);
+    }
     Ok(newly_loaded)
 }
 
 /// Espera a que un slot de concurrencia se libere cuando el JoinSet está lleno.
 async fn wait_for_one_slot(
-    tasks: &mut tokio::task::JoinSet<
-        Result<(u32, String, u64, u128, tokio::sync::OwnedSemaphorePermit), String>,
-    >,
-    ctx: &UploadCtx<'_>,
-    completed_parts: &mut Vec<(u32, String)>,
+    tasks: &mut tokio::task::JoinSet<PartTaskResult>,
+    checkpoint: &mut StreamCheckpoint,
+    persist: &(dyn Fn(&StreamCheckpoint) + Sync),
     loaded: &mut u64,
     concurrency: &mut ConcurrencyController,
 ) -> Result<u64, String> {
@@ -492,14 +356,14 @@ async fn wait_for_one_slot(
                 elapsed_ms,
                 ..
             } => {
-                completed_parts.push((part_number, etag));
+                checkpoint.record_part(part_number, etag, bytes_sent);
+                persist(checkpoint);
                 *loaded += bytes_sent;
                 concurrency.record_part(bytes_sent, elapsed_ms);
                 return Ok(bytes_sent);
             }
             TaskResult::Err(e) => {
                 tasks.abort_all();
-                let _ = multipart_abort(ctx).await;
                 return Err(e);
             }
         }
@@ -513,7 +377,7 @@ async fn wait_for_one_slot(
 /// El throttle por porcentaje entero evita inundar el IPC de Tauri con eventos
 /// mientras el encoder TAR llena el buffer interno sin que la red avance.
 fn maybe_emit_progress(
-    app: &tauri::AppHandle,
+    observer: &dyn SyncObserver,
     game_id: &str,
     filename: &str,
     loaded: u64,
@@ -528,15 +392,12 @@ fn maybe_emit_progress(
     };
     if force || pct > *last_pct {
         *last_pct = pct;
-        let _ = app.emit(
-            "sync-upload-progress",
-            SyncProgressPayload {
-                game_id: game_id.to_string(),
-                filename: filename.to_string(),
-                loaded,
-                total,
-            },
-        );
+        observer.upload_progress(SyncProgressPayload {
+            game_id: game_id.to_string(),
+            filename: filename.to_string(),
+            loaded,
+            total,
+        });
     }
 }
 
@@ -547,11 +408,9 @@ fn maybe_emit_progress(
 /// Esto garantiza que el número de partes en vuelo × `part_size` nunca supera
 /// `strategy.max_inflight_bytes` independientemente de la concurrencia del JoinSet.
 async fn spawn_part_upload(
-    tasks: &mut tokio::task::JoinSet<
-        Result<(u32, String, u64, u128, tokio::sync::OwnedSemaphorePermit), String>,
-    >,
+    tasks: &mut tokio::task::JoinSet<PartTaskResult>,
     semaphore: &std::sync::Arc<Semaphore>,
-    url: String,
+    target: TransferTarget,
     part_number: u32,
     bytes_to_send: bytes::Bytes,
 ) -> Result<(), String> {
@@ -566,9 +425,9 @@ async fn spawn_part_upload(
     let pn = part_number;
     tasks.spawn(async move {
         let result = with_retry(|| {
-            let url = url.clone();
+            let target = &target;
             let b = bytes_to_send.clone();
-            async move { put_part(url, pn, b).await }
+            async move { put_part(target, pn, b).await }
         })
         .await;
         // Adjuntar el permiso al resultado para que se dropee al procesar la tarea.
@@ -578,108 +437,296 @@ async fn spawn_part_upload(
     Ok(())
 }
 
-/// Sube un archivo a S3 mediante multipart upload en modo streaming.
+/// Parte ya confirmada por el backend dentro de una subida en streaming.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamPart {
+    pub part_number: u32,
+    pub etag: String,
+    /// Byte del TAR en el que empieza la parte.
+    pub offset: u64,
+    pub len: u64,
+}
+
+/// Punto de control de una subida en streaming a medias.
+///
+/// Como el TAR es determinista mientras el origen no cambie (`source_fingerprint`),
+/// basta con las partes confirmadas para regenerarlo desde el primer hueco y
+/// completar el mismo `upload_id`. El tamaño de parte se fija al iniciar: si se
+/// recalculara al reanudar, los desplazamientos dejarían de cuadrar.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamCheckpoint {
+    pub upload_id: String,
+    pub key: String,
+    pub relative_filename: String,
+    pub source_dir: String,
+    /// Carpetas o patrones empaquetados (ver `tar_stream::source_entries`). Los
+    /// checkpoints anteriores no lo tienen y empaquetan solo `source_dir`.
+    #[serde(default)]
+    pub source_paths: Vec<String>,
+    pub source_fingerprint: String,
+    pub part_size: u64,
+    pub estimated_total: u64,
+    pub completed_parts: Vec<StreamPart>,
+}
+
+impl StreamCheckpoint {
+    /// Orígenes del TAR para `tar_stream::spawn_tar_stream`.
+    pub fn sources(&self) -> Vec<PathBuf> {
+        if self.source_paths.is_empty() {
+            return vec![PathBuf::from(&self.source_dir)];
+        }
+        self.source_paths.iter().map(PathBuf::from).collect()
+    }
+
+    /// Primera parte que falta; todo lo anterior está confirmado.
+    pub fn first_missing_part(&self) -> u32 {
+        let mut n = 1;
+        while self.has_part(n) {
+            n += 1;
+        }
+        n
+    }
+
+    /// Byte del TAR desde el que hay que regenerarlo para continuar.
+    pub fn resume_offset(&self) -> u64 {
+        (self.first_missing_part() - 1) as u64 * self.part_size
+    }
+
+    /// Bytes ya confirmados (incluye partes sueltas más allá del primer hueco).
+    pub fn uploaded_bytes(&self) -> u64 {
+        self.completed_parts.iter().map(|p| p.len).sum()
+    }
+
+    fn has_part(&self, part_number: u32) -> bool {
+        self.completed_parts
+            .iter()
+            .any(|p| p.part_number == part_number)
+    }
+
+    fn record_part(&mut self, part_number: u32, etag: String, len: u64) {
+        self.completed_parts
+            .retain(|p| p.part_number != part_number);
+        self.completed_parts.push(StreamPart {
+            part_number,
+            etag,
+            offset: (part_number - 1) as u64 * self.part_size,
+            len,
+        });
+    }
+}
+
+/// Inicia una subida multipart nueva para un backup en streaming y devuelve su checkpoint vacío.
+pub(crate) async fn init_stream_checkpoint(
+    storage: &Storage,
+    game_id: &str,
+    relative_filename: &str,
+    source_dir: &str,
+    sources: &[PathBuf],
+    source_fingerprint: String,
+    estimated_total: u64,
+) -> Result<StreamCheckpoint, String> {
+    let strategy = UploadStrategy::for_file(estimated_total);
+    let (upload_id, key) = with_retry(|| async move {
+        storage
+            .multipart_init(game_id, relative_filename)
+            .await
+            .map_err(|e| format!("multipart/init: {}", e))
+    })
+    .await?;
+    Ok(StreamCheckpoint {
+        upload_id,
+        key,
+        relative_filename: relative_filename.to_string(),
+        source_dir: source_dir.to_string(),
+        source_paths: sources
+            .iter()
+            .map(|p| p.to_string_lossy().into_owned())
+            .collect(),
+        source_fingerprint,
+        part_size: strategy.part_size as u64,
+        estimated_total,
+        completed_parts: Vec::new(),
+    })
+}
+
+/// Aborta en el backend la subida de un checkpoint que ya no se va a completar.
+pub(crate) async fn abort_stream_upload(
+    storage: &Storage,
+    checkpoint: &StreamCheckpoint,
+) -> Result<(), String> {
+    let ctx = UploadCtx {
+        storage,
+        key: &checkpoint.key,
+        upload_id: &checkpoint.upload_id,
+    };
+    multipart_abort(&ctx).await
+}
+
+/// Sube un archivo al backend de almacenamiento mediante multipart upload en modo streaming.
 ///
 /// Lee chunks del receptor TAR, los acumula en un `BytesMut` hasta completar
 /// una parte, y la sube concurrentemente con las demás. La concurrencia se
 /// ajusta automáticamente según el throughput medido por `ConcurrencyController`.
 /// Un semáforo de memoria impide que el número de partes en vuelo supere
 /// `strategy.max_inflight_bytes`.
+///
+/// El receptor debe empezar en `checkpoint.resume_offset()`: las partes que el
+/// checkpoint ya tiene se descartan sin subirlas. Cada parte confirmada se anota en
+/// `checkpoint` y se pasa a `persist`, de modo que un cierre inesperado pierde como
+/// mucho las partes en vuelo. Ante pausa o error la subida se conserva en el backend
+/// (devuelve `PAUSED_ERR_MSG` o el error); solo la cancelación la aborta.
 pub(crate) async fn upload_tar_stream_multipart(
-    mut rx: tokio::sync::mpsc::Receiver<TarStreamMsg>,
+    rx: tokio::sync::mpsc::Receiver<TarStreamMsg>,
     game_id: &str,
-    relative_filename: &str,
-    estimated_total: u64,
-    api_base: &str,
-    user_id: &str,
-    api_key: &str,
-    app: tauri::AppHandle,
-    cancel: Option<std::sync::Arc<crate::tray::tray_state::TrayStateInner>>,
+    checkpoint: &mut StreamCheckpoint,
+    storage: &Storage,
+    observer: &dyn SyncObserver,
+    cancel: &CancellationToken,
+    persist: &(dyn Fn(&StreamCheckpoint) + Sync),
 ) -> Result<(), String> {
-    let strategy = UploadStrategy::for_file(estimated_total);
+    let mut strategy = UploadStrategy::for_file(checkpoint.estimated_total);
+    strategy.part_size = checkpoint.part_size as usize;
     let mut concurrency = ConcurrencyController::new(&strategy);
 
+    let log_ctx = format!(
+        "gameId={} filename={} (streaming) resume_part={} strategy=[{}]",
+        game_id,
+        checkpoint.relative_filename,
+        checkpoint.first_missing_part(),
+        strategy.describe(),
+    );
+    sync_logger::log_operation("full_backup_streaming_start", &log_ctx);
+
+    let mut loaded = checkpoint.uploaded_bytes();
+    let result = stream_parts(
+        rx,
+        game_id,
+        checkpoint,
+        storage,
+        observer,
+        cancel,
+        persist,
+        &strategy,
+        &mut concurrency,
+        &mut loaded,
+    )
+    .await;
+
+    let (key, upload_id) = (checkpoint.key.clone(), checkpoint.upload_id.clone());
+    let ctx = UploadCtx {
+        storage,
+        key: &key,
+        upload_id: &upload_id,
+    };
+    if let Err(e) = result {
+        if cancel.is_cancelled() {
+            let _ = multipart_abort(&ctx).await;
+            return Err("subida cancelada".to_string());
+        }
+        persist(checkpoint);
+        if e == PAUSED_ERR_MSG {
+            sync_logger::log_operation("full_backup_streaming_paused", &log_ctx);
+        } else {
+            sync_logger::log_error("full_backup_streaming_error", &log_ctx, &e);
+        }
+        return Err(e);
+    }
+
+    let mut parts: Vec<(u32, String)> = checkpoint
+        .completed_parts
+        .iter()
+        .map(|p| (p.part_number, p.etag.clone()))
+        .collect();
+    parts.sort_by_key(|p| p.0);
+    with_retry(|| multipart_complete(&ctx, &parts)).await?;
+
+    let final_ctx = format!(
+        "{} | parts={} bytes={} concurrency=[{}]",
+        log_ctx,
+        parts.len(),
+        loaded,
+        concurrency.describe(),
+    );
+    sync_logger::log_operation("full_backup_streaming_complete", &final_ctx);
+
+    Ok(())
+}
+
+/// Bucle de subida de [`upload_tar_stream_multipart`]: trocea el stream en partes y
+/// las sube hasta que el TAR termina, se pide pausa/cancelación o algo falla.
+#[allow(clippy::too_many_arguments)]
+async fn stream_parts(
+    mut rx: tokio::sync::mpsc::Receiver<TarStreamMsg>,
+    game_id: &str,
+    checkpoint: &mut StreamCheckpoint,
+    storage: &Storage,
+    observer: &dyn SyncObserver,
+    cancel: &CancellationToken,
+    persist: &(dyn Fn(&StreamCheckpoint) + Sync),
+    strategy: &UploadStrategy,
+    concurrency: &mut ConcurrencyController,
+    loaded: &mut u64,
+) -> Result<(), String> {
     // Semáforo de memoria: `max_inflight_bytes / part_size` permisos disponibles.
     // Limita la presión de memoria sin depender solo de la concurrencia del JoinSet.
     let max_permits = (strategy.max_inflight_bytes / strategy.part_size).max(1);
     let semaphore = std::sync::Arc::new(Semaphore::new(max_permits));
 
-    let (upload_id, key) =
-        with_retry(|| multipart_init(api_base, user_id, api_key, game_id, relative_filename))
-            .await?;
-
+    let (key, upload_id) = (checkpoint.key.clone(), checkpoint.upload_id.clone());
     let ctx = UploadCtx {
-        api_base,
-        user_id,
-        api_key,
+        storage,
         key: &key,
         upload_id: &upload_id,
     };
-    let display_name = format!("{} (stream)", relative_filename);
-    let log_ctx = format!(
-        "gameId={} filename={} (streaming) strategy=[{}]",
-        game_id,
-        relative_filename,
-        strategy.describe(),
-    );
-    sync_logger::log_operation("full_backup_streaming_start", &log_ctx);
+    let display_name = format!("{} (stream)", checkpoint.relative_filename);
+    let estimated_total = checkpoint.estimated_total;
 
-    let mut part_urls_cache: HashMap<u32, String> = HashMap::new();
+    let mut part_urls_cache: HashMap<u32, TransferTarget> = HashMap::new();
     let mut prefetch = PrefetchState::new();
-    let mut part_number: u32 = 1;
+    let mut part_number: u32 = checkpoint.first_missing_part();
 
     // `BytesMut` como buffer de acumulación de partes. `split_to(part_size).freeze()`
     // separa el chunk en O(1) sin mover bytes; el buffer continúa apuntando al
     // espacio contiguo restante del mismo bloque de memoria.
     let mut part_buf = BytesMut::with_capacity(strategy.part_size);
 
-    let mut completed_parts: Vec<(u32, String)> = Vec::new();
-    let mut loaded: u64 = 0;
     let mut last_pct: u8 = 0;
 
-    let mut upload_tasks: tokio::task::JoinSet<
-        Result<(u32, String, u64, u128, tokio::sync::OwnedSemaphorePermit), String>,
-    > = tokio::task::JoinSet::new();
+    let mut upload_tasks: tokio::task::JoinSet<PartTaskResult> = tokio::task::JoinSet::new();
 
     maybe_emit_progress(
-        &app,
+        observer,
         game_id,
         &display_name,
-        0,
+        *loaded,
         estimated_total,
         &mut last_pct,
         true,
     );
 
     while let Some(msg) = rx.recv().await {
-        if let Some(ref t) = cancel {
-            if t.upload_cancel_requested() {
-                upload_tasks.abort_all();
-                let _ = multipart_abort(&ctx).await;
-                return Err("subida cancelada".to_string());
-            }
-            if t.upload_pause_requested() {
-                upload_tasks.abort_all();
-                let _ = multipart_abort(&ctx).await;
-                return Err("pausa no soportada en backups streaming (usa cancelar)".to_string());
-            }
+        if cancel.is_cancelled() {
+            upload_tasks.abort_all();
+            return Err("subida cancelada".to_string());
+        }
+        if cancel.is_paused() {
+            // Las partes en vuelo se pierden; el checkpoint ya tiene las confirmadas.
+            upload_tasks.abort_all();
+            return Err(PAUSED_ERR_MSG.to_string());
         }
 
-        let newly_loaded = collect_finished_tasks(
-            &mut upload_tasks,
-            &ctx,
-            &mut completed_parts,
-            &mut loaded,
-            &mut concurrency,
-        )
-        .await?;
+        let newly_loaded =
+            collect_finished_tasks(&mut upload_tasks, checkpoint, persist, loaded, concurrency)
+                .await?;
 
         if newly_loaded > 0 {
             maybe_emit_progress(
-                &app,
+                observer,
                 game_id,
                 &display_name,
-                loaded,
+                *loaded,
                 estimated_total,
                 &mut last_pct,
                 false,
@@ -695,62 +742,68 @@ pub(crate) async fn upload_tar_stream_multipart(
                     part_buf.put_slice(&bytes[offset..offset + take]);
                     offset += take;
 
-                    if part_buf.len() == strategy.part_size {
-                        ensure_part_url_cached(
-                            &mut part_urls_cache,
-                            &mut prefetch,
-                            &ctx,
-                            part_number,
-                            strategy.prefetch_threshold,
-                        )
-                        .await?;
-
-                        let url = part_urls_cache
-                            .remove(&part_number)
-                            .ok_or_else(|| format!("falta URL para parte {}", part_number))?;
-
-                        // `split_to(part_size).freeze()` separa el chunk del buffer en O(1).
-                        // `Bytes::clone` es O(1) (Arc interno), seguro en el closure de retry.
-                        let bytes_to_send = part_buf.split_to(strategy.part_size).freeze();
-
-                        let slot_bytes = wait_for_one_slot(
-                            &mut upload_tasks,
-                            &ctx,
-                            &mut completed_parts,
-                            &mut loaded,
-                            &mut concurrency,
-                        )
-                        .await?;
-
-                        if slot_bytes > 0 {
-                            maybe_emit_progress(
-                                &app,
-                                game_id,
-                                &display_name,
-                                loaded,
-                                estimated_total,
-                                &mut last_pct,
-                                false,
-                            );
-                        }
-
-                        spawn_part_upload(
-                            &mut upload_tasks,
-                            &semaphore,
-                            url,
-                            part_number,
-                            bytes_to_send,
-                        )
-                        .await?;
+                    if part_buf.len() < strategy.part_size {
+                        continue;
+                    }
+
+                    // `split_to(part_size).freeze()` separa el chunk del buffer en O(1).
+                    // `Bytes::clone` es O(1) (Arc interno), seguro en el closure de retry.
+                    let bytes_to_send = part_buf.split_to(strategy.part_size).freeze();
 
+                    // Parte confirmada en un intento anterior, más allá del primer hueco.
+                    if checkpoint.has_part(part_number) {
                         part_number += 1;
+                        continue;
                     }
+
+                    ensure_part_url_cached(
+                        &mut part_urls_cache,
+                        &mut prefetch,
+                        &ctx,
+                        part_number,
+                        strategy.prefetch_threshold,
+                    )
+                    .await?;
+
+                    let target = part_urls_cache
+                        .remove(&part_number)
+                        .ok_or_else(|| format!("falta URL para parte {}", part_number))?;
+
+                    let slot_bytes = wait_for_one_slot(
+                        &mut upload_tasks,
+                        checkpoint,
+                        persist,
+                        loaded,
+                        concurrency,
+                    )
+                    .await?;
+
+                    if slot_bytes > 0 {
+                        maybe_emit_progress(
+                            observer,
+                            game_id,
+                            &display_name,
+                            *loaded,
+                            estimated_total,
+                            &mut last_pct,
+                            false,
+                        );
+                    }
+
+                    spawn_part_upload(
+                        &mut upload_tasks,
+                        &semaphore,
+                        target,
+                        part_number,
+                        bytes_to_send,
+                    )
+                    .await?;
+
+                    part_number += 1;
                 }
             }
             TarStreamMsg::Err(e) => {
                 upload_tasks.abort_all();
-                let _ = multipart_abort(&ctx).await;
-                sync_logger::log_error("full_backup_streaming_error", &log_ctx, &e);
                 return Err(e);
             }
             TarStreamMsg::Done => break,
@@ -758,7 +811,7 @@ pub(crate) async fn upload_tar_stream_multipart(
     }
 
     // Subir la última parte si quedaron bytes sin enviar (tamaño < part_size).
-    if !part_buf.is_empty() {
+    if !part_buf.is_empty() && !checkpoint.has_part(part_number) {
         ensure_part_url_cached(
             &mut part_urls_cache,
             &mut prefetch,
@@ -768,7 +821,7 @@ pub(crate) async fn upload_tar_stream_multipart(
         )
         .await?;
 
-        let url = part_urls_cache
+        let target = part_urls_cache
             .remove(&part_number)
             .ok_or_else(|| format!("falta URL para parte {}", part_number))?;
 
@@ -776,7 +829,7 @@ pub(crate) async fn upload_tar_stream_multipart(
         spawn_part_upload(
             &mut upload_tasks,
             &semaphore,
-            url,
+            target,
             part_number,
             bytes_to_send,
         )
@@ -784,35 +837,18 @@ pub(crate) async fn upload_tar_stream_multipart(
     }
 
     // Drenar todas las tareas restantes propagando cualquier error.
-    let remaining = drain_upload_tasks(&mut upload_tasks, &ctx).await?;
-    for (pn, etag, bytes_sent, elapsed_ms) in remaining {
-        completed_parts.push((pn, etag));
-        loaded += bytes_sent;
-        concurrency.record_part(bytes_sent, elapsed_ms);
-    }
+    drain_upload_tasks(&mut upload_tasks, checkpoint, persist, loaded, concurrency).await?;
 
     maybe_emit_progress(
-        &app,
+        observer,
         game_id,
         &display_name,
-        loaded,
+        *loaded,
         estimated_total,
         &mut last_pct,
         true,
     );
 
-    completed_parts.sort_by_key(|p| p.0);
-    with_retry(|| multipart_complete(&ctx, &completed_parts)).await?;
-
-    let final_ctx = format!(
-        "{} | parts={} bytes={} concurrency=[{}]",
-        log_ctx,
-        completed_parts.len(),
-        loaded,
-        concurrency.describe(),
-    );
-    sync_logger::log_operation("full_backup_streaming_complete", &final_ctx);
-
     Ok(())
 }
 
@@ -826,8 +862,8 @@ pub(crate) async fn upload_tar_stream_multipart_dry_run(
     game_id: &str,
     relative_filename: &str,
     estimated_total: u64,
-    app: tauri::AppHandle,
-    cancel: Option<std::sync::Arc<crate::tray::tray_state::TrayStateInner>>,
+    observer: &dyn SyncObserver,
+    cancel: &CancellationToken,
 ) -> Result<(), String> {
     let strategy = UploadStrategy::for_file(estimated_total);
     let display_name = format!("{} (stream dry-run)", relative_filename);
@@ -843,7 +879,7 @@ pub(crate) async fn upload_tar_stream_multipart_dry_run(
     let mut last_pct: u8 = 0;
 
     maybe_emit_progress(
-        &app,
+        observer,
         game_id,
         &display_name,
         0,
@@ -853,23 +889,21 @@ pub(crate) async fn upload_tar_stream_multipart_dry_run(
     );
 
     while let Some(msg) = rx.recv().await {
-        if let Some(ref t) = cancel {
-            if t.upload_cancel_requested() {
-                let ctx = format!("{} | cancelled_after_bytes={}", log_ctx, loaded);
-                sync_logger::log_operation("full_backup_streaming_dry_run_cancelled", &ctx);
-                return Err("subida de prueba cancelada".to_string());
-            }
-            if t.upload_pause_requested() {
-                let ctx = format!("{} | pause_requested_bytes={}", log_ctx, loaded);
-                sync_logger::log_operation("full_backup_streaming_dry_run_pause_ignored", &ctx);
-            }
+        if cancel.is_cancelled() {
+            let ctx = format!("{} | cancelled_after_bytes={}", log_ctx, loaded);
+            sync_logger::log_operation("full_backup_streaming_dry_run_cancelled", &ctx);
+            return Err("subida de prueba cancelada".to_string());
+        }
+        if cancel.is_paused() {
+            let ctx = format!("{} | pause_requested_bytes={}", log_ctx, loaded);
+            sync_logger::log_operation("full_backup_streaming_dry_run_pause_ignored", &ctx);
         }
 
         match msg {
             TarStreamMsg::Chunk(bytes) => {
                 loaded += bytes.len() as u64;
                 maybe_emit_progress(
-                    &app,
+                    observer,
                     game_id,
                     &display_name,
                     loaded,
@@ -888,7 +922,7 @@ pub(crate) async fn upload_tar_stream_multipart_dry_run(
 
     // Emit final forzado para garantizar que el frontend refleja el 100%.
     maybe_emit_progress(
-        &app,
+        observer,
         game_id,
         &display_name,
         loaded,
@@ -902,32 +936,23 @@ pub(crate) async fn upload_tar_stream_multipart_dry_run(
     Ok(())
 }
 
-/// Sube una parte a S3 mediante PUT y devuelve número de parte, ETag,
+/// Sube una parte a su destino y devuelve número de parte, ETag,
 /// bytes enviados y tiempo transcurrido en milisegundos.
 ///
 /// El tiempo se usa en `ConcurrencyController` para calcular el throughput real.
-/// `Content-Length` se incluye explícitamente porque S3 lo requiere en PUTs
-/// de partes multipart; `reqwest` lo deriva del tamaño del `Bytes` automáticamente.
 async fn put_part(
-    url: String,
+    target: &TransferTarget,
     part_number: u32,
     bytes: bytes::Bytes,
 ) -> Result<(u32, String, u64, u128), String> {
     let len = bytes.len() as u64;
     let start = Instant::now();
 
-    let res = DATA_CLIENT
-        .put(&url)
-        .body(bytes)
-        .header("Content-Type", "application/octet-stream")
-        .send()
+    let etag = target
+        .put(bytes, "application/octet-stream")
         .await
         .map_err(|e| format!("PUT parte {}: {}", part_number, e))?;
 
-    if !res.status().is_success() {
-        return Err(format!("parte {}: S3 PUT {}", part_number, res.status()));
-    }
-
     let elapsed_ms = start.elapsed().as_millis();
     let throughput_mbps = if elapsed_ms > 0 {
         (len as f64 * 8.0) / (elapsed_ms as f64 / 1000.0) / 1_000_000.0
@@ -946,12 +971,85 @@ async fn put_part(
         ),
     );
 
-    let etag = res
-        .headers()
-        .get("etag")
-        .and_then(|v| v.to_str().ok())
-        .unwrap_or("")
-        .to_string();
-
     Ok((part_number, etag, len, elapsed_ms))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::sync::observer::tests::RecordingObserver;
+    use crate::commands::sync::streaming::tar_stream::{self, spawn_tar_stream};
+    use crate::testing::mock_api::MockApi;
+    use std::sync::Mutex;
+
+    #[tokio::test]
+    async fn resumes_a_paused_stream_on_the_same_upload() {
+        let api = MockApi::start().await;
+        let storage = Storage::Api(api.backend("user1"));
+        let dir = tempfile::tempdir().unwrap();
+        let data: Vec<u8> = (0..=250u8).cycle().take(6 * 1024 * 1024).collect();
+        std::fs::write(dir.path().join("save.bin"), &data).unwrap();
+        let source = dir.path().to_string_lossy().to_string();
+        let sources = vec![dir.path().to_path_buf()];
+        let fingerprint = tar_stream::source_fingerprint(&sources).unwrap();
+
+        let mut checkpoint = init_stream_checkpoint(
+            &storage,
+            "game",
+            "backups/b.tar",
+            &source,
+            &sources,
+            fingerprint,
+            data.len() as u64,
+        )
+        .await
+        .unwrap();
+        // Partes pequeñas para que la pausa llegue con el TAR a medias.
+        checkpoint.part_size = 256 * 1024;
+
+        // Se pausa en cuanto hay dos partes confirmadas.
+        let cancel = CancellationToken::new();
+        let saved: Mutex<Option<StreamCheckpoint>> = Mutex::new(None);
+        let persist = |cp: &StreamCheckpoint| {
+            if cp.completed_parts.len() >= 2 {
+                cancel.pause();
+            }
+            *saved.lock().unwrap() = Some(cp.clone());
+        };
+        let (rx, handle) = spawn_tar_stream(checkpoint.sources(), 1, 0);
+        let result = upload_tar_stream_multipart(
+            rx,
+            "game",
+            &mut checkpoint,
+            &storage,
+            &RecordingObserver::default(),
+            &cancel,
+            &persist,
+        )
+        .await;
+        let _ = handle.await;
+        assert_eq!(result.unwrap_err(), PAUSED_ERR_MSG);
+        assert_eq!(api.open_uploads(), 1);
+
+        // Reanudar desde lo persistido, como tras reiniciar la app.
+        let mut checkpoint = saved.lock().unwrap().clone().unwrap();
+        assert!(checkpoint.resume_offset() > 0);
+        let (rx, handle) = spawn_tar_stream(checkpoint.sources(), 1, checkpoint.resume_offset());
+        upload_tar_stream_multipart(
+            rx,
+            "game",
+            &mut checkpoint,
+            &storage,
+            &RecordingObserver::default(),
+            &CancellationToken::new(),
+            &|_: &StreamCheckpoint| {},
+        )
+        .await
+        .unwrap();
+        let _ = handle.await;
+
+        let expected = tar_stream::tests::collect_tar(dir.path(), 0).await;
+        assert_eq!(api.object(&checkpoint.key).unwrap(), expected);
+        assert_eq!(api.open_uploads(), 0);
+    }
+}
@@ -18,14 +18,29 @@
 //!
 //! El canal actúa como mecanismo de backpressure, limitando la producción
 //! según su capacidad configurada.
+//!
+//! ## TAR determinista y reanudación
+//!
+//! Las entradas se recorren ordenadas por nombre, así que mientras el origen no
+//! cambie ([`source_fingerprint`]) el TAR generado es idéntico byte a byte. Eso
+//! permite reanudar una subida regenerando el stream desde un desplazamiento:
+//! los bytes anteriores se descartan sin enviarlos y el contenido de los archivos
+//! que caen enteros antes de ese punto ni siquiera se lee del disco.
 
-use std::io::{self, Write};
+use std::cell::Cell;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::UNIX_EPOCH;
 
 use bytes::{BufMut, BytesMut};
+use sha2::{Digest, Sha256};
 use walkdir::WalkDir;
 
 use super::upload_strategy::TAR_STREAM_CHUNK_BYTES;
+use crate::utils::path_utils::{split_glob, wildcard_match};
 
 /// Mensajes que el hilo TAR envía al consumidor async.
 #[derive(Debug)]
@@ -47,14 +62,24 @@ struct ChannelWriter {
     tx: tokio::sync::mpsc::Sender<TarStreamMsg>,
     /// Buffer pre-reservado con capacidad para al menos un chunk completo.
     buf: BytesMut,
+    /// Bytes del TAR generados hasta ahora, enviados o descartados.
+    position: Rc<Cell<u64>>,
+    /// Los bytes anteriores a esta posición ya se subieron: se descartan.
+    skip_until: u64,
 }
 
 impl ChannelWriter {
     /// Crea un nuevo `ChannelWriter` con buffer pre-reservado para un chunk.
-    fn new(tx: tokio::sync::mpsc::Sender<TarStreamMsg>) -> Self {
+    fn new(
+        tx: tokio::sync::mpsc::Sender<TarStreamMsg>,
+        position: Rc<Cell<u64>>,
+        skip_until: u64,
+    ) -> Self {
         Self {
             tx,
             buf: BytesMut::with_capacity(TAR_STREAM_CHUNK_BYTES),
+            position,
+            skip_until,
         }
     }
 
@@ -82,6 +107,16 @@ impl ChannelWriter {
 
 impl Write for ChannelWriter {
     fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        let start = self.position.get();
+        self.position.set(start + data.len() as u64);
+
+        // Tramo ya subido en un intento anterior: no sale del hilo TAR.
+        let skip = self.skip_until.saturating_sub(start).min(data.len() as u64) as usize;
+        let data = &data[skip..];
+        if data.is_empty() {
+            return Ok(skip);
+        }
+
         // Fast path: buffer vacío y bloque entrante supera el umbral.
         // Una única copia directa al heap del `Bytes` final, sin pasar por
         // el buffer intermedio.
@@ -90,7 +125,7 @@ impl Write for ChannelWriter {
             self.tx
                 .blocking_send(TarStreamMsg::Chunk(chunk))
                 .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "receptor descartado"))?;
-            return Ok(data.len());
+            return Ok(skip + data.len());
         }
 
         // Path normal: acumular en el buffer pre-reservado.
@@ -99,7 +134,7 @@ impl Write for ChannelWriter {
         if self.buf.len() >= TAR_STREAM_CHUNK_BYTES {
             self.flush_chunk()?;
         }
-        Ok(data.len())
+        Ok(skip + data.len())
     }
 
     /// Vacía bytes pendientes hacia el canal.
@@ -109,6 +144,196 @@ impl Write for ChannelWriter {
     }
 }
 
+/// Contenido de un archivo para el TAR que no toca el disco mientras se descarta.
+///
+/// `tar-rs` escribe cada bloque leído antes de pedir el siguiente, así que la
+/// posición del [`ChannelWriter`] al leer es justo donde irá el bloque: si cae
+/// entero antes de `skip_until` se rellena con ceros (se descartará igualmente) y
+/// el archivo solo se abre cuando hace falta su contenido real.
+struct ResumableFileReader<'a> {
+    path: &'a Path,
+    file: Option<File>,
+    /// Bytes del archivo ya entregados.
+    offset: u64,
+    /// Tamaño anotado en la cabecera; se entregan exactamente estos bytes.
+    len: u64,
+    position: Rc<Cell<u64>>,
+    skip_until: u64,
+}
+
+impl Read for ResumableFileReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = (buf.len() as u64).min(self.len - self.offset) as usize;
+        if n == 0 {
+            return Ok(0);
+        }
+        if self.position.get() + n as u64 <= self.skip_until {
+            buf[..n].fill(0);
+            self.offset += n as u64;
+            return Ok(n);
+        }
+
+        let file = match &mut self.file {
+            Some(f) => f,
+            None => {
+                let mut f = File::open(self.path)?;
+                f.seek(SeekFrom::Start(self.offset))?;
+                self.file.insert(f)
+            }
+        };
+        let read = file.read(&mut buf[..n])?;
+        if read == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "el archivo encogió mientras se empaquetaba",
+            ));
+        }
+        self.offset += read as u64;
+        Ok(read)
+    }
+}
+
+/// Recorrido del origen en el orden del TAR: sin seguir enlaces y ordenado por nombre
+/// para que dos pasadas sobre la misma carpeta den el mismo resultado.
+fn walk(source_dir: &Path) -> walkdir::IntoIter {
+    WalkDir::new(source_dir)
+        .follow_links(false)
+        .same_file_system(true) // evitar cruzar puntos de montaje (ej. particiones distintas)
+        .sort_by_file_name()
+        .into_iter()
+}
+
+/// Entrada del TAR: ruta en disco y ruta dentro del TAR.
+pub(crate) struct SourceEntry {
+    pub(crate) path: PathBuf,
+    pub(crate) relative: PathBuf,
+    pub(crate) file_type: std::fs::FileType,
+}
+
+/// Entradas a empaquetar, en el orden del TAR.
+///
+/// Cada origen es una carpeta, que se recorre entera con rutas relativas a ella, o un
+/// patrón con comodines en el último segmento (`saves/Juego.*`): sus archivos van a la
+/// raíz del TAR con su nombre, sin el resto de la carpeta compartida. Un archivo suelto
+/// también va a la raíz. Si dos orígenes dan la misma ruta relativa gana el primero.
+pub(crate) fn source_entries(sources: &[PathBuf]) -> Result<Vec<SourceEntry>, String> {
+    let mut seen = HashSet::new();
+    let mut entries = Vec::new();
+    let mut push = |entry: SourceEntry| {
+        if seen.insert(entry.relative.clone()) {
+            entries.push(entry);
+        }
+    };
+
+    for source in sources {
+        if let Some((dir, pattern)) = split_glob(&source.to_string_lossy()) {
+            let Ok(read_dir) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            let mut matches: Vec<_> = read_dir
+                .flatten()
+                .filter(|e| wildcard_match(&pattern, &e.file_name().to_string_lossy()))
+                .filter_map(|e| Some((e.path(), e.file_name(), e.file_type().ok()?)))
+                .filter(|(_, _, file_type)| file_type.is_file())
+                .collect();
+            matches.sort_by(|a, b| a.1.cmp(&b.1));
+            for (path, name, file_type) in matches {
+                push(SourceEntry {
+                    path,
+                    relative: PathBuf::from(name),
+                    file_type,
+                });
+            }
+            continue;
+        }
+
+        let Ok(meta) = source.symlink_metadata() else {
+            continue;
+        };
+        if !meta.is_dir() {
+            if let Some(name) = source.file_name() {
+                push(SourceEntry {
+                    path: source.clone(),
+                    relative: PathBuf::from(name),
+                    file_type: meta.file_type(),
+                });
+            }
+            continue;
+        }
+
+        for entry in walk(source) {
+            let entry = entry.map_err(|e| format!("error recorriendo directorio: {}", e))?;
+            // Ruta relativa a la raíz del TAR. `strip_prefix` elimina el prefijo del
+            // directorio fuente, dejando solo la ruta dentro del archivo TAR.
+            let relative = entry
+                .path()
+                .strip_prefix(source)
+                .map_err(|e| format!("error calculando ruta relativa: {}", e))?;
+
+            // Saltar la entrada raíz "." para evitar una entrada de directorio vacía
+            // al inicio del TAR que algunos extractores interpretan de forma distinta.
+            if relative == Path::new("") || relative == Path::new(".") {
+                continue;
+            }
+            push(SourceEntry {
+                relative: relative.to_path_buf(),
+                file_type: entry.file_type(),
+                path: entry.into_path(),
+            });
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Tamaño aproximado del TAR: suma de los archivos de [`source_entries`].
+pub(crate) fn source_size(sources: &[PathBuf]) -> u64 {
+    source_entries(sources)
+        .unwrap_or_default()
+        .iter()
+        .filter(|e| e.file_type.is_file())
+        .filter_map(|e| e.path.metadata().ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// Huella del contenido a empaquetar: ruta, tipo, tamaño, fecha y permisos de cada
+/// entrada, en el orden del TAR. Si coincide, el TAR regenerado es idéntico al original.
+pub(crate) fn source_fingerprint(sources: &[PathBuf]) -> Result<String, String> {
+    let mut hasher = Sha256::new();
+    for entry in source_entries(sources)? {
+        let meta = entry
+            .path
+            .symlink_metadata()
+            .map_err(|e| format!("error leyendo '{}': {}", entry.path.display(), e))?;
+        let mtime = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+
+        hasher.update(entry.relative.to_string_lossy().as_bytes());
+        hasher.update([
+            0,
+            entry.file_type.is_dir() as u8,
+            meta.permissions().readonly() as u8,
+        ]);
+        hasher.update(meta.len().to_le_bytes());
+        hasher.update(mtime.to_le_bytes());
+        if entry.file_type.is_symlink() {
+            if let Ok(target) = std::fs::read_link(&entry.path) {
+                hasher.update(target.to_string_lossy().as_bytes());
+            }
+        }
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
 /// Lanza la generación del TAR en un hilo blocking y devuelve el receptor de chunks.
 ///
 /// Usa un pipeline manual con `walkdir` en lugar de `append_dir_all` para tener
@@ -116,16 +341,19 @@ impl Write for ChannelWriter {
 ///
 /// # Parameters
 ///
-/// - `source_dir`: directorio raíz a empaquetar. Se toma posesión para `'static`.
+/// - `sources`: carpetas o patrones a empaquetar (ver [`source_entries`]). Se toma
+///   posesión para `'static`.
 /// - `channel_capacity`: capacidad del canal mpsc. Debe ser `strategy.tar_channel_capacity`.
+/// - `skip_until`: byte del TAR desde el que se envían chunks (0 para el stream completo).
 ///
 /// # Return
 ///
 /// `(Receiver<TarStreamMsg>, JoinHandle<()>)`. El canal se cierra con
 /// [`TarStreamMsg::Done`] en el camino feliz o [`TarStreamMsg::Err`] ante fallo.
 pub(crate) fn spawn_tar_stream(
-    source_dir: PathBuf,
+    sources: Vec<PathBuf>,
     channel_capacity: usize,
+    skip_until: u64,
 ) -> (
     tokio::sync::mpsc::Receiver<TarStreamMsg>,
     tokio::task::JoinHandle<()>,
@@ -133,7 +361,7 @@ pub(crate) fn spawn_tar_stream(
     let (tx, rx) = tokio::sync::mpsc::channel::<TarStreamMsg>(channel_capacity);
 
     let handle = tokio::task::spawn_blocking(move || {
-        match run_tar_pipeline(&source_dir, tx.clone()) {
+        match run_tar_pipeline(&sources, tx.clone(), skip_until) {
             Ok(()) => {
                 let _ = tx.blocking_send(TarStreamMsg::Done);
             }
@@ -147,7 +375,7 @@ pub(crate) fn spawn_tar_stream(
     (rx, handle)
 }
 
-/// Empaqueta `source_dir` en formato TAR mediante un pipeline manual con `walkdir`.
+/// Empaqueta `sources` en formato TAR mediante un pipeline manual con `walkdir`.
 ///
 /// En vez de delegar el recorrido a `append_dir_all`, itera explícitamente sobre
 /// las entradas del directorio y llama a `append_file` o `append_dir` según el tipo
@@ -160,63 +388,57 @@ pub(crate) fn spawn_tar_stream(
 /// Los symlinks se preservan como entradas TAR de tipo enlace simbólico en vez de
 /// seguirlos, reduciendo el tamaño del TAR en directorios con muchos enlaces.
 fn run_tar_pipeline(
-    source_dir: &Path,
+    sources: &[PathBuf],
     tx: tokio::sync::mpsc::Sender<TarStreamMsg>,
+    skip_until: u64,
 ) -> Result<(), String> {
-    let writer = ChannelWriter::new(tx);
+    let position = Rc::new(Cell::new(0u64));
+    let writer = ChannelWriter::new(tx, position.clone(), skip_until);
     let mut builder = tar::Builder::new(writer);
     builder.follow_symlinks(false);
 
-    // `WalkDir` itera en orden DFS. `min_depth(0)` incluye el directorio raíz
-    // como primera entrada, necesario para que el TAR tenga la entrada de directorio
-    // antes que sus contenidos (comportamiento equivalente a `append_dir_all`).
-    let walker = WalkDir::new(source_dir)
-        .follow_links(false)
-        .same_file_system(true) // evitar cruzar puntos de montaje (ej. particiones distintas)
-        .into_iter();
-
-    for entry_result in walker {
-        let entry = entry_result.map_err(|e| format!("error recorriendo directorio: {}", e))?;
-
-        // Ruta relativa a la raíz del TAR. `strip_prefix` elimina el prefijo del
-        // directorio fuente, dejando solo la ruta dentro del archivo TAR.
-        let relative = entry
-            .path()
-            .strip_prefix(source_dir)
-            .map_err(|e| format!("error calculando ruta relativa: {}", e))?;
-
-        // Saltar la entrada raíz "." para evitar una entrada de directorio vacía
-        // al inicio del TAR que algunos extractores interpretan de forma distinta.
-        if relative == Path::new("") || relative == Path::new(".") {
-            continue;
-        }
-
-        let file_type = entry.file_type();
+    // `source_entries` sigue el orden DFS de `WalkDir`, así que cada directorio tiene
+    // su entrada antes que sus contenidos (comportamiento equivalente a `append_dir_all`).
+    for entry in source_entries(sources)? {
+        let relative = entry.relative.as_path();
+        let file_type = entry.file_type;
 
         if file_type.is_dir() {
             // Las entradas de directorio solo escriben la cabecera TAR (512 bytes).
             // No hay datos que leer del disco, así que el backpressure solo aplica
             // cuando el buffer del `ChannelWriter` se llena con muchas cabeceras.
             builder
-                .append_dir(relative, entry.path())
+                .append_dir(relative, &entry.path)
                 .map_err(|e| format!("error empaquetando dir '{}': {}", relative.display(), e))?;
         } else if file_type.is_file() {
-            // `append_path_with_name` lee el archivo desde la ruta del sistema de
-            // archivos y lo escribe en el TAR con la ruta relativa calculada arriba.
-            // Esta es la operación costosa en I/O: lee el archivo en bloques y los
-            // pasa a `ChannelWriter::write`, que aplica backpressure si el canal está lleno.
-            let mut file = std::fs::File::open(entry.path())
-                .map_err(|e| format!("error abriendo '{}': {}", entry.path().display(), e))?;
+            // Misma cabecera que `append_file`, pero el contenido llega por un lector que
+            // no lee del disco el tramo ya subido. Esta es la operación costosa en I/O:
+            // lee el archivo en bloques y los pasa a `ChannelWriter::write`, que aplica
+            // backpressure si el canal está lleno.
+            let meta = entry
+                .path
+                .metadata()
+                .map_err(|e| format!("error leyendo '{}': {}", entry.path.display(), e))?;
+            let mut header = tar::Header::new_gnu();
+            header.set_metadata_in_mode(&meta, tar::HeaderMode::Complete);
+            let reader = ResumableFileReader {
+                path: &entry.path,
+                file: None,
+                offset: 0,
+                len: meta.len(),
+                position: position.clone(),
+                skip_until,
+            };
 
             builder
-                .append_file(relative, &mut file)
+                .append_data(&mut header, relative, reader)
                 .map_err(|e| format!("error empaquetando '{}': {}", relative.display(), e))?;
         } else if file_type.is_symlink() {
             // Los symlinks se preservan usando `append_path` con `follow_symlinks(false)`.
             // `tar-rs` leerá el destino del enlace con `std::fs::read_link` y escribirá
             // una cabecera TAR de tipo enlace simbólico sin leer el archivo destino.
             builder
-                .append_path_with_name(entry.path(), relative)
+                .append_path_with_name(&entry.path, relative)
                 .map_err(|e| {
                     format!("error empaquetando symlink '{}': {}", relative.display(), e)
                 })?;
@@ -240,3 +462,73 @@ fn run_tar_pipeline(
 
     Ok(())
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Genera el TAR de `dir` desde `skip_until` y lo devuelve entero.
+    pub(crate) async fn collect_tar(dir: &Path, skip_until: u64) -> Vec<u8> {
+        let (mut rx, handle) = spawn_tar_stream(vec![dir.to_path_buf()], 4, skip_until);
+        let mut out = Vec::new();
+        while let Some(msg) = rx.recv().await {
+            match msg {
+                TarStreamMsg::Chunk(bytes) => out.extend_from_slice(&bytes),
+                TarStreamMsg::Err(e) => panic!("{}", e),
+                TarStreamMsg::Done => break,
+            }
+        }
+        handle.await.unwrap();
+        out
+    }
+
+    #[tokio::test]
+    async fn regenerates_the_same_tar_from_an_offset() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        let data: Vec<u8> = (0..=250u8)
+            .cycle()
+            .take(3 * TAR_STREAM_CHUNK_BYTES)
+            .collect();
+        std::fs::write(dir.path().join("sub/save.bin"), &data).unwrap();
+        std::fs::write(dir.path().join("a.cfg"), b"volumen=7").unwrap();
+
+        let full = collect_tar(dir.path(), 0).await;
+        assert_eq!(collect_tar(dir.path(), 0).await, full);
+        for skip in [512, 5000, TAR_STREAM_CHUNK_BYTES as u64 + 123] {
+            let tail = collect_tar(dir.path(), skip).await;
+            assert_eq!(tail, full[skip as usize..]);
+        }
+
+        let sources = [dir.path().to_path_buf()];
+        let before = source_fingerprint(&sources).unwrap();
+        assert_eq!(source_fingerprint(&sources).unwrap(), before);
+        std::fs::write(dir.path().join("a.cfg"), b"volumen=8!").unwrap();
+        assert_ne!(source_fingerprint(&sources).unwrap(), before);
+    }
+
+    #[test]
+    fn glob_sources_only_pack_matching_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let saves = dir.path().join("saves");
+        let states = dir.path().join("states");
+        std::fs::create_dir_all(saves.join("sub")).unwrap();
+        std::fs::create_dir_all(&states).unwrap();
+        std::fs::write(saves.join("Juego.srm"), b"a").unwrap();
+        std::fs::write(saves.join("Otro.srm"), b"b").unwrap();
+        std::fs::write(states.join("Juego.state1"), b"c").unwrap();
+        std::fs::write(states.join("Juego.state"), b"d").unwrap();
+
+        let sources = [saves.join("Juego.*"), states.join("Juego.state*")];
+        let names: Vec<String> = source_entries(&sources)
+            .unwrap()
+            .iter()
+            .map(|e| e.relative.to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, ["Juego.srm", "Juego.state", "Juego.state1"]);
+        assert_eq!(source_size(&sources), 3);
+
+        let whole = source_entries(&[saves]).unwrap();
+        assert_eq!(whole.len(), 3);
+    }
+}
@@ -18,12 +18,16 @@
 //!   la cantidad de solicitudes al backend.
 //! - Se aplican reintentos con backoff en operaciones críticas.
 //! - Se configuran timeouts a nivel de conexión y de request en el cliente HTTP.
-use super::api;
+use super::cancellation::CancellationToken;
 use super::models::{GameSyncResultDto, SyncProgressPayload, SyncResultDto};
 use super::multipart_upload;
-use crate::utils::path_utils;
-use crate::network::DATA_CLIENT;
+use super::observer::SyncObserver;
+use super::paused;
+use crate::plugins::hooks;
+use crate::storage::{Storage, StorageBackend};
+use crate::transfers::{self, TransferKind, TransferPriority};
 use crate::tray::tray_state::TrayState;
+use crate::utils::path_translation;
 use bytes::Bytes;
 use futures_util::stream::{self, Stream, StreamExt};
 use std::collections::HashMap;
@@ -66,7 +70,7 @@ impl Stream for FileProgressStream {
 fn file_stream_with_progress(
     absolute: &std::path::Path,
     total: u64,
-    app: AppHandle,
+    observer: std::sync::Arc<dyn SyncObserver>,
     game_id: String,
     filename: String,
 ) -> Result<FileProgressStream, String> {
@@ -96,15 +100,12 @@ fn file_stream_with_progress(
                         || (total > 0 && loaded >= total)
                     {
                         last_emit = loaded;
-                        let _ = app.emit(
-                            "sync-upload-progress",
-                            SyncProgressPayload {
-                                game_id: game_id.clone(),
-                                filename: filename.clone(),
-                                loaded,
-                                total,
-                            },
-                        );
+                        observer.upload_progress(SyncProgressPayload {
+                            game_id: game_id.clone(),
+                            filename: filename.clone(),
+                            loaded,
+                            total,
+                        });
                     }
 
                     let chunk = Bytes::from(buf[..n].to_vec());
@@ -120,41 +121,51 @@ fn file_stream_with_progress(
         }
 
         if loaded > 0 && loaded < total {
-            let _ = app.emit(
-                "sync-upload-progress",
-                SyncProgressPayload {
-                    game_id,
-                    filename,
-                    loaded: total,
-                    total,
-                },
-            );
+            observer.upload_progress(SyncProgressPayload {
+                game_id,
+                filename,
+                loaded: total,
+                total,
+            });
         }
     });
     Ok(FileProgressStream { rx })
 }
 
-/// Solicita cancelar la subida en curso (solo tiene efecto en subidas multipart entre partes).
+/// Subidas a las que afectan los botones globales de pausar y cancelar.
+const UPLOAD_KINDS: [TransferKind; 2] = [TransferKind::Upload, TransferKind::FullBackup];
+
+/// Solicita cancelar todas las subidas en curso (solo tiene efecto en subidas multipart entre
+/// partes). Para una sola, `cancel_transfer`.
 #[tauri::command]
-pub fn request_upload_cancel(tray_state: State<'_, TrayState>) {
-    tray_state.0.request_upload_cancel();
+pub fn request_upload_cancel() {
+    transfers::global().cancel_kinds(&UPLOAD_KINDS);
 }
 
-/// Solicita pausar la subida en curso. Se guarda el estado en disco y se puede reanudar con sync_upload_resume.
+/// Solicita pausar todas las subidas en curso. Se guarda el estado en disco y se puede reanudar con
+/// sync_upload_resume. Para una sola, `pause_transfer`.
 #[tauri::command]
-pub fn request_upload_pause(tray_state: State<'_, TrayState>) {
-    tray_state.0.request_upload_pause();
+pub fn request_upload_pause() {
+    transfers::global().pause_kinds(&UPLOAD_KINDS);
 }
 
-/// Devuelve la info de la subida pausada, si existe (para mostrar "Reanudar" en la UI).
+/// Subida pausada más reciente, si existe (para mostrar "Reanudar" en la UI). Para ver todas,
+/// `list_paused_transfers`.
 #[tauri::command]
 pub fn get_paused_upload_info() -> Option<PausedUploadInfoDto> {
-    multipart_upload::load_paused_state().map(|s| PausedUploadInfoDto {
-        game_id: s.game_id,
-        filename: s.filename,
+    latest_paused_upload().map(|r| PausedUploadInfoDto {
+        game_id: r.game_id,
+        filename: r.label,
     })
 }
 
+fn latest_paused_upload() -> Option<paused::PausedTransfer> {
+    paused::list()
+        .into_iter()
+        .rev()
+        .find(|r| UPLOAD_KINDS.contains(&r.kind))
+}
+
 #[derive(serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PausedUploadInfoDto {
@@ -162,16 +173,17 @@ pub struct PausedUploadInfoDto {
     pub filename: String,
 }
 
-/// Reanuda la subida multipart guardada (tras pausar). Solo hay una subida pausada a la vez.
+/// Reanuda la subida pausada más reciente. Para elegir cuál, `resume_paused_transfer`.
 #[tauri::command]
 pub async fn sync_upload_resume(app: AppHandle) -> Result<SyncResultDto, String> {
+    let record = latest_paused_upload().ok_or("No hay ninguna subida pausada")?;
     let tray_state = app.state::<TrayState>();
-    tray_state.0.reset_upload_cancel();
-    tray_state.0.reset_upload_pause();
     tray_state.0.syncing_inc();
     tray_state.0.update_tooltip();
 
-    let result = multipart_upload::resume_paused_upload(app.clone()).await;
+    let transfer = transfers::begin(record.kind, &record.game_id, TransferPriority::Normal).await;
+    let result = paused::resume_impl(record, &transfer.observer(&app), transfer.token()).await;
+    drop(transfer);
 
     tray_state.0.syncing_dec();
     tray_state.0.clone().refresh_unsynced_async();
@@ -194,9 +206,9 @@ pub async fn sync_upload_game(
     tray_state.0.update_tooltip();
     tray_state.0.clear_restore_cooldown(&game_id);
 
-    tray_state.0.reset_upload_cancel();
-    tray_state.0.reset_upload_pause();
-    let result = sync_upload_game_impl(game_id, app.clone(), Some(tray_state.0.clone())).await;
+    let transfer = transfers::begin(TransferKind::Upload, &game_id, TransferPriority::Normal).await;
+    let result = sync_upload_game_impl(game_id, &transfer.observer(&app), transfer.token()).await;
+    drop(transfer);
 
     tray_state.0.syncing_dec();
     tray_state.0.clone().refresh_unsynced_async();
@@ -207,8 +219,8 @@ pub async fn sync_upload_game(
 
 pub(crate) async fn sync_upload_game_impl(
     game_id: String,
-    app: AppHandle,
-    tray_inner: Option<std::sync::Arc<crate::tray::tray_state::TrayStateInner>>,
+    observer: &dyn SyncObserver,
+    cancel: &CancellationToken,
 ) -> Result<SyncResultDto, String> {
     let cfg = crate::config::load_config();
     let game = cfg
@@ -224,19 +236,10 @@ pub(crate) async fn sync_upload_game_impl(
         ));
     }
 
-    let api_base = cfg
-        .api_base_url
-        .as_deref()
-        .filter(|s| !s.trim().is_empty())
-        .ok_or("Configura apiBaseUrl en Configuración")?;
-    let user_id = cfg
-        .user_id
-        .as_deref()
-        .filter(|s| !s.trim().is_empty())
-        .ok_or("Configura userId en Configuración")?;
-    let api_key = cfg.api_key.as_deref().unwrap_or("");
-
-    let files = path_utils::list_all_files_from_paths(&game.paths);
+    let storage = Storage::active()?;
+    crate::devices::registry::check_in(&storage).await?;
+
+    let files = path_translation::list_game_files(game);
     if files.is_empty() {
         return Ok(SyncResultDto {
             ok_count: 0,
@@ -255,6 +258,20 @@ pub(crate) async fn sync_upload_game_impl(
         })
         .collect::<Result<Vec<_>, String>>()?;
 
+    // Los plugins pueden cancelar la subida o dejar archivos fuera.
+    let skip = hooks::before_upload(game, &files_with_size).await?;
+    let files_with_size: Vec<_> = files_with_size
+        .into_iter()
+        .filter(|((_, relative), _)| !skip.contains(relative))
+        .collect();
+    if files_with_size.is_empty() {
+        return Ok(SyncResultDto {
+            ok_count: 0,
+            err_count: 0,
+            errors: vec!["Los plugins dejaron fuera todos los archivos del juego".into()],
+        });
+    }
+
     let file_count = files_with_size.len();
     let total_size: u64 = files_with_size.iter().map(|(_, s)| s).sum();
 
@@ -266,19 +283,25 @@ pub(crate) async fn sync_upload_game_impl(
         ));
     }
 
+    // Sin multipart (WebDAV) todos los archivos se suben enteros en un único PUT.
+    let multipart_threshold = if storage.supports_multipart() {
+        multipart_upload::MULTIPART_THRESHOLD
+    } else {
+        u64::MAX
+    };
     let (multipart_files, simple_files): (Vec<_>, Vec<_>) = files_with_size
         .into_iter()
-        .partition(|(_, size)| *size >= multipart_upload::MULTIPART_THRESHOLD);
+        .partition(|(_, size)| *size >= multipart_threshold);
 
     let mut ok_count = 0u32;
     let mut err_count = 0u32;
     let mut errors = Vec::new();
+    // Archivos subidos por completo, para anotar su procedencia.
+    let mut uploaded: Vec<String> = Vec::new();
 
     for ((absolute, relative), total) in multipart_files {
-        if let Some(ref t) = tray_inner {
-            if t.upload_pause_requested() || t.upload_cancel_requested() {
-                break;
-            }
+        if cancel.should_stop() {
+            break;
         }
 
         match multipart_upload::upload_one_file_multipart(
@@ -286,28 +309,21 @@ pub(crate) async fn sync_upload_game_impl(
             &relative,
             total,
             &game_id,
-            api_base,
-            user_id,
-            api_key,
-            app.clone(),
-            tray_inner.clone(),
+            TransferKind::Upload,
+            &storage,
+            observer,
+            cancel,
         )
         .await
         {
             Ok(()) => {
                 ok_count += 1;
+                uploaded.push(relative.clone());
                 let now = filetime::FileTime::from_system_time(std::time::SystemTime::now());
                 let _ = filetime::set_file_mtime(std::path::Path::new(&absolute), now);
             }
             Err(e) => {
                 if e == multipart_upload::PAUSED_ERR_MSG {
-                    let _ = app.emit(
-                        "sync-upload-paused",
-                        serde_json::json!({
-                            "gameId": game_id,
-                            "filename": relative,
-                        }),
-                    );
                     break;
                 } else {
                     crate::commands::logs::sync_logger::log_error(
@@ -324,24 +340,23 @@ pub(crate) async fn sync_upload_game_impl(
         }
     }
 
-    if let Some(ref t) = tray_inner {
-        if t.upload_pause_requested() || t.upload_cancel_requested() {
-            let result = SyncResultDto {
-                ok_count,
-                err_count,
-                errors,
-            };
-            let _ = crate::config::append_operation_log(
-                "upload",
-                &game_id,
-                result.ok_count,
-                result.err_count,
-            );
-            return Ok(result);
-        }
+    if cancel.should_stop() {
+        let result = SyncResultDto {
+            ok_count,
+            err_count,
+            errors,
+        };
+        crate::devices::provenance::record_upload(&storage, &game_id, &uploaded).await;
+        let _ = crate::config::append_operation_log(
+            "upload",
+            &game_id,
+            result.ok_count,
+            result.err_count,
+        );
+        hooks::after_upload(game, &result).await;
+        return Ok(result);
     }
 
-    const UPLOAD_URLS_BATCH_SIZE: usize = 500;
     if !simple_files.is_empty() {
         let total_simple = simple_files.len();
         crate::commands::logs::sync_logger::log_operation(
@@ -350,29 +365,18 @@ pub(crate) async fn sync_upload_game_impl(
         );
 
         let filenames: Vec<String> = simple_files.iter().map(|((_, r), _)| r.clone()).collect();
-        let mut upload_urls = Vec::with_capacity(filenames.len());
-
-        for chunk in filenames.chunks(UPLOAD_URLS_BATCH_SIZE) {
-            let batch = api::get_upload_urls(api_base, user_id, api_key, &game_id, chunk)
-                .await
-                .map_err(|e| {
-                    crate::commands::logs::sync_logger::log_error(
-                        "upload_urls",
-                        &format!("gameId={}", game_id),
-                        &e,
-                    );
-                    format!("upload-urls: {}", e)
-                })?;
-            upload_urls.extend(batch);
-        }
-
-        if upload_urls.len() != total_simple {
-            return Err(format!(
-                "API devolvió {} URLs para {} archivos",
-                upload_urls.len(),
-                total_simple
-            ));
-        }
+        let upload_targets = storage
+            .put_targets(&game_id, &filenames)
+            .await
+            .map_err(|e| {
+                let e = e.to_string();
+                crate::commands::logs::sync_logger::log_error(
+                    "upload_urls",
+                    &format!("gameId={}", game_id),
+                    &e,
+                );
+                format!("upload-urls: {}", e)
+            })?;
 
         crate::commands::logs::sync_logger::log_operation(
             "upload_simple_urls_ok",
@@ -381,50 +385,34 @@ pub(crate) async fn sync_upload_game_impl(
 
         let items: Vec<_> = simple_files
             .into_iter()
-            .zip(upload_urls)
-            .map(|(((abs, rel), total), (url, _))| (abs, rel, total, url))
+            .zip(upload_targets)
+            .map(|(((abs, rel), _), target)| (abs, rel, target))
             .collect();
 
         let mut put_count: usize = 0;
         let mut stream = stream::iter(items)
-            .map(|(absolute, relative, total, upload_url)| async move {
-                let body = match tokio::fs::read(&absolute).await {
-                    Ok(b) => b,
-                    Err(e) => {
-                        return Err((relative.clone(), absolute, format!("{}: {}", relative, e)))
-                    }
-                };
-
-                let put_res = match DATA_CLIENT
-                    .put(&upload_url)
-                    .body(body)
-                    .header("Content-Type", "application/octet-stream")
-                    .header("Content-Length", total.to_string())
-                    .send()
+            .map(|(absolute, relative, target)| async move {
+                match target
+                    .put_file(std::path::Path::new(&absolute), "application/octet-stream")
                     .await
                 {
-                    Ok(r) => r,
+                    Ok(_) => {
+                        let now =
+                            filetime::FileTime::from_system_time(std::time::SystemTime::now());
+                        let _ = filetime::set_file_mtime(std::path::Path::new(&absolute), now);
+                        Ok(relative)
+                    }
                     Err(e) => {
-                        return Err((relative.clone(), absolute, format!("{}: {}", relative, e)))
+                        let msg = format!("{}: {}", relative, e);
+                        Err((relative, absolute, msg))
                     }
-                };
-
-                if put_res.status().is_success() {
-                    let now = filetime::FileTime::from_system_time(std::time::SystemTime::now());
-                    let _ = filetime::set_file_mtime(std::path::Path::new(&absolute), now);
-                    Ok(())
-                } else {
-                    let msg = format!("{}: S3 PUT {}", relative, put_res.status());
-                    Err((relative, absolute, msg))
                 }
             })
             .buffer_unordered(SIMPLE_PUT_CONCURRENCY);
 
         while let Some(result) = stream.next().await {
-            if let Some(ref t) = tray_inner {
-                if t.upload_pause_requested() || t.upload_cancel_requested() {
-                    break;
-                }
+            if cancel.should_stop() {
+                break;
             }
 
             put_count += 1;
@@ -436,7 +424,10 @@ pub(crate) async fn sync_upload_game_impl(
             }
 
             match result {
-                Ok(()) => ok_count += 1,
+                Ok(relative) => {
+                    ok_count += 1;
+                    uploaded.push(relative);
+                }
                 Err((relative, absolute, err_msg)) => {
                     crate::commands::logs::sync_logger::log_error(
                         "upload_put",
@@ -465,15 +456,14 @@ pub(crate) async fn sync_upload_game_impl(
         errors,
     };
 
+    crate::devices::provenance::record_upload(&storage, &game_id, &uploaded).await;
     let _ =
         crate::config::append_operation_log("upload", &game_id, result.ok_count, result.err_count);
+    hooks::after_upload(game, &result).await;
 
     Ok(result)
 }
 
-/// Número de juegos que se suben en paralelo en "subir todos".
-const UPLOAD_BATCH_CONCURRENCY: usize = 4;
-
 /// Sube los guardados de todos los juegos configurados (operación batch, varios juegos en paralelo).
 #[tauri::command]
 pub async fn sync_upload_all_games(
@@ -481,20 +471,9 @@ pub async fn sync_upload_all_games(
     tray_state: State<'_, TrayState>,
 ) -> Result<Vec<GameSyncResultDto>, String> {
     let cfg = crate::config::load_config();
-    let _ = cfg
-        .api_base_url
-        .as_deref()
-        .filter(|s| !s.trim().is_empty())
-        .ok_or("Configura apiBaseUrl en Configuración")?;
-    let _ = cfg
-        .user_id
-        .as_deref()
-        .filter(|s| !s.trim().is_empty())
-        .ok_or("Configura userId en Configuración")?;
+    Storage::active()?;
 
     tray_state.0.syncing_inc();
-    tray_state.0.reset_upload_cancel();
-    tray_state.0.reset_upload_pause();
     tray_state.0.update_tooltip();
 
     let mut results_by_id: HashMap<String, GameSyncResultDto> = HashMap::new();
@@ -530,17 +509,25 @@ pub async fn sync_upload_all_games(
         tray_state.0.clear_restore_cooldown(game_id);
     }
 
-    let tray_inner = Some(tray_state.0.clone());
+    // Todos los juegos entran a la vez en el gestor de transferencias, que pone el límite.
+    let batch_size = to_sync.len().max(1);
     let completed: Vec<(String, Result<SyncResultDto, String>)> = stream::iter(to_sync)
         .map(|game_id| {
-            let app = app.clone();
-            let inner = tray_inner.clone();
+            let app = &app;
             async move {
-                let r = sync_upload_game_impl(game_id.clone(), app, inner).await;
+                let transfer =
+                    transfers::begin(TransferKind::Upload, &game_id, TransferPriority::Normal)
+                        .await;
+                let r = sync_upload_game_impl(
+                    game_id.clone(),
+                    &transfer.observer(app),
+                    transfer.token(),
+                )
+                .await;
                 (game_id, r)
             }
         })
-        .buffer_unordered(UPLOAD_BATCH_CONCURRENCY)
+        .buffer_unordered(batch_size)
         .collect()
         .await;
 
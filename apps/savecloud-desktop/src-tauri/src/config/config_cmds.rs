@@ -4,56 +4,24 @@
 //! almacenamiento S3, importación/exportación de estado y manipulaciones
 //! del árbol de juegos.
 
-use crate::commands::sync::api::{
-    api_request, sync_list_remote_saves, sync_list_remote_saves_for_user,
-};
+use crate::commands::sync::api::{sync_list_remote_saves, sync_list_remote_saves_for_user};
 use crate::config::gamification::GamificationStateDto;
-use crate::config::{self, Config, ConfigDto, ConfiguredGame, GameDto, OperationLogEntryDto};
+use crate::config::{
+    self, Config, ConfigDto, ConfiguredGame, GameDto, IdleDetectionSettings, OperationLogEntryDto,
+    ProcessRule, StorageBackendConfig,
+};
+use crate::devices::session::{self, SessionLockDto};
+use crate::sqlite::AppDb;
 use crate::steam;
+use crate::storage::{Storage, StorageBackend};
 use crate::time;
-use crate::utils::launch_exe;
+use crate::utils::{launch_exe, path_translation};
 use base64::Engine;
 use chrono::Utc;
-use regex::Regex;
+use serde::Serialize;
 use std::fs;
-use std::path::{Path, PathBuf};
-
-/// Resuelve interpolaciones del sistema y variables de entorno dentro de una ruta.
-///
-/// # Arguments
-///
-/// * `raw` - Ruta original conteniendo posibles variables (ej. `%APPDATA%` o `~`).
-///
-/// # Returns
-///
-/// Devuelve el `PathBuf` resuelto si la transformación resulta en una ruta válida.
-fn expand_path(raw: &str) -> Option<PathBuf> {
-    let mut result = raw.to_string();
-    let re = Regex::new(r"%([^%]+)%").ok()?;
-    for cap in re.captures_iter(raw) {
-        let var = cap.get(1)?.as_str();
-        let val = std::env::var(var).unwrap_or_default();
-        result = result.replace(&format!("%{}%", var), &val);
-    }
-    if result.starts_with('~') {
-        let home = std::env::var("USERPROFILE")
-            .or_else(|_| std::env::var("HOME"))
-            .unwrap_or_default();
-        if !home.is_empty() {
-            let rest = result.trim_start_matches('~').trim_start_matches('/');
-            result = if rest.is_empty() {
-                home
-            } else {
-                format!("{}/{}", home.trim_end_matches(&['/', '\\']), rest)
-            };
-        }
-    }
-    if result.is_empty() {
-        None
-    } else {
-        Some(PathBuf::from(result))
-    }
-}
+use std::path::Path;
+use tauri::State;
 
 /// Extrae y compone el objeto de configuración principal para ser entregado a la UI.
 ///
@@ -86,6 +54,8 @@ pub fn get_config() -> ConfigDto {
             .as_ref()
             .filter(|k| !k.trim().is_empty())
             .map(|_| config::MASKED_STEAM_WEB_API_KEY.to_string()),
+        storage: settings.storage.clone(),
+        idle_detection: settings.idle_detection.clone(),
         games: combined
             .games
             .into_iter()
@@ -107,6 +77,8 @@ pub fn get_config() -> ConfigDto {
                     magnet_link: g.magnet_link,
                     executable_names: g.executable_names.clone(),
                     launch_executable_path: g.launch_executable_path.clone(),
+                    install_dir: g.install_dir.clone(),
+                    process_rules: g.process_rules.clone(),
                     playtime_seconds: g.playtime_seconds,
                 }
             })
@@ -134,6 +106,7 @@ pub fn list_operation_history() -> Vec<OperationLogEntryDto> {
             game_id: e.game_id,
             file_count: e.file_count,
             err_count: e.err_count,
+            device_name: e.device_name,
         })
         .collect()
 }
@@ -208,6 +181,47 @@ pub fn set_full_backup_streaming_dry_run(enabled: bool) -> Result<(), String> {
     config::save_settings(&settings)
 }
 
+/// Configura la pausa del tiempo de juego por inactividad.
+#[tauri::command]
+pub fn set_idle_detection(settings: IdleDetectionSettings) -> Result<(), String> {
+    if settings.idle_minutes == 0 {
+        return Err("El tiempo de inactividad debe ser de al menos un minuto".to_string());
+    }
+    let mut current = config::load_settings();
+    current.idle_detection = settings;
+    config::save_settings(&current)
+}
+
+/// Cambia el backend de almacenamiento del perfil.
+///
+/// Si el secreto llega vacío o enmascarado se usa el guardado para ese tipo de backend,
+/// para que la UI pueda reenviar el formulario (o volver a un backend anterior) sin
+/// conocer la clave.
+///
+/// # Errors
+///
+/// Devuelve `Err` si la configuración resultante no permite construir el backend.
+#[tauri::command]
+pub fn set_storage_backend(mut storage: StorageBackendConfig) -> Result<(), String> {
+    let mut settings = config::load_settings();
+
+    let stored = config::get_secure_storage_secret(&storage);
+    if let Some(secret) = storage.secret_mut() {
+        let keep = secret
+            .as_deref()
+            .is_none_or(|s| s.trim().is_empty() || s == config::MASKED_API_KEY);
+        if keep {
+            *secret = stored;
+        }
+    }
+
+    settings.storage = storage;
+    Storage::from_settings(&settings)?;
+    config::save_settings(&settings)?;
+    crate::commands::sync::api::clear_s3_endpoint_cache();
+    Ok(())
+}
+
 /// Persiste la apariencia del perfil (fondo, avatar, marco). Cadenas vacías o `None` borran el valor.
 #[tauri::command]
 pub fn set_profile_appearance(
@@ -285,6 +299,8 @@ pub fn add_game(
             source_url: trim_opt(source_url),
             magnet_link: None,
             launch_executable_path: None,
+            install_dir: None,
+            process_rules: None,
             playtime_seconds: 0,
         });
     }
@@ -398,9 +414,35 @@ pub fn list_running_process_exe_names() -> Vec<String> {
     crate::system::process_check::list_running_process_exe_names()
 }
 
+/// Resultado de [`launch_game`].
+#[derive(Debug, Serialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum LaunchGameOutcome {
+    Launched {
+        /// Sesión de otro dispositivo que terminó sin subir: no impide jugar, pero sus
+        /// últimos guardados pueden no estar en la nube.
+        #[serde(rename = "staleLock")]
+        stale_lock: Option<SessionLockDto>,
+    },
+    /// Otro dispositivo tiene el juego abierto; no se ha lanzado.
+    LockWarning {
+        lock: SessionLockDto,
+        message: String,
+    },
+}
+
 /// Inicia el ejecutable configurado para este juego (ruta absoluta guardada en config).
+///
+/// Si otro dispositivo tiene una sesión abierta del juego, devuelve
+/// [`LaunchGameOutcome::LockWarning`] en lugar de lanzarlo; la UI puede descargar el
+/// último guardado y volver a llamar con `force`. Una sesión caducada (el equipo se apagó
+/// sin subir) no bloquea: se lanza y se devuelve para avisar. Sin backend o sin red, se
+/// lanza sin avisar.
 #[tauri::command]
-pub fn launch_game(game_id: String) -> Result<(), String> {
+pub async fn launch_game(
+    game_id: String,
+    force: Option<bool>,
+) -> Result<LaunchGameOutcome, String> {
     let library = config::load_library();
     let game_id = game_id.trim();
     let game = library
@@ -417,7 +459,27 @@ pub fn launch_game(game_id: String) -> Result<(), String> {
     if !Path::new(path).is_file() {
         return Err(format!("El archivo no existe: {}", path));
     }
-    launch_exe::launch_game_executable(path)
+    let lock = match Storage::active() {
+        Ok(storage) => session::foreign_lock(&storage, &game.id)
+            .await
+            .ok()
+            .flatten(),
+        Err(_) => None,
+    };
+    let stale_lock = match lock {
+        Some(lock) if !lock.stale && !force.unwrap_or(false) => {
+            return Ok(LaunchGameOutcome::LockWarning {
+                message: lock.warning(),
+                lock,
+            });
+        }
+        Some(lock) if lock.stale => Some(lock),
+        _ => None,
+    };
+    if let Some(child) = launch_exe::spawn_game_executable(path)? {
+        crate::system::process_check::track_launched(&game.id, child.id());
+    }
+    Ok(LaunchGameOutcome::Launched { stale_lock })
 }
 
 /// Guarda la ruta al .exe para abrir el juego desde la app (`None` o cadena vacía borra la ruta).
@@ -462,6 +524,55 @@ pub fn set_game_executable_names(game_id: String, names: Vec<String>) -> Result<
     config::save_library(&library)
 }
 
+/// Fija la carpeta de instalación del juego (`None` o cadena vacía la borra). Cualquier
+/// ejecutable dentro de ella cuenta como el juego abierto.
+#[tauri::command]
+pub fn set_game_install_dir(game_id: String, path: Option<String>) -> Result<(), String> {
+    let mut library = config::load_library();
+    let game_id = game_id.trim();
+    let g = library
+        .games
+        .iter_mut()
+        .find(|g| g.id.eq_ignore_ascii_case(game_id))
+        .ok_or_else(|| format!("Juego no encontrado: {}", game_id))?;
+    g.install_dir = path
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string());
+    config::save_library(&library)
+}
+
+/// Fija reglas manuales de detección: el juego está abierto si se cumple cualquiera.
+/// Lista vacía restaura la detección automática.
+#[tauri::command]
+pub fn set_game_process_rules(game_id: String, rules: Vec<ProcessRule>) -> Result<(), String> {
+    let mut library = config::load_library();
+    let game_id = game_id.trim();
+    let g = library
+        .games
+        .iter_mut()
+        .find(|g| g.id.eq_ignore_ascii_case(game_id))
+        .ok_or_else(|| format!("Juego no encontrado: {}", game_id))?;
+    let filtered: Vec<ProcessRule> = rules
+        .into_iter()
+        .map(|rule| match rule {
+            ProcessRule::Name(v) => ProcessRule::Name(v.trim().to_string()),
+            ProcessRule::Path(v) => ProcessRule::Path(v.trim().to_string()),
+            ProcessRule::Dir(v) => ProcessRule::Dir(v.trim().to_string()),
+        })
+        .filter(|rule| match rule {
+            ProcessRule::Name(v) | ProcessRule::Path(v) | ProcessRule::Dir(v) => !v.is_empty(),
+        })
+        .collect();
+    g.process_rules = if filtered.is_empty() {
+        None
+    } else {
+        Some(filtered)
+    };
+    config::save_library(&library)
+}
+
 /// Deriva el path físico final a partir de la primera entrada enmascarada del registro.
 #[tauri::command]
 pub fn get_game_save_path(game_id: String) -> Result<String, String> {
@@ -471,9 +582,11 @@ pub fn get_game_save_path(game_id: String) -> Result<String, String> {
         .iter()
         .find(|g| g.id.eq_ignore_ascii_case(&game_id))
         .ok_or_else(|| format!("Registro nulo: {}", game_id))?;
-    let first = game.paths.first().ok_or("Entidad sin rutas vinculadas")?;
+    if game.paths.is_empty() {
+        return Err("Entidad sin rutas vinculadas".into());
+    }
 
-    expand_path(first.trim())
+    path_translation::resolve_game_save_dir(game)
         .ok_or("Expansión topológica fallida".to_string())?
         .into_os_string()
         .into_string()
@@ -617,14 +730,12 @@ pub fn import_config_from_file(path: String, mode: String) -> Result<(), String>
     Err("Instrucción de modo de ingesta no reconocida".into())
 }
 
-/// Negocia transacciones HTTP hacia el Cloud Storage prescrito.
+/// Negocia transacciones hacia el backend de almacenamiento del perfil.
 ///
 /// # Arguments
 ///
-/// * `api_base` - Host URI.
-/// * `user_id` - Clave partitiva de la instancia de nube.
-/// * `api_key` - Token de autorización pre-empaquetado.
-/// * `filename_or_key` - ID lógico o presignado del objeto remoto.
+/// * `storage` - Backend activo del perfil.
+/// * `filename_or_key` - Nombre lógico (subida) o clave completa (descarga) del objeto remoto.
 /// * `bytes` - Opcional. Blob de datos de subida (excluyente si `is_upload` es falso).
 /// * `is_upload` - Determinador direccional de stream.
 ///
@@ -632,99 +743,40 @@ pub fn import_config_from_file(path: String, mode: String) -> Result<(), String>
 ///
 /// Devuelve `Err` en colisiones HTTP u obstáculos I/O remotos.
 async fn s3_transfer(
-    api_base: &str,
-    user_id: &str,
-    api_key: &str,
+    storage: &Storage,
     filename_or_key: &str,
     bytes: Option<Vec<u8>>,
     is_upload: bool,
 ) -> Result<Vec<u8>, String> {
-    let endpoint = if is_upload {
-        "/upload-url"
-    } else {
-        "/download-url"
-    };
-    let body = if is_upload {
-        serde_json::json!({ "gameId": "__config__", "filename": filename_or_key })
-    } else {
-        serde_json::json!({ "gameId": "__config__", "key": filename_or_key })
-    };
-
-    let res = api_request(
-        api_base,
-        user_id,
-        api_key,
-        "POST",
-        endpoint,
-        Some(body.to_string().as_bytes()),
-    )
-    .await
-    .map_err(|e| e.to_string())?;
-    if !res.status().is_success() {
-        return Err(format!("Socket API rechazó handshake: {}", res.status()));
-    }
-
-    let url_key = if is_upload {
-        "uploadUrl"
-    } else {
-        "downloadUrl"
-    };
-    let json: serde_json::Value = res.json().await.map_err(|e| e.to_string())?;
-    let url = json
-        .get(url_key)
-        .and_then(|v| v.as_str())
-        .ok_or("Payload API sin puntero URL")?;
-
-    let client = reqwest::Client::builder()
-        .user_agent("SaveCloud-desktop/1.0")
-        .build()
-        .unwrap();
     if is_upload {
-        let b = bytes.unwrap();
-        let put_res = client
-            .put(url)
-            .body(b.clone())
-            .header("Content-Type", "application/json")
-            .header("Content-Length", b.len().to_string())
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
-        if !put_res.status().is_success() {
-            return Err(format!("Bucket abortó el frame PUT: {}", put_res.status()));
-        }
+        let targets = storage
+            .put_targets("__config__", &[filename_or_key.to_string()])
+            .await?;
+        let target = targets.first().ok_or("Payload sin destino de subida")?;
+        target
+            .put(bytes.unwrap_or_default().into(), "application/json")
+            .await?;
         Ok(vec![])
     } else {
-        client
-            .get(url)
-            .send()
-            .await
-            .map_err(|e| e.to_string())?
-            .bytes()
-            .await
-            .map(|b| b.to_vec())
-            .map_err(|e| e.to_string())
+        let targets = storage
+            .get_targets(
+                storage.user_id(),
+                &[("__config__".to_string(), filename_or_key.to_string())],
+            )
+            .await?;
+        let target = targets.first().ok_or("Payload sin destino de descarga")?;
+        Ok(target.read_all().await?.to_vec())
     }
 }
 
 /// Extrae la imagen en memoria hacia un flujo que termina escrito en S3, simulando el nodo `__config__`.
 #[tauri::command]
 pub async fn backup_config_to_cloud() -> Result<(), String> {
-    let settings = config::load_settings();
-    let api_base = settings
-        .api_base_url
-        .as_deref()
-        .filter(|s| !s.trim().is_empty())
-        .ok_or("Parámetro URI omitido")?;
-    let user_id = settings
-        .user_id
-        .as_deref()
-        .filter(|s| !s.trim().is_empty())
-        .ok_or("Parámetro UserID omitido")?;
-    let api_key = settings.api_key.as_deref().unwrap_or("");
+    let storage = Storage::active()?;
 
     let combined = config::get_combined_config();
     let bytes = serde_json::to_vec_pretty(&combined).unwrap();
-    s3_transfer(api_base, user_id, api_key, "config.json", Some(bytes), true)
+    s3_transfer(&storage, "config.json", Some(bytes), true)
         .await
         .map(|_| ())
 }
@@ -738,20 +790,7 @@ pub async fn backup_config_to_cloud() -> Result<(), String> {
 /// 4. Distribuye la ingesta atómicamente por cada subsistema.
 #[tauri::command]
 pub async fn restore_config_from_cloud() -> Result<(), String> {
-    let settings = config::load_settings();
-    let api_base = settings
-        .api_base_url
-        .as_deref()
-        .filter(|s| !s.trim().is_empty())
-        .ok_or("Parámetro URI omitido")?
-        .to_string();
-    let user_id = settings
-        .user_id
-        .as_deref()
-        .filter(|s| !s.trim().is_empty())
-        .ok_or("Parámetro UserID omitido")?
-        .to_string();
-    let api_key = settings.api_key.as_deref().unwrap_or("").to_string();
+    let storage = Storage::active()?;
 
     let saves = sync_list_remote_saves().await?;
     let mut config_saves: Vec<_> = saves
@@ -764,8 +803,8 @@ pub async fn restore_config_from_cloud() -> Result<(), String> {
     config_saves.sort_by(|a, b| a.last_modified.cmp(&b.last_modified));
     let latest = config_saves.pop().unwrap();
 
-    let bytes = s3_transfer(&api_base, &user_id, &api_key, &latest.key, None, false).await?;
-    let imported: Config = serde_json::from_slice(&bytes)
+    let bytes = s3_transfer(&storage, &latest.key, None, false).await?;
+    let imported = config::parse_remote_config(&bytes)
         .map_err(|e| format!("Incapacidad de mutar buffer: {}", e))?;
 
     if let Some(data_dir) = config::paths::data_dir() {
@@ -793,13 +832,7 @@ pub async fn get_friend_config(friend_user_id: String) -> Result<ConfigDto, Stri
         return Err("Puntero ID Foráneo inexistente".into());
     }
 
-    let settings = config::load_settings();
-    let api_base = settings
-        .api_base_url
-        .as_deref()
-        .filter(|s| !s.trim().is_empty())
-        .ok_or("Parámetro URI omitido")?;
-    let api_key = settings.api_key.as_deref().unwrap_or("");
+    let storage = Storage::active()?;
 
     let saves = sync_list_remote_saves_for_user(friend_id.to_string()).await?;
     let mut config_saves: Vec<_> = saves
@@ -813,15 +846,7 @@ pub async fn get_friend_config(friend_user_id: String) -> Result<ConfigDto, Stri
     config_saves.sort_by(|a, b| a.last_modified.cmp(&b.last_modified));
     let latest = config_saves.pop().unwrap();
 
-    let bytes = s3_transfer(
-        api_base,
-        settings.user_id.as_deref().unwrap_or(""),
-        api_key,
-        &latest.key,
-        None,
-        false,
-    )
-    .await?;
+    let bytes = s3_transfer(&storage, &latest.key, None, false).await?;
     let imported: Config = serde_json::from_slice(&bytes)
         .map_err(|_| "La memoria transferida por el host fue parseada como errónea")?;
 
@@ -838,6 +863,8 @@ pub async fn get_friend_config(friend_user_id: String) -> Result<ConfigDto, Stri
         profile_avatar: None,
         profile_frame: None,
         steam_web_api_key: None,
+        storage: StorageBackendConfig::default(),
+        idle_detection: IdleDetectionSettings::default(),
         games: imported
             .games
             .into_iter()
@@ -851,6 +878,8 @@ pub async fn get_friend_config(friend_user_id: String) -> Result<ConfigDto, Stri
                 magnet_link: g.magnet_link,
                 executable_names: g.executable_names.clone(),
                 launch_executable_path: g.launch_executable_path.clone(),
+                install_dir: g.install_dir.clone(),
+                process_rules: g.process_rules.clone(),
                 playtime_seconds: g.playtime_seconds,
             })
             .collect(),
@@ -871,10 +900,15 @@ pub fn add_games_from_friend(friend_games: Vec<GameDto>) -> Result<usize, String
         }
         library.games.push(ConfiguredGame {
             id: g.id.trim().to_string(),
+            // Las rutas del amigo pueden venir de otro SO: se guardan en forma portable
+            // y se resuelven en este equipo al descargar o restaurar.
             paths: if g.paths.is_empty() {
                 vec!["(editar ruta en Configuración)".to_string()]
             } else {
                 g.paths
+                    .iter()
+                    .map(|p| path_translation::to_portable_path(p))
+                    .collect()
             },
             steam_app_id: g.steam_app_id,
             image_url: g.image_url,
@@ -883,6 +917,8 @@ pub fn add_games_from_friend(friend_games: Vec<GameDto>) -> Result<usize, String
             source_url: g.source_url,
             magnet_link: None,
             launch_executable_path: g.launch_executable_path.clone(),
+            install_dir: None,
+            process_rules: None,
             playtime_seconds: 0,
         });
         existing_ids.insert(g.id.to_lowercase());
@@ -903,14 +939,7 @@ pub async fn import_friend_config(friend_user_id: String) -> Result<(), String>
         return Err("Target ID nulo".into());
     }
 
-    let settings = config::load_settings();
-    let api_base = settings
-        .api_base_url
-        .as_deref()
-        .filter(|s| !s.trim().is_empty())
-        .ok_or("Parámetro URI omitido")?
-        .to_string();
-    let api_key = settings.api_key.as_deref().unwrap_or("").to_string();
+    let storage = Storage::active()?;
 
     let saves = sync_list_remote_saves_for_user(friend_id.to_string()).await?;
     let mut config_saves: Vec<_> = saves
@@ -924,17 +953,9 @@ pub async fn import_friend_config(friend_user_id: String) -> Result<(), String>
     config_saves.sort_by(|a, b| a.last_modified.cmp(&b.last_modified));
     let latest = config_saves.pop().unwrap();
 
-    let bytes = s3_transfer(
-        &api_base,
-        settings.user_id.as_deref().unwrap_or(""),
-        &api_key,
-        &latest.key,
-        None,
-        false,
-    )
-    .await?;
-    let mut imported: Config =
-        serde_json::from_slice(&bytes).map_err(|e| format!("Volcado estático inválido: {}", e))?;
+    let bytes = s3_transfer(&storage, &latest.key, None, false).await?;
+    let mut imported = config::parse_remote_config(&bytes)
+        .map_err(|e| format!("Volcado estático inválido: {}", e))?;
 
     imported.user_id = Some(friend_id.to_string());
 
@@ -942,10 +963,11 @@ pub async fn import_friend_config(friend_user_id: String) -> Result<(), String>
 }
 
 #[tauri::command]
-pub fn get_gamification_state() -> GamificationStateDto {
+pub async fn get_gamification_state(db: State<'_, AppDb>) -> Result<GamificationStateDto, String> {
+    let history = time::playtime_cmds::play_history(&db).await;
     let g = config::load_gamification();
     let total = time::get_total_playtime();
-    config::gamification::build_state_dto(&g, total)
+    Ok(config::gamification::build_state_dto(&g, total, &history))
 }
 
 #[tauri::command]
@@ -971,7 +993,14 @@ pub fn mark_weekly_digest_notified(week_id: String) -> Result<(), String> {
 }
 
 #[tauri::command]
-pub fn should_show_weekly_digest_notification(current_week_id: String) -> bool {
+pub async fn should_show_weekly_digest_notification(
+    db: State<'_, AppDb>,
+    current_week_id: String,
+) -> Result<bool, String> {
     let g = config::load_gamification();
-    g.last_weekly_digest_notification_week_id != current_week_id && g.weekly_playtime_seconds >= 60
+    if g.last_weekly_digest_notification_week_id == current_week_id {
+        return Ok(false);
+    }
+    let history = time::playtime_cmds::play_history(&db).await;
+    Ok(config::gamification::weekly_playtime_seconds(&g, &history) >= 60)
 }
@@ -1,12 +1,14 @@
 //! Lógica de gamificación local. El estado vive en `GamificationConfig` y se persiste vía `io`
-//! (archivo dedicado y campo en el JSON monolítico para export/import y nube).
+//! (archivo dedicado y campo en el JSON monolítico para export/import y nube). El tiempo
+//! semanal y la racha de juego salen del historial de partidas ([`PlayHistory`]).
 
-use chrono::{Datelike, Utc};
+use chrono::{Datelike, Duration, Utc};
 use serde::Serialize;
 use std::collections::HashSet;
 
 use crate::config::models::GamificationConfig;
 use crate::time;
+use crate::time::sessions::PlayHistory;
 
 const ACH_FIRST_UPLOAD: &str = "first_upload";
 const ACH_SYNCS_10: &str = "syncs_10";
@@ -115,15 +117,29 @@ fn date_set_streak(days: &HashSet<String>) -> u32 {
     n
 }
 
-fn ensure_week_rollover(g: &mut GamificationConfig) {
-    let wid = current_week_id();
-    if g.week_id != wid {
-        g.week_id = wid.clone();
-        g.weekly_playtime_seconds = 0;
-    }
-    if g.week_id.is_empty() {
-        g.week_id = wid;
-    }
+/// Desde cuándo hay que leer el historial: inicio (segundos Unix) del lunes UTC de la
+/// semana ISO actual y del primer día que puede contar para una racha.
+pub fn history_window() -> (i64, i64) {
+    let today = Utc::now().date_naive();
+    let week_start = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+    let streak_start = today - Duration::days(367);
+    let secs = |d: chrono::NaiveDate| {
+        d.and_hms_opt(0, 0, 0)
+            .map(|t| t.and_utc().timestamp())
+            .unwrap_or(0)
+    };
+    (secs(week_start), secs(streak_start))
+}
+
+/// Tiempo jugado esta semana. Suma el contador antiguo de `GamificationConfig` si es de
+/// la semana actual: ya no crece, pero cubre lo jugado antes de existir el historial.
+pub fn weekly_playtime_seconds(g: &GamificationConfig, history: &PlayHistory) -> u64 {
+    let legacy = if g.week_id == current_week_id() {
+        g.weekly_playtime_seconds
+    } else {
+        0
+    };
+    history.week_seconds.saturating_add(legacy)
 }
 
 fn has_achievement(g: &GamificationConfig, id: &str) -> bool {
@@ -174,39 +190,34 @@ pub fn on_operation_logged_inner(
     check_achievements_with_total(g, time::get_total_playtime());
 }
 
-/// Actualiza rachas, semana ISO y logros tras sumar tiempo de juego.
+/// Revisa los logros tras sumar tiempo de juego; el tiempo semanal y las rachas se
+/// derivan del historial de partidas.
 /// `total_playtime_seconds` debe ser la suma en memoria tras actualizar el juego (antes de persistir).
 pub fn apply_playtime_delta(g: &mut GamificationConfig, seconds: u64, total_playtime_seconds: u64) {
     if seconds == 0 {
         return;
     }
-    ensure_week_rollover(g);
-    g.weekly_playtime_seconds = g.weekly_playtime_seconds.saturating_add(seconds);
-
-    let today = Utc::now().format("%Y-%m-%d").to_string();
-    if !g.utc_days_with_play.contains(&today) {
-        g.utc_days_with_play.push(today);
-        trim_dates(&mut g.utc_days_with_play, 400);
-    }
-
     check_achievements_with_total(g, total_playtime_seconds);
 }
 
 pub fn build_state_dto(
     g: &GamificationConfig,
     total_playtime_seconds: u64,
+    history: &PlayHistory,
 ) -> GamificationStateDto {
     let sync_set: HashSet<String> = g.utc_days_with_sync.iter().cloned().collect();
-    let play_set: HashSet<String> = g.utc_days_with_play.iter().cloned().collect();
+    // Los días guardados antes del historial siguen contando para la racha.
+    let play_set: HashSet<String> = g
+        .utc_days_with_play
+        .iter()
+        .cloned()
+        .chain(history.utc_days.iter().cloned())
+        .collect();
 
     GamificationStateDto {
         level_progress: level_progress(total_playtime_seconds),
-        weekly_playtime_seconds: g.weekly_playtime_seconds,
-        week_id: if g.week_id.is_empty() {
-            current_week_id()
-        } else {
-            g.week_id.clone()
-        },
+        weekly_playtime_seconds: weekly_playtime_seconds(g, history),
+        week_id: current_week_id(),
         sync_streak_days: date_set_streak(&sync_set),
         play_streak_days: date_set_streak(&play_set),
         upload_success_count: g.upload_success_count,
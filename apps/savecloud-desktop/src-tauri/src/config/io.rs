@@ -7,10 +7,17 @@ use super::{models::*, paths};
 use chrono::Utc;
 use keyring::Entry;
 use std::fs;
+use std::sync::atomic::{AtomicU64, Ordering};
 
+#[cfg(not(test))]
 pub const KEYRING_SERVICE: &str = "savecloud_api";
+/// Servicio aparte en los tests, para no leer ni pisar las credenciales reales.
+#[cfg(test)]
+pub const KEYRING_SERVICE: &str = "savecloud_api_tests";
 pub const KEYRING_ACCOUNT: &str = "default_user";
 const KEYRING_ACCOUNT_STEAM_WEB_API: &str = "steam_web_api";
+const KEYRING_ACCOUNT_S3_SECRET: &str = "storage_backend_secret_s3";
+const KEYRING_ACCOUNT_WEBDAV_SECRET: &str = "storage_backend_secret_webdav";
 
 /// Recupera la clave de la API desde el almacenamiento seguro del sistema operativo.
 fn get_secure_api_key() -> Option<String> {
@@ -56,6 +63,36 @@ fn set_secure_steam_web_api_key(key: &str) -> Result<(), String> {
     entry.set_password(key).map_err(|e| e.to_string())
 }
 
+/// Cuenta del Keyring con el secreto de un tipo de backend. Cada tipo tiene la suya:
+/// pasar de S3 a WebDAV y volver no pisa la clave del otro.
+fn storage_secret_account(storage: &StorageBackendConfig) -> Option<&'static str> {
+    match storage {
+        StorageBackendConfig::S3 { .. } => Some(KEYRING_ACCOUNT_S3_SECRET),
+        StorageBackendConfig::WebDav { .. } => Some(KEYRING_ACCOUNT_WEBDAV_SECRET),
+        StorageBackendConfig::SaveCloudApi | StorageBackendConfig::Local { .. } => None,
+    }
+}
+
+/// Secreto guardado para el tipo de `storage` (clave S3 o contraseña WebDAV).
+pub fn get_secure_storage_secret(storage: &StorageBackendConfig) -> Option<String> {
+    Entry::new(KEYRING_SERVICE, storage_secret_account(storage)?)
+        .ok()
+        .and_then(|entry| entry.get_password().ok())
+        .filter(|k| k != MASKED_API_KEY)
+}
+
+fn set_secure_storage_secret(storage: &StorageBackendConfig, secret: &str) -> Result<(), String> {
+    let Some(account) = storage_secret_account(storage) else {
+        return Ok(());
+    };
+    if secret == MASKED_API_KEY {
+        return Ok(());
+    }
+
+    let entry = Entry::new(KEYRING_SERVICE, account).map_err(|e| e.to_string())?;
+    entry.set_password(secret).map_err(|e| e.to_string())
+}
+
 /// Inyecta el valor de una variable de entorno en un campo opcional,
 /// priorizando el valor en tiempo de compilación sobre el valor en tiempo de ejecución.
 fn apply_env_fallback(
@@ -131,6 +168,13 @@ pub fn load_settings() -> AppSettings {
         settings.steam_web_api_key = Some(sk);
     }
 
+    if settings.storage.secret().is_none() {
+        let stored = get_secure_storage_secret(&settings.storage);
+        if let Some(secret) = settings.storage.secret_mut() {
+            *secret = stored;
+        }
+    }
+
     apply_env_fallback(
         &mut settings.api_base_url,
         option_env!("SYNC_GAMES_API_URL"),
@@ -155,6 +199,16 @@ pub fn load_settings() -> AppSettings {
     settings
 }
 
+/// Ajustes de inactividad leídos directamente de `settings.json`, sin consultar el
+/// Keyring: el contador de tiempo de juego los relee en cada revisión.
+pub fn load_idle_detection() -> IdleDetectionSettings {
+    paths::settings_path()
+        .and_then(|p| fs::read_to_string(p).ok())
+        .and_then(|c| serde_json::from_str::<AppSettings>(&c).ok())
+        .map(|s| s.idle_detection)
+        .unwrap_or_default()
+}
+
 /// Persiste las configuraciones de la aplicación en disco.
 ///
 /// Extrae automáticamente la clave API y la clave Steam Web API de la estructura
@@ -174,6 +228,9 @@ pub fn save_settings(settings: &AppSettings) -> Result<(), String> {
             set_secure_steam_web_api_key(key)?;
         }
     }
+    if let Some(secret) = settings.storage.secret().filter(|s| !s.trim().is_empty()) {
+        set_secure_storage_secret(&settings.storage, secret)?;
+    }
     let path = paths::settings_path().ok_or("Ruta no disponible")?;
     save_json(&path, settings)
 }
@@ -186,10 +243,20 @@ pub fn load_library() -> GameLibrary {
         .unwrap_or_default()
 }
 
+/// Versión de la biblioteca en esta ejecución: sube con cada guardado, para que las
+/// cachés derivadas de ella (p. ej. reglas de detección de procesos) sepan si siguen al día.
+static LIBRARY_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+pub fn library_generation() -> u64 {
+    LIBRARY_GENERATION.load(Ordering::Acquire)
+}
+
 /// Sobrescribe la biblioteca de juegos en el sistema de archivos.
 pub fn save_library(library: &GameLibrary) -> Result<(), String> {
     let path = paths::library_path().ok_or("Ruta no disponible")?;
-    save_json(&path, library)
+    save_json(&path, library)?;
+    LIBRARY_GENERATION.fetch_add(1, Ordering::AcqRel);
+    Ok(())
 }
 
 /// Carga el historial de operaciones localizadas.
@@ -249,6 +316,7 @@ pub fn append_operation_log(
         game_id: game_id.to_string(),
         file_count,
         err_count,
+        device_name: Some(crate::devices::identity::current().name),
     });
 
     const MAX_ENTRIES: usize = 200;
@@ -287,6 +355,7 @@ pub fn get_combined_config() -> Config {
         profile_background: settings.profile_background.clone(),
         profile_avatar: settings.profile_avatar.clone(),
         profile_frame: settings.profile_frame.clone(),
+        storage: Some(settings.storage),
         games: library.games,
         operation_history: history.entries,
         gamification: load_gamification(),
@@ -321,8 +390,21 @@ pub fn save_gamification(g: &GamificationConfig) -> Result<(), String> {
 ///
 /// Devuelve `Err` si alguna de las escrituras atómicas hacia los subsistemas falla.
 pub fn apply_combined_config(cfg: &Config) -> Result<(), String> {
-    let mut current_settings = load_settings();
+    save_settings(&merge_imported_settings(load_settings(), cfg))?;
+    save_library(&GameLibrary {
+        games: cfg.games.clone(),
+    })?;
+    save_history(&OperationHistory {
+        entries: cfg.operation_history.clone(),
+    })?;
+    save_gamification(&cfg.gamification)?;
 
+    Ok(())
+}
+
+/// Vuelca sobre `current_settings` los ajustes de `cfg`. Los campos opcionales ausentes
+/// en `cfg` conservan el valor local.
+fn merge_imported_settings(mut current_settings: AppSettings, cfg: &Config) -> AppSettings {
     current_settings.api_base_url = cfg.api_base_url.clone().or(current_settings.api_base_url);
 
     current_settings.api_key = cfg
@@ -348,20 +430,85 @@ pub fn apply_combined_config(cfg: &Config) -> Result<(), String> {
         .clone()
         .or(current_settings.profile_avatar);
     current_settings.profile_frame = cfg.profile_frame.clone().or(current_settings.profile_frame);
+    if let Some(storage) = &cfg.storage {
+        current_settings.storage = storage.clone();
+    }
+    current_settings
+}
 
-    save_settings(&current_settings)?;
-    save_library(&GameLibrary {
-        games: cfg.games.clone(),
-    })?;
-    save_history(&OperationHistory {
-        entries: cfg.operation_history.clone(),
-    })?;
-    save_gamification(&cfg.gamification)?;
-
-    Ok(())
+/// Lee una configuración descargada de la nube (la propia o la de un amigo) para aplicarla
+/// con [`apply_combined_config`].
+///
+/// Descarta su `storage`: el JSON no lleva el secreto del backend, así que aplicarlo
+/// dejaría esta instalación apuntando a un bucket o API sin credenciales.
+pub fn parse_remote_config(bytes: &[u8]) -> serde_json::Result<Config> {
+    let mut imported: Config = serde_json::from_slice(bytes)?;
+    imported.storage = None;
+    Ok(imported)
 }
 
 /// Carga la configuración combinada. Útil para mantener la compatibilidad con código legacy.
 pub fn load_config() -> Config {
     get_combined_config()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn storage_secrets_use_one_keyring_account_per_backend_kind() {
+        let s3 = StorageBackendConfig::S3 {
+            endpoint: "https://s3.example".into(),
+            region: String::new(),
+            bucket: "saves".into(),
+            access_key_id: "AKIA".into(),
+            secret_access_key: None,
+            path_style: false,
+        };
+        let webdav = StorageBackendConfig::WebDav {
+            url: "https://nas.local/dav".into(),
+            username: "yo".into(),
+            password: None,
+        };
+        let local = StorageBackendConfig::Local {
+            root: "/mnt/nas".into(),
+        };
+
+        assert_ne!(storage_secret_account(&s3), storage_secret_account(&webdav));
+        assert!(storage_secret_account(&s3).is_some());
+        assert_eq!(storage_secret_account(&local), None);
+        assert_eq!(
+            storage_secret_account(&StorageBackendConfig::SaveCloudApi),
+            None
+        );
+    }
+
+    #[test]
+    fn remote_config_keeps_the_local_storage_backend() {
+        let json = br#"{
+            "customScanPaths": [],
+            "games": [],
+            "storage": {
+                "kind": "s3",
+                "endpoint": "https://s3.amigo.example",
+                "bucket": "saves-amigo",
+                "accessKeyId": "AKIAAMIGO"
+            }
+        }"#;
+        let imported = parse_remote_config(json).unwrap();
+        assert!(imported.storage.is_none());
+
+        let local = AppSettings {
+            storage: StorageBackendConfig::Local {
+                root: "/mnt/nas/savecloud".into(),
+            },
+            ..Default::default()
+        };
+        let merged = merge_imported_settings(local, &imported);
+        assert!(matches!(
+            merged.storage,
+            StorageBackendConfig::Local { ref root } if root == "/mnt/nas/savecloud"
+        ));
+    }
+}
@@ -45,6 +45,100 @@ pub struct AppSettings {
     /// No se serializa en JSON; se guarda en el almacén seguro del SO (Keyring), igual que `api_key`.
     #[serde(skip_serializing, default)]
     pub steam_web_api_key: Option<String>,
+    /// Dónde se guardan los archivos del perfil (API de SaveCloud, S3, carpeta o WebDAV).
+    #[serde(default)]
+    pub storage: StorageBackendConfig,
+    /// Cuándo deja de contar el tiempo de juego por inactividad.
+    #[serde(default)]
+    pub idle_detection: IdleDetectionSettings,
+}
+
+/// Pausa del contador de tiempo de juego mientras nadie juega (desactivada por defecto).
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdleDetectionSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Minutos sin teclado, ratón ni mando tras los que el juego cuenta como inactivo.
+    #[serde(default = "default_idle_minutes")]
+    pub idle_minutes: u32,
+    /// El juego también cuenta como inactivo si su ventana no está en primer plano.
+    #[serde(default)]
+    pub require_foreground: bool,
+}
+
+fn default_idle_minutes() -> u32 {
+    10
+}
+
+impl Default for IdleDetectionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            idle_minutes: default_idle_minutes(),
+            require_foreground: false,
+        }
+    }
+}
+
+/// Backend de almacenamiento elegido por el perfil.
+///
+/// Los secretos (`secret_access_key`, `password`) no se serializan en JSON; se guardan
+/// en el almacén seguro del SO igual que `api_key`.
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum StorageBackendConfig {
+    /// API de SaveCloud (`apiBaseUrl`) con URLs prefirmadas de S3.
+    #[default]
+    SaveCloudApi,
+    /// Bucket S3 compatible (AWS, MinIO, R2...) firmado desde la app con SigV4.
+    #[serde(rename_all = "camelCase")]
+    S3 {
+        endpoint: String,
+        #[serde(default)]
+        region: String,
+        bucket: String,
+        access_key_id: String,
+        #[serde(skip_serializing, default)]
+        secret_access_key: Option<String>,
+        /// `endpoint/bucket/clave` en vez de `bucket.endpoint/clave` (necesario en MinIO).
+        #[serde(default)]
+        path_style: bool,
+    },
+    /// Carpeta local o de red (NAS, unidad montada).
+    Local { root: String },
+    /// Servidor WebDAV (Nextcloud, ownCloud, NAS...).
+    #[serde(rename_all = "camelCase")]
+    WebDav {
+        url: String,
+        #[serde(default)]
+        username: String,
+        #[serde(skip_serializing, default)]
+        password: Option<String>,
+    },
+}
+
+impl StorageBackendConfig {
+    /// Secreto del backend (clave S3 o contraseña WebDAV), si lo usa.
+    pub fn secret(&self) -> Option<&str> {
+        match self {
+            StorageBackendConfig::S3 {
+                secret_access_key, ..
+            } => secret_access_key.as_deref(),
+            StorageBackendConfig::WebDav { password, .. } => password.as_deref(),
+            StorageBackendConfig::SaveCloudApi | StorageBackendConfig::Local { .. } => None,
+        }
+    }
+
+    pub fn secret_mut(&mut self) -> Option<&mut Option<String>> {
+        match self {
+            StorageBackendConfig::S3 {
+                secret_access_key, ..
+            } => Some(secret_access_key),
+            StorageBackendConfig::WebDav { password, .. } => Some(password),
+            StorageBackendConfig::SaveCloudApi | StorageBackendConfig::Local { .. } => None,
+        }
+    }
 }
 
 /// Biblioteca local de juegos configurados.
@@ -85,10 +179,28 @@ pub struct ConfiguredGame {
     /// Ruta absoluta al .exe para abrir el juego desde la app (opcional).
     #[serde(default)]
     pub launch_executable_path: Option<String>,
+    /// Carpeta de instalación: cualquier ejecutable dentro cuenta como el juego abierto.
+    #[serde(default)]
+    pub install_dir: Option<String>,
+    /// Reglas de detección manuales; si hay alguna, sustituyen a las automáticas.
+    #[serde(default)]
+    pub process_rules: Option<Vec<ProcessRule>>,
     #[serde(default)]
     pub playtime_seconds: u64,
 }
 
+/// Regla para reconocer un proceso del juego. El juego está abierto si se cumple cualquiera.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "kind", content = "value", rename_all = "camelCase")]
+pub enum ProcessRule {
+    /// Nombre del ejecutable, sin distinguir mayúsculas ni `.exe`.
+    Name(String),
+    /// Ruta completa del ejecutable.
+    Path(String),
+    /// Cualquier ejecutable dentro de esta carpeta.
+    Dir(String),
+}
+
 /// Entrada descriptiva de una operación de sincronización completada.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -98,6 +210,9 @@ pub struct OperationLogEntry {
     pub game_id: String,
     pub file_count: u32,
     pub err_count: u32,
+    /// Dispositivo que hizo la operación (el historial viaja con la configuración).
+    #[serde(default)]
+    pub device_name: Option<String>,
 }
 
 /// Gamificación y estadísticas locales; viaja en el JSON monolítico (export, import, nube).
@@ -108,10 +223,13 @@ pub struct GamificationConfig {
     pub upload_success_count: u64,
     #[serde(default)]
     pub utc_days_with_sync: Vec<String>,
+    /// Días con partida anteriores al historial en SQLite; ya no se actualiza.
     #[serde(default)]
     pub utc_days_with_play: Vec<String>,
+    /// Contador semanal anterior al historial en SQLite; ya no se actualiza.
     #[serde(default)]
     pub weekly_playtime_seconds: u64,
+    /// Semana ISO de `weekly_playtime_seconds`.
     #[serde(default)]
     pub week_id: String,
     #[serde(default)]
@@ -146,6 +264,8 @@ pub struct Config {
     pub profile_avatar: Option<String>,
     #[serde(default)]
     pub profile_frame: Option<String>,
+    #[serde(default)]
+    pub storage: Option<StorageBackendConfig>,
     pub games: Vec<ConfiguredGame>,
     #[serde(default)]
     pub operation_history: Vec<OperationLogEntry>,
@@ -176,6 +296,10 @@ pub struct ConfigDto {
     /// Presente solo si hay clave configurada; valor enmascarado hacia la UI.
     #[serde(default)]
     pub steam_web_api_key: Option<String>,
+    /// Backend de almacenamiento del perfil (sin secretos).
+    pub storage: StorageBackendConfig,
+    #[serde(default)]
+    pub idle_detection: IdleDetectionSettings,
 }
 
 /// DTO representativo de un juego para el frontend.
@@ -193,6 +317,10 @@ pub struct GameDto {
     pub executable_names: Option<Vec<String>>,
     #[serde(default)]
     pub launch_executable_path: Option<String>,
+    #[serde(default)]
+    pub install_dir: Option<String>,
+    #[serde(default)]
+    pub process_rules: Option<Vec<ProcessRule>>,
     pub playtime_seconds: u64,
 }
 
@@ -205,6 +333,7 @@ pub struct OperationLogEntryDto {
     pub game_id: String,
     pub file_count: u32,
     pub err_count: u32,
+    pub device_name: Option<String>,
 }
 
 /// Valor centinela utilizado para enmascarar la API Key hacia el frontend.
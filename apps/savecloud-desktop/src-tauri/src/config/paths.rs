@@ -23,10 +23,18 @@ pub const SQLITE_CATALOG_DB_NAME: &str = "catalog.sqlite";
 /// Devuelve `Some(PathBuf)` con la ruta resuelta, o `None` si el sistema
 /// operativo no provee una ruta válida.
 pub fn config_dir() -> Option<PathBuf> {
-    let base = dirs::config_dir()
-        .or_else(|| dirs::data_local_dir())
-        .or_else(dirs::home_dir)?;
-    Some(base.join(CONFIG_DIR_NAME))
+    // Los tests nunca tocan la configuración real del usuario.
+    #[cfg(test)]
+    {
+        Some(crate::testing::config_dir().join(CONFIG_DIR_NAME))
+    }
+    #[cfg(not(test))]
+    {
+        let base = dirs::config_dir()
+            .or_else(|| dirs::data_local_dir())
+            .or_else(dirs::home_dir)?;
+        Some(base.join(CONFIG_DIR_NAME))
+    }
 }
 
 /// Obtiene el subdirectorio destinado a la persistencia de datos estructurados.
@@ -12,6 +12,7 @@ pub mod actions;
 pub mod mapper;
 pub mod state;
 
+use crate::system::idle;
 use actions::{ControllerEvent, SemanticAction};
 use gilrs::{Event as GilrsEvent, EventType, Gilrs};
 use state::InputState;
@@ -19,6 +20,10 @@ use std::thread;
 use std::time::Duration;
 use tauri::{AppHandle, Emitter};
 
+/// Desplazamiento mínimo de un eje o gatillo para contar como actividad (ignora la
+/// deriva de los sticks).
+const ACTIVITY_THRESHOLD: f32 = 0.2;
+
 pub fn start_gamepad_loop(app_handle: AppHandle) {
     thread::spawn(move || {
         let mut gilrs = match Gilrs::new() {
@@ -33,6 +38,7 @@ pub fn start_gamepad_loop(app_handle: AppHandle) {
 
                 match event {
                     EventType::ButtonPressed(button, _) => {
+                        idle::note_gamepad_input();
                         if let Some(action) = mapper::map_button(button) {
                             if input_state.press(player_id, action) {
                                 emit_action(&app_handle, player_id, action);
@@ -45,6 +51,9 @@ pub fn start_gamepad_loop(app_handle: AppHandle) {
                         }
                     }
                     EventType::AxisChanged(axis, value, _) => {
+                        if value.abs() >= ACTIVITY_THRESHOLD {
+                            idle::note_gamepad_input();
+                        }
                         for a in [
                             SemanticAction::NavigateUp,
                             SemanticAction::NavigateDown,
@@ -60,7 +69,12 @@ pub fn start_gamepad_loop(app_handle: AppHandle) {
                             }
                         }
                     }
-                    EventType::ButtonRepeated(_, _) | EventType::ButtonChanged(_, _, _) => {}
+                    EventType::ButtonChanged(_, value, _) => {
+                        if value >= ACTIVITY_THRESHOLD {
+                            idle::note_gamepad_input();
+                        }
+                    }
+                    EventType::ButtonRepeated(_, _) => {}
                     EventType::Dropped => {}
                     EventType::Connected | EventType::Disconnected => {}
                 }
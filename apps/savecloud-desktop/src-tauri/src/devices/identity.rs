@@ -0,0 +1,128 @@
+//! Identidad de esta instalación: id estable y nombre visible.
+//!
+//! Se genera la primera vez y se guarda en `device.json` (directorio de
+//! configuración, fuera de `data/`, así que no viaja al exportar o subir la
+//! configuración: cada equipo conserva la suya). La app y la CLI comparten el archivo.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use std::sync::{LazyLock, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const DEVICE_FILE_NAME: &str = "device.json";
+
+/// Versión de la app que firma las subidas.
+pub const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Id y nombre de este dispositivo.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceIdentity {
+    pub id: String,
+    pub name: String,
+}
+
+/// Identidad ya leída; `device.json` solo cambia al renombrar desde esta misma app.
+static CURRENT: LazyLock<Mutex<Option<DeviceIdentity>>> = LazyLock::new(|| Mutex::new(None));
+
+/// Nombre del equipo según el sistema operativo.
+pub fn hostname() -> String {
+    sysinfo::System::host_name()
+        .filter(|h| !h.trim().is_empty())
+        .unwrap_or_else(|| "Equipo desconocido".to_string())
+}
+
+/// Id de 128 bits en hex. No hace falta un RNG: equipo, proceso e instante bastan para no repetirlo.
+fn new_device_id() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let mut hasher = Sha256::new();
+    hasher.update(hostname().as_bytes());
+    hasher.update(std::process::id().to_le_bytes());
+    hasher.update(nanos.to_le_bytes());
+    hasher.finalize()[..16]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Lee la identidad de `path` o crea una nueva (con el nombre del equipo) si no existe o no se puede leer.
+fn load_or_create_in(path: &Path) -> DeviceIdentity {
+    if let Some(identity) = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|json| serde_json::from_str::<DeviceIdentity>(&json).ok())
+        .filter(|d| !d.id.is_empty())
+    {
+        return identity;
+    }
+    let identity = DeviceIdentity {
+        id: new_device_id(),
+        name: hostname(),
+    };
+    let _ = save_in(path, &identity);
+    identity
+}
+
+fn save_in(path: &Path, identity: &DeviceIdentity) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(identity).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| format!("Guardar identidad del dispositivo: {}", e))
+}
+
+/// Identidad de esta instalación (se crea la primera vez).
+pub fn current() -> DeviceIdentity {
+    let mut cached = CURRENT.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(identity) = cached.as_ref() {
+        return identity.clone();
+    }
+    let identity = match crate::config::config_dir() {
+        Some(dir) => load_or_create_in(&dir.join(DEVICE_FILE_NAME)),
+        // Sin directorio de configuración no se puede persistir: id válido solo para esta sesión.
+        None => DeviceIdentity {
+            id: new_device_id(),
+            name: hostname(),
+        },
+    };
+    *cached = Some(identity.clone());
+    identity
+}
+
+/// Cambia el nombre visible de este dispositivo; el id no cambia.
+pub fn set_name(name: &str) -> Result<DeviceIdentity, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("El nombre del dispositivo no puede estar vacío".to_string());
+    }
+    let dir = crate::config::config_dir().ok_or("No se pudo obtener directorio de config")?;
+    let identity = DeviceIdentity {
+        name: name.to_string(),
+        ..current()
+    };
+    save_in(&dir.join(DEVICE_FILE_NAME), &identity)?;
+    *CURRENT.lock().unwrap_or_else(|e| e.into_inner()) = Some(identity.clone());
+    Ok(identity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_same_id_across_loads() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(DEVICE_FILE_NAME);
+
+        let first = load_or_create_in(&path);
+        assert_eq!(first.id.len(), 32);
+        assert_eq!(load_or_create_in(&path), first);
+
+        // Un archivo corrupto no deja la app sin identidad.
+        std::fs::write(&path, "{").unwrap();
+        assert_ne!(load_or_create_in(&path).id, first.id);
+    }
+}
@@ -0,0 +1,138 @@
+//! Dispositivos del perfil y procedencia de las subidas.
+//!
+//! Cada instalación tiene una identidad estable ([`identity`]), se anota en un
+//! registro compartido del perfil ([`registry`]) y firma cada archivo que sube
+//! ([`provenance`]). Así la UI puede mostrar "subido desde el PC del salón hace 2 h"
+//! en conflictos, historial y backups, y revocar equipos que ya no se usan. Mientras
+//! un juego está abierto, su sesión ([`session`]) avisa a los demás equipos.
+
+pub mod identity;
+pub mod provenance;
+pub mod registry;
+pub mod session;
+
+use crate::storage::{object_key, Storage, StorageBackend};
+use identity::DeviceIdentity;
+use provenance::UploadOrigin;
+use registry::DeviceEntry;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use session::SessionLockDto;
+use std::collections::HashMap;
+
+/// Lee un JSON pequeño del perfil; `None` si todavía no existe.
+async fn read_json<T: DeserializeOwned>(
+    storage: &Storage,
+    game_id: &str,
+    filename: &str,
+) -> Result<Option<T>, String> {
+    let key = object_key(storage.user_id(), game_id, filename);
+    let targets = storage
+        .get_targets(storage.user_id(), &[(game_id.to_string(), key)])
+        .await?;
+    let target = targets.first().ok_or("Sin destino de descarga")?;
+    let bytes = match target.read_all().await {
+        Ok(bytes) => bytes,
+        Err(e) if e.is_not_found() => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    serde_json::from_slice(&bytes)
+        .map(Some)
+        .map_err(|e| format!("JSON inválido en {}/{}: {}", game_id, filename, e))
+}
+
+/// Sube (o reemplaza) un JSON pequeño del perfil.
+async fn write_json<T: Serialize>(
+    storage: &Storage,
+    game_id: &str,
+    filename: &str,
+    value: &T,
+) -> Result<(), String> {
+    let bytes = serde_json::to_vec_pretty(value).map_err(|e| e.to_string())?;
+    let targets = storage
+        .put_targets(game_id, &[filename.to_string()])
+        .await?;
+    let target = targets.first().ok_or("Sin destino de subida")?;
+    target.put(bytes.into(), "application/json").await?;
+    Ok(())
+}
+
+/// Dispositivo del registro tal como lo ve la UI.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceDto {
+    #[serde(flatten)]
+    pub entry: DeviceEntry,
+    /// Es esta instalación.
+    pub current: bool,
+}
+
+/// Identidad de este dispositivo.
+#[tauri::command]
+pub fn get_device_info() -> DeviceIdentity {
+    identity::current()
+}
+
+/// Renombra este dispositivo. El registro remoto se actualiza en la siguiente subida.
+#[tauri::command]
+pub fn set_device_name(name: String) -> Result<DeviceIdentity, String> {
+    identity::set_name(&name)
+}
+
+/// Dispositivos que han subido guardados a este perfil, el más reciente primero.
+#[tauri::command]
+pub async fn list_devices() -> Result<Vec<DeviceDto>, String> {
+    let storage = Storage::active()?;
+    let current_id = identity::current().id;
+    let mut devices: Vec<DeviceDto> = registry::load(&storage)
+        .await?
+        .devices
+        .into_iter()
+        .map(|entry| DeviceDto {
+            current: entry.id == current_id,
+            entry,
+        })
+        .collect();
+    devices.sort_by(|a, b| b.entry.last_seen.cmp(&a.entry.last_seen));
+    Ok(devices)
+}
+
+/// Revoca un dispositivo del perfil: a partir de ahora sus subidas se rechazan.
+#[tauri::command]
+pub async fn revoke_device(device_id: String) -> Result<(), String> {
+    let storage = Storage::active()?;
+    registry::revoke(&storage, &device_id).await
+}
+
+/// Última subida de cada juego (dispositivo y fecha). Los juegos sin procedencia no aparecen.
+#[tauri::command]
+pub async fn get_last_uploads(
+    game_ids: Vec<String>,
+) -> Result<HashMap<String, UploadOrigin>, String> {
+    let storage = Storage::active()?;
+    let futures = game_ids
+        .into_iter()
+        .filter(|id| !id.trim().is_empty())
+        .map(|game_id| {
+            let storage = storage.clone();
+            async move {
+                let origin = provenance::load(&storage, &game_id)
+                    .await
+                    .ok()
+                    .and_then(|p| p.last_upload().cloned());
+                (game_id, origin)
+            }
+        });
+    Ok(futures_util::future::join_all(futures)
+        .await
+        .into_iter()
+        .filter_map(|(game_id, origin)| origin.map(|o| (game_id, o)))
+        .collect())
+}
+
+/// Sesión de otro dispositivo sobre el juego (abierta o que terminó sin subir), si la hay.
+#[tauri::command]
+pub async fn get_game_session_lock(game_id: String) -> Result<Option<SessionLockDto>, String> {
+    let storage = Storage::active()?;
+    session::foreign_lock(&storage, game_id.trim()).await
+}
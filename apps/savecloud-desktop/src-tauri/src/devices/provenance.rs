@@ -0,0 +1,145 @@
+//! Procedencia de las subidas: qué dispositivo subió cada archivo y cuándo.
+//!
+//! Los backends no guardan metadatos propios de forma uniforme (URLs prefirmadas,
+//! carpetas locales, WebDAV), así que cada juego lleva un JSON junto a sus
+//! guardados (`.savecloud/provenance.json`) con el origen de cada archivo. Se
+//! mueve y se borra con el juego, y el listado de guardados lo oculta.
+
+use super::identity::{self, APP_VERSION};
+use super::{read_json, write_json};
+use crate::commands::logs::sync_logger;
+use crate::storage::{Storage, META_PREFIX};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Nombre del archivo de procedencia dentro de la carpeta del juego.
+fn provenance_filename() -> String {
+    format!("{}provenance.json", META_PREFIX)
+}
+
+/// Dispositivo, equipo y versión de la app que subieron un archivo.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadOrigin {
+    pub device_id: String,
+    pub device_name: String,
+    pub hostname: String,
+    pub app_version: String,
+    /// Fecha RFC 3339 (UTC, milisegundos) de la subida; ordena como texto.
+    pub uploaded_at: String,
+}
+
+impl UploadOrigin {
+    /// Origen de una subida hecha ahora desde este dispositivo.
+    pub fn current() -> Self {
+        let device = identity::current();
+        Self {
+            device_id: device.id,
+            device_name: device.name,
+            hostname: identity::hostname(),
+            app_version: APP_VERSION.to_string(),
+            uploaded_at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        }
+    }
+}
+
+/// Origen de cada archivo subido de un juego, por nombre relativo.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameProvenance {
+    #[serde(default)]
+    pub files: BTreeMap<String, UploadOrigin>,
+}
+
+impl GameProvenance {
+    pub fn origin_of(&self, filename: &str) -> Option<&UploadOrigin> {
+        self.files.get(filename)
+    }
+
+    /// Subida más reciente del juego (para "subido desde X hace 2 h").
+    pub fn last_upload(&self) -> Option<&UploadOrigin> {
+        self.files
+            .values()
+            .max_by(|a, b| a.uploaded_at.cmp(&b.uploaded_at))
+    }
+
+    fn record(&mut self, filenames: &[String], origin: &UploadOrigin) {
+        for filename in filenames {
+            self.files.insert(filename.clone(), origin.clone());
+        }
+    }
+}
+
+/// Procedencia de un juego; vacía si aún no tiene ninguna subida registrada.
+pub async fn load(storage: &Storage, game_id: &str) -> Result<GameProvenance, String> {
+    Ok(read_json(storage, game_id, &provenance_filename())
+        .await?
+        .unwrap_or_default())
+}
+
+/// Anota `filenames` como subidos ahora desde este dispositivo.
+///
+/// Si no se puede leer la procedencia actual no se escribe nada, para no pisar la
+/// de otros archivos. Los fallos solo se registran en el log: la subida ya terminó.
+pub async fn record_upload(storage: &Storage, game_id: &str, filenames: &[String]) {
+    if filenames.is_empty() {
+        return;
+    }
+    record_upload_from(storage, game_id, filenames, &UploadOrigin::current()).await;
+}
+
+async fn record_upload_from(
+    storage: &Storage,
+    game_id: &str,
+    filenames: &[String],
+    origin: &UploadOrigin,
+) {
+    let mut provenance = match load(storage, game_id).await {
+        Ok(p) => p,
+        Err(e) => {
+            sync_logger::log_error("provenance_load", game_id, &e);
+            return;
+        }
+    };
+    provenance.record(filenames, origin);
+    if let Err(e) = write_json(storage, game_id, &provenance_filename(), &provenance).await {
+        sync_logger::log_error("provenance_save", game_id, &e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::mock_api::MockApi;
+
+    fn origin(device: &str, uploaded_at: &str) -> UploadOrigin {
+        UploadOrigin {
+            device_id: device.to_string(),
+            device_name: format!("PC {}", device),
+            hostname: device.to_string(),
+            app_version: APP_VERSION.to_string(),
+            uploaded_at: uploaded_at.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn records_the_last_upload_per_file() {
+        let api = MockApi::start().await;
+        let storage = Storage::Api(api.backend("user1"));
+        assert!(load(&storage, "hades").await.unwrap().files.is_empty());
+
+        let salon = origin("salon", "2026-01-01T10:00:00.000Z");
+        let portatil = origin("portatil", "2026-01-02T10:00:00.000Z");
+        let files = ["a.sav".to_string(), "b.sav".to_string()];
+        record_upload_from(&storage, "hades", &files, &salon).await;
+        record_upload_from(&storage, "hades", &files[1..], &portatil).await;
+
+        let provenance = load(&storage, "hades").await.unwrap();
+        assert_eq!(provenance.origin_of("a.sav"), Some(&salon));
+        assert_eq!(provenance.origin_of("b.sav"), Some(&portatil));
+        assert_eq!(provenance.last_upload(), Some(&portatil));
+        assert!(api
+            .object(&format!("user1/hades/{}", provenance_filename()))
+            .is_some());
+    }
+}
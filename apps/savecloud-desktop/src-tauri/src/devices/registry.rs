@@ -0,0 +1,208 @@
+//! Registro de los dispositivos del perfil, guardado en el propio backend.
+//!
+//! Vive en `__devices__/devices.json` (una carpeta reservada, como `__config__`).
+//! Cada dispositivo se anota al subir y refresca su última conexión como mucho cada
+//! [`TOUCH_INTERVAL_HOURS`]. Un dispositivo revocado desde otro ya no puede subir.
+//! El archivo se reescribe entero: si dos equipos lo tocan a la vez gana el último,
+//! y el que se pierda vuelve a anotarse en su siguiente subida.
+
+use super::identity::{self, DeviceIdentity, APP_VERSION};
+use super::{read_json, write_json};
+use crate::commands::logs::sync_logger;
+use crate::storage::{Storage, StorageBackend};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// Carpeta reservada del perfil con el registro; el listado de guardados la oculta.
+pub const DEVICES_FOLDER: &str = "__devices__";
+const REGISTRY_FILE: &str = "devices.json";
+
+/// Cada cuánto se actualiza `lastSeen` como mucho, para no reescribir el registro en cada subida.
+const TOUCH_INTERVAL_HOURS: i64 = 6;
+
+/// Tras comprobar el registro, las subidas de los próximos minutos no lo vuelven a leer.
+const CHECK_IN_CACHE: Duration = Duration::from_secs(5 * 60);
+
+/// Usuario y momento de la última comprobación correcta.
+static LAST_CHECK_IN: LazyLock<Mutex<Option<(String, Instant)>>> =
+    LazyLock::new(|| Mutex::new(None));
+
+/// Un dispositivo que ha subido guardados a este perfil.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceEntry {
+    pub id: String,
+    pub name: String,
+    pub hostname: String,
+    pub app_version: String,
+    /// Fechas RFC 3339 (UTC).
+    pub first_seen: String,
+    pub last_seen: String,
+    #[serde(default)]
+    pub revoked: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceRegistry {
+    #[serde(default)]
+    pub devices: Vec<DeviceEntry>,
+}
+
+impl DeviceRegistry {
+    pub fn find(&self, id: &str) -> Option<&DeviceEntry> {
+        self.devices.iter().find(|d| d.id == id)
+    }
+
+    /// Anota o refresca el dispositivo; devuelve si ha cambiado algo que guardar.
+    fn touch(&mut self, device: &DeviceIdentity, hostname: &str, now: DateTime<Utc>) -> bool {
+        let now_str = now.to_rfc3339();
+        let Some(entry) = self.devices.iter_mut().find(|d| d.id == device.id) else {
+            self.devices.push(DeviceEntry {
+                id: device.id.clone(),
+                name: device.name.clone(),
+                hostname: hostname.to_string(),
+                app_version: APP_VERSION.to_string(),
+                first_seen: now_str.clone(),
+                last_seen: now_str,
+                revoked: false,
+            });
+            return true;
+        };
+
+        let stale = DateTime::parse_from_rfc3339(&entry.last_seen)
+            .map(|t| now.signed_duration_since(t).num_hours() >= TOUCH_INTERVAL_HOURS)
+            .unwrap_or(true);
+        let changed = entry.name != device.name
+            || entry.hostname != hostname
+            || entry.app_version != APP_VERSION;
+        if !stale && !changed {
+            return false;
+        }
+        entry.name = device.name.clone();
+        entry.hostname = hostname.to_string();
+        entry.app_version = APP_VERSION.to_string();
+        entry.last_seen = now_str;
+        true
+    }
+}
+
+pub async fn load(storage: &Storage) -> Result<DeviceRegistry, String> {
+    Ok(read_json(storage, DEVICES_FOLDER, REGISTRY_FILE)
+        .await?
+        .unwrap_or_default())
+}
+
+async fn save(storage: &Storage, registry: &DeviceRegistry) -> Result<(), String> {
+    write_json(storage, DEVICES_FOLDER, REGISTRY_FILE, registry).await
+}
+
+/// Comprueba que este dispositivo puede subir y lo anota en el registro.
+///
+/// Solo falla si el dispositivo está revocado: si el registro no se puede leer
+/// (p. ej. sin red) la subida sigue y ya fallará por su cuenta.
+pub async fn check_in(storage: &Storage) -> Result<(), String> {
+    let user_id = storage.user_id().to_string();
+    let recent = LAST_CHECK_IN.lock().is_ok_and(|last| {
+        last.as_ref()
+            .is_some_and(|(user, at)| *user == user_id && at.elapsed() < CHECK_IN_CACHE)
+    });
+    if recent {
+        return Ok(());
+    }
+    check_in_as(storage, &identity::current(), &identity::hostname()).await?;
+    if let Ok(mut last) = LAST_CHECK_IN.lock() {
+        *last = Some((user_id, Instant::now()));
+    }
+    Ok(())
+}
+
+async fn check_in_as(
+    storage: &Storage,
+    device: &DeviceIdentity,
+    hostname: &str,
+) -> Result<(), String> {
+    let mut registry = match load(storage).await {
+        Ok(r) => r,
+        Err(e) => {
+            sync_logger::log_error("devices_load", &device.id, &e);
+            return Ok(());
+        }
+    };
+    if registry.find(&device.id).is_some_and(|d| d.revoked) {
+        return Err(format!(
+            "Este dispositivo ({}) fue revocado desde otro equipo y ya no puede subir guardados",
+            device.name
+        ));
+    }
+    if registry.touch(device, hostname, Utc::now()) {
+        if let Err(e) = save(storage, &registry).await {
+            sync_logger::log_error("devices_save", &device.id, &e);
+        }
+    }
+    Ok(())
+}
+
+/// Revoca otro dispositivo del perfil: deja de poder subir guardados.
+pub async fn revoke(storage: &Storage, device_id: &str) -> Result<(), String> {
+    if device_id == identity::current().id {
+        return Err("No se puede revocar el dispositivo actual".to_string());
+    }
+    revoke_in(storage, device_id).await
+}
+
+async fn revoke_in(storage: &Storage, device_id: &str) -> Result<(), String> {
+    let mut registry = load(storage).await?;
+    let entry = registry
+        .devices
+        .iter_mut()
+        .find(|d| d.id == device_id)
+        .ok_or_else(|| format!("Dispositivo no encontrado: {}", device_id))?;
+    entry.revoked = true;
+    save(storage, &registry).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::mock_api::MockApi;
+
+    fn device(id: &str) -> DeviceIdentity {
+        DeviceIdentity {
+            id: id.to_string(),
+            name: format!("PC {}", id),
+        }
+    }
+
+    #[tokio::test]
+    async fn revoked_devices_cannot_check_in() {
+        let api = MockApi::start().await;
+        let storage = Storage::Api(api.backend("user1"));
+
+        check_in_as(&storage, &device("salon"), "salon-pc")
+            .await
+            .unwrap();
+        check_in_as(&storage, &device("viejo"), "viejo-pc")
+            .await
+            .unwrap();
+        // Sin cambios y dentro del intervalo, no se reescribe el registro.
+        let registry = load(&storage).await.unwrap();
+        check_in_as(&storage, &device("salon"), "salon-pc")
+            .await
+            .unwrap();
+        assert_eq!(load(&storage).await.unwrap().devices, registry.devices);
+        assert_eq!(registry.devices.len(), 2);
+
+        revoke_in(&storage, "viejo").await.unwrap();
+        let err = check_in_as(&storage, &device("viejo"), "viejo-pc")
+            .await
+            .unwrap_err();
+        assert!(err.contains("revocado"));
+        check_in_as(&storage, &device("salon"), "salon-pc")
+            .await
+            .unwrap();
+        assert!(revoke_in(&storage, "nadie").await.is_err());
+    }
+}
@@ -0,0 +1,242 @@
+//! Sesiones de juego: aviso de que una partida sigue abierta en otro equipo.
+//!
+//! Es un bloqueo orientativo, no obligatorio. Al detectar que un juego arranca se
+//! escribe `.savecloud/session.json` en su carpeta remota con el dispositivo y la
+//! hora, y se renueva cada [`HEARTBEAT_INTERVAL`] mientras sigue abierto. La subida
+//! al cerrar el juego lo borra. Si el equipo se apaga o la subida falla, la sesión
+//! deja de renovarse y pasa a "caducada": la partida terminó sin subir sus guardados.
+
+use super::identity;
+use super::{read_json, write_json};
+use crate::commands::logs::sync_logger;
+use crate::storage::{object_key, Storage, StorageBackend, META_PREFIX};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Cada cuánto se renueva la sesión de un juego abierto.
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(2 * 60);
+
+/// Sin renovarse durante este tiempo, la sesión se da por caducada.
+const STALE_AFTER_MINUTES: i64 = 10;
+
+/// Nombre del archivo de sesión dentro de la carpeta del juego.
+fn session_filename() -> String {
+    format!("{}session.json", META_PREFIX)
+}
+
+/// Sesión abierta de un juego: quién lo está jugando y desde cuándo.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionLease {
+    pub device_id: String,
+    pub device_name: String,
+    pub hostname: String,
+    /// Fechas RFC 3339 (UTC).
+    pub started_at: String,
+    pub heartbeat_at: String,
+}
+
+impl SessionLease {
+    /// La sesión dejó de renovarse: el equipo se apagó o no llegó a subir al cerrar.
+    pub fn is_stale(&self, now: DateTime<Utc>) -> bool {
+        DateTime::parse_from_rfc3339(&self.heartbeat_at)
+            .map(|t| now.signed_duration_since(t).num_minutes() >= STALE_AFTER_MINUTES)
+            .unwrap_or(true)
+    }
+}
+
+/// Sesión de otro dispositivo tal como la ve la UI antes de lanzar el juego.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionLockDto {
+    #[serde(flatten)]
+    pub lease: SessionLease,
+    /// La sesión ya no se renueva; sus últimos guardados pueden no estar en la nube.
+    pub stale: bool,
+}
+
+impl SessionLockDto {
+    /// Aviso para el usuario al lanzar el juego. Solo una sesión activa impide lanzarlo
+    /// sin confirmar; una caducada se avisa y se juega.
+    pub fn warning(&self) -> String {
+        if self.stale {
+            format!(
+                "La última partida en {} (iniciada {}) no llegó a subir sus guardados: puede que los de la nube no sean los más recientes.",
+                self.lease.device_name,
+                local_time(&self.lease.started_at)
+            )
+        } else {
+            format!(
+                "Este juego está abierto en {} desde {}. Descarga el último guardado antes de jugar o inicia igualmente.",
+                self.lease.device_name,
+                local_time(&self.lease.started_at)
+            )
+        }
+    }
+}
+
+pub async fn load(storage: &Storage, game_id: &str) -> Result<Option<SessionLease>, String> {
+    read_json(storage, game_id, &session_filename()).await
+}
+
+/// Sesión de otro dispositivo sobre el juego, activa o caducada.
+pub async fn foreign_lock(
+    storage: &Storage,
+    game_id: &str,
+) -> Result<Option<SessionLockDto>, String> {
+    foreign_lock_for(storage, game_id, &identity::current().id).await
+}
+
+async fn foreign_lock_for(
+    storage: &Storage,
+    game_id: &str,
+    device_id: &str,
+) -> Result<Option<SessionLockDto>, String> {
+    Ok(load(storage, game_id)
+        .await?
+        .filter(|lease| lease.device_id != device_id)
+        .map(|lease| SessionLockDto {
+            stale: lease.is_stale(Utc::now()),
+            lease,
+        }))
+}
+
+/// Abre una sesión de este dispositivo; reemplaza la de cualquier otro.
+pub async fn acquire(storage: &Storage, game_id: &str) -> Result<(), String> {
+    let device = identity::current();
+    let now = now_rfc3339();
+    let lease = SessionLease {
+        device_id: device.id,
+        device_name: device.name,
+        hostname: identity::hostname(),
+        started_at: now.clone(),
+        heartbeat_at: now,
+    };
+    write_json(storage, game_id, &session_filename(), &lease).await
+}
+
+/// Renueva la sesión de este dispositivo. Si otro equipo ha abierto el juego después,
+/// su sesión se respeta; si la nuestra ha desaparecido, se vuelve a crear.
+pub async fn heartbeat(storage: &Storage, game_id: &str) -> Result<(), String> {
+    let device_id = identity::current().id;
+    match load(storage, game_id).await? {
+        Some(mut lease) if lease.device_id == device_id => {
+            lease.heartbeat_at = now_rfc3339();
+            write_json(storage, game_id, &session_filename(), &lease).await
+        }
+        Some(_) => Ok(()),
+        None => acquire(storage, game_id).await,
+    }
+}
+
+/// Cierra la sesión si es de este dispositivo.
+pub async fn release(storage: &Storage, game_id: &str) -> Result<(), String> {
+    release_for(storage, game_id, &identity::current().id).await
+}
+
+async fn release_for(storage: &Storage, game_id: &str, device_id: &str) -> Result<(), String> {
+    match load(storage, game_id).await? {
+        Some(lease) if lease.device_id == device_id => {
+            let key = object_key(storage.user_id(), game_id, &session_filename());
+            storage.delete(game_id, &key).await?;
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Fecha RFC 3339 en hora local y formato corto ("18/10 21:40") para los avisos.
+fn local_time(rfc3339: &str) -> String {
+    DateTime::parse_from_rfc3339(rfc3339)
+        .map(|t| {
+            t.with_timezone(&chrono::Local)
+                .format("%d/%m %H:%M")
+                .to_string()
+        })
+        .unwrap_or_else(|_| rfc3339.to_string())
+}
+
+fn now_rfc3339() -> String {
+    Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+}
+
+/// Cambio de estado de un juego visto por el observador de procesos.
+#[derive(Debug, Clone, Copy)]
+pub enum SessionEvent {
+    Started,
+    Heartbeat,
+}
+
+/// Publica el cambio en segundo plano sin bloquear al observador de procesos.
+///
+/// Sin backend configurado no hace nada; los errores (p. ej. sin red) solo se registran.
+pub fn notify(game_id: &str, event: SessionEvent) {
+    let Ok(storage) = Storage::active() else {
+        return;
+    };
+    let game_id = game_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        let result = match event {
+            SessionEvent::Started => acquire(&storage, &game_id).await,
+            SessionEvent::Heartbeat => heartbeat(&storage, &game_id).await,
+        };
+        if let Err(e) = result {
+            sync_logger::log_error("session_lease", &game_id, &e);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::mock_api::MockApi;
+
+    fn lease(device_id: &str, heartbeat_at: &str) -> SessionLease {
+        SessionLease {
+            device_id: device_id.to_string(),
+            device_name: format!("PC {}", device_id),
+            hostname: device_id.to_string(),
+            started_at: heartbeat_at.to_string(),
+            heartbeat_at: heartbeat_at.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn reports_and_releases_foreign_sessions() {
+        let api = MockApi::start().await;
+        let storage = Storage::Api(api.backend("user1"));
+        assert!(foreign_lock_for(&storage, "hades", "salon")
+            .await
+            .unwrap()
+            .is_none());
+
+        let portatil = lease("portatil", &now_rfc3339());
+        write_json(&storage, "hades", &session_filename(), &portatil)
+            .await
+            .unwrap();
+        let lock = foreign_lock_for(&storage, "hades", "salon")
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!lock.stale);
+        // La propia sesión no bloquea, y solo su dueño la cierra.
+        assert!(foreign_lock_for(&storage, "hades", "portatil")
+            .await
+            .unwrap()
+            .is_none());
+        release_for(&storage, "hades", "salon").await.unwrap();
+        assert_eq!(load(&storage, "hades").await.unwrap(), Some(portatil));
+        release_for(&storage, "hades", "portatil").await.unwrap();
+        assert!(load(&storage, "hades").await.unwrap().is_none());
+    }
+
+    #[test]
+    fn sessions_go_stale_without_heartbeat() {
+        let now = Utc::now();
+        let old = (now - chrono::Duration::minutes(STALE_AFTER_MINUTES + 1)).to_rfc3339();
+        assert!(lease("a", &old).is_stale(now));
+        assert!(!lease("a", &now.to_rfc3339()).is_stale(now));
+        assert!(lease("a", "no es una fecha").is_stale(now));
+    }
+}
@@ -0,0 +1,131 @@
+//! Proveedor de Dolphin (GameCube y Wii).
+//!
+//! - Wii: cada juego tiene su carpeta `Wii/title/00010000/<id en hex>/data`.
+//! - GameCube con carpetas GCI: `GC/<región>/Card A/<nn>-<ID>-<nombre>.gci`, varios juegos
+//!   por carpeta; se separan por el ID de 4 caracteres.
+//! - GameCube con tarjetas `.raw`: una tarjeta compartida por región, que se ofrece como
+//!   entrada propia porque no puede dividirse por juego.
+
+use super::{existing_dirs, file_names, subdirs, EmulatorKind, EmulatorSaveEntry};
+use std::path::PathBuf;
+
+/// Carpetas de usuario de Dolphin (las que contienen `GC` y `Wii`).
+fn dolphin_roots() -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    #[cfg(target_os = "windows")]
+    {
+        if let Some(docs) = dirs::document_dir() {
+            candidates.push(docs.join("Dolphin Emulator"));
+        }
+        if let Some(appdata) = dirs::config_dir() {
+            candidates.push(appdata.join("Dolphin Emulator"));
+        }
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        if let Some(data) = dirs::data_dir() {
+            candidates.push(data.join("dolphin-emu"));
+        }
+        if let Some(home) = dirs::home_dir() {
+            candidates.push(home.join(".dolphin-emu"));
+        }
+        candidates.extend(super::flatpak_dir(
+            "org.DolphinEmu.dolphin-emu",
+            "data",
+            "dolphin-emu",
+        ));
+    }
+    existing_dirs(candidates)
+        .into_iter()
+        .filter(|root| root.join("GC").is_dir() || root.join("Wii").is_dir())
+        .collect()
+}
+
+/// ID de juego de Wii a partir de la parte baja del title ID (`52534245` → `RSBE`).
+fn wii_game_id(hex: &str) -> Option<String> {
+    if hex.len() != 8 || !hex.is_ascii() {
+        return None;
+    }
+    let id: String = (0..4)
+        .map(|i| {
+            u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)
+                .ok()
+                .map(char::from)
+        })
+        .collect::<Option<_>>()?;
+    id.chars().all(|c| c.is_ascii_alphanumeric()).then_some(id)
+}
+
+/// ID de juego de un archivo GCI (`01-GALE-SuperSmashBros0110290334.gci` → `GALE`).
+fn gci_game_id(file_name: &str) -> Option<&str> {
+    let stem = file_name
+        .strip_suffix(".gci")
+        .or_else(|| file_name.strip_suffix(".GCI"))?;
+    let mut parts = stem.splitn(3, '-');
+    let _maker = parts.next()?;
+    let id = parts.next()?;
+    (id.len() == 4 && id.chars().all(|c| c.is_ascii_alphanumeric())).then_some(id)
+}
+
+pub fn save_entries() -> Vec<EmulatorSaveEntry> {
+    let mut collector = super::EntryCollector::default();
+
+    for root in dolphin_roots() {
+        let wii_titles = root.join("Wii").join("title").join("00010000");
+        for (title_dir, hex) in subdirs(&wii_titles) {
+            let data = title_dir.join("data");
+            let Some(id) = wii_game_id(&hex) else {
+                continue;
+            };
+            if data.is_dir() {
+                collector.add(
+                    EmulatorKind::Dolphin,
+                    &id,
+                    &id,
+                    data.to_string_lossy().to_string(),
+                );
+            }
+        }
+
+        for (region_dir, _) in subdirs(&root.join("GC")) {
+            for (card_dir, _) in subdirs(&region_dir) {
+                for file_name in file_names(&card_dir) {
+                    let Some(id) = gci_game_id(&file_name) else {
+                        continue;
+                    };
+                    let pattern = card_dir.join(format!("*-{}-*.gci", id));
+                    collector.add(
+                        EmulatorKind::Dolphin,
+                        id,
+                        id,
+                        pattern.to_string_lossy().to_string(),
+                    );
+                }
+            }
+            for file_name in file_names(&region_dir) {
+                if file_name.to_ascii_lowercase().ends_with(".raw") {
+                    let path = region_dir.join(&file_name).to_string_lossy().to_string();
+                    collector.add(EmulatorKind::Dolphin, &path, &file_name, path.clone());
+                }
+            }
+        }
+    }
+
+    collector.into_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_wii_and_gci_game_ids() {
+        assert_eq!(wii_game_id("52534245").as_deref(), Some("RSBE"));
+        assert_eq!(wii_game_id("00000001"), None);
+        assert_eq!(
+            gci_game_id("01-GALE-SuperSmashBros0110290334.gci"),
+            Some("GALE")
+        );
+        assert_eq!(gci_game_id("MemoryCardA.USA.raw"), None);
+    }
+}
@@ -0,0 +1,107 @@
+//! Proveedor de DuckStation.
+//!
+//! Con tarjetas "por título" (la opción por defecto) cada juego tiene
+//! `memcards/<título>_1.mcd` y `_2.mcd`; las tarjetas compartidas (`shared_card_N.mcd`)
+//! se ofrecen como una entrada propia. Los estados guardados van a
+//! `savestates/<serial>_<n>.sav` y se separan por serial.
+
+use super::{existing_dirs, file_names, glob_path, EmulatorKind, EmulatorSaveEntry};
+use std::path::PathBuf;
+
+/// Prefijo de las tarjetas de memoria compartidas.
+const SHARED_CARD_PREFIX: &str = "shared_card";
+
+/// Carpetas de datos de DuckStation (las que contienen `memcards` o `savestates`).
+fn duckstation_roots() -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    #[cfg(target_os = "windows")]
+    {
+        if let Some(docs) = dirs::document_dir() {
+            candidates.push(docs.join("DuckStation"));
+        }
+        if let Some(local) = dirs::data_local_dir() {
+            candidates.push(local.join("DuckStation"));
+        }
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        if let Some(data) = dirs::data_dir() {
+            candidates.push(data.join("duckstation"));
+        }
+        candidates.extend(super::flatpak_dir(
+            "org.duckstation.DuckStation",
+            "data",
+            "duckstation",
+        ));
+    }
+    existing_dirs(candidates)
+        .into_iter()
+        .filter(|root| root.join("memcards").is_dir() || root.join("savestates").is_dir())
+        .collect()
+}
+
+/// Parte anterior a `_<sufijo>` en `nombre_<sufijo>.<ext>` (`Crash_1.mcd` → `Crash`).
+fn split_suffix<'a>(file_name: &'a str, ext: &str) -> Option<&'a str> {
+    let lower = file_name.to_ascii_lowercase();
+    if !lower.ends_with(ext) {
+        return None;
+    }
+    let stem = &file_name[..file_name.len() - ext.len()];
+    let (name, _) = stem.rsplit_once('_')?;
+    (!name.is_empty()).then_some(name)
+}
+
+pub fn save_entries() -> Vec<EmulatorSaveEntry> {
+    let mut collector = super::EntryCollector::default();
+
+    for root in duckstation_roots() {
+        let memcards = root.join("memcards");
+        for file_name in file_names(&memcards) {
+            let Some(title) = split_suffix(&file_name, ".mcd") else {
+                continue;
+            };
+            if let Some(path) = glob_path(&memcards, title, "_?.mcd") {
+                let label = if title == SHARED_CARD_PREFIX {
+                    "Tarjetas compartidas"
+                } else {
+                    title
+                };
+                collector.add(EmulatorKind::DuckStation, title, label, path);
+            }
+        }
+
+        let savestates = root.join("savestates");
+        for file_name in file_names(&savestates) {
+            let Some(serial) = split_suffix(&file_name, ".sav") else {
+                continue;
+            };
+            if let Some(path) = glob_path(&savestates, serial, "_*.sav") {
+                collector.add(EmulatorKind::DuckStation, serial, serial, path);
+            }
+        }
+    }
+
+    collector.into_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_title_and_serial_suffixes() {
+        assert_eq!(
+            split_suffix("Crash Bandicoot_1.mcd", ".mcd"),
+            Some("Crash Bandicoot")
+        );
+        assert_eq!(
+            split_suffix("shared_card_2.mcd", ".mcd"),
+            Some("shared_card")
+        );
+        assert_eq!(
+            split_suffix("SCUS-94900_resume.sav", ".sav"),
+            Some("SCUS-94900")
+        );
+        assert_eq!(split_suffix("readme.txt", ".mcd"), None);
+    }
+}
@@ -0,0 +1,175 @@
+//! Detección de guardados de emuladores.
+//!
+//! Los emuladores suelen guardar todos los juegos en carpetas compartidas (`saves`,
+//! `memcards`, `sstates`...). Cada proveedor conoce las carpetas de su emulador en
+//! Windows y Linux (incluido Flatpak) y las divide en entradas lógicas por juego:
+//!
+//! - RetroArch: por nombre de ROM (`saves/<rom>.srm`, `states/<rom>.state*`).
+//! - Dolphin: por ID de juego (Wii `title/00010000/<id>`, GameCube `*.gci`).
+//! - PCSX2: tarjetas de memoria y estados por serial (`sstates/<serial> (<crc>)...`).
+//! - Yuzu y derivados: por title ID (`nand/user/save/.../<title_id>`).
+//! - DuckStation: tarjetas por juego (`memcards/<título>_1.mcd`) y estados por serial.
+//!
+//! Las rutas de cada entrada son carpetas propias del juego o patrones con comodines
+//! (`carpeta/<rom>.*`), de modo que pueden sincronizarse como `ConfiguredGame`
+//! independientes sin copiar la carpeta completa del emulador.
+
+mod dolphin;
+mod duckstation;
+mod pcsx2;
+mod retroarch;
+mod switch;
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Emulador del que procede una entrada de guardado.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum EmulatorKind {
+    RetroArch,
+    Dolphin,
+    Pcsx2,
+    Yuzu,
+    DuckStation,
+}
+
+impl EmulatorKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            EmulatorKind::RetroArch => "RetroArch",
+            EmulatorKind::Dolphin => "Dolphin",
+            EmulatorKind::Pcsx2 => "PCSX2",
+            EmulatorKind::Yuzu => "Yuzu",
+            EmulatorKind::DuckStation => "DuckStation",
+        }
+    }
+}
+
+/// Guardados de un juego dentro de las carpetas de un emulador.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct EmulatorSaveEntry {
+    pub emulator: EmulatorKind,
+    /// Clave del juego dentro del emulador (nombre de ROM, title ID, serial...).
+    pub game_key: String,
+    pub title: String,
+    /// Carpetas o patrones (`carpeta/archivo.*`) que pertenecen solo a este juego.
+    pub paths: Vec<String>,
+}
+
+/// Acumula entradas agrupando por clave de juego.
+#[derive(Default)]
+struct EntryCollector {
+    entries: Vec<EmulatorSaveEntry>,
+}
+
+impl EntryCollector {
+    fn add(&mut self, emulator: EmulatorKind, game_key: &str, title: &str, path: String) {
+        if let Some(entry) = self
+            .entries
+            .iter_mut()
+            .find(|e| e.emulator == emulator && e.game_key.eq_ignore_ascii_case(game_key))
+        {
+            if !entry.paths.contains(&path) {
+                entry.paths.push(path);
+            }
+            return;
+        }
+        self.entries.push(EmulatorSaveEntry {
+            emulator,
+            game_key: game_key.to_string(),
+            title: title.to_string(),
+            paths: vec![path],
+        });
+    }
+
+    fn into_vec(self) -> Vec<EmulatorSaveEntry> {
+        self.entries
+    }
+}
+
+/// Patrón `carpeta/<nombre>` listo para `ConfiguredGame::paths`.
+///
+/// Devuelve `None` si el nombre ya contiene comodines y no puede usarse como literal.
+fn glob_path(dir: &Path, pattern_prefix: &str, pattern_suffix: &str) -> Option<String> {
+    if pattern_prefix.contains(['*', '?']) {
+        return None;
+    }
+    Some(
+        dir.join(format!("{}{}", pattern_prefix, pattern_suffix))
+            .to_string_lossy()
+            .to_string(),
+    )
+}
+
+/// Filtra candidatos a carpetas existentes, sin duplicados.
+fn existing_dirs(candidates: impl IntoIterator<Item = PathBuf>) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    for candidate in candidates {
+        if !candidate.is_dir() {
+            continue;
+        }
+        let canonical = candidate.canonicalize().unwrap_or(candidate);
+        if !dirs.contains(&canonical) {
+            dirs.push(canonical);
+        }
+    }
+    dirs
+}
+
+/// Carpeta de una app Flatpak (`~/.var/app/<app_id>/<kind>/<name>`), `kind` = `config` o `data`.
+#[cfg(not(target_os = "windows"))]
+fn flatpak_dir(app_id: &str, kind: &str, name: &str) -> Option<PathBuf> {
+    Some(
+        dirs::home_dir()?
+            .join(".var")
+            .join("app")
+            .join(app_id)
+            .join(kind)
+            .join(name),
+    )
+}
+
+/// Nombres de archivo de una carpeta (sin subcarpetas).
+fn file_names(dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_file()))
+        .filter_map(|e| e.file_name().into_string().ok())
+        .collect()
+}
+
+/// Subcarpetas de una carpeta con su nombre.
+fn subdirs(dir: &Path) -> Vec<(PathBuf, String)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .filter_map(|e| Some((e.path(), e.file_name().into_string().ok()?)))
+        .collect()
+}
+
+/// Recorre todos los emuladores y devuelve sus guardados divididos por juego.
+pub fn list_emulator_saves() -> Vec<EmulatorSaveEntry> {
+    let mut entries = Vec::new();
+    entries.extend(retroarch::save_entries());
+    entries.extend(dolphin::save_entries());
+    entries.extend(pcsx2::save_entries());
+    entries.extend(switch::save_entries());
+    entries.extend(duckstation::save_entries());
+    entries
+}
+
+/// Devuelve los guardados de emuladores detectados, una entrada por juego.
+#[tauri::command]
+pub async fn scan_emulator_saves() -> Result<Vec<EmulatorSaveEntry>, String> {
+    tauri::async_runtime::spawn_blocking(list_emulator_saves)
+        .await
+        .map_err(|e| format!("Error en el hilo de escaneo: {}", e))
+}
@@ -0,0 +1,127 @@
+//! Proveedor de PCSX2.
+//!
+//! Las tarjetas de memoria (`memcards/*.ps2`, archivo o carpeta) son compartidas por
+//! todos los juegos y se ofrecen como entradas propias. Los estados guardados van a
+//! `sstates/<serial> (<crc>).<nn>.p2s` y se separan por serial.
+
+use super::{
+    existing_dirs, file_names, glob_path, subdirs, EmulatorKind, EmulatorSaveEntry, EntryCollector,
+};
+use std::path::{Path, PathBuf};
+
+/// Carpetas de datos de PCSX2 (las que contienen `memcards` o `sstates`).
+fn pcsx2_roots() -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    #[cfg(target_os = "windows")]
+    {
+        if let Some(docs) = dirs::document_dir() {
+            candidates.push(docs.join("PCSX2"));
+        }
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        if let Some(config) = dirs::config_dir() {
+            candidates.push(config.join("PCSX2"));
+        }
+        candidates.extend(super::flatpak_dir("net.pcsx2.PCSX2", "config", "PCSX2"));
+    }
+    existing_dirs(candidates)
+        .into_iter()
+        .filter(|root| root.join("memcards").is_dir() || root.join("sstates").is_dir())
+        .collect()
+}
+
+/// Serial del juego de un estado guardado (`SLUS-20312 (5B8D8F2C).01.p2s` → `SLUS-20312`).
+fn savestate_serial(file_name: &str) -> Option<&str> {
+    if !file_name.to_ascii_lowercase().ends_with(".p2s") {
+        return None;
+    }
+    let (serial, _) = file_name.split_once(" (")?;
+    (!serial.is_empty()).then_some(serial)
+}
+
+pub fn save_entries() -> Vec<EmulatorSaveEntry> {
+    let mut collector = EntryCollector::default();
+    for root in pcsx2_roots() {
+        collect_root(&root, &mut collector);
+    }
+    collector.into_vec()
+}
+
+/// Entradas de una carpeta de datos de PCSX2.
+fn collect_root(root: &Path, collector: &mut EntryCollector) {
+    let memcards = root.join("memcards");
+    let cards = file_names(&memcards)
+        .into_iter()
+        .chain(subdirs(&memcards).into_iter().map(|(_, name)| name));
+    for name in cards {
+        if name.to_ascii_lowercase().ends_with(".ps2") {
+            let path = memcards.join(&name).to_string_lossy().to_string();
+            collector.add(EmulatorKind::Pcsx2, &path, &name, path.clone());
+        }
+    }
+
+    let sstates = root.join("sstates");
+    for file_name in file_names(&sstates) {
+        let Some(serial) = savestate_serial(&file_name) else {
+            continue;
+        };
+        if let Some(path) = glob_path(&sstates, serial, " (*") {
+            collector.add(EmulatorKind::Pcsx2, serial, serial, path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::path_utils::{split_glob, wildcard_match};
+
+    #[test]
+    fn savestate_serial_takes_the_name_before_the_crc() {
+        assert_eq!(
+            savestate_serial("SLUS-20312 (5B8D8F2C).01.p2s"),
+            Some("SLUS-20312")
+        );
+        assert_eq!(savestate_serial("SLUS-20312 (5B8D8F2C).01.png"), None);
+        assert_eq!(savestate_serial(" (5B8D8F2C).01.p2s"), None);
+        assert_eq!(savestate_serial("sin_crc.p2s"), None);
+    }
+
+    #[test]
+    fn splits_memcards_and_savestates_per_game() {
+        let root = tempfile::tempdir().unwrap();
+        let memcards = root.path().join("memcards");
+        let sstates = root.path().join("sstates");
+        std::fs::create_dir_all(memcards.join("Carpeta.ps2")).unwrap();
+        std::fs::create_dir_all(&sstates).unwrap();
+        std::fs::write(memcards.join("Mcd001.ps2"), b"").unwrap();
+        std::fs::write(memcards.join("notas.txt"), b"").unwrap();
+        for name in [
+            "SLUS-20312 (5B8D8F2C).01.p2s",
+            "SLUS-20312 (5B8D8F2C).02.p2s",
+            "SCES-50360 (9C7B8A1D).00.p2s",
+            "SLUS-20312 (5B8D8F2C).01.png",
+        ] {
+            std::fs::write(sstates.join(name), b"").unwrap();
+        }
+
+        let mut collector = EntryCollector::default();
+        collect_root(root.path(), &mut collector);
+        let entries = collector.into_vec();
+
+        let mut titles: Vec<&str> = entries.iter().map(|e| e.title.as_str()).collect();
+        titles.sort_unstable();
+        assert_eq!(
+            titles,
+            ["Carpeta.ps2", "Mcd001.ps2", "SCES-50360", "SLUS-20312"]
+        );
+
+        let slus = entries.iter().find(|e| e.game_key == "SLUS-20312").unwrap();
+        assert_eq!(slus.paths.len(), 1);
+        let (dir, pattern) = split_glob(&slus.paths[0]).unwrap();
+        assert_eq!(dir, sstates);
+        assert!(wildcard_match(&pattern, "SLUS-20312 (5B8D8F2C).02.p2s"));
+        assert!(!wildcard_match(&pattern, "SCES-50360 (9C7B8A1D).00.p2s"));
+    }
+}
@@ -0,0 +1,147 @@
+//! Proveedor de RetroArch.
+//!
+//! Las partidas (`.srm`) y estados (`.state`, `.state1`...) de todos los núcleos van a
+//! `saves/` y `states/`, o a las carpetas de `savefile_directory` / `savestate_directory`
+//! en `retroarch.cfg`. Con "ordenar por núcleo" activo hay una subcarpeta por núcleo.
+//! Cada juego se identifica por el nombre de la ROM sin extensión.
+
+use super::{existing_dirs, file_names, glob_path, subdirs, EmulatorKind, EmulatorSaveEntry};
+use std::path::{Path, PathBuf};
+
+/// Extensiones de guardado que RetroArch añade al nombre de la ROM.
+const SAVE_EXTENSIONS: &[&str] = &[
+    "srm", "sav", "rtc", "mcr", "ldci", "eep", "fla", "mpk", "sra",
+];
+
+/// Carpetas raíz de RetroArch (donde está `retroarch.cfg`).
+fn retroarch_roots() -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    #[cfg(target_os = "windows")]
+    {
+        if let Some(appdata) = dirs::config_dir() {
+            candidates.push(appdata.join("RetroArch"));
+        }
+        candidates.push(PathBuf::from(r"C:\RetroArch-Win64"));
+        candidates.push(PathBuf::from(r"C:\RetroArch"));
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        if let Some(config) = dirs::config_dir() {
+            candidates.push(config.join("retroarch"));
+        }
+        candidates.extend(super::flatpak_dir(
+            "org.libretro.RetroArch",
+            "config",
+            "retroarch",
+        ));
+    }
+    existing_dirs(candidates)
+}
+
+/// Valor de una clave de `retroarch.cfg` (`clave = "valor"`).
+fn cfg_value<'a>(content: &'a str, key: &str) -> Option<&'a str> {
+    content.lines().find_map(|line| {
+        let (k, v) = line.split_once('=')?;
+        (k.trim() == key).then(|| v.trim().trim_matches('"'))
+    })
+}
+
+/// Resuelve una carpeta de la configuración: `default` es la carpeta por defecto y `:`
+/// al inicio significa relativa a la raíz de RetroArch.
+fn resolve_cfg_dir(root: &Path, value: Option<&str>, default_name: &str) -> PathBuf {
+    match value.map(str::trim) {
+        None | Some("") | Some("default") => root.join(default_name),
+        Some(v) => {
+            if let Some(rest) = v.strip_prefix(':') {
+                root.join(rest.trim_start_matches(['/', '\\']))
+            } else {
+                crate::utils::path_utils::expand_path(v)
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|| PathBuf::from(v))
+            }
+        }
+    }
+}
+
+/// Nombre de la ROM a partir de un archivo de guardado o estado.
+///
+/// `Juego.srm` → `Juego`, `Juego.state3` → `Juego`, `Juego.state.auto` → `Juego`,
+/// `Juego.state1.png` → `Juego`. Devuelve `None` si no es un archivo de RetroArch.
+fn rom_key(file_name: &str) -> Option<&str> {
+    let mut name = file_name;
+    for suffix in [".png", ".auto"] {
+        if let Some(stripped) = name.strip_suffix(suffix) {
+            name = stripped;
+        }
+    }
+    let (stem, ext) = name.rsplit_once('.')?;
+    let ext = ext.to_ascii_lowercase();
+    let is_state = ext
+        .strip_prefix("state")
+        .is_some_and(|n| n.chars().all(|c| c.is_ascii_digit()));
+    if stem.is_empty() || !(is_state || SAVE_EXTENSIONS.contains(&ext.as_str())) {
+        return None;
+    }
+    Some(stem)
+}
+
+/// Carpeta de guardados y sus subcarpetas por núcleo.
+fn with_core_subdirs(dir: PathBuf) -> Vec<PathBuf> {
+    if !dir.is_dir() {
+        return Vec::new();
+    }
+    let mut dirs: Vec<PathBuf> = subdirs(&dir).into_iter().map(|(p, _)| p).collect();
+    dirs.insert(0, dir);
+    dirs
+}
+
+pub fn save_entries() -> Vec<EmulatorSaveEntry> {
+    let mut collector = super::EntryCollector::default();
+
+    for root in retroarch_roots() {
+        let cfg = std::fs::read_to_string(root.join("retroarch.cfg")).unwrap_or_default();
+        let save_dir = resolve_cfg_dir(&root, cfg_value(&cfg, "savefile_directory"), "saves");
+        let state_dir = resolve_cfg_dir(&root, cfg_value(&cfg, "savestate_directory"), "states");
+
+        // Los estados usan un patrón más específico para que, al restaurar, vuelvan a
+        // `states` aunque el patrón de `saves` también los acepte.
+        let dirs = with_core_subdirs(save_dir)
+            .into_iter()
+            .map(|d| (d, ".*"))
+            .chain(
+                with_core_subdirs(state_dir)
+                    .into_iter()
+                    .map(|d| (d, ".state*")),
+            );
+        for (dir, suffix) in dirs {
+            for file_name in file_names(&dir) {
+                let Some(key) = rom_key(&file_name) else {
+                    continue;
+                };
+                if let Some(path) = glob_path(&dir, key, suffix) {
+                    collector.add(EmulatorKind::RetroArch, key, key, path);
+                }
+            }
+        }
+    }
+
+    collector.into_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rom_key_strips_save_and_state_extensions() {
+        assert_eq!(
+            rom_key("Super Metroid (USA).srm"),
+            Some("Super Metroid (USA)")
+        );
+        assert_eq!(rom_key("Chrono Trigger.state3"), Some("Chrono Trigger"));
+        assert_eq!(rom_key("Chrono Trigger.state.auto"), Some("Chrono Trigger"));
+        assert_eq!(rom_key("Chrono Trigger.state1.png"), Some("Chrono Trigger"));
+        assert_eq!(rom_key("notes.txt"), None);
+        assert_eq!(rom_key(".srm"), None);
+    }
+}
@@ -0,0 +1,95 @@
+//! Proveedor de Yuzu y sus derivados (Suyu, Sudachi, Citron, Eden).
+//!
+//! Cada juego guarda en su propia carpeta dentro de la NAND emulada:
+//! `nand/user/save/0000000000000000/<id de usuario>/<title id>`.
+
+use super::{existing_dirs, subdirs, EmulatorKind, EmulatorSaveEntry, EntryCollector};
+use std::path::{Path, PathBuf};
+
+/// Nombres de carpeta de Yuzu y sus derivados.
+const FORKS: &[&str] = &["yuzu", "suyu", "sudachi", "citron", "eden"];
+
+/// Carpetas de usuario de los emuladores (las que contienen `nand`).
+fn switch_roots() -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    #[cfg(target_os = "windows")]
+    let base = dirs::config_dir();
+    #[cfg(not(target_os = "windows"))]
+    let base = dirs::data_dir();
+    if let Some(base) = base {
+        candidates.extend(FORKS.iter().map(|fork| base.join(fork)));
+    }
+    #[cfg(not(target_os = "windows"))]
+    candidates.extend(super::flatpak_dir("org.yuzu_emu.yuzu", "data", "yuzu"));
+    existing_dirs(candidates)
+        .into_iter()
+        .filter(|root| root.join("nand").is_dir())
+        .collect()
+}
+
+fn is_title_id(name: &str) -> bool {
+    name.len() == 16 && name.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+pub fn save_entries() -> Vec<EmulatorSaveEntry> {
+    let mut collector = EntryCollector::default();
+    for root in switch_roots() {
+        collect_root(&root, &mut collector);
+    }
+    collector.into_vec()
+}
+
+/// Entradas de una carpeta de usuario del emulador, una por title ID.
+fn collect_root(root: &Path, collector: &mut EntryCollector) {
+    let saves = root
+        .join("nand")
+        .join("user")
+        .join("save")
+        .join("0000000000000000");
+    for (user_dir, _) in subdirs(&saves) {
+        for (title_dir, title_id) in subdirs(&user_dir) {
+            if !is_title_id(&title_id) {
+                continue;
+            }
+            let title_id = title_id.to_ascii_uppercase();
+            collector.add(
+                EmulatorKind::Yuzu,
+                &title_id,
+                &title_id,
+                title_dir.to_string_lossy().to_string(),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn title_ids_are_sixteen_hex_digits() {
+        assert!(is_title_id("0100F2C0115B6000"));
+        assert!(is_title_id("0100f2c0115b6000"));
+        assert!(!is_title_id("0100F2C0115B600"));
+        assert!(!is_title_id("0100F2C0115B600G"));
+    }
+
+    #[test]
+    fn one_entry_per_title_folder() {
+        let root = tempfile::tempdir().unwrap();
+        let user = root
+            .path()
+            .join("nand/user/save/0000000000000000/8F3A1C2B4D5E6F708192A3B4C5D6E7F8");
+        let title = user.join("0100f2c0115b6000");
+        std::fs::create_dir_all(&title).unwrap();
+        std::fs::create_dir_all(user.join("cache")).unwrap();
+
+        let mut collector = EntryCollector::default();
+        collect_root(root.path(), &mut collector);
+        let entries = collector.into_vec();
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].game_key, "0100F2C0115B6000");
+        assert_eq!(entries[0].paths, [title.to_string_lossy().to_string()]);
+    }
+}
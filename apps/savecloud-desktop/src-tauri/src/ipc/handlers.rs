@@ -18,6 +18,9 @@ pub fn register_all_commands(builder: Builder<Wry>) -> Builder<Wry> {
         crate::config::config_cmds::set_keep_backups_per_game,
         crate::config::config_cmds::set_full_backup_streaming,
         crate::config::config_cmds::set_full_backup_streaming_dry_run,
+        crate::config::config_cmds::set_idle_detection,
+        crate::config::config_cmds::set_storage_backend,
+        crate::storage::test_storage_backend,
         crate::config::config_cmds::set_profile_appearance,
         crate::steam::steam_search::get_steam_app_name,
         crate::steam::steam_search::get_steam_appdetails_media,
@@ -31,10 +34,18 @@ pub fn register_all_commands(builder: Builder<Wry>) -> Builder<Wry> {
         crate::config::config_cmds::remove_game,
         crate::config::config_cmds::list_running_process_exe_names,
         crate::config::config_cmds::launch_game,
+        crate::launch::launch_game_with_sync,
         crate::config::config_cmds::set_game_launch_executable,
         crate::config::config_cmds::set_game_executable_names,
+        crate::config::config_cmds::set_game_install_dir,
+        crate::config::config_cmds::set_game_process_rules,
         crate::config::config_cmds::read_image_as_data_url,
         crate::commands::scan::scan_path_candidates,
+        crate::manifest::manifest_search::search_manifest_games,
+        crate::manifest::manifest_search::suggest_manifest_save_paths,
+        crate::launchers::list_launcher_games,
+        crate::steam::shortcuts::list_steam_shortcuts,
+        crate::emulators::scan_emulator_saves,
         crate::commands::sync::list_save_files,
         crate::commands::sync::upload::request_upload_cancel,
         crate::commands::sync::upload::request_upload_pause,
@@ -66,6 +77,26 @@ pub fn register_all_commands(builder: Builder<Wry>) -> Builder<Wry> {
         crate::commands::sync::check_game_running,
         crate::commands::sync::check_games_running,
         crate::commands::sync::get_sync_debug_log_path,
+        crate::commands::sync::queue::list_sync_jobs,
+        crate::commands::sync::queue::enqueue_sync_job,
+        crate::commands::sync::queue::set_sync_job_priority,
+        crate::commands::sync::queue::cancel_sync_job,
+        crate::commands::sync::queue::retry_sync_job,
+        crate::transfers::list_transfers,
+        crate::transfers::pause_transfer,
+        crate::transfers::cancel_transfer,
+        crate::transfers::pause_game_transfers,
+        crate::transfers::cancel_game_transfers,
+        crate::commands::sync::paused::list_paused_transfers,
+        crate::commands::sync::paused::resume_paused_transfer,
+        crate::commands::sync::paused::resume_game_transfers,
+        crate::commands::sync::paused::cancel_paused_transfer,
+        crate::devices::get_device_info,
+        crate::devices::set_device_name,
+        crate::devices::list_devices,
+        crate::devices::revoke_device,
+        crate::devices::get_last_uploads,
+        crate::devices::get_game_session_lock,
         crate::config::config_cmds::open_save_folder,
         crate::config::config_cmds::export_config_to_file,
         crate::config::config_cmds::import_config_from_file,
@@ -84,6 +115,10 @@ pub fn register_all_commands(builder: Builder<Wry>) -> Builder<Wry> {
         crate::config::config_cmds::mark_shortcuts_hint_seen,
         crate::config::config_cmds::mark_weekly_digest_notified,
         crate::config::config_cmds::should_show_weekly_digest_notification,
+        crate::time::playtime_cmds::get_playtime_aggregates,
+        crate::time::playtime_cmds::get_longest_play_sessions,
+        crate::time::playtime_cmds::list_play_sessions,
+        crate::time::playtime_cmds::get_last_played,
         crate::tray::tray_tooltip::refresh_tray_tooltip,
         crate::steam::steam_search::get_steam_app_names_batch,
         crate::steam::steam_search::get_steam_app_details,
@@ -91,6 +126,13 @@ pub fn register_all_commands(builder: Builder<Wry>) -> Builder<Wry> {
         crate::compat::get_host_specs,
         crate::plugins::plugin_sdk::export_plugin_sdk,
         crate::commands::logs::command_logs::get_plugin_logs,
+        crate::plugins::permissions::list_pending_plugins,
+        crate::plugins::permissions::approve_plugin_permissions,
+        crate::plugins::permissions::reject_plugin_permissions,
+        crate::plugins::lifecycle::list_plugins,
+        crate::plugins::lifecycle::set_plugin_enabled,
+        crate::plugins::lifecycle::reload_plugin,
+        crate::plugins::lifecycle::reload_plugins,
         crate::commands::share::share::resolve_remote_share_token,
         crate::commands::share::share::create_remote_share_link,
         crate::steam_catalog::commands::sync::sync_steam_catalog,
@@ -1 +1,2 @@
-pub mod handlers;
\ No newline at end of file
+pub mod handlers;
+pub mod sync_observer;
\ No newline at end of file
@@ -0,0 +1,36 @@
+//! Adaptador Tauri del observador de sincronización: reenvía cada aviso al
+//! frontend con `emit`, con los mismos nombres de evento de siempre.
+
+use crate::commands::sync::models::SyncProgressPayload;
+use crate::commands::sync::observer::SyncObserver;
+use tauri::Emitter;
+
+impl SyncObserver for tauri::AppHandle {
+    fn upload_progress(&self, progress: SyncProgressPayload) {
+        let _ = self.emit("sync-upload-progress", progress);
+    }
+
+    fn download_progress(&self, progress: SyncProgressPayload) {
+        let _ = self.emit("sync-download-progress", progress);
+    }
+
+    fn upload_paused(&self, game_id: &str, filename: &str) {
+        let _ = self.emit(
+            "sync-upload-paused",
+            serde_json::json!({
+                "gameId": game_id,
+                "filename": filename,
+            }),
+        );
+    }
+
+    fn download_paused(&self, game_id: &str, filename: &str) {
+        let _ = self.emit(
+            "sync-download-paused",
+            serde_json::json!({
+                "gameId": game_id,
+                "filename": filename,
+            }),
+        );
+    }
+}
@@ -0,0 +1,594 @@
+//! Lanzamiento gestionado: descarga antes de jugar y sube al salir.
+//!
+//! Flujo de una partida:
+//! 1. Si la nube tiene guardados más recientes, se descargan (con copia local previa
+//!    de lo que se sobrescribe). Si además hay cambios locales sin subir, se pregunta.
+//! 2. Se lanza el juego y se sigue ese proceso y sus hijos ([`ProcessTree`]), no
+//!    solo un nombre de ejecutable, mientras se renueva la sesión remota.
+//! 3. Al salir se suben los guardados y se devuelve un resultado conjunto.
+//!
+//! La app lo usa con [`launch_game_with_sync`]; la CLI con `savecloud launch` y como
+//! opción de lanzamiento de Steam (`savecloud-launch %command%`).
+
+use crate::commands::logs::sync_logger;
+use crate::commands::sync::download;
+use crate::commands::sync::models::{DownloadConflictDto, RemoteSaveInfoDto, SyncResultDto};
+use crate::commands::sync::observer::SyncObserver;
+use crate::commands::sync::{api, upload};
+use crate::config::{self, ConfiguredGame};
+use crate::devices::session::{self, SessionLockDto};
+use crate::storage::Storage;
+use crate::system::process_check;
+use crate::system::process_tree::ProcessTree;
+use crate::transfers::{self, TransferKind, TransferPriority};
+use crate::utils::path_translation::SaveDestinations;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Child;
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessStatus, ProcessesToUpdate, System};
+use tauri::{AppHandle, Emitter, State};
+
+/// Cada cuánto se revisa si el juego sigue abierto.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Tiempo para que aparezca un juego lanzado con elevación, que no es hijo nuestro.
+const DETACHED_START_GRACE: Duration = Duration::from_secs(60);
+
+/// Carpeta, dentro de la de la app, con un `<id>.lock` por juego con un lanzamiento
+/// gestionado en curso.
+const MANAGED_DIR: &str = "managed_launches";
+
+/// Los bloqueos son archivos y no un estado en memoria porque la app y la CLI
+/// (`savecloud-launch %command%`) son procesos distintos: `game_exit_sync` no sube un
+/// juego al cerrarse si otro proceso lo lanzó con sincronización y ya lo hace él.
+fn managed_dir() -> Option<PathBuf> {
+    config::config_dir().map(|d| d.join(MANAGED_DIR))
+}
+
+/// Archivo de bloqueo de un juego; el id se escapa para que sirva como nombre de archivo.
+fn lock_path(dir: &Path, game_id: &str) -> PathBuf {
+    let name: String = game_id
+        .to_lowercase()
+        .bytes()
+        .map(|b| match b {
+            b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' => (b as char).to_string(),
+            _ => format!("%{:02x}", b),
+        })
+        .collect();
+    dir.join(format!("{}.lock", name))
+}
+
+/// PID e instante de arranque de un proceso, si sigue vivo.
+fn process_start(pid: u32) -> Option<u64> {
+    let pid = Pid::from_u32(pid);
+    let mut sys = System::new();
+    sys.refresh_processes_specifics(ProcessesToUpdate::Some(&[pid]), ProcessRefreshKind::new());
+    sys.process(pid)
+        .filter(|p| p.status() != ProcessStatus::Zombie)
+        .map(|p| p.start_time())
+}
+
+/// Contenido de un bloqueo de este proceso: `<pid> <arranque>`.
+fn lock_owner() -> String {
+    let pid = std::process::id();
+    format!("{} {}", pid, process_start(pid).unwrap_or(0))
+}
+
+/// El bloqueo sigue en vigor: el proceso que lo creó existe y no es otro con su PID.
+fn lock_is_live(contents: &str) -> bool {
+    let mut parts = contents.split_whitespace();
+    let (Some(Ok(pid)), Some(Ok(start))) = (
+        parts.next().map(str::parse::<u32>),
+        parts.next().map(str::parse::<u64>),
+    ) else {
+        return false;
+    };
+    process_start(pid) == Some(start)
+}
+
+fn is_managed_in(dir: &Path, game_id: &str) -> bool {
+    std::fs::read_to_string(lock_path(dir, game_id)).is_ok_and(|c| lock_is_live(&c))
+}
+
+/// Indica si el juego se lanzó con sincronización, en este proceso o en otro, y su
+/// subida está en marcha.
+pub fn is_managed(game_id: &str) -> bool {
+    managed_dir().is_some_and(|dir| is_managed_in(&dir, game_id))
+}
+
+/// Marca un juego como gestionado mientras vive.
+struct ManagedGuard(Option<PathBuf>);
+
+impl ManagedGuard {
+    fn acquire(game_id: &str) -> Result<Self, String> {
+        match managed_dir() {
+            Some(dir) => Self::acquire_in(&dir, game_id),
+            None => Ok(Self(None)),
+        }
+    }
+
+    /// Crea el bloqueo del juego; falla si otro lanzamiento vivo ya lo tiene. Un bloqueo
+    /// de un proceso que terminó sin borrarlo (cierre forzado) se sustituye.
+    fn acquire_in(dir: &Path, game_id: &str) -> Result<Self, String> {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Crear {}: {}", dir.display(), e))?;
+        let path = lock_path(dir, game_id);
+        for _ in 0..2 {
+            match std::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+            {
+                Ok(mut file) => {
+                    file.write_all(lock_owner().as_bytes())
+                        .map_err(|e| format!("Guardar {}: {}", path.display(), e))?;
+                    return Ok(Self(Some(path)));
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    if is_managed_in(dir, game_id) {
+                        break;
+                    }
+                    let _ = std::fs::remove_file(&path);
+                }
+                Err(e) => return Err(format!("Crear {}: {}", path.display(), e)),
+            }
+        }
+        Err(format!(
+            "{} ya se está ejecutando con sincronización",
+            game_id
+        ))
+    }
+}
+
+impl Drop for ManagedGuard {
+    fn drop(&mut self) {
+        let Some(path) = self.0.take() else {
+            return;
+        };
+        // Solo se borra si sigue siendo nuestro.
+        if std::fs::read_to_string(&path).is_ok_and(|c| c == lock_owner()) {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// Qué hacer si la nube es más reciente pero también hay cambios locales sin subir.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OnConflict {
+    /// Devolver los conflictos sin lanzar, para que el usuario decida.
+    Ask,
+    /// Descargar la nube; lo local que se sobrescribe queda en la copia previa.
+    UseCloud,
+    /// Conservar lo local y jugar sin descargar.
+    KeepLocal,
+}
+
+/// Lo que pasó antes de lanzar el juego.
+#[derive(Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PullReport {
+    /// Resultado de la descarga, si la nube tenía guardados más recientes.
+    pub pulled: Option<SyncResultDto>,
+    /// Conflictos resueltos conservando lo local.
+    pub kept_local: Vec<DownloadConflictDto>,
+    /// Sesión abierta (o sin subir) en otro dispositivo.
+    pub session_lock: Option<SessionLockDto>,
+    /// Avisos que no impiden jugar (sin backend configurado, sin red...).
+    pub warnings: Vec<String>,
+}
+
+/// Resultado de la comprobación previa.
+pub enum PullOutcome {
+    Ready(PullReport),
+    /// Hay cambios locales que la descarga sobrescribiría; no se ha tocado nada.
+    Conflicts(Vec<DownloadConflictDto>),
+}
+
+/// Resultado conjunto de una partida gestionada.
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchSessionResultDto {
+    pub game_id: String,
+    #[serde(flatten)]
+    pub pull: PullReport,
+    /// Código de salida del proceso lanzado (no disponible si se lanzó con elevación).
+    pub exit_code: Option<i32>,
+    pub played_seconds: u64,
+    /// Resultado de la subida al salir.
+    pub pushed: Option<SyncResultDto>,
+    pub errors: Vec<String>,
+}
+
+impl LaunchSessionResultDto {
+    /// Algo de la sincronización falló, aunque se haya jugado.
+    pub fn has_errors(&self) -> bool {
+        !self.errors.is_empty() || self.pushed.as_ref().is_some_and(|p| p.err_count > 0)
+    }
+}
+
+/// Juego configurado por id (sin distinguir mayúsculas).
+pub fn find_game(game_id: &str) -> Result<ConfiguredGame, String> {
+    config::load_config()
+        .games
+        .into_iter()
+        .find(|g| g.id.eq_ignore_ascii_case(game_id.trim()))
+        .ok_or_else(|| format!("Juego no encontrado: {}", game_id))
+}
+
+/// Identifica el juego de una línea de comandos lanzada por otro programa (p. ej. Steam).
+///
+/// Usa el id indicado, si lo hay; si no, el `SteamAppId` del entorno y, por último,
+/// el ejecutable configurado que aparezca en la línea de comandos.
+pub fn resolve_game(game_id: Option<&str>, command: &[String]) -> Result<ConfiguredGame, String> {
+    if let Some(id) = game_id {
+        return find_game(id);
+    }
+    let steam_app_id = std::env::var("SteamAppId")
+        .or_else(|_| std::env::var("SteamGameId"))
+        .ok();
+    match_game(
+        config::load_config().games,
+        steam_app_id.as_deref(),
+        command,
+    )
+    .ok_or_else(|| "No se pudo identificar el juego. Indica cuál con --game <id>.".to_string())
+}
+
+fn match_game(
+    games: Vec<ConfiguredGame>,
+    steam_app_id: Option<&str>,
+    command: &[String],
+) -> Option<ConfiguredGame> {
+    let normalize = |s: &str| s.trim().replace('\\', "/").to_lowercase();
+    let args: Vec<String> = command.iter().map(|a| normalize(a)).collect();
+    let by_app_id = steam_app_id
+        .filter(|id| !id.trim().is_empty() && *id != "0")
+        .and_then(|id| {
+            games
+                .iter()
+                .find(|g| g.steam_app_id.as_deref().map(str::trim) == Some(id.trim()))
+        });
+    let by_exe = || {
+        games.iter().find(|g| {
+            let Some(exe) = g.launch_executable_path.as_deref().map(normalize) else {
+                return false;
+            };
+            let exe_name = exe.rsplit('/').next().unwrap_or(&exe).to_string();
+            !exe.is_empty()
+                && args
+                    .iter()
+                    .any(|a| *a == exe || a.rsplit('/').next() == Some(exe_name.as_str()))
+        })
+    };
+    by_app_id.or_else(by_exe).cloned()
+}
+
+/// Descarga los guardados de la nube si son más recientes que los locales.
+///
+/// Sin backend o sin red se juega con lo local y queda un aviso. Si la descarga
+/// falla se devuelve `Err` para no jugar sobre un guardado a medio descargar.
+pub async fn pull_before_play(
+    game: &ConfiguredGame,
+    on_conflict: OnConflict,
+    observer: &dyn SyncObserver,
+) -> Result<PullOutcome, String> {
+    let mut report = PullReport::default();
+    let Ok(storage) = Storage::active() else {
+        report
+            .warnings
+            .push("Sin backend configurado: se juega sin sincronizar".to_string());
+        return Ok(PullOutcome::Ready(report));
+    };
+    report.session_lock = session::foreign_lock(&storage, &game.id)
+        .await
+        .ok()
+        .flatten();
+
+    let saves: Vec<RemoteSaveInfoDto> = match api::sync_list_remote_saves().await {
+        Ok(all) => all
+            .into_iter()
+            .filter(|s| s.game_id.eq_ignore_ascii_case(&game.id))
+            .collect(),
+        Err(e) => {
+            report
+                .warnings
+                .push(format!("No se pudo consultar la nube: {}", e));
+            return Ok(PullOutcome::Ready(report));
+        }
+    };
+    let dest = SaveDestinations::for_game(game).ok_or("No se pudo expandir la ruta de destino")?;
+    if !download::cloud_has_newer_files(&dest, &saves) {
+        return Ok(PullOutcome::Ready(report));
+    }
+
+    let mut conflicts = download::check_conflicts_for_game(&dest, &saves);
+    if !conflicts.is_empty() {
+        download::attach_remote_origins(&saves, &mut conflicts).await;
+        crate::plugins::hooks::conflict(game, &conflicts).await;
+        match on_conflict {
+            OnConflict::Ask => return Ok(PullOutcome::Conflicts(conflicts)),
+            OnConflict::KeepLocal => {
+                report.kept_local = conflicts;
+                return Ok(PullOutcome::Ready(report));
+            }
+            OnConflict::UseCloud => {}
+        }
+    }
+
+    let transfer =
+        transfers::begin(TransferKind::Download, &game.id, TransferPriority::Normal).await;
+    let pulled = download::sync_download_game_impl(
+        game.id.clone(),
+        &transfer.observer(observer),
+        transfer.token(),
+        Some(saves),
+    )
+    .await
+    .map_err(|e| format!("No se pudieron descargar los guardados de la nube: {}", e))?;
+    if pulled.err_count > 0 {
+        return Err(format!(
+            "No se pudieron descargar {} archivos de la nube: {}",
+            pulled.err_count,
+            pulled.errors.join("; ")
+        ));
+    }
+    report.pulled = Some(pulled);
+    Ok(PullOutcome::Ready(report))
+}
+
+/// Juego lanzado cuyo cierre se está esperando.
+pub struct RunningGame {
+    game: ConfiguredGame,
+    /// `None` si se lanzó con elevación: entonces se sigue por nombre de proceso.
+    child: Option<Child>,
+    started: Instant,
+    _managed: ManagedGuard,
+}
+
+/// Lanza el juego: `command` (p. ej. el `%command%` de Steam) o, si no se indica,
+/// el ejecutable configurado.
+pub fn launch(game: &ConfiguredGame, command: &[String]) -> Result<RunningGame, String> {
+    let managed = ManagedGuard::acquire(&game.id)?;
+    let child = match command.split_first() {
+        Some((program, args)) => Some(
+            std::process::Command::new(program)
+                .args(args)
+                .spawn()
+                .map_err(|e| format!("No se pudo lanzar {}: {}", program, e))?,
+        ),
+        None => {
+            let path = game
+                .launch_executable_path
+                .as_deref()
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .ok_or("Configura primero el ejecutable del juego")?;
+            if !Path::new(path).is_file() {
+                return Err(format!("El archivo no existe: {}", path));
+            }
+            crate::utils::launch_exe::spawn_game_executable(path)?
+        }
+    };
+    if let Some(child) = &child {
+        process_check::track_launched(&game.id, child.id());
+    }
+    Ok(RunningGame {
+        game: game.clone(),
+        child,
+        started: Instant::now(),
+        _managed: managed,
+    })
+}
+
+/// Espera a que el juego termine, sube sus guardados y devuelve el resultado conjunto.
+pub async fn finish(
+    mut running: RunningGame,
+    pull: PullReport,
+    observer: &dyn SyncObserver,
+) -> LaunchSessionResultDto {
+    let game_id = running.game.id.clone();
+    let storage = Storage::active().ok();
+    if let Some(storage) = &storage {
+        if let Err(e) = session::acquire(storage, &game_id).await {
+            sync_logger::log_error("session_lease", &game_id, &e);
+        }
+    }
+
+    let exit_code = wait_for_exit(&mut running, storage.as_ref()).await;
+    let mut result = LaunchSessionResultDto {
+        game_id: game_id.clone(),
+        pull,
+        exit_code,
+        played_seconds: running.started.elapsed().as_secs(),
+        pushed: None,
+        errors: Vec::new(),
+    };
+
+    let transfer =
+        transfers::begin(TransferKind::Upload, &game_id, TransferPriority::GameExit).await;
+    match upload::sync_upload_game_impl(
+        game_id.clone(),
+        &transfer.observer(observer),
+        transfer.token(),
+    )
+    .await
+    {
+        Ok(pushed) => {
+            if pushed.err_count == 0 {
+                if let Some(storage) = &storage {
+                    if let Err(e) = session::release(storage, &game_id).await {
+                        sync_logger::log_error("session_release", &game_id, &e);
+                    }
+                }
+            }
+            result.pushed = Some(pushed);
+        }
+        Err(e) => result.errors.push(format!("Subida al salir: {}", e)),
+    }
+    result
+}
+
+/// Espera al árbol de procesos del juego (o a sus reglas de detección, si no es hijo nuestro)
+/// renovando la sesión remota. Devuelve el código de salida del proceso lanzado.
+async fn wait_for_exit(running: &mut RunningGame, storage: Option<&Storage>) -> Option<i32> {
+    let game_id = running.game.id.clone();
+    let mut last_heartbeat = Instant::now();
+    let mut exit_code = None;
+
+    if let Some(child) = running.child.as_mut() {
+        let mut tree = ProcessTree::new(child.id());
+        let mut exited = false;
+        loop {
+            // Recoger al hijo antes de mirar el árbol, para que no cuente como zombi.
+            if !exited {
+                if let Ok(Some(status)) = child.try_wait() {
+                    exited = true;
+                    exit_code = status.code();
+                }
+            }
+            let alive = tree.refresh(&mut process_check::get_sys());
+            if exited && !alive {
+                break;
+            }
+            heartbeat_if_due(storage, &game_id, &mut last_heartbeat).await;
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    } else {
+        let started = Instant::now();
+        while !process_check::is_game_running(&game_id, &running.game.paths)
+            && started.elapsed() < DETACHED_START_GRACE
+        {
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    // Red de seguridad: un lanzador que sale antes de que veamos a su hijo. Si el
+    // juego sigue abierto según sus reglas, se espera también a que se cierre.
+    while process_check::is_game_running(&game_id, &running.game.paths) {
+        heartbeat_if_due(storage, &game_id, &mut last_heartbeat).await;
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+    exit_code
+}
+
+async fn heartbeat_if_due(storage: Option<&Storage>, game_id: &str, last: &mut Instant) {
+    let Some(storage) = storage else {
+        return;
+    };
+    if last.elapsed() < session::HEARTBEAT_INTERVAL {
+        return;
+    }
+    *last = Instant::now();
+    if let Err(e) = session::heartbeat(storage, game_id).await {
+        sync_logger::log_error("session_lease", game_id, &e);
+    }
+}
+
+/// Respuesta de [`launch_game_with_sync`].
+#[derive(Serialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum ManagedLaunchDto {
+    /// Hay cambios locales que la descarga sobrescribiría; vuelve a llamar con una decisión.
+    Conflicts { conflicts: Vec<DownloadConflictDto> },
+    /// El juego está abierto; al cerrarse llega `launch-session-finished`.
+    Launched(PullReport),
+}
+
+/// Lanza el juego con sincronización: descarga antes, sube al salir.
+///
+/// Vuelve en cuanto el juego arranca. La espera y la subida siguen en segundo plano
+/// y terminan emitiendo `launch-session-finished` con [`LaunchSessionResultDto`].
+/// Con `onConflict` ausente o `ask`, los conflictos se devuelven sin lanzar nada.
+#[tauri::command]
+pub async fn launch_game_with_sync(
+    app: AppHandle,
+    tray_state: State<'_, crate::tray::tray_state::TrayState>,
+    game_id: String,
+    on_conflict: Option<OnConflict>,
+) -> Result<ManagedLaunchDto, String> {
+    let game = find_game(&game_id)?;
+    if is_managed(&game.id) {
+        return Err(format!(
+            "{} ya se está ejecutando con sincronización",
+            game.id
+        ));
+    }
+    let report = match pull_before_play(&game, on_conflict.unwrap_or(OnConflict::Ask), &app).await?
+    {
+        PullOutcome::Conflicts(conflicts) => return Ok(ManagedLaunchDto::Conflicts { conflicts }),
+        PullOutcome::Ready(report) => report,
+    };
+    let running = launch(&game, &[])?;
+
+    let tray = tray_state.0.clone();
+    let pull = report.clone();
+    tauri::async_runtime::spawn(async move {
+        tray.syncing_inc();
+        let result = finish(running, pull, &app).await;
+        tray.syncing_dec();
+        tray.refresh_unsynced_async();
+        let _ = app.emit("launch-session-finished", &result);
+    });
+    Ok(ManagedLaunchDto::Launched(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(id: &str, app_id: Option<&str>, exe: Option<&str>) -> ConfiguredGame {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "steamAppId": app_id,
+            "launchExecutablePath": exe,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn matches_wrapped_commands_to_configured_games() {
+        let games = vec![
+            game("Hades", Some("1145360"), None),
+            game("Celeste", None, Some("C:\\Games\\Celeste\\Celeste.exe")),
+        ];
+        let proton = [
+            "/steam/reaper".to_string(),
+            "SteamLaunch".to_string(),
+            "--".to_string(),
+            "/games/celeste/celeste.exe".to_string(),
+        ];
+
+        let by_id = match_game(games.clone(), Some("1145360"), &[]).unwrap();
+        assert_eq!(by_id.id, "Hades");
+        let by_exe = match_game(games.clone(), Some("0"), &proton).unwrap();
+        assert_eq!(by_exe.id, "Celeste");
+        assert!(match_game(games, None, &["/usr/bin/true".to_string()]).is_none());
+    }
+    #[test]
+    fn managed_lock_is_shared_until_dropped_and_ignores_dead_owners() {
+        let dir = tempfile::tempdir().unwrap();
+        let guard = ManagedGuard::acquire_in(dir.path(), "Hades").unwrap();
+        assert!(is_managed_in(dir.path(), "hades"));
+        assert!(ManagedGuard::acquire_in(dir.path(), "HADES").is_err());
+        drop(guard);
+        assert!(!is_managed_in(dir.path(), "Hades"));
+
+        // Bloqueo de un proceso que ya no existe: no cuenta y se puede sustituir.
+        std::fs::write(lock_path(dir.path(), "Celeste"), "4294967290 1").unwrap();
+        assert!(!is_managed_in(dir.path(), "Celeste"));
+        let _guard = ManagedGuard::acquire_in(dir.path(), "Celeste").unwrap();
+        assert!(is_managed_in(dir.path(), "Celeste"));
+    }
+
+    #[test]
+    fn lock_names_escape_the_game_id() {
+        let dir = Path::new("/locks");
+        assert_eq!(lock_path(dir, "Hades"), dir.join("hades.lock"));
+        assert_eq!(
+            lock_path(dir, "../Foo Bar"),
+            dir.join("%2e%2e%2ffoo%20bar.lock")
+        );
+    }
+}
@@ -0,0 +1,104 @@
+//! Proveedor de Epic Games Launcher (Windows).
+//!
+//! Cada juego instalado tiene un manifiesto JSON `<id>.item` en
+//! `%PROGRAMDATA%\Epic\EpicGamesLauncher\Data\Manifests`.
+
+use super::{InstalledGame, LauncherKind};
+use serde::Deserialize;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct EpicItem {
+    display_name: Option<String>,
+    install_location: Option<String>,
+    app_name: Option<String>,
+    #[serde(default, rename = "bIsIncompleteInstall")]
+    is_incomplete_install: bool,
+}
+
+/// Carpeta de manifiestos del lanzador; solo existe en Windows.
+fn manifests_dir() -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    {
+        let program_data = std::env::var("PROGRAMDATA")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from(r"C:\ProgramData"));
+        Some(
+            program_data
+                .join("Epic")
+                .join("EpicGamesLauncher")
+                .join("Data")
+                .join("Manifests"),
+        )
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        None
+    }
+}
+
+/// Parsea un manifiesto `.item`; descarta instalaciones incompletas.
+fn parse_item(content: &str) -> Option<InstalledGame> {
+    let item: EpicItem = serde_json::from_str(content).ok()?;
+    if item.is_incomplete_install {
+        return None;
+    }
+    let install_dir = item.install_location.filter(|p| !p.is_empty())?;
+    let id = item.app_name?;
+    Some(InstalledGame {
+        launcher: LauncherKind::Epic,
+        title: item.display_name.unwrap_or_else(|| id.clone()),
+        id,
+        install_dir: PathBuf::from(install_dir),
+        wine_prefix: None,
+        steam_app_id: None,
+    })
+}
+
+pub fn installed_games() -> Vec<InstalledGame> {
+    let Some(Ok(entries)) = manifests_dir().map(fs::read_dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            p.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("item"))
+        })
+        .filter_map(|p| fs::read_to_string(p).ok())
+        .filter_map(|content| parse_item(&content))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_item_manifest() {
+        let json = r#"{
+            "FormatVersion": 0,
+            "bIsIncompleteInstall": false,
+            "DisplayName": "Hades",
+            "InstallLocation": "C:\\Program Files\\Epic Games\\Hades",
+            "AppName": "Min"
+        }"#;
+        let game = parse_item(json).expect("item válido");
+        assert_eq!(game.id, "Min");
+        assert_eq!(game.title, "Hades");
+        assert_eq!(
+            game.install_dir,
+            PathBuf::from(r"C:\Program Files\Epic Games\Hades")
+        );
+
+        let incomplete = json.replace(
+            r#""bIsIncompleteInstall": false"#,
+            r#""bIsIncompleteInstall": true"#,
+        );
+        assert!(parse_item(&incomplete).is_none());
+    }
+}
@@ -0,0 +1,72 @@
+//! Proveedor de GOG Galaxy 2.0 (Windows).
+//!
+//! Lee `%PROGRAMDATA%\GOG.com\Galaxy\storage\galaxy-2.0.db`: las instalaciones están en
+//! `InstalledBaseProducts` y los títulos en `LimitedDetails`.
+
+use super::{open_readonly_db, InstalledGame, LauncherKind};
+use std::path::PathBuf;
+
+const INSTALLED_GAMES_SQL: &str = "SELECT ibp.productId, ibp.installationPath, ld.title
+     FROM InstalledBaseProducts ibp
+     LEFT JOIN LimitedDetails ld ON ld.productId = ibp.productId";
+
+/// Base de datos de Galaxy; solo existe en Windows.
+fn galaxy_db_path() -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    {
+        let program_data = std::env::var("PROGRAMDATA")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from(r"C:\ProgramData"));
+        Some(
+            program_data
+                .join("GOG.com")
+                .join("Galaxy")
+                .join("storage")
+                .join("galaxy-2.0.db"),
+        )
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        None
+    }
+}
+
+fn query_installed(conn: &rusqlite::Connection) -> rusqlite::Result<Vec<InstalledGame>> {
+    let mut stmt = conn.prepare(INSTALLED_GAMES_SQL)?;
+    let rows = stmt.query_map([], |row| {
+        let product_id: i64 = row.get(0)?;
+        let install_dir: Option<String> = row.get(1)?;
+        let title: Option<String> = row.get(2)?;
+        Ok((product_id, install_dir, title))
+    })?;
+
+    let mut games = Vec::new();
+    for (product_id, install_dir, title) in rows.flatten() {
+        let Some(install_dir) = install_dir.filter(|p| !p.is_empty()) else {
+            continue;
+        };
+        let install_dir = PathBuf::from(install_dir);
+        let title = title.unwrap_or_else(|| {
+            install_dir
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| product_id.to_string())
+        });
+        games.push(InstalledGame {
+            launcher: LauncherKind::GogGalaxy,
+            id: product_id.to_string(),
+            title,
+            install_dir,
+            wine_prefix: None,
+            steam_app_id: None,
+        });
+    }
+    Ok(games)
+}
+
+pub fn installed_games() -> Vec<InstalledGame> {
+    let Some(conn) = galaxy_db_path().and_then(|p| open_readonly_db(&p)) else {
+        return Vec::new();
+    };
+    query_installed(&conn).unwrap_or_default()
+}
@@ -0,0 +1,220 @@
+//! Proveedor de Heroic Games Launcher (Epic vía Legendary y GOG).
+//!
+//! - Epic: `legendaryConfig/legendary/installed.json`.
+//! - GOG: `gog_store/installed.json`, con los títulos en la caché de la biblioteca.
+//! - Prefijo Wine: `GamesConfig/<appName>.json` → `winePrefix`.
+
+use super::{read_json, InstalledGame, LauncherKind};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+#[derive(Deserialize)]
+struct LegendaryInstalled {
+    title: Option<String>,
+    install_path: Option<String>,
+    platform: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GogInstalledFile {
+    #[serde(default)]
+    installed: Vec<GogInstalled>,
+}
+
+#[derive(Deserialize)]
+struct GogInstalled {
+    #[serde(rename = "appName")]
+    app_name: Option<String>,
+    install_path: Option<String>,
+    platform: Option<String>,
+    #[serde(default)]
+    is_dlc: bool,
+}
+
+#[derive(Deserialize)]
+struct GogLibraryFile {
+    #[serde(default)]
+    games: Vec<GogLibraryGame>,
+}
+
+#[derive(Deserialize)]
+struct GogLibraryGame {
+    app_name: Option<String>,
+    title: Option<String>,
+}
+
+/// Carpetas de configuración de Heroic (nativa y Flatpak en Linux, `%APPDATA%` en Windows).
+fn heroic_config_dirs() -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    if let Some(config) = dirs::config_dir() {
+        candidates.push(config.join("heroic"));
+    }
+    #[cfg(not(target_os = "windows"))]
+    if let Some(home) = dirs::home_dir() {
+        candidates.push(
+            home.join(".var")
+                .join("app")
+                .join("com.heroicgameslauncher.hgl")
+                .join("config")
+                .join("heroic"),
+        );
+    }
+    candidates.into_iter().filter(|p| p.is_dir()).collect()
+}
+
+/// Parsea `installed.json` de Legendary: mapa app name → instalación.
+fn parse_legendary_installed(content: &str) -> Vec<(String, String, PathBuf, bool)> {
+    let Ok(map) = serde_json::from_str::<HashMap<String, LegendaryInstalled>>(content) else {
+        return Vec::new();
+    };
+    map.into_iter()
+        .filter_map(|(app_name, item)| {
+            let install_path = item.install_path.filter(|p| !p.is_empty())?;
+            let title = item.title.unwrap_or_else(|| app_name.clone());
+            let is_windows = item
+                .platform
+                .is_some_and(|p| p.eq_ignore_ascii_case("windows"));
+            Some((app_name, title, PathBuf::from(install_path), is_windows))
+        })
+        .collect()
+}
+
+/// Parsea `gog_store/installed.json` (sin DLC).
+fn parse_gog_installed(content: &str) -> Vec<(String, PathBuf, bool)> {
+    let Ok(file) = serde_json::from_str::<GogInstalledFile>(content) else {
+        return Vec::new();
+    };
+    file.installed
+        .into_iter()
+        .filter(|item| !item.is_dlc)
+        .filter_map(|item| {
+            let app_name = item.app_name?;
+            let install_path = item.install_path.filter(|p| !p.is_empty())?;
+            let is_windows = item
+                .platform
+                .is_some_and(|p| p.eq_ignore_ascii_case("windows"));
+            Some((app_name, PathBuf::from(install_path), is_windows))
+        })
+        .collect()
+}
+
+/// Títulos de la biblioteca de GOG en caché (app name → título).
+fn gog_titles(heroic_dir: &Path) -> HashMap<String, String> {
+    let candidates = [
+        heroic_dir.join("store_cache").join("gog_library.json"),
+        heroic_dir.join("gog_store").join("library.json"),
+    ];
+    candidates
+        .iter()
+        .filter_map(|p| read_json::<GogLibraryFile>(p))
+        .flat_map(|lib| lib.games)
+        .filter_map(|g| Some((g.app_name?, g.title?)))
+        .collect()
+}
+
+/// Prefijo Wine configurado para un juego en Heroic.
+///
+/// Con Proton el prefijo real está en la subcarpeta `pfx`.
+fn wine_prefix(heroic_dir: &Path, app_name: &str) -> Option<PathBuf> {
+    let path = heroic_dir
+        .join("GamesConfig")
+        .join(format!("{}.json", app_name));
+    let config: HashMap<String, serde_json::Value> = read_json(&path)?;
+    let prefix = config.get(app_name)?.get("winePrefix")?.as_str()?;
+    let prefix = PathBuf::from(prefix);
+
+    [prefix.join("pfx"), prefix]
+        .into_iter()
+        .find(|p| p.join("drive_c").is_dir())
+}
+
+pub fn installed_games() -> Vec<InstalledGame> {
+    let mut games = Vec::new();
+
+    for heroic_dir in heroic_config_dirs() {
+        let legendary_file = heroic_dir
+            .join("legendaryConfig")
+            .join("legendary")
+            .join("installed.json");
+        if let Ok(content) = std::fs::read_to_string(&legendary_file) {
+            for (app_name, title, install_dir, is_windows) in parse_legendary_installed(&content) {
+                let wine_prefix = if is_windows && cfg!(not(target_os = "windows")) {
+                    wine_prefix(&heroic_dir, &app_name)
+                } else {
+                    None
+                };
+                games.push(InstalledGame {
+                    launcher: LauncherKind::HeroicEpic,
+                    id: app_name,
+                    title,
+                    install_dir,
+                    wine_prefix,
+                    steam_app_id: None,
+                });
+            }
+        }
+
+        let gog_file = heroic_dir.join("gog_store").join("installed.json");
+        if let Ok(content) = std::fs::read_to_string(&gog_file) {
+            let titles = gog_titles(&heroic_dir);
+            for (app_name, install_dir, is_windows) in parse_gog_installed(&content) {
+                let title = titles.get(&app_name).cloned().unwrap_or_else(|| {
+                    install_dir
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_else(|| app_name.clone())
+                });
+                let wine_prefix = if is_windows && cfg!(not(target_os = "windows")) {
+                    wine_prefix(&heroic_dir, &app_name)
+                } else {
+                    None
+                };
+                games.push(InstalledGame {
+                    launcher: LauncherKind::HeroicGog,
+                    id: app_name,
+                    title,
+                    install_dir,
+                    wine_prefix,
+                    steam_app_id: None,
+                });
+            }
+        }
+    }
+
+    games
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_legendary_installed() {
+        let json = r#"{
+            "Fortnite": { "app_name": "Fortnite", "title": "Fortnite", "install_path": "/games/Fortnite", "platform": "Windows" },
+            "Broken": { "app_name": "Broken", "title": "Broken" }
+        }"#;
+        let games = parse_legendary_installed(json);
+        assert_eq!(games.len(), 1);
+        let (id, title, dir, is_windows) = &games[0];
+        assert_eq!(id, "Fortnite");
+        assert_eq!(title, "Fortnite");
+        assert_eq!(dir, &PathBuf::from("/games/Fortnite"));
+        assert!(is_windows);
+    }
+
+    #[test]
+    fn parses_gog_installed_without_dlc() {
+        let json = r#"{ "installed": [
+            { "appName": "1207658924", "install_path": "/games/Witcher", "platform": "windows", "is_dlc": false },
+            { "appName": "1", "install_path": "/games/Witcher/dlc", "platform": "windows", "is_dlc": true },
+            { "appName": "2", "install_path": "/games/Native", "platform": "linux" }
+        ] }"#;
+        let games = parse_gog_installed(json);
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[0].0, "1207658924");
+        assert!(games[0].2);
+        assert!(!games[1].2);
+    }
+}
@@ -0,0 +1,67 @@
+//! Proveedor de la app de itch.
+//!
+//! La app guarda sus instalaciones ("caves") en `<config>/itch/db/butler.db`. La carpeta
+//! de cada juego es `install_locations.path/install_folder_name`, salvo que el usuario
+//! haya elegido una carpeta propia (`custom_install_folder`).
+
+use super::{open_readonly_db, InstalledGame, LauncherKind};
+use std::path::PathBuf;
+
+const INSTALLED_GAMES_SQL: &str = "SELECT games.id, games.title, caves.install_folder_name,
+            caves.custom_install_folder, install_locations.path
+     FROM caves
+     JOIN games ON caves.game_id = games.id
+     LEFT JOIN install_locations ON caves.install_location_id = install_locations.id";
+
+fn butler_db_path() -> Option<PathBuf> {
+    Some(
+        dirs::config_dir()?
+            .join("itch")
+            .join("db")
+            .join("butler.db"),
+    )
+}
+
+fn query_installed(conn: &rusqlite::Connection) -> rusqlite::Result<Vec<InstalledGame>> {
+    let mut stmt = conn.prepare(INSTALLED_GAMES_SQL)?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, Option<String>>(1)?,
+            row.get::<_, Option<String>>(2)?,
+            row.get::<_, Option<String>>(3)?,
+            row.get::<_, Option<String>>(4)?,
+        ))
+    })?;
+
+    let mut games = Vec::new();
+    for (game_id, title, folder_name, custom_folder, location) in rows.flatten() {
+        let install_dir = match (
+            custom_folder.filter(|p| !p.is_empty()),
+            location,
+            folder_name,
+        ) {
+            (Some(custom), _, _) => PathBuf::from(custom),
+            (None, Some(location), Some(folder)) if !folder.is_empty() => {
+                PathBuf::from(location).join(folder)
+            }
+            _ => continue,
+        };
+        games.push(InstalledGame {
+            launcher: LauncherKind::Itch,
+            id: game_id.to_string(),
+            title: title.unwrap_or_else(|| game_id.to_string()),
+            install_dir,
+            wine_prefix: None,
+            steam_app_id: None,
+        });
+    }
+    Ok(games)
+}
+
+pub fn installed_games() -> Vec<InstalledGame> {
+    let Some(conn) = butler_db_path().and_then(|p| open_readonly_db(&p)) else {
+        return Vec::new();
+    };
+    query_installed(&conn).unwrap_or_default()
+}
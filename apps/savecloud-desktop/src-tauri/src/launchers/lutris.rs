@@ -0,0 +1,127 @@
+//! Proveedor de Lutris (Linux).
+//!
+//! Los juegos instalados están en la tabla `games` de `pga.db`. El prefijo Wine de
+//! cada uno se declara en su YAML (`games/<configpath>.yml` → `game.prefix`).
+
+use super::{open_readonly_db, InstalledGame, LauncherKind};
+use serde::Deserialize;
+use std::path::PathBuf;
+
+const INSTALLED_GAMES_SQL: &str =
+    "SELECT slug, name, directory, runner, configpath, service, service_id
+     FROM games
+     WHERE installed = 1";
+
+#[derive(Deserialize)]
+struct GameConfigFile {
+    game: Option<GameSection>,
+}
+
+#[derive(Deserialize)]
+struct GameSection {
+    prefix: Option<String>,
+}
+
+/// Carpetas de datos de Lutris (nativa y Flatpak).
+fn lutris_data_dirs() -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    if let Some(data) = dirs::data_dir() {
+        candidates.push(data.join("lutris"));
+    }
+    if let Some(home) = dirs::home_dir() {
+        candidates.push(
+            home.join(".var")
+                .join("app")
+                .join("net.lutris.Lutris")
+                .join("data")
+                .join("lutris"),
+        );
+    }
+    candidates.into_iter().filter(|p| p.is_dir()).collect()
+}
+
+/// Extrae `game.prefix` de la configuración YAML de un juego.
+fn parse_wine_prefix(content: &str) -> Option<PathBuf> {
+    let config: GameConfigFile = serde_yaml::from_str(content).ok()?;
+    let prefix = config.game?.prefix.filter(|p| !p.is_empty())?;
+    crate::utils::path_utils::expand_path(&prefix).map(PathBuf::from)
+}
+
+/// Busca el YAML del juego; versiones antiguas lo guardan en `~/.config/lutris/games`.
+fn wine_prefix(data_dir: &std::path::Path, config_path: &str) -> Option<PathBuf> {
+    let file_name = format!("{}.yml", config_path);
+    let mut candidates = vec![data_dir.join("games").join(&file_name)];
+    if let Some(config) = dirs::config_dir() {
+        candidates.push(config.join("lutris").join("games").join(&file_name));
+    }
+    candidates
+        .iter()
+        .filter_map(|p| std::fs::read_to_string(p).ok())
+        .find_map(|content| parse_wine_prefix(&content))
+        .filter(|p| p.join("drive_c").is_dir())
+}
+
+pub fn installed_games() -> Vec<InstalledGame> {
+    let mut games = Vec::new();
+
+    for data_dir in lutris_data_dirs() {
+        let Some(conn) = open_readonly_db(&data_dir.join("pga.db")) else {
+            continue;
+        };
+        let Ok(mut stmt) = conn.prepare(INSTALLED_GAMES_SQL) else {
+            continue;
+        };
+        let Ok(rows) = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, Option<String>>(4)?,
+                row.get::<_, Option<String>>(5)?,
+                row.get::<_, Option<String>>(6)?,
+            ))
+        }) else {
+            continue;
+        };
+
+        for (slug, name, directory, runner, config_path, service, service_id) in rows.flatten() {
+            let Some(directory) = directory.filter(|d| !d.is_empty()) else {
+                continue;
+            };
+            let wine_prefix = match (runner.as_deref(), config_path.as_deref()) {
+                (Some("wine"), Some(config_path)) => wine_prefix(&data_dir, config_path),
+                _ => None,
+            };
+            let steam_app_id = match (service.as_deref(), service_id) {
+                (Some("steam"), Some(id)) if id.chars().all(|c| c.is_ascii_digit()) => Some(id),
+                _ => None,
+            };
+            games.push(InstalledGame {
+                launcher: LauncherKind::Lutris,
+                title: name.unwrap_or_else(|| slug.clone()),
+                id: slug,
+                install_dir: PathBuf::from(directory),
+                wine_prefix,
+                steam_app_id,
+            });
+        }
+    }
+
+    games
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_wine_prefix_from_game_config() {
+        let yaml = "game:\n  exe: /games/foo/foo.exe\n  prefix: /games/foo/prefix\nwine:\n  version: lutris-7\n";
+        assert_eq!(
+            parse_wine_prefix(yaml),
+            Some(PathBuf::from("/games/foo/prefix"))
+        );
+        assert_eq!(parse_wine_prefix("system:\n  env: {}\n"), None);
+    }
+}
@@ -0,0 +1,197 @@
+//! Detección de juegos instalados por lanzadores distintos de Steam.
+//!
+//! Cada proveedor lee los archivos locales de su lanzador y devuelve los juegos
+//! instalados con su carpeta de instalación:
+//!
+//! - Heroic (Epic vía Legendary y GOG): `installed.json` y `gog_store`.
+//! - Lutris: base de datos `pga.db` y configuración YAML de cada juego (Linux).
+//! - Epic Games Launcher: manifiestos `.item` (Windows).
+//! - GOG Galaxy: base de datos `galaxy-2.0.db` (Windows).
+//! - itch: base de datos `butler.db` de la app de itch.
+//!
+//! Con estas carpetas el escaneo y el manifiesto de Ludusavi pueden resolver rutas
+//! `<base>` de juegos que no vienen de Steam.
+
+mod epic;
+mod gog_galaxy;
+mod heroic;
+mod itch;
+mod lutris;
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// Los lanzadores se releen como mucho una vez en este intervalo al buscar prefijos Wine.
+const INDEX_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// Último índice construido para [`wine_prefix_for`] y cuándo se construyó.
+type CachedIndex = (Arc<InstalledGamesIndex>, Instant);
+
+static INDEX_CACHE: LazyLock<Mutex<Option<CachedIndex>>> = LazyLock::new(|| Mutex::new(None));
+
+/// Lanzador del que procede un juego instalado.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum LauncherKind {
+    HeroicEpic,
+    HeroicGog,
+    Lutris,
+    Epic,
+    GogGalaxy,
+    Itch,
+}
+
+impl LauncherKind {
+    /// Etiqueta legible, usada como `base_path` de los candidatos del escaneo.
+    pub fn label(self) -> &'static str {
+        match self {
+            LauncherKind::HeroicEpic => "Heroic (Epic)",
+            LauncherKind::HeroicGog => "Heroic (GOG)",
+            LauncherKind::Lutris => "Lutris",
+            LauncherKind::Epic => "Epic Games",
+            LauncherKind::GogGalaxy => "GOG Galaxy",
+            LauncherKind::Itch => "itch",
+        }
+    }
+}
+
+/// Juego instalado detectado en un lanzador.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledGame {
+    pub launcher: LauncherKind,
+    /// Identificador del juego en su tienda (app name de Epic, product ID de GOG, slug...).
+    pub id: String,
+    pub title: String,
+    pub install_dir: PathBuf,
+    /// Prefijo Wine del juego (Heroic/Lutris en Linux), si se ejecuta con Wine/Proton.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wine_prefix: Option<PathBuf>,
+    /// App ID de Steam cuando el lanzador lo conoce (juegos de Steam importados en Lutris).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub steam_app_id: Option<String>,
+}
+
+/// Recorre todos los proveedores y devuelve los juegos cuya carpeta existe en disco.
+pub fn list_installed_games() -> Vec<InstalledGame> {
+    let mut games = Vec::new();
+    games.extend(heroic::installed_games());
+    games.extend(lutris::installed_games());
+    games.extend(epic::installed_games());
+    games.extend(gog_galaxy::installed_games());
+    games.extend(itch::installed_games());
+
+    games.retain(|g| g.install_dir.is_dir());
+    games
+}
+
+/// Índice de juegos instalados por nombre de carpeta y por título (ambos en minúsculas).
+///
+/// Pensado para escaneos masivos del manifiesto, donde las entradas se identifican
+/// por `installDir` o por nombre.
+pub struct InstalledGamesIndex {
+    games: Vec<InstalledGame>,
+    by_folder: HashMap<String, usize>,
+    by_title: HashMap<String, usize>,
+}
+
+impl InstalledGamesIndex {
+    pub fn build() -> Self {
+        let games = list_installed_games();
+        let mut by_folder = HashMap::new();
+        let mut by_title = HashMap::new();
+        for (i, game) in games.iter().enumerate() {
+            if let Some(folder) = game.install_dir.file_name().and_then(|n| n.to_str()) {
+                by_folder.entry(folder.to_lowercase()).or_insert(i);
+            }
+            by_title.entry(normalize_title(&game.title)).or_insert(i);
+        }
+        Self {
+            games,
+            by_folder,
+            by_title,
+        }
+    }
+
+    pub fn games(&self) -> &[InstalledGame] {
+        &self.games
+    }
+
+    /// Busca un juego por alguna de sus carpetas de instalación o, en su defecto, por título.
+    pub fn find(&self, install_dir_names: &[String], title: &str) -> Option<&InstalledGame> {
+        install_dir_names
+            .iter()
+            .find_map(|name| self.by_folder.get(&name.to_lowercase()))
+            .or_else(|| self.by_title.get(&normalize_title(title)))
+            .map(|&i| &self.games[i])
+    }
+}
+
+/// Prefijo Wine (Heroic/Lutris) del juego instalado en `install_dir` o, si no se conoce
+/// la carpeta, del que se llama como `title`.
+///
+/// Reutiliza durante [`INDEX_CACHE_TTL`] el índice de juegos instalados: se consulta cada
+/// vez que se resuelven las rutas de un juego configurado.
+pub fn wine_prefix_for(install_dir: Option<&str>, title: &str) -> Option<PathBuf> {
+    let index = {
+        let mut cache = INDEX_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+        match cache.as_ref() {
+            Some((index, built)) if built.elapsed() < INDEX_CACHE_TTL => index.clone(),
+            _ => {
+                let index = Arc::new(InstalledGamesIndex::build());
+                *cache = Some((index.clone(), Instant::now()));
+                index
+            }
+        }
+    };
+
+    let install_dir = install_dir.map(Path::new);
+    let folders: Vec<String> = install_dir
+        .and_then(Path::file_name)
+        .map(|n| n.to_string_lossy().to_string())
+        .into_iter()
+        .collect();
+    install_dir
+        .and_then(|dir| index.games().iter().find(|g| g.install_dir == dir))
+        .or_else(|| index.find(&folders, title))?
+        .wine_prefix
+        .clone()
+}
+
+/// Normaliza un título para compararlo entre tiendas (sin símbolos ni mayúsculas).
+fn normalize_title(title: &str) -> String {
+    title
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Lee un JSON del disco; `None` si no existe o no es válido.
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Option<T> {
+    let content = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Abre una base de datos SQLite de otro programa en solo lectura.
+fn open_readonly_db(path: &Path) -> Option<rusqlite::Connection> {
+    if !path.is_file() {
+        return None;
+    }
+    rusqlite::Connection::open_with_flags(
+        path,
+        rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY | rusqlite::OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .ok()
+}
+
+/// Devuelve los juegos instalados por lanzadores distintos de Steam.
+#[tauri::command]
+pub async fn list_launcher_games() -> Result<Vec<InstalledGame>, String> {
+    tauri::async_runtime::spawn_blocking(list_installed_games)
+        .await
+        .map_err(|e| format!("Error en el hilo de detección: {}", e))
+}
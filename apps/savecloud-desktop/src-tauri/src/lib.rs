@@ -1,9 +1,13 @@
+pub mod cli;
 mod commands;
 mod compat;
 mod config;
 mod controller;
+mod devices;
+mod emulators;
 mod ipc;
-#[cfg(target_os = "windows")]
+mod launch;
+mod launchers;
 mod manifest;
 mod network;
 mod plugins;
@@ -12,9 +16,13 @@ mod sqlite;
 mod steam;
 mod steam_cache;
 mod steam_catalog;
+mod storage;
 mod system;
+#[cfg(test)]
+mod testing;
 mod time;
 mod torrent;
+mod transfers;
 mod tray;
 mod utils;
 use tauri::Manager;
@@ -64,6 +72,12 @@ pub fn run() {
 
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|app, event| {
+            // Los plugins reciben `on_unload` antes de cerrar la app.
+            if let tauri::RunEvent::Exit = event {
+                plugins::lifecycle::unload_on_exit(app);
+            }
+        });
 }
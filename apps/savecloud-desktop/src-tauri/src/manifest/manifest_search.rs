@@ -5,8 +5,12 @@
 //! - Buscar juegos en el manifiesto de Ludusavi cuyo nombre contenga el texto indicado.
 //! - Obtener el ID de Steam del juego.
 //! - Obtener el nombre del juego.
+//! - Sugerir rutas de guardado resueltas para el host actual.
 
+use crate::utils::path_translation::HostContext;
 use serde::Serialize;
+use std::collections::HashSet;
+use std::path::Path;
 
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -15,6 +19,13 @@ pub struct ManifestSearchResult {
     pub name: String,
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestPathSuggestion {
+    pub path: String,
+    pub exists: bool,
+}
+
 /// Busca juegos en el manifiesto de Ludusavi cuyo nombre contenga el texto indicado.
 #[tauri::command]
 pub async fn search_manifest_games(query: String) -> Result<Vec<ManifestSearchResult>, String> {
@@ -24,36 +35,84 @@ pub async fn search_manifest_games(query: String) -> Result<Vec<ManifestSearchRe
         return Ok(Vec::new());
     }
 
-    #[cfg(not(target_os = "windows"))]
-    {
-        // En otras plataformas, el manifiesto no está integrado todavía.
-        Ok(Vec::new())
+    let index = match crate::manifest::load_manifest_index_async().await {
+        Ok(idx) => idx,
+        Err(e) => return Err(format!("Error al cargar el manifiesto: {}", e)),
+    };
+
+    let mut seen_ids = HashSet::new();
+    let mut results = Vec::new();
+
+    for (id, entry) in index.iter() {
+        let name_lc = entry.name.to_lowercase();
+
+        if name_lc.contains(&q) && seen_ids.insert(id.clone()) {
+            results.push(ManifestSearchResult {
+                steam_app_id: id.clone(),
+                name: entry.name.clone(),
+            });
+        }
     }
 
-    #[cfg(target_os = "windows")]
-    {
-        use std::collections::HashSet;
+    results.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(results)
+}
 
-        let index = match crate::manifest::load_manifest_index_async().await {
-            Ok(idx) => idx,
-            Err(e) => return Err(format!("Error al cargar el manifiesto: {}", e)),
+/// Devuelve las rutas de guardado del manifiesto para un juego (App ID o nombre),
+/// resueltas para este equipo. Las que ya existen aparecen primero.
+#[tauri::command]
+pub async fn suggest_manifest_save_paths(
+    steam_app_id_or_name: String,
+) -> Result<Vec<ManifestPathSuggestion>, String> {
+    let key = steam_app_id_or_name.trim().to_lowercase();
+    if key.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let index = crate::manifest::load_manifest_index_async()
+        .await
+        .map_err(|e| format!("Error al cargar el manifiesto: {}", e))?;
+
+    tokio::task::spawn_blocking(move || {
+        let Some(entry) = index.get(&key) else {
+            return Vec::new();
         };
 
-        let mut seen_ids = HashSet::new();
-        let mut results = Vec::new();
+        #[cfg(not(target_os = "windows"))]
+        let steam_install_dir = crate::steam::proton::find_install_dir(&entry.install_dirs);
+        #[cfg(target_os = "windows")]
+        let steam_install_dir: Option<std::path::PathBuf> = None;
 
-        for (id, entry) in index.iter() {
-            let name_lc = entry.name.to_lowercase();
+        // Fuera de Steam, la carpeta y el prefijo Wine salen del lanzador que lo instaló.
+        let launcher_games = crate::launchers::InstalledGamesIndex::build();
+        let launcher_game = launcher_games.find(&entry.install_dirs, &entry.name);
 
-            if name_lc.contains(&q) && seen_ids.insert(id.clone()) {
-                results.push(ManifestSearchResult {
-                    steam_app_id: id.clone(),
-                    name: entry.name.clone(),
-                });
-            }
-        }
+        let install_dir = steam_install_dir
+            .or_else(|| launcher_game.map(|g| g.install_dir.clone()))
+            .map(|p| p.to_string_lossy().to_string());
 
-        results.sort_by(|a, b| a.name.cmp(&b.name));
-        Ok(results)
-    }
+        let numeric_id = Some(key.as_str()).filter(|id| id.chars().all(|c| c.is_ascii_digit()));
+        let ctx = HostContext::for_game(
+            numeric_id.or(entry.steam_id.as_deref()),
+            launcher_game.and_then(|g| g.wine_prefix.as_deref()),
+        );
+
+        let (_, resolved) =
+            crate::manifest::resolve_entry_paths(entry, install_dir.as_deref(), &ctx);
+
+        let mut seen = HashSet::new();
+        let mut suggestions: Vec<ManifestPathSuggestion> = resolved
+            .into_iter()
+            .filter(|p| seen.insert(p.to_lowercase()))
+            .map(|path| ManifestPathSuggestion {
+                exists: Path::new(&path).exists(),
+                path,
+            })
+            .collect();
+
+        suggestions.sort_by_key(|s| !s.exists);
+        suggestions
+    })
+    .await
+    .map_err(|e| format!("Error en el hilo bloqueante: {}", e))
 }
@@ -8,25 +8,41 @@
 //! - Obtener el ID de Steam del juego.
 //! - Obtener el nombre del juego.
 //!
+//! Se usa en todas las plataformas: en Linux se aceptan las rutas `os: linux`
+//! (`<xdgData>`, `<xdgConfig>`, `<home>`) y también las de Windows, que se resuelven
+//! dentro del prefijo Proton del juego mediante [`crate::utils::path_translation`].
+//!
 //! Fuente: https://github.com/mtkennerly/ludusavi-manifest
 //! Licencia del manifiesto: MIT (mtkennerly).
 
 use crate::network::API_CLIENT;
+use crate::utils::path_translation::HostContext;
 use serde::{de::IgnoredAny, Deserialize};
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 use tokio::fs;
 
+pub mod manifest_search;
+
 const MANIFEST_URL: &str =
     "https://raw.githubusercontent.com/mtkennerly/ludusavi-manifest/master/data/manifest.yaml";
 
+/// Nombre del archivo de la copia empaquetada con el instalador (ver `resources/ludusavi`).
+const BUNDLED_MANIFEST_FILE: &str = "manifest.yaml";
+
+/// Ruta de la copia offline del manifiesto, registrada en el arranque desde el `resource_dir`.
+static BUNDLED_MANIFEST_PATH: OnceLock<PathBuf> = OnceLock::new();
+
 #[derive(Clone, Debug)]
 pub struct GameManifestEntry {
     pub name: String,
-    pub save_paths: Vec<PathTemplate>,
+    pub save_paths: Vec<ManifestPath>,
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
     pub registry_path: Option<String>,
-    #[allow(dead_code)]
     pub install_dirs: Vec<String>,
+    /// Primer App ID de Steam del juego; permite localizar su prefijo Proton en Linux.
+    pub steam_id: Option<String>,
 }
 
 #[derive(Clone, Debug)]
@@ -35,6 +51,22 @@ pub enum PathTemplate {
     RelativeToInstall(String),
 }
 
+/// Sistema operativo al que el manifiesto restringe una ruta (`when: [{ os: ... }]`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ManifestOs {
+    Windows,
+    Linux,
+    Mac,
+}
+
+/// Plantilla de ruta junto con el SO para el que el manifiesto la declara.
+#[derive(Clone, Debug)]
+pub struct ManifestPath {
+    pub template: PathTemplate,
+    /// `None` si la entrada no restringe el SO.
+    pub os: Option<ManifestOs>,
+}
+
 pub type ManifestIndex = HashMap<String, GameManifestEntry>;
 
 #[derive(Deserialize, Debug)]
@@ -80,6 +112,40 @@ struct WhenCondition {
     os: Option<String>,
 }
 
+impl ManifestOs {
+    fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "windows" => Some(Self::Windows),
+            "linux" => Some(Self::Linux),
+            "mac" => Some(Self::Mac),
+            _ => None,
+        }
+    }
+
+    /// SO del host actual según el manifiesto.
+    fn host() -> Self {
+        if cfg!(target_os = "windows") {
+            Self::Windows
+        } else if cfg!(target_os = "macos") {
+            Self::Mac
+        } else {
+            Self::Linux
+        }
+    }
+}
+
+/// Registra la carpeta de recursos de la app para usar la copia offline del manifiesto.
+pub fn register_bundled_manifest(resource_dir: &Path) {
+    let _ = BUNDLED_MANIFEST_PATH.set(resource_dir.join("ludusavi").join(BUNDLED_MANIFEST_FILE));
+}
+
+fn bundled_manifest_path() -> Option<&'static PathBuf> {
+    BUNDLED_MANIFEST_PATH.get().filter(|p| p.is_file())
+}
+
+/// Descarga el manifiesto si cambió desde la última vez (`ETag` / `Last-Modified`).
+///
+/// Los fallos de red no son fatales mientras exista una copia en caché.
 async fn ensure_manifest_cached(cache_path: &Path) -> Result<(), String> {
     let parent = cache_path
         .parent()
@@ -90,19 +156,24 @@ async fn ensure_manifest_cached(cache_path: &Path) -> Result<(), String> {
         .map_err(|e| e.to_string())?;
 
     let etag_path = cache_path.with_extension("etag");
+    let last_modified_path = cache_path.with_extension("last-modified");
 
     let mut request = API_CLIENT.get(MANIFEST_URL);
 
-    if cache_path.exists() && etag_path.exists() {
+    if cache_path.exists() {
         if let Ok(etag) = fs::read_to_string(&etag_path).await {
             request = request.header(reqwest::header::IF_NONE_MATCH, etag.trim());
         }
+        if let Ok(last_modified) = fs::read_to_string(&last_modified_path).await {
+            request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified.trim());
+        }
     }
 
-    let response = request
-        .send()
-        .await
-        .map_err(|e| format!("Error de red: {}", e))?;
+    let response = match request.send().await {
+        Ok(r) => r,
+        Err(_) if cache_path.exists() => return Ok(()),
+        Err(e) => return Err(format!("Error de red: {}", e)),
+    };
 
     if response.status() == reqwest::StatusCode::NOT_MODIFIED {
         return Ok(());
@@ -115,58 +186,100 @@ async fn ensure_manifest_cached(cache_path: &Path) -> Result<(), String> {
         return Err(format!("Error HTTP: {}", response.status()));
     }
 
-    let new_etag = response
-        .headers()
-        .get(reqwest::header::ETAG)
-        .and_then(|val| val.to_str().ok())
-        .map(|s| s.to_string());
+    let header_value = |name: reqwest::header::HeaderName| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|val| val.to_str().ok())
+            .map(|s| s.to_string())
+    };
+    let new_etag = header_value(reqwest::header::ETAG);
+    let new_last_modified = header_value(reqwest::header::LAST_MODIFIED);
+
+    // Un corte a mitad de la descarga tampoco es fatal: mejor la caché anterior que la
+    // copia empaquetada, que puede ser más antigua.
+    let body = match response.bytes().await {
+        Ok(body) => body,
+        Err(_) if cache_path.exists() => return Ok(()),
+        Err(e) => return Err(format!("Error leyendo bytes: {}", e)),
+    };
 
-    let body = response
-        .bytes()
+    // Escritura atómica: un corte a mitad no debe dejar un YAML truncado en caché.
+    let tmp_path = cache_path.with_extension("yaml.tmp");
+    fs::write(&tmp_path, &body)
         .await
-        .map_err(|e| format!("Error leyendo bytes: {}", e))?;
-
-    fs::write(cache_path, &body)
+        .map_err(|e| e.to_string())?;
+    fs::rename(&tmp_path, cache_path)
         .await
         .map_err(|e| e.to_string())?;
 
-    if let Some(etag_val) = new_etag {
-        let _ = fs::write(&etag_path, etag_val).await;
+    match new_etag {
+        Some(etag_val) => {
+            let _ = fs::write(&etag_path, etag_val).await;
+        }
+        None => {
+            let _ = fs::remove_file(&etag_path).await;
+        }
+    }
+    match new_last_modified {
+        Some(val) => {
+            let _ = fs::write(&last_modified_path, val).await;
+        }
+        None => {
+            let _ = fs::remove_file(&last_modified_path).await;
+        }
     }
 
     Ok(())
 }
 
+/// Carga el índice del manifiesto: caché actualizada, o la copia empaquetada si no hay red.
 pub async fn load_manifest_index_async() -> Result<ManifestIndex, String> {
     let cache_dir = crate::config::config_dir()
         .ok_or_else(|| "No se pudo obtener el directorio de config".to_string())?;
     let cache_path = cache_dir.join("ludusavi-manifest.yaml");
 
-    ensure_manifest_cached(&cache_path).await?;
+    let source_path = match ensure_manifest_cached(&cache_path).await {
+        Ok(()) => cache_path,
+        Err(e) => bundled_manifest_path()
+            .cloned()
+            .ok_or_else(|| format!("{} (sin copia offline del manifiesto)", e))?,
+    };
 
     tokio::task::spawn_blocking(move || {
-        let content = std::fs::read_to_string(&cache_path).map_err(|e| e.to_string())?;
+        let content = std::fs::read_to_string(&source_path).map_err(|e| e.to_string())?;
         parse_manifest_yaml(&content)
     })
     .await
     .map_err(|e| format!("Error en el hilo bloqueante: {}", e))?
 }
 
-fn when_has_windows(when_list: &Option<Vec<WhenCondition>>) -> bool {
+/// Elige el SO con el que se interpretará una ruta, o `None` si no aplica a este host.
+///
+/// En Linux se aceptan también las rutas de Windows: se resuelven dentro del
+/// prefijo Proton del juego. Las rutas nativas tienen prioridad.
+fn select_os_for_host(when_list: &Option<Vec<WhenCondition>>) -> Option<Option<ManifestOs>> {
     let conditions = match when_list {
-        Some(c) => c,
-        None => return true,
+        Some(c) if !c.is_empty() => c,
+        _ => return Some(None),
     };
 
-    if conditions.is_empty() {
-        return true;
-    }
+    let declared: Vec<Option<ManifestOs>> = conditions
+        .iter()
+        .map(|cond| cond.os.as_deref().and_then(ManifestOs::parse))
+        .collect();
 
-    conditions.iter().any(|cond| {
-        cond.os
-            .as_deref()
-            .map_or(true, |o| o.eq_ignore_ascii_case("windows"))
-    })
+    let host = ManifestOs::host();
+    if declared.contains(&Some(host)) {
+        return Some(Some(host));
+    }
+    if declared.contains(&None) {
+        return Some(None);
+    }
+    if host == ManifestOs::Linux && declared.contains(&Some(ManifestOs::Windows)) {
+        return Some(Some(ManifestOs::Windows));
+    }
+    None
 }
 
 fn parse_manifest_yaml(content: &str) -> Result<ManifestIndex, String> {
@@ -213,14 +326,17 @@ fn parse_manifest_yaml(content: &str) -> Result<ManifestIndex, String> {
                     continue;
                 }
 
-                let has_save_or_config = entry.tags.as_ref().map_or(true, |tags| {
+                let has_save_or_config = entry.tags.as_ref().is_none_or(|tags| {
                     tags.iter()
                         .any(|t| t.eq_ignore_ascii_case("save") || t.eq_ignore_ascii_case("config"))
                 });
 
-                if !has_save_or_config || !when_has_windows(&entry.when) {
+                if !has_save_or_config {
                     continue;
                 }
+                let Some(os) = select_os_for_host(&entry.when) else {
+                    continue;
+                };
 
                 let template = if path_str.starts_with("<base>") {
                     let rel_path = path_str
@@ -232,7 +348,7 @@ fn parse_manifest_yaml(content: &str) -> Result<ManifestIndex, String> {
                     PathTemplate::Absolute(path_str.to_string())
                 };
 
-                save_paths.push(template);
+                save_paths.push(ManifestPath { template, os });
             }
         }
 
@@ -254,6 +370,7 @@ fn parse_manifest_yaml(content: &str) -> Result<ManifestIndex, String> {
             save_paths,
             registry_path,
             install_dirs,
+            steam_id: steam_ids.first().cloned(),
         };
 
         index.insert(game_name.to_lowercase(), entry.clone());
@@ -266,11 +383,12 @@ fn parse_manifest_yaml(content: &str) -> Result<ManifestIndex, String> {
     Ok(index)
 }
 
-fn expand_ludusavi_placeholders(s: &str) -> String {
+fn expand_ludusavi_placeholders(s: &str, os: Option<ManifestOs>, ctx: &HostContext) -> String {
     let mut result = s.to_string();
 
     #[cfg(target_os = "windows")]
     {
+        let _ = (os, ctx);
         if result.contains("<winAppData>") {
             if let Ok(val) = std::env::var("APPDATA") {
                 result = result.replace("<winAppData>", &val);
@@ -307,17 +425,31 @@ fn expand_ludusavi_placeholders(s: &str) -> String {
         result = result.replace('/', "\\");
     }
 
+    // Fuera de Windows las raíces se resuelven con la capa de traducción: las rutas
+    // de Windows caen dentro del prefijo Proton y las de Linux en las carpetas XDG.
     #[cfg(not(target_os = "windows"))]
     {
-        if result.contains("<home>") {
-            if let Ok(home) = std::env::var("HOME") {
-                result = result.replace("<home>", &home);
-            }
+        let windows_path = is_windows_template(s, os);
+        let user_name = if windows_path {
+            Some(ctx.proton_prefix.as_deref().map_or_else(
+                || crate::steam::proton::PROTON_USER.to_string(),
+                crate::utils::path_translation::prefix_user_name,
+            ))
+        } else {
+            std::env::var("USER").ok()
+        };
+        if let Some(val) = user_name {
+            result = result.replace("<osUserName>", &val);
         }
-        if result.contains("<osUserName>") {
-            if let Ok(val) = std::env::var("USER") {
-                result = result.replace("<osUserName>", &val);
-            }
+        result = result.replace("<storeUserId>", "*");
+        if windows_path {
+            result = result.replace("<home>", "%USERPROFILE%");
+        }
+
+        if let Some(resolved) = crate::utils::path_translation::LogicalPath::parse(&result)
+            .and_then(|logical| logical.resolve(ctx))
+        {
+            result = resolved.to_string_lossy().to_string();
         }
     }
 
@@ -354,15 +486,35 @@ fn expand_ludusavi_placeholders(s: &str) -> String {
     result
 }
 
-pub fn resolve_path_template(template: &PathTemplate, install_dir: Option<&str>) -> String {
-    match template {
-        PathTemplate::Absolute(s) => expand_ludusavi_placeholders(s),
+/// Indica si la plantilla describe una ruta de Windows (por etiqueta o por marcadores).
+#[cfg(not(target_os = "windows"))]
+fn is_windows_template(s: &str, os: Option<ManifestOs>) -> bool {
+    os == Some(ManifestOs::Windows) || s.contains("<win")
+}
+
+/// Resuelve una plantilla del manifiesto en una ruta del host.
+///
+/// Devuelve una cadena vacía si la ruta no puede resolverse aquí: plantilla relativa sin
+/// carpeta de instalación, o ruta de Windows en Linux sin prefijo Proton en `ctx`.
+pub fn resolve_path_template(
+    path: &ManifestPath,
+    install_dir: Option<&str>,
+    ctx: &HostContext,
+) -> String {
+    match &path.template {
+        PathTemplate::Absolute(s) => {
+            #[cfg(not(target_os = "windows"))]
+            if is_windows_template(s, path.os) && ctx.proton_prefix.is_none() {
+                return String::new();
+            }
+            expand_ludusavi_placeholders(s, path.os, ctx)
+        }
         PathTemplate::RelativeToInstall(rel) => {
-            let rel_expanded = expand_ludusavi_placeholders(rel);
-            let rel_trim = rel_expanded.trim_start_matches(|c| c == ' ' || c == '\\' || c == '/');
+            let rel_expanded = expand_ludusavi_placeholders(rel, path.os, ctx);
+            let rel_trim = rel_expanded.trim_start_matches([' ', '\\', '/']);
 
             if let Some(base) = install_dir.filter(|s| !s.is_empty()) {
-                let base = base.trim_end_matches(&['/', '\\']);
+                let base = base.trim_end_matches(['/', '\\']);
                 format!("{}{}{}", base, std::path::MAIN_SEPARATOR, rel_trim)
             } else {
                 String::new()
@@ -371,20 +523,125 @@ pub fn resolve_path_template(template: &PathTemplate, install_dir: Option<&str>)
     }
 }
 
+#[cfg(target_os = "windows")]
 pub fn get_entry_for_steam_app(
     index: &ManifestIndex,
     steam_app_id: &str,
     install_dir: Option<&str>,
 ) -> Option<(GameManifestEntry, Vec<String>)> {
     let entry = index.get(steam_app_id)?;
+    let numeric_id = Some(steam_app_id).filter(|id| id.chars().all(|c| c.is_ascii_digit()));
+    let ctx = HostContext::for_game(numeric_id.or(entry.steam_id.as_deref()), None);
+    Some(resolve_entry_paths(entry, install_dir, &ctx))
+}
+
+/// Resuelve todas las rutas de una entrada con un contexto ya construido
+/// (p. ej. con el prefijo Wine de un juego de Heroic o Lutris).
+///
+/// Las carpetas con comodín intermedio (`<storeUserId>`) se expanden a las que existen.
+pub fn resolve_entry_paths(
+    entry: &GameManifestEntry,
+    install_dir: Option<&str>,
+    ctx: &HostContext,
+) -> (GameManifestEntry, Vec<String>) {
     let mut resolved = Vec::new();
 
     for template in &entry.save_paths {
-        let path = resolve_path_template(template, install_dir);
+        let path = resolve_path_template(template, install_dir, ctx);
         if !path.is_empty() {
-            resolved.push(path);
+            resolved.extend(crate::utils::path_utils::expand_dir_wildcards(&path));
         }
     }
 
-    Some((entry.clone(), resolved))
+    (entry.clone(), resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn when(oses: &[&str]) -> Option<Vec<WhenCondition>> {
+        Some(
+            oses.iter()
+                .map(|os| WhenCondition {
+                    os: (!os.is_empty()).then(|| os.to_string()),
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn select_os_prefers_the_host_and_accepts_unrestricted_paths() {
+        let host = ManifestOs::host();
+        assert_eq!(select_os_for_host(&None), Some(None));
+        assert_eq!(select_os_for_host(&Some(Vec::new())), Some(None));
+        assert_eq!(select_os_for_host(&when(&[""])), Some(None));
+        assert_eq!(
+            select_os_for_host(&when(&["windows", "linux", "mac"])),
+            Some(Some(host))
+        );
+        assert_eq!(select_os_for_host(&when(&["dos"])), Some(None));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn select_os_on_linux_falls_back_to_windows_paths() {
+        assert_eq!(
+            select_os_for_host(&when(&["windows"])),
+            Some(Some(ManifestOs::Windows))
+        );
+        assert_eq!(select_os_for_host(&when(&["mac"])), None);
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn windows_templates_are_detected_by_tag_or_placeholder() {
+        assert!(is_windows_template(
+            "<home>/Saves",
+            Some(ManifestOs::Windows)
+        ));
+        assert!(is_windows_template("<winAppData>/Foo", None));
+        assert!(!is_windows_template("<home>/.foo", None));
+        assert!(!is_windows_template(
+            "<xdgData>/Foo",
+            Some(ManifestOs::Linux)
+        ));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn linux_placeholders_resolve_inside_the_prefix_or_xdg_folders() {
+        let pfx = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(pfx.path().join("drive_c/users/zz-savecloud-test")).unwrap();
+        let ctx = HostContext {
+            proton_prefix: Some(pfx.path().to_path_buf()),
+        };
+
+        let roaming = expand_ludusavi_placeholders(
+            "<winAppData>/Studio/<osUserName>/<storeUserId>",
+            Some(ManifestOs::Windows),
+            &ctx,
+        );
+        assert_eq!(
+            PathBuf::from(roaming),
+            pfx.path()
+                .join("drive_c/users/zz-savecloud-test/AppData/Roaming")
+                .join("Studio/zz-savecloud-test/*")
+        );
+
+        let home =
+            expand_ludusavi_placeholders("<home>/Saved Games/Foo", Some(ManifestOs::Windows), &ctx);
+        assert_eq!(
+            PathBuf::from(home),
+            pfx.path()
+                .join("drive_c/users/zz-savecloud-test/Saved Games/Foo")
+        );
+
+        let xdg = expand_ludusavi_placeholders(
+            "<xdgData>/Foo",
+            Some(ManifestOs::Linux),
+            &HostContext::default(),
+        );
+        assert_eq!(PathBuf::from(xdg), dirs::data_dir().unwrap().join("Foo"));
+    }
 }
@@ -6,29 +6,66 @@
 //! - Registrar el módulo de UI.
 //! - Registrar el módulo de DB.
 //! - Registrar el módulo de HTTP.
+//! - Registrar el módulo de archivos.
+//! - Registrar el módulo de timers.
+//!
+//! Salvo el log y los timers, cada módulo solo existe si el plugin tiene la capacidad aprobada
+//! ([`Capabilities`]) y sus funciones se limitan a lo aprobado.
 
+use crate::commands::sync::queue::now_secs;
 use crate::plugins::log_buffer::{AppLogs, LogEntry};
-use mlua::{Lua, Result, Table};
-use tauri::AppHandle;
+use crate::plugins::manifest::Capabilities;
+use crate::plugins::sandbox::{FsScope, TASK_TIMEOUT};
+use crate::plugins::storage;
+use crate::plugins::tasks::PluginTasks;
+use crate::sqlite::error::SqliteError;
+use crate::sqlite::AppDb;
+use crate::time::sessions::{self, PlaytimePeriod};
+use mlua::{Function, Lua, LuaSerdeExt, Result, SerializeOptions, Table, Value};
+use rusqlite::types::Value as SqlValue;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
 
 pub fn register_savecloud_api(
     lua: &Lua,
     app_handle: AppHandle,
     logs: AppLogs,
     plugin_name: String,
+    capabilities: &Capabilities,
+    tasks: &PluginTasks,
 ) -> Result<()> {
     let globals = lua.globals();
 
     let savecloud_table = lua.create_table()?;
 
-    register_log_module(lua, &savecloud_table, app_handle.clone(), logs, plugin_name)?;
-    register_ui_module(lua, &savecloud_table, app_handle.clone())?;
-    register_db_module(lua, &savecloud_table)?;
-    register_http_module(lua, &savecloud_table)?;
+    register_log_module(
+        lua,
+        &savecloud_table,
+        app_handle.clone(),
+        logs,
+        plugin_name.clone(),
+    )?;
+    if !capabilities.ui_events.is_empty() {
+        register_ui_module(lua, &savecloud_table, app_handle.clone(), capabilities)?;
+    }
+    if capabilities.db {
+        // Sin base de datos en el estado de Tauri el módulo no existe.
+        if let Some(db) = app_handle.try_state::<AppDb>() {
+            register_db_module(lua, &savecloud_table, db.inner().clone(), plugin_name)?;
+        }
+    }
+    if !capabilities.http.is_empty() {
+        register_http_module(lua, &savecloud_table, capabilities)?;
+    }
+    let fs_scope = FsScope::new(capabilities);
+    if !fs_scope.is_empty() {
+        register_fs_module(lua, &savecloud_table, fs_scope)?;
+    }
+    register_timer_module(lua, &savecloud_table, tasks.clone())?;
 
     globals.set("savecloud", savecloud_table)?;
-    //globals.set("os", mlua::Value::Nil)?;
-    //globals.set("io", mlua::Value::Nil)?;
 
     Ok(())
 }
@@ -103,10 +140,22 @@ fn register_log_module(
     Ok(())
 }
 
-fn register_ui_module(lua: &Lua, parent_table: &Table, app_handle: AppHandle) -> Result<()> {
+fn register_ui_module(
+    lua: &Lua,
+    parent_table: &Table,
+    app_handle: AppHandle,
+    capabilities: &Capabilities,
+) -> Result<()> {
     let ui_table = lua.create_table()?;
 
+    let capabilities = capabilities.clone();
     let emit = lua.create_function(move |_, (event, payload): (String, String)| {
+        if !capabilities.allows_ui_event(&event) {
+            return Err(mlua::Error::RuntimeError(format!(
+                "evento no permitido por plugin.toml: {}",
+                event
+            )));
+        }
         use tauri::Emitter;
         let _ = app_handle.emit(&event, payload);
         Ok(())
@@ -118,31 +167,255 @@ fn register_ui_module(lua: &Lua, parent_table: &Table, app_handle: AppHandle) ->
     Ok(())
 }
 
-fn register_db_module(lua: &Lua, parent_table: &Table) -> Result<()> {
+/// Elementos que devuelven `history` y `sessions` si el plugin no pide otra cantidad.
+const DEFAULT_DB_LIMIT: usize = 20;
+
+// savecloud.db guarda los datos del plugin en la base local ([`storage`]) y da acceso de
+// solo lectura a los datos de la app. Los fallos de SQLite se lanzan como errores Lua.
+fn register_db_module(
+    lua: &Lua,
+    parent_table: &Table,
+    db: AppDb,
+    plugin_name: String,
+) -> Result<()> {
     let db_table = lua.create_table()?;
+    let plugin = Arc::new(plugin_name);
 
-    let log_operation =
-        lua.create_function(|_, (plugin, action, details): (String, String, String)| {
-            println!(
-                "[DB Mock] Insertando operacion -> Plugin: {}, Accion: {}, Detalles: {}",
-                plugin, action, details
-            );
-            Ok(())
+    register_kv_module(lua, &db_table, db.clone(), plugin.clone())?;
+    register_sql_module(lua, &db_table, db.clone(), &plugin)?;
+
+    // savecloud.db.games() -> { { id, paths, steamAppId, installDir }, ... }
+    let games = lua.create_function(|lua, ()| to_lua(lua, &storage::games()))?;
+
+    // savecloud.db.history(limit?) -> operaciones de sincronización, la más reciente primero
+    let history = lua.create_function(|lua, limit: Option<usize>| {
+        to_lua(lua, &storage::history(limit.unwrap_or(DEFAULT_DB_LIMIT)))
+    })?;
+
+    // savecloud.db.playtime(period, game_id?) -> tiempo por "day", "week" o "month"
+    let playtime_db = db.clone();
+    let playtime =
+        lua.create_function(move |lua, (period, game_id): (Value, Option<String>)| {
+            let period: PlaytimePeriod = lua.from_value(period)?;
+            let since = now_secs() - period.default_span_secs();
+            let offset = chrono::Local::now().offset().local_minus_utc() as i64;
+            let buckets = playtime_db
+                .with_conn(|c| sessions::aggregate(c, period, game_id.as_deref(), since, offset))
+                .map_err(db_error)?;
+            to_lua(lua, &buckets)
         })?;
 
-    db_table.set("log_operation", log_operation)?;
+    // savecloud.db.sessions(game_id?, limit?) -> partidas más recientes
+    let sessions_db = db.clone();
+    let sessions = lua.create_function(
+        move |lua, (game_id, limit): (Option<String>, Option<usize>)| {
+            let limit = limit.unwrap_or(DEFAULT_DB_LIMIT) as i64;
+            let recent = sessions_db
+                .with_conn(|c| sessions::recent(c, game_id.as_deref(), limit))
+                .map_err(db_error)?;
+            to_lua(lua, &recent)
+        },
+    )?;
+
+    // savecloud.db.sync_status() -> { jobs = cola de sincronización, transfers = en curso }
+    let sync_status = lua.create_function(move |lua, ()| {
+        let status = db.with_conn(storage::sync_status).map_err(db_error)?;
+        to_lua(lua, &status)
+    })?;
+
+    db_table.set("games", games)?;
+    db_table.set("history", history)?;
+    db_table.set("playtime", playtime)?;
+    db_table.set("sessions", sessions)?;
+    db_table.set("sync_status", sync_status)?;
     parent_table.set("db", db_table)?;
 
     Ok(())
 }
 
+// savecloud.db.kv guarda valores Lua (nil, booleanos, números, strings y tablas de
+// ellos) bajo claves propias del plugin.
+fn register_kv_module(
+    lua: &Lua,
+    parent_table: &Table,
+    db: AppDb,
+    plugin: Arc<String>,
+) -> Result<()> {
+    let kv_table = lua.create_table()?;
+
+    // savecloud.db.kv.get(key) -> valor | nil
+    let (get_db, get_plugin) = (db.clone(), plugin.clone());
+    let get = lua.create_function(move |lua, key: String| {
+        let json = get_db
+            .with_conn(|c| storage::kv_get(c, &get_plugin, &key))
+            .map_err(db_error)?;
+        match json {
+            Some(json) => {
+                let value: serde_json::Value =
+                    serde_json::from_str(&json).map_err(mlua::Error::external)?;
+                to_lua(lua, &value)
+            }
+            None => Ok(Value::Nil),
+        }
+    })?;
+
+    // savecloud.db.kv.set(key, value); guardar nil borra la clave
+    let (set_db, set_plugin) = (db.clone(), plugin.clone());
+    let set = lua.create_function(move |lua, (key, value): (String, Value)| {
+        check_key(&key)?;
+        if value.is_nil() {
+            set_db
+                .with_conn(|c| storage::kv_delete(c, &set_plugin, &key))
+                .map_err(db_error)?;
+            return Ok(());
+        }
+        let value: serde_json::Value = lua.from_value(value)?;
+        let json = value.to_string();
+        if json.len() > storage::MAX_VALUE_BYTES {
+            return Err(mlua::Error::RuntimeError(format!(
+                "el valor supera el máximo de {} bytes",
+                storage::MAX_VALUE_BYTES
+            )));
+        }
+        set_db
+            .with_conn(|c| storage::kv_set(c, &set_plugin, &key, &json, now_secs()))
+            .map_err(db_error)
+    })?;
+
+    // savecloud.db.kv.delete(key) -> boolean (si existía)
+    let (delete_db, delete_plugin) = (db.clone(), plugin.clone());
+    let delete = lua.create_function(move |_, key: String| {
+        delete_db
+            .with_conn(|c| storage::kv_delete(c, &delete_plugin, &key))
+            .map_err(db_error)
+    })?;
+
+    // savecloud.db.kv.list(prefix?) -> { "clave", ... } en orden alfabético
+    let list = lua.create_function(move |_, prefix: Option<String>| {
+        db.with_conn(|c| storage::kv_list(c, &plugin, prefix.as_deref().unwrap_or_default()))
+            .map_err(db_error)
+    })?;
+
+    kv_table.set("get", get)?;
+    kv_table.set("set", set)?;
+    kv_table.set("delete", delete)?;
+    kv_table.set("list", list)?;
+    parent_table.set("kv", kv_table)?;
+
+    Ok(())
+}
+
+// savecloud.db.sql trabaja sobre las tablas del plugin, cuyos nombres deben empezar por
+// `savecloud.db.sql.prefix`. Los parámetros van en una lista y se enlazan como `?1`, `?2`...
+//
+// Ejemplo:
+//   local t = savecloud.db.sql.prefix .. "partidas"
+//   savecloud.db.sql.execute("CREATE TABLE IF NOT EXISTS " .. t .. " (juego TEXT, minutos INTEGER)")
+//   savecloud.db.sql.execute("INSERT INTO " .. t .. " VALUES (?1, ?2)", { "Hades", 30 })
+//   local filas = savecloud.db.sql.query("SELECT * FROM " .. t .. " WHERE juego = ?1", { "Hades" })
+fn register_sql_module(lua: &Lua, parent_table: &Table, db: AppDb, plugin: &str) -> Result<()> {
+    let sql_table = lua.create_table()?;
+    let prefix = Arc::new(storage::table_prefix(plugin));
+
+    // savecloud.db.sql.query(sql, params?) -> { { columna = valor, ... }, ... }
+    let (query_db, query_prefix) = (db.clone(), prefix.clone());
+    let query = lua.create_function(move |lua, (sql, params): (String, Option<Table>)| {
+        let params = sql_params(params)?;
+        let result = query_db
+            .with_conn(|c| storage::query(c, &query_prefix, &sql, params))
+            .map_err(db_error)?;
+        let rows = lua.create_table()?;
+        for values in result.rows {
+            let row = lua.create_table()?;
+            for (column, value) in result.columns.iter().zip(values) {
+                row.set(column.as_str(), sql_to_lua(lua, value)?)?;
+            }
+            rows.push(row)?;
+        }
+        Ok(rows)
+    })?;
+
+    // savecloud.db.sql.execute(sql, params?) -> filas cambiadas
+    let execute_prefix = prefix.clone();
+    let execute = lua.create_function(move |_, (sql, params): (String, Option<Table>)| {
+        let params = sql_params(params)?;
+        db.with_conn(|c| storage::execute(c, &execute_prefix, &sql, params))
+            .map_err(db_error)
+    })?;
+
+    sql_table.set("prefix", prefix.as_str())?;
+    sql_table.set("query", query)?;
+    sql_table.set("execute", execute)?;
+    parent_table.set("sql", sql_table)?;
+
+    Ok(())
+}
+
+fn check_key(key: &str) -> Result<()> {
+    if key.is_empty() || key.len() > storage::MAX_KEY_BYTES {
+        return Err(mlua::Error::RuntimeError(format!(
+            "la clave debe tener entre 1 y {} bytes",
+            storage::MAX_KEY_BYTES
+        )));
+    }
+    Ok(())
+}
+
+fn sql_params(params: Option<Table>) -> Result<Vec<SqlValue>> {
+    let Some(params) = params else {
+        return Ok(Vec::new());
+    };
+    (1..=params.raw_len())
+        .map(|i| match params.raw_get::<Value>(i)? {
+            Value::Nil => Ok(SqlValue::Null),
+            Value::Boolean(b) => Ok(SqlValue::Integer(b.into())),
+            Value::Integer(n) => Ok(SqlValue::Integer(n)),
+            Value::Number(n) => Ok(SqlValue::Real(n)),
+            Value::String(s) => Ok(match s.to_str() {
+                Ok(text) => SqlValue::Text(text.to_string()),
+                Err(_) => SqlValue::Blob(s.as_bytes().to_vec()),
+            }),
+            other => Err(mlua::Error::RuntimeError(format!(
+                "parámetro SQL no admitido: {}",
+                other.type_name()
+            ))),
+        })
+        .collect()
+}
+
+fn sql_to_lua(lua: &Lua, value: SqlValue) -> Result<Value> {
+    Ok(match value {
+        SqlValue::Null => Value::Nil,
+        SqlValue::Integer(n) => Value::Integer(n),
+        SqlValue::Real(n) => Value::Number(n),
+        SqlValue::Text(text) => Value::String(lua.create_string(text)?),
+        SqlValue::Blob(bytes) => Value::String(lua.create_string(bytes)?),
+    })
+}
+
+/// Convierte a Lua dejando los `None` y `null` como `nil`.
+fn to_lua(lua: &Lua, value: &impl Serialize) -> Result<Value> {
+    let options = SerializeOptions::new()
+        .serialize_none_to_null(false)
+        .serialize_unit_to_null(false);
+    lua.to_value_with(value, options)
+}
+
+fn db_error(err: SqliteError) -> mlua::Error {
+    mlua::Error::RuntimeError(err.to_string())
+}
+
 // Todas las funciones devuelven una tabla Lua con la forma:
 //
 //   { ok = true,  status = 200, body = "..." }
 //   { ok = false, status = 0,   body = "",  error = "mensaje" }
 //
 // Esto permite que el plugin maneje errores sin que un fallo de
-// red rompa el plugin completo.
+// red rompa el plugin completo. Un host que no está en plugin.toml
+// (también tras una redirección) se trata como un error de red.
+//
+// Son funciones async: mientras esperan la respuesta, la corrutina del
+// plugin queda suspendida y no bloquea ningún hilo.
 //
 // Headers opcionales se pasan como tabla Lua:
 //   { ["Content-Type"] = "application/json", ["Authorization"] = "Bearer ..." }
@@ -183,8 +456,62 @@ fn headers_from_lua(tabla: Option<Table>) -> reqwest::header::HeaderMap {
     map
 }
 
-fn register_http_module(lua: &Lua, parent_table: &Table) -> Result<()> {
+/// Cliente HTTP para `url` si su host está aprobado; las redirecciones a otros hosts
+/// se cortan.
+fn allowed_client(
+    capabilities: &Arc<Capabilities>,
+    url: &str,
+) -> std::result::Result<reqwest::Client, String> {
+    let parsed = reqwest::Url::parse(url).map_err(|e| format!("URL no válida: {}", e))?;
+    let host = parsed.host_str().unwrap_or_default();
+    if !capabilities.allows_host(host) {
+        return Err(format!("host no permitido por plugin.toml: {}", host));
+    }
+
+    let capabilities = capabilities.clone();
+    let policy = reqwest::redirect::Policy::custom(move |attempt| {
+        let host = attempt.url().host_str().unwrap_or_default().to_string();
+        if attempt.previous().len() >= 10 {
+            attempt.error("demasiadas redirecciones")
+        } else if capabilities.allows_host(&host) {
+            attempt.follow()
+        } else {
+            attempt.error(format!("redirección a un host no permitido: {}", host))
+        }
+    });
+    reqwest::Client::builder()
+        .redirect(policy)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+/// Envía la petición y la convierte en la tabla de respuesta.
+async fn send_request(
+    lua: &Lua,
+    request: std::result::Result<reqwest::RequestBuilder, String>,
+) -> Result<Table> {
+    let request = match request {
+        Ok(request) => request,
+        Err(e) => return build_error_table(lua, e),
+    };
+
+    match request.send().await {
+        Ok(res) => {
+            let status = res.status().as_u16();
+            let body = res.text().await.unwrap_or_default();
+            build_response_table(lua, status, body)
+        }
+        Err(e) => build_error_table(lua, e.to_string()),
+    }
+}
+
+fn register_http_module(
+    lua: &Lua,
+    parent_table: &Table,
+    capabilities: &Capabilities,
+) -> Result<()> {
     let http_table = lua.create_table()?;
+    let capabilities = Arc::new(capabilities.clone());
 
     // savecloud.http.get(url, headers?)
     // Realiza una petición GET. Headers es opcional.
@@ -194,19 +521,11 @@ fn register_http_module(lua: &Lua, parent_table: &Table) -> Result<()> {
     //   local res = savecloud.http.get("https://api.example.com/me", {
     //     ["Authorization"] = "Bearer token123"
     //   })
-    let get = lua.create_function(|lua, (url, headers): (String, Option<Table>)| {
-        let client = reqwest::blocking::Client::new();
-
-        let result = client.get(&url).headers(headers_from_lua(headers)).send();
-
-        match result {
-            Ok(res) => {
-                let status = res.status().as_u16();
-                let body = res.text().unwrap_or_default();
-                Ok(build_response_table(lua, status, body)?)
-            }
-            Err(e) => Ok(build_error_table(lua, e.to_string())?),
-        }
+    let caps = capabilities.clone();
+    let get = lua.create_async_function(move |lua, (url, headers): (String, Option<Table>)| {
+        let request = allowed_client(&caps, &url)
+            .map(|client| client.get(&url).headers(headers_from_lua(headers)));
+        async move { send_request(&lua, request).await }
     })?;
 
     // savecloud.http.post(url, body, headers?)
@@ -218,24 +537,16 @@ fn register_http_module(lua: &Lua, parent_table: &Table) -> Result<()> {
     //     '{"key":"value"}',
     //     { ["Content-Type"] = "application/json" }
     //   )
-    let post = lua.create_function(
-        |lua, (url, body, headers): (String, String, Option<Table>)| {
-            let client = reqwest::blocking::Client::new();
-
-            let result = client
-                .post(&url)
-                .headers(headers_from_lua(headers))
-                .body(body)
-                .send();
-
-            match result {
-                Ok(res) => {
-                    let status = res.status().as_u16();
-                    let body = res.text().unwrap_or_default();
-                    Ok(build_response_table(lua, status, body)?)
-                }
-                Err(e) => Ok(build_error_table(lua, e.to_string())?),
-            }
+    let caps = capabilities.clone();
+    let post = lua.create_async_function(
+        move |lua, (url, body, headers): (String, String, Option<Table>)| {
+            let request = allowed_client(&caps, &url).map(|client| {
+                client
+                    .post(&url)
+                    .headers(headers_from_lua(headers))
+                    .body(body)
+            });
+            async move { send_request(&lua, request).await }
         },
     )?;
 
@@ -248,24 +559,16 @@ fn register_http_module(lua: &Lua, parent_table: &Table) -> Result<()> {
     //     '{"name":"nuevo"}',
     //     { ["Content-Type"] = "application/json" }
     //   )
-    let put = lua.create_function(
-        |lua, (url, body, headers): (String, String, Option<Table>)| {
-            let client = reqwest::blocking::Client::new();
-
-            let result = client
-                .put(&url)
-                .headers(headers_from_lua(headers))
-                .body(body)
-                .send();
-
-            match result {
-                Ok(res) => {
-                    let status = res.status().as_u16();
-                    let body = res.text().unwrap_or_default();
-                    Ok(build_response_table(lua, status, body)?)
-                }
-                Err(e) => Ok(build_error_table(lua, e.to_string())?),
-            }
+    let caps = capabilities.clone();
+    let put = lua.create_async_function(
+        move |lua, (url, body, headers): (String, String, Option<Table>)| {
+            let request = allowed_client(&caps, &url).map(|client| {
+                client
+                    .put(&url)
+                    .headers(headers_from_lua(headers))
+                    .body(body)
+            });
+            async move { send_request(&lua, request).await }
         },
     )?;
 
@@ -277,23 +580,13 @@ fn register_http_module(lua: &Lua, parent_table: &Table) -> Result<()> {
     //     "https://api.example.com/resource/1",
     //     { ["Authorization"] = "Bearer token123" }
     //   )
-    let delete = lua.create_function(|lua, (url, headers): (String, Option<Table>)| {
-        let client = reqwest::blocking::Client::new();
-
-        let result = client
-            .delete(&url)
-            .headers(headers_from_lua(headers))
-            .send();
-
-        match result {
-            Ok(res) => {
-                let status = res.status().as_u16();
-                let body = res.text().unwrap_or_default();
-                Ok(build_response_table(lua, status, body)?)
-            }
-            Err(e) => Ok(build_error_table(lua, e.to_string())?),
-        }
-    })?;
+    let caps = capabilities.clone();
+    let delete =
+        lua.create_async_function(move |lua, (url, headers): (String, Option<Table>)| {
+            let request = allowed_client(&caps, &url)
+                .map(|client| client.delete(&url).headers(headers_from_lua(headers)));
+            async move { send_request(&lua, request).await }
+        })?;
 
     http_table.set("get", get)?;
     http_table.set("post", post)?;
@@ -303,3 +596,140 @@ fn register_http_module(lua: &Lua, parent_table: &Table) -> Result<()> {
 
     Ok(())
 }
+
+// Las funciones devuelven `nil, "mensaje"` si la ruta no está permitida o falla la
+// operación, igual que `io.open`. Las rutas deben ser absolutas y estar dentro de
+// alguna carpeta de `fs` en plugin.toml.
+fn register_fs_module(lua: &Lua, parent_table: &Table, scope: FsScope) -> Result<()> {
+    let fs_table = lua.create_table()?;
+    let scope = Arc::new(scope);
+
+    // savecloud.fs.read(path) -> string | nil, error
+    let read_scope = scope.clone();
+    let read = lua.create_function(move |lua, path: String| {
+        let content = read_scope
+            .resolve(&path)
+            .and_then(|p| std::fs::read(p).map_err(|e| e.to_string()));
+        match content {
+            Ok(bytes) => Ok((Some(lua.create_string(bytes)?), None)),
+            Err(e) => Ok((None, Some(e))),
+        }
+    })?;
+
+    // savecloud.fs.write(path, content) -> true | nil, error
+    let write_scope = scope.clone();
+    let write = lua.create_function(move |_, (path, content): (String, mlua::String)| {
+        let result = write_scope
+            .resolve(&path)
+            .and_then(|p| std::fs::write(p, content.as_bytes()).map_err(|e| e.to_string()));
+        match result {
+            Ok(()) => Ok((Some(true), None)),
+            Err(e) => Ok((None, Some(e))),
+        }
+    })?;
+
+    // savecloud.fs.list(path) -> { "nombre", ... } | nil, error
+    let list_scope = scope.clone();
+    let list = lua.create_function(move |_, path: String| {
+        let names = list_scope.resolve(&path).and_then(|p| {
+            std::fs::read_dir(p)
+                .map(|entries| {
+                    entries
+                        .flatten()
+                        .map(|e| e.file_name().to_string_lossy().into_owned())
+                        .collect::<Vec<_>>()
+                })
+                .map_err(|e| e.to_string())
+        });
+        match names {
+            Ok(names) => Ok((Some(names), None)),
+            Err(e) => Ok((None, Some(e))),
+        }
+    })?;
+
+    // savecloud.fs.exists(path) -> boolean | nil, error
+    let exists = lua.create_function(move |_, path: String| match scope.resolve(&path) {
+        Ok(p) => Ok((Some(p.exists()), None)),
+        Err(e) => Ok((None, Some(e))),
+    })?;
+
+    fs_table.set("read", read)?;
+    fs_table.set("write", write)?;
+    fs_table.set("list", list)?;
+    fs_table.set("exists", exists)?;
+    parent_table.set("fs", fs_table)?;
+
+    Ok(())
+}
+
+/// Intervalo mínimo de `savecloud.timer.every`.
+const MIN_TIMER_INTERVAL_MS: u64 = 1_000;
+
+// Los timers corren en el ejecutor del plugin ([`PluginTasks`]): cada llamada a la
+// función tiene los mismos límites que un hook y sus errores van al log del plugin.
+// Todos se cancelan al descargar el plugin.
+fn register_timer_module(lua: &Lua, parent_table: &Table, tasks: PluginTasks) -> Result<()> {
+    let timer_table = lua.create_table()?;
+
+    // savecloud.timer.after(ms, fn) -> id
+    // Llama a `fn` una vez dentro de `ms` milisegundos. Con 0, en cuanto se pueda:
+    // sirve para hacer trabajo en segundo plano sin retrasar un hook.
+    let after_tasks = tasks.clone();
+    let after = lua.create_function(move |_, (ms, func): (u64, Function)| {
+        let tasks = after_tasks.clone();
+        after_tasks
+            .spawn("timer", async move {
+                tokio::time::sleep(Duration::from_millis(ms)).await;
+                tasks.call::<()>(&func, (), TASK_TIMEOUT).await
+            })
+            .map_err(mlua::Error::RuntimeError)
+    })?;
+
+    // savecloud.timer.every(ms, fn) -> id
+    // Llama a `fn` cada `ms` milisegundos (al menos 1000), empezando dentro de `ms`.
+    // Si `fn` lanza un error, el timer se detiene.
+    let every_tasks = tasks.clone();
+    let every = lua.create_function(move |_, (ms, func): (u64, Function)| {
+        if ms < MIN_TIMER_INTERVAL_MS {
+            return Err(mlua::Error::RuntimeError(format!(
+                "el intervalo mínimo es de {} ms",
+                MIN_TIMER_INTERVAL_MS
+            )));
+        }
+        let tasks = every_tasks.clone();
+        let period = Duration::from_millis(ms);
+        every_tasks
+            .spawn("timer", async move {
+                let start = tokio::time::Instant::now() + period;
+                let mut interval = tokio::time::interval_at(start, period);
+                // Si una llamada tarda más que el intervalo, no se encadenan llamadas.
+                interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                loop {
+                    interval.tick().await;
+                    tasks.call::<()>(&func, (), TASK_TIMEOUT).await?;
+                }
+            })
+            .map_err(mlua::Error::RuntimeError)
+    })?;
+
+    // savecloud.timer.cancel(id) -> boolean
+    // Cancela un timer; false si ya terminó o no existe.
+    let cancel_tasks = tasks.clone();
+    let cancel = lua.create_function(move |_, id: u64| Ok(cancel_tasks.cancel(id)))?;
+
+    // savecloud.timer.sleep(ms)
+    // Suspende la llamada actual del plugin sin bloquear ningún hilo. Cuenta para el
+    // límite de tiempo de la llamada.
+    let sleep = lua.create_async_function(|_, ms: u64| async move {
+        tokio::time::sleep(Duration::from_millis(ms)).await;
+        Ok(())
+    })?;
+
+    timer_table.set("after", after)?;
+    timer_table.set("every", every)?;
+    timer_table.set("cancel", cancel)?;
+    timer_table.set("sleep", sleep)?;
+    parent_table.set("timer", timer_table)?;
+
+    Ok(())
+}
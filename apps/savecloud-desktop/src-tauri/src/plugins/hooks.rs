@@ -0,0 +1,318 @@
+//! Hooks de ciclo de vida que lanzan el motor de sincronización y el monitor de procesos.
+//!
+//! Cada hook es una función global opcional del plugin que recibe tablas Lua:
+//!
+//! | Hook                 | Argumentos                         | Puede cancelar |
+//! |----------------------|------------------------------------|----------------|
+//! | `on_before_upload`   | `game`, `files`                    | sí             |
+//! | `on_after_upload`    | `game`, `result`                   | no             |
+//! | `on_before_download` | `game`, `files`                    | sí             |
+//! | `on_after_download`  | `game`, `result`                   | no             |
+//! | `on_game_started`    | `game`, `{ launched }`             | no             |
+//! | `on_game_exited`     | `game`, `{ played_seconds }`       | no             |
+//! | `on_conflict`        | `game`, `conflicts`                | no             |
+//! | `on_backup_created`  | `game`, `{ kind, location }`       | no             |
+//!
+//! Los hooks que pueden cancelar aceptan las respuestas de [`HookReply`]: el primer
+//! plugin que cancela detiene la operación y los siguientes ya no se llaman. La
+//! operación espera a cada plugin como mucho [`CALL_TIMEOUT`]. El resto de hooks se
+//! lanzan en el ejecutor de cada plugin ([`super::tasks`]) y la operación no los espera.
+//! Un error en un plugin se anota en su registro ([`AppLogs`]) y no afecta a los demás
+//! ni a la operación.
+//!
+//! Los hooks solo se lanzan si la app instaló los plugins con [`install`]; la CLI
+//! comparte el motor y sincroniza sin ellos.
+//!
+//! [`HookReply`]: super::plugin::HookReply
+//! [`CALL_TIMEOUT`]: super::sandbox::CALL_TIMEOUT
+
+use super::log_buffer::{self, AppLogs};
+use super::AppPluginManager;
+use crate::commands::sync::models::{DownloadConflictDto, RemoteSaveInfoDto, SyncResultDto};
+use crate::config::ConfiguredGame;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tauri::AppHandle;
+
+/// Hooks que el core lanza sobre los plugins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hook {
+    BeforeUpload,
+    AfterUpload,
+    BeforeDownload,
+    AfterDownload,
+    GameStarted,
+    GameExited,
+    Conflict,
+    BackupCreated,
+}
+
+impl Hook {
+    /// Nombre de la función global que implementa el hook en Lua.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::BeforeUpload => "on_before_upload",
+            Self::AfterUpload => "on_after_upload",
+            Self::BeforeDownload => "on_before_download",
+            Self::AfterDownload => "on_after_download",
+            Self::GameStarted => "on_game_started",
+            Self::GameExited => "on_game_exited",
+            Self::Conflict => "on_conflict",
+            Self::BackupCreated => "on_backup_created",
+        }
+    }
+
+    /// Si la respuesta del plugin puede cancelar la operación u omitir archivos.
+    pub fn can_veto(self) -> bool {
+        matches!(self, Self::BeforeUpload | Self::BeforeDownload)
+    }
+}
+
+/// Resultado de lanzar un hook en todos los plugins.
+#[derive(Debug, Default)]
+pub struct HookOutcome {
+    /// Plugin que canceló la operación y su motivo, si lo dio.
+    pub cancelled_by: Option<(String, Option<String>)>,
+    /// Rutas relativas que algún plugin pidió dejar fuera.
+    pub skip: HashSet<String>,
+    /// Errores por plugin.
+    pub errors: Vec<(String, String)>,
+}
+
+impl HookOutcome {
+    /// Archivos a omitir, o el error que cancela `operation` ("la subida", "la descarga").
+    fn into_skip(self, operation: &str) -> Result<HashSet<String>, String> {
+        match self.cancelled_by {
+            Some((plugin, Some(reason))) => Err(format!(
+                "El plugin '{}' canceló {}: {}",
+                plugin, operation, reason
+            )),
+            Some((plugin, None)) => Err(format!("El plugin '{}' canceló {}", plugin, operation)),
+            None => Ok(self.skip),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct GameInfo<'a> {
+    id: &'a str,
+    paths: &'a [String],
+}
+
+impl<'a> From<&'a ConfiguredGame> for GameInfo<'a> {
+    fn from(game: &'a ConfiguredGame) -> Self {
+        Self {
+            id: &game.id,
+            paths: &game.paths,
+        }
+    }
+}
+
+/// Archivo de una operación; `path` es relativo a la carpeta del juego.
+#[derive(Serialize)]
+struct FileInfo<'a> {
+    path: &'a str,
+    size: Option<u64>,
+    last_modified: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct ResultInfo<'a> {
+    ok_count: u32,
+    err_count: u32,
+    errors: &'a [String],
+}
+
+impl<'a> From<&'a SyncResultDto> for ResultInfo<'a> {
+    fn from(result: &'a SyncResultDto) -> Self {
+        Self {
+            ok_count: result.ok_count,
+            err_count: result.err_count,
+            errors: &result.errors,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ConflictInfo<'a> {
+    path: &'a str,
+    local_modified: &'a str,
+    cloud_modified: &'a str,
+}
+
+/// Backup recién creado: `local` es la copia previa a una descarga (ruta en disco) y
+/// `cloud` un backup empaquetado subido (nombre relativo en la nube).
+#[derive(Serialize)]
+pub struct BackupInfo {
+    kind: &'static str,
+    location: String,
+}
+
+impl BackupInfo {
+    pub fn local(path: &std::path::Path) -> Self {
+        Self {
+            kind: "local",
+            location: path.to_string_lossy().into_owned(),
+        }
+    }
+
+    pub fn cloud(relative_filename: &str) -> Self {
+        Self {
+            kind: "cloud",
+            location: relative_filename.to_string(),
+        }
+    }
+}
+
+struct PluginHost {
+    manager: AppPluginManager,
+    logs: AppLogs,
+    app: AppHandle,
+}
+
+static HOST: OnceLock<PluginHost> = OnceLock::new();
+
+/// Activa los hooks con los plugins de la app.
+pub fn install(manager: AppPluginManager, logs: AppLogs, app: AppHandle) {
+    let _ = HOST.set(PluginHost { manager, logs, app });
+}
+
+/// `on_before_upload`: devuelve los archivos a omitir o el motivo de la cancelación.
+pub async fn before_upload(
+    game: &ConfiguredGame,
+    files: &[((String, String), u64)],
+) -> Result<HashSet<String>, String> {
+    if HOST.get().is_none() {
+        return Ok(HashSet::new());
+    }
+    let files: Vec<FileInfo> = files
+        .iter()
+        .map(|((_, relative), size)| FileInfo {
+            path: relative,
+            size: Some(*size),
+            last_modified: None,
+        })
+        .collect();
+    let args = [to_json(&GameInfo::from(game)), to_json(&files)];
+    run(Hook::BeforeUpload, args).await.into_skip("la subida")
+}
+
+/// `on_after_upload`.
+pub async fn after_upload(game: &ConfiguredGame, result: &SyncResultDto) {
+    if HOST.get().is_some() {
+        let args = [
+            to_json(&GameInfo::from(game)),
+            to_json(&ResultInfo::from(result)),
+        ];
+        run(Hook::AfterUpload, args).await;
+    }
+}
+
+/// `on_before_download`: devuelve los archivos a omitir o el motivo de la cancelación.
+pub async fn before_download(
+    game: &ConfiguredGame,
+    saves: &[RemoteSaveInfoDto],
+) -> Result<HashSet<String>, String> {
+    if HOST.get().is_none() {
+        return Ok(HashSet::new());
+    }
+    let files: Vec<FileInfo> = saves
+        .iter()
+        .map(|save| FileInfo {
+            path: &save.filename,
+            size: save.size,
+            last_modified: Some(&save.last_modified),
+        })
+        .collect();
+    let args = [to_json(&GameInfo::from(game)), to_json(&files)];
+    run(Hook::BeforeDownload, args)
+        .await
+        .into_skip("la descarga")
+}
+
+/// `on_after_download`.
+pub async fn after_download(game: &ConfiguredGame, result: &SyncResultDto) {
+    if HOST.get().is_some() {
+        let args = [
+            to_json(&GameInfo::from(game)),
+            to_json(&ResultInfo::from(result)),
+        ];
+        run(Hook::AfterDownload, args).await;
+    }
+}
+
+/// `on_conflict`: archivos locales más recientes que los de la nube.
+pub async fn conflict(game: &ConfiguredGame, conflicts: &[DownloadConflictDto]) {
+    if HOST.get().is_none() || conflicts.is_empty() {
+        return;
+    }
+    let conflicts: Vec<ConflictInfo> = conflicts
+        .iter()
+        .map(|c| ConflictInfo {
+            path: &c.filename,
+            local_modified: &c.local_modified,
+            cloud_modified: &c.cloud_modified,
+        })
+        .collect();
+    let args = [to_json(&GameInfo::from(game)), to_json(&conflicts)];
+    run(Hook::Conflict, args).await;
+}
+
+/// `on_backup_created`.
+pub async fn backup_created(game_id: &str, backup: BackupInfo) {
+    if HOST.get().is_some() {
+        run_for_game(Hook::BackupCreated, game_id, to_json(&backup)).await;
+    }
+}
+
+/// `on_game_started`; `launched` indica si lo abrió la app.
+pub async fn game_started(game_id: &str, launched: bool) {
+    if HOST.get().is_some() {
+        let info = serde_json::json!({ "launched": launched });
+        run_for_game(Hook::GameStarted, game_id, info).await;
+    }
+}
+
+/// `on_game_exited`; `played` cuenta desde que se detectó abierto.
+pub async fn game_exited(game_id: &str, played: Duration) {
+    if HOST.get().is_some() {
+        let info = serde_json::json!({ "played_seconds": played.as_secs() });
+        run_for_game(Hook::GameExited, game_id, info).await;
+    }
+}
+
+/// Lanza un hook que solo conoce el id del juego; se omite si ya no está configurado.
+async fn run_for_game(hook: Hook, game_id: &str, info: serde_json::Value) {
+    let cfg = crate::config::load_config();
+    if let Some(game) = cfg
+        .games
+        .iter()
+        .find(|g| g.id.eq_ignore_ascii_case(game_id))
+    {
+        run(hook, [to_json(&GameInfo::from(game)), info]).await;
+    }
+}
+
+fn to_json(value: &impl Serialize) -> serde_json::Value {
+    serde_json::to_value(value).unwrap_or_default()
+}
+
+/// Lanza `hook` en un hilo de bloqueo: esperar a los hooks que pueden cancelar no debe
+/// frenar el runtime.
+async fn run<const N: usize>(hook: Hook, args: [serde_json::Value; N]) -> HookOutcome {
+    let Some(host) = HOST.get() else {
+        return HookOutcome::default();
+    };
+    tokio::task::spawn_blocking(move || {
+        let outcome = host.manager.blocking_lock().run_hook(hook, &args);
+        for (plugin, message) in &outcome.errors {
+            let message = format!("{}: {}", hook.name(), message);
+            log_buffer::report_blocking(&host.logs, &host.app, "error", plugin, &message);
+        }
+        outcome
+    })
+    .await
+    .unwrap_or_default()
+}
@@ -0,0 +1,193 @@
+//! Ciclo de vida de los plugins: listarlos con su estado, activarlos y desactivarlos, y
+//! recargarlos sin reiniciar la app.
+//!
+//! Desactivar un plugin se guarda en `plugin_disabled` y se respeta en los siguientes
+//! arranques. Antes de descargar un plugin (al desactivarlo, recargarlo o cerrar la app) se
+//! llama a su `on_unload`. Tras cada cambio la app emite `plugins_changed` con la lista
+//! completa; los cambios en disco los detecta [`super::watcher`].
+
+use super::manager::PluginManager;
+use super::manifest::{Capabilities, PluginManifest};
+use super::permissions::PERMISSIONS_REQUIRED_EVENT;
+use super::AppPluginManager;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use std::path::Path;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Evento con la lista de plugins ([`PluginInfoDto`]) tras cualquier cambio.
+pub const PLUGINS_CHANGED_EVENT: &str = "plugins_changed";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PluginStatus {
+    Loaded,
+    /// Espera a que el usuario apruebe sus permisos.
+    Pending,
+    /// Desactivado por el usuario.
+    Disabled,
+    /// No se pudo cargar; el motivo va en `error`.
+    Failed,
+}
+
+/// Un plugin de la carpeta de plugins, se haya cargado o no.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginInfoDto {
+    pub name: String,
+    pub version: Option<String>,
+    pub author: Option<String>,
+    pub description: Option<String>,
+    /// Carpeta del plugin.
+    pub dir: String,
+    pub status: PluginStatus,
+    /// Error de carga, o último error de `on_init` o de un hook si está cargado.
+    pub error: Option<String>,
+    pub capabilities: Option<Capabilities>,
+}
+
+impl PluginInfoDto {
+    pub fn new(
+        dir: &Path,
+        name: &str,
+        manifest: Option<&PluginManifest>,
+        status: PluginStatus,
+        error: Option<String>,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            version: manifest.map(|m| m.version.clone()),
+            author: manifest.and_then(|m| m.author.clone()),
+            description: manifest.and_then(|m| m.description.clone()),
+            dir: dir.to_string_lossy().into_owned(),
+            status,
+            error,
+            capabilities: manifest.map(|m| m.capabilities.clone()),
+        }
+    }
+}
+
+pub fn is_disabled(conn: &Connection, plugin: &str) -> rusqlite::Result<bool> {
+    conn.query_row(
+        "SELECT 1 FROM plugin_disabled WHERE plugin = ?1",
+        params![plugin],
+        |_| Ok(()),
+    )
+    .optional()
+    .map(|row| row.is_some())
+}
+
+pub fn set_disabled(
+    conn: &Connection,
+    plugin: &str,
+    disabled: bool,
+    now: i64,
+) -> rusqlite::Result<()> {
+    if disabled {
+        conn.execute(
+            "INSERT OR IGNORE INTO plugin_disabled (plugin, disabled_at) VALUES (?1, ?2)",
+            params![plugin, now],
+        )?;
+    } else {
+        conn.execute(
+            "DELETE FROM plugin_disabled WHERE plugin = ?1",
+            params![plugin],
+        )?;
+    }
+    Ok(())
+}
+
+/// Nombres de los plugins pendientes de aprobación, para [`notify_changed`].
+pub fn pending_names(manager: &PluginManager) -> Vec<String> {
+    manager
+        .pending
+        .iter()
+        .map(|p| p.manifest.name.to_lowercase())
+        .collect()
+}
+
+/// Emite [`PLUGINS_CHANGED_EVENT`] y, si algún plugin que no estaba en `pending_before`
+/// quedó pendiente de aprobación, [`PERMISSIONS_REQUIRED_EVENT`].
+pub fn notify_changed(app: &AppHandle, manager: &PluginManager, pending_before: &[String]) {
+    let _ = app.emit(PLUGINS_CHANGED_EVENT, manager.list());
+    let new_pending = pending_names(manager)
+        .iter()
+        .any(|name| !pending_before.contains(name));
+    if new_pending {
+        let _ = app.emit(PERMISSIONS_REQUIRED_EVENT, manager.pending_dtos());
+    }
+}
+
+/// Ejecuta `f` sobre el manager en un hilo aparte: cargar o descargar un plugin espera a
+/// su `init.lua`, `on_init` u `on_unload` con `block_on`.
+pub(super) async fn with_manager<T: Send + 'static>(
+    app: AppHandle,
+    manager: AppPluginManager,
+    f: impl FnOnce(&mut PluginManager) -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    tokio::task::spawn_blocking(move || {
+        let mut manager = manager.blocking_lock();
+        let pending_before = pending_names(&manager);
+        let result = f(&mut manager);
+        notify_changed(&app, &manager, &pending_before);
+        result
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Todos los plugins de la carpeta de plugins con su estado.
+#[tauri::command]
+pub async fn list_plugins(
+    manager: State<'_, AppPluginManager>,
+) -> Result<Vec<PluginInfoDto>, String> {
+    Ok(manager.lock().await.list())
+}
+
+/// Activa o desactiva el plugin `name`. Desactivarlo llama a su `on_unload`.
+#[tauri::command]
+pub async fn set_plugin_enabled(
+    name: String,
+    enabled: bool,
+    app: AppHandle,
+    manager: State<'_, AppPluginManager>,
+) -> Result<(), String> {
+    with_manager(app, manager.inner().clone(), move |m| {
+        m.set_enabled(&name, enabled)
+    })
+    .await
+}
+
+/// Descarga el plugin `name` y lo vuelve a cargar desde disco.
+#[tauri::command]
+pub async fn reload_plugin(
+    name: String,
+    app: AppHandle,
+    manager: State<'_, AppPluginManager>,
+) -> Result<(), String> {
+    with_manager(app, manager.inner().clone(), move |m| m.reload(&name)).await
+}
+
+/// Descarga todos los plugins y vuelve a leer la carpeta de plugins.
+#[tauri::command]
+pub async fn reload_plugins(
+    app: AppHandle,
+    manager: State<'_, AppPluginManager>,
+) -> Result<Vec<PluginInfoDto>, String> {
+    with_manager(app, manager.inner().clone(), |m| {
+        m.reload_all();
+        Ok(m.list())
+    })
+    .await
+}
+
+/// Llama a `on_unload` en todos los plugins al cerrar la app. Si el manager está ocupado
+/// (un hook en curso) no espera.
+pub fn unload_on_exit(app: &AppHandle) {
+    let Some(manager) = app.try_state::<AppPluginManager>() else {
+        return;
+    };
+    if let Ok(mut manager) = manager.try_lock() {
+        manager.unload_all();
+    };
+}
@@ -5,6 +5,7 @@
 
 use std::collections::VecDeque;
 use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
 use tokio::sync::Mutex;
 
 #[derive(Debug, Clone, serde::Serialize)]
@@ -44,4 +45,28 @@ pub type AppLogs = Arc<Mutex<LogBuffer>>;
 
 pub fn new_log_buffer() -> AppLogs {
     Arc::new(Mutex::new(LogBuffer::new()))
-}
\ No newline at end of file
+}
+
+/// Anota un mensaje del core sobre `plugin` y lo envía al panel de logs (`plugin_log`).
+/// Bloquea el buffer: solo desde hilos fuera del runtime async.
+pub fn report_blocking(logs: &AppLogs, app: &AppHandle, level: &str, plugin: &str, message: &str) {
+    let entry = core_entry(level, plugin, message);
+    logs.blocking_lock().push(entry.clone());
+    let _ = app.emit("plugin_log", entry);
+}
+
+/// Como [`report_blocking`], desde tareas async.
+pub async fn report(logs: &AppLogs, app: &AppHandle, level: &str, plugin: &str, message: &str) {
+    let entry = core_entry(level, plugin, message);
+    logs.lock().await.push(entry.clone());
+    let _ = app.emit("plugin_log", entry);
+}
+
+fn core_entry(level: &str, plugin: &str, message: &str) -> LogEntry {
+    LogEntry {
+        timestamp: chrono::Local::now().format("%H:%M:%S").to_string(),
+        level: level.to_string(),
+        plugin: plugin.to_string(),
+        message: message.to_string(),
+    }
+}
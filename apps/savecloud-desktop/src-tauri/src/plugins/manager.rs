@@ -2,24 +2,63 @@
 //!
 //! Contiene las funciones para:
 //!
-//! - Cargar todos los plugins.
+//! - Cargar todos los plugins con permisos aprobados.
 //! - Registrar el plugin.
-//! - Ejecutar el hook de inicialización.
-//! - Ejecutar el hook de pre-subida (Pipeline).
+//! - Guardar los plugins pendientes de aprobación, desactivados o que no cargaron.
+//! - Ejecutar los hooks de inicialización y de descarga.
+//! - Recargar, activar y desactivar plugins sin reiniciar la app ([`super::lifecycle`]).
+//! - Lanzar los hooks de ciclo de vida en todos los plugins.
 
+use super::hooks::{Hook, HookOutcome};
+use super::lifecycle::{self, PluginInfoDto, PluginStatus};
+use super::log_buffer::{self, AppLogs};
+use super::manifest::PluginManifest;
+use super::permissions::{self, PendingPluginDto};
 use super::plugin::{clean_lua_error, Plugin};
-use crate::plugins::log_buffer::AppLogs;
-use std::path::PathBuf;
+use crate::commands::sync::queue::now_secs;
+use crate::sqlite::AppDb;
+use std::path::{Path, PathBuf};
 use tauri::AppHandle;
 
+/// Lo que necesita el manager para cargar plugins en cualquier momento, no solo al arrancar.
+#[derive(Clone)]
+pub struct PluginContext {
+    pub plugins_dir: PathBuf,
+    pub app: AppHandle,
+    pub logs: AppLogs,
+    pub db: AppDb,
+}
+
 pub struct PluginManager {
     pub plugins: Vec<Plugin>,
+    /// Plugins que esperan a que el usuario apruebe sus permisos ([`permissions`]).
+    pub pending: Vec<PendingPlugin>,
+    /// Plugins desactivados por el usuario o que no se pudieron cargar.
+    pub unloaded: Vec<UnloadedPlugin>,
+    context: Option<PluginContext>,
+}
+
+pub struct PendingPlugin {
+    pub dir: PathBuf,
+    pub manifest: PluginManifest,
+}
+
+pub struct UnloadedPlugin {
+    pub dir: PathBuf,
+    pub name: String,
+    /// `None` si el manifiesto no se pudo leer.
+    pub manifest: Option<PluginManifest>,
+    /// Error de carga; `None` si el usuario lo desactivó.
+    pub error: Option<String>,
 }
 
 impl PluginManager {
     pub fn new() -> Self {
         Self {
             plugins: Vec::new(),
+            pending: Vec::new(),
+            unloaded: Vec::new(),
+            context: None,
         }
     }
 
@@ -27,52 +66,301 @@ impl PluginManager {
         self.plugins.len()
     }
 
-    pub fn load_all(&mut self, plugins_dir: PathBuf, app_handle: AppHandle, logs: AppLogs) {
-        if let Ok(entries) = std::fs::read_dir(plugins_dir) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-
-                if path.is_dir() {
-                    match Plugin::load_from_dir(&path, app_handle.clone(), logs.clone()) {
-                        Ok(plugin) => {
-                            if let Err(e) = plugin.trigger_on_init() {
-                                eprintln!(
-                                    "Error en on_init de '{}': {}",
-                                    plugin.name,
-                                    clean_lua_error(&e)
-                                );
-                            }
-
-                            self.plugins.push(plugin);
-                        }
-                        Err(e) => {
-                            eprintln!(
-                                "Omitiendo carpeta {:?}: {}",
-                                path.file_name().unwrap(),
-                                clean_lua_error(&e)
-                            );
-                        }
-                    }
+    /// Carga los plugins de `context.plugins_dir`, en orden alfabético de carpeta. Los
+    /// que no se pueden cargar quedan en [`PluginManager::pending`] o
+    /// [`PluginManager::unloaded`].
+    pub fn load_all(&mut self, context: PluginContext) {
+        let plugins_dir = context.plugins_dir.clone();
+        self.context = Some(context);
+
+        let Ok(entries) = std::fs::read_dir(plugins_dir) else {
+            return;
+        };
+        let mut dirs: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .collect();
+        dirs.sort();
+
+        for dir in dirs {
+            // Los errores ya quedan en el log y en la lista de plugins.
+            let _ = self.load_dir(&dir);
+        }
+    }
+
+    /// Lee el manifiesto de `dir` y carga el plugin si está activado y sus permisos están
+    /// aprobados; si no, lo deja pendiente o desactivado.
+    ///
+    /// # Errors
+    ///
+    /// Devuelve `Err` si el plugin no se pudo cargar; el error también se anota en su log
+    /// y queda en [`PluginManager::unloaded`].
+    pub fn load_dir(&mut self, dir: &Path) -> Result<(), String> {
+        let context = self
+            .context
+            .clone()
+            .ok_or("Los plugins aún no se han cargado")?;
+        let folder = dir
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let manifest = match PluginManifest::load(dir) {
+            Ok(manifest) => manifest,
+            Err(e) => return Err(self.fail(dir, folder, None, e)),
+        };
+        if self.has_name(&manifest.name) {
+            let error = format!("ya hay un plugin llamado '{}'", manifest.name);
+            return Err(self.fail(dir, folder, Some(manifest), error));
+        }
+
+        let name = manifest.name.clone();
+        let (disabled, granted) = context
+            .db
+            .with_conn(|c| {
+                Ok((
+                    lifecycle::is_disabled(c, &name)?,
+                    permissions::granted(c, &name, dir)?,
+                ))
+            })
+            .unwrap_or_default();
+        if disabled {
+            self.unloaded.push(UnloadedPlugin {
+                dir: dir.to_path_buf(),
+                name,
+                manifest: Some(manifest),
+                error: None,
+            });
+            return Ok(());
+        }
+
+        let requested = &manifest.capabilities;
+        let approved =
+            requested.is_empty() || granted.is_some_and(|granted| granted.covers(requested));
+        if !approved {
+            self.pending.push(PendingPlugin {
+                dir: dir.to_path_buf(),
+                manifest,
+            });
+            return Ok(());
+        }
+
+        match Plugin::load_from_dir(dir, manifest.clone(), context.app, context.logs) {
+            Ok(plugin) => {
+                if let Err(e) = plugin.trigger_on_init() {
+                    let error = clean_lua_error(&e);
+                    self.report(&plugin.name, &format!("on_init: {}", error));
+                    plugin.set_last_error(error);
                 }
+                self.plugins.push(plugin);
+                Ok(())
             }
+            Err(e) => Err(self.fail(dir, name, Some(manifest), clean_lua_error(&e))),
         }
     }
 
-    pub fn _execute_pre_upload(&self, mut data: Vec<u8>) -> Vec<u8> {
+    /// Descarga el plugin `name` (sea cual sea su estado) y lo vuelve a cargar desde su
+    /// carpeta con el manifiesto y los permisos actuales.
+    ///
+    /// # Errors
+    ///
+    /// Devuelve `Err` si no existe o no se pudo cargar.
+    pub fn reload(&mut self, name: &str) -> Result<(), String> {
+        let dir = self
+            .dir_of(name)
+            .ok_or_else(|| format!("No existe el plugin '{}'", name))?;
+        self.remove_dir(&dir);
+        self.load_dir(&dir)
+    }
+
+    /// Descarga todos los plugins y vuelve a escanear la carpeta de plugins.
+    pub fn reload_all(&mut self) {
+        let Some(context) = self.context.clone() else {
+            return;
+        };
+        self.unload_all();
+        self.load_all(context);
+    }
+
+    /// Vuelve a cargar lo que haya en `dir` tras un cambio en disco: un plugin nuevo, uno
+    /// modificado o uno borrado.
+    pub fn reload_dir(&mut self, dir: &Path) {
+        self.remove_dir(dir);
+        if dir.is_dir() {
+            let _ = self.load_dir(dir);
+        }
+    }
+
+    /// Activa o desactiva el plugin `name`; se recuerda entre arranques.
+    ///
+    /// # Errors
+    ///
+    /// Devuelve `Err` si no existe, no se pudo guardar o, al activarlo, no se pudo cargar.
+    pub fn set_enabled(&mut self, name: &str, enabled: bool) -> Result<(), String> {
+        let context = self
+            .context
+            .clone()
+            .ok_or("Los plugins aún no se han cargado")?;
+        let dir = self
+            .dir_of(name)
+            .ok_or_else(|| format!("No existe el plugin '{}'", name))?;
+        context
+            .db
+            .with_conn(|c| lifecycle::set_disabled(c, name, !enabled, now_secs()))
+            .map_err(|e| e.to_string())?;
+        self.remove_dir(&dir);
+        self.load_dir(&dir)
+    }
+
+    /// Llama a `on_unload` en cada plugin cargado y vacía el manager.
+    pub fn unload_all(&mut self) {
+        for plugin in std::mem::take(&mut self.plugins) {
+            self.unload_plugin(plugin);
+        }
+        self.pending.clear();
+        self.unloaded.clear();
+    }
+
+    pub fn list(&self) -> Vec<PluginInfoDto> {
+        let loaded = self.plugins.iter().map(|p| {
+            PluginInfoDto::new(
+                &p.dir,
+                &p.name,
+                Some(&p.manifest),
+                PluginStatus::Loaded,
+                p.last_error(),
+            )
+        });
+        let pending = self.pending.iter().map(|p| {
+            PluginInfoDto::new(
+                &p.dir,
+                &p.manifest.name,
+                Some(&p.manifest),
+                PluginStatus::Pending,
+                None,
+            )
+        });
+        let unloaded = self.unloaded.iter().map(|p| {
+            let status = if p.error.is_some() {
+                PluginStatus::Failed
+            } else {
+                PluginStatus::Disabled
+            };
+            PluginInfoDto::new(
+                &p.dir,
+                &p.name,
+                p.manifest.as_ref(),
+                status,
+                p.error.clone(),
+            )
+        });
+
+        let mut all: Vec<PluginInfoDto> = loaded.chain(pending).chain(unloaded).collect();
+        all.sort_by_key(|p| p.name.to_lowercase());
+        all
+    }
+
+    pub fn pending_dtos(&self) -> Vec<PendingPluginDto> {
+        self.pending
+            .iter()
+            .map(|p| PendingPluginDto::from(&p.manifest))
+            .collect()
+    }
+
+    pub fn take_pending(&mut self, name: &str) -> Option<PendingPlugin> {
+        let index = self
+            .pending
+            .iter()
+            .position(|p| p.manifest.name.eq_ignore_ascii_case(name))?;
+        Some(self.pending.remove(index))
+    }
+
+    fn has_name(&self, name: &str) -> bool {
+        self.dir_of(name).is_some()
+    }
+
+    fn dir_of(&self, name: &str) -> Option<PathBuf> {
+        let loaded = self.plugins.iter().map(|p| (&p.name, &p.dir));
+        let pending = self.pending.iter().map(|p| (&p.manifest.name, &p.dir));
+        let unloaded = self.unloaded.iter().map(|p| (&p.name, &p.dir));
+        loaded
+            .chain(pending)
+            .chain(unloaded)
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, dir)| dir.clone())
+    }
+
+    /// Saca del manager todo lo cargado desde `dir`, llamando a `on_unload` si hace falta.
+    fn remove_dir(&mut self, dir: &Path) {
+        let (removed, kept) = std::mem::take(&mut self.plugins)
+            .into_iter()
+            .partition(|p| p.dir == dir);
+        self.plugins = kept;
+        for plugin in removed {
+            self.unload_plugin(plugin);
+        }
+        self.pending.retain(|p| p.dir != dir);
+        self.unloaded.retain(|p| p.dir != dir);
+    }
+
+    fn unload_plugin(&self, plugin: Plugin) {
+        if let Err(e) = plugin.trigger_on_unload() {
+            self.report(&plugin.name, &format!("on_unload: {}", clean_lua_error(&e)));
+        }
+    }
+
+    /// Anota el error de carga y deja el plugin en la lista de no cargados.
+    fn fail(
+        &mut self,
+        dir: &Path,
+        name: String,
+        manifest: Option<PluginManifest>,
+        error: String,
+    ) -> String {
+        self.report(&name, &error);
+        self.unloaded.push(UnloadedPlugin {
+            dir: dir.to_path_buf(),
+            name,
+            manifest,
+            error: Some(error.clone()),
+        });
+        error
+    }
+
+    fn report(&self, plugin: &str, message: &str) {
+        if let Some(context) = &self.context {
+            log_buffer::report_blocking(&context.logs, &context.app, "error", plugin, message);
+        }
+    }
+
+    /// Lanza `hook` en cada plugin, en orden de carga. Si el hook puede cancelar, espera
+    /// a cada plugin y el primero que cancela detiene la ronda; si no, lo lanza en el
+    /// ejecutor de cada plugin sin esperar.
+    pub fn run_hook(&self, hook: Hook, args: &[serde_json::Value]) -> HookOutcome {
+        let mut outcome = HookOutcome::default();
         for plugin in &self.plugins {
-            match plugin._on_pre_upload(&data) {
-                Ok(modified_data) => {
-                    data = modified_data;
-                }
+            let reply = if hook.can_veto() {
+                plugin.call_hook(hook.name(), args)
+            } else {
+                plugin.spawn_hook(hook.name(), args).map(|_| None)
+            };
+            let reply = match reply {
+                Ok(Some(reply)) => reply,
+                Ok(None) => continue,
                 Err(e) => {
-                    eprintln!(
-                        "[Plugin Error] '{}' falló en on_pre_upload: {}",
-                        plugin.name,
-                        clean_lua_error(&e)
-                    );
+                    let error = clean_lua_error(&e);
+                    plugin.set_last_error(format!("{}: {}", hook.name(), error));
+                    outcome.errors.push((plugin.name.clone(), error));
+                    continue;
                 }
+            };
+            if reply.cancel {
+                outcome.cancelled_by = Some((plugin.name.clone(), reply.reason));
+                break;
             }
+            outcome.skip.extend(reply.skip);
         }
-        data
+        outcome
     }
 }
@@ -0,0 +1,178 @@
+//! Manifiesto `plugin.toml`: identidad del plugin y capacidades que pide.
+//!
+//! ```toml
+//! name = "notificador"
+//! version = "1.2.0"
+//! author = "Ana"
+//! description = "Avisa a un webhook al subir guardados"
+//!
+//! [capabilities]
+//! http = ["hooks.example.com", "*.example.org"]
+//! fs = ["~/Documents/notas"]
+//! ui_events = ["plugin_notificado"]
+//! db = true
+//! stdlib = ["os"]
+//! ```
+//!
+//! Lo que no aparece en `capabilities` no existe dentro del plugin ([`super::sandbox`]).
+//! Sin `plugin.toml` el plugin toma el nombre de su carpeta y no tiene capacidades:
+//! solo puede escribir en su log y responder a los hooks.
+
+use serde::{Deserialize, Serialize};
+use std::io::ErrorKind;
+use std::path::Path;
+
+pub const MANIFEST_FILE_NAME: &str = "plugin.toml";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PluginManifest {
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub capabilities: Capabilities,
+}
+
+/// Capacidades que un plugin pide o que el usuario le aprobó.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Capabilities {
+    /// Hosts a los que `savecloud.http` puede llamar; `*.dominio` incluye los subdominios.
+    pub http: Vec<String>,
+    /// Carpetas (admiten `~` y `%VAR%`) que `savecloud.fs` puede leer y escribir.
+    pub fs: Vec<String>,
+    /// Eventos que `savecloud.ui.emit` puede enviar al frontend.
+    pub ui_events: Vec<String>,
+    /// Acceso a `savecloud.db`.
+    pub db: bool,
+    /// Librerías estándar de Lua con acceso al sistema.
+    pub stdlib: Vec<StdLibGrant>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StdLibGrant {
+    Os,
+    Io,
+}
+
+impl PluginManifest {
+    /// Lee el manifiesto de la carpeta del plugin.
+    ///
+    /// # Errors
+    ///
+    /// Devuelve `Err` si `plugin.toml` existe pero no se puede leer o no es válido.
+    pub fn load(dir: &Path) -> Result<Self, String> {
+        match std::fs::read_to_string(dir.join(MANIFEST_FILE_NAME)) {
+            Ok(content) => Self::parse(&content),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::without_manifest(dir)),
+            Err(e) => Err(format!("{}: {}", MANIFEST_FILE_NAME, e)),
+        }
+    }
+
+    fn parse(content: &str) -> Result<Self, String> {
+        let manifest: Self = toml::from_str(content)
+            .map_err(|e| format!("{} no válido: {}", MANIFEST_FILE_NAME, e))?;
+        if manifest.name.trim().is_empty() {
+            return Err(format!("{}: `name` está vacío", MANIFEST_FILE_NAME));
+        }
+        Ok(manifest)
+    }
+
+    fn without_manifest(dir: &Path) -> Self {
+        Self {
+            name: dir
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            version: String::new(),
+            author: None,
+            description: None,
+            capabilities: Capabilities::default(),
+        }
+    }
+}
+
+impl Capabilities {
+    /// Sin capacidades no hay nada que aprobar.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Indica si lo aprobado (`self`) incluye todo lo que pide `requested`.
+    pub fn covers(&self, requested: &Capabilities) -> bool {
+        requested.http.iter().all(|h| self.http.contains(h))
+            && requested.fs.iter().all(|p| self.fs.contains(p))
+            && requested
+                .ui_events
+                .iter()
+                .all(|e| self.ui_events.contains(e))
+            && (!requested.db || self.db)
+            && requested.stdlib.iter().all(|l| self.stdlib.contains(l))
+    }
+
+    pub fn allows_host(&self, host: &str) -> bool {
+        self.http.iter().any(|pattern| host_matches(pattern, host))
+    }
+
+    pub fn allows_ui_event(&self, event: &str) -> bool {
+        self.ui_events.iter().any(|e| e == event)
+    }
+
+    pub fn has_stdlib(&self, lib: StdLibGrant) -> bool {
+        self.stdlib.contains(&lib)
+    }
+}
+
+/// `example.com` solo admite ese host; `*.example.com` admite sus subdominios.
+fn host_matches(pattern: &str, host: &str) -> bool {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    let pattern = pattern.trim().to_ascii_lowercase();
+    match pattern.strip_prefix("*.") {
+        Some(domain) => host
+            .strip_suffix(domain)
+            .is_some_and(|sub| sub.len() > 1 && sub.ends_with('.')),
+        None => host == pattern,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_manifest_and_checks_granted_capabilities() {
+        let manifest = PluginManifest::parse(
+            r#"
+            name = "notificador"
+            version = "1.0.0"
+
+            [capabilities]
+            http = ["hooks.example.com", "*.example.org"]
+            db = true
+            "#,
+        )
+        .unwrap();
+        let caps = &manifest.capabilities;
+        assert_eq!(manifest.author, None);
+        assert!(caps.allows_host("hooks.example.com"));
+        assert!(caps.allows_host("API.example.org"));
+        assert!(!caps.allows_host("example.org"));
+        assert!(!caps.allows_host("evilexample.org"));
+        assert!(!caps.allows_host("example.com"));
+
+        // Lo aprobado cubre el manifiesto hasta que este pide algo nuevo.
+        let mut requested = caps.clone();
+        assert!(caps.covers(&requested));
+        requested.stdlib.push(StdLibGrant::Io);
+        assert!(!caps.covers(&requested));
+        assert!(Capabilities::default().is_empty());
+
+        assert!(PluginManifest::parse("name = \"x\"\nversion = \"1\"\nrede = 1").is_err());
+        assert!(PluginManifest::parse("name = \" \"\nversion = \"1\"").is_err());
+    }
+}
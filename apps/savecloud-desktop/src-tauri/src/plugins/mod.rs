@@ -1,8 +1,16 @@
 pub mod api;
+pub mod hooks;
+pub mod lifecycle;
 pub mod log_buffer;
 pub mod manager;
+pub mod manifest;
+pub mod permissions;
 pub mod plugin;
 pub mod plugin_sdk;
+pub mod sandbox;
+pub mod storage;
+pub mod tasks;
+pub mod watcher;
 
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -0,0 +1,160 @@
+//! Aprobación de los permisos de los plugins.
+//!
+//! Un plugin cuyo `plugin.toml` pide capacidades ([`Capabilities`]) no se carga hasta
+//! que el usuario las aprueba. Lo aprobado se guarda en `plugin_grants` junto con la
+//! carpeta del plugin; si una versión nueva del plugin pide algo más, o el mismo nombre
+//! aparece en otra carpeta, vuelve a quedar pendiente. Al arrancar, la app emite
+//! `plugin_permissions_required` con los plugins pendientes.
+
+use super::lifecycle;
+use super::manifest::{Capabilities, PluginManifest};
+use super::AppPluginManager;
+use crate::commands::sync::queue::now_secs;
+use crate::sqlite::AppDb;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+/// Evento con la lista de plugins que esperan aprobación.
+pub const PERMISSIONS_REQUIRED_EVENT: &str = "plugin_permissions_required";
+
+/// Plugin pendiente de aprobación, tal como lo describe su manifiesto.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingPluginDto {
+    pub name: String,
+    pub version: String,
+    pub author: Option<String>,
+    pub description: Option<String>,
+    pub capabilities: Capabilities,
+}
+
+impl From<&PluginManifest> for PendingPluginDto {
+    fn from(manifest: &PluginManifest) -> Self {
+        Self {
+            name: manifest.name.clone(),
+            version: manifest.version.clone(),
+            author: manifest.author.clone(),
+            description: manifest.description.clone(),
+            capabilities: manifest.capabilities.clone(),
+        }
+    }
+}
+
+/// Capacidades aprobadas para `plugin` cargado desde `dir`; `None` si nunca se aprobó
+/// nada o se aprobó para un plugin de otra carpeta con el mismo nombre.
+pub fn granted(
+    conn: &Connection,
+    plugin: &str,
+    dir: &Path,
+) -> rusqlite::Result<Option<Capabilities>> {
+    let row: Option<(String, String)> = conn
+        .query_row(
+            "SELECT capabilities, dir FROM plugin_grants WHERE plugin = ?1",
+            params![plugin],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+    let Some((json, approved_dir)) = row else {
+        return Ok(None);
+    };
+    if approved_dir != dir_key(dir) {
+        return Ok(None);
+    }
+    // Un registro ilegible equivale a no tener nada aprobado.
+    Ok(serde_json::from_str(&json).ok())
+}
+
+pub fn grant(
+    conn: &Connection,
+    plugin: &str,
+    dir: &Path,
+    capabilities: &Capabilities,
+    now: i64,
+) -> rusqlite::Result<()> {
+    let json = serde_json::to_string(capabilities)
+        .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+    conn.execute(
+        "INSERT INTO plugin_grants (plugin, capabilities, approved_at, dir) VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(plugin) DO UPDATE SET capabilities = excluded.capabilities,
+                                           approved_at = excluded.approved_at,
+                                           dir = excluded.dir",
+        params![plugin, json, now, dir_key(dir)],
+    )?;
+    Ok(())
+}
+
+fn dir_key(dir: &Path) -> String {
+    dir.to_string_lossy().into_owned()
+}
+
+/// Plugins que esperan a que el usuario apruebe sus permisos.
+#[tauri::command]
+pub async fn list_pending_plugins(
+    manager: State<'_, AppPluginManager>,
+) -> Result<Vec<PendingPluginDto>, String> {
+    Ok(manager.lock().await.pending_dtos())
+}
+
+/// Aprueba los permisos que pide el plugin `name` y lo carga.
+#[tauri::command]
+pub async fn approve_plugin_permissions(
+    name: String,
+    app: AppHandle,
+    db: State<'_, AppDb>,
+    manager: State<'_, AppPluginManager>,
+) -> Result<(), String> {
+    let db = db.inner().clone();
+
+    lifecycle::with_manager(app, manager.inner().clone(), move |manager| {
+        let (dir, manifest) = manager
+            .pending
+            .iter()
+            .find(|p| p.manifest.name.eq_ignore_ascii_case(&name))
+            .map(|p| (p.dir.clone(), p.manifest.clone()))
+            .ok_or_else(|| format!("No hay permisos pendientes para el plugin '{}'", name))?;
+        db.with_conn(|c| grant(c, &manifest.name, &dir, &manifest.capabilities, now_secs()))
+            .map_err(|e| e.to_string())?;
+        manager.reload(&manifest.name)
+    })
+    .await
+}
+
+/// Descarta la petición de permisos del plugin `name`; se volverá a pedir en el
+/// próximo arranque.
+#[tauri::command]
+pub async fn reject_plugin_permissions(
+    name: String,
+    manager: State<'_, AppPluginManager>,
+) -> Result<(), String> {
+    manager
+        .lock()
+        .await
+        .take_pending(&name)
+        .map(|_| ())
+        .ok_or_else(|| format!("No hay permisos pendientes para el plugin '{}'", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sqlite::run_migrations;
+
+    #[test]
+    fn grants_stay_with_the_approved_folder() {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        let caps = Capabilities {
+            http: vec!["api.example.com".into()],
+            ..Default::default()
+        };
+        let original = Path::new("/data/plugins/notas");
+        grant(&conn, "notas", original, &caps, 1).unwrap();
+
+        assert_eq!(granted(&conn, "Notas", original).unwrap(), Some(caps));
+        let impostor = Path::new("/data/plugins/otro");
+        assert_eq!(granted(&conn, "notas", impostor).unwrap(), None);
+        assert_eq!(granted(&conn, "otro", original).unwrap(), None);
+    }
+}
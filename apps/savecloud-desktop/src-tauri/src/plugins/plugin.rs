@@ -2,19 +2,31 @@
 //!
 //! Contiene las funciones para:
 //!
-//! - Cargar el plugin desde un directorio.
-//! - Ejecutar el hook de inicialización.
-//! - Ejecutar el hook de pre-subida (Pipeline).
+//! - Cargar el plugin desde un directorio en una VM aislada ([`super::sandbox`]).
+//! - Ejecutar los hooks de inicialización y de descarga.
+//! - Ejecutar los hooks de ciclo de vida ([`super::hooks`]).
+//!
+//! Cada entrada al plugin pasa por su ejecutor ([`PluginTasks`]), con límite de
+//! instrucciones y de tiempo.
 
 use super::api::register_savecloud_api;
+use super::manifest::PluginManifest;
+use super::sandbox::{self, CALL_TIMEOUT, TASK_TIMEOUT};
+use super::tasks::PluginTasks;
 use crate::plugins::log_buffer::AppLogs;
-use mlua::{Function, Lua, Result};
-use std::path::Path;
+use mlua::{
+    ChunkMode, Function, Lua, LuaSerdeExt, MultiValue, Result, SerializeOptions, Table, Value,
+};
+use std::path::{Path, PathBuf};
 use tauri::AppHandle;
 
 pub struct Plugin {
     pub name: String,
+    pub manifest: PluginManifest,
+    /// Carpeta de la que se cargó.
+    pub dir: PathBuf,
     lua: Lua,
+    tasks: PluginTasks,
 }
 
 pub fn clean_lua_error(err: &mlua::Error) -> String {
@@ -25,20 +37,67 @@ pub fn clean_lua_error(err: &mlua::Error) -> String {
     }
 }
 
-impl Plugin {
-    pub fn load_from_dir(dir_path: &Path, app_handle: AppHandle, logs: AppLogs) -> Result<Self> {
-        let lua = Lua::new();
+/// Respuesta de un plugin a un hook.
+///
+/// - `nil` o `true`: seguir.
+/// - `false`: cancelar la operación.
+/// - `{ cancel = true, reason = "..." }`: cancelar explicando el motivo.
+/// - `{ skip = { "ruta/relativa", ... } }`: dejar esos archivos fuera.
+#[derive(Debug, Default, PartialEq)]
+pub struct HookReply {
+    pub cancel: bool,
+    pub reason: Option<String>,
+    pub skip: Vec<String>,
+}
 
-        let name = dir_path.file_name().unwrap().to_string_lossy().to_string();
+impl HookReply {
+    fn from_lua(value: Value) -> Result<Self> {
+        match value {
+            Value::Nil | Value::Boolean(true) => Ok(Self::default()),
+            Value::Boolean(false) => Ok(Self {
+                cancel: true,
+                ..Self::default()
+            }),
+            Value::Table(table) => Self::from_table(&table),
+            otro => Err(mlua::Error::RuntimeError(format!(
+                "respuesta no válida: se esperaba nil, boolean o tabla y llegó {}",
+                otro.type_name()
+            ))),
+        }
+    }
 
-        register_savecloud_api(&lua, app_handle, logs, name.clone())?;
+    fn from_table(table: &Table) -> Result<Self> {
+        Ok(Self {
+            cancel: table.get::<Option<bool>>("cancel")?.unwrap_or(false),
+            reason: table.get("reason")?,
+            skip: table
+                .get::<Option<Vec<String>>>("skip")?
+                .unwrap_or_default(),
+        })
+    }
+}
 
-        let folder_str = dir_path.to_string_lossy().replace('\\', "/");
-        let setup_script = format!(
-            "package.path = package.path .. ';{}/?.lua;{}/?/init.lua'",
-            folder_str, folder_str
-        );
-        lua.load(&setup_script).exec()?;
+impl Plugin {
+    /// Carga el plugin en una VM aislada con las capacidades de `manifest`, que el
+    /// usuario ya aprobó ([`super::permissions`]).
+    pub fn load_from_dir(
+        dir_path: &Path,
+        manifest: PluginManifest,
+        app_handle: AppHandle,
+        logs: AppLogs,
+    ) -> Result<Self> {
+        let (lua, budget) = sandbox::new_vm(dir_path, &manifest.capabilities)?;
+        let name = manifest.name.clone();
+        let tasks = PluginTasks::new(name.clone(), budget, logs.clone(), app_handle.clone());
+
+        register_savecloud_api(
+            &lua,
+            app_handle,
+            logs,
+            name.clone(),
+            &manifest.capabilities,
+            &tasks,
+        )?;
 
         let init_path = dir_path.join("init.lua");
         if !init_path.exists() {
@@ -48,33 +107,133 @@ impl Plugin {
             )));
         }
 
-        let script = std::fs::read_to_string(&init_path)?;
-        lua.load(&script).exec()?;
+        let plugin = Self {
+            name,
+            manifest,
+            dir: dir_path.to_path_buf(),
+            lua,
+            tasks,
+        };
 
-        Ok(Self { name, lua })
+        let script = std::fs::read_to_string(&init_path)?;
+        let chunk = plugin
+            .lua
+            .load(&script)
+            .set_name("init.lua")
+            .set_mode(ChunkMode::Text)
+            .into_function()?;
+        // Si falla, `plugin` se descarta y cancela los timers que ya hubiera creado.
+        plugin.tasks.call_blocking::<()>(&chunk, (), CALL_TIMEOUT)?;
+
+        Ok(plugin)
     }
 
     pub fn trigger_on_init(&self) -> Result<()> {
-        let globals = self.lua.globals();
+        self.call_global("on_init")
+    }
+
+    /// Llama a `on_unload` antes de descargar el plugin (al desactivarlo, recargarlo,
+    /// borrar su carpeta o cerrar la app).
+    pub fn trigger_on_unload(&self) -> Result<()> {
+        self.call_global("on_unload")
+    }
+
+    /// Último error de `on_init`, de un hook o de una tarea, para la lista de plugins.
+    pub fn last_error(&self) -> Option<String> {
+        self.tasks.last_error()
+    }
+
+    pub fn set_last_error(&self, error: String) {
+        self.tasks.set_last_error(error);
+    }
 
-        if let Ok(func) = globals.get::<Function>("on_init") {
-            func.call::<()>(())
-                .map_err(|err| mlua::Error::RuntimeError(clean_lua_error(&err)))?;
+    fn call_global(&self, name: &str) -> Result<()> {
+        if let Ok(func) = self.lua.globals().get::<Function>(name) {
+            self.tasks.call_blocking::<()>(&func, (), CALL_TIMEOUT)?;
         }
 
         Ok(())
     }
 
-    pub fn _on_pre_upload(&self, data: &[u8]) -> Result<Vec<u8>> {
-        let globals = self.lua.globals();
+    /// Llama al hook `name` con `args` convertidos a tablas Lua y espera su respuesta.
+    /// Devuelve `None` si el plugin no lo define.
+    pub fn call_hook(&self, name: &str, args: &[serde_json::Value]) -> Result<Option<HookReply>> {
+        let Ok(func) = self.lua.globals().get::<Function>(name) else {
+            return Ok(None);
+        };
+
+        let args = self.hook_args(args)?;
+        let reply = self
+            .tasks
+            .call_blocking::<Value>(&func, args, CALL_TIMEOUT)?;
+        HookReply::from_lua(reply).map(Some)
+    }
 
-        if let Ok(func) = globals.get::<Function>("on_pre_upload") {
-            let modified_data: Vec<u8> = func
-                .call::<Vec<u8>>(data)
-                .map_err(|err| mlua::Error::RuntimeError(clean_lua_error(&err)))?;
-            return Ok(modified_data);
-        }
+    /// Lanza el hook `name` en segundo plano, sin esperar su respuesta. Devuelve `false`
+    /// si el plugin no lo define.
+    ///
+    /// # Errors
+    ///
+    /// Devuelve `Err` si los argumentos no se pueden convertir o el ejecutor del plugin
+    /// está lleno; los errores del propio hook van al log del plugin.
+    pub fn spawn_hook(&self, name: &str, args: &[serde_json::Value]) -> Result<bool> {
+        let Ok(func) = self.lua.globals().get::<Function>(name) else {
+            return Ok(false);
+        };
+
+        let args = self.hook_args(args)?;
+        let tasks = self.tasks.clone();
+        self.tasks
+            .spawn(name, async move {
+                tasks.call::<()>(&func, args, TASK_TIMEOUT).await
+            })
+            .map_err(mlua::Error::RuntimeError)?;
+        Ok(true)
+    }
+
+    fn hook_args(&self, args: &[serde_json::Value]) -> Result<MultiValue> {
+        // Los campos ausentes llegan como `nil` y no como `savecloud.null`.
+        let options = SerializeOptions::new()
+            .serialize_none_to_null(false)
+            .serialize_unit_to_null(false);
+        args.iter()
+            .map(|arg| self.lua.to_value_with(arg, options))
+            .collect::<Result<Vec<_>>>()
+            .map(MultiValue::from_vec)
+    }
+}
+
+impl Drop for Plugin {
+    /// Un plugin descargado no deja timers ni tareas vivas.
+    fn drop(&mut self) {
+        self.tasks.shutdown();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reply(lua: &Lua, chunk: &str) -> Result<HookReply> {
+        HookReply::from_lua(lua.load(chunk).eval::<Value>()?)
+    }
+
+    #[test]
+    fn hook_replies_cancel_or_skip_files() {
+        let lua = Lua::new();
+
+        assert_eq!(reply(&lua, "nil").unwrap(), HookReply::default());
+        assert_eq!(reply(&lua, "true").unwrap(), HookReply::default());
+        assert!(reply(&lua, "false").unwrap().cancel);
+
+        let cancel = reply(&lua, "{ cancel = true, reason = 'sin red' }").unwrap();
+        assert!(cancel.cancel);
+        assert_eq!(cancel.reason.as_deref(), Some("sin red"));
+
+        let skip = reply(&lua, "{ skip = { 'a.sav', 'dir/b.sav' } }").unwrap();
+        assert!(!skip.cancel);
+        assert_eq!(skip.skip, ["a.sav", "dir/b.sav"]);
 
-        Ok(data.to_vec())
+        assert!(reply(&lua, "42").is_err());
     }
 }
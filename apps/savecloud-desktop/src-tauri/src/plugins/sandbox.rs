@@ -0,0 +1,227 @@
+//! Máquina Lua aislada de cada plugin.
+//!
+//! - Solo se cargan las librerías sin acceso al sistema (`table`, `string`, `math`,
+//!   `bit`, `package`); `os` e `io` únicamente si el manifiesto las pide y el usuario
+//!   las aprobó. Sin `dofile`/`loadfile`, sin bytecode (`string.dump`, `load` en modo
+//!   binario) y sin módulos C: `require` solo busca archivos `.lua` del plugin.
+//! - Límite de memoria por plugin y de instrucciones por llamada ([`InstructionBudget`]).
+//!   El JIT de LuaJIT se apaga porque el código compilado no pasa por el contador.
+//! - Límite de tiempo por llamada ([`CALL_TIMEOUT`], [`TASK_TIMEOUT`]): una llamada que
+//!   espera (HTTP, `savecloud.timer.sleep`) no cuenta instrucciones mientras espera.
+//! - `savecloud.fs` solo ve las carpetas aprobadas ([`FsScope`]).
+
+use super::manifest::{Capabilities, StdLibGrant};
+use mlua::{HookTriggers, Lua, LuaOptions, Result, StdLib, VmState};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Memoria máxima de la VM de un plugin.
+pub const MEMORY_LIMIT_BYTES: usize = 64 * 1024 * 1024;
+
+/// Instrucciones Lua por llamada al plugin (ejecutar `init.lua`, `on_init` o un hook).
+pub const INSTRUCTION_LIMIT: u64 = 100_000_000;
+
+/// Cada cuántas instrucciones se revisa el límite.
+const INSTRUCTION_STEP: u32 = 10_000;
+
+/// Tiempo máximo de una llamada que la app espera: `init.lua`, `on_init`, `on_unload` y
+/// los hooks que pueden cancelar.
+pub const CALL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Tiempo máximo de una tarea en segundo plano: timers y hooks que no pueden cancelar.
+pub const TASK_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Crea la VM de un plugin con las librerías aprobadas y `require` limitado a `dir`.
+pub fn new_vm(dir: &Path, capabilities: &Capabilities) -> Result<(Lua, InstructionBudget)> {
+    let mut libs =
+        StdLib::TABLE | StdLib::STRING | StdLib::MATH | StdLib::BIT | StdLib::PACKAGE | StdLib::JIT;
+    if capabilities.has_stdlib(StdLibGrant::Os) {
+        libs |= StdLib::OS;
+    }
+    if capabilities.has_stdlib(StdLibGrant::Io) {
+        libs |= StdLib::IO;
+    }
+
+    let lua = Lua::new_with(libs, LuaOptions::new())?;
+    lua.set_memory_limit(MEMORY_LIMIT_BYTES)?;
+
+    let folder = dir.to_string_lossy().replace('\\', "/");
+    lua.load(
+        r#"
+        local folder = ...
+        jit.off()
+        jit = nil
+        package.loaded.jit = nil
+
+        dofile = nil
+        loadfile = nil
+        string.dump = nil
+        local load_text = load
+        load = function(chunk, name, _, env)
+            return load_text(chunk, name, "t", env)
+        end
+        loadstring = function(chunk, name)
+            return load_text(chunk, name, "t")
+        end
+
+        package.path = folder .. "/?.lua;" .. folder .. "/?/init.lua"
+        "#,
+    )
+    .call::<()>(folder)?;
+
+    let budget = InstructionBudget::install(&lua)?;
+    Ok((lua, budget))
+}
+
+/// Contador de instrucciones de la llamada en curso; [`InstructionBudget::reset`] antes
+/// de cada entrada al plugin.
+#[derive(Clone)]
+pub struct InstructionBudget(Arc<AtomicU64>);
+
+impl InstructionBudget {
+    fn install(lua: &Lua) -> Result<Self> {
+        let steps = Arc::new(AtomicU64::new(0));
+        let counter = steps.clone();
+        let max_steps = INSTRUCTION_LIMIT / u64::from(INSTRUCTION_STEP);
+        // Global: también cuenta en las corrutinas de las llamadas async y en las que
+        // cree el plugin.
+        lua.set_global_hook(
+            HookTriggers::new().every_nth_instruction(INSTRUCTION_STEP),
+            move |_, _| {
+                if counter.fetch_add(1, Ordering::Relaxed) >= max_steps {
+                    return Err(mlua::Error::RuntimeError(format!(
+                        "el plugin superó el límite de {} instrucciones por llamada",
+                        INSTRUCTION_LIMIT
+                    )));
+                }
+                Ok(VmState::Continue)
+            },
+        )?;
+        Ok(Self(steps))
+    }
+
+    pub fn reset(&self) {
+        self.0.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Carpetas que un plugin puede leer y escribir.
+pub struct FsScope {
+    roots: Vec<PathBuf>,
+}
+
+impl FsScope {
+    pub fn new(capabilities: &Capabilities) -> Self {
+        let roots = capabilities
+            .fs
+            .iter()
+            .filter_map(|raw| crate::utils::path_utils::expand_path(raw))
+            .map(|root| real_path(Path::new(&root)))
+            .collect();
+        Self { roots }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.roots.is_empty()
+    }
+
+    /// Ruta real de `path` si cae dentro de una carpeta aprobada. Se resuelven los
+    /// enlaces simbólicos para que no sirvan de salida.
+    ///
+    /// # Errors
+    ///
+    /// Devuelve `Err` si la ruta es relativa, contiene `..` o queda fuera del permiso.
+    pub fn resolve(&self, path: &str) -> std::result::Result<PathBuf, String> {
+        let path = Path::new(path);
+        if !path.is_absolute() || path.components().any(|c| c == Component::ParentDir) {
+            return Err(format!("ruta no permitida: {}", path.display()));
+        }
+        let real = real_path(path);
+        if self.roots.iter().any(|root| real.starts_with(root)) {
+            Ok(real)
+        } else {
+            Err(format!(
+                "ruta fuera de las carpetas de plugin.toml: {}",
+                path.display()
+            ))
+        }
+    }
+}
+
+/// Resuelve enlaces de la parte de `path` que ya existe (un archivo nuevo aún no existe).
+fn real_path(path: &Path) -> PathBuf {
+    if let Ok(real) = std::fs::canonicalize(path) {
+        return real;
+    }
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => real_path(parent).join(name),
+        _ => path.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vm_without_grants_has_no_system_access_and_stops_runaway_loops() {
+        let dir = tempfile::tempdir().unwrap();
+        let (lua, budget) = new_vm(dir.path(), &Capabilities::default()).unwrap();
+
+        let missing: Vec<bool> = lua
+            .load("return { io == nil, os == nil, dofile == nil, string.dump == nil }")
+            .eval()
+            .unwrap();
+        assert!(missing.into_iter().all(|m| m));
+
+        budget.reset();
+        assert!(lua.load("while true do end").exec().is_err());
+        budget.reset();
+        assert_eq!(lua.load("return 1 + 1").eval::<i64>().unwrap(), 2);
+
+        let granted = Capabilities {
+            stdlib: vec![StdLibGrant::Os],
+            ..Capabilities::default()
+        };
+        let (lua, _) = new_vm(dir.path(), &granted).unwrap();
+        assert!(lua.load("return os.time()").eval::<i64>().is_ok());
+    }
+
+    #[test]
+    fn instruction_limit_also_stops_coroutines_and_async_calls() {
+        let dir = tempfile::tempdir().unwrap();
+        let (lua, budget) = new_vm(dir.path(), &Capabilities::default()).unwrap();
+
+        budget.reset();
+        let nested = "coroutine.wrap(function() while true do end end)()";
+        assert!(lua.load(nested).exec().is_err());
+
+        budget.reset();
+        let runaway = lua.load("while true do end").into_function().unwrap();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        assert!(runtime.block_on(runaway.call_async::<()>(())).is_err());
+    }
+
+    #[test]
+    fn fs_scope_only_resolves_paths_inside_granted_folders() {
+        let root = tempfile::tempdir().unwrap();
+        let granted = root.path().join("notas");
+        std::fs::create_dir(&granted).unwrap();
+        let scope = FsScope::new(&Capabilities {
+            fs: vec![granted.to_string_lossy().into_owned()],
+            ..Capabilities::default()
+        });
+
+        let inside = granted.join("nuevo.txt");
+        assert!(scope.resolve(&inside.to_string_lossy()).is_ok());
+        let escape = granted.join("..").join("otro.txt");
+        assert!(scope.resolve(&escape.to_string_lossy()).is_err());
+        let sibling = root.path().join("otro.txt");
+        assert!(scope.resolve(&sibling.to_string_lossy()).is_err());
+        assert!(scope.resolve("notas/nuevo.txt").is_err());
+    }
+}
@@ -0,0 +1,349 @@
+//! Almacenamiento de los plugins en [`AppDb`](crate::sqlite::AppDb) (capacidad `db`).
+//!
+//! - Clave-valor (`plugin_kv`): cada plugin solo ve sus propias claves; los valores se
+//!   guardan como JSON.
+//! - Tablas propias: el plugin crea y consulta tablas cuyo nombre empieza por su prefijo
+//!   ([`table_prefix`]). Mientras corre su SQL, un autorizador de SQLite rechaza
+//!   cualquier otra tabla, `ATTACH`, `PRAGMA`, triggers, vistas y transacciones, y un
+//!   límite de pasos corta las consultas que no terminan.
+//! - Consultas de solo lectura sobre los juegos, el historial de operaciones, el tiempo
+//!   de juego y el estado de la sincronización.
+
+use crate::commands::sync::queue::store::{self as queue_store, SyncJobDto};
+use crate::config::OperationLogEntryDto;
+use crate::transfers::manager::TransferSnapshot;
+use rusqlite::hooks::{AuthAction, AuthContext, Authorization};
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use serde::Serialize;
+
+/// Longitud máxima de una clave.
+pub const MAX_KEY_BYTES: usize = 256;
+
+/// Tamaño máximo de un valor ya serializado a JSON.
+pub const MAX_VALUE_BYTES: usize = 1024 * 1024;
+
+/// Filas máximas que devuelve una consulta.
+pub const MAX_ROWS: usize = 10_000;
+
+/// Cada cuántas instrucciones de SQLite se revisa el límite de pasos.
+const PROGRESS_STEP: i32 = 1_000;
+
+/// Revisiones tras las que se interrumpe una sentencia (unos 10 millones de instrucciones).
+const MAX_PROGRESS_CHECKS: u32 = 10_000;
+
+pub fn kv_get(conn: &Connection, plugin: &str, key: &str) -> rusqlite::Result<Option<String>> {
+    conn.query_row(
+        "SELECT value FROM plugin_kv WHERE plugin = ?1 AND key = ?2",
+        params![plugin, key],
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+pub fn kv_set(
+    conn: &Connection,
+    plugin: &str,
+    key: &str,
+    json: &str,
+    now: i64,
+) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO plugin_kv (plugin, key, value, updated_at) VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(plugin, key) DO UPDATE SET value = excluded.value,
+                                                updated_at = excluded.updated_at",
+        params![plugin, key, json, now],
+    )?;
+    Ok(())
+}
+
+/// Borra la clave; devuelve si existía.
+pub fn kv_delete(conn: &Connection, plugin: &str, key: &str) -> rusqlite::Result<bool> {
+    let deleted = conn.execute(
+        "DELETE FROM plugin_kv WHERE plugin = ?1 AND key = ?2",
+        params![plugin, key],
+    )?;
+    Ok(deleted > 0)
+}
+
+/// Claves del plugin que empiezan por `prefix`, en orden alfabético.
+pub fn kv_list(conn: &Connection, plugin: &str, prefix: &str) -> rusqlite::Result<Vec<String>> {
+    let mut stmt = conn.prepare(
+        "SELECT key FROM plugin_kv WHERE plugin = ?1 AND substr(key, 1, length(?2)) = ?2 \
+         ORDER BY key",
+    )?;
+    let rows = stmt.query_map(params![plugin, prefix], |row| row.get(0))?;
+    rows.collect()
+}
+
+/// Prefijo de las tablas del plugin: `plugin_` + su nombre en minúsculas + `__`. Lo que
+/// no es letra o dígito ASCII se escribe como `_` y su código en hexadecimal; como tras
+/// un `_` siempre vienen dos dígitos hexadecimales, `__` solo aparece al final y ningún
+/// prefijo puede ser el comienzo de otro.
+pub fn table_prefix(plugin: &str) -> String {
+    let mut prefix = String::from("plugin_");
+    for byte in plugin.to_ascii_lowercase().bytes() {
+        if byte.is_ascii_alphanumeric() {
+            prefix.push(byte as char);
+        } else {
+            prefix.push_str(&format!("_{:02x}", byte));
+        }
+    }
+    prefix.push_str("__");
+    prefix
+}
+
+/// Resultado de una consulta del plugin.
+pub struct SqlRows {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<SqlValue>>,
+}
+
+/// Ejecuta una consulta del plugin limitada a sus tablas.
+///
+/// # Errors
+///
+/// Devuelve `Err` si la sentencia no es válida, toca algo fuera de las tablas del
+/// plugin, supera el límite de pasos o devuelve más de [`MAX_ROWS`] filas.
+pub fn query(
+    conn: &Connection,
+    prefix: &str,
+    sql: &str,
+    params: Vec<SqlValue>,
+) -> rusqlite::Result<SqlRows> {
+    scoped(conn, prefix, || {
+        let mut stmt = conn.prepare(sql)?;
+        let columns: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+        let mut rows = stmt.query(params_from_iter(params))?;
+        let mut out = Vec::new();
+        while let Some(row) = rows.next()? {
+            if out.len() == MAX_ROWS {
+                return Err(rusqlite::Error::ToSqlConversionFailure(
+                    format!("la consulta devuelve más de {} filas", MAX_ROWS).into(),
+                ));
+            }
+            let values = (0..columns.len())
+                .map(|i| row.get::<_, SqlValue>(i))
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            out.push(values);
+        }
+        Ok(SqlRows { columns, rows: out })
+    })
+}
+
+/// Ejecuta una sentencia del plugin limitada a sus tablas; devuelve las filas cambiadas.
+///
+/// # Errors
+///
+/// Igual que [`query`].
+pub fn execute(
+    conn: &Connection,
+    prefix: &str,
+    sql: &str,
+    params: Vec<SqlValue>,
+) -> rusqlite::Result<usize> {
+    scoped(conn, prefix, || {
+        conn.prepare(sql)?.execute(params_from_iter(params))
+    })
+}
+
+/// Corre `f` con el autorizador y el límite de pasos del plugin y los retira después,
+/// aunque falle. El llamador tiene la conexión bloqueada, así que no afectan a nadie más.
+fn scoped<T>(
+    conn: &Connection,
+    prefix: &str,
+    f: impl FnOnce() -> rusqlite::Result<T>,
+) -> rusqlite::Result<T> {
+    let owned = prefix.to_string();
+    conn.authorizer(Some(move |ctx: AuthContext<'_>| authorize(&owned, ctx)))?;
+    let mut checks = 0u32;
+    conn.progress_handler(
+        PROGRESS_STEP,
+        Some(move || {
+            checks += 1;
+            checks > MAX_PROGRESS_CHECKS
+        }),
+    )?;
+
+    let result = f();
+
+    conn.authorizer(None::<fn(AuthContext<'_>) -> Authorization>)?;
+    conn.progress_handler(0, None::<fn() -> bool>)?;
+    result
+}
+
+fn authorize(prefix: &str, ctx: AuthContext<'_>) -> Authorization {
+    // Solo la base principal: nada de `temp` ni bases adjuntas.
+    if ctx.database_name.is_some_and(|db| db != "main") {
+        return Authorization::Deny;
+    }
+    let owns = |name: &str| name.len() > prefix.len() && name.starts_with(prefix);
+    let allowed = match ctx.action {
+        AuthAction::Select | AuthAction::Recursive | AuthAction::Function { .. } => true,
+        // Las CTE (`WITH ...`) se leen sin base de datos.
+        AuthAction::Read { .. } if ctx.database_name.is_none() => true,
+        AuthAction::CreateTable { table_name } | AuthAction::DropTable { table_name } => {
+            owns(table_name)
+        }
+        // Crear o borrar una tabla o un índice lee y escribe el esquema; SQLite ya impide
+        // modificarlo directamente y el esquema de la app no es secreto.
+        AuthAction::Read { table_name, .. }
+        | AuthAction::Insert { table_name }
+        | AuthAction::Update { table_name, .. }
+        | AuthAction::Delete { table_name } => owns(table_name) || table_name == "sqlite_master",
+        AuthAction::CreateIndex {
+            index_name,
+            table_name,
+        }
+        | AuthAction::DropIndex {
+            index_name,
+            table_name,
+        } => owns(index_name) && owns(table_name),
+        AuthAction::Reindex { index_name } => owns(index_name),
+        _ => false,
+    };
+    if allowed {
+        Authorization::Allow
+    } else {
+        Authorization::Deny
+    }
+}
+
+/// Juego configurado, tal como lo ven los plugins.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginGameDto {
+    pub id: String,
+    pub paths: Vec<String>,
+    pub steam_app_id: Option<String>,
+    pub install_dir: Option<String>,
+}
+
+pub fn games() -> Vec<PluginGameDto> {
+    crate::config::load_config()
+        .games
+        .into_iter()
+        .map(|g| PluginGameDto {
+            id: g.id,
+            paths: g.paths,
+            steam_app_id: g.steam_app_id,
+            install_dir: g.install_dir,
+        })
+        .collect()
+}
+
+/// Las `limit` operaciones más recientes del historial, de la más nueva a la más antigua.
+pub fn history(limit: usize) -> Vec<OperationLogEntryDto> {
+    let mut entries = crate::config::config_cmds::list_operation_history();
+    entries.reverse();
+    entries.truncate(limit);
+    entries
+}
+
+/// Cola de sincronización y transferencias en curso.
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncStatusDto {
+    pub jobs: Vec<SyncJobDto>,
+    pub transfers: Vec<TransferSnapshot>,
+}
+
+pub fn sync_status(conn: &Connection) -> rusqlite::Result<SyncStatusDto> {
+    Ok(SyncStatusDto {
+        jobs: queue_store::list(conn)?,
+        transfers: crate::transfers::list_transfers(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sqlite::run_migrations;
+
+    #[test]
+    fn plugins_only_reach_their_own_keys_and_tables() {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+
+        kv_set(&conn, "alfa", "ultimo", "\"Hades\"", 1).unwrap();
+        kv_set(&conn, "alfa", "conteo", "3", 1).unwrap();
+        assert_eq!(
+            kv_get(&conn, "ALFA", "ultimo").unwrap().as_deref(),
+            Some("\"Hades\"")
+        );
+        assert_eq!(kv_get(&conn, "beta", "ultimo").unwrap(), None);
+        assert_eq!(kv_list(&conn, "alfa", "ul").unwrap(), vec!["ultimo"]);
+        assert!(kv_delete(&conn, "alfa", "ultimo").unwrap());
+        assert!(!kv_delete(&conn, "alfa", "ultimo").unwrap());
+
+        let prefix = table_prefix("mi-plugin");
+        assert_eq!(prefix, "plugin_mi_2dplugin__");
+        let table = format!("{}partidas", prefix);
+        let run = |sql: &str| execute(&conn, &prefix, sql, Vec::new());
+        run(&format!(
+            "CREATE TABLE {} (juego TEXT, minutos INTEGER)",
+            table
+        ))
+        .unwrap();
+        run(&format!("CREATE INDEX {}idx ON {} (juego)", prefix, table)).unwrap();
+        let inserted = execute(
+            &conn,
+            &prefix,
+            &format!("INSERT INTO {} VALUES (?1, ?2)", table),
+            vec![SqlValue::Text("Hades".into()), SqlValue::Integer(30)],
+        )
+        .unwrap();
+        assert_eq!(inserted, 1);
+        let rows = query(
+            &conn,
+            &prefix,
+            &format!(
+                "SELECT juego, sum(minutos) AS total FROM {} GROUP BY juego",
+                table
+            ),
+            Vec::new(),
+        )
+        .unwrap();
+        assert_eq!(rows.columns, vec!["juego", "total"]);
+        assert_eq!(rows.rows[0][1], SqlValue::Integer(30));
+
+        // Nada fuera de su prefijo, ni otras bases, ni cambios de nombre, ni bucles sin fin.
+        assert!(query(&conn, &prefix, "SELECT * FROM plugin_kv", Vec::new()).is_err());
+        assert!(run("CREATE TABLE sync_jobs_copia (x)").is_err());
+        assert!(run("DELETE FROM play_sessions").is_err());
+        assert!(run("ATTACH DATABASE ':memory:' AS otra").is_err());
+        assert!(run("PRAGMA user_version = 0").is_err());
+        assert!(run(&format!("ALTER TABLE {} RENAME TO juegos", table)).is_err());
+        assert!(query(
+            &conn,
+            &prefix,
+            "WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n) \
+             SELECT count(*) FROM n",
+            Vec::new(),
+        )
+        .is_err());
+
+        // Un plugin cuyo nombre empieza igual tampoco llega a sus tablas.
+        let ajeno = table_prefix("a-b");
+        execute(
+            &conn,
+            &ajeno,
+            &format!("CREATE TABLE {}datos (x)", ajeno),
+            Vec::new(),
+        )
+        .unwrap();
+        let corto = table_prefix("a");
+        assert!(!ajeno.starts_with(&corto));
+        let leer = format!("SELECT * FROM {}datos", ajeno);
+        assert!(query(&conn, &corto, &leer, Vec::new()).is_err());
+        for sql in [
+            format!("INSERT INTO {}datos VALUES (1)", ajeno),
+            format!("DROP TABLE {}datos", ajeno),
+        ] {
+            assert!(execute(&conn, &corto, &sql, Vec::new()).is_err());
+        }
+
+        // El autorizador se retira al terminar: el resto de la app sigue igual.
+        assert_eq!(kv_list(&conn, "alfa", "").unwrap(), vec!["conteo"]);
+        run(&format!("DROP TABLE {}", table)).unwrap();
+    }
+}
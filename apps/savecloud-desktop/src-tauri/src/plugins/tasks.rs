@@ -0,0 +1,160 @@
+//! Ejecutor de tareas de cada plugin.
+//!
+//! Todas las entradas a un plugin (`init.lua`, `on_init`, hooks, timers) se ejecutan como
+//! llamadas async de `mlua` sobre el runtime de Tokio: las funciones async de la API
+//! (`savecloud.http.*`, `savecloud.timer.sleep`) suspenden la corrutina del plugin en vez
+//! de bloquear un hilo. Cada llamada tiene su límite de instrucciones
+//! ([`InstructionBudget`]) y de tiempo ([`super::sandbox::CALL_TIMEOUT`]).
+//!
+//! Las tareas en segundo plano (timers y hooks que no pueden cancelar) viven en el
+//! [`PluginTasks`] del plugin: se limitan a [`MAX_TASKS`] a la vez y se cancelan todas
+//! al descargarlo. Sus errores se anotan en el log del plugin.
+
+use super::log_buffer::{self, AppLogs};
+use super::plugin::clean_lua_error;
+use super::sandbox::InstructionBudget;
+use mlua::{FromLuaMulti, Function, IntoLuaMulti};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use tauri::async_runtime::JoinHandle;
+use tauri::AppHandle;
+
+/// Tareas en segundo plano de un plugin a la vez.
+pub const MAX_TASKS: usize = 64;
+
+/// Tareas de un plugin; [`Clone`] comparte el mismo ejecutor.
+#[derive(Clone)]
+pub struct PluginTasks(Arc<Inner>);
+
+struct Inner {
+    plugin: String,
+    budget: InstructionBudget,
+    logs: AppLogs,
+    app: AppHandle,
+    next_id: AtomicU64,
+    /// Tareas en curso; `None` tras [`PluginTasks::shutdown`].
+    running: Mutex<Option<HashMap<u64, JoinHandle<()>>>>,
+    /// Último error de una llamada al plugin, para la lista de plugins.
+    last_error: Mutex<Option<String>>,
+}
+
+impl PluginTasks {
+    pub fn new(plugin: String, budget: InstructionBudget, logs: AppLogs, app: AppHandle) -> Self {
+        Self(Arc::new(Inner {
+            plugin,
+            budget,
+            logs,
+            app,
+            next_id: AtomicU64::new(1),
+            running: Mutex::new(Some(HashMap::new())),
+            last_error: Mutex::new(None),
+        }))
+    }
+
+    /// Llama a `func` con el límite de instrucciones y, como mucho, durante `limit`.
+    pub async fn call<R: FromLuaMulti>(
+        &self,
+        func: &Function,
+        args: impl IntoLuaMulti,
+        limit: Duration,
+    ) -> mlua::Result<R> {
+        self.0.budget.reset();
+        match tokio::time::timeout(limit, func.call_async::<R>(args)).await {
+            Ok(result) => result.map_err(|err| mlua::Error::RuntimeError(clean_lua_error(&err))),
+            Err(_) => Err(mlua::Error::RuntimeError(format!(
+                "el plugin superó el límite de {} s por llamada",
+                limit.as_secs()
+            ))),
+        }
+    }
+
+    /// Como [`PluginTasks::call`], desde hilos fuera del runtime async.
+    pub fn call_blocking<R: FromLuaMulti>(
+        &self,
+        func: &Function,
+        args: impl IntoLuaMulti,
+        limit: Duration,
+    ) -> mlua::Result<R> {
+        tauri::async_runtime::block_on(self.call(func, args, limit))
+    }
+
+    /// Lanza `task` en segundo plano y devuelve su id. Si falla, el error se anota en el
+    /// log del plugin con `label` delante.
+    ///
+    /// # Errors
+    ///
+    /// Devuelve `Err` si el plugin ya tiene [`MAX_TASKS`] tareas o se está descargando.
+    pub fn spawn(
+        &self,
+        label: &str,
+        task: impl Future<Output = mlua::Result<()>> + Send + 'static,
+    ) -> Result<u64, String> {
+        let mut running = self.running();
+        let Some(tasks) = running.as_mut() else {
+            return Err("el plugin se está descargando".to_string());
+        };
+        if tasks.len() >= MAX_TASKS {
+            return Err(format!("el plugin ya tiene {} tareas en curso", MAX_TASKS));
+        }
+
+        let id = self.0.next_id.fetch_add(1, Ordering::Relaxed);
+        let this = self.clone();
+        let label = label.to_string();
+        // La tarea se quita a sí misma al terminar; el cerrojo sigue tomado hasta que
+        // se registra, así que no puede adelantarse.
+        let handle = tauri::async_runtime::spawn(async move {
+            if let Err(err) = task.await {
+                this.fail(&format!("{}: {}", label, clean_lua_error(&err)))
+                    .await;
+            }
+            if let Some(tasks) = this.running().as_mut() {
+                tasks.remove(&id);
+            }
+        });
+        tasks.insert(id, handle);
+        Ok(id)
+    }
+
+    /// Cancela la tarea `id`; `false` si ya terminó o no existe.
+    pub fn cancel(&self, id: u64) -> bool {
+        match self.running().as_mut().and_then(|tasks| tasks.remove(&id)) {
+            Some(handle) => {
+                handle.abort();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Cancela todas las tareas y no admite más.
+    pub fn shutdown(&self) {
+        let tasks = self.running().take();
+        for handle in tasks.into_iter().flat_map(HashMap::into_values) {
+            handle.abort();
+        }
+    }
+
+    pub fn last_error(&self) -> Option<String> {
+        self.0
+            .last_error
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    pub fn set_last_error(&self, error: String) {
+        *self.0.last_error.lock().unwrap_or_else(|e| e.into_inner()) = Some(error);
+    }
+
+    fn running(&self) -> MutexGuard<'_, Option<HashMap<u64, JoinHandle<()>>>> {
+        self.0.running.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    async fn fail(&self, message: &str) {
+        self.set_last_error(message.to_string());
+        log_buffer::report(&self.0.logs, &self.0.app, "error", &self.0.plugin, message).await;
+    }
+}
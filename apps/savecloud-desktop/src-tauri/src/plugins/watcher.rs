@@ -0,0 +1,119 @@
+//! Recarga en caliente: vigila la carpeta de plugins y recarga el plugin cuya carpeta
+//! cambia (un `.lua` o su `plugin.toml` editado, una carpeta nueva o borrada).
+//!
+//! Los cambios se agrupan con un debounce corto para que guardar varios archivos a la vez
+//! recargue el plugin una sola vez. Solo cuentan los `.lua` y el `plugin.toml`: los datos
+//! que un plugin escribe en su propia carpeta no provocan recargas en bucle.
+
+use super::lifecycle;
+use super::AppPluginManager;
+use crate::commands::logs::sync_logger;
+use notify::RecursiveMode;
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::AppHandle;
+
+const DEBOUNCE: Duration = Duration::from_millis(750);
+
+/// Empieza a vigilar `plugins_dir` en un hilo propio durante toda la vida de la app.
+pub fn spawn(plugins_dir: PathBuf, manager: AppPluginManager, app: AppHandle) {
+    let (tx, rx) = std::sync::mpsc::channel::<DebounceEventResult>();
+    let mut debouncer = match new_debouncer(DEBOUNCE, tx) {
+        Ok(debouncer) => debouncer,
+        Err(e) => {
+            sync_logger::log_error("plugins", "No se pudo crear el watcher", &e.to_string());
+            return;
+        }
+    };
+    // Las rutas de los eventos llegan resueltas; se comparan contra la carpeta canónica.
+    let watched = plugins_dir
+        .canonicalize()
+        .unwrap_or_else(|_| plugins_dir.clone());
+    if let Err(e) = debouncer
+        .watcher()
+        .watch(&watched, RecursiveMode::Recursive)
+    {
+        sync_logger::log_error(
+            "plugins",
+            &format!("No se pudo vigilar {:?}", plugins_dir.to_string_lossy()),
+            &e.to_string(),
+        );
+        return;
+    }
+
+    std::thread::spawn(move || {
+        // Mantiene el watcher vivo mientras el hilo escuche.
+        let _debouncer = debouncer;
+
+        for result in rx {
+            let events = match result {
+                Ok(events) => events,
+                Err(e) => {
+                    sync_logger::log_error("plugins", "Error del watcher", &e.to_string());
+                    continue;
+                }
+            };
+
+            let changed: BTreeSet<String> = events
+                .iter()
+                .filter_map(|event| changed_plugin(&watched, &event.path))
+                .collect();
+            if changed.is_empty() {
+                continue;
+            }
+
+            let mut manager = manager.blocking_lock();
+            let pending_before = lifecycle::pending_names(&manager);
+            for folder in changed {
+                manager.reload_dir(&plugins_dir.join(folder));
+            }
+            lifecycle::notify_changed(&app, &manager, &pending_before);
+        }
+    });
+}
+
+/// Carpeta del plugin afectado por un cambio en `path`, si el cambio debe recargarlo.
+fn changed_plugin(plugins_dir: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(plugins_dir).ok()?;
+    let mut components = relative.components();
+    let folder = components
+        .next()?
+        .as_os_str()
+        .to_string_lossy()
+        .into_owned();
+
+    // La propia carpeta del plugin: se creó, se renombró o se borró.
+    if components.next().is_none() {
+        return Some(folder);
+    }
+    let is_source = path.extension().is_some_and(|ext| ext == "lua")
+        || path.file_name().is_some_and(|name| name == "plugin.toml");
+    is_source.then_some(folder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::changed_plugin;
+    use std::path::Path;
+
+    #[test]
+    fn only_sources_and_plugin_folders_trigger_reloads() {
+        let root = Path::new("/data/plugins");
+        let changed = |p: &str| changed_plugin(root, Path::new(p));
+
+        assert_eq!(changed("/data/plugins/demo"), Some("demo".into()));
+        assert_eq!(changed("/data/plugins/demo/init.lua"), Some("demo".into()));
+        assert_eq!(
+            changed("/data/plugins/demo/lib/util.lua"),
+            Some("demo".into())
+        );
+        assert_eq!(
+            changed("/data/plugins/demo/plugin.toml"),
+            Some("demo".into())
+        );
+        assert_eq!(changed("/data/plugins/demo/cache.json"), None);
+        assert_eq!(changed("/data/otra/demo/init.lua"), None);
+    }
+}
@@ -6,8 +6,11 @@
 //! demonios de sincronización automática.
 
 use crate::system::game_exit_sync;
+use crate::system::sync_queue;
 //use crate::system::watch_sync;
 use crate::controller::start_gamepad_loop;
+use crate::plugins::manager::PluginContext;
+use crate::plugins::permissions::PERMISSIONS_REQUIRED_EVENT;
 use crate::plugins::{log_buffer::new_log_buffer, AppPluginManager};
 use crate::sqlite::AppDb;
 use crate::system::process_check::start_process_watcher;
@@ -15,7 +18,7 @@ use crate::torrent::{engine::TorrentEngine, state::TorrentState};
 use crate::tray::tray_state::TrayState;
 
 use std::sync::Arc;
-use tauri::{App, Manager};
+use tauri::{App, Emitter, Manager};
 use tokio::sync::Mutex;
 
 /// Ejecuta la secuencia de arranque de los demonios y vincula los estados globales.
@@ -44,9 +47,14 @@ pub fn init_states_and_background_tasks(app: &mut App) -> Result<(), Box<dyn std
         let _ = std::fs::create_dir_all(&plugins_dir);
     }
 
+    // Copia offline del manifiesto de Ludusavi (si el instalador la incluye).
+    if let Ok(resource_dir) = app.path().resource_dir() {
+        crate::manifest::register_bundled_manifest(&resource_dir);
+    }
+
     let db = AppDb::open()?;
     db.ping()?;
-    app.manage(db);
+    app.manage(db.clone());
 
     let logs = new_log_buffer();
     app.manage(logs.clone());
@@ -54,19 +62,35 @@ pub fn init_states_and_background_tasks(app: &mut App) -> Result<(), Box<dyn std
     let shared_manager: AppPluginManager =
         Arc::new(Mutex::new(crate::plugins::manager::PluginManager::new()));
     app.manage(shared_manager.clone());
+    crate::plugins::hooks::install(shared_manager.clone(), logs.clone(), app.handle().clone());
 
     let tokio_handle = tauri::async_runtime::handle();
     let handle = app.handle().clone();
+    let plugins_context = PluginContext {
+        plugins_dir: plugins_dir.clone(),
+        app: handle.clone(),
+        logs,
+        db: db.clone(),
+    };
 
     // La carga de plugins se delega a un hilo de fondo para no bloquear
     // el renderizado inicial de la interfaz de usuario.
     std::thread::spawn(move || {
         let mut manager = crate::plugins::manager::PluginManager::new();
-        manager.load_all(plugins_dir, handle, logs);
+        manager.load_all(plugins_context);
+        let pending = manager.pending_dtos();
 
         tokio_handle.block_on(async {
             *shared_manager.lock().await = manager;
         });
+
+        // Recarga en caliente: a partir de aquí los cambios en la carpeta se aplican solos.
+        crate::plugins::watcher::spawn(plugins_dir, shared_manager, handle.clone());
+
+        // Los plugins con permisos sin aprobar esperan a que el usuario los revise.
+        if !pending.is_empty() {
+            let _ = handle.emit(PERMISSIONS_REQUIRED_EVENT, pending);
+        }
     });
 
     // 3. Inicialización del motor P2P (BitTorrent)
@@ -79,6 +103,12 @@ pub fn init_states_and_background_tasks(app: &mut App) -> Result<(), Box<dyn std
         engine: std::sync::Arc::new(tokio::sync::Mutex::new(torrent_engine)),
     });
 
+    // Vista en vivo de las transferencias: la UI recibe la lista completa cada vez que cambia.
+    let transfers_handle = app.handle().clone();
+    crate::transfers::global().set_listener(move || {
+        let _ = transfers_handle.emit("transfers-changed", crate::transfers::global().snapshot());
+    });
+
     // 4. Extracción de estados compartidos
     let tray_state = app.state::<TrayState>();
 
@@ -87,6 +117,10 @@ pub fn init_states_and_background_tasks(app: &mut App) -> Result<(), Box<dyn std
     // Sincronización Reactiva: Sube archivos cuando detecta que el proceso de un juego termina.
     game_exit_sync::spawn_exit_watcher(app.handle().clone(), tray_state.inner().0.clone());
 
+    // Cola Offline: Reintenta con backoff las operaciones que fallaron (p. ej. sin red)
+    // y la vacía en cuanto el backend vuelve a responder.
+    sync_queue::spawn_queue_worker(app.handle().clone(), db, tray_state.inner().0.clone());
+
     // Sincronización Activa (Nuestro nuevo módulo): Vigila cambios en el disco duro
     // y los encola con un debounce de 5 minutos para subidas silenciosas.
     // comentado temporalmente para evitar bugs:
@@ -94,6 +128,8 @@ pub fn init_states_and_background_tasks(app: &mut App) -> Result<(), Box<dyn std
 
     // Observador de Procesos: Audita la memoria del SO y emite eventos IPC al frontend.
     start_process_watcher(app.handle().clone());
+    // La bandeja muestra los juegos abiertos.
+    tray_state.inner().0.clone().watch_running_games();
 
     // Bucle del Controlador: Inicia la escucha activa de inputs de mandos/gamepads.
     start_gamepad_loop(app.handle().clone());
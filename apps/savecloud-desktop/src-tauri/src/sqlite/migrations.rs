@@ -31,6 +31,36 @@ pub fn run_migrations(conn: &Connection) -> Result<(), rusqlite::Error> {
         conn.pragma_update(None, "user_version", "5")?;
     }
 
+    if version < 6 {
+        conn.execute_batch(include_str!("sql/006_sync_job_queue.sql"))?;
+        conn.pragma_update(None, "user_version", "6")?;
+    }
+
+    if version < 7 {
+        conn.execute_batch(include_str!("sql/007_play_sessions.sql"))?;
+        conn.pragma_update(None, "user_version", "7")?;
+    }
+
+    if version < 8 {
+        conn.execute_batch(include_str!("sql/008_play_sessions_idle.sql"))?;
+        conn.pragma_update(None, "user_version", "8")?;
+    }
+
+    if version < 9 {
+        conn.execute_batch(include_str!("sql/009_plugin_grants.sql"))?;
+        conn.pragma_update(None, "user_version", "9")?;
+    }
+
+    if version < 10 {
+        conn.execute_batch(include_str!("sql/010_plugin_storage.sql"))?;
+        conn.pragma_update(None, "user_version", "10")?;
+    }
+
+    if version < 11 {
+        conn.execute_batch(include_str!("sql/011_plugin_disabled.sql"))?;
+        conn.pragma_update(None, "user_version", "11")?;
+    }
+
     Ok(())
 }
 
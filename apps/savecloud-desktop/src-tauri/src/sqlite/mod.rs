@@ -1,4 +1,8 @@
-//! Base de datos local **SQLite** para el catálogo Steam (sembrado, listados y `details_json` enriquecido).
+//! Base de datos local **SQLite** para el catálogo Steam (sembrado, listados y `details_json` enriquecido),
+//! la cola persistente de sincronización (`sync_jobs`), el historial de partidas (`play_sessions`)
+//! y los datos de los plugins: permisos aprobados (`plugin_grants`), plugins desactivados
+//! (`plugin_disabled`), clave-valor (`plugin_kv`) y las tablas propias de cada plugin
+//! (`plugin_<nombre>__*`).
 //!
 //! Se usa **`rusqlite`** con SQLite embebido (`bundled`) para no depender de binarios del SO.
 //! El SQL **no** se expone al frontend: solo comandos Tauri invocan este módulo.
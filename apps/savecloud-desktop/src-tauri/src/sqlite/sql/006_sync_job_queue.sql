@@ -0,0 +1,17 @@
+-- Cola persistente de operaciones de sincronización que fallaron o esperan conexión.
+-- Un solo trabajo `pending` por (tipo, juego): volver a encolar actualiza el existente.
+CREATE TABLE IF NOT EXISTS sync_jobs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    kind TEXT NOT NULL CHECK (kind IN ('upload', 'download', 'full_backup')),
+    game_id TEXT NOT NULL COLLATE NOCASE,
+    state TEXT NOT NULL DEFAULT 'pending' CHECK (state IN ('pending', 'running', 'failed')),
+    priority INTEGER NOT NULL DEFAULT 0,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at INTEGER NOT NULL DEFAULT (unixepoch()),
+    last_error TEXT,
+    created_at INTEGER NOT NULL DEFAULT (unixepoch()),
+    updated_at INTEGER NOT NULL DEFAULT (unixepoch())
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_sync_jobs_pending_game ON sync_jobs (kind, game_id) WHERE state = 'pending';
+CREATE INDEX IF NOT EXISTS idx_sync_jobs_due ON sync_jobs (state, next_attempt_at);
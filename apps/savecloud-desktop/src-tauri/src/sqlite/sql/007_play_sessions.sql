@@ -0,0 +1,15 @@
+-- Historial de partidas: una fila por sesión de juego en este dispositivo.
+-- `ended_at` y `duration_seconds` se actualizan en cada punto de guardado, así que una
+-- partida interrumpida por un cierre inesperado conserva el tiempo hasta el último.
+CREATE TABLE IF NOT EXISTS play_sessions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    game_id TEXT NOT NULL COLLATE NOCASE,
+    device_id TEXT NOT NULL,
+    started_at INTEGER NOT NULL,
+    ended_at INTEGER NOT NULL,
+    duration_seconds INTEGER NOT NULL DEFAULT 0 CHECK (duration_seconds >= 0),
+    launched_via TEXT NOT NULL DEFAULT 'detected' CHECK (launched_via IN ('detected', 'app', 'managed'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_play_sessions_game_started ON play_sessions (game_id, started_at);
+CREATE INDEX IF NOT EXISTS idx_play_sessions_started ON play_sessions (started_at);
@@ -0,0 +1,3 @@
+-- Tiempo con el juego abierto pero sin jugar (detección de inactividad). No cuenta en
+-- `duration_seconds`, que es solo el tiempo jugado.
+ALTER TABLE play_sessions ADD COLUMN idle_seconds INTEGER NOT NULL DEFAULT 0 CHECK (idle_seconds >= 0);
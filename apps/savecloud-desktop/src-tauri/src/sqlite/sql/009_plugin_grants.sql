@@ -0,0 +1,10 @@
+-- Permisos de plugins aprobados por el usuario: una fila por plugin con las capacidades
+-- de su `plugin.toml` (JSON) tal como se aprobaron y la carpeta desde la que se cargó.
+-- Si el manifiesto pide algo más, o el mismo nombre aparece en otra carpeta, el plugin
+-- vuelve a quedar pendiente de aprobación.
+CREATE TABLE IF NOT EXISTS plugin_grants (
+    plugin TEXT PRIMARY KEY COLLATE NOCASE,
+    capabilities TEXT NOT NULL,
+    approved_at INTEGER NOT NULL,
+    dir TEXT NOT NULL
+);
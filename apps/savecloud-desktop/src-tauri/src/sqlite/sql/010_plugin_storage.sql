@@ -0,0 +1,9 @@
+-- Almacenamiento clave-valor de los plugins: cada plugin solo ve sus propias claves.
+-- `value` es JSON con el valor Lua tal como lo guardó el plugin.
+CREATE TABLE IF NOT EXISTS plugin_kv (
+    plugin TEXT NOT NULL COLLATE NOCASE,
+    key TEXT NOT NULL,
+    value TEXT NOT NULL,
+    updated_at INTEGER NOT NULL,
+    PRIMARY KEY (plugin, key)
+);
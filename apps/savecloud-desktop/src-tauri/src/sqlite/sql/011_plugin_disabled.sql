@@ -0,0 +1,5 @@
+-- Plugins que el usuario desactivó: no se cargan al arrancar hasta que los vuelva a activar.
+CREATE TABLE IF NOT EXISTS plugin_disabled (
+    plugin TEXT PRIMARY KEY COLLATE NOCASE,
+    disabled_at INTEGER NOT NULL
+);
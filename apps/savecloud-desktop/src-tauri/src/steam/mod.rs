@@ -3,10 +3,16 @@
 //! Escanea las bibliotecas de Steam y asocia rutas de juego con sus app IDs.
 //! La búsqueda por API (nombre → App ID) está en `commands::steam_search`.
 //! La API pública Store `appdetails` compartida vive en [`appdetails`].
+//! Los archivos VDF (texto y binario) se leen con [`vdf`]; los juegos no-Steam
+//! añadidos a la biblioteca, con [`shortcuts`].
 
 pub mod appdetails;
 mod path_resolver;
+#[cfg(not(target_os = "windows"))]
+pub mod proton;
+pub mod shortcuts;
 pub mod steam_search;
+pub mod vdf;
 
 pub use path_resolver::resolve_app_id_for_game;
 pub use path_resolver::{get_steam_path_to_appid_map, resolve_steam_app_id_from_map};
@@ -16,10 +16,6 @@ use std::path::Path;
 #[cfg(target_os = "windows")]
 use std::sync::LazyLock;
 
-#[cfg(target_os = "windows")]
-static VDF_PATH_REGEX: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r#""path"\s+"([^"]+)""#).unwrap());
-
 #[cfg(target_os = "windows")]
 static ENV_VAR_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"%([^%]+)%").unwrap());
 
@@ -66,7 +62,7 @@ fn normalize_path(s: &str) -> PathBuf {
 
 /// Rutas posibles de Steam en Windows.
 #[cfg(target_os = "windows")]
-fn steam_path_candidates() -> Vec<PathBuf> {
+pub(super) fn steam_path_candidates() -> Vec<PathBuf> {
     let mut candidates = Vec::new();
 
     if let Ok(path) = read_steam_path_from_registry() {
@@ -118,10 +114,8 @@ fn read_library_paths(steam_root: &Path) -> Vec<PathBuf> {
 /// Extrae las rutas del VDF.
 #[cfg(target_os = "windows")]
 fn parse_libraryfolders_vdf(content: &str) -> Option<Vec<PathBuf>> {
-    let paths: Vec<PathBuf> = VDF_PATH_REGEX
-        .captures_iter(content)
-        .filter_map(|cap| cap.get(1))
-        .map(|m| PathBuf::from(m.as_str().replace("\\\\", "\\")))
+    let paths: Vec<PathBuf> = super::vdf::parse_libraryfolders(content)
+        .into_iter()
         .filter(|p| p.is_dir())
         .collect();
 
@@ -132,33 +126,6 @@ fn parse_libraryfolders_vdf(content: &str) -> Option<Vec<PathBuf>> {
     }
 }
 
-/// Parsea appmanifest_*.acf
-#[cfg(target_os = "windows")]
-fn parse_appmanifest(content: &str) -> Option<(String, String)> {
-    let mut appid = None;
-    let mut installdir = None;
-
-    for line in content.lines() {
-        let line = line.trim();
-        let line_lower = line.to_lowercase();
-
-        if line_lower.starts_with("\"appid\"") {
-            appid = line.split('"').nth(3).map(|s| s.to_string());
-        } else if line_lower.starts_with("\"installdir\"") {
-            installdir = line.split('"').nth(3).map(|s| s.to_string());
-        }
-
-        if appid.is_some() && installdir.is_some() {
-            break;
-        }
-    }
-
-    match (appid, installdir) {
-        (Some(a), Some(i)) if !a.is_empty() && !i.is_empty() => Some((a, i)),
-        _ => None,
-    }
-}
-
 /// Construye el mapa path -> appid.
 #[cfg(target_os = "windows")]
 fn build_path_to_appid_map(library_paths: &[PathBuf]) -> HashMap<PathBuf, String> {
@@ -181,11 +148,11 @@ fn build_path_to_appid_map(library_paths: &[PathBuf]) -> HashMap<PathBuf, String
 
             if name.starts_with("appmanifest_") && name.ends_with(".acf") {
                 if let Ok(content) = fs::read_to_string(&path) {
-                    if let Some((appid, installdir)) = parse_appmanifest(&content) {
-                        let game_path = steamapps.join("common").join(&installdir);
+                    if let Some(manifest) = super::vdf::parse_appmanifest(&content) {
+                        let game_path = steamapps.join("common").join(&manifest.install_dir);
 
                         if let Ok(canonical) = game_path.canonicalize() {
-                            map.insert(clean_unc_path(&canonical), appid);
+                            map.insert(clean_unc_path(&canonical), manifest.app_id);
                         }
                     }
                 }
@@ -0,0 +1,146 @@
+//! Localización de prefijos Proton (Wine) de Steam en Linux.
+//!
+//! Cada juego de Windows ejecutado con Proton tiene su propio prefijo en
+//! `<biblioteca>/steamapps/compatdata/<app_id>/pfx`, que contiene un árbol
+//! `drive_c/users/steamuser/...` equivalente al perfil de usuario de Windows.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Usuario fijo que Proton crea dentro de cada prefijo.
+pub const PROTON_USER: &str = "steamuser";
+
+/// Raíces de instalación de Steam habituales en Linux (nativa, enlace legado y Flatpak).
+pub fn linux_steam_roots() -> Vec<PathBuf> {
+    let Some(home) = dirs::home_dir() else {
+        return Vec::new();
+    };
+
+    let candidates = [
+        home.join(".steam").join("steam"),
+        home.join(".local").join("share").join("Steam"),
+        home.join(".var")
+            .join("app")
+            .join("com.valvesoftware.Steam")
+            .join(".local")
+            .join("share")
+            .join("Steam"),
+    ];
+
+    let mut roots: Vec<PathBuf> = Vec::new();
+    for candidate in candidates {
+        if !candidate.join("steamapps").is_dir() {
+            continue;
+        }
+        // `~/.steam/steam` suele ser un enlace simbólico a `~/.local/share/Steam`.
+        let canonical = candidate.canonicalize().unwrap_or(candidate);
+        if !roots.contains(&canonical) {
+            roots.push(canonical);
+        }
+    }
+    roots
+}
+
+/// Devuelve las bibliotecas de Steam declaradas en `libraryfolders.vdf`, incluida la raíz.
+fn library_paths(steam_root: &Path) -> Vec<PathBuf> {
+    let mut libraries = vec![steam_root.to_path_buf()];
+
+    let vdf = steam_root.join("steamapps").join("libraryfolders.vdf");
+    if let Ok(content) = fs::read_to_string(vdf) {
+        for path in super::vdf::parse_libraryfolders(&content) {
+            if path.is_dir() && !libraries.contains(&path) {
+                libraries.push(path);
+            }
+        }
+    }
+
+    libraries
+}
+
+/// Todas las bibliotecas de Steam del equipo, sin duplicados.
+pub fn linux_library_paths() -> Vec<PathBuf> {
+    let mut libraries: Vec<PathBuf> = Vec::new();
+    for lib in linux_steam_roots()
+        .iter()
+        .flat_map(|root| library_paths(root))
+    {
+        if !libraries.contains(&lib) {
+            libraries.push(lib);
+        }
+    }
+    libraries
+}
+
+/// Mapa App ID → prefijo Proton de todos los juegos con `compatdata` en el equipo.
+///
+/// Pensado para escaneos masivos (manifiesto completo), donde llamar a
+/// [`find_proton_prefix`] por cada entrada recorrería las bibliotecas miles de veces.
+pub fn list_proton_prefixes() -> HashMap<String, PathBuf> {
+    let mut prefixes = HashMap::new();
+    for lib in linux_library_paths() {
+        let Ok(entries) = fs::read_dir(lib.join("steamapps").join("compatdata")) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let pfx = entry.path().join("pfx");
+            if !pfx.join("drive_c").is_dir() {
+                continue;
+            }
+            if let Ok(app_id) = entry.file_name().into_string() {
+                prefixes.entry(app_id).or_insert(pfx);
+            }
+        }
+    }
+    prefixes
+}
+
+/// Mapa nombre de carpeta (minúsculas) → ruta de cada juego en `steamapps/common`.
+pub fn list_common_install_dirs() -> HashMap<String, PathBuf> {
+    let mut dirs = HashMap::new();
+    for lib in linux_library_paths() {
+        let Ok(entries) = fs::read_dir(lib.join("steamapps").join("common")) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if !entry.path().is_dir() {
+                continue;
+            }
+            if let Ok(name) = entry.file_name().into_string() {
+                dirs.entry(name.to_lowercase())
+                    .or_insert_with(|| entry.path());
+            }
+        }
+    }
+    dirs
+}
+
+/// Busca en las bibliotecas de Steam la carpeta de instalación con alguno de los nombres dados.
+pub fn find_install_dir(install_dir_names: &[String]) -> Option<PathBuf> {
+    if install_dir_names.is_empty() {
+        return None;
+    }
+    let common = list_common_install_dirs();
+    install_dir_names
+        .iter()
+        .find_map(|name| common.get(&name.to_lowercase()).cloned())
+}
+
+/// Busca el prefijo Proton (`.../compatdata/<app_id>/pfx`) de un juego en todas las bibliotecas.
+pub fn find_proton_prefix(steam_app_id: &str) -> Option<PathBuf> {
+    let app_id = steam_app_id.trim();
+    if app_id.is_empty() || !app_id.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    linux_steam_roots()
+        .iter()
+        .flat_map(|root| library_paths(root))
+        .map(|lib| {
+            lib.join("steamapps")
+                .join("compatdata")
+                .join(app_id)
+                .join("pfx")
+        })
+        .find(|pfx| pfx.join("drive_c").is_dir())
+}
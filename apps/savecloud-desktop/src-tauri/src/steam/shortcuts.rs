@@ -0,0 +1,170 @@
+//! Juegos no-Steam añadidos a la biblioteca de Steam (accesos directos).
+//!
+//! Steam los guarda en `userdata/<user_id>/config/shortcuts.vdf` (VDF binario). Suelen
+//! ser emuladores, juegos de otras tiendas o ejecutables sueltos; de cada uno se obtiene
+//! el nombre, el ejecutable, la carpeta de inicio y el App ID generado por Steam.
+
+use super::vdf::{self, VdfMap, VdfValue};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+
+/// Acceso directo no-Steam de la biblioteca.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SteamShortcut {
+    /// App ID de 32 bits que Steam asigna al acceso directo (carpetas `grid`, `compatdata`...).
+    pub app_id: u32,
+    pub app_name: String,
+    /// Ruta del ejecutable sin comillas.
+    pub exe: String,
+    pub start_dir: String,
+    /// Nombre del archivo ejecutable, listo para `executable_names` de un juego.
+    pub executable_name: String,
+    /// Usuario de Steam (carpeta de `userdata`) al que pertenece.
+    pub steam_user_id: String,
+}
+
+/// Raíces de Steam instaladas en este equipo.
+fn steam_roots() -> Vec<PathBuf> {
+    #[cfg(target_os = "windows")]
+    {
+        let mut roots: Vec<PathBuf> = Vec::new();
+        for root in super::path_resolver::steam_path_candidates() {
+            if root.join("userdata").is_dir() && !roots.contains(&root) {
+                roots.push(root);
+            }
+        }
+        roots
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        super::proton::linux_steam_roots()
+    }
+}
+
+/// App ID que Steam calcula para accesos directos antiguos sin campo `appid`:
+/// `crc32(exe + nombre) | 0x80000000`.
+fn generated_app_id(exe: &str, app_name: &str) -> u32 {
+    let mut crc: u32 = 0xffff_ffff;
+    for byte in exe.bytes().chain(app_name.bytes()) {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+    !crc | 0x8000_0000
+}
+
+fn unquote(s: &str) -> String {
+    s.trim().trim_matches('"').to_string()
+}
+
+/// Nombre de archivo del ejecutable, aceptando separadores de Windows y Unix.
+fn executable_file_name(exe: &str) -> String {
+    exe.rsplit(['/', '\\']).next().unwrap_or(exe).to_string()
+}
+
+fn shortcut_from_map(entry: &VdfMap, steam_user_id: &str) -> Option<SteamShortcut> {
+    let app_name = entry.get_str("appname")?.trim().to_string();
+    let raw_exe = entry.get_str("exe")?;
+    let exe = unquote(raw_exe);
+    if app_name.is_empty() || exe.is_empty() {
+        return None;
+    }
+
+    let app_id = entry
+        .get("appid")
+        .and_then(VdfValue::as_u64)
+        .map(|id| id as u32)
+        .filter(|&id| id != 0)
+        .unwrap_or_else(|| generated_app_id(raw_exe, &app_name));
+
+    Some(SteamShortcut {
+        app_id,
+        executable_name: executable_file_name(&exe),
+        start_dir: entry.get_str("startdir").map(unquote).unwrap_or_default(),
+        exe,
+        app_name,
+        steam_user_id: steam_user_id.to_string(),
+    })
+}
+
+/// Parsea el contenido de un `shortcuts.vdf`.
+fn parse_shortcuts(bytes: &[u8], steam_user_id: &str) -> Vec<SteamShortcut> {
+    let Ok(doc) = vdf::parse_binary(bytes) else {
+        return Vec::new();
+    };
+    let Some(shortcuts) = doc.get_map("shortcuts") else {
+        return Vec::new();
+    };
+    shortcuts
+        .iter()
+        .filter_map(|(_, value)| value.as_map())
+        .filter_map(|entry| shortcut_from_map(entry, steam_user_id))
+        .collect()
+}
+
+/// Lista los accesos directos no-Steam de todos los usuarios de Steam del equipo.
+pub fn read_steam_shortcuts() -> Vec<SteamShortcut> {
+    let mut seen = HashSet::new();
+    let mut shortcuts = Vec::new();
+
+    for root in steam_roots() {
+        let Ok(users) = fs::read_dir(root.join("userdata")) else {
+            continue;
+        };
+        for user in users.flatten() {
+            let Ok(user_id) = user.file_name().into_string() else {
+                continue;
+            };
+            let file = user.path().join("config").join("shortcuts.vdf");
+            let Ok(bytes) = fs::read(&file) else {
+                continue;
+            };
+            for shortcut in parse_shortcuts(&bytes, &user_id) {
+                if seen.insert((shortcut.app_id, shortcut.steam_user_id.clone())) {
+                    shortcuts.push(shortcut);
+                }
+            }
+        }
+    }
+
+    shortcuts
+}
+
+/// Accesos directos cuyo nombre coincide con el juego.
+pub fn shortcuts_for_game<'a>(
+    shortcuts: &'a [SteamShortcut],
+    game_id: &str,
+) -> Vec<&'a SteamShortcut> {
+    let game_id = game_id.trim();
+    shortcuts
+        .iter()
+        .filter(|s| s.app_name.eq_ignore_ascii_case(game_id))
+        .collect()
+}
+
+/// Devuelve los juegos no-Steam de la biblioteca como candidatos para añadir.
+#[tauri::command]
+pub async fn list_steam_shortcuts() -> Result<Vec<SteamShortcut>, String> {
+    tauri::async_runtime::spawn_blocking(read_steam_shortcuts)
+        .await
+        .map_err(|e| format!("Error al leer los accesos directos de Steam: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_app_id_is_crc32_with_high_bit() {
+        // CRC-32 de "123456789" es 0xCBF43926 (valor de comprobación estándar).
+        assert_eq!(generated_app_id("12345", "6789"), 0xcbf4_3926);
+        assert_eq!(generated_app_id("", ""), 0x8000_0000);
+        assert_eq!(executable_file_name("C:\\Emu\\dolphin.exe"), "dolphin.exe");
+        assert_eq!(executable_file_name("/usr/bin/retroarch"), "retroarch");
+    }
+}
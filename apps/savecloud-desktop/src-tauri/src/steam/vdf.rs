@@ -0,0 +1,436 @@
+//! Parser de archivos VDF (KeyValues) de Steam, en formato texto y binario.
+//!
+//! - Texto: `libraryfolders.vdf`, `appmanifest_*.acf`, `loginusers.vdf`...
+//! - Binario: `userdata/<id>/config/shortcuts.vdf` (juegos no-Steam añadidos a la biblioteca).
+//!
+//! Las claves se comparan sin distinguir mayúsculas: Steam no es consistente entre
+//! versiones (`AppName` / `appname`).
+
+use std::path::PathBuf;
+
+/// Errores de lectura de un VDF.
+#[derive(Debug, thiserror::Error)]
+pub enum VdfError {
+    #[error("Fin de archivo inesperado en la posición {0}")]
+    UnexpectedEof(usize),
+
+    #[error("Token inesperado en la posición {0}")]
+    UnexpectedToken(usize),
+
+    #[error("Tipo binario desconocido 0x{0:02x} en la posición {1}")]
+    UnknownType(u8, usize),
+}
+
+/// Valor de un nodo VDF.
+#[derive(Debug, Clone, PartialEq)]
+pub enum VdfValue {
+    Str(String),
+    Int(i32),
+    Float(f32),
+    UInt64(u64),
+    Int64(i64),
+    Map(VdfMap),
+}
+
+impl VdfValue {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            VdfValue::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_map(&self) -> Option<&VdfMap> {
+        match self {
+            VdfValue::Map(m) => Some(m),
+            _ => None,
+        }
+    }
+
+    /// Valor entero, aceptando también cadenas numéricas (VDF de texto).
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            VdfValue::Int(i) => Some(*i as u32 as u64),
+            VdfValue::UInt64(u) => Some(*u),
+            VdfValue::Int64(i) => u64::try_from(*i).ok(),
+            VdfValue::Str(s) => s.trim().parse().ok(),
+            VdfValue::Float(_) | VdfValue::Map(_) => None,
+        }
+    }
+}
+
+/// Mapa ordenado de claves a valores; admite claves repetidas como hace Steam.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VdfMap(Vec<(String, VdfValue)>);
+
+impl VdfMap {
+    pub fn get(&self, key: &str) -> Option<&VdfValue> {
+        self.0
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v)
+    }
+
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key).and_then(VdfValue::as_str)
+    }
+
+    pub fn get_map(&self, key: &str) -> Option<&VdfMap> {
+        self.get(key).and_then(VdfValue::as_map)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &VdfValue)> {
+        self.0.iter().map(|(k, v)| (k.as_str(), v))
+    }
+
+    /// Si el mapa tiene una única clave raíz (lo habitual en los archivos de Steam), la devuelve.
+    pub fn root(&self) -> Option<&VdfMap> {
+        match self.0.as_slice() {
+            [(_, VdfValue::Map(m))] => Some(m),
+            _ => None,
+        }
+    }
+}
+
+// --- Texto -------------------------------------------------------------------
+
+enum Token {
+    Str(String),
+    Open,
+    Close,
+}
+
+struct TextLexer<'a> {
+    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+    len: usize,
+}
+
+impl<'a> TextLexer<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            chars: input.char_indices().peekable(),
+            len: input.len(),
+        }
+    }
+
+    fn skip_whitespace_and_comments(&mut self) {
+        while let Some(&(_, c)) = self.chars.peek() {
+            if c.is_whitespace() {
+                self.chars.next();
+            } else if c == '/' {
+                let mut lookahead = self.chars.clone();
+                lookahead.next();
+                if matches!(lookahead.peek(), Some(&(_, '/'))) {
+                    for (_, c) in self.chars.by_ref() {
+                        if c == '\n' {
+                            break;
+                        }
+                    }
+                } else {
+                    return;
+                }
+            } else {
+                return;
+            }
+        }
+    }
+
+    fn next_token(&mut self) -> Result<Option<(usize, Token)>, VdfError> {
+        self.skip_whitespace_and_comments();
+        let Some((pos, c)) = self.chars.next() else {
+            return Ok(None);
+        };
+
+        let token = match c {
+            '{' => Token::Open,
+            '}' => Token::Close,
+            '"' => {
+                let mut s = String::new();
+                loop {
+                    match self.chars.next() {
+                        Some((_, '"')) => break,
+                        Some((_, '\\')) => match self.chars.next() {
+                            Some((_, 'n')) => s.push('\n'),
+                            Some((_, 't')) => s.push('\t'),
+                            Some((_, other)) => s.push(other),
+                            None => return Err(VdfError::UnexpectedEof(self.len)),
+                        },
+                        Some((_, other)) => s.push(other),
+                        None => return Err(VdfError::UnexpectedEof(self.len)),
+                    }
+                }
+                Token::Str(s)
+            }
+            _ => {
+                let mut s = String::from(c);
+                while let Some(&(_, c)) = self.chars.peek() {
+                    if c.is_whitespace() || c == '{' || c == '}' || c == '"' {
+                        break;
+                    }
+                    s.push(c);
+                    self.chars.next();
+                }
+                Token::Str(s)
+            }
+        };
+        Ok(Some((pos, token)))
+    }
+
+    /// Salta condicionales de plataforma (`[$WIN32]`) que siguen a un valor.
+    fn skip_conditional(&mut self) {
+        self.skip_whitespace_and_comments();
+        if matches!(self.chars.peek(), Some(&(_, '['))) {
+            for (_, c) in self.chars.by_ref() {
+                if c == ']' {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+fn parse_text_map(lexer: &mut TextLexer, nested: bool) -> Result<VdfMap, VdfError> {
+    let mut map = VdfMap::default();
+    loop {
+        let key = match lexer.next_token()? {
+            None if nested => return Err(VdfError::UnexpectedEof(lexer.len)),
+            None => return Ok(map),
+            Some((_, Token::Close)) if nested => return Ok(map),
+            Some((pos, Token::Close | Token::Open)) => return Err(VdfError::UnexpectedToken(pos)),
+            Some((_, Token::Str(key))) => key,
+        };
+        lexer.skip_conditional();
+
+        let value = match lexer.next_token()? {
+            None => return Err(VdfError::UnexpectedEof(lexer.len)),
+            Some((_, Token::Open)) => VdfValue::Map(parse_text_map(lexer, true)?),
+            Some((_, Token::Str(s))) => VdfValue::Str(s),
+            Some((pos, Token::Close)) => return Err(VdfError::UnexpectedToken(pos)),
+        };
+        lexer.skip_conditional();
+        map.0.push((key, value));
+    }
+}
+
+/// Parsea un VDF de texto.
+pub fn parse_text(input: &str) -> Result<VdfMap, VdfError> {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    parse_text_map(&mut TextLexer::new(input), false)
+}
+
+// --- Binario -----------------------------------------------------------------
+
+const BIN_MAP: u8 = 0x00;
+const BIN_STRING: u8 = 0x01;
+const BIN_INT32: u8 = 0x02;
+const BIN_FLOAT32: u8 = 0x03;
+const BIN_POINTER: u8 = 0x04;
+const BIN_COLOR: u8 = 0x06;
+const BIN_UINT64: u8 = 0x07;
+const BIN_END: u8 = 0x08;
+const BIN_INT64: u8 = 0x0a;
+const BIN_END_ALT: u8 = 0x0b;
+
+struct BinaryReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl BinaryReader<'_> {
+    fn byte(&mut self) -> Result<u8, VdfError> {
+        let b = *self
+            .bytes
+            .get(self.pos)
+            .ok_or(VdfError::UnexpectedEof(self.pos))?;
+        self.pos += 1;
+        Ok(b)
+    }
+
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], VdfError> {
+        let slice = self
+            .bytes
+            .get(self.pos..self.pos + N)
+            .ok_or(VdfError::UnexpectedEof(self.pos))?;
+        self.pos += N;
+        let mut out = [0u8; N];
+        out.copy_from_slice(slice);
+        Ok(out)
+    }
+
+    fn cstring(&mut self) -> Result<String, VdfError> {
+        let rest = &self.bytes[self.pos..];
+        let len = rest
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or(VdfError::UnexpectedEof(self.bytes.len()))?;
+        let s = String::from_utf8_lossy(&rest[..len]).into_owned();
+        self.pos += len + 1;
+        Ok(s)
+    }
+}
+
+fn parse_binary_map(reader: &mut BinaryReader, nested: bool) -> Result<VdfMap, VdfError> {
+    let mut map = VdfMap::default();
+    loop {
+        if !nested && reader.pos >= reader.bytes.len() {
+            return Ok(map);
+        }
+        let kind_pos = reader.pos;
+        let kind = reader.byte()?;
+        if kind == BIN_END || kind == BIN_END_ALT {
+            return Ok(map);
+        }
+
+        let key = reader.cstring()?;
+        let value = match kind {
+            BIN_MAP => VdfValue::Map(parse_binary_map(reader, true)?),
+            BIN_STRING => VdfValue::Str(reader.cstring()?),
+            BIN_INT32 | BIN_POINTER | BIN_COLOR => {
+                VdfValue::Int(i32::from_le_bytes(reader.take()?))
+            }
+            BIN_FLOAT32 => VdfValue::Float(f32::from_le_bytes(reader.take()?)),
+            BIN_UINT64 => VdfValue::UInt64(u64::from_le_bytes(reader.take()?)),
+            BIN_INT64 => VdfValue::Int64(i64::from_le_bytes(reader.take()?)),
+            other => return Err(VdfError::UnknownType(other, kind_pos)),
+        };
+        map.0.push((key, value));
+    }
+}
+
+/// Parsea un VDF binario (formato de `shortcuts.vdf`).
+pub fn parse_binary(bytes: &[u8]) -> Result<VdfMap, VdfError> {
+    parse_binary_map(&mut BinaryReader { bytes, pos: 0 }, false)
+}
+
+// --- Archivos concretos de Steam ---------------------------------------------
+
+/// Rutas de bibliotecas declaradas en `libraryfolders.vdf`.
+///
+/// Soporta el formato actual (`"0" { "path" "..." }`) y el antiguo, donde cada
+/// clave numérica apunta directamente a la ruta.
+pub fn parse_libraryfolders(content: &str) -> Vec<PathBuf> {
+    let Ok(doc) = parse_text(content) else {
+        return Vec::new();
+    };
+    let Some(root) = doc.root() else {
+        return Vec::new();
+    };
+
+    root.iter()
+        .filter(|(key, _)| key.chars().all(|c| c.is_ascii_digit()))
+        .filter_map(|(_, value)| match value {
+            VdfValue::Map(folder) => folder.get_str("path"),
+            VdfValue::Str(path) => Some(path.as_str()),
+            _ => None,
+        })
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// Datos básicos de un `appmanifest_<id>.acf`.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppManifest {
+    pub app_id: String,
+    pub install_dir: String,
+}
+
+/// Lee `appid` e `installdir` de un `appmanifest_*.acf`.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub fn parse_appmanifest(content: &str) -> Option<AppManifest> {
+    let doc = parse_text(content).ok()?;
+    let state = doc.root()?;
+    let app_id = state.get_str("appid").filter(|s| !s.is_empty())?;
+    let install_dir = state.get_str("installdir").filter(|s| !s.is_empty())?;
+    Some(AppManifest {
+        app_id: app_id.to_string(),
+        install_dir: install_dir.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_libraryfolders_new_and_legacy_formats() {
+        let current = r#"
+"libraryfolders"
+{
+	"0"
+	{
+		"path"		"C:\\Program Files (x86)\\Steam"
+		"label"		""
+		"apps"
+		{
+			"228980"		"1"
+		}
+	}
+	"1"
+	{
+		"path"		"D:\\SteamLibrary"
+	}
+}"#;
+        assert_eq!(
+            parse_libraryfolders(current),
+            vec![
+                PathBuf::from(r"C:\Program Files (x86)\Steam"),
+                PathBuf::from(r"D:\SteamLibrary"),
+            ]
+        );
+
+        let legacy = "\"LibraryFolders\"\n{\n\t\"TimeNextStatsReport\"\t\"1\"\n\t\"1\"\t\"/mnt/games/Steam\"\n}\n";
+        assert_eq!(
+            parse_libraryfolders(legacy),
+            vec![PathBuf::from("/mnt/games/Steam")]
+        );
+    }
+
+    #[test]
+    fn parses_appmanifest_with_comments_and_conditionals() {
+        let acf = r#"// generado por Steam
+"AppState"
+{
+	"appid"		"620"
+	"name"		"Portal 2"
+	"installdir"		"Portal 2"
+	"UserConfig" { "language" "spanish" [$WIN32] }
+}"#;
+        let manifest = parse_appmanifest(acf).expect("acf válido");
+        assert_eq!(manifest.app_id, "620");
+        assert_eq!(manifest.install_dir, "Portal 2");
+    }
+
+    #[test]
+    fn parses_binary_shortcuts() {
+        let mut bytes = Vec::new();
+        bytes.push(BIN_MAP);
+        bytes.extend_from_slice(b"shortcuts\0");
+        bytes.push(BIN_MAP);
+        bytes.extend_from_slice(b"0\0");
+        bytes.push(BIN_INT32);
+        bytes.extend_from_slice(b"appid\0");
+        bytes.extend_from_slice(&(-1_234_567_i32).to_le_bytes());
+        bytes.push(BIN_STRING);
+        bytes.extend_from_slice(b"AppName\0Dolphin\0");
+        bytes.push(BIN_MAP);
+        bytes.extend_from_slice(b"tags\0");
+        bytes.push(BIN_END);
+        bytes.push(BIN_END);
+        bytes.push(BIN_END);
+        bytes.push(BIN_END);
+
+        let doc = parse_binary(&bytes).expect("binario válido");
+        let shortcut = doc
+            .get_map("shortcuts")
+            .and_then(|s| s.get_map("0"))
+            .expect("atajo 0");
+        assert_eq!(shortcut.get_str("appname"), Some("Dolphin"));
+        assert_eq!(
+            shortcut.get("appid").and_then(VdfValue::as_u64),
+            Some((-1_234_567_i32) as u32 as u64)
+        );
+        assert!(parse_binary(&bytes[..bytes.len() - 3]).is_err());
+    }
+}
@@ -0,0 +1,578 @@
+//! Backend de la API de SaveCloud.
+//!
+//! La API guarda los objetos en S3 y devuelve URLs prefirmadas para cada
+//! transferencia; las operaciones de metadatos (listar, borrar, renombrar juegos y
+//! backups, multipart) son endpoints propios bajo `/saves`.
+
+use super::{RemoteObject, StorageBackend, StorageError, StorageResult, TransferTarget};
+use crate::network::API_CLIENT;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Máximo de elementos por petición de URLs en bloque.
+const URL_BATCH_SIZE: usize = 500;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UploadUrlItemRequest<'a> {
+    game_id: &'a str,
+    filename: &'a str,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UploadUrlResultItem {
+    upload_url: String,
+    filename: String,
+}
+
+#[derive(Deserialize)]
+struct UploadUrlsResponse {
+    urls: Vec<UploadUrlResultItem>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DownloadUrlItemRequest<'a> {
+    game_id: &'a str,
+    key: &'a str,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DownloadUrlResultItem {
+    download_url: String,
+    key: String,
+}
+
+#[derive(Deserialize)]
+struct DownloadUrlsResponse {
+    urls: Vec<DownloadUrlResultItem>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RemoteSaveDto {
+    game_id: String,
+    key: String,
+    last_modified: String,
+    #[serde(default)]
+    size: Option<u64>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BackupDto {
+    key: String,
+    last_modified: String,
+    size: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct BackupsResponse {
+    backups: Vec<BackupDto>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MultipartInitResponse {
+    upload_id: String,
+    key: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PartUrlsRequest<'a> {
+    key: &'a str,
+    upload_id: &'a str,
+    part_numbers: &'a [u32],
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PartUrlItem {
+    part_number: u32,
+    url: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PartUrlsResponse {
+    part_urls: Vec<PartUrlItem>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CompletedPartReq<'a> {
+    part_number: u32,
+    etag: &'a str,
+}
+
+#[derive(Clone)]
+pub struct ApiBackend {
+    base_url: String,
+    user_id: String,
+    api_key: String,
+}
+
+impl ApiBackend {
+    pub fn new(base_url: String, user_id: String, api_key: String) -> Self {
+        Self {
+            base_url,
+            user_id,
+            api_key,
+        }
+    }
+
+    /// Petición JSON en nombre de `as_user`; devuelve la respuesta solo si es 2xx.
+    async fn call(
+        &self,
+        as_user: &str,
+        method: &str,
+        path: &str,
+        body: Option<serde_json::Value>,
+        context: &str,
+    ) -> StorageResult<reqwest::Response> {
+        let url = format!("{}/saves{}", self.base_url.trim_end_matches('/'), path);
+        let method: reqwest::Method = method
+            .parse()
+            .map_err(|_| StorageError::Config(format!("Método HTTP inválido: {}", method)))?;
+        let mut req = API_CLIENT
+            .request(method, &url)
+            .header("x-user-id", as_user)
+            .header("x-api-key", &self.api_key);
+        if let Some(body) = body {
+            req = req.json(&body);
+        }
+        let res = req.send().await?;
+        if !res.status().is_success() {
+            let status = res.status().as_u16();
+            let text = res.text().await.unwrap_or_default();
+            crate::commands::logs::sync_logger::log_api(context, path, status, &text);
+            return Err(StorageError::Status {
+                context: format!("API {}", context),
+                status,
+                body: text,
+            });
+        }
+        Ok(res)
+    }
+
+    async fn json<T: serde::de::DeserializeOwned>(
+        res: reqwest::Response,
+        context: &str,
+    ) -> StorageResult<T> {
+        res.json()
+            .await
+            .map_err(|e| StorageError::InvalidResponse(format!("{}: {}", context, e)))
+    }
+
+    /// URL prefirmada de subida para un archivo suelto, usada para detectar el
+    /// tipo de endpoint S3 (acelerado o estándar) que expone la API.
+    pub async fn probe_upload_url(&self, game_id: &str, filename: &str) -> StorageResult<String> {
+        let body = serde_json::json!({ "gameId": game_id, "filename": filename });
+        let res = self
+            .call(
+                &self.user_id,
+                "POST",
+                "/upload-url",
+                Some(body),
+                "upload-url",
+            )
+            .await?;
+        let json: serde_json::Value = Self::json(res, "upload-url").await?;
+        json.get("uploadUrl")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| StorageError::InvalidResponse("API no devolvió uploadUrl".into()))
+    }
+}
+
+impl StorageBackend for ApiBackend {
+    fn name(&self) -> &'static str {
+        "API de SaveCloud"
+    }
+
+    fn user_id(&self) -> &str {
+        &self.user_id
+    }
+
+    async fn list(&self, owner: &str) -> StorageResult<Vec<RemoteObject>> {
+        let res = self.call(owner, "GET", "", None, "GET /saves").await?;
+        let raw: Vec<RemoteSaveDto> = Self::json(res, "GET /saves").await?;
+        Ok(raw
+            .into_iter()
+            .map(|s| RemoteObject {
+                game_id: s.game_id,
+                key: s.key,
+                last_modified: s.last_modified,
+                size: s.size,
+            })
+            .collect())
+    }
+
+    async fn put_targets(
+        &self,
+        game_id: &str,
+        filenames: &[String],
+    ) -> StorageResult<Vec<TransferTarget>> {
+        let mut by_filename: HashMap<String, String> = HashMap::with_capacity(filenames.len());
+        for chunk in filenames.chunks(URL_BATCH_SIZE) {
+            let items: Vec<UploadUrlItemRequest> = chunk
+                .iter()
+                .map(|f| UploadUrlItemRequest {
+                    game_id,
+                    filename: f,
+                })
+                .collect();
+            let res = self
+                .call(
+                    &self.user_id,
+                    "POST",
+                    "/upload-urls",
+                    Some(serde_json::json!({ "items": items })),
+                    "upload-urls",
+                )
+                .await?;
+            let parsed: UploadUrlsResponse = Self::json(res, "upload-urls").await?;
+            by_filename.extend(parsed.urls.into_iter().map(|u| (u.filename, u.upload_url)));
+        }
+        filenames
+            .iter()
+            .map(|f| {
+                by_filename
+                    .remove(f)
+                    .map(TransferTarget::http)
+                    .ok_or_else(|| {
+                        StorageError::InvalidResponse(format!("Sin URL de subida: {}", f))
+                    })
+            })
+            .collect()
+    }
+
+    async fn get_targets(
+        &self,
+        owner: &str,
+        items: &[(String, String)],
+    ) -> StorageResult<Vec<TransferTarget>> {
+        let mut by_key: HashMap<String, String> = HashMap::with_capacity(items.len());
+        for chunk in items.chunks(URL_BATCH_SIZE) {
+            let req_items: Vec<DownloadUrlItemRequest> = chunk
+                .iter()
+                .map(|(game_id, key)| DownloadUrlItemRequest { game_id, key })
+                .collect();
+            let res = self
+                .call(
+                    owner,
+                    "POST",
+                    "/download-urls",
+                    Some(serde_json::json!({ "items": req_items })),
+                    "download-urls",
+                )
+                .await?;
+            let parsed: DownloadUrlsResponse = Self::json(res, "download-urls").await?;
+            by_key.extend(parsed.urls.into_iter().map(|u| (u.key, u.download_url)));
+        }
+        items
+            .iter()
+            .map(|(_, key)| {
+                by_key
+                    .get(key)
+                    .cloned()
+                    .map(TransferTarget::http)
+                    .ok_or_else(|| {
+                        StorageError::InvalidResponse(format!("Sin URL de descarga: {}", key))
+                    })
+            })
+            .collect()
+    }
+
+    async fn delete(&self, game_id: &str, key: &str) -> StorageResult<()> {
+        let body = serde_json::json!({ "gameId": game_id, "key": key });
+        self.call(
+            &self.user_id,
+            "DELETE",
+            "/backup",
+            Some(body),
+            "delete backup",
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// La API solo renombra dentro de la misma carpeta: se envía el último segmento
+    /// de `new_key` como `newFilename`.
+    async fn rename(&self, game_id: &str, key: &str, new_key: &str) -> StorageResult<()> {
+        let new_filename = new_key.rsplit('/').next().unwrap_or(new_key);
+        let body = serde_json::json!({
+            "gameId": game_id,
+            "key": key,
+            "newFilename": new_filename
+        });
+        self.call(
+            &self.user_id,
+            "PATCH",
+            "/backup",
+            Some(body),
+            "rename backup",
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn list_backups(&self, game_id: &str) -> StorageResult<Vec<RemoteObject>> {
+        let path = format!("/backups?gameId={}", urlencoding::encode(game_id));
+        let res = self
+            .call(&self.user_id, "GET", &path, None, "backups")
+            .await?;
+        let parsed: BackupsResponse = Self::json(res, "backups").await?;
+        Ok(parsed
+            .backups
+            .into_iter()
+            .map(|b| RemoteObject {
+                game_id: game_id.to_string(),
+                key: b.key,
+                last_modified: b.last_modified,
+                size: b.size,
+            })
+            .collect())
+    }
+
+    async fn delete_game(&self, game_id: &str) -> StorageResult<()> {
+        let body = serde_json::json!({ "gameId": game_id });
+        self.call(
+            &self.user_id,
+            "POST",
+            "/delete-game",
+            Some(body),
+            "delete-game",
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn rename_game(&self, old_game_id: &str, new_game_id: &str) -> StorageResult<()> {
+        let body = serde_json::json!({ "oldGameId": old_game_id, "newGameId": new_game_id });
+        self.call(
+            &self.user_id,
+            "POST",
+            "/rename-game",
+            Some(body),
+            "rename-game",
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn multipart_init(
+        &self,
+        game_id: &str,
+        filename: &str,
+    ) -> StorageResult<(String, String)> {
+        let body = serde_json::json!({ "gameId": game_id, "filename": filename });
+        let res = self
+            .call(
+                &self.user_id,
+                "POST",
+                "/multipart/init",
+                Some(body),
+                "multipart/init",
+            )
+            .await?;
+        let parsed: MultipartInitResponse = Self::json(res, "multipart/init").await?;
+        Ok((parsed.upload_id, parsed.key))
+    }
+
+    async fn multipart_part_targets(
+        &self,
+        key: &str,
+        upload_id: &str,
+        part_numbers: &[u32],
+    ) -> StorageResult<Vec<(u32, TransferTarget)>> {
+        if part_numbers.is_empty() {
+            return Ok(Vec::new());
+        }
+        let body = serde_json::to_value(PartUrlsRequest {
+            key,
+            upload_id,
+            part_numbers,
+        })
+        .map_err(|e| StorageError::InvalidResponse(e.to_string()))?;
+        let res = self
+            .call(
+                &self.user_id,
+                "POST",
+                "/multipart/part-urls",
+                Some(body),
+                "multipart/part-urls",
+            )
+            .await?;
+        let parsed: PartUrlsResponse = Self::json(res, "multipart/part-urls").await?;
+        Ok(parsed
+            .part_urls
+            .into_iter()
+            .map(|p| (p.part_number, TransferTarget::http(p.url)))
+            .collect())
+    }
+
+    async fn multipart_complete(
+        &self,
+        key: &str,
+        upload_id: &str,
+        parts: &[(u32, String)],
+    ) -> StorageResult<()> {
+        let parts: Vec<CompletedPartReq> = parts
+            .iter()
+            .map(|(n, etag)| CompletedPartReq {
+                part_number: *n,
+                etag,
+            })
+            .collect();
+        let body = serde_json::json!({ "key": key, "uploadId": upload_id, "parts": parts });
+        self.call(
+            &self.user_id,
+            "POST",
+            "/multipart/complete",
+            Some(body),
+            "multipart/complete",
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn multipart_abort(&self, key: &str, upload_id: &str) -> StorageResult<()> {
+        let body = serde_json::json!({ "key": key, "uploadId": upload_id });
+        self.call(
+            &self.user_id,
+            "POST",
+            "/multipart/abort",
+            Some(body),
+            "multipart/abort",
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::mock_api::{Fault, MockApi};
+    use bytes::Bytes;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn upload_list_and_download_roundtrip() {
+        let api = MockApi::start().await;
+        let backend = api.backend("user1");
+
+        let files = vec!["slot 1.sav".to_string(), "sub/dir.sav".to_string()];
+        let targets = backend.put_targets("My Game", &files).await.unwrap();
+        for (target, name) in targets.iter().zip(&files) {
+            target
+                .put(
+                    Bytes::from(name.clone().into_bytes()),
+                    "application/octet-stream",
+                )
+                .await
+                .unwrap();
+        }
+
+        let mut listed = backend.list("user1").await.unwrap();
+        listed.sort_by(|a, b| a.key.cmp(&b.key));
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0].key, "user1/My Game/slot 1.sav");
+        assert_eq!(listed[1].filename(), "sub/dir.sav");
+
+        let items: Vec<(String, String)> = listed
+            .iter()
+            .map(|o| (o.game_id.clone(), o.key.clone()))
+            .collect();
+        let downloads = backend.get_targets("user1", &items).await.unwrap();
+        let body = downloads[0].read_all().await.unwrap();
+        assert_eq!(&body[..], b"slot 1.sav");
+    }
+
+    #[tokio::test]
+    async fn multipart_upload_assembles_parts_in_order() {
+        let api = MockApi::start().await;
+        let backend = api.backend("user1");
+
+        let (upload_id, key) = backend
+            .multipart_init("game", "backups/big.tar")
+            .await
+            .unwrap();
+        let targets = backend
+            .multipart_part_targets(&key, &upload_id, &[2, 1])
+            .await
+            .unwrap();
+        let mut parts = Vec::new();
+        for (n, target) in targets {
+            let etag = target
+                .put(
+                    Bytes::from(format!("part{}-", n)),
+                    "application/octet-stream",
+                )
+                .await
+                .unwrap();
+            parts.push((n, etag));
+        }
+        backend
+            .multipart_complete(&key, &upload_id, &parts)
+            .await
+            .unwrap();
+
+        assert_eq!(api.object(&key).unwrap(), b"part1-part2-");
+        assert_eq!(api.open_uploads(), 0);
+        let backups = backend.list_backups("game").await.unwrap();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(backups[0].key, key);
+    }
+
+    #[tokio::test]
+    async fn injected_faults_surface_as_errors() {
+        let api = MockApi::start().await;
+        let backend = api.backend("user1");
+        api.put_object("user1/game/save.dat", &[7u8; 4096]);
+
+        api.inject("/saves/upload-urls", Fault::Status(503), 1);
+        let err = backend
+            .put_targets("game", &["a.sav".to_string()])
+            .await
+            .unwrap_err();
+        assert!(matches!(err, StorageError::Status { status: 503, .. }));
+        // El fallo solo afecta a una petición.
+        backend
+            .put_targets("game", &["a.sav".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(api.hits("/saves/upload-urls"), 2);
+
+        let items = [("game".to_string(), "user1/game/save.dat".to_string())];
+        let target = backend
+            .get_targets("user1", &items)
+            .await
+            .unwrap()
+            .remove(0);
+        api.inject("/objects/", Fault::Truncate(100), 1);
+        assert!(target.read_all().await.is_err());
+        assert_eq!(target.read_all().await.unwrap().len(), 4096);
+
+        api.inject("/saves", Fault::Latency(Duration::from_millis(50)), 1);
+        assert_eq!(backend.list("user1").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn rejects_keys_of_other_users() {
+        let api = MockApi::start().await;
+        api.put_object("user2/game/save.dat", b"ajeno");
+
+        let err = api
+            .backend("user1")
+            .delete("game", "user2/game/save.dat")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, StorageError::Status { status: 400, .. }));
+        assert!(api.object("user2/game/save.dat").is_some());
+    }
+}
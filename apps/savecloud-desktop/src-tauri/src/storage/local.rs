@@ -0,0 +1,340 @@
+//! Backend de carpeta local o de red (disco externo, NAS montado, carpeta de Syncthing...).
+//!
+//! Cada clave es una ruta relativa a la raíz: `<raíz>/<userId>/<gameId>/<archivo>`.
+//! Las subidas multipart escriben cada parte en `<raíz>/.savecloud-multipart/<uploadId>/`
+//! y al completar se concatenan en el archivo final.
+
+use super::{
+    object_key, RemoteObject, StorageBackend, StorageError, StorageResult, TransferTarget,
+};
+use chrono::{DateTime, Utc};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Carpeta (dentro de la raíz) donde se acumulan las partes multipart pendientes.
+const MULTIPART_DIR: &str = ".savecloud-multipart";
+
+/// Contador para distinguir subidas iniciadas en el mismo instante.
+static UPLOAD_SEQ: AtomicU32 = AtomicU32::new(0);
+
+#[derive(Clone)]
+pub struct LocalBackend {
+    root: PathBuf,
+    user_id: String,
+}
+
+impl LocalBackend {
+    pub fn new(root: PathBuf, user_id: String) -> Self {
+        Self { root, user_id }
+    }
+
+    /// Ruta de una clave; rechaza segmentos que saldrían de la raíz.
+    fn path_for(&self, key: &str) -> StorageResult<PathBuf> {
+        let relative = Path::new(key);
+        if relative
+            .components()
+            .any(|c| !matches!(c, Component::Normal(_)))
+        {
+            return Err(StorageError::Config(format!("Clave no válida: {}", key)));
+        }
+        Ok(self.root.join(relative))
+    }
+
+    fn ensure_root(&self) -> StorageResult<()> {
+        if self.root.is_dir() {
+            Ok(())
+        } else {
+            Err(StorageError::Config(format!(
+                "La carpeta de almacenamiento no existe: {}",
+                self.root.display()
+            )))
+        }
+    }
+
+    fn staging_dir(&self, upload_id: &str) -> StorageResult<PathBuf> {
+        if upload_id.is_empty()
+            || !upload_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-')
+        {
+            return Err(StorageError::Config(format!(
+                "uploadId no válido: {}",
+                upload_id
+            )));
+        }
+        Ok(self.root.join(MULTIPART_DIR).join(upload_id))
+    }
+}
+
+/// Objetos bajo `<raíz>/<owner>`, con la fecha de modificación en RFC 3339.
+fn walk_objects(root: &Path, owner: &str) -> Vec<RemoteObject> {
+    let owner_dir = root.join(owner);
+    walkdir::WalkDir::new(&owner_dir)
+        .into_iter()
+        .flatten()
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| !e.file_name().to_string_lossy().ends_with(".savecloud-tmp"))
+        .filter_map(|e| {
+            let relative = e.path().strip_prefix(root).ok()?;
+            let key = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            let meta = e.metadata().ok()?;
+            let last_modified = meta
+                .modified()
+                .ok()
+                .map(|t| DateTime::<Utc>::from(t).to_rfc3339())
+                .unwrap_or_default();
+            RemoteObject::from_key(key, last_modified, Some(meta.len()))
+        })
+        .collect()
+}
+
+impl StorageBackend for LocalBackend {
+    fn name(&self) -> &'static str {
+        "Carpeta local"
+    }
+
+    fn user_id(&self) -> &str {
+        &self.user_id
+    }
+
+    async fn list(&self, owner: &str) -> StorageResult<Vec<RemoteObject>> {
+        self.ensure_root()?;
+        let root = self.root.clone();
+        let owner = owner.to_string();
+        tokio::task::spawn_blocking(move || walk_objects(&root, &owner))
+            .await
+            .map_err(|e| StorageError::Io(std::io::Error::other(e.to_string())))
+    }
+
+    async fn put_targets(
+        &self,
+        game_id: &str,
+        filenames: &[String],
+    ) -> StorageResult<Vec<TransferTarget>> {
+        self.ensure_root()?;
+        filenames
+            .iter()
+            .map(|f| {
+                self.path_for(&object_key(&self.user_id, game_id, f))
+                    .map(TransferTarget::File)
+            })
+            .collect()
+    }
+
+    async fn get_targets(
+        &self,
+        _owner: &str,
+        items: &[(String, String)],
+    ) -> StorageResult<Vec<TransferTarget>> {
+        self.ensure_root()?;
+        items
+            .iter()
+            .map(|(_, key)| self.path_for(key).map(TransferTarget::File))
+            .collect()
+    }
+
+    async fn delete(&self, _game_id: &str, key: &str) -> StorageResult<()> {
+        match tokio::fs::remove_file(self.path_for(key)?).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    async fn rename(&self, _game_id: &str, key: &str, new_key: &str) -> StorageResult<()> {
+        let from = self.path_for(key)?;
+        let to = self.path_for(new_key)?;
+        if let Some(parent) = to.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::rename(from, to).await?;
+        Ok(())
+    }
+
+    async fn delete_game(&self, game_id: &str) -> StorageResult<()> {
+        let dir = self.path_for(&format!("{}/{}", self.user_id, game_id))?;
+        match tokio::fs::remove_dir_all(dir).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    async fn rename_game(&self, old_game_id: &str, new_game_id: &str) -> StorageResult<()> {
+        let from = self.path_for(&format!("{}/{}", self.user_id, old_game_id))?;
+        let to = self.path_for(&format!("{}/{}", self.user_id, new_game_id))?;
+        if !from.is_dir() {
+            return Ok(());
+        }
+        if to.exists() {
+            return Err(StorageError::Config(format!(
+                "Ya existe una carpeta para {}",
+                new_game_id
+            )));
+        }
+        tokio::fs::rename(from, to).await?;
+        Ok(())
+    }
+
+    async fn multipart_init(
+        &self,
+        game_id: &str,
+        filename: &str,
+    ) -> StorageResult<(String, String)> {
+        self.ensure_root()?;
+        let key = object_key(&self.user_id, game_id, filename);
+        self.path_for(&key)?;
+        let upload_id = format!(
+            "{}-{}",
+            Utc::now().format("%Y%m%d%H%M%S%f"),
+            UPLOAD_SEQ.fetch_add(1, Ordering::Relaxed)
+        );
+        tokio::fs::create_dir_all(self.staging_dir(&upload_id)?).await?;
+        Ok((upload_id, key))
+    }
+
+    async fn multipart_part_targets(
+        &self,
+        _key: &str,
+        upload_id: &str,
+        part_numbers: &[u32],
+    ) -> StorageResult<Vec<(u32, TransferTarget)>> {
+        let dir = self.staging_dir(upload_id)?;
+        Ok(part_numbers
+            .iter()
+            .map(|n| (*n, TransferTarget::File(dir.join(n.to_string()))))
+            .collect())
+    }
+
+    async fn multipart_complete(
+        &self,
+        key: &str,
+        upload_id: &str,
+        parts: &[(u32, String)],
+    ) -> StorageResult<()> {
+        let dir = self.staging_dir(upload_id)?;
+        let dest = self.path_for(key)?;
+        let mut numbers: Vec<u32> = parts.iter().map(|(n, _)| *n).collect();
+        numbers.sort_unstable();
+
+        tokio::task::spawn_blocking(move || -> std::io::Result<()> {
+            if let Some(parent) = dest.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let mut tmp_name = dest.file_name().unwrap_or_default().to_os_string();
+            tmp_name.push(".savecloud-tmp");
+            let tmp = dest.with_file_name(tmp_name);
+            {
+                let mut out = std::io::BufWriter::new(std::fs::File::create(&tmp)?);
+                for n in numbers {
+                    let mut part = std::fs::File::open(dir.join(n.to_string()))?;
+                    std::io::copy(&mut part, &mut out)?;
+                }
+                std::io::Write::flush(&mut out)?;
+            }
+            std::fs::rename(&tmp, &dest)?;
+            std::fs::remove_dir_all(&dir)
+        })
+        .await
+        .map_err(|e| StorageError::Io(std::io::Error::other(e.to_string())))??;
+        Ok(())
+    }
+
+    async fn multipart_abort(&self, _key: &str, upload_id: &str) -> StorageResult<()> {
+        match tokio::fs::remove_dir_all(self.staging_dir(upload_id)?).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file_target(target: &TransferTarget) -> &Path {
+        match target {
+            TransferTarget::File(path) => path,
+            TransferTarget::Http { .. } => panic!("el backend local solo da rutas"),
+        }
+    }
+
+    #[tokio::test]
+    async fn put_list_get_and_delete_round_trip() {
+        let root = tempfile::tempdir().unwrap();
+        let backend = LocalBackend::new(root.path().to_path_buf(), "user1".into());
+
+        let puts = backend
+            .put_targets("Juego", &["perfil\\slot1.sav".to_string()])
+            .await
+            .unwrap();
+        let path = file_target(&puts[0]);
+        assert_eq!(path, root.path().join("user1/Juego/perfil/slot1.sav"));
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, b"partida").unwrap();
+
+        let objects = backend.list("user1").await.unwrap();
+        assert_eq!(objects.len(), 1);
+        assert_eq!(objects[0].key, "user1/Juego/perfil/slot1.sav");
+        assert_eq!(objects[0].game_id, "Juego");
+        assert_eq!(objects[0].size, Some(7));
+
+        let key = objects[0].key.clone();
+        let gets = backend
+            .get_targets("user1", &[("Juego".to_string(), key.clone())])
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(file_target(&gets[0])).unwrap(), b"partida");
+
+        backend.delete("Juego", &key).await.unwrap();
+        assert!(backend.list("user1").await.unwrap().is_empty());
+        // Borrar lo que ya no existe no es un error.
+        backend.delete("Juego", &key).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn multipart_parts_are_joined_in_order() {
+        let root = tempfile::tempdir().unwrap();
+        let backend = LocalBackend::new(root.path().to_path_buf(), "user1".into());
+
+        let (upload_id, key) = backend
+            .multipart_init("Juego", "backups/copia.tar")
+            .await
+            .unwrap();
+        let parts = backend
+            .multipart_part_targets(&key, &upload_id, &[1, 2])
+            .await
+            .unwrap();
+        std::fs::write(file_target(&parts[1].1), b"mundo").unwrap();
+        std::fs::write(file_target(&parts[0].1), b"hola ").unwrap();
+        backend
+            .multipart_complete(&key, &upload_id, &[(2, String::new()), (1, String::new())])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            std::fs::read(root.path().join("user1/Juego/backups/copia.tar")).unwrap(),
+            b"hola mundo"
+        );
+        assert!(!root.path().join(MULTIPART_DIR).join(&upload_id).exists());
+    }
+
+    #[test]
+    fn path_for_rejects_keys_outside_the_root() {
+        let backend = LocalBackend::new(PathBuf::from("/srv/saves"), "user1".into());
+        assert!(backend.path_for("user1/Juego/slot1.sav").is_ok());
+        for key in ["../fuera.sav", "user1/../../fuera.sav", "/etc/passwd"] {
+            assert!(backend.path_for(key).is_err(), "{} debería rechazarse", key);
+        }
+    }
+
+    #[test]
+    fn staging_dir_rejects_unexpected_upload_ids() {
+        let backend = LocalBackend::new(PathBuf::from("/srv/saves"), "user1".into());
+        assert!(backend.staging_dir("20240101-0").is_ok());
+        assert!(backend.staging_dir("../x").is_err());
+        assert!(backend.staging_dir("").is_err());
+    }
+}
@@ -0,0 +1,600 @@
+//! Backends de almacenamiento remoto de los guardados.
+//!
+//! Todas las transferencias (archivos sueltos, multipart, backups empaquetados,
+//! torrents y copia de la configuración) pasan por [`StorageBackend`], de modo que
+//! cada perfil elige dónde viven sus guardados:
+//!
+//! - [`api::ApiBackend`]: API de SaveCloud (`/saves`) con URLs prefirmadas de S3.
+//! - [`s3::S3Backend`]: bucket S3 compatible (MinIO, R2...) firmado en la app con SigV4.
+//! - [`local::LocalBackend`]: carpeta local o de red (NAS).
+//! - [`webdav::WebDavBackend`]: servidor WebDAV (Nextcloud, ownCloud...).
+//!
+//! Las claves siguen siempre el esquema `<userId>/<gameId>/<archivo>`. Las operaciones
+//! de datos no mueven bytes: devuelven [`TransferTarget`]s (URL firmada o ruta local)
+//! que los módulos de sincronización recorren con su propia concurrencia y progreso.
+
+pub mod api;
+pub mod local;
+pub mod s3;
+mod sigv4;
+pub mod webdav;
+mod xml;
+
+use crate::config::StorageBackendConfig;
+use crate::network::DATA_CLIENT;
+use bytes::Bytes;
+use futures_util::stream::{BoxStream, StreamExt};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Prefijo de los backups empaquetados dentro de la carpeta de un juego.
+pub const BACKUPS_PREFIX: &str = "backups/";
+
+/// Prefijo de los metadatos de la app (procedencia de las subidas) dentro de la carpeta de un juego.
+pub const META_PREFIX: &str = ".savecloud/";
+
+#[derive(Debug, Error)]
+pub enum StorageError {
+    #[error("{0}")]
+    Config(String),
+
+    #[error("Error de red: {0}")]
+    Network(#[from] reqwest::Error),
+
+    #[error("{context}: HTTP {status} {body}")]
+    Status {
+        context: String,
+        status: u16,
+        body: String,
+    },
+
+    #[error("Error de E/S: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Respuesta inválida: {0}")]
+    InvalidResponse(String),
+
+    #[error("{backend} no soporta {operation}")]
+    Unsupported {
+        backend: &'static str,
+        operation: &'static str,
+    },
+}
+
+impl StorageError {
+    /// El objeto no existe (404 del backend o archivo ausente en una carpeta local).
+    pub fn is_not_found(&self) -> bool {
+        match self {
+            StorageError::Status { status, .. } => *status == 404,
+            StorageError::Io(e) => e.kind() == std::io::ErrorKind::NotFound,
+            _ => false,
+        }
+    }
+}
+
+impl From<StorageError> for String {
+    fn from(e: StorageError) -> Self {
+        e.to_string()
+    }
+}
+
+pub type StorageResult<T> = Result<T, StorageError>;
+
+/// Devuelve la respuesta si es 2xx; si no, un [`StorageError::Status`] con el cuerpo.
+pub(crate) async fn check_status(
+    res: reqwest::Response,
+    context: &str,
+) -> StorageResult<reqwest::Response> {
+    if res.status().is_success() {
+        return Ok(res);
+    }
+    let status = res.status().as_u16();
+    let body = res.text().await.unwrap_or_default();
+    Err(StorageError::Status {
+        context: context.to_string(),
+        status,
+        body,
+    })
+}
+
+/// Objeto remoto listado por un backend.
+#[derive(Debug, Clone)]
+pub struct RemoteObject {
+    pub game_id: String,
+    /// Clave completa: `<userId>/<gameId>/<archivo>`.
+    pub key: String,
+    /// Fecha RFC 3339 de la última modificación.
+    pub last_modified: String,
+    pub size: Option<u64>,
+}
+
+impl RemoteObject {
+    /// Ruta del archivo dentro de la carpeta del juego (lo que sigue a `<userId>/<gameId>/`).
+    pub fn filename(&self) -> String {
+        let parts: Vec<&str> = self.key.split('/').collect();
+        if parts.len() >= 3 {
+            parts[2..].join("/")
+        } else {
+            self.key.clone()
+        }
+    }
+
+    /// Construye el objeto a partir de su clave; `None` si no sigue el esquema de claves.
+    pub(crate) fn from_key(key: String, last_modified: String, size: Option<u64>) -> Option<Self> {
+        let mut parts = key.splitn(3, '/');
+        let (_user, game_id, filename) = (parts.next()?, parts.next()?, parts.next()?);
+        if game_id.is_empty() || filename.is_empty() {
+            return None;
+        }
+        Some(Self {
+            game_id: game_id.to_string(),
+            key,
+            last_modified,
+            size,
+        })
+    }
+}
+
+/// Clave de un archivo de un juego: `<userId>/<gameId>/<archivo>`.
+pub fn object_key(user_id: &str, game_id: &str, filename: &str) -> String {
+    format!(
+        "{}/{}/{}",
+        user_id,
+        game_id,
+        filename.replace('\\', "/").trim_start_matches('/')
+    )
+}
+
+/// Clave resultante de cambiar el último segmento de `key` por `new_filename`.
+pub fn sibling_key(key: &str, new_filename: &str) -> String {
+    match key.rsplit_once('/') {
+        Some((parent, _)) => format!("{}/{}", parent, new_filename),
+        None => new_filename.to_string(),
+    }
+}
+
+/// Destino concreto de una transferencia.
+#[derive(Debug, Clone)]
+pub enum TransferTarget {
+    /// URL (prefirmada o con cabeceras de autenticación) para `PUT`/`GET`.
+    Http {
+        url: String,
+        headers: Vec<(String, String)>,
+    },
+    /// Archivo en una carpeta local o de red.
+    File(PathBuf),
+}
+
+/// Lectura en streaming de un objeto remoto.
+pub struct DownloadStream {
+    pub size: Option<u64>,
+    pub stream: BoxStream<'static, StorageResult<Bytes>>,
+}
+
+impl TransferTarget {
+    pub fn http(url: impl Into<String>) -> Self {
+        TransferTarget::Http {
+            url: url.into(),
+            headers: Vec::new(),
+        }
+    }
+
+    fn request(&self, method: reqwest::Method) -> Option<reqwest::RequestBuilder> {
+        let TransferTarget::Http { url, headers } = self else {
+            return None;
+        };
+        let mut req = DATA_CLIENT.request(method, url);
+        for (name, value) in headers {
+            req = req.header(name, value);
+        }
+        Some(req)
+    }
+
+    /// Sube el contenido completo y devuelve el ETag (vacío si el destino no lo informa).
+    pub async fn put(&self, body: Bytes, content_type: &str) -> StorageResult<String> {
+        match self {
+            TransferTarget::File(path) => {
+                write_file_atomic(path, &body).await?;
+                Ok(String::new())
+            }
+            TransferTarget::Http { .. } => {
+                let len = body.len();
+                let res = self
+                    .request(reqwest::Method::PUT)
+                    .expect("destino HTTP")
+                    .header("Content-Type", content_type)
+                    .header("Content-Length", len.to_string())
+                    .body(body)
+                    .send()
+                    .await?;
+                let res = check_status(res, "PUT").await?;
+                Ok(res
+                    .headers()
+                    .get("etag")
+                    .and_then(|v| v.to_str().ok())
+                    .unwrap_or("")
+                    .to_string())
+            }
+        }
+    }
+
+    /// Sube un archivo local en streaming, sin cargarlo entero en memoria.
+    pub async fn put_file(&self, source: &Path, content_type: &str) -> StorageResult<String> {
+        match self {
+            TransferTarget::File(path) => {
+                if let Some(parent) = path.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+                let tmp = temp_sibling(path);
+                tokio::fs::copy(source, &tmp).await?;
+                tokio::fs::rename(&tmp, path).await?;
+                Ok(String::new())
+            }
+            TransferTarget::Http { .. } => {
+                let file = tokio::fs::File::open(source).await?;
+                let len = file.metadata().await?.len();
+                let body = reqwest::Body::wrap_stream(tokio_util::io::ReaderStream::new(file));
+                let res = self
+                    .request(reqwest::Method::PUT)
+                    .expect("destino HTTP")
+                    .header("Content-Type", content_type)
+                    .header("Content-Length", len.to_string())
+                    .body(body)
+                    .send()
+                    .await?;
+                let res = check_status(res, "PUT").await?;
+                Ok(res
+                    .headers()
+                    .get("etag")
+                    .and_then(|v| v.to_str().ok())
+                    .unwrap_or("")
+                    .to_string())
+            }
+        }
+    }
+
+    /// Abre el objeto para leerlo por bloques.
+    pub async fn open(&self) -> StorageResult<DownloadStream> {
+        match self {
+            TransferTarget::File(path) => {
+                let file = tokio::fs::File::open(path).await?;
+                let size = file.metadata().await.ok().map(|m| m.len());
+                let stream = tokio_util::io::ReaderStream::new(file)
+                    .map(|chunk| chunk.map_err(StorageError::from))
+                    .boxed();
+                Ok(DownloadStream { size, stream })
+            }
+            TransferTarget::Http { .. } => {
+                let res = self
+                    .request(reqwest::Method::GET)
+                    .expect("destino HTTP")
+                    .send()
+                    .await?;
+                let res = check_status(res, "GET").await?;
+                let size = res.content_length();
+                let stream = res
+                    .bytes_stream()
+                    .map(|chunk| chunk.map_err(StorageError::from))
+                    .boxed();
+                Ok(DownloadStream { size, stream })
+            }
+        }
+    }
+
+    /// Lee el objeto completo en memoria (archivos pequeños: torrents, configuración).
+    pub async fn read_all(&self) -> StorageResult<Bytes> {
+        match self {
+            TransferTarget::File(path) => Ok(Bytes::from(tokio::fs::read(path).await?)),
+            TransferTarget::Http { .. } => {
+                let res = self
+                    .request(reqwest::Method::GET)
+                    .expect("destino HTTP")
+                    .send()
+                    .await?;
+                Ok(check_status(res, "GET").await?.bytes().await?)
+            }
+        }
+    }
+}
+
+/// Ruta temporal junto a `path` para escrituras atómicas.
+fn temp_sibling(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".savecloud-tmp");
+    path.with_file_name(name)
+}
+
+/// Escribe en un temporal y lo renombra, para no dejar archivos a medias.
+async fn write_file_atomic(path: &Path, body: &[u8]) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let tmp = temp_sibling(path);
+    tokio::fs::write(&tmp, body).await?;
+    tokio::fs::rename(&tmp, path).await
+}
+
+/// Operaciones que necesita la sincronización sobre un almacenamiento remoto.
+///
+/// Los métodos con implementación por defecto se apoyan en `list`, `delete` y
+/// `rename`; los backends con endpoints propios (la API) los sobrescriben.
+pub trait StorageBackend {
+    /// Nombre legible del backend para mensajes de error.
+    fn name(&self) -> &'static str;
+
+    /// Usuario del perfil (primer segmento de las claves propias).
+    fn user_id(&self) -> &str;
+
+    /// Si el backend admite subidas multipart. Los que no, reciben archivos enteros.
+    fn supports_multipart(&self) -> bool {
+        true
+    }
+
+    /// Lista todos los objetos de un usuario (el propio o el de un amigo).
+    async fn list(&self, owner: &str) -> StorageResult<Vec<RemoteObject>>;
+
+    /// Destinos de subida para archivos de un juego, en el mismo orden que `filenames`.
+    async fn put_targets(
+        &self,
+        game_id: &str,
+        filenames: &[String],
+    ) -> StorageResult<Vec<TransferTarget>>;
+
+    /// Destinos de descarga para `(gameId, clave)` de `owner`, en el mismo orden.
+    async fn get_targets(
+        &self,
+        owner: &str,
+        items: &[(String, String)],
+    ) -> StorageResult<Vec<TransferTarget>>;
+
+    /// Borra un objeto propio.
+    async fn delete(&self, game_id: &str, key: &str) -> StorageResult<()>;
+
+    /// Mueve un objeto propio a otra clave.
+    async fn rename(&self, game_id: &str, key: &str, new_key: &str) -> StorageResult<()>;
+
+    /// Backups empaquetados (`backups/*.tar`) de un juego.
+    async fn list_backups(&self, game_id: &str) -> StorageResult<Vec<RemoteObject>> {
+        let mut backups: Vec<RemoteObject> = self
+            .list(self.user_id())
+            .await?
+            .into_iter()
+            .filter(|o| {
+                o.game_id.eq_ignore_ascii_case(game_id) && o.filename().starts_with(BACKUPS_PREFIX)
+            })
+            .collect();
+        backups.sort_by(|a, b| b.last_modified.cmp(&a.last_modified));
+        Ok(backups)
+    }
+
+    /// Borra todos los objetos de un juego.
+    async fn delete_game(&self, game_id: &str) -> StorageResult<()> {
+        for object in self.list(self.user_id()).await? {
+            if object.game_id.eq_ignore_ascii_case(game_id) {
+                self.delete(&object.game_id, &object.key).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Mueve todos los objetos de un juego a otro `gameId`.
+    async fn rename_game(&self, old_game_id: &str, new_game_id: &str) -> StorageResult<()> {
+        for object in self.list(self.user_id()).await? {
+            if object.game_id.eq_ignore_ascii_case(old_game_id) {
+                let new_key = object_key(self.user_id(), new_game_id, &object.filename());
+                self.rename(&object.game_id, &object.key, &new_key).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Inicia una subida multipart; devuelve `(upload_id, clave)`.
+    async fn multipart_init(
+        &self,
+        game_id: &str,
+        filename: &str,
+    ) -> StorageResult<(String, String)>;
+
+    /// Destinos de las partes indicadas (numeradas desde 1).
+    async fn multipart_part_targets(
+        &self,
+        key: &str,
+        upload_id: &str,
+        part_numbers: &[u32],
+    ) -> StorageResult<Vec<(u32, TransferTarget)>>;
+
+    /// Une las partes `(número, etag)` en el objeto final.
+    async fn multipart_complete(
+        &self,
+        key: &str,
+        upload_id: &str,
+        parts: &[(u32, String)],
+    ) -> StorageResult<()>;
+
+    /// Descarta una subida multipart y sus partes.
+    async fn multipart_abort(&self, key: &str, upload_id: &str) -> StorageResult<()>;
+}
+
+/// Backend activo del perfil.
+#[derive(Clone)]
+pub enum Storage {
+    Api(api::ApiBackend),
+    S3(s3::S3Backend),
+    Local(local::LocalBackend),
+    WebDav(webdav::WebDavBackend),
+}
+
+/// Delegación de cada método de [`StorageBackend`] en el backend concreto.
+macro_rules! delegate {
+    ($self:ident, $b:ident => $call:expr) => {
+        match $self {
+            Storage::Api($b) => $call,
+            Storage::S3($b) => $call,
+            Storage::Local($b) => $call,
+            Storage::WebDav($b) => $call,
+        }
+    };
+}
+
+impl StorageBackend for Storage {
+    fn name(&self) -> &'static str {
+        delegate!(self, b => b.name())
+    }
+
+    fn user_id(&self) -> &str {
+        delegate!(self, b => b.user_id())
+    }
+
+    fn supports_multipart(&self) -> bool {
+        delegate!(self, b => b.supports_multipart())
+    }
+
+    async fn list(&self, owner: &str) -> StorageResult<Vec<RemoteObject>> {
+        delegate!(self, b => b.list(owner).await)
+    }
+
+    async fn put_targets(
+        &self,
+        game_id: &str,
+        filenames: &[String],
+    ) -> StorageResult<Vec<TransferTarget>> {
+        delegate!(self, b => b.put_targets(game_id, filenames).await)
+    }
+
+    async fn get_targets(
+        &self,
+        owner: &str,
+        items: &[(String, String)],
+    ) -> StorageResult<Vec<TransferTarget>> {
+        delegate!(self, b => b.get_targets(owner, items).await)
+    }
+
+    async fn delete(&self, game_id: &str, key: &str) -> StorageResult<()> {
+        delegate!(self, b => b.delete(game_id, key).await)
+    }
+
+    async fn rename(&self, game_id: &str, key: &str, new_key: &str) -> StorageResult<()> {
+        delegate!(self, b => b.rename(game_id, key, new_key).await)
+    }
+
+    async fn list_backups(&self, game_id: &str) -> StorageResult<Vec<RemoteObject>> {
+        delegate!(self, b => b.list_backups(game_id).await)
+    }
+
+    async fn delete_game(&self, game_id: &str) -> StorageResult<()> {
+        delegate!(self, b => b.delete_game(game_id).await)
+    }
+
+    async fn rename_game(&self, old_game_id: &str, new_game_id: &str) -> StorageResult<()> {
+        delegate!(self, b => b.rename_game(old_game_id, new_game_id).await)
+    }
+
+    async fn multipart_init(
+        &self,
+        game_id: &str,
+        filename: &str,
+    ) -> StorageResult<(String, String)> {
+        delegate!(self, b => b.multipart_init(game_id, filename).await)
+    }
+
+    async fn multipart_part_targets(
+        &self,
+        key: &str,
+        upload_id: &str,
+        part_numbers: &[u32],
+    ) -> StorageResult<Vec<(u32, TransferTarget)>> {
+        delegate!(self, b => b.multipart_part_targets(key, upload_id, part_numbers).await)
+    }
+
+    async fn multipart_complete(
+        &self,
+        key: &str,
+        upload_id: &str,
+        parts: &[(u32, String)],
+    ) -> StorageResult<()> {
+        delegate!(self, b => b.multipart_complete(key, upload_id, parts).await)
+    }
+
+    async fn multipart_abort(&self, key: &str, upload_id: &str) -> StorageResult<()> {
+        delegate!(self, b => b.multipart_abort(key, upload_id).await)
+    }
+}
+
+fn required(value: &str, message: &str) -> StorageResult<String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(StorageError::Config(message.to_string()));
+    }
+    Ok(value.to_string())
+}
+
+impl Storage {
+    /// Construye el backend configurado en el perfil.
+    ///
+    /// # Errors
+    ///
+    /// Devuelve [`StorageError::Config`] si falta algún dato obligatorio del backend.
+    pub fn from_settings(settings: &crate::config::AppSettings) -> StorageResult<Self> {
+        let user_id = required(
+            settings.user_id.as_deref().unwrap_or(""),
+            "Configura userId en Configuración",
+        )?;
+
+        Ok(match &settings.storage {
+            StorageBackendConfig::SaveCloudApi => Storage::Api(api::ApiBackend::new(
+                required(
+                    settings.api_base_url.as_deref().unwrap_or(""),
+                    "Configura apiBaseUrl en Configuración",
+                )?,
+                user_id,
+                settings.api_key.clone().unwrap_or_default(),
+            )),
+            StorageBackendConfig::S3 {
+                endpoint,
+                region,
+                bucket,
+                access_key_id,
+                secret_access_key,
+                path_style,
+            } => Storage::S3(s3::S3Backend::new(
+                required(endpoint, "Configura el endpoint S3")?,
+                region.trim().to_string(),
+                required(bucket, "Configura el bucket S3")?,
+                required(access_key_id, "Configura la access key S3")?,
+                required(
+                    secret_access_key.as_deref().unwrap_or(""),
+                    "Configura la secret key S3",
+                )?,
+                *path_style,
+                user_id,
+            )?),
+            StorageBackendConfig::Local { root } => Storage::Local(local::LocalBackend::new(
+                PathBuf::from(required(root, "Configura la carpeta de almacenamiento")?),
+                user_id,
+            )),
+            StorageBackendConfig::WebDav {
+                url,
+                username,
+                password,
+            } => Storage::WebDav(webdav::WebDavBackend::new(
+                required(url, "Configura la URL WebDAV")?,
+                username.trim().to_string(),
+                password.clone().unwrap_or_default(),
+                user_id,
+            )),
+        })
+    }
+
+    /// Backend del perfil activo, leído de la configuración guardada.
+    pub fn active() -> StorageResult<Self> {
+        Self::from_settings(&crate::config::load_settings())
+    }
+}
+
+/// Comprueba que el backend configurado responde listando los objetos del perfil.
+#[tauri::command]
+pub async fn test_storage_backend() -> Result<usize, String> {
+    let storage = Storage::active()?;
+    let objects = storage.list(storage.user_id()).await?;
+    Ok(objects.len())
+}
@@ -0,0 +1,360 @@
+//! Backend S3 compatible (AWS, MinIO, Cloudflare R2, Wasabi...).
+//!
+//! La app firma directamente con las credenciales del perfil: las transferencias de
+//! datos usan URLs prefirmadas, igual que las que entrega la API, y el resto de
+//! operaciones (listado, multipart, copia y borrado) van firmadas en cabeceras.
+
+use super::sigv4::{self, Credentials, RequestParts};
+use super::{
+    check_status, object_key, xml, RemoteObject, StorageBackend, StorageError, StorageResult,
+    TransferTarget,
+};
+use crate::network::API_CLIENT;
+use chrono::Utc;
+
+/// Validez de las URLs prefirmadas. Holgada para partes multipart lentas.
+const PRESIGN_EXPIRES_SECS: u64 = 6 * 60 * 60;
+
+/// Región de firma si el perfil no indica ninguna (MinIO y R2 la aceptan).
+const DEFAULT_REGION: &str = "us-east-1";
+
+#[derive(Clone)]
+pub struct S3Backend {
+    scheme: String,
+    /// Host del endpoint con puerto si lo tiene (`localhost:9000`).
+    endpoint_host: String,
+    region: String,
+    bucket: String,
+    access_key_id: String,
+    secret_access_key: String,
+    path_style: bool,
+    user_id: String,
+}
+
+impl S3Backend {
+    /// # Errors
+    ///
+    /// Devuelve [`StorageError::Config`] si el endpoint no es una URL válida.
+    pub fn new(
+        endpoint: String,
+        region: String,
+        bucket: String,
+        access_key_id: String,
+        secret_access_key: String,
+        path_style: bool,
+        user_id: String,
+    ) -> StorageResult<Self> {
+        let with_scheme = if endpoint.contains("://") {
+            endpoint
+        } else {
+            format!("https://{}", endpoint)
+        };
+        let url = reqwest::Url::parse(&with_scheme)
+            .map_err(|e| StorageError::Config(format!("Endpoint S3 inválido: {}", e)))?;
+        let host = url
+            .host_str()
+            .ok_or_else(|| StorageError::Config("Endpoint S3 sin host".into()))?;
+        let endpoint_host = match url.port() {
+            Some(port) => format!("{}:{}", host, port),
+            None => host.to_string(),
+        };
+        Ok(Self {
+            scheme: url.scheme().to_string(),
+            endpoint_host,
+            region: if region.is_empty() {
+                DEFAULT_REGION.to_string()
+            } else {
+                region
+            },
+            bucket,
+            access_key_id,
+            secret_access_key,
+            path_style,
+            user_id,
+        })
+    }
+
+    fn credentials(&self) -> Credentials<'_> {
+        Credentials {
+            access_key_id: &self.access_key_id,
+            secret_access_key: &self.secret_access_key,
+            region: &self.region,
+        }
+    }
+
+    /// Host y ruta (sin codificar) de un objeto, o del bucket si `key` es `None`.
+    fn location(&self, key: Option<&str>) -> (String, String) {
+        let key = key.unwrap_or("");
+        if self.path_style {
+            (
+                self.endpoint_host.clone(),
+                format!("/{}/{}", self.bucket, key),
+            )
+        } else {
+            (
+                format!("{}.{}", self.bucket, self.endpoint_host),
+                format!("/{}", key),
+            )
+        }
+    }
+
+    fn presign(&self, method: &str, key: &str, query: &[(String, String)]) -> TransferTarget {
+        let (host, path) = self.location(Some(key));
+        TransferTarget::http(sigv4::presign(
+            &self.credentials(),
+            &RequestParts {
+                method,
+                scheme: &self.scheme,
+                host: &host,
+                path: &path,
+                query,
+            },
+            PRESIGN_EXPIRES_SECS,
+            Utc::now(),
+        ))
+    }
+
+    /// Petición firmada en cabeceras; devuelve la respuesta solo si es 2xx.
+    async fn send_signed(
+        &self,
+        method: reqwest::Method,
+        key: Option<&str>,
+        query: &[(String, String)],
+        body: Vec<u8>,
+        extra_headers: &[(String, String)],
+        context: &str,
+    ) -> StorageResult<reqwest::Response> {
+        let (host, path) = self.location(key);
+        let payload_hash = sigv4::sha256_hex(&body);
+        let (url, headers) = sigv4::sign_headers(
+            &self.credentials(),
+            &RequestParts {
+                method: method.as_str(),
+                scheme: &self.scheme,
+                host: &host,
+                path: &path,
+                query,
+            },
+            &payload_hash,
+            extra_headers,
+            Utc::now(),
+        );
+        let mut req = API_CLIENT.request(method, &url);
+        for (name, value) in headers {
+            req = req.header(name, value);
+        }
+        if !body.is_empty() {
+            req = req.header("Content-Type", "application/xml").body(body);
+        }
+        check_status(req.send().await?, context).await
+    }
+}
+
+fn query(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+impl StorageBackend for S3Backend {
+    fn name(&self) -> &'static str {
+        "S3"
+    }
+
+    fn user_id(&self) -> &str {
+        &self.user_id
+    }
+
+    async fn list(&self, owner: &str) -> StorageResult<Vec<RemoteObject>> {
+        let prefix = format!("{}/", owner);
+        let mut objects = Vec::new();
+        let mut continuation: Option<String> = None;
+        loop {
+            let mut params = query(&[("list-type", "2"), ("prefix", &prefix)]);
+            if let Some(token) = &continuation {
+                params.push(("continuation-token".to_string(), token.clone()));
+            }
+            let res = self
+                .send_signed(
+                    reqwest::Method::GET,
+                    None,
+                    &params,
+                    Vec::new(),
+                    &[],
+                    "S3 ListObjectsV2",
+                )
+                .await?;
+            let body = res.text().await?;
+            for contents in xml::elements(&body, "Contents") {
+                let Some(key) = xml::text(contents, "Key") else {
+                    continue;
+                };
+                let last_modified = xml::text(contents, "LastModified").unwrap_or_default();
+                let size = xml::text(contents, "Size").and_then(|s| s.parse().ok());
+                objects.extend(RemoteObject::from_key(key, last_modified, size));
+            }
+            continuation = xml::text(&body, "NextContinuationToken");
+            if xml::text(&body, "IsTruncated").as_deref() != Some("true") || continuation.is_none()
+            {
+                break;
+            }
+        }
+        Ok(objects)
+    }
+
+    async fn put_targets(
+        &self,
+        game_id: &str,
+        filenames: &[String],
+    ) -> StorageResult<Vec<TransferTarget>> {
+        Ok(filenames
+            .iter()
+            .map(|f| self.presign("PUT", &object_key(&self.user_id, game_id, f), &[]))
+            .collect())
+    }
+
+    async fn get_targets(
+        &self,
+        _owner: &str,
+        items: &[(String, String)],
+    ) -> StorageResult<Vec<TransferTarget>> {
+        Ok(items
+            .iter()
+            .map(|(_, key)| self.presign("GET", key, &[]))
+            .collect())
+    }
+
+    async fn delete(&self, _game_id: &str, key: &str) -> StorageResult<()> {
+        self.send_signed(
+            reqwest::Method::DELETE,
+            Some(key),
+            &[],
+            Vec::new(),
+            &[],
+            "S3 DeleteObject",
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn rename(&self, game_id: &str, key: &str, new_key: &str) -> StorageResult<()> {
+        let source = format!("/{}/{}", self.bucket, sigv4::uri_encode(key, true));
+        let res = self
+            .send_signed(
+                reqwest::Method::PUT,
+                Some(new_key),
+                &[],
+                Vec::new(),
+                &[("x-amz-copy-source".to_string(), source)],
+                "S3 CopyObject",
+            )
+            .await?;
+        // CopyObject puede fallar con 200 y un <Error> en el cuerpo.
+        let body = res.text().await?;
+        if xml::element(&body, "Error").is_some() {
+            return Err(StorageError::InvalidResponse(format!(
+                "S3 CopyObject: {}",
+                xml::text(&body, "Message").unwrap_or(body)
+            )));
+        }
+        self.delete(game_id, key).await
+    }
+
+    async fn multipart_init(
+        &self,
+        game_id: &str,
+        filename: &str,
+    ) -> StorageResult<(String, String)> {
+        let key = object_key(&self.user_id, game_id, filename);
+        let res = self
+            .send_signed(
+                reqwest::Method::POST,
+                Some(&key),
+                &query(&[("uploads", "")]),
+                Vec::new(),
+                &[],
+                "S3 CreateMultipartUpload",
+            )
+            .await?;
+        let body = res.text().await?;
+        let upload_id = xml::text(&body, "UploadId")
+            .ok_or_else(|| StorageError::InvalidResponse("S3 no devolvió UploadId".to_string()))?;
+        Ok((upload_id, key))
+    }
+
+    async fn multipart_part_targets(
+        &self,
+        key: &str,
+        upload_id: &str,
+        part_numbers: &[u32],
+    ) -> StorageResult<Vec<(u32, TransferTarget)>> {
+        Ok(part_numbers
+            .iter()
+            .map(|n| {
+                let params = query(&[("partNumber", &n.to_string()), ("uploadId", upload_id)]);
+                (*n, self.presign("PUT", key, &params))
+            })
+            .collect())
+    }
+
+    async fn multipart_complete(
+        &self,
+        key: &str,
+        upload_id: &str,
+        parts: &[(u32, String)],
+    ) -> StorageResult<()> {
+        // S3 no admite completar sin partes: un archivo vacío se sube como objeto simple.
+        if parts.is_empty() {
+            self.multipart_abort(key, upload_id).await?;
+            self.presign("PUT", key, &[])
+                .put(bytes::Bytes::new(), "application/octet-stream")
+                .await?;
+            return Ok(());
+        }
+
+        let mut sorted = parts.to_vec();
+        sorted.sort_by_key(|(n, _)| *n);
+        let mut body = String::from("<CompleteMultipartUpload>");
+        for (n, etag) in &sorted {
+            body.push_str(&format!(
+                "<Part><PartNumber>{}</PartNumber><ETag>{}</ETag></Part>",
+                n,
+                xml::escape(etag)
+            ));
+        }
+        body.push_str("</CompleteMultipartUpload>");
+
+        let res = self
+            .send_signed(
+                reqwest::Method::POST,
+                Some(key),
+                &query(&[("uploadId", upload_id)]),
+                body.into_bytes(),
+                &[],
+                "S3 CompleteMultipartUpload",
+            )
+            .await?;
+        let body = res.text().await?;
+        if xml::element(&body, "Error").is_some() {
+            return Err(StorageError::InvalidResponse(format!(
+                "S3 CompleteMultipartUpload: {}",
+                xml::text(&body, "Message").unwrap_or(body)
+            )));
+        }
+        Ok(())
+    }
+
+    async fn multipart_abort(&self, key: &str, upload_id: &str) -> StorageResult<()> {
+        self.send_signed(
+            reqwest::Method::DELETE,
+            Some(key),
+            &query(&[("uploadId", upload_id)]),
+            Vec::new(),
+            &[],
+            "S3 AbortMultipartUpload",
+        )
+        .await?;
+        Ok(())
+    }
+}
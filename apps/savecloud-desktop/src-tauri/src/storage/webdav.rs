@@ -0,0 +1,344 @@
+//! Backend WebDAV (Nextcloud, ownCloud, Synology, Apache `mod_dav`...).
+//!
+//! Las claves se traducen a rutas bajo la URL base (`<url>/<userId>/<gameId>/<archivo>`).
+//! Los listados recorren las colecciones con `PROPFIND` de profundidad 1, porque
+//! muchos servidores no permiten `Depth: infinity`. WebDAV no tiene subidas por
+//! partes, así que los archivos grandes se envían enteros en un único `PUT`.
+
+use super::{
+    check_status, object_key, xml, RemoteObject, StorageBackend, StorageError, StorageResult,
+    TransferTarget,
+};
+use crate::network::API_CLIENT;
+use base64::Engine;
+use std::collections::{HashSet, VecDeque};
+
+const PROPFIND_BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:"><d:prop><d:resourcetype/><d:getlastmodified/><d:getcontentlength/></d:prop></d:propfind>"#;
+
+#[derive(Clone)]
+pub struct WebDavBackend {
+    base_url: String,
+    /// Ruta de la URL base, para convertir los `href` de las respuestas en claves.
+    base_path: String,
+    authorization: Option<String>,
+    user_id: String,
+}
+
+/// Entrada de una respuesta `PROPFIND`.
+#[derive(Debug, PartialEq)]
+struct DavEntry {
+    path: String,
+    is_collection: bool,
+    last_modified: String,
+    size: Option<u64>,
+}
+
+/// Interpreta un `multistatus` de `PROPFIND`; las rutas quedan decodificadas y sin `/` final.
+fn parse_multistatus(body: &str) -> Vec<DavEntry> {
+    xml::elements(body, "response")
+        .into_iter()
+        .filter_map(|response| {
+            let href = xml::text(response, "href")?;
+            let raw_path = match href.find("://") {
+                Some(scheme_end) => {
+                    let after = &href[scheme_end + 3..];
+                    after.find('/').map(|i| &after[i..]).unwrap_or("/")
+                }
+                None => href.as_str(),
+            };
+            let path = urlencoding::decode(raw_path)
+                .map(|p| p.into_owned())
+                .unwrap_or_else(|_| raw_path.to_string());
+            let last_modified = xml::text(response, "getlastmodified")
+                .and_then(|d| chrono::DateTime::parse_from_rfc2822(&d).ok())
+                .map(|d| d.to_utc().to_rfc3339())
+                .unwrap_or_default();
+            Some(DavEntry {
+                path: path.trim_end_matches('/').to_string(),
+                is_collection: xml::element(response, "collection").is_some(),
+                last_modified,
+                size: xml::text(response, "getcontentlength").and_then(|s| s.parse().ok()),
+            })
+        })
+        .collect()
+}
+
+fn dav_method(name: &str) -> reqwest::Method {
+    reqwest::Method::from_bytes(name.as_bytes()).expect("método WebDAV válido")
+}
+
+impl WebDavBackend {
+    pub fn new(url: String, username: String, password: String, user_id: String) -> Self {
+        let base_url = url.trim_end_matches('/').to_string();
+        let base_path = reqwest::Url::parse(&base_url)
+            .ok()
+            .and_then(|u| urlencoding::decode(u.path()).ok().map(|p| p.into_owned()))
+            .unwrap_or_default()
+            .trim_end_matches('/')
+            .to_string();
+        let authorization = (!username.is_empty()).then(|| {
+            format!(
+                "Basic {}",
+                base64::engine::general_purpose::STANDARD
+                    .encode(format!("{}:{}", username, password))
+            )
+        });
+        Self {
+            base_url,
+            base_path,
+            authorization,
+            user_id,
+        }
+    }
+
+    fn url_for(&self, key: &str) -> String {
+        let encoded: Vec<String> = key
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .map(|s| urlencoding::encode(s).into_owned())
+            .collect();
+        format!("{}/{}", self.base_url, encoded.join("/"))
+    }
+
+    fn auth_headers(&self) -> Vec<(String, String)> {
+        self.authorization
+            .iter()
+            .map(|a| ("Authorization".to_string(), a.clone()))
+            .collect()
+    }
+
+    fn target(&self, key: &str) -> TransferTarget {
+        TransferTarget::Http {
+            url: self.url_for(key),
+            headers: self.auth_headers(),
+        }
+    }
+
+    fn request(&self, method: reqwest::Method, key: &str) -> reqwest::RequestBuilder {
+        let mut req = API_CLIENT.request(method, self.url_for(key));
+        if let Some(auth) = &self.authorization {
+            req = req.header("Authorization", auth);
+        }
+        req
+    }
+
+    /// Crea las colecciones que faltan hasta la carpeta padre de `key`.
+    async fn ensure_parents(&self, key: &str, created: &mut HashSet<String>) -> StorageResult<()> {
+        let segments: Vec<&str> = key.split('/').filter(|s| !s.is_empty()).collect();
+        let mut current = String::new();
+        for segment in segments.iter().take(segments.len().saturating_sub(1)) {
+            if !current.is_empty() {
+                current.push('/');
+            }
+            current.push_str(segment);
+            if created.contains(&current) {
+                continue;
+            }
+            let res = self.request(dav_method("MKCOL"), &current).send().await?;
+            // 405: la colección ya existe.
+            if !res.status().is_success() && res.status().as_u16() != 405 {
+                check_status(res, "WebDAV MKCOL").await?;
+            }
+            created.insert(current.clone());
+        }
+        Ok(())
+    }
+
+    /// Hijos directos de una colección; `None` si la colección no existe.
+    async fn propfind(&self, collection: &str) -> StorageResult<Option<Vec<DavEntry>>> {
+        let res = self
+            .request(dav_method("PROPFIND"), collection)
+            .header("Depth", "1")
+            .header("Content-Type", "application/xml")
+            .body(PROPFIND_BODY)
+            .send()
+            .await?;
+        if res.status().as_u16() == 404 {
+            return Ok(None);
+        }
+        let body = check_status(res, "WebDAV PROPFIND").await?.text().await?;
+        let own_path = format!("{}/{}", self.base_path, collection.trim_end_matches('/'));
+        Ok(Some(
+            parse_multistatus(&body)
+                .into_iter()
+                .filter(|e| e.path != own_path)
+                .collect(),
+        ))
+    }
+
+    async fn move_to(&self, from: &str, to: &str, overwrite: bool) -> StorageResult<()> {
+        let res = self
+            .request(dav_method("MOVE"), from)
+            .header("Destination", self.url_for(to))
+            .header("Overwrite", if overwrite { "T" } else { "F" })
+            .send()
+            .await?;
+        check_status(res, "WebDAV MOVE").await?;
+        Ok(())
+    }
+
+    async fn remove(&self, key: &str) -> StorageResult<()> {
+        let res = self.request(reqwest::Method::DELETE, key).send().await?;
+        if res.status().as_u16() == 404 {
+            return Ok(());
+        }
+        check_status(res, "WebDAV DELETE").await?;
+        Ok(())
+    }
+}
+
+impl StorageBackend for WebDavBackend {
+    fn name(&self) -> &'static str {
+        "WebDAV"
+    }
+
+    fn user_id(&self) -> &str {
+        &self.user_id
+    }
+
+    fn supports_multipart(&self) -> bool {
+        false
+    }
+
+    async fn list(&self, owner: &str) -> StorageResult<Vec<RemoteObject>> {
+        let prefix = format!("{}/", self.base_path);
+        let mut objects = Vec::new();
+        let mut pending = VecDeque::from([owner.to_string()]);
+        while let Some(collection) = pending.pop_front() {
+            let Some(entries) = self.propfind(&collection).await? else {
+                continue;
+            };
+            for entry in entries {
+                let Some(key) = entry.path.strip_prefix(&prefix) else {
+                    continue;
+                };
+                if entry.is_collection {
+                    pending.push_back(key.to_string());
+                } else {
+                    objects.extend(RemoteObject::from_key(
+                        key.to_string(),
+                        entry.last_modified,
+                        entry.size,
+                    ));
+                }
+            }
+        }
+        Ok(objects)
+    }
+
+    async fn put_targets(
+        &self,
+        game_id: &str,
+        filenames: &[String],
+    ) -> StorageResult<Vec<TransferTarget>> {
+        let mut created = HashSet::new();
+        let mut targets = Vec::with_capacity(filenames.len());
+        for filename in filenames {
+            let key = object_key(&self.user_id, game_id, filename);
+            self.ensure_parents(&key, &mut created).await?;
+            targets.push(self.target(&key));
+        }
+        Ok(targets)
+    }
+
+    async fn get_targets(
+        &self,
+        _owner: &str,
+        items: &[(String, String)],
+    ) -> StorageResult<Vec<TransferTarget>> {
+        Ok(items.iter().map(|(_, key)| self.target(key)).collect())
+    }
+
+    async fn delete(&self, _game_id: &str, key: &str) -> StorageResult<()> {
+        self.remove(key).await
+    }
+
+    async fn rename(&self, _game_id: &str, key: &str, new_key: &str) -> StorageResult<()> {
+        self.ensure_parents(new_key, &mut HashSet::new()).await?;
+        self.move_to(key, new_key, true).await
+    }
+
+    async fn delete_game(&self, game_id: &str) -> StorageResult<()> {
+        self.remove(&format!("{}/{}/", self.user_id, game_id)).await
+    }
+
+    async fn rename_game(&self, old_game_id: &str, new_game_id: &str) -> StorageResult<()> {
+        self.move_to(
+            &format!("{}/{}/", self.user_id, old_game_id),
+            &format!("{}/{}/", self.user_id, new_game_id),
+            false,
+        )
+        .await
+    }
+
+    async fn multipart_init(
+        &self,
+        _game_id: &str,
+        _filename: &str,
+    ) -> StorageResult<(String, String)> {
+        Err(StorageError::Unsupported {
+            backend: self.name(),
+            operation: "subidas multipart",
+        })
+    }
+
+    async fn multipart_part_targets(
+        &self,
+        _key: &str,
+        _upload_id: &str,
+        _part_numbers: &[u32],
+    ) -> StorageResult<Vec<(u32, TransferTarget)>> {
+        Err(StorageError::Unsupported {
+            backend: self.name(),
+            operation: "subidas multipart",
+        })
+    }
+
+    async fn multipart_complete(
+        &self,
+        _key: &str,
+        _upload_id: &str,
+        _parts: &[(u32, String)],
+    ) -> StorageResult<()> {
+        Err(StorageError::Unsupported {
+            backend: self.name(),
+            operation: "subidas multipart",
+        })
+    }
+
+    async fn multipart_abort(&self, _key: &str, _upload_id: &str) -> StorageResult<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_propfind_multistatus() {
+        let body = r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:">
+ <d:response><d:href>/dav/user1/</d:href>
+  <d:propstat><d:prop><d:resourcetype><d:collection/></d:resourcetype></d:prop></d:propstat></d:response>
+ <d:response><d:href>https://nas.local/dav/user1/My%20Game/slot%201.sav</d:href>
+  <d:propstat><d:prop><d:resourcetype/>
+   <d:getlastmodified>Tue, 15 Nov 1994 12:45:26 GMT</d:getlastmodified>
+   <d:getcontentlength>42</d:getcontentlength></d:prop></d:propstat></d:response>
+</d:multistatus>"#;
+        let entries = parse_multistatus(body);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].path, "/dav/user1");
+        assert!(entries[0].is_collection);
+        assert_eq!(
+            entries[1],
+            DavEntry {
+                path: "/dav/user1/My Game/slot 1.sav".to_string(),
+                is_collection: false,
+                last_modified: "1994-11-15T12:45:26+00:00".to_string(),
+                size: Some(42),
+            }
+        );
+    }
+}
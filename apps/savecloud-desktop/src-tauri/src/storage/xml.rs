@@ -0,0 +1,92 @@
+//! Lectura mínima de XML para las respuestas de S3 y WebDAV.
+//!
+//! Solo se necesitan unos pocos elementos de respuestas pequeñas (`ListObjectsV2`,
+//! `InitiateMultipartUpload`, `PROPFIND`), así que basta con extraer el contenido de
+//! etiquetas por nombre local, ignorando el prefijo de espacio de nombres (`d:href`).
+
+/// Contenido de cada elemento `<tag>...</tag>` (sin anidar el mismo nombre).
+pub fn elements<'a>(xml: &'a str, tag: &str) -> Vec<&'a str> {
+    let mut out = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find('<') {
+        let after = &rest[start + 1..];
+        let name_end = after
+            .find(|c: char| c == '>' || c == '/' || c.is_whitespace())
+            .unwrap_or(after.len());
+        let name = &after[..name_end];
+        let local = name.rsplit(':').next().unwrap_or(name);
+        if name.is_empty() || local != tag {
+            rest = after;
+            continue;
+        }
+        let Some(gt) = after.find('>') else {
+            break;
+        };
+        if after[..gt].ends_with('/') {
+            out.push("");
+            rest = &after[gt + 1..];
+            continue;
+        }
+        let body = &after[gt + 1..];
+        let close = format!("</{}>", name);
+        let Some(close_at) = body.find(&close) else {
+            break;
+        };
+        out.push(&body[..close_at]);
+        rest = &body[close_at + close.len()..];
+    }
+    out
+}
+
+/// Contenido del primer elemento `<tag>`.
+pub fn element<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    elements(xml, tag).into_iter().next()
+}
+
+/// Texto de un elemento con las entidades básicas resueltas.
+pub fn text(xml: &str, tag: &str) -> Option<String> {
+    element(xml, tag).map(|v| unescape(v.trim()))
+}
+
+pub fn unescape(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+pub fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_elements_with_and_without_namespace_prefix() {
+        let s3 = r#"<?xml version="1.0"?><ListBucketResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+            <Contents><Key>u/g/a&amp;b.sav</Key><Size>12</Size></Contents>
+            <Contents><Key>u/g/c.sav</Key><Size>3</Size></Contents>
+            <IsTruncated>false</IsTruncated></ListBucketResult>"#;
+        let contents = elements(s3, "Contents");
+        assert_eq!(contents.len(), 2);
+        assert_eq!(text(contents[0], "Key").as_deref(), Some("u/g/a&b.sav"));
+        assert_eq!(text(s3, "IsTruncated").as_deref(), Some("false"));
+
+        let dav = r#"<d:multistatus xmlns:d="DAV:"><d:response><d:href>/dav/u/</d:href>
+            <d:propstat><d:prop><d:resourcetype><d:collection/></d:resourcetype></d:prop></d:propstat>
+            </d:response></d:multistatus>"#;
+        let responses = elements(dav, "response");
+        assert_eq!(responses.len(), 1);
+        assert_eq!(text(responses[0], "href").as_deref(), Some("/dav/u/"));
+        assert_eq!(element(responses[0], "collection"), Some(""));
+        assert_eq!(element(responses[0], "getcontentlength"), None);
+    }
+}
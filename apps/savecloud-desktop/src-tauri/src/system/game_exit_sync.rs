@@ -2,96 +2,124 @@
 //!
 //! Detecta cuando un juego se cierra y dispara la subida automática de guardados.
 //! Complementa el watcher de archivos: captura el guardado final al salir.
-//! Escucha los eventos emitidos por `process_check` en lugar de hacer polling.
+//! Se suscribe al monitor de procesos (`process_monitor`) en lugar de hacer polling.
 
 use crate::commands::sync;
 use crate::config;
-use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
-use tauri::{AppHandle, Emitter, Listener};
+use crate::system::process_monitor::{self, GameProcessEvent};
+use crate::transfers::{self, TransferKind, TransferPriority};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::broadcast::error::RecvError;
 
-/// Inicia la escucha de eventos de procesos en segundo plano para
+/// Se suscribe al monitor de procesos en segundo plano para
 /// subir los guardados automáticamente cuando un juego se cierra.
-pub fn spawn_exit_watcher(app: AppHandle, tray_state: Arc<crate::tray::tray_state::TrayStateInner>) {
-    let was_running: Arc<Mutex<HashMap<String, bool>>> = Arc::new(Mutex::new(HashMap::new()));
+pub fn spawn_exit_watcher(
+    app: AppHandle,
+    tray_state: Arc<crate::tray::tray_state::TrayStateInner>,
+) {
+    let mut events = process_monitor::global().subscribe();
 
-    let app_clone = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let game_id = match events.recv().await {
+                Ok(GameProcessEvent::Stopped { game_id, .. }) => game_id,
+                Ok(GameProcessEvent::Started { .. }) | Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            };
 
-    app.listen("games-running-status", move |event| {
-        let cfg = config::load_config();
-        if cfg
-            .api_base_url
-            .as_ref()
-            .map_or(true, |s| s.trim().is_empty())
-            || cfg.user_id.as_ref().map_or(true, |s| s.trim().is_empty())
-        {
-            return;
-        }
-
-        let payload = event.payload();
-        let current_state: HashMap<String, bool> = match serde_json::from_str(payload) {
-            Ok(state) => state,
-            Err(_) => return,
-        };
-
-        let mut prev_state = was_running.lock().unwrap();
+            let cfg = config::load_config();
+            if cfg
+                .api_base_url
+                .as_ref()
+                .map_or(true, |s| s.trim().is_empty())
+                || cfg.user_id.as_ref().map_or(true, |s| s.trim().is_empty())
+            {
+                continue;
+            }
 
-        for (game_id, is_running) in &current_state {
-            let prev = prev_state.get(game_id).copied().unwrap_or(false);
+            // Los lanzamientos gestionados (desde la app o la CLI) suben por su cuenta
+            // al terminar.
+            if crate::launch::is_managed(&game_id) {
+                continue;
+            }
 
-            if prev && !is_running {
-                let app_for_async = app_clone.clone();
-                let gid = game_id.clone();
-                let tray = tray_state.clone();
+            let app_for_async = app.clone();
+            let gid = game_id;
+            let tray = tray_state.clone();
 
-                tray.syncing_inc();
-                tray.update_tooltip();
+            tray.syncing_inc();
+            tray.update_tooltip();
 
-                tauri::async_runtime::spawn(async move {
-                    let res = sync::upload::sync_upload_game_impl(
-                        gid.clone(),
-                        app_for_async.clone(),
-                        None,
-                    )
-                    .await;
+            tauri::async_runtime::spawn(async move {
+                // Prioridad máxima: es el guardado final de la partida.
+                let transfer =
+                    transfers::begin(TransferKind::Upload, &gid, TransferPriority::GameExit).await;
+                let res = sync::upload::sync_upload_game_impl(
+                    gid.clone(),
+                    &transfer.observer(&app_for_async),
+                    transfer.token(),
+                )
+                .await;
+                drop(transfer);
 
-                    tray.syncing_dec();
-                    tray.clone().refresh_unsynced_async();
+                tray.syncing_dec();
+                tray.clone().refresh_unsynced_async();
 
-                    match res {
-                        Ok(r) => {
-                            let _ = app_for_async.emit("auto-sync-done", {
-                                #[derive(serde::Serialize, Clone)]
-                                struct Payload {
-                                    game_id: String,
-                                    ok_count: u32,
-                                    err_count: u32,
-                                }
-                                Payload {
-                                    game_id: gid.clone(),
-                                    ok_count: r.ok_count,
-                                    err_count: r.err_count,
-                                }
-                            });
-                        }
-                        Err(e) => {
-                            let _ = app_for_async.emit("auto-sync-error", {
-                                #[derive(serde::Serialize, Clone)]
-                                struct Payload {
-                                    game_id: String,
-                                    error: String,
-                                }
-                                Payload {
-                                    game_id: gid,
-                                    error: e,
-                                }
-                            });
+                match res {
+                    Ok(r) => {
+                        // Con todo subido, la partida queda cerrada para los demás equipos;
+                        // si algo falló, la sesión caduca y avisa de que falta la subida.
+                        if r.err_count == 0 {
+                            release_session(&gid).await;
                         }
+                        let _ = app_for_async.emit("auto-sync-done", {
+                            #[derive(serde::Serialize, Clone)]
+                            struct Payload {
+                                game_id: String,
+                                ok_count: u32,
+                                err_count: u32,
+                            }
+                            Payload {
+                                game_id: gid.clone(),
+                                ok_count: r.ok_count,
+                                err_count: r.err_count,
+                            }
+                        });
                     }
-                });
-            }
+                    Err(e) => {
+                        // La subida no se pierde: queda en la cola offline para reintentarla.
+                        sync::queue::enqueue_after_failure(
+                            &app_for_async,
+                            sync::queue::store::SyncJobKind::Upload,
+                            &gid,
+                            &e,
+                        )
+                        .await;
+                        let _ = app_for_async.emit("auto-sync-error", {
+                            #[derive(serde::Serialize, Clone)]
+                            struct Payload {
+                                game_id: String,
+                                error: String,
+                            }
+                            Payload {
+                                game_id: gid,
+                                error: e,
+                            }
+                        });
+                    }
+                }
+            });
         }
-
-        *prev_state = current_state;
     });
 }
+
+/// Cierra la sesión remota del juego en este dispositivo; los fallos solo se registran.
+async fn release_session(game_id: &str) {
+    let Ok(storage) = crate::storage::Storage::active() else {
+        return;
+    };
+    if let Err(e) = crate::devices::session::release(&storage, game_id).await {
+        crate::commands::logs::sync_logger::log_error("session_release", game_id, &e);
+    }
+}
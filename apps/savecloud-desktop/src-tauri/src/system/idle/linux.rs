@@ -0,0 +1,45 @@
+//! Tiempo sin entrada en Linux, preguntando por D-Bus al escritorio: Mutter en GNOME
+//! (X11 y Wayland) y `org.freedesktop.ScreenSaver` en KDE y otros.
+
+use std::sync::OnceLock;
+use std::time::Duration;
+use zbus::blocking::Connection;
+
+pub(super) fn idle_time() -> Option<Duration> {
+    let bus = session_bus()?;
+    mutter_idle_time(bus).or_else(|| screensaver_idle_time(bus))
+}
+
+/// Bus de sesión; si no hay (sin escritorio) no se vuelve a intentar.
+fn session_bus() -> Option<&'static Connection> {
+    static BUS: OnceLock<Option<Connection>> = OnceLock::new();
+    BUS.get_or_init(|| Connection::session().ok()).as_ref()
+}
+
+fn mutter_idle_time(bus: &Connection) -> Option<Duration> {
+    let reply = bus
+        .call_method(
+            Some("org.gnome.Mutter.IdleMonitor"),
+            "/org/gnome/Mutter/IdleMonitor/Core",
+            Some("org.gnome.Mutter.IdleMonitor"),
+            "GetIdletime",
+            &(),
+        )
+        .ok()?;
+    let millis: u64 = reply.body().deserialize().ok()?;
+    Some(Duration::from_millis(millis))
+}
+
+fn screensaver_idle_time(bus: &Connection) -> Option<Duration> {
+    let reply = bus
+        .call_method(
+            Some("org.freedesktop.ScreenSaver"),
+            "/org/freedesktop/ScreenSaver",
+            Some("org.freedesktop.ScreenSaver"),
+            "GetSessionIdleTime",
+            &(),
+        )
+        .ok()?;
+    let millis: u32 = reply.body().deserialize().ok()?;
+    Some(Duration::from_millis(u64::from(millis)))
+}
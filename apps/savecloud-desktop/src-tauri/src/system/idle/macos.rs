@@ -0,0 +1,18 @@
+//! Tiempo sin entrada en macOS, según `HIDIdleTime` de `IOHIDSystem`.
+
+use std::process::Command;
+use std::time::Duration;
+
+pub(super) fn idle_time() -> Option<Duration> {
+    let output = Command::new("ioreg")
+        .args(["-c", "IOHIDSystem", "-d", "4"])
+        .output()
+        .ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    // Línea `"HIDIdleTime" = 1234567890`, en nanosegundos.
+    let nanos = text
+        .lines()
+        .find_map(|line| line.split_once("\"HIDIdleTime\" = "))
+        .and_then(|(_, value)| value.trim().parse::<u64>().ok())?;
+    Some(Duration::from_nanos(nanos))
+}
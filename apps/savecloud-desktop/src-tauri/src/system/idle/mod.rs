@@ -0,0 +1,171 @@
+//! Detección de inactividad: el tiempo de juego deja de contar si nadie toca el
+//! teclado, el ratón ni el mando durante un rato o, si se pide, mientras el juego no
+//! está en primer plano ([`IdleDetectionSettings`]).
+//!
+//! - Teclado y ratón: tiempo sin entrada según el sistema (`GetLastInputInfo` en
+//!   Windows, Mutter u `org.freedesktop.ScreenSaver` por D-Bus en Linux, `HIDIdleTime`
+//!   en macOS).
+//! - Mando: el bucle de `gilrs` anota cada pulsación ([`note_gamepad_input`]); el
+//!   sistema no siempre la cuenta como entrada (XInput en Windows no lo hace).
+//! - Primer plano: proceso dueño de la ventana activa, solo en Windows.
+//!
+//! Si el sistema no informa de alguno de los dos datos, esa comprobación se omite: sin
+//! saber del teclado y el ratón no se puede dar a nadie por ausente.
+
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(target_os = "macos")]
+mod macos;
+#[cfg(windows)]
+mod windows;
+
+use super::process_check::get_sys;
+use crate::config::IdleDetectionSettings;
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use sysinfo::Pid;
+
+/// Antecesores que se revisan como mucho al buscar el juego dueño de la ventana activa.
+const MAX_ANCESTORS: usize = 16;
+
+static LAST_GAMEPAD_INPUT: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Anota actividad en un mando.
+pub fn note_gamepad_input() {
+    if let Ok(mut last) = LAST_GAMEPAD_INPUT.lock() {
+        *last = Some(Instant::now());
+    }
+}
+
+/// Estado de la entrada del usuario en un instante.
+#[derive(Debug, Default, Clone)]
+pub struct IdleSample {
+    /// Tiempo sin teclado, ratón ni mando; `None` si el sistema no informa.
+    input_idle: Option<Duration>,
+    /// Proceso dueño de la ventana activa seguido de sus antecesores.
+    foreground: Option<Vec<u32>>,
+}
+
+impl IdleSample {
+    /// Lee el estado actual. Puede bloquear (D-Bus, `ioreg`), así que no se debe llamar
+    /// desde el runtime async.
+    pub fn read(with_foreground: bool) -> Self {
+        let gamepad_idle = LAST_GAMEPAD_INPUT
+            .lock()
+            .ok()
+            .and_then(|last| last.map(|t| t.elapsed()));
+        let input_idle =
+            os_idle_time().map(|os| gamepad_idle.map_or(os, |gamepad| gamepad.min(os)));
+        let foreground = if with_foreground {
+            foreground_pid().map(with_ancestors)
+        } else {
+            None
+        };
+        Self {
+            input_idle,
+            foreground,
+        }
+    }
+
+    /// Indica si un juego abierto con los procesos `game_pids` está inactivo.
+    pub fn is_idle(&self, settings: &IdleDetectionSettings, game_pids: &HashSet<u32>) -> bool {
+        if !settings.enabled {
+            return false;
+        }
+        let limit = Duration::from_secs(u64::from(settings.idle_minutes.max(1)) * 60);
+        if self.input_idle.is_some_and(|idle| idle >= limit) {
+            return true;
+        }
+        // Sin procesos (el juego se acaba de cerrar) no se puede saber si estaba delante.
+        settings.require_foreground
+            && !game_pids.is_empty()
+            && self
+                .foreground
+                .as_ref()
+                .is_some_and(|chain| !chain.iter().any(|pid| game_pids.contains(pid)))
+    }
+}
+
+fn os_idle_time() -> Option<Duration> {
+    #[cfg(target_os = "linux")]
+    {
+        linux::idle_time()
+    }
+    #[cfg(target_os = "macos")]
+    {
+        macos::idle_time()
+    }
+    #[cfg(windows)]
+    {
+        windows::idle_time()
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+    {
+        None
+    }
+}
+
+fn foreground_pid() -> Option<u32> {
+    #[cfg(windows)]
+    {
+        windows::foreground_pid()
+    }
+    #[cfg(not(windows))]
+    {
+        None
+    }
+}
+
+/// `pid` y sus antecesores: la ventana puede ser de un hijo del proceso del juego.
+fn with_ancestors(pid: u32) -> Vec<u32> {
+    let sys = get_sys();
+    let mut chain = vec![pid];
+    let mut current = Pid::from_u32(pid);
+    while let Some(parent) = sys.process(current).and_then(|p| p.parent()) {
+        if chain.len() >= MAX_ANCESTORS || chain.contains(&parent.as_u32()) {
+            break;
+        }
+        chain.push(parent.as_u32());
+        current = parent;
+    }
+    chain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(require_foreground: bool) -> IdleDetectionSettings {
+        IdleDetectionSettings {
+            enabled: true,
+            idle_minutes: 5,
+            require_foreground,
+        }
+    }
+
+    #[test]
+    fn idle_after_limit_or_when_game_is_not_in_foreground() {
+        let game = HashSet::from([20, 21]);
+        let sample = |idle_secs: Option<u64>, foreground: Option<Vec<u32>>| IdleSample {
+            input_idle: idle_secs.map(Duration::from_secs),
+            foreground,
+        };
+
+        assert!(!sample(Some(4 * 60), None).is_idle(&settings(false), &game));
+        assert!(sample(Some(5 * 60), None).is_idle(&settings(false), &game));
+        // Sin datos del sistema no se da a nadie por ausente.
+        assert!(!sample(None, None).is_idle(&settings(true), &game));
+
+        // Ventana de un hijo del juego (30 → 21): sigue delante.
+        let child = sample(Some(0), Some(vec![30, 21, 1]));
+        assert!(!child.is_idle(&settings(true), &game));
+        let other = sample(Some(0), Some(vec![40, 1]));
+        assert!(other.is_idle(&settings(true), &game));
+        assert!(!other.is_idle(&settings(false), &game));
+        assert!(!other.is_idle(&settings(true), &HashSet::new()));
+
+        let disabled = IdleDetectionSettings::default();
+        assert!(!sample(Some(3600), Some(vec![40])).is_idle(&disabled, &game));
+    }
+}
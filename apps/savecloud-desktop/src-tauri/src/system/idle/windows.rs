@@ -0,0 +1,34 @@
+//! Tiempo sin entrada y ventana activa en Windows.
+
+use std::time::Duration;
+use windows_sys::Win32::System::SystemInformation::GetTickCount;
+use windows_sys::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+use windows_sys::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId};
+
+pub(super) fn idle_time() -> Option<Duration> {
+    let mut info = LASTINPUTINFO {
+        cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+        dwTime: 0,
+    };
+    // SAFETY: `info` es un `LASTINPUTINFO` válido con `cbSize` inicializado.
+    if unsafe { GetLastInputInfo(&mut info) } == 0 {
+        return None;
+    }
+    // SAFETY: sin argumentos. Ambos contadores dan la vuelta a los ~49 días.
+    let now = unsafe { GetTickCount() };
+    Some(Duration::from_millis(u64::from(
+        now.wrapping_sub(info.dwTime),
+    )))
+}
+
+pub(super) fn foreground_pid() -> Option<u32> {
+    // SAFETY: sin argumentos; puede devolver nulo (p. ej. al cambiar de ventana).
+    let hwnd = unsafe { GetForegroundWindow() };
+    if hwnd.is_null() {
+        return None;
+    }
+    let mut pid = 0u32;
+    // SAFETY: `hwnd` es una ventana y `pid` un `u32` válido donde escribir.
+    unsafe { GetWindowThreadProcessId(hwnd, &mut pid) };
+    (pid != 0).then_some(pid)
+}
@@ -1,3 +1,8 @@
 pub mod process_check;
+pub mod process_match;
+pub mod process_monitor;
+pub mod process_tree;
 pub mod game_exit_sync;
+pub mod idle;
+pub mod sync_queue;
 pub mod watch_sync;
\ No newline at end of file
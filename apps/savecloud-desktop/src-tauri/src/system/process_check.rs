@@ -5,14 +5,26 @@
 //! Además, gestiona el rastreo de tiempo de juego (playtime) emitiendo eventos
 //! reactivos hacia el frontend.
 
+use super::idle::IdleSample;
+use super::process_monitor::{self, GameProcessEvent};
+use crate::commands::logs::sync_logger;
+use crate::commands::sync::queue::{now_secs, with_db};
 use crate::config;
-use crate::time;
+use crate::devices::identity;
+use crate::devices::session::{self, SessionEvent};
+use crate::plugins::hooks;
+use crate::sqlite::AppDb;
+use crate::time::{self, sessions, sessions::LaunchedVia};
 use std::collections::HashMap;
 use std::sync::Mutex;
 use std::sync::OnceLock;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::broadcast::error::RecvError;
+
+/// Cada cuánto se revisan el tiempo de juego y las sesiones de los juegos abiertos.
+const PLAYTIME_TICK: Duration = Duration::from_secs(10);
 
 /// Instancia global de `System` compartida para evitar sobrecarga de memoria
 /// al re-inicializar el árbol de procesos en cada consulta.
@@ -26,11 +38,6 @@ pub(crate) fn get_sys() -> std::sync::MutexGuard<'static, System> {
         .expect("Mutex de sysinfo envenenado")
 }
 
-/// Determina si un juego específico está en ejecución basándose en sus ejecutables conocidos.
-///
-/// # Arguments
-/// * `game_id` - Identificador único del juego.
-/// * `_paths` - Rutas de guardado (actualmente no utilizadas para la detección de proceso).
 /// Lista nombres de ejecutable únicos de procesos en ejecución (ordenados), para el selector manual en la UI.
 pub fn list_running_process_exe_names() -> Vec<String> {
     let mut sys = get_sys();
@@ -48,157 +55,222 @@ pub fn list_running_process_exe_names() -> Vec<String> {
     names
 }
 
-pub fn is_game_running(game_id: &str, _paths: &[String]) -> bool {
-    let names = get_executable_names_to_check(game_id);
-    if names.is_empty() {
-        return false;
-    }
-
-    let mut sys = get_sys();
-    sys.refresh_processes_specifics(
-        ProcessesToUpdate::All,
-        ProcessRefreshKind::new().with_exe(UpdateKind::OnlyIfNotSet),
-    );
+/// Sigue un proceso lanzado por la app y todos sus hijos: el juego cuenta como
+/// abierto mientras viva alguno, aunque no cumpla sus reglas (p. ej. un lanzador).
+pub fn track_launched(game_id: &str, pid: u32) {
+    process_monitor::global().track_launched(game_id, pid);
+}
 
-    for process in sys.processes().values() {
-        let proc_name = process.name().to_string_lossy().to_lowercase();
-        for check in &names {
-            if proc_name == check.to_lowercase() {
-                return true;
-            }
-        }
-    }
-    false
+/// Determina si un juego específico está en ejecución.
+///
+/// # Arguments
+/// * `game_id` - Identificador único del juego.
+/// * `_paths` - Rutas de guardado (actualmente no utilizadas para la detección de proceso).
+pub fn is_game_running(game_id: &str, _paths: &[String]) -> bool {
+    are_games_running(&[game_id.to_string()])
+        .get(game_id)
+        .copied()
+        .unwrap_or(false)
 }
 
 /// Evalúa el estado de ejecución de múltiples juegos de forma simultánea.
 ///
-/// Optimiza el rendimiento realizando una única actualización del árbol de procesos
-/// y pre-calculando los candidatos de nombres de ejecutables.
+/// Un juego está abierto si sigue vivo algún proceso lanzado para él por la app
+/// ([`track_launched`]) o si algún proceso cumple sus reglas de detección
+/// ([`process_match::rules_for_game`]). El estado sale del [`process_monitor`], que
+/// solo vuelve a revisar los procesos si su última revisión no es reciente.
 ///
 /// # Returns
 /// Un `HashMap` donde la clave es el `game_id` y el valor es un booleano de ejecución.
 pub fn are_games_running(game_ids: &[String]) -> HashMap<String, bool> {
-    let cfg = config::load_config();
-    let mut result: HashMap<String, bool> = HashMap::with_capacity(game_ids.len());
-
     if game_ids.is_empty() {
-        return result;
+        return HashMap::new();
     }
+    let running = process_monitor::global().running();
+    game_ids
+        .iter()
+        .map(|id| {
+            let is_running = running
+                .iter()
+                .any(|(game_id, &r)| r && game_id.eq_ignore_ascii_case(id));
+            (id.clone(), is_running)
+        })
+        .collect()
+}
 
-    let mut names_by_game: HashMap<String, Vec<String>> = HashMap::with_capacity(game_ids.len());
+/// Arranca el [`process_monitor`] y se suscribe a él para:
+/// 1. Emitir el estado de ejecución al frontend ("games-running-status").
+/// 2. Acumular tiempo de juego (Playtime) cada 60 segundos y el restante al cerrarse
+///    el juego, tanto en el contador de la biblioteca como en la partida del
+///    historial ([`sessions`]). Con la detección de inactividad activada ([`super::idle`]),
+///    el tiempo sin jugar solo se anota aparte en el historial.
+/// 3. Abrir y renovar la sesión remota de cada juego en ejecución para avisar a
+///    otros equipos ([`session`]); la cierra la subida al terminar la partida.
+/// 4. Avisar a los plugins de que el juego se abrió o se cerró ([`hooks`]).
+#[tauri::command]
+pub fn start_process_watcher(app: AppHandle) {
+    let monitor = process_monitor::global();
+    let mut status = monitor.watch_status();
+    let mut events = monitor.subscribe();
+    monitor.start();
 
-    for id in game_ids {
-        result.insert(id.clone(), false);
+    let status_app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        while status.changed().await.is_ok() {
+            let current = status.borrow_and_update().clone();
+            let _ = status_app.emit("games-running-status", &current);
+        }
+    });
 
-        if let Some(game) = cfg.games.iter().find(|g| g.id.eq_ignore_ascii_case(id)) {
-            let mut names: Vec<String> = Vec::new();
+    let db = app.try_state::<AppDb>().map(|db| db.inner().clone());
+    tauri::async_runtime::spawn(async move {
+        let mut playing: HashMap<String, Playing> = HashMap::new();
 
-            if let Some(ref execs) = game.executable_names {
-                if !execs.is_empty() {
-                    names = execs
-                        .iter()
-                        .filter_map(|s| {
-                            let t = s.trim();
-                            if t.is_empty() {
-                                None
-                            } else {
-                                Some(ensure_exe_ext(t))
-                            }
-                        })
-                        .collect();
+        loop {
+            let event = tokio::time::timeout(PLAYTIME_TICK, events.recv()).await;
+            tally(&mut playing).await;
+            match event {
+                Ok(Ok(GameProcessEvent::Started { game_id, launched })) => {
+                    let history_id = match &db {
+                        Some(db) => {
+                            start_history(db, &game_id, launched_via(&game_id, launched)).await
+                        }
+                        None => None,
+                    };
+                    playing.insert(game_id.clone(), Playing::new(history_id));
+                    session::notify(&game_id, SessionEvent::Started);
+                    // Aparte, para que un plugin lento no retrase el tiempo de juego.
+                    tauri::async_runtime::spawn(async move {
+                        hooks::game_started(&game_id, launched).await
+                    });
                 }
+                Ok(Ok(GameProcessEvent::Stopped { game_id, played })) => {
+                    // El juego acaba de cerrarse: procesar tiempo final
+                    if let Some(mut game) = playing.remove(&game_id) {
+                        save_playtime(&app, db.as_ref(), &game_id, &mut game).await;
+                    }
+                    tauri::async_runtime::spawn(async move {
+                        hooks::game_exited(&game_id, played).await
+                    });
+                }
+                Ok(Err(RecvError::Lagged(_))) | Err(_) => {}
+                Ok(Err(RecvError::Closed)) => break,
             }
 
-            if names.is_empty() {
-                names = infer_exe_candidates(id);
-            }
-
-            if !names.is_empty() {
-                let names_lower: Vec<String> =
-                    names.into_iter().map(|n| n.to_lowercase()).collect();
-                names_by_game.insert(game.id.clone(), names_lower);
+            for (game_id, game) in playing.iter_mut() {
+                // Guardar tiempo acumulado cada minuto
+                if game.checkpoint.elapsed() >= Duration::from_secs(60) {
+                    save_playtime(&app, db.as_ref(), game_id, game).await;
+                }
+                if game.heartbeat.elapsed() >= session::HEARTBEAT_INTERVAL {
+                    game.heartbeat = Instant::now();
+                    session::notify(game_id, SessionEvent::Heartbeat);
+                }
             }
         }
-    }
-
-    let mut sys = get_sys();
-    sys.refresh_processes_specifics(
-        ProcessesToUpdate::All,
-        ProcessRefreshKind::new().with_exe(UpdateKind::OnlyIfNotSet),
-    );
+    });
+}
 
-    for process in sys.processes().values() {
-        let proc_name = process.name().to_string_lossy().to_lowercase();
-        for game_id in game_ids {
-            if result[game_id] {
-                continue;
-            }
+/// Juego abierto según el monitor.
+struct Playing {
+    /// Último punto de guardado del tiempo de juego.
+    checkpoint: Instant,
+    /// Último reparto del tiempo entre jugado e inactivo ([`tally`]).
+    last_tally: Instant,
+    /// Tiempo jugado e inactivo pendiente de guardar.
+    active: Duration,
+    idle: Duration,
+    /// Última renovación de la sesión remota.
+    heartbeat: Instant,
+    /// Fila de la partida en `play_sessions`, si se pudo crear.
+    history_id: Option<i64>,
+}
 
-            if let Some(check_names) = names_by_game.get(game_id) {
-                if check_names.contains(&proc_name) {
-                    result.insert(game_id.clone(), true);
-                }
-            }
+impl Playing {
+    fn new(history_id: Option<i64>) -> Self {
+        let now = Instant::now();
+        Self {
+            checkpoint: now,
+            last_tally: now,
+            active: Duration::ZERO,
+            idle: Duration::ZERO,
+            heartbeat: now,
+            history_id,
         }
     }
-    result
 }
 
-/// Orquesta el monitoreo continuo de procesos en segundo plano.
-///
-/// Este hilo realiza tres tareas principales:
-/// 1. Emite eventos de estado de ejecución ("games-running-status").
-/// 2. Acumula tiempo de juego (Playtime) cada 60 segundos de actividad.
-/// 3. Sincroniza el tiempo restante al detectar el cierre de un proceso.
-#[tauri::command]
-pub fn start_process_watcher(app: AppHandle) {
-    tauri::async_runtime::spawn(async move {
-        let mut previous_state: HashMap<String, bool> = HashMap::new();
-        let mut last_checkpoint: HashMap<String, Instant> = HashMap::new();
-
-        loop {
-            let cfg = config::load_config();
-            let game_ids: Vec<String> = cfg.games.iter().map(|g| g.id.clone()).collect();
-            let current_state = are_games_running(&game_ids);
-
-            // Notificar cambios de estado (Ej. Un juego se abrió o se cerró)
-            if current_state != previous_state {
-                let _ = app.emit("games-running-status", &current_state);
-            }
-
-            for (game_id, &is_running) in &current_state {
-                let was_running = *previous_state.get(game_id).unwrap_or(&false);
+/// Reparte el tiempo desde el último reparto de cada juego entre jugado e inactivo.
+async fn tally(playing: &mut HashMap<String, Playing>) {
+    if playing.is_empty() {
+        return;
+    }
+    let settings = config::load_idle_detection();
+    let sample = if settings.enabled {
+        let with_foreground = settings.require_foreground;
+        tokio::task::spawn_blocking(move || IdleSample::read(with_foreground))
+            .await
+            .unwrap_or_default()
+    } else {
+        IdleSample::default()
+    };
+    let monitor = process_monitor::global();
+    for (game_id, game) in playing.iter_mut() {
+        let elapsed = game.last_tally.elapsed();
+        game.last_tally = Instant::now();
+        if settings.enabled && sample.is_idle(&settings, &monitor.pids_of(game_id)) {
+            game.idle += elapsed;
+        } else {
+            game.active += elapsed;
+        }
+    }
+}
 
-                if is_running {
-                    if !was_running {
-                        last_checkpoint.insert(game_id.clone(), Instant::now());
-                    } else if let Some(start) = last_checkpoint.get(game_id) {
-                        let elapsed = start.elapsed().as_secs();
+fn launched_via(game_id: &str, launched: bool) -> LaunchedVia {
+    if !launched {
+        LaunchedVia::Detected
+    } else if crate::launch::is_managed(game_id) {
+        LaunchedVia::Managed
+    } else {
+        LaunchedVia::App
+    }
+}
 
-                        // Guardar tiempo acumulado cada minuto de juego activo
-                        if elapsed >= 60 {
-                            let _ = time::add_playtime(game_id, elapsed);
-                            last_checkpoint.insert(game_id.clone(), Instant::now());
-                            emit_playtime_update(&app, game_id);
-                        }
-                    }
-                } else if was_running {
-                    // El juego acaba de cerrarse: procesar tiempo final
-                    if let Some(start) = last_checkpoint.remove(game_id) {
-                        let remaining = start.elapsed().as_secs();
-                        if remaining > 0 {
-                            let _ = time::add_playtime(game_id, remaining);
-                            emit_playtime_update(&app, game_id);
-                        }
-                    }
-                }
-            }
+/// Abre la partida en el historial; `None` si la base de datos falla.
+async fn start_history(db: &AppDb, game_id: &str, via: LaunchedVia) -> Option<i64> {
+    let device_id = identity::current().id;
+    let game_id = game_id.to_string();
+    with_db(db, move |c| {
+        sessions::start(c, &game_id, &device_id, via, now_secs())
+    })
+    .await
+    .map_err(|e| sync_logger::log_error("play_sessions", "No se pudo abrir la partida", &e))
+    .ok()
+}
 
-            previous_state = current_state;
-            tokio::time::sleep(std::time::Duration::from_secs(10)).await;
+/// Guarda el tiempo jugado pendiente en el contador del juego y, junto con el
+/// inactivo, en su partida del historial. Las fracciones de segundo quedan para la
+/// siguiente vez.
+async fn save_playtime(app: &AppHandle, db: Option<&AppDb>, game_id: &str, game: &mut Playing) {
+    game.checkpoint = Instant::now();
+    let seconds = game.active.as_secs();
+    let idle_seconds = game.idle.as_secs();
+    game.active -= Duration::from_secs(seconds);
+    game.idle -= Duration::from_secs(idle_seconds);
+
+    if seconds > 0 {
+        let _ = time::add_playtime(game_id, seconds);
+        emit_playtime_update(app, game_id);
+    }
+    if let (Some(db), Some(id)) = (db, game.history_id) {
+        let res = with_db(db, move |c| {
+            sessions::extend(c, id, seconds as i64, idle_seconds as i64, now_secs())
+        })
+        .await;
+        if let Err(e) = res {
+            sync_logger::log_error("play_sessions", "No se pudo guardar la partida", &e);
         }
-    });
+    }
 }
 
 /// Despacha eventos IPC para actualizar los contadores de tiempo en la UI.
@@ -222,98 +294,3 @@ struct Payload {
     game_id: String,
     new_time: u64,
 }
-
-/// Obtiene la lista de nombres de ejecutables a monitorear para un ID de juego.
-fn get_executable_names_to_check(game_id: &str) -> Vec<String> {
-    let cfg = config::load_config();
-    if let Some(game) = cfg
-        .games
-        .iter()
-        .find(|g| g.id.eq_ignore_ascii_case(game_id))
-    {
-        if let Some(ref names) = game.executable_names {
-            if !names.is_empty() {
-                return names
-                    .iter()
-                    .filter_map(|s| {
-                        let t = s.trim().to_string();
-                        if t.is_empty() {
-                            None
-                        } else {
-                            Some(ensure_exe_ext(&t))
-                        }
-                    })
-                    .collect();
-            }
-        }
-    }
-    infer_exe_candidates(game_id)
-}
-
-/// Asegura que el nombre del ejecutable tenga la extensión apropiada según el SO.
-fn ensure_exe_ext(s: &str) -> String {
-    let s = s.trim();
-    #[cfg(target_os = "windows")]
-    {
-        if s.to_lowercase().ends_with(".exe") {
-            s.to_string()
-        } else {
-            format!("{}.exe", s)
-        }
-    }
-    #[cfg(not(target_os = "windows"))]
-    {
-        s.to_string()
-    }
-}
-
-/// Deduce posibles nombres de ejecutables basándose en el identificador del juego.
-///
-/// Genera variaciones como el nombre compacto y acrónimos para mejorar la
-/// tasa de éxito en la detección automática.
-fn infer_exe_candidates(text: &str) -> Vec<String> {
-    let mut candidates = Vec::new();
-    let text = text.trim();
-    if text.is_empty() {
-        return candidates;
-    }
-
-    // Candidato 1: Nombre base sin símbolos ni espacios
-    let base = text.replace(['\'', '"', ':', '-'], "").replace(' ', "");
-    if !base.is_empty() {
-        #[cfg(target_os = "windows")]
-        {
-            candidates.push(ensure_exe_ext(&base));
-            candidates.push(format!("{}-Win64-Shipping.exe", base));
-        }
-        #[cfg(not(target_os = "windows"))]
-        {
-            candidates.push(base.clone());
-        }
-    }
-
-    // Candidato 2: Acrónimo (Ej: "Grand Theft Auto" -> "gta")
-    let mut acronym = String::new();
-    let words = text
-        .split(|c: char| !c.is_alphanumeric())
-        .filter(|w| !w.is_empty());
-
-    for word in words {
-        if word.chars().all(|c| c.is_ascii_digit()) {
-            acronym.push_str(word);
-        } else if let Some(c) = word.chars().next() {
-            acronym.push(c.to_ascii_lowercase());
-        }
-    }
-
-    if acronym.len() >= 2 && acronym.len() <= 6 {
-        #[cfg(target_os = "windows")]
-        candidates.push(ensure_exe_ext(&acronym));
-        #[cfg(not(target_os = "windows"))]
-        candidates.push(acronym.clone());
-    }
-
-    candidates.sort();
-    candidates.dedup();
-    candidates
-}
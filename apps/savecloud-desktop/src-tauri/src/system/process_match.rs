@@ -0,0 +1,416 @@
+//! Reglas para reconocer los procesos de un juego.
+//!
+//! Un juego está en ejecución si algún proceso cumple alguna de sus reglas
+//! ([`ProcessRule`]): nombre de ejecutable, ruta completa o carpeta (la de instalación).
+//! Bajo Wine/Proton el ejecutable del proceso es el cargador (`wine64-preloader`) y el
+//! `.exe` de Windows va en la línea de órdenes, así que también se compara ese.
+
+use crate::config::{ConfiguredGame, ProcessRule};
+use crate::steam::shortcuts::{self, SteamShortcut};
+use std::path::{Path, PathBuf};
+
+/// Cargadores de Wine/Proton: el juego real es el `.exe` de su línea de órdenes.
+const WINE_LOADERS: &[&str] = &["wine", "wine64", "wine-preloader", "wine64-preloader"];
+
+/// Nombres demasiado comunes para identificar un juego por sí solos. Las reglas
+/// automáticas los descartan; el usuario puede usarlos con una regla de carpeta o ruta.
+const GENERIC_NAMES: &[&str] = &[
+    "game",
+    "game.exe",
+    "launcher",
+    "launcher.exe",
+    "start.exe",
+    "unitycrashhandler32.exe",
+    "unitycrashhandler64.exe",
+    "crashreportclient.exe",
+    "java",
+    "java.exe",
+    "javaw.exe",
+    "python",
+    "python3",
+    "python.exe",
+    "electron",
+    "wine",
+    "wine64",
+    "wine-preloader",
+    "wine64-preloader",
+];
+
+fn is_generic(name: &str) -> bool {
+    GENERIC_NAMES.contains(&name.to_lowercase().as_str())
+}
+
+/// Lo necesario de un proceso para compararlo con las reglas.
+#[derive(Debug, Default, Clone)]
+pub struct ProcessInfo {
+    pub name: String,
+    pub exe: Option<PathBuf>,
+    pub cmd: Vec<String>,
+    pub cwd: Option<PathBuf>,
+}
+
+impl ProcessInfo {
+    pub fn from_sysinfo(process: &sysinfo::Process) -> Self {
+        Self {
+            name: process.name().to_string_lossy().into_owned(),
+            exe: process.exe().map(Path::to_path_buf),
+            cmd: process
+                .cmd()
+                .iter()
+                .map(|a| a.to_string_lossy().into_owned())
+                .collect(),
+            cwd: process.cwd().map(Path::to_path_buf),
+        }
+    }
+
+    fn is_wine_loader(&self) -> bool {
+        let exe_name = self
+            .exe
+            .as_deref()
+            .and_then(Path::file_name)
+            .map(|n| n.to_string_lossy().to_lowercase());
+        WINE_LOADERS
+            .iter()
+            .any(|l| self.name.eq_ignore_ascii_case(l) || exe_name.as_deref() == Some(*l))
+    }
+
+    /// Ejecutables que representa el proceso, normalizados: el suyo y, bajo Wine,
+    /// los `.exe` de su línea de órdenes.
+    fn images(&self) -> Vec<String> {
+        let mut images: Vec<String> = self
+            .exe
+            .as_deref()
+            .map(|p| normalize(&p.to_string_lossy()))
+            .into_iter()
+            .collect();
+        if self.is_wine_loader() {
+            images.extend(
+                self.cmd
+                    .iter()
+                    .filter(|a| a.to_lowercase().ends_with(".exe"))
+                    .map(|a| wine_arg_to_path(a, self.cwd.as_deref())),
+            );
+        }
+        images
+    }
+}
+
+/// Ruta en minúsculas, con `/` y sin barra final, para comparar sin depender del SO.
+fn normalize(path: &str) -> String {
+    let path = path.trim().replace('\\', "/").to_lowercase();
+    match path.trim_end_matches('/') {
+        "" => path,
+        trimmed => trimmed.to_string(),
+    }
+}
+
+/// Ruta de Windows vista por Wine a ruta del sistema: `Z:` es la raíz y las relativas
+/// cuelgan de la carpeta de trabajo. Otras unidades (`C:`) se quedan como están.
+fn wine_arg_to_path(arg: &str, cwd: Option<&Path>) -> String {
+    let arg = normalize(arg);
+    if let Some(rest) = arg.strip_prefix("z:") {
+        return rest.to_string();
+    }
+    let has_drive = arg.as_bytes().get(1) == Some(&b':');
+    match cwd {
+        Some(cwd) if !has_drive && !arg.starts_with('/') => {
+            normalize(&cwd.join(&arg).to_string_lossy())
+        }
+        _ => arg,
+    }
+}
+
+fn file_name(image: &str) -> &str {
+    image.rsplit('/').next().unwrap_or(image)
+}
+
+/// Nombre sin `.exe`, para que "Hades" y "Hades.exe" coincidan en cualquier SO.
+fn stem(name: &str) -> &str {
+    name.strip_suffix(".exe").unwrap_or(name)
+}
+
+/// Reglas de un juego ya normalizadas; coincide si se cumple cualquiera.
+#[derive(Debug, Default, Clone)]
+pub struct GameMatcher {
+    names: Vec<String>,
+    paths: Vec<String>,
+    dirs: Vec<String>,
+}
+
+impl GameMatcher {
+    pub fn new(rules: &[ProcessRule]) -> Self {
+        let mut matcher = Self::default();
+        for rule in rules {
+            match rule {
+                ProcessRule::Name(n) if !n.trim().is_empty() => matcher
+                    .names
+                    .push(stem(&n.trim().to_lowercase()).to_string()),
+                ProcessRule::Path(p) if !p.trim().is_empty() => matcher.paths.push(normalize(p)),
+                ProcessRule::Dir(d) if !d.trim().is_empty() => matcher.dirs.push(normalize(d)),
+                _ => {}
+            }
+        }
+        matcher
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty() && self.paths.is_empty() && self.dirs.is_empty()
+    }
+
+    pub fn matches(&self, process: &ProcessInfo) -> bool {
+        let images = process.images();
+        // El nombre del proceso puede venir truncado (15 caracteres en Linux): se
+        // compara también con el nombre de archivo de cada ejecutable.
+        let name = process.name.to_lowercase();
+        let by_name = self
+            .names
+            .iter()
+            .any(|n| stem(&name) == n || images.iter().any(|i| stem(file_name(i)) == n));
+        by_name
+            || images.iter().any(|image| {
+                self.paths.contains(image)
+                    || self.dirs.iter().any(|d| {
+                        image
+                            .strip_prefix(d.as_str())
+                            .is_some_and(|rest| rest.starts_with('/'))
+                    })
+            })
+    }
+}
+
+/// Reglas con las que se detecta el juego.
+///
+/// Las configuradas por el usuario mandan. Si no hay, se deducen: carpeta de instalación
+/// (la indicada o la del ejecutable de lanzamiento), nombres configurados y, en su
+/// defecto, el acceso directo no-Steam o los nombres inferidos del ID, sin los genéricos.
+pub fn rules_for_game<'a>(
+    game: &ConfiguredGame,
+    load_shortcuts: impl FnOnce() -> &'a [SteamShortcut],
+    inferred: impl FnOnce() -> Vec<String>,
+) -> Vec<ProcessRule> {
+    if let Some(rules) = game.process_rules.as_ref().filter(|r| !r.is_empty()) {
+        return rules.clone();
+    }
+
+    let mut rules = Vec::new();
+    let launch_dir = game
+        .launch_executable_path
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .and_then(|p| Path::new(p).parent())
+        .map(|d| d.to_string_lossy().into_owned())
+        .filter(|d| !d.is_empty());
+    let install_dir = game
+        .install_dir
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .or(launch_dir);
+    if let Some(dir) = install_dir.clone() {
+        rules.push(ProcessRule::Dir(dir));
+    }
+
+    let names: Vec<&String> = game
+        .executable_names
+        .iter()
+        .flatten()
+        .filter(|n| !n.trim().is_empty())
+        .collect();
+    if !names.is_empty() {
+        rules.extend(
+            names
+                .into_iter()
+                .map(|n| ProcessRule::Name(n.trim().to_string())),
+        );
+        return rules;
+    }
+
+    let from_shortcut = shortcuts::shortcuts_for_game(load_shortcuts(), &game.id);
+    if !from_shortcut.is_empty() {
+        for s in from_shortcut {
+            if !s.exe.trim().is_empty() {
+                rules.push(ProcessRule::Path(s.exe.trim().to_string()));
+            }
+            if !s.executable_name.is_empty() && !is_generic(&s.executable_name) {
+                rules.push(ProcessRule::Name(s.executable_name.clone()));
+            }
+        }
+        return rules;
+    }
+
+    // Con carpeta de instalación conocida no hace falta adivinar nombres.
+    if install_dir.is_none() {
+        rules.extend(
+            inferred()
+                .into_iter()
+                .filter(|n| !is_generic(n))
+                .map(ProcessRule::Name),
+        );
+    }
+    rules
+}
+
+/// Deduce posibles nombres de ejecutables basándose en el identificador del juego.
+///
+/// Genera variaciones como el nombre compacto y acrónimos para mejorar la
+/// tasa de éxito en la detección automática.
+pub fn infer_exe_candidates(text: &str) -> Vec<String> {
+    let mut candidates = Vec::new();
+    let text = text.trim();
+    if text.is_empty() {
+        return candidates;
+    }
+
+    // Candidato 1: Nombre base sin símbolos ni espacios
+    let base = text.replace(['\'', '"', ':', '-'], "").replace(' ', "");
+    if !base.is_empty() {
+        #[cfg(target_os = "windows")]
+        {
+            candidates.push(format!("{}.exe", base));
+            candidates.push(format!("{}-Win64-Shipping.exe", base));
+        }
+        #[cfg(not(target_os = "windows"))]
+        {
+            candidates.push(base.clone());
+        }
+    }
+
+    // Candidato 2: Acrónimo (Ej: "Grand Theft Auto" -> "gta")
+    let mut acronym = String::new();
+    let words = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty());
+
+    for word in words {
+        if word.chars().all(|c| c.is_ascii_digit()) {
+            acronym.push_str(word);
+        } else if let Some(c) = word.chars().next() {
+            acronym.push(c.to_ascii_lowercase());
+        }
+    }
+
+    if acronym.len() >= 2 && acronym.len() <= 6 {
+        #[cfg(target_os = "windows")]
+        candidates.push(format!("{}.exe", acronym));
+        #[cfg(not(target_os = "windows"))]
+        candidates.push(acronym.clone());
+    }
+
+    candidates.sort();
+    candidates.dedup();
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(name: &str, exe: &str, cmd: &[&str], cwd: Option<&str>) -> ProcessInfo {
+        ProcessInfo {
+            name: name.to_string(),
+            exe: Some(PathBuf::from(exe)),
+            cmd: cmd.iter().map(|a| a.to_string()).collect(),
+            cwd: cwd.map(PathBuf::from),
+        }
+    }
+
+    #[test]
+    fn matches_by_install_dir_and_proton_command_line() {
+        let matcher = GameMatcher::new(&[ProcessRule::Dir(
+            "/home/ana/.steam/steamapps/common/Celeste/".to_string(),
+        )]);
+        assert!(matcher.matches(&process(
+            "Celeste",
+            "/home/ana/.steam/steamapps/common/Celeste/Celeste",
+            &[],
+            None
+        )));
+        // Proton: el ejecutable es el cargador de Wine y el juego va como argumento.
+        let proton = "/home/ana/.steam/steamapps/common/Proton 9.0/files/bin/wine64-preloader";
+        assert!(matcher.matches(&process(
+            "Celeste.exe",
+            proton,
+            &[
+                proton,
+                "Z:\\home\\ana\\.steam\\steamapps\\common\\Celeste\\Celeste.exe"
+            ],
+            None
+        )));
+        assert!(matcher.matches(&process(
+            "Celeste.exe",
+            proton,
+            &[proton, "Celeste.exe"],
+            Some("/home/ana/.steam/steamapps/common/Celeste")
+        )));
+        // Un `game.exe` cualquiera fuera de la carpeta, o una carpeta hermana, no cuenta.
+        assert!(!matcher.matches(&process("game.exe", "/opt/otro/game.exe", &[], None)));
+        assert!(!matcher.matches(&process(
+            "Celeste",
+            "/home/ana/.steam/steamapps/common/Celeste2/Celeste",
+            &[],
+            None
+        )));
+    }
+
+    #[test]
+    fn matches_by_name_even_if_truncated_or_windows_path() {
+        let matcher = GameMatcher::new(&[
+            ProcessRule::Name("HollowKnightSilksong.exe".to_string()),
+            ProcessRule::Path("C:\\Games\\Hades\\Hades.exe".to_string()),
+        ]);
+        assert!(matcher.matches(&process(
+            "HollowKnightSil",
+            "/usr/bin/wine64-preloader",
+            &[
+                "/usr/bin/wine64-preloader",
+                "C:\\juegos\\HollowKnightSilksong.exe"
+            ],
+            None
+        )));
+        assert!(matcher.matches(&process(
+            "Hades.exe",
+            "c:\\games\\hades\\hades.exe",
+            &[],
+            None
+        )));
+        assert!(
+            GameMatcher::new(&[ProcessRule::Name("Hades".to_string())]).matches(&process(
+                "Hades.exe",
+                "D:\\Hades\\Hades.exe",
+                &[],
+                None
+            ))
+        );
+        assert!(!matcher.matches(&process("Hades.exe", "D:\\Hades\\Hades.exe", &[], None)));
+    }
+
+    #[test]
+    fn automatic_rules_skip_generic_names() {
+        let mut game: ConfiguredGame =
+            serde_json::from_value(serde_json::json!({ "id": "Game" })).unwrap();
+        let inferred = || {
+            vec![
+                "Game.exe".to_string(),
+                "Game-Win64-Shipping.exe".to_string(),
+            ]
+        };
+        assert_eq!(
+            rules_for_game(&game, || &[], inferred),
+            vec![ProcessRule::Name("Game-Win64-Shipping.exe".to_string())]
+        );
+
+        game.launch_executable_path = Some("/juegos/game/game.exe".to_string());
+        assert_eq!(
+            rules_for_game(&game, || &[], inferred),
+            vec![ProcessRule::Dir("/juegos/game".to_string())]
+        );
+
+        game.process_rules = Some(vec![ProcessRule::Name("java".to_string())]);
+        assert_eq!(
+            rules_for_game(&game, || &[], inferred),
+            vec![ProcessRule::Name("java".to_string())]
+        );
+    }
+}
@@ -0,0 +1,263 @@
+//! Avisos del kernel de Linux sobre procesos.
+//!
+//! - Conector de procesos (netlink): avisa de cada `exec` y cada salida del sistema.
+//!   Requiere `CAP_NET_ADMIN`, así que normalmente solo está disponible como root o si
+//!   se concede la capacidad al binario (`setcap cap_net_admin+ep`).
+//! - `pidfd` (Linux 5.3+): espera la salida de un proceso concreto sin permisos
+//!   especiales. Se usa con los procesos de juego ya detectados para notar el cierre al
+//!   instante aunque el conector no esté disponible.
+
+use super::ProcessMonitor;
+use std::collections::HashSet;
+use std::io;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::sync::{LazyLock, Mutex};
+
+/// Grupo y valor del conector de procesos (`linux/connector.h`).
+const CN_IDX_PROC: u32 = 1;
+const CN_VAL_PROC: u32 = 1;
+/// Operación de `linux/cn_proc.h` para empezar a recibir avisos.
+const PROC_CN_MCAST_LISTEN: u32 = 1;
+const PROC_EVENT_EXEC: u32 = 0x0000_0002;
+const PROC_EVENT_EXIT: u32 = 0x8000_0000;
+const CAP_NET_ADMIN: u32 = 12;
+
+/// Tamaños de `nlmsghdr` y `cn_msg`, que preceden a cada `proc_event`.
+const NLMSG_HDR_LEN: usize = 16;
+const CN_MSG_LEN: usize = 20;
+
+/// Se suscribe al conector de procesos y lee sus avisos en un hilo propio.
+/// Devuelve `false` si no está disponible (sin permisos o sin soporte en el kernel).
+pub(super) fn listen_proc_events(monitor: &'static ProcessMonitor) -> bool {
+    if !has_net_admin() {
+        return false;
+    }
+    let Ok(socket) = subscribe() else {
+        return false;
+    };
+    std::thread::Builder::new()
+        .name("proc-connector".to_string())
+        .spawn(move || read_events(socket, monitor))
+        .is_ok()
+}
+
+/// Sin `CAP_NET_ADMIN` efectiva el kernel descarta la suscripción sin dar error.
+fn has_net_admin() -> bool {
+    std::fs::read_to_string("/proc/self/status")
+        .ok()
+        .and_then(|status| {
+            status
+                .lines()
+                .find_map(|l| l.strip_prefix("CapEff:"))
+                .and_then(|hex| u64::from_str_radix(hex.trim(), 16).ok())
+        })
+        .is_some_and(|caps| caps & (1 << CAP_NET_ADMIN) != 0)
+}
+
+fn subscribe() -> io::Result<OwnedFd> {
+    // SAFETY: llamadas al sistema con argumentos válidos; el descriptor pasa a un `OwnedFd`.
+    let socket = unsafe {
+        let fd = libc::socket(
+            libc::AF_NETLINK,
+            libc::SOCK_DGRAM | libc::SOCK_CLOEXEC,
+            libc::NETLINK_CONNECTOR,
+        );
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        OwnedFd::from_raw_fd(fd)
+    };
+
+    // SAFETY: `sockaddr_nl` es POD; se inicializa a cero y se pasa con su tamaño.
+    let bound = unsafe {
+        let mut addr: libc::sockaddr_nl = std::mem::zeroed();
+        addr.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+        addr.nl_groups = CN_IDX_PROC;
+        libc::bind(
+            socket.as_raw_fd(),
+            &addr as *const libc::sockaddr_nl as *const libc::sockaddr,
+            std::mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+        )
+    };
+    if bound < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    // nlmsghdr + cn_msg + operación, en el orden de bytes del sistema.
+    let payload_len = CN_MSG_LEN + 4;
+    let mut msg = Vec::with_capacity(NLMSG_HDR_LEN + payload_len);
+    msg.extend(((NLMSG_HDR_LEN + payload_len) as u32).to_ne_bytes());
+    msg.extend((libc::NLMSG_DONE as u16).to_ne_bytes());
+    msg.extend(0u16.to_ne_bytes()); // flags
+    msg.extend(0u32.to_ne_bytes()); // seq
+    msg.extend(std::process::id().to_ne_bytes());
+    msg.extend(CN_IDX_PROC.to_ne_bytes());
+    msg.extend(CN_VAL_PROC.to_ne_bytes());
+    msg.extend(0u32.to_ne_bytes()); // seq
+    msg.extend(0u32.to_ne_bytes()); // ack
+    msg.extend(4u16.to_ne_bytes()); // len
+    msg.extend(0u16.to_ne_bytes()); // flags
+    msg.extend(PROC_CN_MCAST_LISTEN.to_ne_bytes());
+
+    // SAFETY: `msg` es un búfer válido de `msg.len()` bytes.
+    let sent = unsafe {
+        libc::send(
+            socket.as_raw_fd(),
+            msg.as_ptr() as *const libc::c_void,
+            msg.len(),
+            0,
+        )
+    };
+    if sent < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(socket)
+}
+
+fn read_events(socket: OwnedFd, monitor: &'static ProcessMonitor) {
+    let mut buf = vec![0u8; 8192];
+    loop {
+        // SAFETY: `buf` es un búfer válido de `buf.len()` bytes.
+        let n = unsafe {
+            libc::recv(
+                socket.as_raw_fd(),
+                buf.as_mut_ptr() as *mut libc::c_void,
+                buf.len(),
+                0,
+            )
+        };
+        if n < 0 {
+            match io::Error::last_os_error().raw_os_error() {
+                // Señal, o avisos perdidos por llegar demasiados: basta con revisar.
+                Some(libc::EINTR) => continue,
+                Some(libc::ENOBUFS) => {
+                    monitor.wake();
+                    continue;
+                }
+                _ => return,
+            }
+        }
+        for event in parse_events(&buf[..n as usize]) {
+            match event {
+                ProcEvent::Exec(pid) => monitor.notify_exec(pid),
+                ProcEvent::Exit(pid) => monitor.notify_exit(pid),
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum ProcEvent {
+    Exec(u32),
+    Exit(u32),
+}
+
+fn read_u32(buf: &[u8], at: usize) -> Option<u32> {
+    buf.get(at..at + 4)
+        .map(|b| u32::from_ne_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+/// Avisos de `exec` y de salida de procesos (no de hilos) de un datagrama del conector.
+fn parse_events(buf: &[u8]) -> Vec<ProcEvent> {
+    let mut events = Vec::new();
+    let mut offset = 0;
+    while let Some(len) = read_u32(buf, offset).map(|l| l as usize) {
+        if len < NLMSG_HDR_LEN || offset + len > buf.len() {
+            break;
+        }
+        // proc_event: what, cpu, timestamp_ns y después pid y tgid del proceso.
+        let event = offset + NLMSG_HDR_LEN + CN_MSG_LEN;
+        if let (Some(what), Some(pid), Some(tgid)) = (
+            read_u32(buf, event),
+            read_u32(buf, event + 16),
+            read_u32(buf, event + 20),
+        ) {
+            match what {
+                PROC_EVENT_EXEC => events.push(ProcEvent::Exec(tgid)),
+                PROC_EVENT_EXIT if pid == tgid => events.push(ProcEvent::Exit(tgid)),
+                _ => {}
+            }
+        }
+        // NLMSG_ALIGN: los mensajes van alineados a 4 bytes.
+        offset += (len + 3) & !3;
+    }
+    events
+}
+
+/// PIDs con un hilo esperando su salida.
+static WAITING: LazyLock<Mutex<HashSet<u32>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
+/// Espera en segundo plano la salida de cada proceso que aún no se esté esperando y
+/// despierta al monitor cuando termina.
+pub(super) fn watch_exits(monitor: &'static ProcessMonitor, pids: impl IntoIterator<Item = u32>) {
+    let Ok(mut waiting) = WAITING.lock() else {
+        return;
+    };
+    for pid in pids {
+        if waiting.contains(&pid) {
+            continue;
+        }
+        // SAFETY: `pidfd_open` no toca memoria; un resultado válido es un descriptor propio.
+        let fd = unsafe { libc::syscall(libc::SYS_pidfd_open, pid as libc::pid_t, 0) };
+        if fd < 0 {
+            // Kernel sin pidfd o el proceso ya terminó: lo verá la siguiente revisión.
+            continue;
+        }
+        // SAFETY: descriptor recién abierto que nadie más posee.
+        let pidfd = unsafe { OwnedFd::from_raw_fd(fd as RawFd) };
+        waiting.insert(pid);
+        let spawned = std::thread::Builder::new()
+            .name(format!("pidfd-{}", pid))
+            .spawn(move || {
+                wait_readable(&pidfd);
+                if let Ok(mut waiting) = WAITING.lock() {
+                    waiting.remove(&pid);
+                }
+                monitor.wake();
+            });
+        if spawned.is_err() {
+            waiting.remove(&pid);
+        }
+    }
+}
+
+/// Bloquea hasta que el `pidfd` indica que el proceso ha terminado.
+fn wait_readable(pidfd: &OwnedFd) {
+    let mut pfd = libc::pollfd {
+        fd: pidfd.as_raw_fd(),
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    // SAFETY: `pfd` es un `pollfd` válido y se pasa uno solo.
+    while unsafe { libc::poll(&mut pfd, 1, -1) } < 0
+        && io::Error::last_os_error().kind() == io::ErrorKind::Interrupted
+    {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(what: u32, pid: u32, tgid: u32) -> Vec<u8> {
+        let mut msg = vec![0u8; NLMSG_HDR_LEN + CN_MSG_LEN + 24];
+        let len = msg.len() as u32;
+        msg[..4].copy_from_slice(&len.to_ne_bytes());
+        let event = NLMSG_HDR_LEN + CN_MSG_LEN;
+        msg[event..event + 4].copy_from_slice(&what.to_ne_bytes());
+        msg[event + 16..event + 20].copy_from_slice(&pid.to_ne_bytes());
+        msg[event + 20..event + 24].copy_from_slice(&tgid.to_ne_bytes());
+        msg
+    }
+
+    #[test]
+    fn parses_exec_and_process_exit_events() {
+        let mut buf = message(PROC_EVENT_EXEC, 42, 42);
+        buf.extend(message(PROC_EVENT_EXIT, 43, 42)); // sale un hilo: no cuenta
+        buf.extend(message(PROC_EVENT_EXIT, 42, 42));
+        buf.extend(message(1, 50, 50)); // fork
+        assert_eq!(
+            parse_events(&buf),
+            vec![ProcEvent::Exec(42), ProcEvent::Exit(42)]
+        );
+    }
+}
@@ -0,0 +1,402 @@
+//! Servicio de monitorización de procesos de juego.
+//!
+//! Mantiene en memoria qué juegos están abiertos y avisa a quien se suscriba (subida
+//! al cerrar, tiempo de juego, bandeja, frontend) cuando algo cambia. En lugar de
+//! releer la configuración y evaluar todos los procesos en cada consulta:
+//! - las reglas de detección se cachean hasta que se guarda la biblioteca;
+//! - cada proceso se evalúa una sola vez, al aparecer o al hacer `exec`;
+//! - en Linux se escuchan los avisos del kernel ([`linux`]) y solo se revisa la lista
+//!   de procesos cuando llega alguno. Sin ellos se revisa cada [`POLL_INTERVAL`].
+//!
+//! Las consultas puntuales ([`ProcessMonitor::running`]) reutilizan la última
+//! revisión si es reciente, así que varias seguidas no recorren los procesos de nuevo.
+
+#[cfg(target_os = "linux")]
+mod linux;
+
+use super::process_check::get_sys;
+use super::process_match::{self, GameMatcher, ProcessInfo};
+use super::process_tree::ProcessTree;
+use crate::config;
+use crate::steam::shortcuts::{self, SteamShortcut};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Condvar, Mutex, MutexGuard, Once, OnceLock};
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessStatus, ProcessesToUpdate, UpdateKind};
+use tokio::sync::{broadcast, watch};
+
+/// Revisión periódica cuando no hay avisos del sistema.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Con avisos del kernel, la revisión periódica es solo una red de seguridad.
+const EVENT_SAFETY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Espera tras un aviso para agrupar ráfagas (un lanzador que abre varios procesos).
+const EVENT_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Separación mínima entre revisiones, aunque lleguen avisos sin parar.
+const MIN_SCAN_GAP: Duration = Duration::from_secs(1);
+
+/// Una consulta puntual reutiliza la última revisión si es así de reciente.
+const FRESH_FOR: Duration = Duration::from_secs(2);
+
+/// Un juego se ha abierto o cerrado.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GameProcessEvent {
+    /// `launched` indica si lo abrió la app ([`ProcessMonitor::track_launched`]).
+    Started { game_id: String, launched: bool },
+    /// `played` cuenta desde que se detectó abierto.
+    Stopped { game_id: String, played: Duration },
+}
+
+/// Reglas de detección de la biblioteca y versión de la que salieron.
+struct RuleCache {
+    generation: u64,
+    games: Vec<(String, GameMatcher)>,
+}
+
+/// Proceso ya evaluado: su arranque (por si el PID se reutiliza) y los juegos que cumple.
+struct SeenProcess {
+    start: u64,
+    games: Vec<String>,
+}
+
+#[derive(Default)]
+struct MonitorState {
+    rules: Option<RuleCache>,
+    seen: HashMap<u32, SeenProcess>,
+    /// Árboles de los procesos lanzados por la app, por ID de juego en minúsculas.
+    launched: HashMap<String, ProcessTree>,
+    /// Procesos que han hecho `exec` desde la última revisión.
+    exec_pending: HashSet<u32>,
+    running_since: HashMap<String, Instant>,
+    last_scan: Option<Instant>,
+    snapshot: HashMap<String, bool>,
+}
+
+pub struct ProcessMonitor {
+    state: Mutex<MonitorState>,
+    /// PIDs de procesos de juego, para decidir rápido si una salida importa.
+    game_pids: Mutex<HashSet<u32>>,
+    wake: Mutex<bool>,
+    wake_cv: Condvar,
+    events: broadcast::Sender<GameProcessEvent>,
+    status: watch::Sender<HashMap<String, bool>>,
+}
+
+static MONITOR: OnceLock<ProcessMonitor> = OnceLock::new();
+
+/// Monitor compartido por toda la app (y por la CLI, sin revisión en segundo plano).
+pub fn global() -> &'static ProcessMonitor {
+    MONITOR.get_or_init(ProcessMonitor::new)
+}
+
+impl ProcessMonitor {
+    fn new() -> Self {
+        let (events, _) = broadcast::channel(64);
+        let (status, _) = watch::channel(HashMap::new());
+        Self {
+            state: Mutex::new(MonitorState::default()),
+            game_pids: Mutex::new(HashSet::new()),
+            wake: Mutex::new(false),
+            wake_cv: Condvar::new(),
+            events,
+            status,
+        }
+    }
+
+    /// Avisos de juegos que se abren o se cierran.
+    pub fn subscribe(&self) -> broadcast::Receiver<GameProcessEvent> {
+        self.events.subscribe()
+    }
+
+    /// Estado de todos los juegos de la biblioteca (`id → abierto`); cambia con cada aviso.
+    pub fn watch_status(&self) -> watch::Receiver<HashMap<String, bool>> {
+        self.status.subscribe()
+    }
+
+    /// Estado actual de todos los juegos; solo revisa los procesos si la última
+    /// revisión tiene más de [`FRESH_FOR`] o la biblioteca ha cambiado.
+    pub fn running(&self) -> HashMap<String, bool> {
+        let mut state = self.lock_state();
+        let rules_current = state
+            .rules
+            .as_ref()
+            .is_some_and(|r| r.generation == config::library_generation());
+        if rules_current && state.last_scan.is_some_and(|t| t.elapsed() < FRESH_FOR) {
+            return state.snapshot.clone();
+        }
+        self.scan(&mut state)
+    }
+
+    /// Sigue un proceso lanzado por la app y todos sus hijos: el juego cuenta como
+    /// abierto mientras viva alguno, aunque no cumpla sus reglas (p. ej. un lanzador).
+    pub fn track_launched(&self, game_id: &str, pid: u32) {
+        self.lock_state()
+            .launched
+            .insert(game_id.trim().to_lowercase(), ProcessTree::new(pid));
+        self.wake();
+    }
+
+    /// Procesos del juego según la última revisión: los que cumplen sus reglas y los
+    /// lanzados para él por la app.
+    pub fn pids_of(&self, game_id: &str) -> HashSet<u32> {
+        let state = self.lock_state();
+        state
+            .seen
+            .iter()
+            .filter(|(_, seen)| seen.games.iter().any(|g| g.eq_ignore_ascii_case(game_id)))
+            .map(|(&pid, _)| pid)
+            .chain(
+                state
+                    .launched
+                    .get(&game_id.trim().to_lowercase())
+                    .into_iter()
+                    .flat_map(ProcessTree::pids),
+            )
+            .collect()
+    }
+
+    /// Pide una revisión cuanto antes.
+    pub fn wake(&self) {
+        if let Ok(mut wake) = self.wake.lock() {
+            *wake = true;
+            self.wake_cv.notify_one();
+        }
+    }
+
+    /// Un proceso ha hecho `exec`: su ejecutable ha cambiado y hay que evaluarlo de nuevo.
+    fn notify_exec(&self, pid: u32) {
+        self.lock_state().exec_pending.insert(pid);
+        self.wake();
+    }
+
+    /// Ha terminado un proceso; solo despierta al monitor si era de un juego.
+    fn notify_exit(&self, pid: u32) {
+        if self.game_pids.lock().is_ok_and(|pids| pids.contains(&pid)) {
+            self.wake();
+        }
+    }
+
+    /// Arranca la revisión en segundo plano; las llamadas siguientes no hacen nada.
+    pub fn start(&'static self) {
+        static STARTED: Once = Once::new();
+        STARTED.call_once(|| {
+            let _ = std::thread::Builder::new()
+                .name("process-monitor".to_string())
+                .spawn(move || self.run());
+        });
+    }
+
+    fn run(&'static self) {
+        #[cfg(target_os = "linux")]
+        let kernel_events = linux::listen_proc_events(self);
+        #[cfg(not(target_os = "linux"))]
+        let kernel_events = false;
+        let interval = if kernel_events {
+            EVENT_SAFETY_INTERVAL
+        } else {
+            POLL_INTERVAL
+        };
+
+        loop {
+            let started = Instant::now();
+            let game_pids = {
+                let mut state = self.lock_state();
+                self.scan(&mut state);
+                game_pids(&state)
+            };
+            #[cfg(target_os = "linux")]
+            linux::watch_exits(self, game_pids.iter().copied());
+            if let Ok(mut pids) = self.game_pids.lock() {
+                *pids = game_pids;
+            }
+
+            if self.wait_for_wake(interval) {
+                std::thread::sleep(
+                    EVENT_DEBOUNCE.max(MIN_SCAN_GAP.saturating_sub(started.elapsed())),
+                );
+            }
+        }
+    }
+
+    /// Espera un aviso o a que pase `timeout`; `true` si fue un aviso.
+    fn wait_for_wake(&self, timeout: Duration) -> bool {
+        let Ok(guard) = self.wake.lock() else {
+            std::thread::sleep(timeout);
+            return false;
+        };
+        let Ok((mut woke, _)) = self
+            .wake_cv
+            .wait_timeout_while(guard, timeout, |woke| !*woke)
+        else {
+            return false;
+        };
+        std::mem::take(&mut *woke)
+    }
+
+    fn lock_state(&self) -> MutexGuard<'_, MonitorState> {
+        self.state
+            .lock()
+            .expect("Mutex del monitor de procesos envenenado")
+    }
+
+    /// Revisa los procesos, evalúa solo los nuevos y publica los cambios.
+    fn scan(&self, state: &mut MonitorState) -> HashMap<String, bool> {
+        let generation = config::library_generation();
+        if state
+            .rules
+            .as_ref()
+            .is_none_or(|r| r.generation != generation)
+        {
+            state.rules = Some(RuleCache {
+                generation,
+                games: load_rules(),
+            });
+            state.seen.clear();
+        }
+
+        let mut sys = get_sys();
+        let exec: Vec<Pid> = state.exec_pending.drain().map(Pid::from_u32).collect();
+        if !exec.is_empty() {
+            sys.refresh_processes_specifics(
+                ProcessesToUpdate::Some(&exec),
+                refresh_kind(UpdateKind::Always),
+            );
+            for pid in &exec {
+                state.seen.remove(&pid.as_u32());
+            }
+        }
+        sys.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            refresh_kind(UpdateKind::OnlyIfNotSet),
+        );
+
+        let processes = sys.processes();
+        state.seen.retain(|pid, seen| {
+            processes.get(&Pid::from_u32(*pid)).is_some_and(|p| {
+                p.start_time() == seen.start && p.status() != ProcessStatus::Zombie
+            })
+        });
+        let rules = &state.rules.as_ref().expect("reglas recién cargadas").games;
+        for (pid, process) in processes {
+            if process.status() == ProcessStatus::Zombie || state.seen.contains_key(&pid.as_u32()) {
+                continue;
+            }
+            let info = ProcessInfo::from_sysinfo(process);
+            let games = rules
+                .iter()
+                .filter(|(_, matcher)| matcher.matches(&info))
+                .map(|(id, _)| id.clone())
+                .collect();
+            state.seen.insert(
+                pid.as_u32(),
+                SeenProcess {
+                    start: process.start_time(),
+                    games,
+                },
+            );
+        }
+        state.launched.retain(|_, tree| tree.observe(&sys));
+        drop(sys);
+
+        let mut open: HashSet<String> = state
+            .seen
+            .values()
+            .flat_map(|s| s.games.iter().map(|g| g.to_lowercase()))
+            .collect();
+        open.extend(state.launched.keys().cloned());
+        let snapshot: HashMap<String, bool> = rules
+            .iter()
+            .map(|(id, _)| (id.clone(), open.contains(&id.to_lowercase())))
+            .collect();
+        self.publish(state, snapshot)
+    }
+
+    /// Guarda el nuevo estado y avisa de los juegos que se han abierto o cerrado.
+    fn publish(
+        &self,
+        state: &mut MonitorState,
+        snapshot: HashMap<String, bool>,
+    ) -> HashMap<String, bool> {
+        let now = Instant::now();
+        for (game_id, &running) in &snapshot {
+            if running && !state.running_since.contains_key(game_id) {
+                state.running_since.insert(game_id.clone(), now);
+                let _ = self.events.send(GameProcessEvent::Started {
+                    game_id: game_id.clone(),
+                    launched: state.launched.contains_key(&game_id.to_lowercase()),
+                });
+            }
+        }
+        // Cerrados, o quitados de la biblioteca mientras estaban abiertos.
+        let stopped: Vec<String> = state
+            .running_since
+            .keys()
+            .filter(|id| !snapshot.get(*id).copied().unwrap_or(false))
+            .cloned()
+            .collect();
+        for game_id in stopped {
+            if let Some(since) = state.running_since.remove(&game_id) {
+                let _ = self.events.send(GameProcessEvent::Stopped {
+                    game_id,
+                    played: since.elapsed(),
+                });
+            }
+        }
+
+        state.last_scan = Some(now);
+        state.snapshot = snapshot.clone();
+        self.status.send_if_modified(|current| {
+            if *current == snapshot {
+                return false;
+            }
+            *current = snapshot.clone();
+            true
+        });
+        snapshot
+    }
+}
+
+fn refresh_kind(update: UpdateKind) -> ProcessRefreshKind {
+    ProcessRefreshKind::new()
+        .with_exe(update)
+        .with_cmd(update)
+        .with_cwd(update)
+}
+
+/// Reglas de detección de cada juego de la biblioteca.
+fn load_rules() -> Vec<(String, GameMatcher)> {
+    let cfg = config::load_config();
+    // Accesos directos no-Steam: se leen una sola vez y solo si algún juego los necesita.
+    let mut steam_shortcuts: Option<Vec<SteamShortcut>> = None;
+    let mut games = Vec::with_capacity(cfg.games.len());
+    for game in &cfg.games {
+        let rules = process_match::rules_for_game(
+            game,
+            || {
+                steam_shortcuts
+                    .get_or_insert_with(shortcuts::read_steam_shortcuts)
+                    .as_slice()
+            },
+            || process_match::infer_exe_candidates(&game.id),
+        );
+        let matcher = GameMatcher::new(&rules);
+        // Sin reglas útiles no hay nada que comparar en cada escaneo.
+        if !matcher.is_empty() {
+            games.push((game.id.clone(), matcher));
+        }
+    }
+    games
+}
+
+/// PIDs de los procesos de juego vivos: los que cumplen reglas y los lanzados por la app.
+fn game_pids(state: &MonitorState) -> HashSet<u32> {
+    state
+        .seen
+        .iter()
+        .filter(|(_, seen)| !seen.games.is_empty())
+        .map(|(&pid, _)| pid)
+        .chain(state.launched.values().flat_map(ProcessTree::pids))
+        .collect()
+}
@@ -0,0 +1,108 @@
+//! Seguimiento del árbol de procesos de un juego lanzado por la app.
+//!
+//! Muchos juegos arrancan a través de un lanzador que abre el ejecutable real como
+//! hijo y termina enseguida, así que esperar solo al PID lanzado no basta. Aquí se
+//! sigue al proceso raíz y a todos sus descendientes (aunque queden huérfanos) hasta
+//! que no quede ninguno vivo.
+
+use std::collections::HashMap;
+use sysinfo::{ProcessRefreshKind, ProcessStatus, ProcessesToUpdate, System};
+
+/// Procesos vivos del árbol: PID e instante de arranque (para no confundir un PID reutilizado).
+pub struct ProcessTree {
+    tracked: HashMap<u32, Option<u64>>,
+}
+
+impl ProcessTree {
+    pub fn new(root_pid: u32) -> Self {
+        Self {
+            tracked: HashMap::from([(root_pid, None)]),
+        }
+    }
+
+    /// Actualiza el árbol con los procesos actuales del sistema; `true` si sigue vivo.
+    pub fn refresh(&mut self, sys: &mut System) -> bool {
+        sys.refresh_processes_specifics(ProcessesToUpdate::All, ProcessRefreshKind::new());
+        self.observe(sys)
+    }
+
+    /// Como [`Self::refresh`], con la lista de procesos que `sys` ya tiene cargada.
+    pub fn observe(&mut self, sys: &System) -> bool {
+        let snapshot = sys
+            .processes()
+            .iter()
+            .filter(|(_, p)| p.status() != ProcessStatus::Zombie)
+            .map(|(pid, p)| {
+                (
+                    pid.as_u32(),
+                    p.parent().map(|pp| pp.as_u32()),
+                    p.start_time(),
+                )
+            });
+        self.update(snapshot)
+    }
+
+    /// PIDs seguidos en la última actualización.
+    pub fn pids(&self) -> impl Iterator<Item = u32> + '_ {
+        self.tracked.keys().copied()
+    }
+
+    /// Aplica una instantánea `(pid, padre, arranque)` de los procesos vivos.
+    fn update(&mut self, snapshot: impl Iterator<Item = (u32, Option<u32>, u64)>) -> bool {
+        let processes: HashMap<u32, (Option<u32>, u64)> = snapshot
+            .map(|(pid, parent, start)| (pid, (parent, start)))
+            .collect();
+
+        // Fuera los que terminaron o cuyo PID ya es de otro proceso.
+        self.tracked.retain(|pid, start| match processes.get(pid) {
+            Some(&(_, current)) if start.is_none_or(|s| s == current) => {
+                *start = Some(current);
+                true
+            }
+            _ => false,
+        });
+
+        // Hijos de procesos seguidos, hasta no encontrar más (nietos recién creados incluidos).
+        loop {
+            let children: Vec<(u32, u64)> = processes
+                .iter()
+                .filter(|(pid, _)| !self.tracked.contains_key(pid))
+                .filter_map(|(&pid, &(parent, start))| {
+                    let parent_start = (*self.tracked.get(&parent?)?)?;
+                    (start >= parent_start).then_some((pid, start))
+                })
+                .collect();
+            if children.is_empty() {
+                break;
+            }
+            self.tracked
+                .extend(children.into_iter().map(|(pid, start)| (pid, Some(start))));
+        }
+
+        !self.tracked.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn follows_children_after_the_launcher_exits() {
+        let mut tree = ProcessTree::new(10);
+        // El lanzador (10) abre el juego (11), que abre un ayudante (12).
+        assert!(tree.update(
+            [
+                (1, None, 0),
+                (10, Some(1), 100),
+                (11, Some(10), 105),
+                (12, Some(11), 106)
+            ]
+            .into_iter()
+        ));
+        // El lanzador termina y el juego queda huérfano (reasignado a init).
+        assert!(tree.update([(1, None, 0), (11, Some(1), 105)].into_iter()));
+        // Un PID reutilizado por otro proceso no cuenta como el juego.
+        assert!(!tree.update([(1, None, 0), (11, Some(1), 500)].into_iter()));
+    }
+}
@@ -0,0 +1,152 @@
+//! Demonio que vacía la cola persistente de sincronización (`sync_jobs`).
+//!
+//! Cada tick (o en cuanto se encola algo) comprueba si hay trabajos cuyo turno
+//! llegó. Antes de ejecutarlos sondea el backend: sin conexión no se gastan
+//! intentos, y al volver la conexión se adelantan todos los pendientes.
+
+use crate::commands::logs::sync_logger;
+use crate::commands::sync::multipart_upload::PAUSED_ERR_MSG;
+use crate::commands::sync::queue::store::{self, SyncJobDto, SyncJobKind};
+use crate::commands::sync::queue::{notify_changed, now_secs, with_db, QUEUE_WAKE};
+use crate::commands::sync::{download, full_backup, upload};
+use crate::sqlite::AppDb;
+use crate::storage::{Storage, StorageBackend};
+use crate::transfers::{self, TransferKind, TransferPriority};
+use crate::tray::tray_state::TrayStateInner;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::AppHandle;
+
+/// Intervalo entre comprobaciones de la cola (y de la conexión mientras no hay red).
+const QUEUE_TICK: Duration = Duration::from_secs(30);
+
+/// Arranca el demonio. Los trabajos que quedaron `running` tras un cierre vuelven a `pending`.
+pub fn spawn_queue_worker(app: AppHandle, db: AppDb, tray: Arc<TrayStateInner>) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = with_db(&db, store::recover_interrupted).await {
+            sync_logger::log_error("sync_queue", "No se pudo recuperar la cola", &e);
+        }
+
+        let mut offline = false;
+        loop {
+            tokio::select! {
+                _ = QUEUE_WAKE.notified() => {}
+                _ = tokio::time::sleep(QUEUE_TICK) => {}
+            }
+
+            let Ok((pending, due)) = with_db(&db, |c| store::pending_counts(c, now_secs())).await
+            else {
+                continue;
+            };
+            // Sin red se sondea en cada tick aunque nada toque aún, para detectar la vuelta.
+            if pending == 0 || (due == 0 && !offline) {
+                continue;
+            }
+
+            if !backend_reachable().await {
+                offline = true;
+                continue;
+            }
+            if offline {
+                offline = false;
+                let _ = with_db(&db, |c| store::release_backoff(c, now_secs())).await;
+            }
+
+            drain_due_jobs(&app, &db, &tray).await;
+        }
+    });
+}
+
+/// Sondeo de conectividad: el backend activo responde a un listado.
+async fn backend_reachable() -> bool {
+    let Ok(storage) = Storage::active() else {
+        return false;
+    };
+    storage.list(storage.user_id()).await.is_ok()
+}
+
+/// Ejecuta, uno a uno, los trabajos cuyo turno ya llegó.
+async fn drain_due_jobs(app: &AppHandle, db: &AppDb, tray: &Arc<TrayStateInner>) {
+    loop {
+        let job = match with_db(db, |c| store::claim_next_due(c, now_secs())).await {
+            Ok(Some(job)) => job,
+            Ok(None) => return,
+            Err(e) => {
+                sync_logger::log_error("sync_queue", "No se pudo leer la cola", &e);
+                return;
+            }
+        };
+        notify_changed(app);
+
+        tray.syncing_inc();
+        tray.update_tooltip();
+        let result = run_job(app, &job).await;
+        tray.syncing_dec();
+        tray.clone().refresh_unsynced_async();
+
+        let id = job.id;
+        let res = match result {
+            Ok(()) => with_db(db, move |c| store::complete(c, id)).await,
+            Err(e) => {
+                sync_logger::log_error(
+                    "sync_queue",
+                    &format!(
+                        "{:?} de {} (intento {})",
+                        job.kind,
+                        job.game_id,
+                        job.attempts + 1
+                    ),
+                    &e,
+                );
+                with_db(db, move |c| store::fail(c, id, &e, now_secs())).await
+            }
+        };
+        if let Err(e) = res {
+            sync_logger::log_error("sync_queue", "No se pudo actualizar la cola", &e);
+        }
+        notify_changed(app);
+    }
+}
+
+async fn run_job(app: &AppHandle, job: &SyncJobDto) -> Result<(), String> {
+    let game_id = job.game_id.clone();
+    let kind = match job.kind {
+        SyncJobKind::Upload => TransferKind::Upload,
+        SyncJobKind::Download => TransferKind::Download,
+        SyncJobKind::FullBackup => TransferKind::FullBackup,
+    };
+    // Los reintentos ceden el paso a lo que el usuario lanza a mano.
+    let transfer = transfers::begin(kind, &game_id, TransferPriority::Background).await;
+    let observer = transfer.observer(app);
+    let result = match job.kind {
+        SyncJobKind::Upload => {
+            upload::sync_upload_game_impl(game_id, &observer, transfer.token()).await?
+        }
+        SyncJobKind::Download => {
+            // No se sobrescriben guardados de un juego abierto: se reintenta más tarde.
+            let running =
+                crate::system::process_check::are_games_running(std::slice::from_ref(&game_id));
+            if running.get(&game_id).copied().unwrap_or(false) {
+                return Err("El juego está en ejecución".to_string());
+            }
+            download::sync_download_game_impl(game_id, &observer, transfer.token(), None).await?
+        }
+        SyncJobKind::FullBackup => {
+            // Una pausa queda en `paused_transfers`: desde ahí se reanuda, no desde la cola.
+            return match full_backup::create_and_upload_full_backup_impl(
+                game_id,
+                &observer,
+                transfer.token(),
+            )
+            .await
+            {
+                Err(e) if e != PAUSED_ERR_MSG => Err(e),
+                _ => Ok(()),
+            };
+        }
+    };
+    if result.err_count > 0 {
+        return Err(result.errors.join("; "));
+    }
+    Ok(())
+}
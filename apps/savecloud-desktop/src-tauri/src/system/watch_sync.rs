@@ -12,6 +12,9 @@
 use crate::commands::logs::sync_logger;
 use crate::commands::sync::{self};
 use crate::config;
+use crate::transfers::{self, TransferKind, TransferPriority};
+use crate::utils::path_translation::{resolve_save_path, HostContext};
+use crate::utils::path_utils;
 use notify::{RecursiveMode, Watcher};
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
@@ -21,68 +24,39 @@ use tauri::{AppHandle, Emitter};
 use tokio::sync::mpsc;
 use tokio::time::Instant;
 
-/// Resuelve interpolaciones de entorno (ej. `%APPDATA%`) y atajos (ej. `~`)
-/// devolviendo una ruta física absoluta y evaluable por el sistema operativo.
-///
-/// # Arguments
-///
-/// * `raw` - Cadena de texto representativa de la ruta sin procesar.
-///
-/// # Returns
-///
-/// Devuelve `Some(PathBuf)` si la expansión resulta en una ruta válida,
-/// o `None` si la cadena está vacía o malformada.
-fn expand_path(raw: &str) -> Option<PathBuf> {
-    let mut result = raw.to_string();
-    let re = regex::Regex::new(r"%([^%]+)%").ok()?;
-
-    for cap in re.captures_iter(raw) {
-        let var = cap.get(1)?.as_str();
-        let val = std::env::var(var).unwrap_or_default();
-        result = result.replace(&format!("%{}%", var), &val);
-    }
-
-    if result.starts_with('~') {
-        let home = std::env::var("USERPROFILE")
-            .or_else(|_| std::env::var("HOME"))
-            .unwrap_or_default();
-
-        if !home.is_empty() {
-            let rest = result.trim_start_matches('~').trim_start_matches('/');
-            result = if rest.is_empty() {
-                home
-            } else {
-                format!("{}/{}", home.trim_end_matches(&['/', '\\']), rest)
-            };
-        }
-    }
-
-    if result.is_empty() {
-        None
-    } else {
-        Some(PathBuf::from(result))
-    }
-}
-
 /// Cruza un evento de modificación del sistema de archivos contra el registro
 /// de directorios vigilados para identificar qué juego originó el cambio.
 ///
 /// Selecciona la coincidencia más profunda (el directorio más específico) en caso
-/// de que existan rutas anidadas entre distintos juegos.
+/// de que existan rutas anidadas entre distintos juegos. Las raíces con patrón
+/// (carpetas compartidas de emuladores) solo aceptan archivos que lo cumplan.
 ///
 /// # Arguments
 ///
 /// * `path` - Ruta del archivo modificado notificada por el evento del OS.
-/// * `watch_roots` - Matriz de tuplas vinculando rutas raíz con identificadores lógicos.
-fn path_to_game_id(path: &Path, watch_roots: &[(PathBuf, String)]) -> Option<String> {
+/// * `watch_roots` - Matriz de tuplas vinculando rutas raíz (y patrón opcional) con identificadores lógicos.
+fn path_to_game_id(
+    path: &Path,
+    watch_roots: &[(PathBuf, Option<String>, String)],
+) -> Option<String> {
     let path = path.canonicalize().ok()?;
     let mut best: Option<(PathBuf, String)> = None;
 
-    for (root, game_id) in watch_roots {
+    for (root, pattern, game_id) in watch_roots {
         let Ok(canon_root) = root.canonicalize() else {
             continue;
         };
 
+        if let Some(pattern) = pattern {
+            let matches = path.parent() == Some(canon_root.as_path())
+                && path
+                    .file_name()
+                    .is_some_and(|n| path_utils::wildcard_match(pattern, &n.to_string_lossy()));
+            if !matches {
+                continue;
+            }
+        }
+
         if path.starts_with(&canon_root) {
             match &best {
                 None => best = Some((canon_root, game_id.clone())),
@@ -122,13 +96,25 @@ pub fn spawn_watcher(app: AppHandle, tray_state: Arc<crate::tray::tray_state::Tr
         return;
     }
 
-    let mut watch_roots: Vec<(PathBuf, String)> = Vec::new();
+    let mut watch_roots: Vec<(PathBuf, Option<String>, String)> = Vec::new();
     let mut unique_dirs: HashSet<PathBuf> = HashSet::new();
 
     // Resolver y normalizar las rutas objetivo configuradas
     for game in &cfg.games {
+        let ctx = HostContext::for_configured_game(game);
         for raw in &game.paths {
-            let Some(expanded) = expand_path(raw.trim()) else {
+            if let Some((dir, pattern)) = path_utils::split_glob(raw) {
+                let Some(dir) =
+                    resolve_save_path(&dir.to_string_lossy(), &ctx).filter(|d| d.is_dir())
+                else {
+                    continue;
+                };
+                unique_dirs.insert(dir.clone());
+                watch_roots.push((dir, Some(pattern), game.id.clone()));
+                continue;
+            }
+
+            let Some(expanded) = resolve_save_path(raw, &ctx) else {
                 continue;
             };
             if !expanded.exists() {
@@ -142,7 +128,7 @@ pub fn spawn_watcher(app: AppHandle, tray_state: Arc<crate::tray::tray_state::Tr
             };
 
             unique_dirs.insert(watch_dir.clone());
-            watch_roots.push((watch_dir, game.id.clone()));
+            watch_roots.push((watch_dir, None, game.id.clone()));
         }
     }
 
@@ -246,11 +232,17 @@ pub fn spawn_watcher(app: AppHandle, tray_state: Arc<crate::tray::tray_state::Tr
                         tray_clone.update_tooltip();
 
                         tauri::async_runtime::spawn(async move {
+                            let transfer = transfers::begin(
+                                TransferKind::Upload,
+                                &gid,
+                                TransferPriority::Normal
+                            ).await;
                             let res = sync::upload::sync_upload_game_impl(
                                 gid.clone(),
-                                app_clone.clone(),
-                                None
+                                &transfer.observer(&app_clone),
+                                transfer.token()
                             ).await;
+                            drop(transfer);
 
                             // Al terminar, liberamos el juego para que el watcher pueda volver a escucharlo
                             active_syncs_clone.lock().unwrap().remove(&gid);
@@ -267,6 +259,12 @@ pub fn spawn_watcher(app: AppHandle, tray_state: Arc<crate::tray::tray_state::Tr
                                     });
                                 }
                                 Err(e) => {
+                                    sync::queue::enqueue_after_failure(
+                                        &app_clone,
+                                        sync::queue::store::SyncJobKind::Upload,
+                                        &gid,
+                                        &e,
+                                    ).await;
                                     let _ = app_clone.emit("auto-sync-error", PayloadError {
                                         game_id: gid,
                                         error: e
@@ -0,0 +1,769 @@
+//! Servidor HTTP en proceso que imita la API de SaveCloud para tests de integración.
+//!
+//! Implementa los endpoints `/saves` que usa la app (URLs de subida y descarga,
+//! multipart, backups, borrado y renombrado de juegos) sobre un directorio
+//! temporal, de modo que upload, download, multipart y backups completos se pueden
+//! ejercitar sin desplegar la pila de AWS.
+//!
+//! Las URLs de transferencia imitan a las prefirmadas de S3: apuntan al propio
+//! servidor (`/objects/<clave>` y `/parts/<uploadId>/<n>`) y llevan una firma en la
+//! query que se valida en cada petición, así que una URL manipulada devuelve 403.
+//!
+//! ## Inyección de fallos
+//!
+//! [`MockApi::inject`] registra fallos por prefijo de ruta (latencia, respuestas 5xx
+//! o cuerpos truncados) que se aplican a las siguientes `n` peticiones que
+//! coincidan; [`MockApi::hits`] cuenta las peticiones recibidas por ruta para
+//! comprobar cuántos reintentos hizo el cliente.
+//!
+//! ```ignore
+//! let api = MockApi::start().await;
+//! api.inject("/saves/upload-urls", Fault::Status(503), 2);
+//! let backend = api.backend("user1");
+//! ```
+
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::body::{Body, Bytes};
+use axum::extract::{DefaultBodyLimit, State};
+use axum::http::{header, HeaderMap, HeaderValue, Method, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use axum::{Json, Router};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use tokio::sync::oneshot;
+
+use crate::storage::api::ApiBackend;
+
+/// API key que envían los backends creados con [`MockApi::backend`].
+pub const MOCK_API_KEY: &str = "mock-api-key";
+
+/// Fallo a inyectar en una petición.
+#[derive(Clone, Debug)]
+pub enum Fault {
+    /// Retrasa la respuesta; la petición se procesa con normalidad después.
+    Latency(Duration),
+    /// Responde con este código de estado sin procesar la petición.
+    Status(u16),
+    /// Procesa la petición pero corta el cuerpo de la respuesta tras estos bytes,
+    /// anunciando el `Content-Length` completo (conexión cortada a mitad).
+    Truncate(usize),
+}
+
+struct FaultRule {
+    path_prefix: String,
+    fault: Fault,
+    remaining: u32,
+}
+
+struct MockState {
+    objects_dir: PathBuf,
+    parts_dir: PathBuf,
+    base_url: String,
+    secret: String,
+    /// `uploadId` → clave destino de las subidas multipart abiertas.
+    uploads: Mutex<HashMap<String, String>>,
+    faults: Mutex<Vec<FaultRule>>,
+    hits: Mutex<HashMap<String, u32>>,
+    upload_seq: AtomicU32,
+}
+
+/// Servidor de pruebas; se detiene y borra su directorio al soltarse.
+pub struct MockApi {
+    state: Arc<MockState>,
+    shutdown: Option<oneshot::Sender<()>>,
+    _root: tempfile::TempDir,
+}
+
+impl MockApi {
+    /// Arranca el servidor en un puerto libre de `127.0.0.1`.
+    pub async fn start() -> Self {
+        let root = tempfile::tempdir().expect("directorio temporal del mock");
+        let objects_dir = root.path().join("objects");
+        let parts_dir = root.path().join("parts");
+        std::fs::create_dir_all(&objects_dir).expect("carpeta de objetos del mock");
+        std::fs::create_dir_all(&parts_dir).expect("carpeta de partes del mock");
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("puerto libre para el mock");
+        let addr = listener.local_addr().expect("dirección del mock");
+
+        let state = Arc::new(MockState {
+            objects_dir,
+            parts_dir,
+            base_url: format!("http://{}", addr),
+            secret: format!("mock-secret-{}", addr.port()),
+            uploads: Mutex::new(HashMap::new()),
+            faults: Mutex::new(Vec::new()),
+            hits: Mutex::new(HashMap::new()),
+            upload_seq: AtomicU32::new(0),
+        });
+
+        // Sin el límite de 2 MB de axum: las partes multipart reales son de 10 MB.
+        let app = Router::new()
+            .fallback(handle)
+            .layer(DefaultBodyLimit::disable())
+            .with_state(state.clone());
+        let (tx, rx) = oneshot::channel::<()>();
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app)
+                .with_graceful_shutdown(async {
+                    let _ = rx.await;
+                })
+                .await;
+        });
+
+        Self {
+            state,
+            shutdown: Some(tx),
+            _root: root,
+        }
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.state.base_url
+    }
+
+    /// Backend de API apuntando al mock con la identidad `user_id`.
+    pub fn backend(&self, user_id: &str) -> ApiBackend {
+        ApiBackend::new(
+            self.base_url().to_string(),
+            user_id.to_string(),
+            MOCK_API_KEY.to_string(),
+        )
+    }
+
+    /// Aplica `fault` a las siguientes `times` peticiones cuya ruta empiece por
+    /// `path_prefix` (`/saves/upload-urls`, `/objects/`, `/parts/`...).
+    pub fn inject(&self, path_prefix: &str, fault: Fault, times: u32) {
+        if times == 0 {
+            return;
+        }
+        self.state.faults.lock().unwrap().push(FaultRule {
+            path_prefix: path_prefix.to_string(),
+            fault,
+            remaining: times,
+        });
+    }
+
+    /// Peticiones recibidas en rutas que empiezan por `path_prefix`.
+    pub fn hits(&self, path_prefix: &str) -> u32 {
+        self.state
+            .hits
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(path, _)| path.starts_with(path_prefix))
+            .map(|(_, n)| n)
+            .sum()
+    }
+
+    /// Guarda un objeto directamente, sin pasar por la API.
+    pub fn put_object(&self, key: &str, data: &[u8]) {
+        let path = self
+            .state
+            .object_path(key)
+            .expect("clave válida para el mock");
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).expect("carpeta del objeto");
+        }
+        std::fs::write(path, data).expect("escritura del objeto");
+    }
+
+    /// Contenido de un objeto, o `None` si no existe.
+    pub fn object(&self, key: &str) -> Option<Vec<u8>> {
+        std::fs::read(self.state.object_path(key)?).ok()
+    }
+
+    /// Subidas multipart iniciadas que no se han completado ni abortado.
+    pub fn open_uploads(&self) -> usize {
+        self.state.uploads.lock().unwrap().len()
+    }
+}
+
+impl Drop for MockApi {
+    fn drop(&mut self) {
+        if let Some(tx) = self.shutdown.take() {
+            let _ = tx.send(());
+        }
+    }
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn safe_relative(relative: &str) -> Option<PathBuf> {
+    let path = Path::new(relative);
+    let valid =
+        !relative.is_empty() && path.components().all(|c| matches!(c, Component::Normal(_)));
+    valid.then(|| path.to_path_buf())
+}
+
+impl MockState {
+    fn object_path(&self, key: &str) -> Option<PathBuf> {
+        safe_relative(key).map(|p| self.objects_dir.join(p))
+    }
+
+    fn part_path(&self, upload_id: &str, part_number: u32) -> Option<PathBuf> {
+        safe_relative(upload_id).map(|p| self.parts_dir.join(p).join(part_number.to_string()))
+    }
+
+    fn sign(&self, method: &str, path: &str) -> String {
+        sha256_hex(format!("{}:{}:{}", self.secret, method, path).as_bytes())
+    }
+
+    /// URL "prefirmada" para `method` sobre `path` (ruta ya codificada).
+    fn presigned(&self, method: &str, path: &str) -> String {
+        format!(
+            "{}{}?signature={}",
+            self.base_url,
+            path,
+            self.sign(method, path)
+        )
+    }
+
+    fn object_url_path(key: &str) -> String {
+        let encoded: Vec<String> = key
+            .split('/')
+            .map(|s| urlencoding::encode(s).into_owned())
+            .collect();
+        format!("/objects/{}", encoded.join("/"))
+    }
+
+    fn record_hit(&self, path: &str) {
+        *self
+            .hits
+            .lock()
+            .unwrap()
+            .entry(path.to_string())
+            .or_insert(0) += 1;
+    }
+
+    fn take_fault(&self, path: &str) -> Option<Fault> {
+        let mut faults = self.faults.lock().unwrap();
+        let idx = faults
+            .iter()
+            .position(|r| path.starts_with(&r.path_prefix))?;
+        let fault = faults[idx].fault.clone();
+        faults[idx].remaining -= 1;
+        if faults[idx].remaining == 0 {
+            faults.remove(idx);
+        }
+        Some(fault)
+    }
+
+    /// Objetos bajo `<raíz>/<prefix>` como `(clave, bytes, fecha RFC 3339)`.
+    fn walk(&self, prefix: &str) -> Vec<(String, u64, String)> {
+        let Some(dir) = self.object_path(prefix) else {
+            return Vec::new();
+        };
+        walkdir::WalkDir::new(dir)
+            .into_iter()
+            .flatten()
+            .filter(|e| e.file_type().is_file())
+            .filter_map(|e| {
+                let relative = e.path().strip_prefix(&self.objects_dir).ok()?;
+                let key = relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                let meta = e.metadata().ok()?;
+                let modified = meta
+                    .modified()
+                    .ok()
+                    .map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339())
+                    .unwrap_or_default();
+                Some((key, meta.len(), modified))
+            })
+            .collect()
+    }
+}
+
+fn status(code: u16, message: &str) -> Response {
+    let code = StatusCode::from_u16(code).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    (
+        code,
+        Json(json!({ "error": code.canonical_reason(), "message": message })),
+    )
+        .into_response()
+}
+
+/// Sustituye el cuerpo por sus primeros `keep` bytes seguidos de un error de E/S,
+/// manteniendo el `Content-Length` original: el cliente ve la conexión cortada.
+async fn truncate(response: Response, keep: usize) -> Response {
+    let (mut parts, body) = response.into_parts();
+    let full = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(full) => full,
+        Err(e) => return status(500, &e.to_string()),
+    };
+    parts
+        .headers
+        .insert(header::CONTENT_LENGTH, HeaderValue::from(full.len()));
+    let chunks: Vec<Result<Bytes, std::io::Error>> = vec![
+        Ok(full.slice(..keep.min(full.len()))),
+        Err(std::io::Error::other("cuerpo truncado por el mock")),
+    ];
+    Response::from_parts(parts, Body::from_stream(futures_util::stream::iter(chunks)))
+}
+
+async fn handle(
+    State(state): State<Arc<MockState>>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let path = uri.path().to_string();
+    state.record_hit(&path);
+
+    let mut truncate_at = None;
+    match state.take_fault(&path) {
+        Some(Fault::Latency(delay)) => tokio::time::sleep(delay).await,
+        Some(Fault::Status(code)) => return status(code, "fallo inyectado por el mock"),
+        Some(Fault::Truncate(keep)) => truncate_at = Some(keep),
+        None => {}
+    }
+
+    let response = route(&state, &method, &uri, &headers, &body).await;
+    match truncate_at {
+        Some(keep) => truncate(response, keep).await,
+        None => response,
+    }
+}
+
+async fn route(
+    state: &MockState,
+    method: &Method,
+    uri: &Uri,
+    headers: &HeaderMap,
+    body: &Bytes,
+) -> Response {
+    let path = uri.path();
+    if path.starts_with("/objects/") || path.starts_with("/parts/") {
+        return transfer(state, method, uri, body);
+    }
+
+    let user_id = match headers.get("x-user-id").and_then(|v| v.to_str().ok()) {
+        Some(u) if !u.is_empty() => u.to_string(),
+        _ => return status(401, "Falta x-user-id"),
+    };
+    if headers.get("x-api-key").and_then(|v| v.to_str().ok()) != Some(MOCK_API_KEY) {
+        return status(401, "API key inválida");
+    }
+    let json_body = || serde_json::from_slice::<Value>(body).unwrap_or(Value::Null);
+
+    match (method.as_str(), path) {
+        ("GET", "/saves") => list_saves(state, &user_id),
+        ("GET", "/saves/backups") => {
+            let game_id = uri
+                .query()
+                .unwrap_or_default()
+                .split('&')
+                .filter_map(|kv| kv.split_once('='))
+                .find(|(k, _)| *k == "gameId")
+                .and_then(|(_, v)| urlencoding::decode(v).ok())
+                .map(|v| v.into_owned())
+                .unwrap_or_default();
+            list_backups(state, &user_id, &game_id)
+        }
+        ("DELETE", "/saves/backup") => delete_backup(state, &user_id, &json_body()),
+        ("PATCH", "/saves/backup") => rename_backup(state, &user_id, &json_body()),
+        ("POST", "/saves/delete-game") => delete_game(state, &user_id, &json_body()),
+        ("POST", "/saves/rename-game") => rename_game(state, &user_id, &json_body()),
+        ("POST", "/saves/upload-url") => upload_url(state, &user_id, &json_body()),
+        ("POST", "/saves/upload-urls") => upload_urls(state, &user_id, &json_body()),
+        ("POST", "/saves/download-url") => download_url(state, &user_id, &json_body()),
+        ("POST", "/saves/download-urls") => download_urls(state, &user_id, &json_body()),
+        ("POST", "/saves/multipart/init") => multipart_init(state, &user_id, &json_body()),
+        ("POST", "/saves/multipart/part-urls") => multipart_part_urls(state, &json_body()),
+        ("POST", "/saves/multipart/complete") => multipart_complete(state, &json_body()),
+        ("POST", "/saves/multipart/abort") => multipart_abort(state, &json_body()),
+        _ => status(404, "Ruta no implementada en el mock"),
+    }
+}
+
+fn str_field<'a>(body: &'a Value, field: &str) -> Option<&'a str> {
+    body.get(field)
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+/// Ruta en disco de `key` si pertenece a `user_id`; si no, la respuesta 400 de la API.
+fn owned_object(state: &MockState, user_id: &str, key: &str) -> Result<PathBuf, Box<Response>> {
+    state
+        .object_path(key)
+        .filter(|_| key.starts_with(&format!("{}/", user_id)))
+        .ok_or_else(|| Box::new(status(400, &format!("Invalid key: {}", key))))
+}
+
+fn list_saves(state: &MockState, user_id: &str) -> Response {
+    let saves: Vec<Value> = state
+        .walk(user_id)
+        .into_iter()
+        .filter_map(|(key, size, last_modified)| {
+            let mut parts = key.splitn(3, '/');
+            let (_, game_id, filename) = (parts.next()?, parts.next()?, parts.next()?);
+            Some(json!({
+                "gameId": game_id,
+                "key": key,
+                "filename": filename,
+                "lastModified": last_modified,
+                "size": size,
+            }))
+        })
+        .collect();
+    Json(saves).into_response()
+}
+
+fn list_backups(state: &MockState, user_id: &str, game_id: &str) -> Response {
+    if game_id.is_empty() {
+        return status(400, "gameId requerido");
+    }
+    let backups: Vec<Value> = state
+        .walk(&format!("{}/{}/backups", user_id, game_id))
+        .into_iter()
+        .map(|(key, size, last_modified)| {
+            let filename = key.rsplit('/').next().unwrap_or_default().to_string();
+            json!({
+                "key": key,
+                "lastModified": last_modified,
+                "size": size,
+                "filename": filename,
+            })
+        })
+        .collect();
+    Json(json!({ "backups": backups })).into_response()
+}
+
+fn delete_backup(state: &MockState, user_id: &str, body: &Value) -> Response {
+    let Some(key) = str_field(body, "key") else {
+        return status(400, "key requerido");
+    };
+    match owned_object(state, user_id, key) {
+        Ok(path) => {
+            let _ = std::fs::remove_file(path);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(res) => *res,
+    }
+}
+
+fn rename_backup(state: &MockState, user_id: &str, body: &Value) -> Response {
+    let (Some(key), Some(new_filename)) = (str_field(body, "key"), str_field(body, "newFilename"))
+    else {
+        return status(400, "key y newFilename requeridos");
+    };
+    if new_filename.contains('/') {
+        return status(400, "newFilename must not contain '/'");
+    }
+    let from = match owned_object(state, user_id, key) {
+        Ok(p) => p,
+        Err(res) => return *res,
+    };
+    match std::fs::rename(&from, from.with_file_name(new_filename)) {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => status(500, &e.to_string()),
+    }
+}
+
+fn delete_game(state: &MockState, user_id: &str, body: &Value) -> Response {
+    let Some(game_id) = str_field(body, "gameId") else {
+        return status(400, "gameId requerido");
+    };
+    if let Some(dir) = state.object_path(&format!("{}/{}", user_id, game_id)) {
+        let _ = std::fs::remove_dir_all(dir);
+    }
+    StatusCode::NO_CONTENT.into_response()
+}
+
+fn rename_game(state: &MockState, user_id: &str, body: &Value) -> Response {
+    let (Some(old_id), Some(new_id)) = (str_field(body, "oldGameId"), str_field(body, "newGameId"))
+    else {
+        return status(400, "oldGameId y newGameId requeridos");
+    };
+    if old_id == new_id {
+        return status(400, "oldGameId and newGameId must be different");
+    }
+    let (Some(from), Some(to)) = (
+        state.object_path(&format!("{}/{}", user_id, old_id)),
+        state.object_path(&format!("{}/{}", user_id, new_id)),
+    ) else {
+        return status(400, "gameId inválido");
+    };
+    if from.is_dir() {
+        if let Err(e) = std::fs::rename(from, to) {
+            return status(500, &e.to_string());
+        }
+    }
+    StatusCode::NO_CONTENT.into_response()
+}
+
+fn upload_entry(state: &MockState, user_id: &str, game_id: &str, filename: &str) -> Option<Value> {
+    let key = crate::storage::object_key(user_id, game_id, filename);
+    state.object_path(&key)?;
+    let url = state.presigned("PUT", &MockState::object_url_path(&key));
+    Some(json!({
+        "gameId": game_id,
+        "filename": filename,
+        "uploadUrl": url,
+        "key": key,
+    }))
+}
+
+fn upload_url(state: &MockState, user_id: &str, body: &Value) -> Response {
+    let (Some(game_id), Some(filename)) = (str_field(body, "gameId"), str_field(body, "filename"))
+    else {
+        return status(400, "gameId y filename requeridos");
+    };
+    match upload_entry(state, user_id, game_id, filename) {
+        Some(entry) => Json(entry).into_response(),
+        None => status(400, &format!("Invalid key: {}", filename)),
+    }
+}
+
+fn upload_urls(state: &MockState, user_id: &str, body: &Value) -> Response {
+    let items = body.get("items").and_then(|v| v.as_array());
+    let Some(items) = items.filter(|i| !i.is_empty() && i.len() <= 500) else {
+        return status(400, "items debe tener entre 1 y 500 elementos");
+    };
+    let mut urls = Vec::with_capacity(items.len());
+    for item in items {
+        let (Some(game_id), Some(filename)) =
+            (str_field(item, "gameId"), str_field(item, "filename"))
+        else {
+            return status(400, "gameId y filename requeridos");
+        };
+        match upload_entry(state, user_id, game_id, filename) {
+            Some(entry) => urls.push(entry),
+            None => return status(400, &format!("Invalid key: {}", filename)),
+        }
+    }
+    Json(json!({ "urls": urls })).into_response()
+}
+
+fn download_url(state: &MockState, user_id: &str, body: &Value) -> Response {
+    let Some(key) = str_field(body, "key") else {
+        return status(400, "key requerido");
+    };
+    if let Err(res) = owned_object(state, user_id, key) {
+        return *res;
+    }
+    let url = state.presigned("GET", &MockState::object_url_path(key));
+    Json(json!({ "downloadUrl": url })).into_response()
+}
+
+fn download_urls(state: &MockState, user_id: &str, body: &Value) -> Response {
+    let items = body.get("items").and_then(|v| v.as_array());
+    let Some(items) = items.filter(|i| !i.is_empty() && i.len() <= 500) else {
+        return status(400, "items debe tener entre 1 y 500 elementos");
+    };
+    let mut urls = Vec::with_capacity(items.len());
+    for item in items {
+        let (Some(game_id), Some(key)) = (str_field(item, "gameId"), str_field(item, "key")) else {
+            return status(400, "gameId y key requeridos");
+        };
+        if let Err(res) = owned_object(state, user_id, key) {
+            return *res;
+        }
+        urls.push(json!({
+            "gameId": game_id,
+            "key": key,
+            "downloadUrl": state.presigned("GET", &MockState::object_url_path(key)),
+        }));
+    }
+    Json(json!({ "urls": urls })).into_response()
+}
+
+fn multipart_init(state: &MockState, user_id: &str, body: &Value) -> Response {
+    let (Some(game_id), Some(filename)) = (str_field(body, "gameId"), str_field(body, "filename"))
+    else {
+        return status(400, "gameId y filename requeridos");
+    };
+    let key = crate::storage::object_key(user_id, game_id, filename);
+    if state.object_path(&key).is_none() {
+        return status(400, &format!("Invalid key: {}", key));
+    }
+    let upload_id = format!(
+        "upload-{}",
+        state.upload_seq.fetch_add(1, Ordering::Relaxed) + 1
+    );
+    state
+        .uploads
+        .lock()
+        .unwrap()
+        .insert(upload_id.clone(), key.clone());
+    Json(json!({ "uploadId": upload_id, "key": key })).into_response()
+}
+
+/// Comprueba que la subida existe y corresponde a `key`.
+fn open_upload(state: &MockState, body: &Value) -> Result<String, Box<Response>> {
+    let (Some(key), Some(upload_id)) = (str_field(body, "key"), str_field(body, "uploadId")) else {
+        return Err(Box::new(status(400, "key y uploadId requeridos")));
+    };
+    match state.uploads.lock().unwrap().get(upload_id) {
+        Some(k) if k == key => Ok(upload_id.to_string()),
+        _ => Err(Box::new(status(404, "NoSuchUpload"))),
+    }
+}
+
+fn multipart_part_urls(state: &MockState, body: &Value) -> Response {
+    let upload_id = match open_upload(state, body) {
+        Ok(id) => id,
+        Err(res) => return *res,
+    };
+    let numbers: Vec<u64> = body
+        .get("partNumbers")
+        .and_then(|v| v.as_array())
+        .map(|a| a.iter().filter_map(|n| n.as_u64()).collect())
+        .unwrap_or_default();
+    if numbers.is_empty() || numbers.iter().any(|n| !(1..=10_000).contains(n)) {
+        return status(400, "partNumbers inválidos");
+    }
+    let part_urls: Vec<Value> = numbers
+        .into_iter()
+        .map(|n| {
+            let path = format!("/parts/{}/{}", upload_id, n);
+            json!({ "partNumber": n, "url": state.presigned("PUT", &path) })
+        })
+        .collect();
+    Json(json!({ "partUrls": part_urls })).into_response()
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CompletedPart {
+    part_number: u32,
+    etag: String,
+}
+
+fn multipart_complete(state: &MockState, body: &Value) -> Response {
+    let upload_id = match open_upload(state, body) {
+        Ok(id) => id,
+        Err(res) => return *res,
+    };
+    let mut parts: Vec<CompletedPart> = body
+        .get("parts")
+        .cloned()
+        .and_then(|p| serde_json::from_value(p).ok())
+        .unwrap_or_default();
+    if parts.is_empty() {
+        return status(400, "parts requerido");
+    }
+    parts.sort_by_key(|p| p.part_number);
+
+    let mut assembled = Vec::new();
+    for part in &parts {
+        let Some(data) = state
+            .part_path(&upload_id, part.part_number)
+            .and_then(|p| std::fs::read(p).ok())
+        else {
+            return status(400, &format!("InvalidPart: {}", part.part_number));
+        };
+        if part.etag.trim_matches('"') != sha256_hex(&data) {
+            return status(400, &format!("InvalidPart (ETag): {}", part.part_number));
+        }
+        assembled.extend_from_slice(&data);
+    }
+
+    let Some(key) = state.uploads.lock().unwrap().remove(&upload_id) else {
+        return status(404, "NoSuchUpload");
+    };
+    let Some(dest) = state.object_path(&key) else {
+        return status(400, &format!("Invalid key: {}", key));
+    };
+    if let Some(parent) = dest.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    if let Err(e) = std::fs::write(&dest, assembled) {
+        return status(500, &e.to_string());
+    }
+    let _ = std::fs::remove_dir_all(state.parts_dir.join(&upload_id));
+    StatusCode::NO_CONTENT.into_response()
+}
+
+fn multipart_abort(state: &MockState, body: &Value) -> Response {
+    let upload_id = match open_upload(state, body) {
+        Ok(id) => id,
+        Err(res) => return *res,
+    };
+    state.uploads.lock().unwrap().remove(&upload_id);
+    let _ = std::fs::remove_dir_all(state.parts_dir.join(&upload_id));
+    StatusCode::NO_CONTENT.into_response()
+}
+
+/// `GET`/`PUT` sobre URLs prefirmadas de objetos y `PUT` de partes multipart.
+fn transfer(state: &MockState, method: &Method, uri: &Uri, body: &Bytes) -> Response {
+    let path = uri.path();
+    let signature = uri
+        .query()
+        .unwrap_or_default()
+        .split('&')
+        .find_map(|kv| kv.strip_prefix("signature="))
+        .unwrap_or_default();
+    if signature != state.sign(method.as_str(), path) {
+        return status(403, "SignatureDoesNotMatch");
+    }
+
+    if let Some(rest) = path.strip_prefix("/parts/") {
+        let Some((upload_id, n)) = rest.split_once('/') else {
+            return status(404, "NoSuchKey");
+        };
+        if method != Method::PUT || !state.uploads.lock().unwrap().contains_key(upload_id) {
+            return status(404, "NoSuchUpload");
+        }
+        let Some(dest) = n
+            .parse::<u32>()
+            .ok()
+            .and_then(|n| state.part_path(upload_id, n))
+        else {
+            return status(400, "partNumber inválido");
+        };
+        return store(&dest, body);
+    }
+
+    let raw_key = path.trim_start_matches("/objects/");
+    let key: Vec<String> = raw_key
+        .split('/')
+        .map(|s| {
+            urlencoding::decode(s)
+                .map(|d| d.into_owned())
+                .unwrap_or_else(|_| s.to_string())
+        })
+        .collect();
+    let Some(dest) = state.object_path(&key.join("/")) else {
+        return status(400, "Invalid key");
+    };
+    match *method {
+        Method::PUT => store(&dest, body),
+        Method::GET => match std::fs::read(&dest) {
+            Ok(data) => {
+                ([(header::CONTENT_TYPE, "application/octet-stream")], data).into_response()
+            }
+            Err(_) => status(404, "NoSuchKey"),
+        },
+        _ => status(405, "MethodNotAllowed"),
+    }
+}
+
+/// Escribe el cuerpo y responde con su ETag (sha256 entre comillas, como S3 con MD5).
+fn store(dest: &Path, body: &Bytes) -> Response {
+    if let Some(parent) = dest.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    match std::fs::write(dest, body) {
+        Ok(()) => ([(header::ETAG, format!("\"{}\"", sha256_hex(body)))], "").into_response(),
+        Err(e) => status(500, &e.to_string()),
+    }
+}
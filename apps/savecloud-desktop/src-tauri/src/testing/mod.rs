@@ -0,0 +1,37 @@
+//! Utilidades compartidas por los tests del crate.
+
+pub mod mock_api;
+
+use mock_api::{MockApi, MOCK_API_KEY};
+use std::path::Path;
+use std::sync::{LazyLock, OnceLock};
+
+/// Carpeta de configuración de los tests, común a todo el binario de tests:
+/// [`crate::config::config_dir`] la devuelve en lugar de la del usuario.
+pub fn config_dir() -> &'static Path {
+    static DIR: OnceLock<tempfile::TempDir> = OnceLock::new();
+    DIR.get_or_init(|| tempfile::tempdir().expect("carpeta de configuración de los tests"))
+        .path()
+}
+
+/// Serializa los tests que escriben la configuración compartida (ajustes y biblioteca).
+pub static CONFIG_LOCK: LazyLock<tokio::sync::Mutex<()>> =
+    LazyLock::new(|| tokio::sync::Mutex::new(()));
+
+/// Configura la app contra `api` con la identidad `user_id` y `games` como biblioteca
+/// (los mismos campos que `library.json`). Hay que tener tomado [`CONFIG_LOCK`].
+pub fn use_mock_api(api: &MockApi, user_id: &str, games: serde_json::Value) {
+    // `apiKey` no se escribe nunca en `settings.json`, pero se lee si está: así el test
+    // no depende del Keyring del sistema.
+    let settings = serde_json::json!({
+        "apiBaseUrl": api.base_url(),
+        "apiKey": MOCK_API_KEY,
+        "userId": user_id,
+    });
+    let path = crate::config::settings_path().expect("ruta de ajustes de los tests");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, settings.to_string()).unwrap();
+
+    let library = serde_json::from_value(serde_json::json!({ "games": games })).unwrap();
+    crate::config::save_library(&library).unwrap();
+}
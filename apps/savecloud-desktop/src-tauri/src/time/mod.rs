@@ -1,3 +1,5 @@
+pub mod playtime_cmds;
+pub mod sessions;
 mod tracker;
 
 pub use tracker::add_playtime;
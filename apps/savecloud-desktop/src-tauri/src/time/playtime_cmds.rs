@@ -0,0 +1,70 @@
+//! Comandos de consulta del historial de partidas ([`sessions`]).
+
+use super::sessions::{
+    self, LastPlayedDto, PlayHistory, PlaySessionDto, PlaytimeBucketDto, PlaytimePeriod,
+};
+use crate::commands::sync::queue::{now_secs, with_db};
+use crate::config::gamification;
+use crate::sqlite::AppDb;
+use tauri::State;
+
+/// Sesiones que devuelven las listas si el frontend no pide otra cantidad.
+const DEFAULT_LIMIT: u32 = 20;
+
+/// Resumen del historial para la gamificación; vacío si la base de datos falla.
+pub(crate) async fn play_history(db: &AppDb) -> PlayHistory {
+    let (week_start, streak_start) = gamification::history_window();
+    with_db(db, move |c| {
+        sessions::play_history(c, week_start, streak_start)
+    })
+    .await
+    .unwrap_or_default()
+}
+
+/// Tiempo jugado por día, semana ISO o mes en hora local, de un juego o de todos.
+/// Sin `since` (segundos Unix) abarca 30 días, 12 semanas o 12 meses.
+#[tauri::command]
+pub async fn get_playtime_aggregates(
+    db: State<'_, AppDb>,
+    period: PlaytimePeriod,
+    game_id: Option<String>,
+    since: Option<i64>,
+) -> Result<Vec<PlaytimeBucketDto>, String> {
+    let since = since.unwrap_or_else(|| now_secs() - period.default_span_secs());
+    let offset = chrono::Local::now().offset().local_minus_utc() as i64;
+    with_db(&db, move |c| {
+        sessions::aggregate(c, period, game_id.as_deref(), since, offset)
+    })
+    .await
+}
+
+/// Partidas más largas, de un juego o de todos.
+#[tauri::command]
+pub async fn get_longest_play_sessions(
+    db: State<'_, AppDb>,
+    game_id: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<PlaySessionDto>, String> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT) as i64;
+    with_db(&db, move |c| {
+        sessions::longest(c, game_id.as_deref(), limit)
+    })
+    .await
+}
+
+/// Partidas más recientes, de un juego o de todos.
+#[tauri::command]
+pub async fn list_play_sessions(
+    db: State<'_, AppDb>,
+    game_id: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<PlaySessionDto>, String> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT) as i64;
+    with_db(&db, move |c| sessions::recent(c, game_id.as_deref(), limit)).await
+}
+
+/// Cuándo se jugó por última vez a cada juego con historial.
+#[tauri::command]
+pub async fn get_last_played(db: State<'_, AppDb>) -> Result<Vec<LastPlayedDto>, String> {
+    with_db(&db, sessions::last_played).await
+}
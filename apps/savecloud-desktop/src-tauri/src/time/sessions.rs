@@ -0,0 +1,377 @@
+//! Acceso SQL a la tabla `play_sessions` (historial de partidas).
+//!
+//! Todas las funciones reciben los instantes en segundos Unix para que las
+//! agregaciones sean deterministas en los tests.
+
+use rusqlite::{Connection, Row};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Cómo empezó una partida.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LaunchedVia {
+    /// Abierto fuera de la app; lo detectaron sus reglas de proceso.
+    Detected,
+    /// Lanzado desde la app sin sincronizar.
+    App,
+    /// Lanzamiento gestionado: descarga antes de jugar y sube al salir.
+    Managed,
+}
+
+impl LaunchedVia {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Detected => "detected",
+            Self::App => "app",
+            Self::Managed => "managed",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "detected" => Some(Self::Detected),
+            "app" => Some(Self::App),
+            "managed" => Some(Self::Managed),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaySessionDto {
+    pub id: i64,
+    pub game_id: String,
+    pub device_id: String,
+    pub started_at: i64,
+    pub ended_at: i64,
+    /// Tiempo jugado; no incluye `idle_seconds`.
+    pub duration_seconds: i64,
+    /// Tiempo abierto sin jugar (detección de inactividad).
+    pub idle_seconds: i64,
+    pub launched_via: LaunchedVia,
+}
+
+const SESSION_COLUMNS: &str =
+    "id, game_id, device_id, started_at, ended_at, duration_seconds, idle_seconds, launched_via";
+
+fn session_from_row(row: &Row<'_>) -> Result<PlaySessionDto, rusqlite::Error> {
+    let via: String = row.get(7)?;
+    Ok(PlaySessionDto {
+        id: row.get(0)?,
+        game_id: row.get(1)?,
+        device_id: row.get(2)?,
+        started_at: row.get(3)?,
+        ended_at: row.get(4)?,
+        duration_seconds: row.get(5)?,
+        idle_seconds: row.get(6)?,
+        launched_via: LaunchedVia::parse(&via).ok_or_else(|| {
+            rusqlite::Error::FromSqlConversionFailure(
+                7,
+                rusqlite::types::Type::Text,
+                format!("origen de partida desconocido: {}", via).into(),
+            )
+        })?,
+    })
+}
+
+/// Periodo de las agregaciones de tiempo de juego.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlaytimePeriod {
+    Day,
+    Week,
+    Month,
+}
+
+impl PlaytimePeriod {
+    /// Formato `strftime` de la clave de cada periodo (la semana es la ISO).
+    fn key_format(self) -> &'static str {
+        match self {
+            Self::Day => "%Y-%m-%d",
+            Self::Week => "%G-W%V",
+            Self::Month => "%Y-%m",
+        }
+    }
+
+    /// Alcance por defecto de una consulta: 30 días, 12 semanas o 12 meses.
+    pub fn default_span_secs(self) -> i64 {
+        const DAY: i64 = 24 * 60 * 60;
+        match self {
+            Self::Day => 30 * DAY,
+            Self::Week => 12 * 7 * DAY,
+            Self::Month => 365 * DAY,
+        }
+    }
+}
+
+/// Tiempo jugado en un día, semana o mes.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaytimeBucketDto {
+    /// `2026-10-19`, `2026-W42` o `2026-10`.
+    pub period: String,
+    pub seconds: i64,
+    pub idle_seconds: i64,
+    pub sessions: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LastPlayedDto {
+    pub game_id: String,
+    pub last_played_at: i64,
+}
+
+/// Abre una sesión que empieza (y de momento acaba) en `now`. Devuelve su id.
+pub fn start(
+    conn: &Connection,
+    game_id: &str,
+    device_id: &str,
+    via: LaunchedVia,
+    now: i64,
+) -> Result<i64, rusqlite::Error> {
+    conn.execute(
+        "INSERT INTO play_sessions (game_id, device_id, started_at, ended_at, launched_via) \
+         VALUES (?1, ?2, ?3, ?3, ?4)",
+        (game_id, device_id, now, via.as_str()),
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Suma `seconds` de juego e `idle_seconds` de inactividad a la sesión y mueve su fin a `now`.
+pub fn extend(
+    conn: &Connection,
+    id: i64,
+    seconds: i64,
+    idle_seconds: i64,
+    now: i64,
+) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "UPDATE play_sessions SET duration_seconds = duration_seconds + max(?2, 0), \
+         idle_seconds = idle_seconds + max(?3, 0), ended_at = max(ended_at, ?4) WHERE id = ?1",
+        (id, seconds, idle_seconds, now),
+    )?;
+    Ok(())
+}
+
+/// Tiempo jugado por periodo desde `since`, del más antiguo al más reciente.
+///
+/// Cada sesión cuenta en el periodo en que empezó; `utc_offset_secs` desplaza los
+/// límites a la hora local del usuario.
+pub fn aggregate(
+    conn: &Connection,
+    period: PlaytimePeriod,
+    game_id: Option<&str>,
+    since: i64,
+    utc_offset_secs: i64,
+) -> Result<Vec<PlaytimeBucketDto>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT strftime(?1, started_at + ?2, 'unixepoch') AS bucket, \
+         sum(duration_seconds), sum(idle_seconds), count(*) FROM play_sessions \
+         WHERE started_at >= ?3 AND (?4 IS NULL OR game_id = ?4) \
+         GROUP BY bucket ORDER BY bucket",
+    )?;
+    let rows = stmt.query_map(
+        (period.key_format(), utc_offset_secs, since, game_id),
+        |row| {
+            Ok(PlaytimeBucketDto {
+                period: row.get(0)?,
+                seconds: row.get(1)?,
+                idle_seconds: row.get(2)?,
+                sessions: row.get(3)?,
+            })
+        },
+    )?;
+    rows.collect()
+}
+
+/// Las `limit` sesiones más largas, de todos los juegos o de uno.
+pub fn longest(
+    conn: &Connection,
+    game_id: Option<&str>,
+    limit: i64,
+) -> Result<Vec<PlaySessionDto>, rusqlite::Error> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM play_sessions WHERE ?1 IS NULL OR game_id = ?1 \
+         ORDER BY duration_seconds DESC, started_at DESC LIMIT ?2",
+        SESSION_COLUMNS
+    ))?;
+    let rows = stmt.query_map((game_id, limit), session_from_row)?;
+    rows.collect()
+}
+
+/// Las `limit` sesiones más recientes, de todos los juegos o de uno.
+pub fn recent(
+    conn: &Connection,
+    game_id: Option<&str>,
+    limit: i64,
+) -> Result<Vec<PlaySessionDto>, rusqlite::Error> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM play_sessions WHERE ?1 IS NULL OR game_id = ?1 \
+         ORDER BY started_at DESC, id DESC LIMIT ?2",
+        SESSION_COLUMNS
+    ))?;
+    let rows = stmt.query_map((game_id, limit), session_from_row)?;
+    rows.collect()
+}
+
+/// Fin de la última partida de cada juego, del más reciente al más antiguo.
+pub fn last_played(conn: &Connection) -> Result<Vec<LastPlayedDto>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT game_id, max(ended_at) AS last FROM play_sessions \
+         GROUP BY game_id ORDER BY last DESC",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(LastPlayedDto {
+            game_id: row.get(0)?,
+            last_played_at: row.get(1)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// Resumen del historial para la gamificación.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PlayHistory {
+    /// Segundos de las sesiones empezadas desde el inicio de la semana.
+    pub week_seconds: u64,
+    /// Días UTC (`AAAA-MM-DD`) con alguna partida, desde `days_since`.
+    pub utc_days: HashSet<String>,
+}
+
+/// Tiempo de la semana que empieza en `week_start` y días con partida desde `days_since`.
+/// Una partida que cruza la medianoche cuenta en el día en que empieza y en el que acaba.
+pub fn play_history(
+    conn: &Connection,
+    week_start: i64,
+    days_since: i64,
+) -> Result<PlayHistory, rusqlite::Error> {
+    let week_seconds: i64 = conn.query_row(
+        "SELECT coalesce(sum(duration_seconds), 0) FROM play_sessions WHERE started_at >= ?1",
+        [week_start],
+        |row| row.get(0),
+    )?;
+    let mut stmt = conn.prepare(
+        "SELECT date(started_at, 'unixepoch') FROM play_sessions \
+         WHERE ended_at >= ?1 AND duration_seconds > 0 \
+         UNION SELECT date(ended_at, 'unixepoch') FROM play_sessions \
+         WHERE ended_at >= ?1 AND duration_seconds > 0",
+    )?;
+    let utc_days = stmt
+        .query_map([days_since], |row| row.get(0))?
+        .collect::<Result<HashSet<String>, _>>()?;
+    Ok(PlayHistory {
+        week_seconds: week_seconds.max(0) as u64,
+        utc_days,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sqlite::run_migrations;
+
+    /// 2026-10-19 00:00:00 UTC (lunes, semana ISO 43).
+    const MONDAY: i64 = 1_792_368_000;
+    const HOUR: i64 = 60 * 60;
+
+    fn db() -> Connection {
+        let conn = Connection::open_in_memory().expect("in memory");
+        run_migrations(&conn).expect("migraciones");
+        conn
+    }
+
+    fn play(conn: &Connection, game_id: &str, start_at: i64, seconds: i64, via: LaunchedVia) {
+        let id = start(conn, game_id, "dev", via, start_at).unwrap();
+        extend(conn, id, seconds, 0, start_at + seconds).unwrap();
+    }
+
+    #[test]
+    fn aggregates_by_period_and_game() {
+        let conn = db();
+        play(
+            &conn,
+            "Hades",
+            MONDAY - 2 * HOUR,
+            HOUR,
+            LaunchedVia::Detected,
+        );
+        play(
+            &conn,
+            "hades",
+            MONDAY + HOUR,
+            2 * HOUR,
+            LaunchedVia::Managed,
+        );
+        play(
+            &conn,
+            "Celeste",
+            MONDAY + 5 * HOUR,
+            30 * 60,
+            LaunchedVia::App,
+        );
+        // Diez minutos más abierto sin jugar: no suman tiempo jugado.
+        extend(&conn, 3, 0, 10 * 60, MONDAY + 6 * HOUR).unwrap();
+
+        let days = aggregate(&conn, PlaytimePeriod::Day, None, 0, 0).unwrap();
+        assert_eq!(
+            days,
+            vec![
+                PlaytimeBucketDto {
+                    period: "2026-10-18".into(),
+                    seconds: HOUR,
+                    idle_seconds: 0,
+                    sessions: 1
+                },
+                PlaytimeBucketDto {
+                    period: "2026-10-19".into(),
+                    seconds: 2 * HOUR + 30 * 60,
+                    idle_seconds: 10 * 60,
+                    sessions: 2
+                },
+            ]
+        );
+        // Tres horas más al este, la partida del domingo ya cae en lunes.
+        let weeks = aggregate(&conn, PlaytimePeriod::Week, Some("HADES"), 0, 3 * HOUR).unwrap();
+        assert_eq!(weeks.len(), 1);
+        assert_eq!(weeks[0].period, "2026-W43");
+        assert_eq!(weeks[0].seconds, 3 * HOUR);
+
+        let longest = longest(&conn, None, 1).unwrap();
+        assert_eq!(longest[0].duration_seconds, 2 * HOUR);
+        assert_eq!(longest[0].launched_via, LaunchedVia::Managed);
+
+        let last = last_played(&conn).unwrap();
+        assert_eq!(last.len(), 2);
+        assert_eq!(last[0].last_played_at, MONDAY + 6 * HOUR);
+        assert_eq!(last[1].last_played_at, MONDAY + 3 * HOUR);
+    }
+
+    #[test]
+    fn play_history_counts_week_and_days_across_midnight() {
+        let conn = db();
+        play(
+            &conn,
+            "hades",
+            MONDAY - HOUR,
+            2 * HOUR,
+            LaunchedVia::Detected,
+        );
+        play(
+            &conn,
+            "hades",
+            MONDAY - 3 * 24 * HOUR,
+            0,
+            LaunchedVia::Detected,
+        );
+        play(&conn, "celeste", MONDAY + 2 * HOUR, HOUR, LaunchedVia::App);
+
+        let history = play_history(&conn, MONDAY, 0).unwrap();
+        assert_eq!(history.week_seconds, HOUR as u64);
+        let mut days: Vec<_> = history.utc_days.into_iter().collect();
+        days.sort();
+        assert_eq!(days, vec!["2026-10-18", "2026-10-19"]);
+    }
+}
@@ -6,6 +6,9 @@
 //! - Obtener el tiempo de un juego en segundos.
 //! - Obtener la suma de tiempo de todos los juegos.
 //! - Convertir segundos a formato legible.
+//!
+//! El detalle por partida (inicio, fin, dispositivo) vive en el historial de SQLite
+//! ([`super::sessions`]); aquí solo se mantiene el total de cada juego.
 
 #![allow(dead_code)]
 
@@ -2,11 +2,12 @@ use std::path::PathBuf;
 
 use tauri::{AppHandle, Emitter, State};
 
-use crate::network::{API_CLIENT, DATA_CLIENT};
+use crate::storage::{Storage, StorageBackend};
 use crate::torrent::engine;
 use crate::torrent::errors::TorrentError;
 use crate::torrent::models::CloudTorrentInfo;
 use crate::torrent::state::TorrentState;
+use crate::transfers::{self, TransferKind, TransferPriority};
 
 /// Descarga un torrent a partir de un magnet link.
 ///
@@ -26,15 +27,22 @@ pub async fn start_torrent_download(
 
     engine::emit_starting_event(&app, "", &magnet);
 
+    // Ocupa un hueco del gestor de transferencias mientras dure la descarga.
+    let transfer = transfers::begin(TransferKind::Torrent, &magnet, TransferPriority::Normal).await;
+    if transfer.token().is_cancelled() {
+        return Err(TorrentError::CancelledBeforeStart);
+    }
+
     let (info_hash, name, id) =
         engine::add_magnet_to_session(&session, &magnet, &save_path).await?;
+    transfer.set_label(&name);
 
     {
         let mut eng = state.engine.lock().await;
         eng.register_active(info_hash.clone());
     }
 
-    engine::spawn_progress_monitor(session, id, info_hash.clone(), name, app);
+    engine::spawn_progress_monitor(session, id, info_hash.clone(), name, app, transfer);
 
     Ok(info_hash)
 }
@@ -54,15 +62,23 @@ pub async fn start_torrent_file_download(
 
     engine::emit_starting_event(&app, "", &file_path);
 
+    // Ocupa un hueco del gestor de transferencias mientras dure la descarga.
+    let transfer =
+        transfers::begin(TransferKind::Torrent, &file_path, TransferPriority::Normal).await;
+    if transfer.token().is_cancelled() {
+        return Err(TorrentError::CancelledBeforeStart);
+    }
+
     let (info_hash, name, id) =
         engine::add_file_to_session(&session, &file_path, &save_path).await?;
+    transfer.set_label(&name);
 
     {
         let mut eng = state.engine.lock().await;
         eng.register_active(info_hash.clone());
     }
 
-    engine::spawn_progress_monitor(session, id, info_hash.clone(), name, app);
+    engine::spawn_progress_monitor(session, id, info_hash.clone(), name, app, transfer);
 
     Ok(info_hash)
 }
@@ -122,7 +138,7 @@ pub async fn upload_torrent_to_cloud(
     game_id: String,
     torrent_path: String,
 ) -> Result<(), TorrentError> {
-    let ctx = get_api_context()?;
+    let storage = active_storage()?;
 
     let path = PathBuf::from(&torrent_path);
     let file_name = path
@@ -140,37 +156,19 @@ pub async fn upload_torrent_to_cloud(
 
     let bytes = tokio::fs::read(&torrent_path).await?;
 
-    let upload_urls = crate::commands::sync::api::get_upload_urls(
-        &ctx.base_url,
-        &ctx.user_id,
-        &ctx.api_key,
-        &game_id,
-        &[remote_filename],
-    )
-    .await
-    .map_err(|e| TorrentError::CloudUrls(e))?;
-
-    let (upload_url, _) = upload_urls
-        .into_iter()
-        .next()
-        .ok_or_else(|| TorrentError::CloudUrls("API no devolvió URL de subida".into()))?;
-
-    let content_length = bytes.len();
-    let res = DATA_CLIENT
-        .put(&upload_url)
-        .body(bytes)
-        .header("Content-Type", "application/x-bittorrent")
-        .header("Content-Length", content_length.to_string())
-        .send()
+    let target = storage
+        .put_targets(&game_id, &[remote_filename])
         .await
-        .map_err(|e| TorrentError::CloudDownload(e.to_string()))?;
+        .map_err(|e| TorrentError::CloudUrls(e.to_string()))?
+        .pop()
+        .ok_or_else(|| {
+            TorrentError::CloudUrls("El backend no devolvió destino de subida".into())
+        })?;
 
-    if !res.status().is_success() {
-        return Err(TorrentError::CloudDownload(format!(
-            "S3 PUT falló: {}",
-            res.status()
-        )));
-    }
+    target
+        .put(bytes.into(), "application/x-bittorrent")
+        .await
+        .map_err(|e| TorrentError::CloudDownload(e.to_string()))?;
 
     Ok(())
 }
@@ -208,37 +206,19 @@ pub async fn download_torrent_from_cloud(
     state: State<'_, TorrentState>,
     app: AppHandle,
 ) -> Result<String, TorrentError> {
-    let ctx = get_api_context()?;
-
-    let download_urls = crate::commands::sync::api::get_download_urls(
-        &ctx.base_url,
-        &ctx.user_id,
-        &ctx.api_key,
-        &[(game_id.clone(), torrent_key.clone())],
-    )
-    .await
-    .map_err(|e| TorrentError::CloudUrls(e))?;
-
-    let (download_url, _) = download_urls
-        .into_iter()
-        .next()
-        .ok_or_else(|| TorrentError::CloudUrls("API no devolvió URL de descarga".into()))?;
+    let storage = active_storage()?;
 
-    let res = API_CLIENT
-        .get(&download_url)
-        .send()
+    let target = storage
+        .get_targets(storage.user_id(), &[(game_id.clone(), torrent_key.clone())])
         .await
-        .map_err(|e| TorrentError::CloudDownload(e.to_string()))?;
-
-    if !res.status().is_success() {
-        return Err(TorrentError::CloudDownload(format!(
-            "Descarga falló: {}",
-            res.status()
-        )));
-    }
+        .map_err(|e| TorrentError::CloudUrls(e.to_string()))?
+        .pop()
+        .ok_or_else(|| {
+            TorrentError::CloudUrls("El backend no devolvió origen de descarga".into())
+        })?;
 
-    let torrent_bytes = res
-        .bytes()
+    let torrent_bytes = target
+        .read_all()
         .await
         .map_err(|e| TorrentError::CloudDownload(e.to_string()))?;
 
@@ -265,74 +245,39 @@ pub async fn download_torrent_from_cloud(
 
     engine::emit_starting_event(&app, "", &game_id);
 
+    // Ocupa un hueco del gestor de transferencias mientras dure la descarga.
+    let transfer =
+        transfers::begin(TransferKind::Torrent, &game_id, TransferPriority::Normal).await;
+    if transfer.token().is_cancelled() {
+        return Err(TorrentError::CancelledBeforeStart);
+    }
+
     let (info_hash, name, id) =
         engine::add_file_to_session(&session, &temp_path, &save_path).await?;
+    transfer.set_label(&name);
 
     {
         let mut eng = state.engine.lock().await;
         eng.register_active(info_hash.clone());
     }
 
-    engine::spawn_progress_monitor(session, id, info_hash.clone(), name, app);
+    engine::spawn_progress_monitor(session, id, info_hash.clone(), name, app, transfer);
 
     Ok(info_hash)
 }
 
-/// Elimina un archivo .torrent almacenado en la nube (S3).
+/// Elimina un archivo .torrent almacenado en la nube.
 #[tauri::command]
 pub async fn delete_cloud_torrent(
     game_id: String,
     torrent_key: String,
 ) -> Result<(), TorrentError> {
-    let ctx = get_api_context()?;
-    let body = serde_json::json!({ "gameId": game_id, "key": torrent_key });
-
-    let res = crate::commands::sync::api::api_request(
-        &ctx.base_url,
-        &ctx.user_id,
-        &ctx.api_key,
-        "DELETE",
-        "/backup",
-        Some(body.to_string().as_bytes()),
-    )
-    .await
-    .map_err(|e| TorrentError::CloudUrls(e))?;
-
-    if !res.status().is_success() && res.status().as_u16() != 204 {
-        return Err(TorrentError::CloudDownload(format!(
-            "API DELETE torrent: {} {}",
-            res.status(),
-            res.text().await.unwrap_or_default()
-        )));
-    }
-    Ok(())
-}
-
-struct ApiContext {
-    base_url: String,
-    user_id: String,
-    api_key: String,
+    active_storage()?
+        .delete(&game_id, &torrent_key)
+        .await
+        .map_err(|e| TorrentError::CloudDownload(e.to_string()))
 }
 
-fn get_api_context() -> Result<ApiContext, TorrentError> {
-    let cfg = crate::config::load_config();
-    let base_url = cfg
-        .api_base_url
-        .as_deref()
-        .filter(|s| !s.trim().is_empty())
-        .ok_or_else(|| TorrentError::Config("apiBaseUrl no configurado".into()))?
-        .to_string();
-    let user_id = cfg
-        .user_id
-        .as_deref()
-        .filter(|s| !s.trim().is_empty())
-        .ok_or_else(|| TorrentError::Config("userId no configurado".into()))?
-        .to_string();
-    let api_key = cfg.api_key.unwrap_or_default();
-
-    Ok(ApiContext {
-        base_url,
-        user_id,
-        api_key,
-    })
+fn active_storage() -> Result<Storage, TorrentError> {
+    Storage::active().map_err(|e| TorrentError::Config(e.to_string()))
 }
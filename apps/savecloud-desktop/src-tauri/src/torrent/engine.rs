@@ -20,10 +20,12 @@ use std::sync::Arc;
 
 use librqbit::api::TorrentIdOrHash;
 use librqbit::{AddTorrent, AddTorrentOptions, Session, SessionOptions, TorrentStatsState};
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Manager};
 
 use super::errors::TorrentError;
 use super::models::{TorrentDownloadState, TorrentProgressPayload};
+use super::state::TorrentState;
+use crate::transfers::manager::TransferGuard;
 
 /// Evento emitido periódicamente mientras un torrent está activo.
 ///
@@ -357,6 +359,10 @@ pub async fn add_file_to_session(
 /// llamada a `session.get()` en cada tick devuelve `None` y la tarea termina
 /// limpiamente.
 ///
+/// La tarea es dueña de `transfer`: el hueco en el gestor de transferencias se
+/// libera al terminar, y en cada tick atiende la pausa o cancelación pedidas
+/// desde la vista de transferencias.
+///
 /// # Seguridad ante cancelación
 ///
 /// Se usa deliberadamente una sola llamada a `session.get()` por tick. El patrón
@@ -372,6 +378,7 @@ pub fn spawn_progress_monitor(
     info_hash: String,
     name: String,
     app: AppHandle,
+    transfer: TransferGuard,
 ) {
     tokio::spawn(async move {
         let id = TorrentIdOrHash::Id(torrent_id);
@@ -380,6 +387,20 @@ pub fn spawn_progress_monitor(
         loop {
             interval.tick().await;
 
+            // Pausa y cancelación pedidas desde la vista de transferencias.
+            if transfer.token().is_cancelled() {
+                let _ = cancel_via_session(&session, &info_hash).await;
+                if let Some(state) = app.try_state::<TorrentState>() {
+                    state.engine.lock().await.unregister_active(&info_hash);
+                }
+                let _ = app.emit(TORRENT_CANCELLED_EVENT, &info_hash);
+                break;
+            }
+            if transfer.token().is_paused() {
+                transfer.token().resume();
+                let _ = pause_via_session(&session, &info_hash).await;
+            }
+
             // Un único lookup por tick garantiza que la presencia del torrent y
             // la lectura de sus estadísticas son consistentes entre sí.
             let managed = match session.get(id) {
@@ -405,6 +426,7 @@ pub fn spawn_progress_monitor(
 
             let total_bytes = stats.total_bytes;
             let downloaded_bytes = stats.progress_bytes;
+            transfer.set_progress(downloaded_bytes, total_bytes);
 
             // Se limita a 100.0 para absorber pequeñas imprecisiones de punto
             // flotante que podrían producir valores como 100.0000001.
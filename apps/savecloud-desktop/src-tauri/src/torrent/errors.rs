@@ -28,6 +28,9 @@ pub enum TorrentError {
     #[error("Error al reanudar el torrent: {0}")]
     Resume(String),
 
+    #[error("Descarga cancelada antes de empezar")]
+    CancelledBeforeStart,
+
     #[error("Error de red al descargar .torrent desde la nube: {0}")]
     CloudDownload(String),
 
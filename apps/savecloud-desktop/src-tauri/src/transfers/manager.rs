@@ -0,0 +1,536 @@
+//! Planificador de transferencias: registro de las activas, huecos por tipo y prioridad.
+
+use crate::commands::sync::cancellation::CancellationToken;
+use crate::commands::sync::models::SyncProgressPayload;
+use crate::commands::sync::observer::SyncObserver;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::Notify;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferKind {
+    Upload,
+    Download,
+    FullBackup,
+    Torrent,
+}
+
+/// Orden de paso cuando no hay hueco para todos (de menor a mayor).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferPriority {
+    /// Reintentos de la cola offline y tareas programadas.
+    Background,
+    /// Acciones lanzadas por el usuario desde la UI o la CLI.
+    Normal,
+    /// Subida al cerrar un juego: es la que más importa no perder.
+    GameExit,
+}
+
+/// Límites de transferencias simultáneas.
+#[derive(Debug, Clone, Copy)]
+pub struct TransferLimits {
+    pub global: usize,
+    pub upload: usize,
+    pub download: usize,
+    pub full_backup: usize,
+    pub torrent: usize,
+}
+
+impl TransferLimits {
+    fn for_kind(&self, kind: TransferKind) -> usize {
+        match kind {
+            TransferKind::Upload => self.upload,
+            TransferKind::Download => self.download,
+            TransferKind::FullBackup => self.full_backup,
+            TransferKind::Torrent => self.torrent,
+        }
+    }
+}
+
+impl Default for TransferLimits {
+    /// Cada juego ya abre varias conexiones (partes y archivos en paralelo), así que
+    /// pocos juegos a la vez bastan para llenar el enlace. Los backups empaquetados
+    /// van de uno en uno: cada uno lee la carpeta entera del disco.
+    fn default() -> Self {
+        Self {
+            global: 6,
+            upload: 4,
+            download: 4,
+            full_backup: 1,
+            torrent: 2,
+        }
+    }
+}
+
+/// Estado visible de una transferencia para la UI.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferSnapshot {
+    pub id: u64,
+    pub kind: TransferKind,
+    /// Juego (o nombre del torrent).
+    pub label: String,
+    pub priority: TransferPriority,
+    /// `queued`, `running`, `pausing` o `cancelling`.
+    pub state: &'static str,
+    pub loaded: u64,
+    pub total: u64,
+    /// Segundos Unix en que se registró.
+    pub created_at: u64,
+}
+
+struct Entry {
+    id: u64,
+    kind: TransferKind,
+    label: Mutex<String>,
+    priority: TransferPriority,
+    created_at: u64,
+    token: CancellationToken,
+    running: AtomicBool,
+    /// Progreso por archivo: `nombre → (cargado, total)`.
+    progress: Mutex<HashMap<String, (u64, u64)>>,
+}
+
+impl Entry {
+    /// Las sincronizaciones usan el id del juego como etiqueta.
+    fn is_for_game(&self, game_id: &str) -> bool {
+        self.kind != TransferKind::Torrent
+            && self
+                .label
+                .lock()
+                .is_ok_and(|l| l.eq_ignore_ascii_case(game_id))
+    }
+
+    fn snapshot(&self) -> TransferSnapshot {
+        let (loaded, total) = self
+            .progress
+            .lock()
+            .map(|p| p.values().fold((0, 0), |(l, t), (fl, ft)| (l + fl, t + ft)))
+            .unwrap_or((0, 0));
+        let state = if self.token.is_cancelled() {
+            "cancelling"
+        } else if self.token.is_paused() {
+            "pausing"
+        } else if self.running.load(Ordering::Acquire) {
+            "running"
+        } else {
+            "queued"
+        };
+        TransferSnapshot {
+            id: self.id,
+            kind: self.kind,
+            label: self.label.lock().map(|l| l.clone()).unwrap_or_default(),
+            priority: self.priority,
+            state,
+            loaded,
+            total,
+            created_at: self.created_at,
+        }
+    }
+}
+
+type Listener = Box<dyn Fn() + Send + Sync>;
+
+/// Dueño de todas las transferencias en curso (subidas, descargas, backups y torrents).
+///
+/// Cada transferencia pide hueco con [`TransferManager::begin`]; si se superan los
+/// límites globales o de su tipo espera en cola, y al liberarse un hueco pasa la
+/// de mayor prioridad (a igualdad, la más antigua).
+pub struct TransferManager {
+    limits: TransferLimits,
+    /// Registradas en orden de llegada, en cola o en curso.
+    entries: Mutex<Vec<Arc<Entry>>>,
+    slot_freed: Notify,
+    next_id: AtomicU64,
+    listener: Mutex<Option<Listener>>,
+}
+
+impl TransferManager {
+    pub fn new(limits: TransferLimits) -> Self {
+        Self {
+            limits,
+            entries: Mutex::new(Vec::new()),
+            slot_freed: Notify::new(),
+            next_id: AtomicU64::new(1),
+            listener: Mutex::new(None),
+        }
+    }
+
+    /// Función a la que se avisa cuando una transferencia entra, arranca, cambia de estado o termina.
+    pub fn set_listener(&self, listener: impl Fn() + Send + Sync + 'static) {
+        if let Ok(mut l) = self.listener.lock() {
+            *l = Some(Box::new(listener));
+        }
+    }
+
+    fn notify_listener(&self) {
+        if let Ok(l) = self.listener.lock() {
+            if let Some(f) = l.as_ref() {
+                f();
+            }
+        }
+    }
+
+    /// Registra una transferencia y espera hueco. Si se cancela mientras espera, se
+    /// devuelve igualmente: el token ya está cancelado y el motor terminará enseguida.
+    pub async fn begin(
+        self: &Arc<Self>,
+        kind: TransferKind,
+        label: &str,
+        priority: TransferPriority,
+    ) -> TransferGuard {
+        let entry = Arc::new(Entry {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            kind,
+            label: Mutex::new(label.to_string()),
+            priority,
+            created_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            token: CancellationToken::new(),
+            running: AtomicBool::new(false),
+            progress: Mutex::new(HashMap::new()),
+        });
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.push(entry.clone());
+        }
+        let guard = TransferGuard {
+            manager: self.clone(),
+            entry,
+        };
+        self.notify_listener();
+
+        loop {
+            let freed = self.slot_freed.notified();
+            tokio::pin!(freed);
+            freed.as_mut().enable();
+
+            if self.try_start(&guard.entry) {
+                // Las demás en cola pudieron despertar antes de que esta arrancara y
+                // volver a dormirse al no ser la candidata: si queda hueco, que lo
+                // intenten otra vez.
+                if self.has_free_slot() {
+                    self.slot_freed.notify_waiters();
+                }
+                break;
+            }
+            if guard.entry.token.is_cancelled() {
+                break;
+            }
+            freed.await;
+        }
+        self.notify_listener();
+        guard
+    }
+
+    /// Arranca `entry` si hay hueco y es la candidata de más prioridad que podría arrancar.
+    fn try_start(&self, entry: &Arc<Entry>) -> bool {
+        let Ok(entries) = self.entries.lock() else {
+            return false;
+        };
+        let running = |kind: Option<TransferKind>| {
+            entries
+                .iter()
+                .filter(|e| e.running.load(Ordering::Acquire))
+                .filter(|e| kind.is_none_or(|k| e.kind == k))
+                .count()
+        };
+        if running(None) >= self.limits.global {
+            return false;
+        }
+        let has_room = |kind: TransferKind| running(Some(kind)) < self.limits.for_kind(kind);
+
+        // Las que esperan un tipo sin hueco no bloquean a las de otros tipos.
+        let best = entries
+            .iter()
+            .filter(|e| !e.running.load(Ordering::Acquire) && has_room(e.kind))
+            .max_by(|a, b| a.priority.cmp(&b.priority).then(b.id.cmp(&a.id)));
+        match best {
+            Some(best) if Arc::ptr_eq(best, entry) => {
+                entry.running.store(true, Ordering::Release);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Si quedan huecos por debajo del límite global.
+    fn has_free_slot(&self) -> bool {
+        self.entries.lock().is_ok_and(|entries| {
+            entries
+                .iter()
+                .filter(|e| e.running.load(Ordering::Acquire))
+                .count()
+                < self.limits.global
+        })
+    }
+
+    fn finish(&self, entry: &Arc<Entry>) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.retain(|e| !Arc::ptr_eq(e, entry));
+        }
+        self.slot_freed.notify_waiters();
+        self.notify_listener();
+    }
+
+    /// Vista de todas las transferencias, en orden de llegada.
+    pub fn snapshot(&self) -> Vec<TransferSnapshot> {
+        self.entries
+            .lock()
+            .map(|entries| entries.iter().map(|e| e.snapshot()).collect())
+            .unwrap_or_default()
+    }
+
+    fn with_entries(
+        &self,
+        filter: impl Fn(&Entry) -> bool,
+        action: impl Fn(&CancellationToken),
+    ) -> usize {
+        let matched: Vec<Arc<Entry>> = self
+            .entries
+            .lock()
+            .map(|entries| entries.iter().filter(|e| filter(e)).cloned().collect())
+            .unwrap_or_default();
+        for entry in &matched {
+            action(&entry.token);
+        }
+        if !matched.is_empty() {
+            // Las que esperan hueco se despiertan para ver que las cancelaron.
+            self.slot_freed.notify_waiters();
+            self.notify_listener();
+        }
+        matched.len()
+    }
+
+    /// Pide pausar una transferencia. Devuelve `false` si no existe.
+    pub fn pause(&self, id: u64) -> bool {
+        self.with_entries(|e| e.id == id, CancellationToken::pause) > 0
+    }
+
+    /// Pide cancelar una transferencia. Devuelve `false` si no existe.
+    pub fn cancel(&self, id: u64) -> bool {
+        self.with_entries(|e| e.id == id, CancellationToken::cancel) > 0
+    }
+
+    /// Pausa todas las transferencias de esos tipos; devuelve cuántas.
+    pub fn pause_kinds(&self, kinds: &[TransferKind]) -> usize {
+        self.with_entries(|e| kinds.contains(&e.kind), CancellationToken::pause)
+    }
+
+    /// Cancela todas las transferencias de esos tipos; devuelve cuántas.
+    pub fn cancel_kinds(&self, kinds: &[TransferKind]) -> usize {
+        self.with_entries(|e| kinds.contains(&e.kind), CancellationToken::cancel)
+    }
+
+    /// Pausa las transferencias de un juego (los torrents no pertenecen a ninguno); devuelve cuántas.
+    pub fn pause_game(&self, game_id: &str) -> usize {
+        self.with_entries(|e| e.is_for_game(game_id), CancellationToken::pause)
+    }
+
+    /// Cancela las transferencias de un juego; devuelve cuántas.
+    pub fn cancel_game(&self, game_id: &str) -> usize {
+        self.with_entries(|e| e.is_for_game(game_id), CancellationToken::cancel)
+    }
+
+    /// Cancela todas las transferencias (p. ej. al salir).
+    pub fn cancel_all(&self) -> usize {
+        self.with_entries(|_| true, CancellationToken::cancel)
+    }
+}
+
+/// Hueco ocupado por una transferencia; al soltarlo se libera para la siguiente.
+pub struct TransferGuard {
+    manager: Arc<TransferManager>,
+    entry: Arc<Entry>,
+}
+
+impl TransferGuard {
+    /// Token de pausa/cancelación propio de esta transferencia.
+    pub fn token(&self) -> &CancellationToken {
+        &self.entry.token
+    }
+
+    /// Cambia la etiqueta (p. ej. el nombre del torrent, que se conoce tras añadirlo).
+    pub fn set_label(&self, label: &str) {
+        if let Ok(mut l) = self.entry.label.lock() {
+            *l = label.to_string();
+        }
+        self.manager.notify_listener();
+    }
+
+    /// Progreso total cuando no se reporta por archivo (torrents).
+    pub fn set_progress(&self, loaded: u64, total: u64) {
+        if let Ok(mut p) = self.entry.progress.lock() {
+            p.insert(String::new(), (loaded, total));
+        }
+    }
+
+    /// Observador que anota el progreso en la transferencia y lo reenvía a `inner`.
+    pub fn observer<'a>(&'a self, inner: &'a dyn SyncObserver) -> TransferObserver<'a> {
+        TransferObserver {
+            entry: &self.entry,
+            inner,
+        }
+    }
+}
+
+impl Drop for TransferGuard {
+    fn drop(&mut self) {
+        self.manager.finish(&self.entry);
+    }
+}
+
+pub struct TransferObserver<'a> {
+    entry: &'a Entry,
+    inner: &'a dyn SyncObserver,
+}
+
+impl TransferObserver<'_> {
+    fn record(&self, progress: &SyncProgressPayload) {
+        if let Ok(mut p) = self.entry.progress.lock() {
+            p.insert(progress.filename.clone(), (progress.loaded, progress.total));
+        }
+    }
+}
+
+impl SyncObserver for TransferObserver<'_> {
+    fn upload_progress(&self, progress: SyncProgressPayload) {
+        self.record(&progress);
+        self.inner.upload_progress(progress);
+    }
+
+    fn download_progress(&self, progress: SyncProgressPayload) {
+        self.record(&progress);
+        self.inner.download_progress(progress);
+    }
+
+    fn upload_paused(&self, game_id: &str, filename: &str) {
+        self.inner.upload_paused(game_id, filename);
+    }
+
+    fn download_paused(&self, game_id: &str, filename: &str) {
+        self.inner.download_paused(game_id, filename);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn limits(global: usize) -> TransferLimits {
+        TransferLimits {
+            global,
+            upload: 1,
+            download: 2,
+            full_backup: 1,
+            torrent: 1,
+        }
+    }
+
+    async fn settle() {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    #[tokio::test]
+    async fn enforces_per_kind_limit_and_priority_order() {
+        let manager = Arc::new(TransferManager::new(limits(4)));
+        let first = manager
+            .begin(TransferKind::Upload, "a", TransferPriority::Normal)
+            .await;
+
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let mut tasks = Vec::new();
+        for (label, priority) in [
+            ("backup", TransferPriority::Background),
+            ("exit", TransferPriority::GameExit),
+        ] {
+            let (manager, order) = (manager.clone(), order.clone());
+            tasks.push(tokio::spawn(async move {
+                let _guard = manager.begin(TransferKind::Upload, label, priority).await;
+                order.lock().unwrap().push(label);
+                settle().await;
+            }));
+            settle().await;
+        }
+
+        // El hueco de subidas está ocupado, pero una descarga pasa sin esperar.
+        let download = manager
+            .begin(TransferKind::Download, "b", TransferPriority::Background)
+            .await;
+        let states: Vec<_> = manager.snapshot().iter().map(|s| s.state).collect();
+        assert_eq!(states, ["running", "queued", "queued", "running"]);
+        drop(download);
+
+        drop(first);
+        for task in tasks {
+            task.await.unwrap();
+        }
+        assert_eq!(*order.lock().unwrap(), ["exit", "backup"]);
+        assert!(manager.snapshot().is_empty());
+    }
+
+    #[tokio::test]
+    async fn cancelling_a_queued_transfer_releases_its_wait() {
+        let manager = Arc::new(TransferManager::new(limits(1)));
+        let _busy = manager
+            .begin(TransferKind::Download, "a", TransferPriority::Normal)
+            .await;
+
+        let waiting = {
+            let manager = manager.clone();
+            tokio::spawn(async move {
+                let guard = manager
+                    .begin(TransferKind::Upload, "b", TransferPriority::Normal)
+                    .await;
+                guard.token().is_cancelled()
+            })
+        };
+        settle().await;
+        let queued = manager.snapshot()[1].id;
+        assert!(manager.cancel(queued));
+        assert!(waiting.await.unwrap());
+        assert_eq!(manager.snapshot().len(), 1);
+    }
+
+    /// Al liberarse dos huecos a la vez arrancan las dos en cola, aunque la de menor
+    /// prioridad despierte antes de que arranque la otra.
+    #[tokio::test]
+    async fn every_free_slot_is_taken_when_several_are_freed_at_once() {
+        let manager = Arc::new(TransferManager::new(limits(2)));
+        let busy = [
+            manager
+                .begin(TransferKind::Download, "a", TransferPriority::Normal)
+                .await,
+            manager
+                .begin(TransferKind::Download, "b", TransferPriority::Normal)
+                .await,
+        ];
+
+        let mut queued = Vec::new();
+        for (label, priority) in [
+            ("backup", TransferPriority::Background),
+            ("exit", TransferPriority::GameExit),
+        ] {
+            let manager = manager.clone();
+            queued.push(tokio::spawn(async move {
+                manager.begin(TransferKind::Download, label, priority).await
+            }));
+            settle().await;
+        }
+
+        drop(busy);
+        settle().await;
+        let states: Vec<_> = manager.snapshot().iter().map(|s| s.state).collect();
+        assert_eq!(states, ["running", "running"]);
+        for task in queued {
+            drop(task.await.unwrap());
+        }
+    }
+}
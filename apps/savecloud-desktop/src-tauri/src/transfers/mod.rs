@@ -0,0 +1,64 @@
+//! Gestor central de transferencias.
+//!
+//! Subidas, descargas, backups empaquetados y torrents piden hueco al mismo
+//! [`TransferManager`] antes de empezar, así que compiten por el ancho de banda
+//! con límites globales y por tipo, y una subida al cerrar un juego pasa por
+//! delante de un backup programado. Cada transferencia tiene su propio token de
+//! pausa/cancelación y la UI ve en vivo todas las activas (`transfers-changed`).
+
+pub mod manager;
+
+use manager::{TransferGuard, TransferLimits, TransferManager, TransferSnapshot};
+use std::sync::{Arc, LazyLock};
+
+pub use manager::{TransferKind, TransferPriority};
+
+static TRANSFERS: LazyLock<Arc<TransferManager>> =
+    LazyLock::new(|| Arc::new(TransferManager::new(TransferLimits::default())));
+
+/// Gestor compartido por la app y la CLI.
+pub fn global() -> &'static Arc<TransferManager> {
+    &TRANSFERS
+}
+
+/// Registra una transferencia en el gestor global y espera hueco.
+pub async fn begin(kind: TransferKind, label: &str, priority: TransferPriority) -> TransferGuard {
+    global().begin(kind, label, priority).await
+}
+
+/// Transferencias en cola o en curso.
+#[tauri::command]
+pub fn list_transfers() -> Vec<TransferSnapshot> {
+    global().snapshot()
+}
+
+/// Pausa una transferencia; queda en `list_paused_transfers` para reanudarla.
+#[tauri::command]
+pub fn pause_transfer(id: u64) -> Result<(), String> {
+    if !global().pause(id) {
+        return Err(format!("No hay ninguna transferencia con id {}", id));
+    }
+    Ok(())
+}
+
+/// Cancela una transferencia en cola o en curso.
+#[tauri::command]
+pub fn cancel_transfer(id: u64) -> Result<(), String> {
+    if !global().cancel(id) {
+        return Err(format!("No hay ninguna transferencia con id {}", id));
+    }
+    Ok(())
+}
+
+/// Pausa las transferencias en curso o en cola de un juego; devuelve cuántas.
+#[tauri::command]
+pub fn pause_game_transfers(game_id: String) -> usize {
+    global().pause_game(&game_id)
+}
+
+/// Cancela las transferencias de un juego y descarta las que tenía pausadas; devuelve cuántas.
+#[tauri::command]
+pub async fn cancel_game_transfers(game_id: String) -> usize {
+    let active = global().cancel_game(&game_id);
+    active + crate::commands::sync::paused::discard_game(&game_id).await
+}
@@ -1,6 +1,6 @@
 //! Estado del icono de bandeja y tooltip: "Idle", "Sincronizando…", "N juegos con cambios pendientes".
 
-use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Mutex;
 use std::time::Instant;
 use tauri::tray::TrayIcon;
@@ -9,10 +9,8 @@ pub struct TrayStateInner {
     pub tray: TrayIcon,
     syncing_count: AtomicU32,
     unsynced_count: Mutex<Option<u32>>,
-    /// Pide cancelar la subida en curso (subida multipart lo comprueba entre partes).
-    upload_cancel: AtomicBool,
-    /// Pide pausar la subida (se guarda estado en disco y se puede reanudar después).
-    upload_pause: AtomicBool,
+    /// Juegos abiertos ahora mismo, según el monitor de procesos.
+    playing: Mutex<Vec<String>>,
     /// Juego restaurado recientemente (backup desde nube); el watcher no debe auto-subir ese juego.
     last_restored: Mutex<Option<(String, Instant)>>,
 }
@@ -23,8 +21,7 @@ impl TrayStateInner {
             tray,
             syncing_count: AtomicU32::new(0),
             unsynced_count: Mutex::new(None),
-            upload_cancel: AtomicBool::new(false),
-            upload_pause: AtomicBool::new(false),
+            playing: Mutex::new(Vec::new()),
             last_restored: Mutex::new(None),
         }
     }
@@ -60,36 +57,6 @@ impl TrayStateInner {
         }
     }
 
-    /// Marca que se solicita pausar la subida en curso.
-    pub fn request_upload_pause(&self) {
-        self.upload_pause.store(true, Ordering::Release);
-    }
-
-    /// Resetea el flag de pausa (llamar al iniciar una subida).
-    pub fn reset_upload_pause(&self) {
-        self.upload_pause.store(false, Ordering::Release);
-    }
-
-    /// True si se ha pedido pausar la subida.
-    pub fn upload_pause_requested(&self) -> bool {
-        self.upload_pause.load(Ordering::Acquire)
-    }
-
-    /// Marca que se solicita cancelar la subida en curso.
-    pub fn request_upload_cancel(&self) {
-        self.upload_cancel.store(true, Ordering::Release);
-    }
-
-    /// Resetea el flag de cancelación (llamar al iniciar una subida).
-    pub fn reset_upload_cancel(&self) {
-        self.upload_cancel.store(false, Ordering::Release);
-    }
-
-    /// True si se ha pedido cancelar la subida.
-    pub fn upload_cancel_requested(&self) -> bool {
-        self.upload_cancel.load(Ordering::Acquire)
-    }
-
     pub fn syncing_inc(&self) {
         self.syncing_count.fetch_add(1, Ordering::Relaxed);
     }
@@ -104,6 +71,12 @@ impl TrayStateInner {
         }
     }
 
+    pub fn set_playing(&self, games: Vec<String>) {
+        if let Ok(mut g) = self.playing.lock() {
+            *g = games;
+        }
+    }
+
     /// Actualiza el tooltip según el estado actual.
     pub fn update_tooltip(&self) {
         let text = self.tooltip_text();
@@ -115,6 +88,11 @@ impl TrayStateInner {
         if syncing > 0 {
             return "Sincronizando…".to_string();
         }
+        if let Ok(g) = self.playing.lock() {
+            if !g.is_empty() {
+                return format!("Jugando: {}", g.join(", "));
+            }
+        }
         if let Ok(g) = self.unsynced_count.lock() {
             if let Some(n) = *g {
                 if n > 0 {
@@ -134,6 +112,27 @@ impl TrayStateInner {
             self.update_tooltip();
         });
     }
+
+    /// Sigue al monitor de procesos para mostrar en el tooltip los juegos abiertos.
+    pub fn watch_running_games(self: std::sync::Arc<Self>) {
+        let mut status = crate::system::process_monitor::global().watch_status();
+        tauri::async_runtime::spawn(async move {
+            loop {
+                let mut playing: Vec<String> = status
+                    .borrow_and_update()
+                    .iter()
+                    .filter(|(_, &running)| running)
+                    .map(|(id, _)| id.clone())
+                    .collect();
+                playing.sort();
+                self.set_playing(playing);
+                self.update_tooltip();
+                if status.changed().await.is_err() {
+                    break;
+                }
+            }
+        });
+    }
 }
 
 /// Estado compartido del tray (para app.manage y para pasar a threads).
@@ -5,11 +5,15 @@
 //! El directorio de trabajo se fija en la carpeta del `.exe`. Si no se hace, el hijo hereda el CWD de SaveCloud
 //! y muchos juegos fallan o quedan en pantalla negra (recursos y DLLs con rutas relativas).
 
+use std::process::Child;
+
 #[cfg(windows)]
 const ERROR_ELEVATION_REQUIRED: i32 = 740;
 
-/// Inicia un ejecutable por ruta absoluta. En Windows reintenta con elevación (UAC) si hace falta.
-pub fn launch_game_executable(path: &str) -> Result<(), String> {
+/// Inicia un ejecutable por ruta absoluta y devuelve el proceso hijo para poder seguirlo. En Windows
+/// reintenta con elevación (UAC) si hace falta; entonces devuelve `None`, porque el proceso lo crea
+/// el shell y no es hijo nuestro.
+pub fn spawn_game_executable(path: &str) -> Result<Option<Child>, String> {
     #[cfg(windows)]
     {
         windows_launch(path)
@@ -20,12 +24,12 @@ pub fn launch_game_executable(path: &str) -> Result<(), String> {
         if let Some(dir) = std::path::Path::new(path).parent() {
             cmd.current_dir(dir);
         }
-        cmd.spawn().map(|_| ()).map_err(|e| e.to_string())
+        cmd.spawn().map(Some).map_err(|e| e.to_string())
     }
 }
 
 #[cfg(windows)]
-fn windows_launch(path: &str) -> Result<(), String> {
+fn windows_launch(path: &str) -> Result<Option<Child>, String> {
     use std::ffi::OsStr;
     use std::os::windows::ffi::OsStrExt;
     use std::path::Path;
@@ -41,7 +45,7 @@ fn windows_launch(path: &str) -> Result<(), String> {
     }
 
     match cmd.spawn() {
-        Ok(_) => Ok(()),
+        Ok(child) => Ok(Some(child)),
         Err(e) if e.raw_os_error() == Some(ERROR_ELEVATION_REQUIRED) => unsafe {
             let path_w: Vec<u16> = OsStr::new(path).encode_wide().chain(Some(0)).collect();
             let runas: Vec<u16> = OsStr::new("runas").encode_wide().chain(Some(0)).collect();
@@ -62,7 +66,7 @@ fn windows_launch(path: &str) -> Result<(), String> {
                     h as isize
                 ))
             } else {
-                Ok(())
+                Ok(None)
             }
         },
         Err(e) => Err(e.to_string()),
@@ -1,2 +1,3 @@
 pub mod launch_exe;
+pub mod path_translation;
 pub mod path_utils;
@@ -0,0 +1,729 @@
+//! Traducción de rutas de guardado entre sistemas operativos.
+//!
+//! Una ruta configurada en Windows (`%APPDATA%/Foo`, `C:\Users\ana\Documents\Foo`) no
+//! significa nada en Linux, donde el mismo juego puede ejecutarse bajo Proton o como
+//! port nativo. Este módulo descompone la ruta en una raíz lógica ([`SaveRoot`]) más un
+//! resto relativo, y vuelve a resolverla en el host actual:
+//!
+//! - Carpetas conocidas de Windows ↔ su equivalente dentro del prefijo Proton
+//!   (`pfx/drive_c/users/steamuser/...`).
+//! - Carpetas XDG de Linux (`~/.local/share`, `~/.config`) ↔ `%LOCALAPPDATA%` / `%APPDATA%`.
+//! - Sin prefijo Proton, las carpetas de Windows caen en su equivalente nativo aproximado.
+
+use std::path::{Path, PathBuf};
+
+use super::path_utils;
+use crate::config::ConfiguredGame;
+
+/// Raíz lógica de una ruta de guardado, independiente del sistema operativo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaveRoot {
+    /// `%USERPROFILE%`
+    WinProfile,
+    /// `%APPDATA%` (AppData/Roaming)
+    WinAppData,
+    /// `%LOCALAPPDATA%` (AppData/Local)
+    WinLocalAppData,
+    /// `%USERPROFILE%/AppData/LocalLow`
+    WinLocalLow,
+    /// `%USERPROFILE%/Documents`
+    WinDocuments,
+    /// `%USERPROFILE%/Saved Games`
+    WinSavedGames,
+    /// `%PUBLIC%` (C:/Users/Public)
+    WinPublic,
+    /// `%PROGRAMDATA%`
+    WinProgramData,
+    /// Directorio personal sin convenciones de plataforma (`~`).
+    Home,
+    /// `$XDG_DATA_HOME` (`~/.local/share`)
+    XdgData,
+    /// `$XDG_CONFIG_HOME` (`~/.config`)
+    XdgConfig,
+}
+
+/// Origen de la ruta: determina qué subcarpetas del perfil se reconocen.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Flavor {
+    Windows,
+    Linux,
+    Any,
+}
+
+/// Marcadores que identifican directamente una raíz lógica (comparados en minúsculas).
+const ROOT_TOKENS: &[(&str, SaveRoot)] = &[
+    ("%appdata%", SaveRoot::WinAppData),
+    ("<winappdata>", SaveRoot::WinAppData),
+    ("%localappdata%", SaveRoot::WinLocalAppData),
+    ("<winlocalappdata>", SaveRoot::WinLocalAppData),
+    ("<windocuments>", SaveRoot::WinDocuments),
+    ("%public%", SaveRoot::WinPublic),
+    ("<winpublic>", SaveRoot::WinPublic),
+    ("%programdata%", SaveRoot::WinProgramData),
+    ("<winprogramdata>", SaveRoot::WinProgramData),
+    ("$xdg_data_home", SaveRoot::XdgData),
+    ("<xdgdata>", SaveRoot::XdgData),
+    ("$xdg_config_home", SaveRoot::XdgConfig),
+    ("<xdgconfig>", SaveRoot::XdgConfig),
+];
+
+/// Ruta descompuesta en raíz lógica + resto relativo.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogicalPath {
+    pub root: SaveRoot,
+    /// Resto relativo a la raíz, separado por `/` y sin barras iniciales.
+    pub rest: String,
+    /// Prefijo Proton/Wine de origen, si la ruta apuntaba dentro de uno.
+    pub source_prefix: Option<PathBuf>,
+}
+
+/// Contexto del host necesario para resolver raíces lógicas.
+#[derive(Debug, Clone, Default)]
+pub struct HostContext {
+    /// Prefijo Proton (`.../compatdata/<app_id>/pfx`) o Wine (Heroic, Lutris) del juego.
+    pub proton_prefix: Option<PathBuf>,
+}
+
+impl HostContext {
+    /// Construye el contexto de un juego; en Linux busca su prefijo Proton por App ID y,
+    /// si no lo tiene, usa el prefijo Wine que indica su lanzador (Heroic, Lutris).
+    pub fn for_game(steam_app_id: Option<&str>, wine_prefix: Option<&Path>) -> Self {
+        #[cfg(not(target_os = "windows"))]
+        {
+            Self {
+                proton_prefix: steam_app_id
+                    .and_then(crate::steam::proton::find_proton_prefix)
+                    .or_else(|| wine_prefix.map(Path::to_path_buf)),
+            }
+        }
+        #[cfg(target_os = "windows")]
+        {
+            let _ = (steam_app_id, wine_prefix);
+            Self::default()
+        }
+    }
+
+    /// Contexto de un juego configurado: su prefijo Proton o, si no es de Steam, el
+    /// prefijo Wine del lanzador que lo instaló (por carpeta de instalación o nombre).
+    pub fn for_configured_game(game: &ConfiguredGame) -> Self {
+        let ctx = Self::for_game(game.steam_app_id.as_deref(), None);
+        #[cfg(not(target_os = "windows"))]
+        if ctx.proton_prefix.is_none() {
+            return Self {
+                proton_prefix: crate::launchers::wine_prefix_for(
+                    game.install_dir.as_deref(),
+                    &game.id,
+                ),
+            };
+        }
+        ctx
+    }
+}
+
+fn join_rest(segments: &[&str]) -> String {
+    segments.join("/")
+}
+
+/// Clasifica los segmentos que siguen al directorio personal.
+///
+/// Devuelve la raíz y cuántos segmentos consume.
+fn classify_profile(segments: &[&str], flavor: Flavor) -> (SaveRoot, usize) {
+    let first = segments.first().map(|s| s.to_ascii_lowercase());
+    let second = segments.get(1).map(|s| s.to_ascii_lowercase());
+
+    if flavor != Flavor::Linux {
+        match (first.as_deref(), second.as_deref()) {
+            (Some("appdata"), Some("roaming")) => return (SaveRoot::WinAppData, 2),
+            (Some("appdata"), Some("local")) => return (SaveRoot::WinLocalAppData, 2),
+            (Some("appdata"), Some("locallow")) => return (SaveRoot::WinLocalLow, 2),
+            (Some("saved games"), _) => return (SaveRoot::WinSavedGames, 1),
+            (Some("documents" | "my documents"), _) if flavor == Flavor::Windows => {
+                return (SaveRoot::WinDocuments, 1)
+            }
+            _ => {}
+        }
+    }
+
+    if flavor != Flavor::Windows {
+        match (first.as_deref(), second.as_deref()) {
+            (Some(".local"), Some("share")) => return (SaveRoot::XdgData, 2),
+            (Some(".config"), _) => return (SaveRoot::XdgConfig, 1),
+            _ => {}
+        }
+    }
+
+    match flavor {
+        Flavor::Windows => (SaveRoot::WinProfile, 0),
+        _ => (SaveRoot::Home, 0),
+    }
+}
+
+fn from_profile(segments: &[&str], flavor: Flavor) -> LogicalPath {
+    let (root, consumed) = classify_profile(segments, flavor);
+    LogicalPath {
+        root,
+        rest: join_rest(&segments[consumed..]),
+        source_prefix: None,
+    }
+}
+
+/// Interpreta la parte posterior a la letra de unidad (`C:/`) de una ruta de Windows.
+fn parse_windows_drive_rest(inner: &str) -> Option<LogicalPath> {
+    let segments: Vec<&str> = inner.split('/').filter(|s| !s.is_empty()).collect();
+    let first = segments.first()?.to_ascii_lowercase();
+
+    match first.as_str() {
+        "users" => {
+            let user = segments.get(1)?;
+            if user.eq_ignore_ascii_case("public") {
+                Some(LogicalPath {
+                    root: SaveRoot::WinPublic,
+                    rest: join_rest(&segments[2..]),
+                    source_prefix: None,
+                })
+            } else {
+                Some(from_profile(&segments[2..], Flavor::Windows))
+            }
+        }
+        "programdata" => Some(LogicalPath {
+            root: SaveRoot::WinProgramData,
+            rest: join_rest(&segments[1..]),
+            source_prefix: None,
+        }),
+        _ => None,
+    }
+}
+
+/// `path` empieza por `token` como segmento completo: `~` no acepta `~foo` ni `$home`
+/// acepta `$homepath`.
+fn starts_with_token(path: &str, token: &str) -> bool {
+    path.strip_prefix(token)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+fn has_drive_letter(s: &str) -> bool {
+    let b = s.as_bytes();
+    b.len() >= 3 && b[0].is_ascii_alphabetic() && b[1] == b':' && b[2] == b'/'
+}
+
+impl LogicalPath {
+    /// Descompone una ruta configurada (con variables, marcadores de Ludusavi o absoluta).
+    ///
+    /// Devuelve `None` si la ruta no cuelga de ninguna raíz conocida (p. ej. `D:/Juegos/...`).
+    pub fn parse(raw: &str) -> Option<Self> {
+        let norm = raw.trim().replace('\\', "/");
+        if norm.is_empty() {
+            return None;
+        }
+        // `to_ascii_lowercase` conserva las longitudes en bytes, así que los índices
+        // calculados sobre `lower` son válidos en `norm`.
+        let lower = norm.to_ascii_lowercase();
+
+        if let Some(idx) = lower.find("/drive_c/") {
+            let mut parsed = parse_windows_drive_rest(&norm[idx + "/drive_c/".len()..])?;
+            parsed.source_prefix = Some(PathBuf::from(&norm[..idx]));
+            return Some(parsed);
+        }
+
+        for (token, root) in ROOT_TOKENS {
+            if starts_with_token(&lower, token) {
+                let segments: Vec<&str> = norm[token.len()..]
+                    .split('/')
+                    .filter(|s| !s.is_empty())
+                    .collect();
+                return Some(Self {
+                    root: *root,
+                    rest: join_rest(&segments),
+                    source_prefix: None,
+                });
+            }
+        }
+
+        let profile_tokens: &[(&str, Flavor)] = &[
+            ("%userprofile%", Flavor::Windows),
+            ("<home>", Flavor::Any),
+            ("$home", Flavor::Any),
+            ("~", Flavor::Any),
+        ];
+        for (token, flavor) in profile_tokens {
+            if starts_with_token(&lower, token) {
+                let segments: Vec<&str> = norm[token.len()..]
+                    .split('/')
+                    .filter(|s| !s.is_empty())
+                    .collect();
+                return Some(from_profile(&segments, *flavor));
+            }
+        }
+
+        if has_drive_letter(&norm) {
+            return parse_windows_drive_rest(&norm[3..]);
+        }
+
+        if lower.starts_with("/home/") {
+            let segments: Vec<&str> = norm.split('/').filter(|s| !s.is_empty()).collect();
+            if segments.len() >= 2 {
+                return Some(from_profile(&segments[2..], Flavor::Linux));
+            }
+        }
+
+        None
+    }
+
+    /// Forma portable de la ruta: variables de Windows o `~` según la raíz.
+    ///
+    /// Es la forma que se guarda en la biblioteca al importar juegos de otro equipo;
+    /// [`resolve_save_path`] la vuelve a resolver en cada host.
+    pub fn to_portable(&self) -> String {
+        let base = match self.root {
+            SaveRoot::WinProfile => "%USERPROFILE%",
+            SaveRoot::WinAppData => "%APPDATA%",
+            SaveRoot::WinLocalAppData => "%LOCALAPPDATA%",
+            SaveRoot::WinLocalLow => "%USERPROFILE%/AppData/LocalLow",
+            SaveRoot::WinDocuments => "%USERPROFILE%/Documents",
+            SaveRoot::WinSavedGames => "%USERPROFILE%/Saved Games",
+            SaveRoot::WinPublic => "%PUBLIC%",
+            SaveRoot::WinProgramData => "%PROGRAMDATA%",
+            SaveRoot::Home => "~",
+            SaveRoot::XdgData => "~/.local/share",
+            SaveRoot::XdgConfig => "~/.config",
+        };
+        if self.rest.is_empty() {
+            base.to_string()
+        } else {
+            format!("{}/{}", base, self.rest)
+        }
+    }
+
+    /// Resuelve la ruta en el host actual.
+    pub fn resolve(&self, ctx: &HostContext) -> Option<PathBuf> {
+        let mut path = self.root_dir(ctx)?;
+        for segment in self.rest.split('/').filter(|s| !s.is_empty()) {
+            path.push(segment);
+        }
+        Some(path)
+    }
+
+    #[cfg(target_os = "windows")]
+    fn root_dir(&self, _ctx: &HostContext) -> Option<PathBuf> {
+        let env = |name: &str| std::env::var(name).ok().map(PathBuf::from);
+        let profile = env("USERPROFILE").or_else(dirs::home_dir);
+
+        match self.root {
+            SaveRoot::WinProfile | SaveRoot::Home => profile,
+            SaveRoot::WinAppData | SaveRoot::XdgConfig => env("APPDATA").or_else(dirs::config_dir),
+            SaveRoot::WinLocalAppData | SaveRoot::XdgData => {
+                env("LOCALAPPDATA").or_else(dirs::data_local_dir)
+            }
+            SaveRoot::WinLocalLow => profile.map(|p| p.join("AppData").join("LocalLow")),
+            SaveRoot::WinDocuments => {
+                dirs::document_dir().or_else(|| profile.map(|p| p.join("Documents")))
+            }
+            SaveRoot::WinSavedGames => profile.map(|p| p.join("Saved Games")),
+            SaveRoot::WinPublic => {
+                Some(env("PUBLIC").unwrap_or_else(|| PathBuf::from(r"C:\Users\Public")))
+            }
+            SaveRoot::WinProgramData => {
+                Some(env("PROGRAMDATA").unwrap_or_else(|| PathBuf::from(r"C:\ProgramData")))
+            }
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    fn root_dir(&self, ctx: &HostContext) -> Option<PathBuf> {
+        let prefix = ctx.proton_prefix.clone().or_else(|| {
+            self.source_prefix
+                .as_ref()
+                .and_then(|p| path_utils::expand_path(&p.to_string_lossy()))
+                .map(PathBuf::from)
+                .filter(|p| p.join("drive_c").is_dir())
+        });
+
+        if let Some(pfx) = prefix {
+            let drive_c = pfx.join("drive_c");
+            let user = drive_c.join("users").join(prefix_user_name(&pfx));
+            let mapped = match self.root {
+                SaveRoot::WinProfile => Some(user),
+                SaveRoot::WinAppData => Some(user.join("AppData").join("Roaming")),
+                SaveRoot::WinLocalAppData => Some(user.join("AppData").join("Local")),
+                SaveRoot::WinLocalLow => Some(user.join("AppData").join("LocalLow")),
+                SaveRoot::WinDocuments => {
+                    // Prefijos antiguos de Wine usan "My Documents".
+                    let legacy = user.join("My Documents");
+                    Some(if legacy.is_dir() {
+                        legacy
+                    } else {
+                        user.join("Documents")
+                    })
+                }
+                SaveRoot::WinSavedGames => Some(user.join("Saved Games")),
+                SaveRoot::WinPublic => Some(drive_c.join("users").join("Public")),
+                SaveRoot::WinProgramData => Some(drive_c.join("ProgramData")),
+                SaveRoot::Home | SaveRoot::XdgData | SaveRoot::XdgConfig => None,
+            };
+            if mapped.is_some() {
+                return mapped;
+            }
+        }
+
+        match self.root {
+            SaveRoot::Home | SaveRoot::WinProfile | SaveRoot::WinPublic => dirs::home_dir(),
+            SaveRoot::XdgData | SaveRoot::WinSavedGames | SaveRoot::WinProgramData => {
+                dirs::data_dir()
+            }
+            SaveRoot::XdgConfig | SaveRoot::WinAppData | SaveRoot::WinLocalLow => {
+                dirs::config_dir()
+            }
+            SaveRoot::WinLocalAppData => dirs::data_local_dir(),
+            SaveRoot::WinDocuments => {
+                dirs::document_dir().or_else(|| dirs::home_dir().map(|h| h.join("Documents")))
+            }
+        }
+    }
+}
+
+/// Usuario de Windows dentro de un prefijo (`<prefijo>/drive_c/users/<usuario>`):
+/// `steamuser` en Proton y el usuario de Linux en prefijos de Wine creados por Heroic o
+/// Lutris. Si no existe ninguno de los dos, la primera carpeta de `drive_c/users` que
+/// no sea `Public`.
+#[cfg(not(target_os = "windows"))]
+pub fn prefix_user_name(prefix: &Path) -> String {
+    let users = prefix.join("drive_c").join("users");
+    let known = [
+        Some(crate::steam::proton::PROTON_USER.to_string()),
+        std::env::var("USER").ok(),
+    ];
+    if let Some(name) = known
+        .into_iter()
+        .flatten()
+        .find(|name| users.join(name).is_dir())
+    {
+        return name;
+    }
+
+    let mut others: Vec<String> = std::fs::read_dir(&users)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .filter_map(|e| e.file_name().into_string().ok())
+        .filter(|name| !name.eq_ignore_ascii_case("public"))
+        .collect();
+    others.sort_unstable();
+    others
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| crate::steam::proton::PROTON_USER.to_string())
+}
+
+/// Indica si la ruta expandida pertenece a otro sistema operativo.
+fn looks_foreign(raw: &str, expanded: &str) -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        let _ = expanded;
+        let lower = raw.trim().replace('\\', "/").to_ascii_lowercase();
+        lower.starts_with('/')
+            || lower.starts_with('$')
+            || lower.starts_with("~/.")
+            || lower.contains("/drive_c/")
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = raw;
+        has_drive_letter(&expanded.replace('\\', "/"))
+            || expanded.contains('%')
+            || expanded.contains('\\')
+            || expanded.starts_with('<')
+    }
+}
+
+/// Resuelve una ruta de guardado en este host, traduciéndola si viene de otro SO.
+///
+/// El flujo es el siguiente:
+/// 1. Si la expansión nativa (`%VAR%`, `~`) es válida para este SO y existe, se usa tal cual.
+/// 2. Si no, se descompone en [`LogicalPath`] y se resuelve con `ctx`.
+/// 3. Como último recurso se devuelve la expansión nativa aunque no exista (destino nuevo).
+pub fn resolve_save_path(raw: &str, ctx: &HostContext) -> Option<PathBuf> {
+    let raw = raw.trim();
+    let native = path_utils::expand_path(raw).filter(|p| !looks_foreign(raw, p));
+
+    if let Some(p) = native.as_deref() {
+        if Path::new(p).exists() {
+            return Some(PathBuf::from(p));
+        }
+    }
+
+    if let Some(translated) = LogicalPath::parse(raw).and_then(|l| l.resolve(ctx)) {
+        return Some(translated);
+    }
+
+    native.map(PathBuf::from)
+}
+
+/// Resuelve la carpeta de una ruta configurada: si termina en comodines (`saves/Juego.*`)
+/// o apunta a un archivo existente, devuelve la carpeta que lo contiene.
+fn resolve_configured_dir(raw: &str, ctx: &HostContext) -> Option<PathBuf> {
+    let resolved = match path_utils::split_glob(raw) {
+        Some((dir, _)) => return resolve_save_path(&dir.to_string_lossy(), ctx),
+        None => resolve_save_path(raw, ctx)?,
+    };
+    if resolved.is_file() {
+        return resolved.parent().map(Path::to_path_buf);
+    }
+    Some(resolved)
+}
+
+/// Resuelve la carpeta de guardado principal (`paths[0]`) de un juego en este host.
+pub fn resolve_game_save_dir(game: &ConfiguredGame) -> Option<PathBuf> {
+    let first = game.paths.first()?;
+    let ctx = HostContext::for_configured_game(game);
+    resolve_configured_dir(first, &ctx)
+}
+
+/// Rutas configuradas de un juego resueltas en este host con [`resolve_save_path`].
+///
+/// Un comodín en el último segmento (`saves/Juego.*`) se conserva tras la carpeta
+/// resuelta para que el listado siga filtrando por él. Todo lo que lee o vigila los
+/// guardados locales (subida, vista previa, backups, estadísticas, vigilancia) debe
+/// partir de aquí, para que un juego restaurado en un prefijo Proton se encuentre igual
+/// que al descargarlo.
+pub fn resolve_game_paths(game: &ConfiguredGame) -> Vec<PathBuf> {
+    let ctx = HostContext::for_configured_game(game);
+    game.paths
+        .iter()
+        .filter_map(|raw| match path_utils::split_glob(raw) {
+            Some((dir, pattern)) => {
+                resolve_save_path(&dir.to_string_lossy(), &ctx).map(|dir| dir.join(pattern))
+            }
+            None => resolve_save_path(raw, &ctx),
+        })
+        .collect()
+}
+
+/// Archivos locales de un juego (ruta absoluta, relativa, fecha y tamaño) en sus rutas
+/// ya resueltas en este host.
+pub fn list_game_files_with_mtime(
+    game: &ConfiguredGame,
+) -> Vec<(String, String, std::time::SystemTime, u64)> {
+    let paths: Vec<String> = resolve_game_paths(game)
+        .iter()
+        .map(|p| p.to_string_lossy().into_owned())
+        .collect();
+    path_utils::list_all_files_with_mtime(&paths)
+}
+
+/// Como [`list_game_files_with_mtime`], solo con las rutas absoluta y relativa.
+pub fn list_game_files(game: &ConfiguredGame) -> Vec<(String, String)> {
+    list_game_files_with_mtime(game)
+        .into_iter()
+        .map(|(a, r, _, _)| (a, r))
+        .collect()
+}
+
+/// Carpetas locales a las que vuelve cada archivo de un juego al descargar o restaurar.
+///
+/// Los juegos de emulador declaran rutas con comodines en carpetas distintas
+/// (`saves/Juego.*`, `states/Juego.state*`); cada archivo va a la carpeta cuyo
+/// patrón lo acepta y el resto a la carpeta principal.
+#[derive(Clone, Debug)]
+pub struct SaveDestinations {
+    base: PathBuf,
+    globs: Vec<(PathBuf, String)>,
+}
+
+impl SaveDestinations {
+    pub fn for_game(game: &ConfiguredGame) -> Option<Self> {
+        let ctx = HostContext::for_configured_game(game);
+        let base = resolve_configured_dir(game.paths.first()?, &ctx)?;
+        let globs = game
+            .paths
+            .iter()
+            .filter_map(|raw| {
+                let (dir, pattern) = path_utils::split_glob(raw)?;
+                let dir = resolve_save_path(&dir.to_string_lossy(), &ctx)?;
+                Some((dir, pattern))
+            })
+            .collect();
+        Some(Self { base, globs })
+    }
+
+    /// Carpeta de destino de un archivo identificado por su ruta relativa en la nube.
+    ///
+    /// Si varios patrones lo aceptan gana el más específico (el más largo).
+    pub fn dir_for(&self, filename: &str) -> &Path {
+        if !filename.contains(['/', '\\']) {
+            if let Some((dir, _)) = self
+                .globs
+                .iter()
+                .filter(|(_, pattern)| path_utils::wildcard_match(pattern, filename))
+                .max_by_key(|(_, pattern)| pattern.len())
+            {
+                return dir;
+            }
+        }
+        &self.base
+    }
+}
+
+/// Convierte una ruta de otro equipo a su forma portable; si no es traducible, la deja igual.
+pub fn to_portable_path(raw: &str) -> String {
+    LogicalPath::parse(raw)
+        .map(|l| l.to_portable())
+        .unwrap_or_else(|| raw.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_windows_env_var() {
+        let p = LogicalPath::parse(r"%APPDATA%\Foo\Saves").expect("parse");
+        assert_eq!(p.root, SaveRoot::WinAppData);
+        assert_eq!(p.rest, "Foo/Saves");
+    }
+
+    #[test]
+    fn parses_absolute_windows_profile() {
+        let p = LogicalPath::parse(r"C:\Users\ana\AppData\LocalLow\Studio\Game").expect("parse");
+        assert_eq!(p.root, SaveRoot::WinLocalLow);
+        assert_eq!(p.rest, "Studio/Game");
+
+        let docs = LogicalPath::parse(r"C:\Users\ana\Documents\My Games\Foo").expect("parse");
+        assert_eq!(docs.root, SaveRoot::WinDocuments);
+        assert_eq!(docs.rest, "My Games/Foo");
+    }
+
+    #[test]
+    fn parses_proton_prefix_path() {
+        let raw = "/home/ana/.local/share/Steam/steamapps/compatdata/123/pfx/drive_c/users/steamuser/AppData/Roaming/Foo";
+        let p = LogicalPath::parse(raw).expect("parse");
+        assert_eq!(p.root, SaveRoot::WinAppData);
+        assert_eq!(p.rest, "Foo");
+        assert_eq!(
+            p.source_prefix,
+            Some(PathBuf::from(
+                "/home/ana/.local/share/Steam/steamapps/compatdata/123/pfx"
+            ))
+        );
+    }
+
+    #[test]
+    fn parses_linux_xdg_paths() {
+        let data = LogicalPath::parse("~/.local/share/Foo").expect("parse");
+        assert_eq!(data.root, SaveRoot::XdgData);
+        assert_eq!(data.rest, "Foo");
+
+        let config = LogicalPath::parse("/home/ana/.config/Foo/saves").expect("parse");
+        assert_eq!(config.root, SaveRoot::XdgConfig);
+        assert_eq!(config.rest, "Foo/saves");
+
+        let docs = LogicalPath::parse("~/Documents/Foo").expect("parse");
+        assert_eq!(docs.root, SaveRoot::Home);
+        assert_eq!(docs.rest, "Documents/Foo");
+    }
+
+    #[test]
+    fn home_tokens_only_match_whole_segments() {
+        assert_eq!(
+            LogicalPath::parse("~/Foo").map(|p| p.root),
+            Some(SaveRoot::Home)
+        );
+        assert_eq!(
+            LogicalPath::parse("$HOME").map(|p| p.root),
+            Some(SaveRoot::Home)
+        );
+        assert!(LogicalPath::parse("~foo/saves").is_none());
+        assert!(LogicalPath::parse("$HOMEPATH/saves").is_none());
+    }
+
+    #[test]
+    fn untranslatable_paths_return_none() {
+        assert!(LogicalPath::parse(r"D:\Games\Foo\saves").is_none());
+        assert!(LogicalPath::parse("/mnt/games/foo").is_none());
+    }
+
+    #[test]
+    fn portable_form_round_trips() {
+        for raw in [
+            r"C:\Users\ana\Saved Games\Foo",
+            "/home/ana/.local/share/Foo",
+            r"C:\ProgramData\Foo",
+            r"C:\Users\Public\Documents\Foo",
+        ] {
+            let parsed = LogicalPath::parse(raw).expect("parse");
+            let reparsed = LogicalPath::parse(&parsed.to_portable()).expect("reparse");
+            assert_eq!(parsed.root, reparsed.root, "{}", raw);
+            assert_eq!(parsed.rest, reparsed.rest, "{}", raw);
+        }
+        assert_eq!(
+            to_portable_path(r"C:\Users\ana\AppData\Roaming\Foo"),
+            "%APPDATA%/Foo"
+        );
+    }
+
+    #[test]
+    fn destinations_route_files_by_the_most_specific_pattern() {
+        let dest = SaveDestinations {
+            base: PathBuf::from("/emu/saves"),
+            globs: vec![
+                (PathBuf::from("/emu/saves"), "Juego.*".to_string()),
+                (PathBuf::from("/emu/states"), "Juego.state*".to_string()),
+            ],
+        };
+        assert_eq!(dest.dir_for("Juego.srm"), Path::new("/emu/saves"));
+        assert_eq!(dest.dir_for("Juego.state2"), Path::new("/emu/states"));
+        assert_eq!(dest.dir_for("otro.cfg"), Path::new("/emu/saves"));
+        // Las rutas con carpetas vienen de la carpeta principal, no de un patrón.
+        assert_eq!(dest.dir_for("sub/Juego.state2"), Path::new("/emu/saves"));
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn prefix_user_comes_from_the_prefix_users_folder() {
+        let pfx = tempfile::tempdir().unwrap();
+        let users = pfx.path().join("drive_c/users");
+        std::fs::create_dir_all(users.join("Public")).unwrap();
+        std::fs::create_dir_all(users.join("zz-savecloud-test")).unwrap();
+        assert_eq!(prefix_user_name(pfx.path()), "zz-savecloud-test");
+
+        std::fs::create_dir_all(users.join("steamuser")).unwrap();
+        assert_eq!(prefix_user_name(pfx.path()), "steamuser");
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn resolves_windows_roots_inside_proton_prefix() {
+        let ctx = HostContext {
+            proton_prefix: Some(PathBuf::from("/pfx")),
+        };
+        let p = LogicalPath::parse("%APPDATA%/Foo").expect("parse");
+        assert_eq!(
+            p.resolve(&ctx),
+            Some(PathBuf::from(
+                "/pfx/drive_c/users/steamuser/AppData/Roaming/Foo"
+            ))
+        );
+
+        let public = LogicalPath::parse(r"C:\Users\Public\Documents\Foo").expect("parse");
+        assert_eq!(
+            public.resolve(&ctx),
+            Some(PathBuf::from("/pfx/drive_c/users/Public/Documents/Foo"))
+        );
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn launcher_wine_prefix_is_used_without_a_steam_app() {
+        let ctx = HostContext::for_game(None, Some(Path::new("/heroic/pfx")));
+        assert_eq!(ctx.proton_prefix.as_deref(), Some(Path::new("/heroic/pfx")));
+        let p = LogicalPath::parse("%LOCALAPPDATA%/Foo").expect("parse");
+        assert_eq!(
+            p.resolve(&ctx),
+            Some(PathBuf::from(
+                "/heroic/pfx/drive_c/users/steamuser/AppData/Local/Foo"
+            ))
+        );
+
+        assert!(HostContext::for_game(None, None).proton_prefix.is_none());
+    }
+}
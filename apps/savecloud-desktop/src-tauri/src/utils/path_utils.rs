@@ -21,13 +21,14 @@ pub fn expand_path(raw: &str) -> Option<String> {
         }
     }
 
-    if result.starts_with('~') {
+    // Solo `~` como segmento completo: `~foo` es un nombre, no el directorio personal.
+    if result == "~" || result.starts_with("~/") || result.starts_with("~\\") {
         let home = std::env::var("USERPROFILE")
             .or_else(|_| std::env::var("HOME"))
             .unwrap_or_default();
 
         if !home.is_empty() {
-            let rest = result.trim_start_matches('~').trim_start_matches('/');
+            let rest = result[1..].trim_start_matches(['/', '\\']);
             result = if rest.is_empty() {
                 home
             } else {
@@ -53,7 +54,7 @@ pub fn collect_files_with_mtime(
     };
 
     for e in entries.flatten() {
-            if e.file_name().to_str().map_or(false, |s| s.starts_with('.')) {
+        if e.file_name().to_str().map_or(false, |s| s.starts_with('.')) {
             continue;
         }
 
@@ -74,6 +75,11 @@ pub fn collect_files_with_mtime(
     }
 }
 
+/// Archivos de cada ruta (ruta absoluta, relativa, fecha y tamaño), sin repetir.
+///
+/// Las rutas solo se expanden con [`expand_path`]; las de un juego configurado deben
+/// pasar antes por [`super::path_translation::resolve_game_paths`] (o usar
+/// [`super::path_translation::list_game_files_with_mtime`]).
 pub fn list_all_files_with_mtime(
     paths: &[String],
 ) -> Vec<(String, String, std::time::SystemTime, u64)> {
@@ -84,6 +90,29 @@ pub fn list_all_files_with_mtime(
         let Some(expanded_str) = expand_path(raw.trim()) else {
             continue;
         };
+        // Rutas con comodines (`saves/Juego.*`): solo los archivos que coinciden,
+        // sin recorrer el resto de la carpeta compartida.
+        if let Some((dir, pattern)) = split_glob(&expanded_str) {
+            let Ok(entries) = fs::read_dir(&dir) else {
+                continue;
+            };
+            for e in entries.flatten() {
+                let name = e.file_name().to_string_lossy().to_string();
+                if !wildcard_match(&pattern, &name) {
+                    continue;
+                }
+                let Ok(meta) = e.metadata() else {
+                    continue;
+                };
+                let abs = e.path().to_string_lossy().to_string();
+                if meta.is_file() && seen.insert(abs.clone()) {
+                    let mtime = meta.modified().unwrap_or(UNIX_EPOCH);
+                    results.push((abs, name, mtime, meta.len()));
+                }
+            }
+            continue;
+        }
+
         let expanded = PathBuf::from(expanded_str);
 
         let Ok(meta) = fs::metadata(&expanded) else {
@@ -117,9 +146,136 @@ pub fn list_all_files_with_mtime(
     results
 }
 
-pub fn list_all_files_from_paths(paths: &[String]) -> Vec<(String, String)> {
-    list_all_files_with_mtime(paths)
+/// Separa una ruta con comodines en el último segmento (`dir/Juego.*`) en carpeta y patrón.
+///
+/// Permite sincronizar solo los archivos de un juego dentro de una carpeta compartida
+/// (por ejemplo, la carpeta `saves` de un emulador).
+pub fn split_glob(path: &str) -> Option<(PathBuf, String)> {
+    let trimmed = path.trim().trim_end_matches(['/', '\\']);
+    let idx = trimmed.rfind(['/', '\\'])?;
+    let (dir, pattern) = (&trimmed[..idx], &trimmed[idx + 1..]);
+    if !pattern.contains(['*', '?']) || dir.contains(['*', '?']) {
+        return None;
+    }
+    Some((PathBuf::from(dir), pattern.to_string()))
+}
+
+/// Expande los comodines de los segmentos anteriores al último (`Studio/*/saves`) listando
+/// las carpetas que coinciden; el último segmento queda tal cual para [`split_glob`].
+///
+/// Devuelve la ruta sin cambios si no hay comodines intermedios, y ninguna si no
+/// coincide ninguna carpeta.
+pub fn expand_dir_wildcards(path: &str) -> Vec<String> {
+    let trimmed = path.trim().trim_end_matches(['/', '\\']);
+    let Some(last_sep) = trimmed.rfind(['/', '\\']) else {
+        return vec![path.to_string()];
+    };
+    let Some(wildcard) = trimmed[..last_sep].find(['*', '?']) else {
+        return vec![path.to_string()];
+    };
+    let Some(start) = trimmed[..wildcard].rfind(['/', '\\']).map(|i| i + 1) else {
+        return Vec::new();
+    };
+    let end = wildcard
+        + trimmed[wildcard..]
+            .find(['/', '\\'])
+            .unwrap_or(trimmed.len() - wildcard);
+    let (parent, pattern, rest) = (&trimmed[..start], &trimmed[start..end], &trimmed[end..]);
+
+    let Ok(entries) = fs::read_dir(parent) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|name| wildcard_match(pattern, name))
+        .collect();
+    names.sort();
+    names
         .into_iter()
-        .map(|(a, r, _, _)| (a, r))
+        .flat_map(|name| expand_dir_wildcards(&format!("{parent}{name}{rest}")))
         .collect()
 }
+
+/// Comprueba un nombre contra un patrón con `*` y `?`, sin distinguir mayúsculas.
+pub fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let p: Vec<char> = pattern.to_lowercase().chars().collect();
+    let n: Vec<char> = name.to_lowercase().chars().collect();
+    let (mut pi, mut ni) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while ni < n.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == n[ni]) {
+            pi += 1;
+            ni += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            backtrack = Some((pi, ni));
+            pi += 1;
+        } else if let Some((star, matched)) = backtrack {
+            pi = star + 1;
+            ni = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    p[pi..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wildcard_match_handles_stars_and_question_marks() {
+        assert!(wildcard_match("Juego.*", "Juego.srm"));
+        assert!(wildcard_match("Juego.*", "juego.STATE1"));
+        assert!(wildcard_match("Juego.state?", "Juego.state3"));
+        assert!(!wildcard_match("Juego.state?", "Juego.state"));
+        assert!(wildcard_match("*(*)*.p2s", "SLUS-20312 (5B8D8F2C).01.p2s"));
+        assert!(wildcard_match("a*b*c", "aXXbYYbZZc"));
+        assert!(!wildcard_match("Juego.*", "Otro Juego.srm"));
+        assert!(wildcard_match("*", ""));
+    }
+
+    #[test]
+    fn split_glob_only_accepts_wildcards_in_the_last_segment() {
+        assert_eq!(
+            split_glob("/emu/saves/Juego.*"),
+            Some((PathBuf::from("/emu/saves"), "Juego.*".to_string()))
+        );
+        assert_eq!(
+            split_glob(r"C:\emu\states\Juego.state*\"),
+            Some((PathBuf::from(r"C:\emu\states"), "Juego.state*".to_string()))
+        );
+        assert_eq!(split_glob("/emu/saves"), None);
+        assert_eq!(split_glob("/emu/*/Juego.srm"), None);
+        assert_eq!(split_glob("/emu/*/Juego.*"), None);
+        assert_eq!(split_glob("Juego.*"), None);
+    }
+
+    #[test]
+    fn expand_dir_wildcards_lists_matching_folders_before_the_last_segment() {
+        let tmp = tempfile::tempdir().unwrap();
+        let studio = tmp.path().join("Studio");
+        fs::create_dir_all(studio.join("111/saves")).unwrap();
+        fs::create_dir_all(studio.join("222/saves")).unwrap();
+        fs::write(studio.join("333"), b"no es carpeta").unwrap();
+        let studio = studio.to_string_lossy().to_string();
+
+        assert_eq!(
+            expand_dir_wildcards(&format!("{studio}/*/saves")),
+            vec![format!("{studio}/111/saves"), format!("{studio}/222/saves")]
+        );
+        assert_eq!(
+            expand_dir_wildcards(&format!("{studio}/1?1/saves/*.sav")),
+            vec![format!("{studio}/111/saves/*.sav")]
+        );
+        assert_eq!(
+            expand_dir_wildcards(&format!("{studio}/*")),
+            vec![format!("{studio}/*")]
+        );
+        assert!(expand_dir_wildcards(&format!("{studio}/9*/saves")).is_empty());
+    }
+}
@@ -1,6 +1,7 @@
 import { MemoryRouter } from "react-router-dom";
 import { AppLayout, SyncProgressBar } from "@components/layout";
 import { NAV_ITEMS, AppRoutes } from "@components/navigation/PageContent";
+import { PluginPermissionsDialog } from "@components/plugins/PluginPermissionsDialog";
 import { TrayActionsListener } from "@components/sync/TrayActionsListener";
 import { UnsyncedSavesModalWithProgress } from "@features/games";
 import { useAppInitialization } from "@hooks/useAppInitialization";
@@ -13,6 +14,7 @@ function App() {
     <>
       <TrayActionsListener />
       <UnsyncedSavesModalWithProgress />
+      <PluginPermissionsDialog />
 
       <MemoryRouter>
         <AppLayout navItems={NAV_ITEMS}>
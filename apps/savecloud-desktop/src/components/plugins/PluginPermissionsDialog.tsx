@@ -0,0 +1,158 @@
+import { Button, Chip, Modal, ModalBody, ModalContent, ModalFooter, ModalHeader } from "@heroui/react";
+import { listen } from "@tauri-apps/api/event";
+import { ShieldAlert } from "lucide-react";
+import { useCallback, useEffect, useState } from "react";
+import {
+  approvePluginPermissions,
+  listPendingPlugins,
+  PLUGIN_PERMISSIONS_REQUIRED_EVENT,
+  rejectPluginPermissions,
+  type PendingPlugin,
+  type PluginCapabilities,
+} from "@services/tauri";
+import { toastError, toastSuccess } from "@utils/toast";
+
+interface PermissionGroup {
+  label: string;
+  detail: string;
+  values: string[];
+}
+
+/** Lo que pide el plugin, en el orden en que se muestra. */
+function permissionGroups(capabilities: PluginCapabilities): PermissionGroup[] {
+  const groups: PermissionGroup[] = [
+    {
+      label: "Red",
+      detail: "Puede hacer peticiones HTTP a estos hosts.",
+      values: capabilities.http,
+    },
+    {
+      label: "Archivos",
+      detail: "Puede leer y escribir en estas carpetas.",
+      values: capabilities.fs,
+    },
+    {
+      label: "Eventos de la interfaz",
+      detail: "Puede enviar estos eventos a la app.",
+      values: capabilities.ui_events,
+    },
+    {
+      label: "Sistema",
+      detail: "Puede usar estas librerías de Lua con acceso al sistema.",
+      values: capabilities.stdlib,
+    },
+  ].filter((g) => g.values.length > 0);
+  if (capabilities.db) {
+    groups.push({
+      label: "Base de datos",
+      detail: "Puede guardar datos propios y leer tu biblioteca, sesiones y sincronizaciones.",
+      values: [],
+    });
+  }
+  return groups;
+}
+
+/**
+ * Pide al usuario que apruebe los permisos de los plugins nuevos o actualizados.
+ *
+ * Consulta los pendientes al montarse (el aviso del arranque puede llegar antes que la UI)
+ * y cada vez que el backend emite `plugin_permissions_required`. Muestra uno a la vez.
+ */
+export function PluginPermissionsDialog() {
+  const [pending, setPending] = useState<PendingPlugin[]>([]);
+  const [busy, setBusy] = useState<"approve" | "reject" | null>(null);
+
+  const refresh = useCallback(() => {
+    listPendingPlugins().then(setPending).catch(console.error);
+  }, []);
+
+  useEffect(() => {
+    refresh();
+    const unlisten = listen<PendingPlugin[]>(PLUGIN_PERMISSIONS_REQUIRED_EVENT, (event) => {
+      setPending(event.payload);
+    });
+    return () => {
+      unlisten.then((f) => f());
+    };
+  }, [refresh]);
+
+  const current = pending[0];
+  if (!current) return null;
+
+  const decide = async (approve: boolean) => {
+    setBusy(approve ? "approve" : "reject");
+    try {
+      if (approve) {
+        await approvePluginPermissions(current.name);
+        toastSuccess("Plugin activado", `${current.name} ya tiene los permisos que pidió.`);
+      } else {
+        await rejectPluginPermissions(current.name);
+      }
+    } catch (e) {
+      toastError(`No se pudo ${approve ? "activar" : "rechazar"} ${current.name}`, String(e));
+    } finally {
+      setBusy(null);
+      setPending((prev) => prev.filter((p) => p.name !== current.name));
+      refresh();
+    }
+  };
+
+  return (
+    <Modal isOpen isDismissable={false} hideCloseButton size="lg" scrollBehavior="inside">
+      <ModalContent>
+        <ModalHeader className="flex items-center gap-2">
+          <ShieldAlert size={22} />
+          Permisos del plugin {current.name}
+          <Chip size="sm" variant="flat">
+            v{current.version}
+          </Chip>
+        </ModalHeader>
+        <ModalBody className="gap-3">
+          {current.author && <p className="text-sm text-default-500">Autor: {current.author}</p>}
+          {current.description && <p className="text-default-600">{current.description}</p>}
+          <p className="text-sm text-default-600">
+            El plugin no se cargará hasta que apruebes lo que pide. Si lo rechazas, se volverá a preguntar en el
+            próximo arranque.
+          </p>
+          <ul className="flex flex-col gap-2">
+            {permissionGroups(current.capabilities).map((group) => (
+              <li key={group.label} className="rounded-md border border-default-200 bg-default-50/50 px-3 py-2">
+                <p className="font-medium text-foreground">{group.label}</p>
+                <p className="text-xs text-default-500">{group.detail}</p>
+                {group.values.length > 0 && (
+                  <div className="mt-1.5 flex flex-wrap gap-1">
+                    {group.values.map((value) => (
+                      <Chip key={value} size="sm" variant="flat" classNames={{ content: "font-mono text-xs" }}>
+                        {value}
+                      </Chip>
+                    ))}
+                  </div>
+                )}
+              </li>
+            ))}
+          </ul>
+          {pending.length > 1 && (
+            <p className="text-xs text-default-400">Quedan {pending.length - 1} plugins más por revisar.</p>
+          )}
+        </ModalBody>
+        <ModalFooter>
+          <Button
+            variant="flat"
+            color="danger"
+            onPress={() => decide(false)}
+            isLoading={busy === "reject"}
+            isDisabled={busy !== null}>
+            Rechazar
+          </Button>
+          <Button
+            color="primary"
+            onPress={() => decide(true)}
+            isLoading={busy === "approve"}
+            isDisabled={busy !== null}>
+            Aprobar y cargar
+          </Button>
+        </ModalFooter>
+      </ModalContent>
+    </Modal>
+  );
+}
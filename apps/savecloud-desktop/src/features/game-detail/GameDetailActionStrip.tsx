@@ -1,7 +1,7 @@
 import { Chip } from "@heroui/react";
-import { AlertTriangle, Calendar, CloudCheck } from "lucide-react";
+import { AlertTriangle, Calendar, CloudCheck, MonitorSmartphone } from "lucide-react";
 import { formatBytes, formatPlaytime, formatRelativeDate } from "@utils/format";
-import type { GameStats } from "@services/tauri";
+import type { GameStats, UploadOrigin } from "@services/tauri";
 import { GameDetailActions, type GameDetailActionsProps } from "./GameDetailActions";
 
 function StatBlock({ label, value }: { label: string; value: string }) {
@@ -15,10 +15,17 @@ function StatBlock({ label, value }: { label: string; value: string }) {
 
 export interface GameDetailActionStripProps extends GameDetailActionsProps {
   stats: GameStats | null;
+  /** Dispositivo de la última subida a la nube. */
+  lastUpload: UploadOrigin | null;
 }
 
-export function GameDetailActionStrip({ stats, isGameRunning, ...actionsProps }: GameDetailActionStripProps) {
-  const hasMeta = isGameRunning || !!stats;
+export function GameDetailActionStrip({
+  stats,
+  lastUpload,
+  isGameRunning,
+  ...actionsProps
+}: GameDetailActionStripProps) {
+  const hasMeta = isGameRunning || !!stats || !!lastUpload;
 
   return (
     <div className="flex flex-col gap-4 rounded-xl border border-default-200/70 bg-default-100/95 px-4 py-3 shadow-sm backdrop-blur-sm dark:border-default-100/20 dark:bg-default-50/15 lg:flex-row lg:items-center lg:justify-between lg:gap-6">
@@ -60,6 +67,22 @@ export function GameDetailActionStrip({ stats, isGameRunning, ...actionsProps }:
               )}
             </>
           )}
+
+          {lastUpload && (
+            <div
+              className="flex min-w-30 items-start gap-2 text-sm text-default-600"
+              title={`${lastUpload.hostname} · SaveCloud ${lastUpload.appVersion}`}>
+              <MonitorSmartphone size={16} className="mt-0.5 shrink-0 text-primary" />
+              <div className="flex min-w-0 flex-col gap-0.5">
+                <span className="text-[0.65rem] font-semibold uppercase tracking-wider text-default-400">
+                  Subido desde
+                </span>
+                <span className="truncate font-medium">
+                  {lastUpload.deviceName} · {formatRelativeDate(lastUpload.uploadedAt)}
+                </span>
+              </div>
+            </div>
+          )}
         </div>
       )}
     </div>
@@ -7,7 +7,7 @@ import { ArrowLeft, Cpu, Gamepad2, LayoutList, ScrollText } from "lucide-react";
 import { formatGameDisplayName } from "@utils/gameImage";
 import { launchGame, openSaveFolder, removeGame, scheduleConfigBackupToCloud } from "@services/tauri";
 import { createShareLink } from "@services/share.service";
-import { toastError, toastSuccess } from "@utils/toast";
+import { toastError, toastSuccess, toastWarning } from "@utils/toast";
 import { CONFIG_QUERY_KEY } from "@hooks/useConfig";
 import { LARGE_GAME_BLOCK_SIZE_BYTES } from "@utils/packageRecommendation";
 import { GameDrawer } from "@features/games/GameDrawer";
@@ -19,6 +19,7 @@ import { useGameDetailCloudActions } from "@/hooks/useGameDetailCloudActions";
 import { GameDetailHero } from "@features/game-detail/GameDetailHero";
 import { GameDetailActionStrip } from "@features/game-detail/GameDetailActionStrip";
 import { GameDetailSyncSetupBanner } from "@features/game-detail/GameDetailSyncSetupBanner";
+import { LaunchLockModal } from "@features/game-detail/LaunchLockModal";
 import {
   GameDetailLocalSummary,
   GameDetailRequirementsPanel,
@@ -38,6 +39,7 @@ export function GameDetailPage() {
     game,
     steamDetails,
     stats,
+    lastUpload,
     isGameRunning,
     mediaUrls,
     libraryHeroFallbackUrl,
@@ -52,6 +54,7 @@ export function GameDetailPage() {
   const [gameForTorrent, setGameForTorrent] = useState<ConfiguredGame | null>(null);
   const [gameToFullBackupConfirm, setGameToFullBackupConfirm] = useState<ConfiguredGame | null>(null);
   const [gameToRestoreBackup, setGameToRestoreBackup] = useState<ConfiguredGame | null>(null);
+  const [launchLock, setLaunchLock] = useState<{ game: ConfiguredGame; message: string } | null>(null);
 
   const goBackFromDetail = useCallback(() => {
     if (backToPath) {
@@ -82,6 +85,9 @@ export function GameDetailPage() {
       case !!gameToRestoreBackup:
         setGameToRestoreBackup(null);
         return true;
+      case !!launchLock:
+        setLaunchLock(null);
+        return true;
       default:
         handleBackWithTransition();
         return true;
@@ -109,14 +115,24 @@ export function GameDetailPage() {
     }
   }, []);
 
-  const handlePlay = useCallback(async (g: ConfiguredGame) => {
+  const launch = useCallback(async (g: ConfiguredGame, force: boolean) => {
     try {
-      await launchGame(g.id);
+      const result = await launchGame(g.id, force);
+      if (result.status === "lockWarning") {
+        setLaunchLock({ game: g, message: result.message });
+      } else if (result.staleLock) {
+        toastWarning(
+          "La última partida no llegó a subir",
+          `Los guardados de ${result.staleLock.deviceName} pueden no estar en la nube.`
+        );
+      }
     } catch (e) {
       toastError("No se pudo abrir el juego", e instanceof Error ? e.message : "Error inesperado");
     }
   }, []);
 
+  const handlePlay = useCallback((g: ConfiguredGame) => launch(g, false), [launch]);
+
   const handleShare = useCallback(async (g: ConfiguredGame) => {
     try {
       const { shareUrl } = await createShareLink(g.id);
@@ -190,6 +206,7 @@ export function GameDetailPage() {
       <GameDetailActionStrip
         game={game}
         stats={stats}
+        lastUpload={lastUpload}
         isGameRunning={isGameRunning}
         isUploadTooLarge={isUploadTooLarge}
         isSyncing={isSyncing}
@@ -234,6 +251,21 @@ export function GameDetailPage() {
           }
         }}
       />
+      <LaunchLockModal
+        game={launchLock?.game ?? null}
+        message={launchLock?.message ?? ""}
+        onClose={() => setLaunchLock(null)}
+        onPullAndLaunch={async () => {
+          if (launchLock && (await handleDownload(launchLock.game))) {
+            await launch(launchLock.game, true);
+          }
+        }}
+        onLaunchAnyway={async () => {
+          if (launchLock) {
+            await launch(launchLock.game, true);
+          }
+        }}
+      />
       <RestoreBackupModal
         isOpen={!!gameToRestoreBackup}
         onClose={() => setGameToRestoreBackup(null)}
@@ -0,0 +1,69 @@
+import { useState } from "react";
+import { Button, Modal, ModalBody, ModalContent, ModalFooter, ModalHeader } from "@heroui/react";
+import { CloudDownload, MonitorSmartphone, Play } from "lucide-react";
+import { formatGameDisplayName } from "@utils/gameImage";
+import type { ConfiguredGame } from "@app-types/config";
+
+interface LaunchLockModalProps {
+  /** Juego que no se lanzó porque otro equipo lo tiene abierto; `null` cierra el modal. */
+  game: ConfiguredGame | null;
+  /** Aviso del backend (equipo y hora de la sesión). */
+  message: string;
+  onClose: () => void;
+  /** Descarga el último guardado y lanza; debe ser async. */
+  onPullAndLaunch: () => Promise<void>;
+  /** Lanza sin descargar; debe ser async. */
+  onLaunchAnyway: () => Promise<void>;
+}
+
+export function LaunchLockModal({ game, message, onClose, onPullAndLaunch, onLaunchAnyway }: LaunchLockModalProps) {
+  const [busy, setBusy] = useState<"pull" | "force" | null>(null);
+
+  const run = async (kind: "pull" | "force", action: () => Promise<void>) => {
+    setBusy(kind);
+    try {
+      await action();
+      onClose();
+    } finally {
+      setBusy(null);
+    }
+  };
+
+  return (
+    <Modal isOpen={game !== null} onOpenChange={(open) => !open && busy === null && onClose()} size="lg">
+      <ModalContent>
+        <ModalHeader className="flex items-center gap-2">
+          <MonitorSmartphone size={22} className="text-warning" />
+          {game ? `${formatGameDisplayName(game.id)} está abierto en otro equipo` : "Juego abierto en otro equipo"}
+        </ModalHeader>
+        <ModalBody className="space-y-3">
+          <p className="text-default-600">{message}</p>
+          <p className="text-sm text-default-500">
+            Si juegas sin descargar, al cerrar el juego tus guardados pueden pisar los del otro equipo.
+          </p>
+        </ModalBody>
+        <ModalFooter>
+          <Button variant="light" onPress={onClose} isDisabled={busy !== null}>
+            Cancelar
+          </Button>
+          <Button
+            variant="flat"
+            startContent={busy !== "force" ? <Play size={16} /> : undefined}
+            onPress={() => run("force", onLaunchAnyway)}
+            isLoading={busy === "force"}
+            isDisabled={busy !== null}>
+            Iniciar igualmente
+          </Button>
+          <Button
+            color="primary"
+            startContent={busy !== "pull" ? <CloudDownload size={16} /> : undefined}
+            onPress={() => run("pull", onPullAndLaunch)}
+            isLoading={busy === "pull"}
+            isDisabled={busy !== null}>
+            Descargar y jugar
+          </Button>
+        </ModalFooter>
+      </ModalContent>
+    </Modal>
+  );
+}
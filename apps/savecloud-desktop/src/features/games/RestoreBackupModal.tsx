@@ -287,6 +287,7 @@ export function RestoreBackupModal({ isOpen, onClose, game, onSuccess }: Restore
                             <p className="text-xs text-default-500">
                               {b.lastModified}
                               {b.size != null && ` • ${formatBytes(b.size)}`}
+                              {b.uploadedFrom && ` • desde ${b.uploadedFrom.deviceName}`}
                             </p>
                           </div>
                           <div className="flex items-center gap-1">
@@ -9,6 +9,7 @@ import { LocalBackupInfoCard } from "@features/settings/LocalBackupInfoCard";
 import { NotificationsCard } from "@features/settings/NotificationsCard";
 import { ReleaseNotesCard } from "@features/settings/ReleaseNotesCard";
 import { RestoreConfigModal } from "@features/settings/RestoreConfigModal";
+import { StorageBackendCard } from "@features/settings/StorageBackendCard";
 import { ResetSteamCatalogModal } from "@features/settings/ResetSteamCatalogModal";
 import { PullFriendConfigModal } from "@/features/settings/PullFriendConfigModal";
 import { UpdatesCard } from "@features/settings/UpdatesCard";
@@ -129,27 +130,30 @@ export function SettingsPage() {
               Cuenta y datos
             </span>
           }>
-          <ConfigSection
-            exporting={exporting}
-            importing={importing}
-            backingUpConfig={backingUpConfig}
-            restoringConfig={restoringConfig}
-            configPath={configPath}
-            userId={config?.userId}
-            hasSteamWebApiKey={!!config?.steamWebApiKey?.trim()}
-            s3TransferEndpointType={s3TransferEndpointType}
-            isLoadingData={loadingConfigData}
-            steamCatalogBusy={steamCatalogBusy}
-            onCreateConfig={openCreateConfigModal}
-            onPullFriendConfig={() => setPullFriendConfigModalOpen(true)}
-            onExport={handleExportConfig}
-            onImportMerge={() => handleImportConfig("merge")}
-            onImportReplace={() => handleImportConfig("replace")}
-            onBackupToCloud={handleBackupConfigToCloud}
-            onRestoreFromCloud={() => setRestoreConfirmOpen(true)}
-            onSyncSteamCatalog={handleSyncSteamCatalog}
-            onResetSteamCatalogSync={handleResetSteamCatalogSync}
-          />
+          <div className="space-y-4">
+            <ConfigSection
+              exporting={exporting}
+              importing={importing}
+              backingUpConfig={backingUpConfig}
+              restoringConfig={restoringConfig}
+              configPath={configPath}
+              userId={config?.userId}
+              hasSteamWebApiKey={!!config?.steamWebApiKey?.trim()}
+              s3TransferEndpointType={s3TransferEndpointType}
+              isLoadingData={loadingConfigData}
+              steamCatalogBusy={steamCatalogBusy}
+              onCreateConfig={openCreateConfigModal}
+              onPullFriendConfig={() => setPullFriendConfigModalOpen(true)}
+              onExport={handleExportConfig}
+              onImportMerge={() => handleImportConfig("merge")}
+              onImportReplace={() => handleImportConfig("replace")}
+              onBackupToCloud={handleBackupConfigToCloud}
+              onRestoreFromCloud={() => setRestoreConfirmOpen(true)}
+              onSyncSteamCatalog={handleSyncSteamCatalog}
+              onResetSteamCatalogSync={handleResetSteamCatalogSync}
+            />
+            <StorageBackendCard />
+          </div>
         </Tab>
 
         <Tab
@@ -0,0 +1,246 @@
+import { Button, Card, CardBody, Input, Select, SelectItem, Switch } from "@heroui/react";
+import { open } from "@tauri-apps/plugin-dialog";
+import { FolderOpen, HardDrive } from "lucide-react";
+import { useEffect, useState } from "react";
+import type { StorageBackendConfig, StorageBackendKind } from "@app-types/config";
+import { scheduleConfigBackupToCloud, setStorageBackend } from "@services/tauri/config.service";
+import { useConfig } from "@hooks/useConfig";
+import { toastError, toastSuccess } from "@utils/toast";
+
+const KIND_OPTIONS: { key: StorageBackendKind; label: string; description: string }[] = [
+  { key: "saveCloudApi", label: "API de SaveCloud", description: "El servidor configurado en la conexión a la nube." },
+  { key: "s3", label: "Bucket S3", description: "AWS, MinIO, Cloudflare R2 o cualquier S3 compatible." },
+  { key: "local", label: "Carpeta local o de red", description: "Disco externo, NAS montado o carpeta sincronizada." },
+  { key: "webDav", label: "WebDAV", description: "Nextcloud, ownCloud o un NAS con WebDAV." },
+];
+
+/** Campos del formulario; los secretos empiezan vacíos (el backend nunca los devuelve). */
+type StorageForm = {
+  kind: StorageBackendKind;
+  endpoint: string;
+  region: string;
+  bucket: string;
+  accessKeyId: string;
+  secretAccessKey: string;
+  pathStyle: boolean;
+  root: string;
+  url: string;
+  username: string;
+  password: string;
+};
+
+const EMPTY_FORM: StorageForm = {
+  kind: "saveCloudApi",
+  endpoint: "",
+  region: "",
+  bucket: "",
+  accessKeyId: "",
+  secretAccessKey: "",
+  pathStyle: false,
+  root: "",
+  url: "",
+  username: "",
+  password: "",
+};
+
+function formFromConfig(storage: StorageBackendConfig | undefined): StorageForm {
+  switch (storage?.kind) {
+    case "s3":
+      return {
+        ...EMPTY_FORM,
+        kind: "s3",
+        endpoint: storage.endpoint,
+        region: storage.region,
+        bucket: storage.bucket,
+        accessKeyId: storage.accessKeyId,
+        pathStyle: storage.pathStyle,
+      };
+    case "local":
+      return { ...EMPTY_FORM, kind: "local", root: storage.root };
+    case "webDav":
+      return { ...EMPTY_FORM, kind: "webDav", url: storage.url, username: storage.username };
+    default:
+      return EMPTY_FORM;
+  }
+}
+
+function configFromForm(form: StorageForm): StorageBackendConfig {
+  switch (form.kind) {
+    case "s3":
+      return {
+        kind: "s3",
+        endpoint: form.endpoint.trim(),
+        region: form.region.trim(),
+        bucket: form.bucket.trim(),
+        accessKeyId: form.accessKeyId.trim(),
+        secretAccessKey: form.secretAccessKey,
+        pathStyle: form.pathStyle,
+      };
+    case "local":
+      return { kind: "local", root: form.root.trim() };
+    case "webDav":
+      return { kind: "webDav", url: form.url.trim(), username: form.username.trim(), password: form.password };
+    default:
+      return { kind: "saveCloudApi" };
+  }
+}
+
+export function StorageBackendCard() {
+  const { config, refetch } = useConfig();
+  const [form, setForm] = useState<StorageForm>(EMPTY_FORM);
+  const [saving, setSaving] = useState(false);
+
+  useEffect(() => {
+    setForm(formFromConfig(config?.storage));
+  }, [config?.storage]);
+
+  const update = <K extends keyof StorageForm>(field: K, value: StorageForm[K]) =>
+    setForm((prev) => ({ ...prev, [field]: value }));
+
+  const savedKind = config?.storage?.kind ?? "saveCloudApi";
+  const secretHint =
+    form.kind === savedKind
+      ? "Déjala vacía para conservar la guardada."
+      : "Vacía usa la última guardada para este tipo.";
+
+  const handlePickRoot = async () => {
+    const selected = await open({ directory: true, multiple: false, title: "Carpeta de almacenamiento" });
+    if (typeof selected === "string") update("root", selected);
+  };
+
+  const handleSave = async () => {
+    setSaving(true);
+    try {
+      await setStorageBackend(configFromForm(form));
+      scheduleConfigBackupToCloud();
+      await refetch();
+      toastSuccess("Almacenamiento guardado", KIND_OPTIONS.find((o) => o.key === form.kind)?.label ?? "");
+    } catch (e) {
+      toastError("Error al guardar el almacenamiento", e instanceof Error ? e.message : String(e));
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  return (
+    <Card className="border border-default-200 bg-default-50/30">
+      <CardBody className="gap-4">
+        <div className="flex items-start gap-3">
+          <HardDrive size={20} className="mt-0.5 shrink-0 text-default-500" />
+          <div>
+            <h2 className="text-base font-semibold text-foreground">Almacenamiento de guardados</h2>
+            <p className="mt-0.5 text-sm text-default-500">
+              Dónde guarda este perfil sus partidas y backups. Las claves se guardan en el almacén seguro del sistema.
+            </p>
+          </div>
+        </div>
+
+        <Select
+          label="Backend"
+          selectedKeys={[form.kind]}
+          disallowEmptySelection
+          onSelectionChange={(keys) => update("kind", Array.from(keys)[0] as StorageBackendKind)}
+          variant="bordered">
+          {KIND_OPTIONS.map((o) => (
+            <SelectItem key={o.key} textValue={o.label} description={o.description}>
+              {o.label}
+            </SelectItem>
+          ))}
+        </Select>
+
+        {form.kind === "s3" && (
+          <div className="grid gap-3 sm:grid-cols-2">
+            <Input
+              label="Endpoint"
+              placeholder="https://s3.eu-west-1.amazonaws.com"
+              value={form.endpoint}
+              onValueChange={(v) => update("endpoint", v)}
+              variant="bordered"
+            />
+            <Input
+              label="Región"
+              placeholder="us-east-1"
+              value={form.region}
+              onValueChange={(v) => update("region", v)}
+              variant="bordered"
+            />
+            <Input label="Bucket" value={form.bucket} onValueChange={(v) => update("bucket", v)} variant="bordered" />
+            <Input
+              label="Access key ID"
+              value={form.accessKeyId}
+              onValueChange={(v) => update("accessKeyId", v)}
+              variant="bordered"
+            />
+            <Input
+              label="Secret access key"
+              type="password"
+              description={secretHint}
+              value={form.secretAccessKey}
+              onValueChange={(v) => update("secretAccessKey", v)}
+              variant="bordered"
+              className="sm:col-span-2"
+            />
+            <div className="flex items-center justify-between gap-4 rounded-lg border border-default-200 bg-default-100/50 px-3 py-2 sm:col-span-2">
+              <div className="min-w-0">
+                <p className="text-sm font-medium text-default-700">Rutas estilo path</p>
+                <p className="mt-0.5 text-xs text-default-500">
+                  Usa <code>endpoint/bucket/clave</code> en vez de <code>bucket.endpoint/clave</code>. Necesario en
+                  MinIO.
+                </p>
+              </div>
+              <Switch isSelected={form.pathStyle} onValueChange={(v) => update("pathStyle", v)} />
+            </div>
+          </div>
+        )}
+
+        {form.kind === "local" && (
+          <div className="flex items-end gap-2">
+            <Input
+              label="Carpeta"
+              placeholder="/mnt/nas/savecloud"
+              value={form.root}
+              onValueChange={(v) => update("root", v)}
+              variant="bordered"
+            />
+            <Button isIconOnly variant="flat" aria-label="Elegir carpeta" onPress={() => void handlePickRoot()}>
+              <FolderOpen size={18} />
+            </Button>
+          </div>
+        )}
+
+        {form.kind === "webDav" && (
+          <div className="grid gap-3 sm:grid-cols-2">
+            <Input
+              label="URL"
+              placeholder="https://nas.local/remote.php/dav/files/usuario/SaveCloud"
+              value={form.url}
+              onValueChange={(v) => update("url", v)}
+              variant="bordered"
+              className="sm:col-span-2"
+            />
+            <Input
+              label="Usuario"
+              value={form.username}
+              onValueChange={(v) => update("username", v)}
+              variant="bordered"
+            />
+            <Input
+              label="Contraseña"
+              type="password"
+              description={secretHint}
+              value={form.password}
+              onValueChange={(v) => update("password", v)}
+              variant="bordered"
+            />
+          </div>
+        )}
+
+        <div className="flex justify-end">
+          <Button color="primary" variant="flat" isLoading={saving} onPress={() => void handleSave()}>
+            Guardar almacenamiento
+          </Button>
+        </div>
+      </CardBody>
+    </Card>
+  );
+}
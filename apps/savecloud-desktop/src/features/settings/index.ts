@@ -7,6 +7,7 @@ export { LocalBackupInfoCard } from "./LocalBackupInfoCard";
 export { NotificationsCard } from "./NotificationsCard";
 export { ReleaseNotesCard } from "./ReleaseNotesCard";
 export { RestoreConfigModal } from "./RestoreConfigModal";
+export { StorageBackendCard } from "./StorageBackendCard";
 export { PullFriendConfigModal } from "./PullFriendConfigModal";
 export { UpdatesCard } from "./UpdatesCard";
 export { useSettingsPage } from "./useSettingsPage";
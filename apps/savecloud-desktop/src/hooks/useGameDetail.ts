@@ -5,8 +5,10 @@ import {
   getConfig,
   getSteamAppDetails,
   getGameStats,
+  getLastUploads,
   type SteamAppDetailsResult,
   type GameStats,
+  type UploadOrigin,
 } from "@services/tauri";
 import { useGameRunningStatus } from "@hooks/useGameRunningStatus";
 import { getGameLibraryHeroUrl, getSteamAppId, isSteamMoviePosterUrl } from "@utils/gameImage";
@@ -61,6 +63,17 @@ export function useGameDetail() {
 
   const stats = useMemo(() => allStats?.find((s) => s.gameId === gameId) ?? null, [allStats, gameId]);
 
+  const isCatalogRoute = isSteamCatalogRouteGameId(gameId);
+  const hasSyncConfig = !!(config?.apiBaseUrl && config?.apiKey && config?.userId);
+
+  const { data: lastUploads } = useQuery<Record<string, UploadOrigin>>({
+    queryKey: ["last-uploads", gameId],
+    queryFn: () => getLastUploads([gameId!]),
+    enabled: !!gameId && hasSyncConfig && !isCatalogRoute,
+    staleTime: 60_000,
+    refetchOnWindowFocus: false,
+  });
+
   const runningByGame = useGameRunningStatus(gameId ? [gameId] : []);
   const isGameRunning = gameId ? (runningByGame[gameId] ?? false) : false;
 
@@ -75,8 +88,6 @@ export function useGameDetail() {
     return getGameLibraryHeroUrl(game, navState?.resolvedSteamAppId);
   }, [game, navState?.resolvedSteamAppId]);
 
-  const isCatalogRoute = isSteamCatalogRouteGameId(gameId);
-
   const isLoading = !gameId || (!isCatalogRoute && isConfigLoading) || (!!steamAppId && isSteamLoading);
 
   return {
@@ -85,12 +96,14 @@ export function useGameDetail() {
     steamAppId,
     steamDetails: steamDetails ?? null,
     stats,
+    /** Dispositivo de la última subida a la nube, si quedó registrado. */
+    lastUpload: (gameId && lastUploads?.[gameId]) || null,
     isGameRunning,
     mediaUrls,
     libraryHeroFallbackUrl,
     videoUrl: steamDetails?.media.videoUrl ?? null,
     isLoading,
-    hasSyncConfig: !!(config?.apiBaseUrl && config?.apiKey && config?.userId),
+    hasSyncConfig,
     isSteamCatalogOnly: isCatalogRoute,
     /** Ruta para volver con atrás; si falta, el detalle usa `navigate(-1)`. */
     backToPath: navState?.from ?? null,
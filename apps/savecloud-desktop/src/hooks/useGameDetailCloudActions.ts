@@ -25,6 +25,7 @@ export function useGameDetailCloudActions() {
         await syncUploadGame(g.id);
         toastSuccess("Subido", `${formatGameDisplayName(g.id)} sincronizado con la nube.`);
         await queryClient.invalidateQueries({ queryKey: ["game-stats"] });
+        await queryClient.invalidateQueries({ queryKey: ["last-uploads", g.id] });
         await queryClient.invalidateQueries({ queryKey: CONFIG_QUERY_KEY });
         await queryClient.invalidateQueries({ queryKey: ["unsynced-games"] });
       } catch (e) {
@@ -37,8 +38,9 @@ export function useGameDetailCloudActions() {
     [queryClient]
   );
 
+  /** Devuelve si la descarga terminó bien (los errores ya se muestran en un toast). */
   const handleDownload = useCallback(
-    async (g: ConfiguredGame) => {
+    async (g: ConfiguredGame): Promise<boolean> => {
       setOpLoading("download");
       useSyncStore.getState().setSyncOperation({ type: "download", mode: "single", gameId: g.id });
       try {
@@ -46,8 +48,10 @@ export function useGameDetailCloudActions() {
         toastSuccess("Descargado", `${formatGameDisplayName(g.id)} restaurado desde la nube.`);
         await queryClient.invalidateQueries({ queryKey: ["game-stats"] });
         await queryClient.invalidateQueries({ queryKey: CONFIG_QUERY_KEY });
+        return true;
       } catch (e) {
         toastError("Error al descargar", e instanceof Error ? e.message : "Error inesperado");
+        return false;
       } finally {
         setOpLoading(null);
         useSyncStore.getState().setSyncOperation(null);
@@ -68,6 +72,7 @@ export function useGameDetailCloudActions() {
         );
         await queryClient.invalidateQueries({ queryKey: ["game-stats"] });
         await queryClient.invalidateQueries({ queryKey: ["cloud-backups", g.id] });
+        await queryClient.invalidateQueries({ queryKey: ["last-uploads", g.id] });
         await queryClient.invalidateQueries({ queryKey: ["cloud-backup-counts"] });
         await queryClient.invalidateQueries({ queryKey: CONFIG_QUERY_KEY });
         await queryClient.invalidateQueries({ queryKey: ["unsynced-games"] });
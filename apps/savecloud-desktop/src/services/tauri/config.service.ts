@@ -1,5 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { Config } from "@app-types/config";
+import type { Config, StorageBackendConfig } from "@app-types/config";
 
 export interface PluginLogEntry {
   timestamp: string;
@@ -36,6 +36,11 @@ export async function setProfileAppearance(updates: {
   });
 }
 
+/** Cambia el backend de almacenamiento del perfil. Un secreto vacío conserva el guardado para ese tipo. */
+export async function setStorageBackend(storage: StorageBackendConfig): Promise<void> {
+  await invoke("set_storage_backend", { storage });
+}
+
 /** Ruta del archivo de configuración (para mostrar al usuario) */
 export async function getConfigPath(): Promise<string> {
   return invoke<string>("get_config_path");
@@ -115,9 +120,30 @@ export function listRunningProcessExeNames(): Promise<string[]> {
   return invoke<string[]>("list_running_process_exe_names");
 }
 
-/** Inicia el ejecutable configurado para el juego. */
-export function launchGame(gameId: string): Promise<void> {
-  return invoke("launch_game", { gameId });
+/** Sesión de otro dispositivo sobre un juego. */
+export interface SessionLock {
+  deviceId: string;
+  deviceName: string;
+  hostname: string;
+  /** Fechas RFC 3339 (UTC). */
+  startedAt: string;
+  heartbeatAt: string;
+  /** La sesión ya no se renueva: terminó sin subir sus guardados. */
+  stale: boolean;
+}
+
+export type LaunchGameResult =
+  /** `staleLock`: la última partida en otro equipo no llegó a subir; se lanza igualmente. */
+  | { status: "launched"; staleLock: SessionLock | null }
+  /** Otro equipo tiene el juego abierto; no se ha lanzado. */
+  | { status: "lockWarning"; lock: SessionLock; message: string };
+
+/**
+ * Inicia el ejecutable configurado para el juego. Si otro equipo lo tiene abierto devuelve
+ * `lockWarning` sin lanzarlo; con `force` se lanza igualmente.
+ */
+export function launchGame(gameId: string, force = false): Promise<LaunchGameResult> {
+  return invoke<LaunchGameResult>("launch_game", { gameId, force });
 }
 
 /** Guarda la ruta al .exe para abrir el juego desde la app (`null` borra). */
@@ -654,12 +680,30 @@ export async function restoreBackup(gameId: string, backupId: string): Promise<S
   };
 }
 
+/** Dispositivo que subió un archivo o backup (procedencia guardada en la nube). */
+export interface UploadOrigin {
+  deviceId: string;
+  deviceName: string;
+  hostname: string;
+  appVersion: string;
+  /** Fecha RFC 3339 (UTC) de la subida. */
+  uploadedAt: string;
+}
+
+/** Última subida de cada juego; los juegos sin procedencia registrada no aparecen. */
+export async function getLastUploads(gameIds: readonly string[]): Promise<Record<string, UploadOrigin>> {
+  if (gameIds.length === 0) return {};
+  return invoke<Record<string, UploadOrigin>>("get_last_uploads", { gameIds });
+}
+
 /** Información de un backup completo en la nube (un .tar por juego) */
 export interface CloudBackupInfo {
   key: string;
   lastModified: string;
   size?: number;
   filename: string;
+  /** Dispositivo que lo subió, si quedó registrado. */
+  uploadedFrom?: UploadOrigin | null;
 }
 
 /** Crea un .tar de la carpeta del juego y lo sube a la nube (recomendado para juegos grandes). */
@@ -836,3 +880,39 @@ export async function exportPluginSdk(): Promise<string> {
 export async function getPluginLogs(): Promise<PluginLogEntry[]> {
   return invoke<PluginLogEntry[]>("get_plugin_logs");
 }
+
+/** Capacidades que pide un plugin en su `plugin.toml`. */
+export interface PluginCapabilities {
+  http: string[];
+  fs: string[];
+  ui_events: string[];
+  db: boolean;
+  stdlib: ("os" | "io")[];
+}
+
+/** Plugin que no se carga hasta que el usuario apruebe sus permisos. */
+export interface PendingPlugin {
+  name: string;
+  version: string;
+  author?: string | null;
+  description?: string | null;
+  capabilities: PluginCapabilities;
+}
+
+/** Evento que emite el backend cuando hay plugins esperando aprobación. */
+export const PLUGIN_PERMISSIONS_REQUIRED_EVENT = "plugin_permissions_required";
+
+/** Plugins que esperan a que el usuario apruebe sus permisos. */
+export async function listPendingPlugins(): Promise<PendingPlugin[]> {
+  return invoke<PendingPlugin[]>("list_pending_plugins");
+}
+
+/** Aprueba los permisos del plugin y lo carga. */
+export async function approvePluginPermissions(name: string): Promise<void> {
+  await invoke("approve_plugin_permissions", { name });
+}
+
+/** Rechaza los permisos del plugin; se vuelven a pedir en el próximo arranque. */
+export async function rejectPluginPermissions(name: string): Promise<void> {
+  await invoke("reject_plugin_permissions", { name });
+}
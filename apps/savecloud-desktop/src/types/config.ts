@@ -22,6 +22,27 @@ export interface ConfiguredGame {
   readonly magnetLink?: string;
 }
 
+/**
+ * Backend de almacenamiento del perfil. Los secretos (`secretAccessKey`, `password`) nunca
+ * llegan desde el backend; al guardar, vacíos conservan el guardado en el almacén seguro.
+ */
+export type StorageBackendConfig =
+  | { readonly kind: "saveCloudApi" }
+  | {
+      readonly kind: "s3";
+      readonly endpoint: string;
+      readonly region: string;
+      readonly bucket: string;
+      readonly accessKeyId: string;
+      readonly secretAccessKey?: string;
+      /** `endpoint/bucket/clave` en vez de `bucket.endpoint/clave` (MinIO). */
+      readonly pathStyle: boolean;
+    }
+  | { readonly kind: "local"; readonly root: string }
+  | { readonly kind: "webDav"; readonly url: string; readonly username: string; readonly password?: string };
+
+export type StorageBackendKind = StorageBackendConfig["kind"];
+
 export interface Config {
   readonly apiBaseUrl?: string;
   readonly apiKey?: string;
@@ -44,4 +65,6 @@ export interface Config {
   readonly profileAvatar?: string;
   /** URL o ruta local del marco sobre el avatar. */
   readonly profileFrame?: string;
+  /** Dónde se guardan los archivos del perfil. */
+  readonly storage?: StorageBackendConfig;
 }